@@ -65,7 +65,8 @@ function Badge({ label, color, small }) {
 function AppCard({ app, onClick }) {
   return (
     <div style={styles.card} onClick={onClick}>
-      <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'flex-start' }}>
+      <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'flex-start', gap: 10 }}>
+        {app.logo && <img src={app.logo} alt="" width={40} height={40} style={{ borderRadius: 8, flexShrink: 0 }} />}
         <div style={{ flex: 1, minWidth: 0 }}>
           <div style={{ display: 'flex', alignItems: 'center', gap: 6, flexWrap: 'wrap' }}>
             <h3 style={{ margin: 0, fontSize: 16, color: '#f1f5f9' }}>{app.name}</h3>
@@ -135,6 +136,7 @@ function AppDetail({ app, onBack, onRefresh }) {
         <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'flex-start', flexWrap: 'wrap', gap: 8 }}>
           <div>
             <div style={{ display: 'flex', alignItems: 'center', gap: 8, flexWrap: 'wrap' }}>
+              {app.logo && <img src={app.logo} alt="" width={48} height={48} style={{ borderRadius: 10 }} />}
               <h2 style={{ margin: 0, color: '#f1f5f9' }}>{app.name}</h2>
               {app.is_featured && <Badge label="⭐ Featured" color="#f59e0b" />}
               {app.is_verified && <Badge label="✓ Verified" color="#22c55e" />}
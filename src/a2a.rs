@@ -0,0 +1,331 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+/// Where A2A agents publish their card, relative to their origin.
+pub const AGENT_CARD_PATH: &str = "/.well-known/agent.json";
+
+/// A2A protocol version the directory's own card declares.
+const PROTOCOL_VERSION: &str = "0.3.0";
+
+/// HTTP timeout for fetching a listed agent's card.
+const FETCH_TIMEOUT_SECS: u64 = 10;
+
+/// Cards larger than this are rejected without parsing.
+const MAX_CARD_BYTES: usize = 256 * 1024;
+
+/// The directory's own agent card, with URLs under `origin` (empty keeps
+/// them relative). Its skills map onto the search, submit and review APIs.
+pub fn directory_card(origin: &str) -> Value {
+    json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "name": "App Directory",
+        "description": "A curated registry of agent-native applications. Search listings by text, category and protocol, submit new apps, and rate and review the ones you use.",
+        "url": format!("{origin}/api/v1"),
+        "preferredTransport": "HTTP+JSON",
+        "version": env!("CARGO_PKG_VERSION"),
+        "provider": { "organization": "Humans Not Required", "url": format!("{origin}/") },
+        "documentationUrl": format!("{origin}/SKILL.md"),
+        "capabilities": { "streaming": false, "pushNotifications": false, "stateTransitionHistory": false },
+        "securitySchemes": {
+            "apiKey": { "type": "apiKey", "in": "header", "name": "X-API-Key" },
+        },
+        // Reads, submissions and reviews all work without a key
+        "security": [],
+        "defaultInputModes": ["application/json"],
+        "defaultOutputModes": ["application/json", "text/markdown"],
+        "skills": [
+            {
+                "id": "search",
+                "name": "Search apps",
+                "description": "Find agent-native apps by keywords, category, protocol and health. GET /api/v1/apps/search?q=...",
+                "tags": ["search", "discovery", "directory"],
+                "examples": ["Find a weather API that speaks MCP", "GET /api/v1/apps/search?q=weather&protocol=mcp"],
+            },
+            {
+                "id": "submit",
+                "name": "Submit an app",
+                "description": "List a new app or API for review. POST /api/v1/apps with name, descriptions, URLs, protocol and category.",
+                "tags": ["submit", "listing"],
+                "examples": ["Submit my A2A agent to the directory"],
+            },
+            {
+                "id": "review",
+                "name": "Review an app",
+                "description": "Rate an app 1-5 with optional pros, cons and use case. POST /api/v1/apps/{id}/reviews.",
+                "tags": ["review", "rating"],
+                "examples": ["Rate the Skyline Weather API 4 stars: fast, but sparse docs"],
+            },
+        ],
+    })
+}
+
+/// Outcome of checking an agent card.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CardReport {
+    pub valid: bool,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub url: Option<String>,
+    /// Skill ids the card declares.
+    pub skills: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl CardReport {
+    fn failure(message: String) -> Self {
+        CardReport {
+            errors: vec![message],
+            ..Default::default()
+        }
+    }
+}
+
+/// Check an A2A agent card has the fields clients rely on: `name`,
+/// `description`, an http(s) `url`, `version`, a `capabilities` object, and
+/// `skills` each with an `id` and `name`.
+pub fn lint_card(raw: &str) -> CardReport {
+    let card: Value = match serde_json::from_str(raw) {
+        Ok(v @ Value::Object(_)) => v,
+        Ok(_) => return CardReport::failure("Agent card must be a JSON object".to_string()),
+        Err(e) => return CardReport::failure(format!("Not valid JSON: {}", e)),
+    };
+    let text = |field: &str| card[field].as_str().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+
+    let mut report = CardReport {
+        name: text("name"),
+        version: text("version"),
+        url: text("url"),
+        ..Default::default()
+    };
+    for field in ["name", "description", "version"] {
+        if text(field).is_none() {
+            report.errors.push(format!("Missing '{}'", field));
+        }
+    }
+    match report.url.as_deref() {
+        None => report.errors.push("Missing 'url'".to_string()),
+        Some(url) if !url.starts_with("http://") && !url.starts_with("https://") => {
+            report.errors.push("'url' must start with http:// or https://".to_string())
+        }
+        Some(_) => {}
+    }
+    if !card["capabilities"].is_object() {
+        report.errors.push("Missing 'capabilities' object".to_string());
+    }
+    match card["skills"].as_array() {
+        None => report.errors.push("Missing 'skills' array".to_string()),
+        Some(skills) => {
+            for (i, skill) in skills.iter().enumerate() {
+                match (skill["id"].as_str(), skill["name"].as_str()) {
+                    (Some(id), Some(_)) => report.skills.push(id.to_string()),
+                    _ => report.errors.push(format!("skills[{}] needs an 'id' and 'name'", i)),
+                }
+            }
+        }
+    }
+    report.valid = report.errors.is_empty();
+    report
+}
+
+/// Where a listed agent's card should be: the api_url itself when it points
+/// at a card, otherwise the well-known path on the api_url's (or
+/// homepage's) origin.
+pub fn card_url(api_url: Option<&str>, homepage_url: Option<&str>) -> Option<String> {
+    if let Some(api) = api_url.filter(|u| u.ends_with(".json")) {
+        return Some(api.to_string());
+    }
+    let base = api_url.or(homepage_url)?;
+    let parsed = reqwest::Url::parse(base).ok()?;
+    let origin = parsed.origin();
+    origin.is_tuple().then(|| format!("{}{}", origin.ascii_serialization(), AGENT_CARD_PATH))
+}
+
+/// Background verifier for the agent cards of `a2a` listings, run when one
+/// is submitted or its protocol or URLs change. Valid cards are stored and
+/// served back from `GET /api/v1/apps/<id>/agent.json`. Turned off with
+/// `A2A_VERIFY_AGENT_CARDS=false`.
+pub struct AgentCardVerifier {
+    db_path: String,
+    client: reqwest::Client,
+    enabled: bool,
+}
+
+impl AgentCardVerifier {
+    pub fn new(db_path: &str) -> Self {
+        AgentCardVerifier {
+            db_path: db_path.to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+                .redirect(reqwest::redirect::Policy::limited(5))
+                .build()
+                .unwrap_or_default(),
+            enabled: !matches!(std::env::var("A2A_VERIFY_AGENT_CARDS").as_deref(), Ok("0") | Ok("false")),
+        }
+    }
+
+    /// Re-check an app's agent card in the background, or drop the stored
+    /// one if the app is no longer `a2a`. Must be called from within the
+    /// Tokio runtime (i.e. a route handler).
+    pub fn verify_async(&self, conn: &rusqlite::Connection, app_id: &str) {
+        let app: Option<(String, Option<String>, Option<String>)> = conn
+            .query_row(
+                "SELECT protocol, api_url, homepage_url FROM apps WHERE id = ?1",
+                rusqlite::params![app_id],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .ok();
+        let (api_url, homepage_url) = match app {
+            Some((protocol, api_url, homepage_url)) if protocol == "a2a" && self.enabled => (api_url, homepage_url),
+            _ => {
+                let _ = conn.execute("DELETE FROM app_agent_cards WHERE app_id = ?1", rusqlite::params![app_id]);
+                return;
+            }
+        };
+        let url = match card_url(api_url.as_deref(), homepage_url.as_deref()) {
+            Some(url) => url,
+            None => {
+                let report = CardReport::failure("No api_url or homepage_url to find the agent card at".to_string());
+                record_result(conn, app_id, "", "unreachable", &report, None);
+                return;
+            }
+        };
+        record_result(conn, app_id, &url, "pending", &CardReport::default(), None);
+
+        let db_path = self.db_path.clone();
+        let client = self.client.clone();
+        let app_id = app_id.to_string();
+        tokio::spawn(async move {
+            let (status, report, card) = fetch_and_lint(&client, &url).await;
+            let conn = match rusqlite::Connection::open(&db_path) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("❌ Agent card verification for {app_id}: failed to open DB: {e}");
+                    return;
+                }
+            };
+            let _ = conn.busy_timeout(Duration::from_secs(5));
+            record_result(&conn, &app_id, &url, status, &report, card.as_deref());
+        });
+    }
+}
+
+/// Fetch and lint a card. Returns its status, the report and, when valid,
+/// the card itself.
+async fn fetch_and_lint(client: &reqwest::Client, url: &str) -> (&'static str, CardReport, Option<String>) {
+    let unreachable = |msg: String| ("unreachable", CardReport::failure(msg), None);
+
+    let resp = match client.get(url).header("Accept", "application/json").send().await {
+        Ok(r) => r,
+        Err(e) if e.is_timeout() => return unreachable(format!("Connection timed out ({}s)", FETCH_TIMEOUT_SECS)),
+        Err(e) if e.is_connect() => return unreachable("Connection refused or DNS failure".to_string()),
+        Err(e) => return unreachable(e.to_string()),
+    };
+    if !resp.status().is_success() {
+        return unreachable(format!("HTTP {}", resp.status()));
+    }
+    let body = match crate::health::read_body_limited(resp, MAX_CARD_BYTES).await {
+        Ok(Some(b)) => b,
+        Ok(None) => {
+            return ("invalid", CardReport::failure(format!("Agent card exceeds {} bytes", MAX_CARD_BYTES)), None)
+        }
+        Err(e) => return unreachable(e.to_string()),
+    };
+
+    let raw = String::from_utf8_lossy(&body);
+    let report = lint_card(&raw);
+    match report.valid {
+        true => ("valid", report, Some(raw.into_owned())),
+        false => ("invalid", report, None),
+    }
+}
+
+fn record_result(
+    conn: &rusqlite::Connection,
+    app_id: &str,
+    card_url: &str,
+    status: &str,
+    report: &CardReport,
+    card: Option<&str>,
+) {
+    let skills = serde_json::to_string(&report.skills).unwrap_or_else(|_| "[]".to_string());
+    let errors = serde_json::to_string(&report.errors).unwrap_or_else(|_| "[]".to_string());
+    let _ = conn.execute(
+        "INSERT INTO app_agent_cards (app_id, card_url, status, name, version, skills, errors, card, checked_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, datetime('now'))
+         ON CONFLICT(app_id) DO UPDATE SET
+           card_url = excluded.card_url, status = excluded.status, name = excluded.name,
+           version = excluded.version, skills = excluded.skills, errors = excluded.errors,
+           card = excluded.card, checked_at = excluded.checked_at",
+        rusqlite::params![app_id, card_url, status, report.name, report.version, skills, errors, card],
+    );
+}
+
+/// The stored verification of an app's agent card, if any.
+pub fn card_details(conn: &rusqlite::Connection, app_id: &str) -> Option<Value> {
+    conn.query_row(
+        "SELECT card_url, status, name, version, skills, errors, checked_at FROM app_agent_cards WHERE app_id = ?1",
+        rusqlite::params![app_id],
+        |row| {
+            let skills: Vec<String> = serde_json::from_str(&row.get::<_, String>(4)?).unwrap_or_default();
+            let errors: Vec<String> = serde_json::from_str(&row.get::<_, String>(5)?).unwrap_or_default();
+            Ok(json!({
+                "card_url": row.get::<_, String>(0)?,
+                "status": row.get::<_, String>(1)?,
+                "name": row.get::<_, Option<String>>(2)?,
+                "version": row.get::<_, Option<String>>(3)?,
+                "skills": skills,
+                "errors": errors,
+                "checked_at": row.get::<_, String>(6)?,
+            }))
+        },
+    )
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lints_agent_cards() {
+        let ok = lint_card(
+            r#"{"name":"Echo","description":"Repeats","url":"https://echo.example.com/a2a","version":"1.0",
+                "capabilities":{},"skills":[{"id":"echo","name":"Echo"}]}"#,
+        );
+        assert!(ok.valid, "{:?}", ok.errors);
+        assert_eq!(ok.skills, vec!["echo".to_string()]);
+
+        let bad = lint_card(r#"{"name":"Echo","url":"ftp://x","skills":[{"id":"a"}]}"#);
+        assert!(!bad.valid);
+        assert_eq!(
+            bad.errors,
+            vec![
+                "Missing 'description'",
+                "Missing 'version'",
+                "'url' must start with http:// or https://",
+                "Missing 'capabilities' object",
+                "skills[0] needs an 'id' and 'name'",
+            ]
+        );
+        assert!(!lint_card("[]").valid);
+        assert!(lint_card(&directory_card("https://apps.example.com").to_string()).valid);
+    }
+
+    #[test]
+    fn finds_the_card_url() {
+        assert_eq!(
+            card_url(Some("https://agent.example.com/a2a/v1"), None).as_deref(),
+            Some("https://agent.example.com/.well-known/agent.json")
+        );
+        assert_eq!(
+            card_url(Some("https://agent.example.com/card.json"), None).as_deref(),
+            Some("https://agent.example.com/card.json")
+        );
+        assert_eq!(
+            card_url(None, Some("http://localhost:8080/docs")).as_deref(),
+            Some("http://localhost:8080/.well-known/agent.json")
+        );
+        assert_eq!(card_url(None, None), None);
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use rand::Rng;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rusqlite::Connection;
+
+use crate::auth::{hash_key, AuthenticatedKey};
+use crate::DbState;
+
+/// Login codes are valid for 15 minutes.
+pub const CODE_TTL_SECS: i64 = 15 * 60;
+
+/// Wrong guesses allowed before a login code is burned.
+pub const MAX_CODE_ATTEMPTS: i64 = 5;
+
+/// Login codes one email can be sent per window, so reissuing a burned code
+/// can't buy unlimited guesses (or flood the inbox).
+pub const MAX_CODES_PER_WINDOW: i64 = 5;
+
+/// Length of the per-email code window: one hour.
+pub const CODE_WINDOW_SECS: i64 = 60 * 60;
+
+/// Messages the log mailer keeps; older ones are dropped first.
+pub const LOG_OUTBOX_CAPACITY: usize = 100;
+
+/// Sessions last 30 days.
+const SESSION_TTL_DAYS: i64 = 30;
+
+/// Prefix that distinguishes session tokens from API keys (`ad_`).
+pub(crate) const SESSION_PREFIX: &str = "sess_";
+
+/// Sent-mail record kept by the log mailer.
+#[derive(Debug, Clone)]
+pub struct OutgoingMail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Outgoing email for login codes and notifications.
+///
+/// Uses SMTP when `SMTP_HOST` is set. Otherwise messages are printed to
+/// stdout and the last `LOG_OUTBOX_CAPACITY` are kept in memory, which is
+/// enough for local development and tests. Clones share the same transport or outbox.
+#[derive(Clone)]
+pub enum Mailer {
+    Smtp {
+        transport: Box<AsyncSmtpTransport<Tokio1Executor>>,
+        from: Mailbox,
+    },
+    Log {
+        outbox: Arc<Mutex<VecDeque<OutgoingMail>>>,
+    },
+}
+
+impl Mailer {
+    /// Configure from `SMTP_HOST`, `SMTP_PORT` (default 587, STARTTLS),
+    /// `SMTP_USERNAME`, `SMTP_PASSWORD`, and `SMTP_FROM`.
+    pub fn from_env() -> Self {
+        let host = match std::env::var("SMTP_HOST").ok().filter(|h| !h.is_empty()) {
+            Some(h) => h,
+            None => return Mailer::log(),
+        };
+
+        let port: u16 = std::env::var("SMTP_PORT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(587);
+        let from: Mailbox = std::env::var("SMTP_FROM")
+            .ok()
+            .and_then(|f| f.parse().ok())
+            .unwrap_or_else(|| "App Directory <noreply@localhost>".parse().unwrap());
+
+        let mut builder = match AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host) {
+            Ok(b) => b.port(port),
+            Err(e) => {
+                eprintln!("❌ Invalid SMTP_HOST '{}': {} — falling back to log mailer", host, e);
+                return Mailer::log();
+            }
+        };
+        if let (Ok(user), Ok(pass)) = (std::env::var("SMTP_USERNAME"), std::env::var("SMTP_PASSWORD")) {
+            builder = builder.credentials(Credentials::new(user, pass));
+        }
+
+        println!("📧 Sending email via SMTP ({}:{})", host, port);
+        Mailer::Smtp {
+            transport: Box::new(builder.build()),
+            from,
+        }
+    }
+
+    pub fn log() -> Self {
+        Mailer::Log {
+            outbox: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Fire-and-forget delivery. SMTP errors are logged, never surfaced to the caller.
+    pub fn send(&self, to: &str, subject: &str, body: String) {
+        // The log mailer records synchronously so the outbox is readable right away
+        if let Mailer::Log { outbox } = self {
+            log_mail(outbox, to, subject, body);
+            return;
+        }
+        let mailer = self.clone();
+        let (to, subject) = (to.to_string(), subject.to_string());
+        tokio::spawn(async move {
+            if let Err(e) = mailer.deliver(&to, &subject, body).await {
+                eprintln!("❌ Email delivery failed: {e}");
+            }
+        });
+    }
+
+    /// Deliver and wait for the outcome, so callers can retry failures.
+    pub async fn deliver(&self, to: &str, subject: &str, body: String) -> Result<(), String> {
+        match self {
+            Mailer::Smtp { transport, from } => {
+                let to = to.parse::<Mailbox>().map_err(|e| e.to_string())?;
+                let message = Message::builder()
+                    .from(from.clone())
+                    .to(to)
+                    .subject(subject)
+                    .body(body)
+                    .map_err(|e| e.to_string())?;
+                transport.send(message).await.map(|_| ()).map_err(|e| e.to_string())
+            }
+            Mailer::Log { outbox } => {
+                log_mail(outbox, to, subject, body);
+                Ok(())
+            }
+        }
+    }
+
+    /// Messages captured by the log mailer, oldest first (empty for SMTP).
+    pub fn outbox(&self) -> Vec<OutgoingMail> {
+        match self {
+            Mailer::Smtp { .. } => Vec::new(),
+            Mailer::Log { outbox } => outbox.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect(),
+        }
+    }
+}
+
+fn log_mail(outbox: &Mutex<VecDeque<OutgoingMail>>, to: &str, subject: &str, body: String) {
+    println!("📧 (SMTP not configured) To: {to} — {subject}\n{body}");
+    let mut outbox = outbox.lock().unwrap_or_else(|e| e.into_inner());
+    if outbox.len() >= LOG_OUTBOX_CAPACITY {
+        outbox.pop_front();
+    }
+    outbox.push_back(OutgoingMail {
+        to: to.to_string(),
+        subject: subject.to_string(),
+        body,
+    });
+}
+
+/// Whether a (normalized) email address is syntactically valid.
+pub fn is_valid_email(email: &str) -> bool {
+    email.len() <= 254 && email.parse::<lettre::Address>().is_ok()
+}
+
+/// Lowercase and trim an email address for lookup.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// A fresh 6-digit login code.
+pub fn generate_code() -> String {
+    format!("{:06}", rand::thread_rng().gen_range(0..1_000_000))
+}
+
+/// Store a new login code for an email, replacing any previous one.
+///
+/// Returns `false` without storing when the email was already sent
+/// `MAX_CODES_PER_WINDOW` codes in the current `CODE_WINDOW_SECS` window.
+/// The count survives burned and expired codes; only a successful login clears it.
+pub fn store_code(conn: &Connection, email: &str, code: &str) -> rusqlite::Result<bool> {
+    let window = format!("-{} seconds", CODE_WINDOW_SECS);
+    // Forget emails whose code and window have both run out
+    conn.execute(
+        "DELETE FROM login_codes WHERE expires_at <= datetime('now')
+         AND (window_start IS NULL OR window_start <= datetime('now', ?1))",
+        rusqlite::params![window],
+    )?;
+
+    let issued: i64 = conn
+        .query_row(
+            "SELECT issued FROM login_codes WHERE email = ?1 AND window_start > datetime('now', ?2)",
+            rusqlite::params![email, window],
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+    if issued >= MAX_CODES_PER_WINDOW {
+        return Ok(false);
+    }
+
+    conn.execute(
+        "INSERT INTO login_codes (email, code_hash, attempts, expires_at, issued, window_start)
+         VALUES (?1, ?2, 0, datetime('now', ?3), 1, datetime('now'))
+         ON CONFLICT(email) DO UPDATE SET
+            code_hash = excluded.code_hash, attempts = 0, expires_at = excluded.expires_at,
+            issued = CASE WHEN window_start > datetime('now', ?4) THEN issued + 1 ELSE 1 END,
+            window_start = CASE WHEN window_start > datetime('now', ?4) THEN window_start ELSE excluded.window_start END",
+        rusqlite::params![email, hash_key(code), format!("+{} seconds", CODE_TTL_SECS), window],
+    )?;
+    Ok(true)
+}
+
+/// Why a login code was rejected.
+#[derive(Debug, PartialEq, Eq)]
+pub enum CodeError {
+    /// No code, expired, or burned by too many attempts
+    Expired,
+    /// Wrong code; the attempt was counted
+    Invalid,
+}
+
+/// Check a login code and consume it on success.
+pub fn consume_code(conn: &Connection, email: &str, code: &str) -> Result<(), CodeError> {
+    let row: Option<(String, i64)> = conn
+        .query_row(
+            "SELECT code_hash, attempts FROM login_codes WHERE email = ?1 AND expires_at > datetime('now')",
+            rusqlite::params![email],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .ok();
+
+    // The row stays behind so the email's issue count holds until its window ends
+    let (code_hash, attempts) = match row {
+        Some(r) if r.1 < MAX_CODE_ATTEMPTS => r,
+        _ => return Err(CodeError::Expired),
+    };
+
+    if code_hash != hash_key(code.trim()) {
+        let _ = conn.execute(
+            "UPDATE login_codes SET attempts = ?1 WHERE email = ?2",
+            rusqlite::params![attempts + 1, email],
+        );
+        return Err(CodeError::Invalid);
+    }
+
+    let _ = conn.execute("DELETE FROM login_codes WHERE email = ?1", rusqlite::params![email]);
+    Ok(())
+}
+
+/// Find or create the account for an email and open a session.
+/// Returns `(account_id, session_token, expires_at)`.
+pub fn start_session(conn: &Connection, email: &str) -> rusqlite::Result<(String, String, String)> {
+    conn.execute(
+        "INSERT INTO accounts (id, email) VALUES (?1, ?2) ON CONFLICT(email) DO NOTHING",
+        rusqlite::params![uuid::Uuid::new_v4().to_string(), email],
+    )?;
+    let account_id: String = conn.query_row(
+        "SELECT id FROM accounts WHERE email = ?1",
+        rusqlite::params![email],
+        |r| r.get(0),
+    )?;
+    conn.execute(
+        "UPDATE accounts SET last_login_at = datetime('now') WHERE id = ?1",
+        rusqlite::params![account_id],
+    )?;
+
+    let token = format!("{}{}", SESSION_PREFIX, uuid::Uuid::new_v4().to_string().replace('-', ""));
+    conn.execute(
+        "INSERT INTO account_sessions (id, account_id, token_hash, expires_at)
+         VALUES (?1, ?2, ?3, datetime('now', ?4))",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            account_id,
+            hash_key(&token),
+            format!("+{} days", SESSION_TTL_DAYS),
+        ],
+    )?;
+    let expires_at: String = conn.query_row(
+        "SELECT expires_at FROM account_sessions WHERE token_hash = ?1",
+        rusqlite::params![hash_key(&token)],
+        |r| r.get(0),
+    )?;
+
+    Ok((account_id, token, expires_at))
+}
+
+/// Account signed in with a session token (`X-Session-Token` or `Authorization: Bearer sess_...`).
+#[derive(Debug, Clone)]
+pub struct AccountSession {
+    pub account_id: String,
+    pub email: String,
+}
+
+pub(crate) fn session_token<'r>(request: &'r Request<'_>) -> Option<&'r str> {
+    request.headers().get_one("X-Session-Token").or_else(|| {
+        request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .filter(|t| t.starts_with(SESSION_PREFIX))
+    })
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AccountSession {
+    type Error = &'static str;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let token = match session_token(request) {
+            Some(t) => t,
+            None => return Outcome::Error((Status::Unauthorized, "Missing session token")),
+        };
+
+        let db = request.rocket().state::<DbState>().expect("DB not initialized");
+        let conn = db.conn();
+        let result = conn.query_row(
+            "SELECT a.id, a.email FROM account_sessions s JOIN accounts a ON a.id = s.account_id
+             WHERE s.token_hash = ?1 AND s.expires_at > datetime('now')",
+            rusqlite::params![hash_key(token)],
+            |r| {
+                Ok(AccountSession {
+                    account_id: r.get(0)?,
+                    email: r.get(1)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(session) => Outcome::Success(session),
+            Err(_) => Outcome::Error((Status::Unauthorized, "Invalid or expired session")),
+        }
+    }
+}
+
+/// Caller identified by either an account session or an API key.
+/// A session token takes precedence; otherwise the API key guard decides
+/// (including its rate limiting and error responses).
+#[derive(Debug)]
+pub enum Principal {
+    Account(AccountSession),
+    Key(AuthenticatedKey),
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Principal {
+    type Error = &'static str;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        if session_token(request).is_some() {
+            return request.guard::<AccountSession>().await.map(Principal::Account);
+        }
+        request.guard::<AuthenticatedKey>().await.map(Principal::Key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_outbox_keeps_the_latest_messages() {
+        let mailer = Mailer::log();
+        for i in 0..LOG_OUTBOX_CAPACITY + 5 {
+            mailer.send("a@example.com", "code", i.to_string());
+        }
+        let outbox = mailer.outbox();
+        assert_eq!(outbox.len(), LOG_OUTBOX_CAPACITY);
+        assert_eq!(outbox[0].body, "5");
+        assert_eq!(outbox.last().unwrap().body, (LOG_OUTBOX_CAPACITY + 4).to_string());
+    }
+}
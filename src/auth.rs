@@ -5,7 +5,12 @@ use rusqlite::Connection;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
-use crate::rate_limit::RateLimiter;
+use crate::accounts::{session_token, AccountSession, Principal, SESSION_PREFIX};
+use crate::errors::{ApiError, ErrorCode};
+use crate::events::{AppEvent, EventBus};
+use crate::key_usage::{self, KeyUsage};
+use crate::oauth;
+use crate::rate_limit::{self, RateLimiter};
 use crate::DbState;
 
 /// Simple hash for API keys and edit tokens (not cryptographic — fine for this use case)
@@ -22,25 +27,58 @@ pub fn create_api_key(
     is_admin: bool,
     rate_limit: Option<i64>,
 ) -> String {
+    create_expiring_api_key(conn, name, is_admin, rate_limit, None).1
+}
+
+/// Create an API key with an optional expiry (SQLite datetime, UTC).
+/// Returns `(id, raw_key)`.
+pub fn create_expiring_api_key(
+    conn: &Connection,
+    name: &str,
+    is_admin: bool,
+    rate_limit: Option<i64>,
+    expires_at: Option<&str>,
+) -> (String, String) {
     let id = uuid::Uuid::new_v4().to_string();
-    let raw_key = format!("ad_{}", uuid::Uuid::new_v4().to_string().replace('-', ""));
+    let raw_key = generate_raw_key();
     let key_hash = hash_key(&raw_key);
     let rl = rate_limit.unwrap_or(if is_admin { 10_000 } else { 100 });
 
     conn.execute(
-        "INSERT INTO api_keys (id, name, key_hash, is_admin, rate_limit) VALUES (?1, ?2, ?3, ?4, ?5)",
-        rusqlite::params![id, name, key_hash, is_admin as i32, rl],
+        "INSERT INTO api_keys (id, name, key_hash, is_admin, rate_limit, expires_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![id, name, key_hash, is_admin as i32, rl, expires_at],
     )
     .expect("Failed to create API key");
 
-    raw_key
+    (id, raw_key)
 }
 
+/// A fresh random API key secret.
+pub fn generate_raw_key() -> String {
+    format!("ad_{}", uuid::Uuid::new_v4().to_string().replace('-', ""))
+}
+
+/// Parse an RFC 3339 expiry into SQLite's UTC datetime format.
+/// Rejects unparseable or past timestamps.
+pub fn parse_expiry(raw: &str) -> Result<String, &'static str> {
+    let parsed = chrono::DateTime::parse_from_rfc3339(raw.trim())
+        .map_err(|_| "expires_at must be an RFC 3339 timestamp")?
+        .with_timezone(&chrono::Utc);
+    if parsed <= chrono::Utc::now() {
+        return Err("expires_at must be in the future");
+    }
+    Ok(parsed.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// Why the API key guard rejected a request. Cached on the request so the
+/// 401 catcher can return a specific error code.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AuthFailure(pub Option<&'static str>);
+
 /// Authenticated caller info extracted from request (OPTIONAL for most routes now)
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct AuthenticatedKey {
     pub id: String,
-    #[allow(dead_code)]
     pub name: String,
     pub is_admin: bool,
 }
@@ -50,7 +88,8 @@ impl<'r> FromRequest<'r> for AuthenticatedKey {
     type Error = &'static str;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        // Extract key from Authorization: Bearer or X-API-Key header
+        // Extract key from Authorization: Bearer or X-API-Key header. A
+        // bearer token may also be an OAuth access token standing in for a key.
         let raw_key = request
             .headers()
             .get_one("Authorization")
@@ -72,9 +111,27 @@ impl<'r> FromRequest<'r> for AuthenticatedKey {
         // Scope the DB lock so it's dropped before any .await
         let result = {
             let conn = db.0.lock().expect("DB lock poisoned");
+            let lookup = match raw_key.starts_with(oauth::TOKEN_PREFIX) {
+                false => ("key_hash", key_hash),
+                true => match oauth::token_key(&conn, &raw_key) {
+                    Some((_, true)) => {
+                        request.local_cache(|| AuthFailure(Some("TOKEN_EXPIRED")));
+                        return Outcome::Error((Status::Unauthorized, "Access token expired"));
+                    }
+                    Some((key_id, false)) => ("id", key_id),
+                    None => return Outcome::Error((Status::Unauthorized, "Invalid access token")),
+                },
+            };
             conn.query_row(
-                "SELECT id, name, is_admin, rate_limit FROM api_keys WHERE key_hash = ?1 AND revoked = 0",
-                rusqlite::params![key_hash],
+                &format!(
+                    "SELECT id, name, is_admin, rate_limit,
+                            expires_at IS NOT NULL AND expires_at <= datetime('now'),
+                            rate_burst, exempt_routes,
+                            throttled_until IS NOT NULL AND throttled_until > datetime('now')
+                     FROM api_keys WHERE {} = ?1 AND revoked = 0",
+                    lookup.0
+                ),
+                rusqlite::params![lookup.1],
                 |row| {
                     Ok((
                         AuthenticatedKey {
@@ -83,13 +140,34 @@ impl<'r> FromRequest<'r> for AuthenticatedKey {
                             is_admin: row.get::<_, i32>(2)? != 0,
                         },
                         row.get::<_, i64>(3)?,
+                        row.get::<_, bool>(4)?,
+                        row.get::<_, i64>(5)?,
+                        row.get::<_, String>(6)?,
+                        row.get::<_, bool>(7)?,
                     ))
                 },
             )
         };
 
         match result {
-            Ok((auth_key, rate_limit)) => {
+            Ok((_, _, true, _, _, _)) => {
+                request.local_cache(|| AuthFailure(Some("KEY_EXPIRED")));
+                Outcome::Error((Status::Unauthorized, "API key expired"))
+            }
+            Ok((auth_key, rate_limit, false, burst, exempt_routes, throttled)) => {
+                if let Some(usage) = request.rocket().state::<KeyUsage>() {
+                    usage.record(&auth_key.id);
+                }
+
+                // Exempt routes skip the limiter entirely: nothing is counted
+                // and no rate limit headers are sent. The rate limit info
+                // endpoint only reads the window.
+                let peek = request.uri().path() == rate_limit::INFO_PATH;
+                let exempt: Vec<String> = serde_json::from_str(&exempt_routes).unwrap_or_default();
+                if !peek && rate_limit::is_exempt(&exempt, request.method().as_str(), request.uri().path().as_str()) {
+                    return Outcome::Success(auth_key);
+                }
+
                 // Get the rate limiter from Rocket state
                 let limiter = match request.guard::<&State<RateLimiter>>().await {
                     Outcome::Success(l) => l,
@@ -101,13 +179,42 @@ impl<'r> FromRequest<'r> for AuthenticatedKey {
                     }
                 };
 
-                // Enforce rate limit (per-key, fixed window)
-                let rl_result = limiter.check(&auth_key.id, rate_limit as u64);
+                // Enforce rate limit (per-key, fixed window plus burst allowance);
+                // a key flagged as anomalous gets a fraction of its limit for a while
+                let (rate_limit, burst) = match throttled {
+                    true => ((rate_limit / key_usage::THROTTLE_DIVISOR).max(1), 0),
+                    false => (rate_limit, burst),
+                };
+                // Expensive routes count as several requests
+                let rl_result = match peek {
+                    true => limiter.peek(&auth_key.id, rate_limit as u64, burst.max(0) as u64),
+                    false => {
+                        let cost = limiter.cost_of(request.method().as_str(), request.uri().path().as_str());
+                        limiter.check_with_cost(&auth_key.id, rate_limit as u64, burst.max(0) as u64, cost)
+                    }
+                };
 
                 // Store rate limit info in request-local state for response headers
                 let _ = request.local_cache(|| Some(rl_result.clone()));
 
-                if !rl_result.allowed {
+                if rl_result.warning {
+                    if let Some(bus) = request.rocket().state::<EventBus>() {
+                        let reset_at = chrono::Utc::now() + chrono::Duration::seconds(rl_result.reset_secs as i64);
+                        bus.emit(AppEvent {
+                            event: "rate_limit.warning".to_string(),
+                            data: serde_json::json!({
+                                "key_id": auth_key.id,
+                                "name": auth_key.name,
+                                "limit": rl_result.limit,
+                                "remaining": rl_result.remaining,
+                                "reset_secs": rl_result.reset_secs,
+                                "reset_at": reset_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                            }),
+                        });
+                    }
+                }
+
+                if !rl_result.allowed && !peek {
                     return Outcome::Error((
                         Status::TooManyRequests,
                         "Rate limit exceeded. Try again later.",
@@ -137,6 +244,60 @@ impl<'r> FromRequest<'r> for OptionalKey {
     }
 }
 
+/// Whether read endpoints (list/get/search) require authentication.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReadPolicy {
+    pub require_auth: bool,
+}
+
+/// Caller of a read endpoint. Behaves like `OptionalKey` unless
+/// `REQUIRE_AUTH_FOR_READS` is set, in which case an API key or account
+/// session is required. Holds the API key or account session used, if any.
+#[derive(Debug)]
+pub struct ReadAccess(pub Option<AuthenticatedKey>, pub Option<AccountSession>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ReadAccess {
+    type Error = &'static str;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let require_auth = request
+            .rocket()
+            .state::<ReadPolicy>()
+            .is_some_and(|p| p.require_auth);
+        if !require_auth {
+            if let Outcome::Success(OptionalKey(Some(key))) = request.guard::<OptionalKey>().await {
+                return Outcome::Success(ReadAccess(Some(key), None));
+            }
+            // A session that doesn't authenticate reads as anonymous here
+            let session = match session_token(request) {
+                Some(_) => request.guard::<AccountSession>().await.succeeded(),
+                None => None,
+            };
+            return Outcome::Success(ReadAccess(None, session));
+        }
+        match request.guard::<Principal>().await {
+            Outcome::Success(Principal::Key(key)) => Outcome::Success(ReadAccess(Some(key), None)),
+            Outcome::Success(Principal::Account(session)) => Outcome::Success(ReadAccess(None, Some(session))),
+            Outcome::Error(e) => Outcome::Error(e),
+            Outcome::Forward(s) => Outcome::Forward(s),
+        }
+    }
+}
+
+impl ReadAccess {
+    /// Whether the reader used an admin API key.
+    pub fn is_admin(&self) -> bool {
+        self.0.as_ref().is_some_and(|k| k.is_admin)
+    }
+
+    /// Whether an API key or account session authenticated the reader.
+    /// Private listings are only shown to signed-in readers.
+    pub fn is_signed_in(&self) -> bool {
+        self.0.is_some() || self.1.is_some()
+    }
+}
+
 /// Edit token extracted from ?token= query param or X-Edit-Token header (optional)
 #[derive(Debug)]
 pub struct EditTokenParam(pub Option<String>);
@@ -159,6 +320,53 @@ impl<'r> FromRequest<'r> for EditTokenParam {
     }
 }
 
+/// Review secret from the X-Review-Secret header or ?secret= query param
+/// (optional). Issued to anonymous reviewers so they can edit or delete their review.
+#[derive(Debug)]
+pub struct ReviewSecret(pub Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ReviewSecret {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let secret = request
+            .headers()
+            .get_one("X-Review-Secret")
+            .map(|s| s.to_string())
+            .or_else(|| request.query_value::<String>("secret").and_then(|r| r.ok()));
+        Outcome::Success(ReviewSecret(secret))
+    }
+}
+
+/// Anonymous client identity for abuse controls: the client IP plus a
+/// fingerprint hashed from IP and User-Agent. Raw values are never stored.
+/// The IP is the peer address unless `TRUSTED_IP_HEADER` names a proxy header.
+#[derive(Debug)]
+pub struct ClientFingerprint {
+    /// Hash of the client IP alone (for per-IP throttling)
+    pub ip_hash: String,
+    /// Hash of client IP + User-Agent (for per-reviewer dedup)
+    pub fingerprint: String,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ClientFingerprint {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let ip = request
+            .client_ip()
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let ua = request.headers().get_one("User-Agent").unwrap_or("");
+        Outcome::Success(ClientFingerprint {
+            ip_hash: hash_key(&format!("ip:{}", ip)),
+            fingerprint: hash_key(&format!("{}|{}", ip, ua)),
+        })
+    }
+}
+
 /// Result of checking edit access for an app
 #[derive(Debug)]
 pub enum EditAccess {
@@ -166,6 +374,8 @@ pub enum EditAccess {
     EditToken,
     /// Authenticated via API key (owner of the app)
     Owner(String),
+    /// Authenticated via account session (account the app is bound to)
+    Account(String),
     /// Authenticated via admin API key
     Admin(String),
 }
@@ -175,78 +385,126 @@ impl EditAccess {
     pub fn is_admin(&self) -> bool {
         matches!(self, EditAccess::Admin(_))
     }
+
+    /// Editor recorded in revision history: `(kind, id)`.
+    pub fn editor(&self) -> (&'static str, Option<&str>) {
+        match self {
+            EditAccess::EditToken => ("edit_token", None),
+            EditAccess::Owner(id) => ("api_key", Some(id)),
+            EditAccess::Account(id) => ("account", Some(id)),
+            EditAccess::Admin(id) => ("admin", Some(id)),
+        }
+    }
 }
 
-/// Check if the caller can edit a specific app.
-/// Tries: (1) edit token, (2) API key owner, (3) admin key.
-/// Returns Ok(EditAccess) or Err((Status, error json)).
-pub fn check_edit_access(
-    conn: &Connection,
-    app_id: &str,
-    edit_token: &Option<String>,
-    api_key: &Option<AuthenticatedKey>,
-) -> Result<EditAccess, (Status, serde_json::Value)> {
-    // First, verify app exists
-    let app_exists: bool = conn
-        .query_row(
-            "SELECT COUNT(*) > 0 FROM apps WHERE id = ?1",
-            rusqlite::params![app_id],
-            |r| r.get(0),
-        )
-        .unwrap_or(false);
-
-    if !app_exists {
-        return Err((
-            Status::NotFound,
-            serde_json::json!({ "error": "NOT_FOUND", "message": "App not found" }),
-        ));
-    }
-
-    // Try edit token first
-    if let Some(token) = edit_token {
-        let token_hash = hash_key(token);
-        let valid: bool = conn
-            .query_row(
-                "SELECT COUNT(*) > 0 FROM apps WHERE id = ?1 AND edit_token_hash = ?2",
-                rusqlite::params![app_id, token_hash],
-                |r| r.get(0),
-            )
-            .unwrap_or(false);
+/// Who is making a request, resolved once from every credential the API
+/// accepts. When a request carries several, the API key wins, then the
+/// account session, then the edit token.
+///
+/// A presented API key or session that fails to authenticate rejects the
+/// request (401/429 via the key guard) rather than falling back to anonymous.
+#[derive(Debug)]
+pub enum Actor {
+    AdminKey(AuthenticatedKey),
+    Key(AuthenticatedKey),
+    Account(AccountSession),
+    EditToken(String),
+    Anonymous,
+}
+
+impl Actor {
+    pub fn is_admin(&self) -> bool {
+        matches!(self, Actor::AdminKey(_))
+    }
 
-        if valid {
-            return Ok(EditAccess::EditToken);
+    /// The API key used, admin or not.
+    pub fn key(&self) -> Option<&AuthenticatedKey> {
+        match self {
+            Actor::AdminKey(key) | Actor::Key(key) => Some(key),
+            _ => None,
         }
     }
 
-    // Try API key
-    if let Some(key) = api_key {
-        if key.is_admin {
-            return Ok(EditAccess::Admin(key.id.clone()));
+    pub fn key_id(&self) -> Option<&str> {
+        self.key().map(|k| k.id.as_str())
+    }
+
+    pub fn account_id(&self) -> Option<&str> {
+        match self {
+            Actor::Account(session) => Some(session.account_id.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The admin key, or the error for a non-admin (`ADMIN_REQUIRED` with
+    /// `message`) or unauthenticated (`UNAUTHORIZED`) caller.
+    pub fn require_admin(&self, message: &str) -> Result<&AuthenticatedKey, ApiError> {
+        match self {
+            Actor::AdminKey(key) => Ok(key),
+            Actor::Key(_) => Err(ApiError::new(ErrorCode::AdminRequired, message)),
+            _ => Err(ApiError::from(ErrorCode::Unauthorized)),
         }
+    }
 
-        let is_owner: bool = conn
+    /// Check whether this caller can edit an app: admins always, keys and
+    /// accounts that own it, or the app's own edit token.
+    pub fn can_edit(&self, conn: &Connection, app_id: &str) -> Result<EditAccess, ApiError> {
+        let owner: Option<(Option<String>, Option<String>, Option<String>)> = conn
             .query_row(
-                "SELECT COUNT(*) > 0 FROM apps WHERE id = ?1 AND submitted_by_key_id = ?2",
-                rusqlite::params![app_id, key.id],
-                |r| r.get(0),
+                "SELECT submitted_by_key_id, account_id, edit_token_hash FROM apps WHERE id = ?1",
+                rusqlite::params![app_id],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
             )
-            .unwrap_or(false);
+            .ok();
+        let (owner_key, owner_account, token_hash) = match owner {
+            Some(o) => o,
+            None => return Err(ApiError::new(ErrorCode::NotFound, "App not found")),
+        };
+
+        let access = match self {
+            Actor::AdminKey(key) => Some(EditAccess::Admin(key.id.clone())),
+            Actor::Key(key) if owner_key.as_deref() == Some(key.id.as_str()) => Some(EditAccess::Owner(key.id.clone())),
+            Actor::Account(session) if owner_account.as_deref() == Some(session.account_id.as_str()) => {
+                Some(EditAccess::Account(session.account_id.clone()))
+            }
+            Actor::EditToken(token) if token_hash.as_deref() == Some(hash_key(token).as_str()) => Some(EditAccess::EditToken),
+            _ => None,
+        };
 
-        if is_owner {
-            return Ok(EditAccess::Owner(key.id.clone()));
+        match (access, self) {
+            (Some(access), _) => Ok(access),
+            (None, Actor::Anonymous) => Err(ApiError::new(
+                ErrorCode::Unauthorized,
+                "Edit token, API key, or session token required. Pass edit token via ?token= query param or X-Edit-Token header.",
+            )),
+            (None, _) => Err(ApiError::new(ErrorCode::Forbidden, "You don't have permission to edit this app")),
         }
     }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Actor {
+    type Error = &'static str;
 
-    // No valid auth
-    if edit_token.is_some() || api_key.is_some() {
-        Err((
-            Status::Forbidden,
-            serde_json::json!({ "error": "FORBIDDEN", "message": "You don't have permission to edit this app" }),
-        ))
-    } else {
-        Err((
-            Status::Unauthorized,
-            serde_json::json!({ "error": "UNAUTHORIZED", "message": "Edit token or API key required. Pass edit token via ?token= query param or X-Edit-Token header." }),
-        ))
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let has_key = request.headers().get_one("X-API-Key").is_some()
+            || request
+                .headers()
+                .get_one("Authorization")
+                .and_then(|h| h.strip_prefix("Bearer "))
+                .is_some_and(|t| !t.starts_with(SESSION_PREFIX));
+        if has_key {
+            return request
+                .guard::<AuthenticatedKey>()
+                .await
+                .map(|key| if key.is_admin { Actor::AdminKey(key) } else { Actor::Key(key) });
+        }
+        if session_token(request).is_some() {
+            return request.guard::<AccountSession>().await.map(Actor::Account);
+        }
+        match request.guard::<EditTokenParam>().await {
+            Outcome::Success(EditTokenParam(Some(token))) => Outcome::Success(Actor::EditToken(token)),
+            _ => Outcome::Success(Actor::Anonymous),
+        }
     }
 }
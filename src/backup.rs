@@ -0,0 +1,173 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, DatabaseName};
+use serde::Serialize;
+
+/// Backups kept when `BACKUP_KEEP` is unset.
+pub const DEFAULT_BACKUP_KEEP: usize = 7;
+
+const PREFIX: &str = "backup-";
+const SUFFIX: &str = ".db";
+
+/// Where snapshots are written and how many are kept.
+#[derive(Debug, Clone)]
+pub struct BackupConfig {
+    pub dir: PathBuf,
+    /// Newest backups to keep after each snapshot (0 keeps all).
+    pub keep: usize,
+}
+
+impl BackupConfig {
+    /// `BACKUP_DIR` (default: `<database name>-backups` next to the
+    /// database) and `BACKUP_KEEP` (default: 7, 0 keeps all).
+    pub fn from_env(db_path: &str) -> Self {
+        let dir = std::env::var("BACKUP_DIR")
+            .ok()
+            .filter(|d| !d.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| default_dir(db_path));
+        let keep = std::env::var("BACKUP_KEEP")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_BACKUP_KEEP);
+        BackupConfig { dir, keep }
+    }
+}
+
+fn default_dir(db_path: &str) -> PathBuf {
+    let path = Path::new(db_path);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("app_directory");
+    path.with_file_name(format!("{stem}-backups"))
+}
+
+/// A snapshot file in the backup directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub name: String,
+    pub size_bytes: u64,
+    pub created_at: String,
+}
+
+impl BackupInfo {
+    fn read(path: &Path) -> io::Result<BackupInfo> {
+        let meta = std::fs::metadata(path)?;
+        let created_at: chrono::DateTime<chrono::Utc> = meta.modified()?.into();
+        Ok(BackupInfo {
+            name: path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string(),
+            size_bytes: meta.len(),
+            created_at: created_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        })
+    }
+}
+
+/// Whether `name` is a snapshot file this module wrote. Also rejects
+/// anything that could escape the backup directory.
+pub fn is_backup_name(name: &str) -> bool {
+    name.starts_with(PREFIX)
+        && name.ends_with(SUFFIX)
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+}
+
+/// Snapshots in `config.dir`, newest first.
+pub fn list(config: &BackupConfig) -> io::Result<Vec<BackupInfo>> {
+    let entries = match std::fs::read_dir(&config.dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut backups = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.file_name().and_then(|n| n.to_str()).is_some_and(is_backup_name) {
+            backups.push(BackupInfo::read(&path)?);
+        }
+    }
+    // Names embed a UTC timestamp, so they sort chronologically
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(backups)
+}
+
+/// Copy the live database into a new snapshot with SQLite's online backup
+/// API, which gives a consistent copy even while other connections write.
+/// The file appears under its final name only once complete. Older
+/// snapshots beyond `config.keep` are deleted; their names are returned
+/// alongside the new backup.
+pub fn create(conn: &Connection, config: &BackupConfig) -> io::Result<(BackupInfo, Vec<String>)> {
+    std::fs::create_dir_all(&config.dir)?;
+    let name = format!("{PREFIX}{}{SUFFIX}", chrono::Utc::now().format("%Y%m%dT%H%M%S%3fZ"));
+    let path = config.dir.join(&name);
+    let partial = config.dir.join(format!("{name}.partial"));
+
+    let copied = conn.backup(DatabaseName::Main, &partial, None).map_err(io::Error::other);
+    if let Err(e) = copied {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+    std::fs::rename(&partial, &path)?;
+    let info = BackupInfo::read(&path)?;
+    let removed = rotate(config)?;
+    Ok((info, removed))
+}
+
+/// Delete all but the newest `config.keep` snapshots.
+fn rotate(config: &BackupConfig) -> io::Result<Vec<String>> {
+    if config.keep == 0 {
+        return Ok(Vec::new());
+    }
+    let mut removed = Vec::new();
+    for old in list(config)?.into_iter().skip(config.keep) {
+        std::fs::remove_file(config.dir.join(&old.name))?;
+        removed.push(old.name);
+    }
+    Ok(removed)
+}
+
+/// Replace the database at `db_path` with the snapshot at `backup_path`.
+/// The snapshot is integrity-checked first, and the current database is
+/// itself snapshotted so the restore can be undone. Run with the server
+/// stopped.
+pub fn restore(backup_path: &Path, db_path: &str, config: &BackupConfig) -> io::Result<BackupInfo> {
+    if !backup_path.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} not found", backup_path.display()),
+        ));
+    }
+    let source = Connection::open_with_flags(backup_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(io::Error::other)?;
+    match crate::db::integrity_check(&source, true).map_err(io::Error::other)? {
+        Ok(()) => {}
+        Err(problems) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("backup failed integrity check: {}", problems.join("; ")),
+            ))
+        }
+    }
+    drop(source);
+
+    let mut conn = Connection::open(db_path).map_err(io::Error::other)?;
+    conn.busy_timeout(crate::db::BUSY_TIMEOUT).map_err(io::Error::other)?;
+    let (safety, _) = create(&conn, config)?;
+    conn.restore(DatabaseName::Main, backup_path, None::<fn(rusqlite::backup::Progress)>)
+        .map_err(io::Error::other)?;
+    Ok(safety)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_backup_names() {
+        assert!(is_backup_name("backup-20261016T120000123Z.db"));
+        assert!(!is_backup_name("backup-20261016T120000123Z.db.partial"));
+        assert!(!is_backup_name("backup-../app_directory.db"));
+        assert!(!is_backup_name("app_directory.db"));
+        assert_eq!(
+            default_dir("/var/lib/app_directory.db"),
+            PathBuf::from("/var/lib/app_directory-backups")
+        );
+    }
+}
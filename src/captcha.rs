@@ -0,0 +1,245 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::auth::Actor;
+use crate::errors::{ApiError, ErrorCode};
+
+/// How long to wait for the provider's verify endpoint.
+const VERIFY_TIMEOUT_SECS: u64 = 5;
+
+/// CAPTCHA service whose tokens are accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptchaProvider {
+    HCaptcha,
+    Turnstile,
+}
+
+impl CaptchaProvider {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "hcaptcha" => Some(CaptchaProvider::HCaptcha),
+            "turnstile" => Some(CaptchaProvider::Turnstile),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CaptchaProvider::HCaptcha => "hcaptcha",
+            CaptchaProvider::Turnstile => "turnstile",
+        }
+    }
+
+    /// Both providers take the same form-encoded `secret`/`response`/`remoteip`
+    /// request and answer `{"success": bool, "error-codes": [...]}`.
+    fn verify_url(self) -> &'static str {
+        match self {
+            CaptchaProvider::HCaptcha => "https://api.hcaptcha.com/siteverify",
+            CaptchaProvider::Turnstile => "https://challenges.cloudflare.com/turnstile/v0/siteverify",
+        }
+    }
+}
+
+/// Write endpoints that can demand a CAPTCHA from anonymous callers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptchaRoute {
+    /// `POST /apps`
+    Apps,
+    /// `POST /apps/<id>/reviews`
+    Reviews,
+}
+
+impl CaptchaRoute {
+    pub const ALL: &'static [CaptchaRoute] = &[CaptchaRoute::Apps, CaptchaRoute::Reviews];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "apps" => Some(CaptchaRoute::Apps),
+            "reviews" => Some(CaptchaRoute::Reviews),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CaptchaRoute::Apps => "apps",
+            CaptchaRoute::Reviews => "reviews",
+        }
+    }
+}
+
+/// Optional CAPTCHA check for anonymous submissions and reviews. Callers
+/// with an API key or account session are never asked for a token.
+/// Provider errors and timeouts fail closed with `503 CAPTCHA_UNAVAILABLE`,
+/// since letting the request through would defeat the check.
+pub struct Captcha {
+    provider: Option<CaptchaProvider>,
+    secret: String,
+    site_key: Option<String>,
+    verify_url: String,
+    routes: Vec<CaptchaRoute>,
+    client: reqwest::Client,
+}
+
+impl Default for Captcha {
+    /// No provider: every request passes.
+    fn default() -> Self {
+        Captcha::new(None, String::new(), Vec::new())
+    }
+}
+
+impl Captcha {
+    pub fn new(provider: Option<CaptchaProvider>, secret: String, routes: Vec<CaptchaRoute>) -> Self {
+        Captcha {
+            provider,
+            secret,
+            site_key: None,
+            verify_url: provider.map(|p| p.verify_url().to_string()).unwrap_or_default(),
+            routes,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(VERIFY_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Send verification requests somewhere other than the provider's
+    /// public endpoint (a proxy, or a stub in tests).
+    pub fn with_verify_url(mut self, url: impl Into<String>) -> Self {
+        self.verify_url = url.into();
+        self
+    }
+
+    pub fn with_site_key(mut self, site_key: Option<String>) -> Self {
+        self.site_key = site_key;
+        self
+    }
+
+    /// `CAPTCHA_PROVIDER` (`hcaptcha` or `turnstile`) and `CAPTCHA_SECRET`
+    /// enable the check; `CAPTCHA_ROUTES` (comma-separated, default
+    /// `apps,reviews`) picks the endpoints it applies to. `CAPTCHA_SITE_KEY`
+    /// is published for clients and `CAPTCHA_VERIFY_URL` overrides the
+    /// provider's endpoint.
+    pub fn from_env() -> Self {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let provider = match env("CAPTCHA_PROVIDER") {
+            Some(name) => match CaptchaProvider::parse(&name) {
+                Some(p) => p,
+                None => {
+                    eprintln!("❌ CAPTCHA disabled: unknown CAPTCHA_PROVIDER '{name}' (use hcaptcha or turnstile)");
+                    return Captcha::default();
+                }
+            },
+            None => return Captcha::default(),
+        };
+        let Some(secret) = env("CAPTCHA_SECRET") else {
+            eprintln!("❌ CAPTCHA disabled: CAPTCHA_PROVIDER is set but CAPTCHA_SECRET is not");
+            return Captcha::default();
+        };
+        let routes = match env("CAPTCHA_ROUTES") {
+            Some(list) => list.split(',').filter_map(CaptchaRoute::parse).collect(),
+            None => CaptchaRoute::ALL.to_vec(),
+        };
+
+        let mut captcha = Captcha::new(Some(provider), secret, routes).with_site_key(env("CAPTCHA_SITE_KEY"));
+        if let Some(url) = env("CAPTCHA_VERIFY_URL") {
+            captcha = captcha.with_verify_url(url);
+        }
+        captcha
+    }
+
+    /// Whether anonymous callers of `route` must send a token.
+    pub fn required_for(&self, route: CaptchaRoute) -> bool {
+        self.provider.is_some() && self.routes.contains(&route)
+    }
+
+    /// Public settings for clients rendering the widget.
+    pub fn describe(&self) -> Value {
+        json!({
+            "enabled": self.provider.is_some() && !self.routes.is_empty(),
+            "provider": self.provider.map(CaptchaProvider::as_str),
+            "site_key": self.site_key,
+            "routes": self.routes.iter().map(|r| r.as_str()).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Check `token` when `route` requires it and the caller is anonymous.
+    pub async fn verify(
+        &self,
+        route: CaptchaRoute,
+        actor: &Actor,
+        token: Option<&str>,
+        remote_ip: Option<IpAddr>,
+    ) -> Result<(), ApiError> {
+        let anonymous = actor.key().is_none() && actor.account_id().is_none();
+        if !anonymous || !self.required_for(route) {
+            return Ok(());
+        }
+        let token = match token.map(str::trim).filter(|t| !t.is_empty()) {
+            Some(t) => t,
+            None => return Err(ApiError::from(ErrorCode::CaptchaRequired).with("route", route.as_str())),
+        };
+
+        let mut form = vec![("secret", self.secret.clone()), ("response", token.to_string())];
+        if let Some(ip) = remote_ip {
+            form.push(("remoteip", ip.to_string()));
+        }
+        let outcome: Result<Value, reqwest::Error> = async {
+            self.client
+                .post(&self.verify_url)
+                .header("Content-Type", "application/x-www-form-urlencoded")
+                .body(form_encode(&form))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+        }
+        .await;
+        let answer = match outcome {
+            Ok(answer) => answer,
+            Err(e) => {
+                eprintln!("❌ CAPTCHA verification request failed: {e}");
+                return Err(ApiError::from(ErrorCode::CaptchaUnavailable));
+            }
+        };
+
+        if answer.get("success").and_then(|s| s.as_bool()) == Some(true) {
+            Ok(())
+        } else {
+            let codes = answer.get("error-codes").cloned().unwrap_or_else(|| json!([]));
+            Err(ApiError::from(ErrorCode::CaptchaFailed).with("provider_errors", codes))
+        }
+    }
+}
+
+/// `application/x-www-form-urlencoded` body for `pairs`.
+fn form_encode(pairs: &[(&str, String)]) -> String {
+    fn encode(value: &str) -> String {
+        value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+                _ => format!("%{b:02X}"),
+            })
+            .collect()
+    }
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", encode(k), encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_form_bodies() {
+        let body = form_encode(&[("secret", "a b&c".to_string()), ("response", "x=1/é".to_string())]);
+        assert_eq!(body, "secret=a%20b%26c&response=x%3D1%2F%C3%A9");
+    }
+}
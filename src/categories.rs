@@ -0,0 +1,124 @@
+use rusqlite::Connection;
+
+use crate::models::{slugify, VALID_CATEGORIES};
+
+/// Maximum depth of a category path (`top/sub/leaf`).
+pub const MAX_CATEGORY_DEPTH: usize = 3;
+
+/// Normalize and validate a category path such as `data/weather`.
+///
+/// The first segment must be one of `VALID_CATEGORIES`; deeper segments are
+/// free-form slugs (lowercase letters, digits, dashes) and are created on demand.
+pub fn parse_category_path(raw: &str) -> Result<String, String> {
+    let segments: Vec<String> = raw
+        .trim()
+        .trim_matches('/')
+        .split('/')
+        .map(|s| s.trim().to_lowercase())
+        .collect();
+
+    if segments.len() > MAX_CATEGORY_DEPTH {
+        return Err(format!(
+            "Category paths can be at most {} levels deep",
+            MAX_CATEGORY_DEPTH
+        ));
+    }
+
+    if !VALID_CATEGORIES.contains(&segments[0].as_str()) {
+        return Err(format!("Valid categories: {}", VALID_CATEGORIES.join(", ")));
+    }
+
+    for seg in &segments[1..] {
+        if seg.is_empty() || slugify(seg) != *seg {
+            return Err(format!(
+                "Invalid subcategory '{}': use lowercase letters, digits, and dashes",
+                seg
+            ));
+        }
+    }
+
+    Ok(segments.join("/"))
+}
+
+/// Parent of a category path (`data/weather` → `data`), `None` for top level.
+pub fn parent_of(path: &str) -> Option<&str> {
+    path.rsplit_once('/').map(|(parent, _)| parent)
+}
+
+/// Make sure a category and all its ancestors exist in `categories`.
+pub fn ensure_category(conn: &Connection, path: &str) -> rusqlite::Result<()> {
+    let mut current = String::new();
+    for segment in path.split('/') {
+        let parent = if current.is_empty() { None } else { Some(current.clone()) };
+        if !current.is_empty() {
+            current.push('/');
+        }
+        current.push_str(segment);
+        conn.execute(
+            "INSERT OR IGNORE INTO categories (id, name, parent_id) VALUES (?1, ?2, ?3)",
+            rusqlite::params![current, segment, parent],
+        )?;
+    }
+    Ok(())
+}
+
+/// Built-in icon and description for each top-level category, seeded into
+/// `categories` at startup. Rows an admin has edited keep their values.
+pub const DEFAULT_PRESENTATION: &[(&str, &str, &str)] = &[
+    ("communication", "💬", "Messaging, email, chat and notification services"),
+    ("data", "📊", "Datasets, data feeds and data processing APIs"),
+    ("developer-tools", "🛠️", "Tools for building, testing and shipping software"),
+    ("finance", "💰", "Payments, banking, markets and accounting"),
+    ("media", "🎬", "Images, audio, video and content generation"),
+    ("productivity", "✅", "Tasks, calendars, documents and workflow automation"),
+    ("search", "🔍", "Web, document and semantic search"),
+    ("security", "🔒", "Authentication, scanning and threat intelligence"),
+    ("social", "👥", "Social networks, communities and profiles"),
+    ("ai-ml", "🤖", "Models, inference, embeddings and agent tooling"),
+    ("infrastructure", "🏗️", "Hosting, storage, compute and monitoring"),
+    ("other", "📦", "Everything else"),
+];
+
+/// Longest accepted category `icon` and `description`, in characters.
+pub const MAX_ICON_LENGTH: usize = 64;
+pub const MAX_DESCRIPTION_LENGTH: usize = 280;
+
+/// Apply `DEFAULT_PRESENTATION` to categories no admin has customized, so
+/// changes to the defaults reach existing databases.
+pub fn seed_presentation(conn: &Connection) -> rusqlite::Result<()> {
+    for (id, icon, description) in DEFAULT_PRESENTATION {
+        conn.execute(
+            "UPDATE categories SET icon = ?2, description = ?3 WHERE id = ?1 AND customized = 0",
+            rusqlite::params![id, icon, description],
+        )?;
+    }
+    Ok(())
+}
+
+/// SQL condition matching a category and all of its descendants,
+/// bound to positional parameter `?{idx}`.
+pub fn subtree_condition(column: &str, idx: usize) -> String {
+    format!("({col} = ?{i} OR {col} LIKE ?{i} || '/%')", col = column, i = idx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_category_paths() {
+        assert_eq!(parse_category_path("data").unwrap(), "data");
+        assert_eq!(parse_category_path(" Data/Weather/ ").unwrap(), "data/weather");
+        assert_eq!(parse_category_path("data/weather/marine").unwrap(), "data/weather/marine");
+        assert!(parse_category_path("nope/weather").is_err());
+        assert!(parse_category_path("data//weather").is_err());
+        assert!(parse_category_path("data/we ather").is_err());
+        assert!(parse_category_path("data/a/b/c").is_err());
+    }
+
+    #[test]
+    fn finds_parent() {
+        assert_eq!(parent_of("data/weather/marine"), Some("data/weather"));
+        assert_eq!(parent_of("data"), None);
+    }
+}
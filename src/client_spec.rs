@@ -0,0 +1,276 @@
+use std::collections::BTreeSet;
+use std::sync::LazyLock;
+
+use serde_json::{json, Map, Value};
+
+use crate::errors::ErrorCode;
+
+/// The hand-maintained OpenAPI document, served as `/api/v1/openapi.json`
+/// and the source of the compact client description.
+pub const OPENAPI_JSON: &str = include_str!("../openapi.json");
+
+/// Prefix of every API path in the OpenAPI document.
+pub const BASE_PATH: &str = "/api/v1";
+
+const METHODS: &[&str] = &["get", "put", "post", "delete", "patch", "head", "options"];
+
+static CLIENT_SPEC: LazyLock<Value> = LazyLock::new(|| {
+    let doc: Value = serde_json::from_str(OPENAPI_JSON).expect("openapi.json is valid JSON");
+    client_spec(&doc)
+});
+
+/// The client description of this API, built once from [`OPENAPI_JSON`].
+pub fn get() -> &'static Value {
+    &CLIENT_SPEC
+}
+
+/// Follow a local `$ref` (`#/components/...`) to what it points at.
+/// Anything else is returned as is.
+pub fn resolve<'a>(doc: &'a Value, value: &'a Value) -> &'a Value {
+    let mut value = value;
+    // Bounded so a reference cycle can't spin forever
+    for _ in 0..8 {
+        match value.get("$ref").and_then(Value::as_str) {
+            Some(r) if r.starts_with("#/") => match doc.pointer(&r[1..]) {
+                Some(target) => value = target,
+                None => break,
+            },
+            _ => break,
+        }
+    }
+    value
+}
+
+/// `none`, `optional` (anonymous or with a key) or `api_key`, from the
+/// operation's `security` requirements.
+fn auth_mode(op: &Value) -> &'static str {
+    match op.get("security").and_then(Value::as_array) {
+        Some(reqs) if !reqs.is_empty() => {
+            if reqs.iter().any(|r| r.as_object().is_some_and(Map::is_empty)) {
+                if reqs.len() == 1 {
+                    "none"
+                } else {
+                    "optional"
+                }
+            } else {
+                "api_key"
+            }
+        }
+        _ => "none",
+    }
+}
+
+/// Short type name for a schema: `string`, `integer`, `array<string>`, ...
+fn type_name(doc: &Value, schema: &Value) -> String {
+    let schema = resolve(doc, schema);
+    match schema.get("type").and_then(Value::as_str) {
+        Some("array") => match schema.get("items") {
+            Some(items) => format!("array<{}>", type_name(doc, items)),
+            None => "array".to_string(),
+        },
+        Some(t) => t.to_string(),
+        None if schema.get("properties").is_some() => "object".to_string(),
+        None => "any".to_string(),
+    }
+}
+
+fn param(doc: &Value, name: &str, location: &str, required: bool, schema: &Value, description: Option<&Value>) -> Value {
+    let schema = resolve(doc, schema);
+    let mut p = json!({
+        "name": name,
+        "in": location,
+        "type": type_name(doc, schema),
+        "required": required,
+    });
+    for key in ["enum", "default"] {
+        if let Some(v) = schema.get(key) {
+            p[key] = v.clone();
+        }
+    }
+    if let Some(d) = description.or_else(|| schema.get("description")) {
+        p["description"] = d.clone();
+    }
+    p
+}
+
+/// Path, query and header parameters, then the top-level fields of a JSON
+/// request body as `in: "body"` parameters.
+fn params(doc: &Value, path_item: &Value, op: &Value) -> Vec<Value> {
+    let declared = path_item
+        .get("parameters")
+        .and_then(Value::as_array)
+        .into_iter()
+        .chain(op.get("parameters").and_then(Value::as_array))
+        .flatten()
+        .map(|p| resolve(doc, p));
+    let mut out: Vec<Value> = declared
+        .filter_map(|p| {
+            let name = p.get("name")?.as_str()?;
+            let location = p.get("in")?.as_str()?;
+            let required = location == "path" || p.get("required").and_then(Value::as_bool).unwrap_or(false);
+            Some(param(doc, name, location, required, p.get("schema").unwrap_or(&Value::Null), p.get("description")))
+        })
+        .collect();
+
+    let body = op
+        .get("requestBody")
+        .map(|b| resolve(doc, b))
+        .and_then(|b| b.pointer("/content/application~1json/schema"))
+        .map(|s| resolve(doc, s));
+    if let Some(schema) = body {
+        let required: BTreeSet<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|r| r.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        match schema.get("properties").and_then(Value::as_object) {
+            Some(props) => out.extend(
+                props.iter().map(|(name, s)| param(doc, name, "body", required.contains(name.as_str()), s, None)),
+            ),
+            None => out.push(param(doc, "body", "body", true, schema, None)),
+        }
+    }
+    out
+}
+
+/// Error codes named in the operation's responses, grouped by status. A
+/// status whose description names no code gets the generic code for it.
+fn errors(op: &Value) -> Vec<Value> {
+    let Some(responses) = op.get("responses").and_then(Value::as_object) else {
+        return vec![];
+    };
+    let mut out: Vec<Value> = responses
+        .iter()
+        .filter_map(|(status, response)| {
+            let status: u16 = status.parse().ok().filter(|s| *s >= 400)?;
+            let description = response.get("description").and_then(Value::as_str).unwrap_or("");
+            let mut codes: Vec<&str> = ErrorCode::ALL
+                .iter()
+                .filter(|c| c.status().code == status && description.contains(&format!("`{}`", c.as_str())))
+                .map(|c| c.as_str())
+                .collect();
+            if codes.is_empty() {
+                codes.extend(generic_code(status).map(|c| c.as_str()));
+            }
+            Some(json!({ "status": status, "codes": codes }))
+        })
+        .collect();
+    out.sort_by_key(|e| e["status"].as_u64());
+    out
+}
+
+fn generic_code(status: u16) -> Option<ErrorCode> {
+    match status {
+        400 => Some(ErrorCode::BadRequest),
+        401 => Some(ErrorCode::Unauthorized),
+        403 => Some(ErrorCode::Forbidden),
+        404 => Some(ErrorCode::NotFound),
+        413 => Some(ErrorCode::PayloadTooLarge),
+        422 => Some(ErrorCode::UnprocessableEntity),
+        429 => Some(ErrorCode::RateLimited),
+        _ => None,
+    }
+}
+
+/// Compact description of every operation in an OpenAPI document: method,
+/// full path, parameters, auth mode and error codes, for generating client
+/// code or function-calling schemas without walking the whole spec.
+pub fn client_spec(doc: &Value) -> Value {
+    let mut endpoints = vec![];
+    for (path, item) in doc.get("paths").and_then(Value::as_object).into_iter().flatten() {
+        for (method, op) in item.as_object().into_iter().flatten() {
+            if !METHODS.contains(&method.as_str()) {
+                continue;
+            }
+            // Discovery documents are served at the site root, not under the API prefix
+            let root = path.starts_with("/.well-known/") || op.get("servers").is_some();
+            let mut endpoint = json!({
+                "name": op.get("operationId"),
+                "method": method.to_uppercase(),
+                "path": if root { path.clone() } else { format!("{BASE_PATH}{path}") },
+                "summary": op.get("summary"),
+                "auth": auth_mode(op),
+                "params": params(doc, item, op),
+                "errors": errors(op),
+            });
+            if let Some(tag) = op.pointer("/tags/0") {
+                endpoint["tag"] = tag.clone();
+            }
+            endpoints.push(endpoint);
+        }
+    }
+    json!({
+        "name": doc.pointer("/info/title"),
+        "version": doc.pointer("/info/version"),
+        "base_path": BASE_PATH,
+        "auth": {
+            "header": "X-API-Key",
+            "alternatives": ["Authorization: Bearer <key>"],
+            "modes": {
+                "none": "No credentials needed",
+                "optional": "Works anonymously; a key, session or edit token unlocks more",
+                "api_key": "Requires an API key",
+            },
+        },
+        "error_format": {
+            "error": "Machine-readable code, see GET /api/v1/errors",
+            "message": "Human-readable message",
+            "message_key": "Stable i18n key",
+        },
+        "common_errors": [
+            ErrorCode::RateLimited.as_str(),
+            ErrorCode::Maintenance.as_str(),
+            ErrorCode::InternalError.as_str(),
+        ],
+        "endpoints": endpoints,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_operations() {
+        let doc = json!({
+            "info": {"title": "T", "version": "1"},
+            "paths": {
+                "/things/{id}": {
+                    "parameters": [{"name": "id", "in": "path", "schema": {"type": "string"}}],
+                    "patch": {
+                        "operationId": "updateThing",
+                        "security": [{}, {"apiKey": []}],
+                        "requestBody": {"content": {"application/json": {"schema": {"$ref": "#/components/schemas/Thing"}}}},
+                        "responses": {
+                            "200": {"description": "ok"},
+                            "400": {"description": "Too long (`FIELD_TOO_LONG`)"},
+                            "404": {"description": "Missing"},
+                        },
+                    },
+                },
+                "/.well-known/thing.json": {"get": {"operationId": "wellKnown", "responses": {"200": {"description": "ok"}}}},
+            },
+            "components": {"schemas": {"Thing": {
+                "type": "object",
+                "required": ["name"],
+                "properties": {"name": {"type": "string"}, "tags": {"type": "array", "items": {"type": "string"}}},
+            }}},
+        });
+        let spec = client_spec(&doc);
+        let endpoints = spec["endpoints"].as_array().unwrap();
+        let update = endpoints.iter().find(|e| e["name"] == "updateThing").unwrap();
+        assert_eq!(update["path"], "/api/v1/things/{id}");
+        assert_eq!(update["auth"], "optional");
+        assert_eq!(update["params"][0]["required"], true);
+        assert_eq!(update["params"][1]["name"], "name");
+        assert_eq!(update["params"][1]["in"], "body");
+        assert_eq!(update["params"][2]["type"], "array<string>");
+        assert_eq!(update["errors"], json!([
+            {"status": 400, "codes": ["FIELD_TOO_LONG"]},
+            {"status": 404, "codes": ["NOT_FOUND"]},
+        ]));
+        let well_known = endpoints.iter().find(|e| e["name"] == "wellKnown").unwrap();
+        assert_eq!(well_known["path"], "/.well-known/thing.json");
+        assert_eq!(well_known["auth"], "none");
+    }
+}
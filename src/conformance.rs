@@ -0,0 +1,468 @@
+use std::time::Duration;
+
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use serde_json::{json, Value};
+
+use crate::auth::AuthenticatedKey;
+use crate::client_spec::resolve;
+use crate::errors::{ApiError, ErrorCode};
+use crate::DbState;
+
+/// HTTP timeout for the spec fetch and each probe request.
+const PROBE_TIMEOUT_SECS: u64 = 10;
+
+/// Specs and response bodies larger than this are not parsed.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Endpoints called per conformance check.
+const MAX_PROBES: usize = 2;
+
+/// Schema mismatches reported per probe.
+const MAX_SCHEMA_ERRORS: usize = 10;
+
+/// Schemas nested deeper than this are accepted without checking.
+const MAX_SCHEMA_DEPTH: usize = 16;
+
+/// One probed endpoint.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Probe {
+    pub method: &'static str,
+    /// Path template from the spec, e.g. `/pets/{id}`.
+    pub path: String,
+    pub url: String,
+    pub status_code: Option<u16>,
+    pub ok: bool,
+    pub errors: Vec<String>,
+}
+
+/// Outcome of a conformance check: `passed` when every probe returned a
+/// declared 2xx response matching its schema, `failed` when one didn't, and
+/// `untestable` when the spec couldn't be read or has no endpoint that is
+/// safe to call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub status: &'static str,
+    pub probes: Vec<Probe>,
+    pub errors: Vec<String>,
+}
+
+impl Report {
+    fn untestable(message: String) -> Self {
+        Report {
+            status: "untestable",
+            probes: Vec::new(),
+            errors: vec![message],
+        }
+    }
+}
+
+/// A GET operation that can be called without credentials, with its path
+/// filled in from declared examples, defaults or enums.
+#[derive(Debug, Clone, PartialEq)]
+struct Candidate<'a> {
+    path: &'a str,
+    target: String,
+    responses: &'a Value,
+}
+
+/// Fetch `spec_url` and call up to two of its GET endpoints, checking each
+/// response against the spec.
+pub async fn run(client: &reqwest::Client, spec_url: &str, api_url: Option<&str>) -> Report {
+    let raw = match fetch(client, spec_url).await {
+        Ok((200..=299, body)) => body,
+        Ok((status, _)) => return Report::untestable(format!("Spec fetch answered HTTP {status}")),
+        Err(e) => return Report::untestable(format!("Spec unreachable: {e}")),
+    };
+    let doc: Value = match serde_json::from_str(&raw).or_else(|_| serde_yaml::from_str(&raw)) {
+        Ok(doc) => doc,
+        Err(_) => return Report::untestable("Spec is not valid JSON or YAML".to_string()),
+    };
+    let Some(base) = base_url(&doc, spec_url, api_url) else {
+        return Report::untestable("No server URL to probe".to_string());
+    };
+    let candidates = candidates(&doc);
+    if candidates.is_empty() {
+        return Report::untestable("No GET endpoint can be called without credentials or unknown parameters".to_string());
+    }
+
+    let mut probes = Vec::new();
+    for candidate in candidates.into_iter().take(MAX_PROBES) {
+        let url = format!("{}{}", base.trim_end_matches('/'), candidate.target);
+        let mut probe = Probe {
+            method: "GET",
+            path: candidate.path.to_string(),
+            url: url.clone(),
+            status_code: None,
+            ok: false,
+            errors: Vec::new(),
+        };
+        match fetch(client, &url).await {
+            Ok((status, body)) => {
+                probe.status_code = Some(status);
+                probe.errors = check_response(&doc, candidate.responses, status, &body);
+                probe.ok = probe.errors.is_empty();
+            }
+            Err(e) => probe.errors.push(format!("Request failed: {e}")),
+        }
+        probes.push(probe);
+    }
+    Report {
+        status: if probes.iter().all(|p| p.ok) { "passed" } else { "failed" },
+        probes,
+        errors: Vec::new(),
+    }
+}
+
+/// GET `url`, returning the status and (size-capped) body.
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<(u16, String), String> {
+    let resp = client
+        .get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| if e.is_timeout() { format!("timed out ({PROBE_TIMEOUT_SECS}s)") } else { e.to_string() })?;
+    let status = resp.status().as_u16();
+    let body = crate::health::read_body_limited(resp, MAX_BODY_BYTES)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("body exceeds {MAX_BODY_BYTES} bytes"))?;
+    Ok((status, String::from_utf8_lossy(&body).into_owned()))
+}
+
+/// The spec's first server (variables set to their defaults, relative URLs
+/// resolved against the spec), else the app's `api_url`, else the spec's
+/// origin.
+fn base_url(doc: &Value, spec_url: &str, api_url: Option<&str>) -> Option<String> {
+    let spec = reqwest::Url::parse(spec_url).ok()?;
+    if let Some(server) = doc.pointer("/servers/0") {
+        let mut url = server.get("url").and_then(Value::as_str).unwrap_or("/").to_string();
+        for (name, var) in server.get("variables").and_then(Value::as_object).into_iter().flatten() {
+            let default = var.get("default").and_then(Value::as_str).unwrap_or_default();
+            url = url.replace(&format!("{{{name}}}"), default);
+        }
+        return spec.join(&url).ok().map(String::from);
+    }
+    match api_url {
+        Some(api) => Some(api.to_string()),
+        None => spec.join("/").ok().map(String::from),
+    }
+}
+
+/// GET operations that need no credentials and whose required parameters
+/// all have a declared value, those with fewer parameters first.
+fn candidates(doc: &Value) -> Vec<Candidate<'_>> {
+    let global_security = doc.get("security");
+    let mut found: Vec<(usize, Candidate)> = Vec::new();
+    for (path, item) in doc.get("paths").and_then(Value::as_object).into_iter().flatten() {
+        let Some(op) = item.get("get") else { continue };
+        if !is_public(op.get("security").or(global_security)) {
+            continue;
+        }
+        let Some(responses) = op.get("responses") else { continue };
+        let params = item
+            .get("parameters")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .chain(op.get("parameters").and_then(Value::as_array).into_iter().flatten())
+            .map(|p| resolve(doc, p));
+        let mut target = path.clone();
+        let mut query = Vec::new();
+        let mut usable = true;
+        for param in params {
+            let (Some(name), Some(location)) = (
+                param.get("name").and_then(Value::as_str),
+                param.get("in").and_then(Value::as_str),
+            ) else {
+                continue;
+            };
+            let required = location == "path" || param.get("required").and_then(Value::as_bool).unwrap_or(false);
+            if !required {
+                continue;
+            }
+            match (location, safe_value(doc, param)) {
+                ("path", Some(value)) => target = target.replace(&format!("{{{name}}}"), &value),
+                ("query", Some(value)) => query.push(format!("{name}={value}")),
+                _ => usable = false,
+            }
+        }
+        if !usable || target.contains('{') {
+            continue;
+        }
+        let weight = query.len() + path.matches('{').count();
+        if !query.is_empty() {
+            target = format!("{target}?{}", query.join("&"));
+        }
+        found.push((weight, Candidate { path, target, responses }));
+    }
+    found.sort_by_key(|(weight, _)| *weight);
+    found.into_iter().map(|(_, c)| c).collect()
+}
+
+/// Whether a security requirement list allows anonymous calls: absent,
+/// empty, or containing an empty requirement.
+fn is_public(security: Option<&Value>) -> bool {
+    match security.and_then(Value::as_array) {
+        None => true,
+        Some(reqs) => reqs.is_empty() || reqs.iter().any(|r| r.as_object().is_some_and(|o| o.is_empty())),
+    }
+}
+
+/// A URL-safe value the spec declares for a parameter: its example, the
+/// schema's example or default, or the first enum value.
+fn safe_value(doc: &Value, param: &Value) -> Option<String> {
+    let schema = param.get("schema").map(|s| resolve(doc, s));
+    let value = param
+        .get("example")
+        .or_else(|| schema?.get("example"))
+        .or_else(|| schema?.get("default"))
+        .or_else(|| schema?.get("enum")?.get(0))?;
+    let text = match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => return None,
+    };
+    text.chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-._~".contains(c))
+        .then_some(text)
+        .filter(|t| !t.is_empty())
+}
+
+/// Problems with a response: an undeclared or non-2xx status, or a JSON
+/// body that doesn't match the declared schema.
+fn check_response(doc: &Value, responses: &Value, status: u16, body: &str) -> Vec<String> {
+    let code = status.to_string();
+    let declared = responses
+        .get(&code)
+        .or_else(|| responses.get(format!("{}XX", &code[..1])))
+        .or_else(|| responses.get("default"));
+    let Some(declared) = declared.map(|d| resolve(doc, d)) else {
+        return vec![format!("Status {status} is not declared")];
+    };
+    if !(200..300).contains(&status) {
+        return vec![format!("Expected a 2xx response, got {status}")];
+    }
+    let schema = declared.get("content").and_then(Value::as_object).and_then(|content| {
+        content
+            .iter()
+            .find(|(media, _)| media.starts_with("application/json") || media.ends_with("+json"))
+            .and_then(|(_, m)| m.get("schema"))
+    });
+    let Some(schema) = schema else { return Vec::new() };
+    let value: Value = match serde_json::from_str(body) {
+        Ok(v) => v,
+        Err(_) => return vec!["Response body is not valid JSON".to_string()],
+    };
+    let mut errors = Vec::new();
+    validate(doc, schema, &value, "$", 0, &mut errors);
+    errors.truncate(MAX_SCHEMA_ERRORS);
+    errors
+}
+
+/// Check `value` against an OpenAPI schema, covering `type` (and
+/// `nullable`), `enum`, `required`, `properties`, `items`, `allOf`, `anyOf`
+/// and `oneOf`. Other keywords are ignored.
+pub fn validate(doc: &Value, schema: &Value, value: &Value, at: &str, depth: usize, errors: &mut Vec<String>) {
+    if depth > MAX_SCHEMA_DEPTH || errors.len() >= MAX_SCHEMA_ERRORS {
+        return;
+    }
+    let schema = resolve(doc, schema);
+    if value.is_null() && schema.get("nullable").and_then(Value::as_bool) == Some(true) {
+        return;
+    }
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            errors.push(format!("{at}: expected {}, got {}", types.join(" or "), type_name(value)));
+            return;
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            errors.push(format!("{at}: value is not one of the declared enum values"));
+        }
+    }
+    for sub in schema.get("allOf").and_then(Value::as_array).into_iter().flatten() {
+        validate(doc, sub, value, at, depth + 1, errors);
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        let Some(options) = schema.get(keyword).and_then(Value::as_array) else { continue };
+        let matches = options.iter().any(|sub| {
+            let mut sub_errors = Vec::new();
+            validate(doc, sub, value, at, depth + 1, &mut sub_errors);
+            sub_errors.is_empty()
+        });
+        if !matches && !options.is_empty() {
+            errors.push(format!("{at}: matches none of the {keyword} schemas"));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        for name in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if !object.contains_key(name) {
+                errors.push(format!("{at}: missing required property '{name}'"));
+            }
+        }
+        for (name, sub) in schema.get("properties").and_then(Value::as_object).into_iter().flatten() {
+            if let Some(v) = object.get(name) {
+                validate(doc, sub, v, &format!("{at}.{name}"), depth + 1, errors);
+            }
+        }
+    }
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (i, v) in array.iter().enumerate() {
+            validate(doc, items, v, &format!("{at}[{i}]"), depth + 1, errors);
+        }
+    }
+}
+
+fn has_type(value: &Value, t: &str) -> bool {
+    match t {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|f| f.fract() == 0.0),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Store a check's outcome, replacing the previous one.
+fn record(conn: &rusqlite::Connection, app_id: &str, spec_url: &str, report: &Report) {
+    let probes = serde_json::to_string(&report.probes).unwrap_or_else(|_| "[]".to_string());
+    let errors = serde_json::to_string(&report.errors).unwrap_or_else(|_| "[]".to_string());
+    let _ = conn.execute(
+        "INSERT INTO app_conformance (app_id, spec_url, status, probes, errors, checked_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))
+         ON CONFLICT(app_id) DO UPDATE SET
+           spec_url = excluded.spec_url, status = excluded.status, probes = excluded.probes,
+           errors = excluded.errors, checked_at = excluded.checked_at",
+        rusqlite::params![app_id, spec_url, report.status, probes, errors],
+    );
+}
+
+/// The last conformance check of an app, if it has been checked.
+pub fn conformance_details(conn: &rusqlite::Connection, app_id: &str) -> Option<Value> {
+    conn.query_row(
+        "SELECT spec_url, status, probes, errors, checked_at FROM app_conformance WHERE app_id = ?1",
+        rusqlite::params![app_id],
+        |row| {
+            let probes: Value = serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or(json!([]));
+            let errors: Vec<String> = serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default();
+            Ok(json!({
+                "spec_url": row.get::<_, String>(0)?,
+                "status": row.get::<_, String>(1)?,
+                "probes": probes,
+                "errors": errors,
+                "checked_at": row.get::<_, String>(4)?,
+            }))
+        },
+    )
+    .ok()
+}
+
+/// Run a protocol conformance check on an app's API. Admin only.
+/// Fetches the app's `api_spec_url`, calls up to two GET endpoints that need
+/// no credentials, and records whether the responses match the spec.
+#[post("/apps/<app_id>/conformance-check")]
+pub async fn check_app_conformance(
+    key: AuthenticatedKey,
+    app_id: &str,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::new(ErrorCode::AdminRequired, "Only admins can trigger conformance checks").into();
+    }
+
+    let app_info = db.conn().query_row(
+        "SELECT id, api_spec_url, api_url FROM apps WHERE id = ?1 OR slug = ?1",
+        rusqlite::params![app_id],
+        |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, Option<String>>(2)?)),
+    );
+    let (id, spec_url, api_url) = match app_info {
+        Ok((id, Some(spec_url), api_url)) => (id, spec_url, api_url),
+        Ok(_) => return ApiError::new(ErrorCode::NoUrl, "App has no api_spec_url to check").into(),
+        Err(_) => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(PROBE_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .build()
+        .unwrap_or_default();
+    let report = run(&client, &spec_url, api_url.as_deref()).await;
+
+    let conn = db.conn();
+    record(&conn, &id, &spec_url, &report);
+    let mut details = conformance_details(&conn, &id).unwrap_or(Value::Null);
+    details["app_id"] = json!(id);
+    (Status::Ok, Json(details))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_public_gets_with_known_parameters() {
+        let doc = json!({
+            "security": [{ "key": [] }],
+            "paths": {
+                "/pets/{id}": { "get": {
+                    "security": [],
+                    "parameters": [{ "name": "id", "in": "path", "schema": { "type": "integer", "example": 7 } }],
+                    "responses": { "200": {} },
+                }},
+                "/health": { "get": { "security": [{}], "responses": { "200": {} } } },
+                "/private": { "get": { "responses": { "200": {} } } },
+                "/search": { "get": {
+                    "security": [],
+                    "parameters": [{ "name": "q", "in": "query", "required": true, "schema": { "type": "string" } }],
+                    "responses": { "200": {} },
+                }},
+            },
+        });
+        let targets: Vec<String> = candidates(&doc).into_iter().map(|c| c.target).collect();
+        assert_eq!(targets, ["/health", "/pets/7"]);
+    }
+
+    #[test]
+    fn validates_responses_against_schemas() {
+        let doc = json!({ "components": { "schemas": { "Pet": {
+            "type": "object",
+            "required": ["id", "name"],
+            "properties": { "id": { "type": "integer" }, "tag": { "type": "string", "nullable": true } },
+        }}}});
+        let responses = json!({ "200": { "content": { "application/json": { "schema": {
+            "type": "array", "items": { "$ref": "#/components/schemas/Pet" },
+        }}}}});
+
+        assert!(check_response(&doc, &responses, 200, r#"[{"id":1,"name":"Rex","tag":null}]"#).is_empty());
+        assert_eq!(
+            check_response(&doc, &responses, 200, r#"[{"id":"1","name":"Rex"},{"id":2}]"#),
+            ["$[0].id: expected integer, got string", "$[1]: missing required property 'name'"]
+        );
+        assert_eq!(check_response(&doc, &responses, 404, "{}"), ["Status 404 is not declared"]);
+        assert_eq!(check_response(&doc, &responses, 200, "<html>"), ["Response body is not valid JSON"]);
+    }
+}
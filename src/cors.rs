@@ -0,0 +1,212 @@
+use regex::Regex;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{Header, Method, Status};
+use rocket::{Request, Response};
+
+/// Request headers browsers may send when `CORS_ALLOWED_HEADERS` is unset.
+pub const DEFAULT_ALLOWED_HEADERS: &[&str] = &["Content-Type", "Authorization", "X-API-Key", "X-Session-Token", "If-Match"];
+
+const ALLOWED_METHODS: &str = "GET, POST, PUT, PATCH, DELETE, OPTIONS";
+
+const EXPOSED_HEADERS: &str = "ETag";
+
+/// Origins allowed to read responses.
+#[derive(Debug, Clone)]
+pub enum AllowedOrigins {
+    /// Any origin (`*`).
+    Any,
+    /// Exact origins, plus patterns from `*` wildcards and `CORS_ALLOWED_ORIGIN_REGEX`,
+    /// each matched against the whole `Origin` header.
+    List { exact: Vec<String>, patterns: Vec<Regex> },
+}
+
+impl AllowedOrigins {
+    /// Parse a comma-separated list. `*` on its own allows any origin; an
+    /// entry with a `*` inside matches one or more host characters there,
+    /// so `https://*.example.com` covers every subdomain and
+    /// `http://localhost:*` every port.
+    pub fn parse(list: &str) -> Self {
+        let entries: Vec<&str> = list
+            .split(',')
+            .map(|o| o.trim().trim_end_matches('/'))
+            .filter(|o| !o.is_empty())
+            .collect();
+        if entries.is_empty() || entries.contains(&"*") {
+            return AllowedOrigins::Any;
+        }
+        let (wildcards, exact): (Vec<&str>, Vec<&str>) = entries.into_iter().partition(|o| o.contains('*'));
+        let patterns = wildcards
+            .into_iter()
+            .filter_map(|o| {
+                let parts: Vec<String> = o.split('*').map(regex::escape).collect();
+                Regex::new(&format!("^{}$", parts.join("[A-Za-z0-9.-]+"))).ok()
+            })
+            .collect();
+        AllowedOrigins::List {
+            exact: exact.into_iter().map(str::to_string).collect(),
+            patterns,
+        }
+    }
+
+    fn allows(&self, origin: &str) -> bool {
+        match self {
+            AllowedOrigins::Any => true,
+            AllowedOrigins::List { exact, patterns } => {
+                exact.iter().any(|o| o == origin) || patterns.iter().any(|p| p.is_match(origin))
+            }
+        }
+    }
+}
+
+/// CORS headers for every response. With `AllowedOrigins::Any` and no
+/// credentials the origin is `*`; otherwise the request's `Origin` is
+/// echoed back when allowed, and responses carry `Vary: Origin`.
+#[derive(Debug, Clone)]
+pub struct Cors {
+    pub origins: AllowedOrigins,
+    pub allowed_headers: Vec<String>,
+    /// `Access-Control-Max-Age` on preflight responses; `None` leaves it to the browser.
+    pub max_age_secs: Option<u64>,
+    /// Send `Access-Control-Allow-Credentials: true`.
+    pub allow_credentials: bool,
+}
+
+impl Default for Cors {
+    fn default() -> Self {
+        Cors {
+            origins: AllowedOrigins::Any,
+            allowed_headers: DEFAULT_ALLOWED_HEADERS.iter().map(|h| h.to_string()).collect(),
+            max_age_secs: None,
+            allow_credentials: false,
+        }
+    }
+}
+
+impl Cors {
+    /// Only `origins` (exact or with `*` wildcards), other settings default.
+    pub fn allow_origins(origins: &[&str]) -> Self {
+        Cors {
+            origins: AllowedOrigins::parse(&origins.join(",")),
+            ..Cors::default()
+        }
+    }
+
+    /// `CORS_ALLOWED_ORIGINS` (comma-separated, default `*`),
+    /// `CORS_ALLOWED_ORIGIN_REGEX`, `CORS_ALLOWED_HEADERS` (comma-separated),
+    /// `CORS_MAX_AGE_SECS` and `CORS_ALLOW_CREDENTIALS`.
+    pub fn from_env() -> Self {
+        let d = Cors::default();
+        let mut origins = std::env::var("CORS_ALLOWED_ORIGINS")
+            .map(|v| AllowedOrigins::parse(&v))
+            .unwrap_or(AllowedOrigins::Any);
+        if let Ok(pattern) = std::env::var("CORS_ALLOWED_ORIGIN_REGEX") {
+            match Regex::new(&format!("^(?:{})$", pattern.trim())) {
+                Ok(regex) => {
+                    // A regex narrows an otherwise open policy to just its matches
+                    if let AllowedOrigins::Any = origins {
+                        origins = AllowedOrigins::List { exact: vec![], patterns: vec![] };
+                    }
+                    if let AllowedOrigins::List { patterns, .. } = &mut origins {
+                        patterns.push(regex);
+                    }
+                }
+                Err(e) => eprintln!("⚠️  Ignoring invalid CORS_ALLOWED_ORIGIN_REGEX: {e}"),
+            }
+        }
+        let allowed_headers = std::env::var("CORS_ALLOWED_HEADERS")
+            .ok()
+            .map(|v| v.split(',').map(|h| h.trim().to_string()).filter(|h| !h.is_empty()).collect::<Vec<_>>())
+            .filter(|headers| !headers.is_empty())
+            .unwrap_or(d.allowed_headers);
+        Cors {
+            origins,
+            allowed_headers,
+            max_age_secs: std::env::var("CORS_MAX_AGE_SECS").ok().and_then(|v| v.parse().ok()),
+            allow_credentials: matches!(std::env::var("CORS_ALLOW_CREDENTIALS").as_deref(), Ok("1") | Ok("true")),
+        }
+    }
+
+    /// `Access-Control-Allow-Origin` for a request from `origin`, if any.
+    fn allow_origin(&self, origin: Option<&str>) -> Option<String> {
+        match (&self.origins, origin) {
+            // Browsers reject `*` on credentialed requests, so echo the origin instead
+            (AllowedOrigins::Any, _) if !self.allow_credentials => Some("*".to_string()),
+            (_, Some(origin)) if self.origins.allows(origin) => Some(origin.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Whether the response depends on the request's `Origin`.
+    fn varies_by_origin(&self) -> bool {
+        self.allow_credentials || matches!(self.origins, AllowedOrigins::List { .. })
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let origin = request.headers().get_one("Origin");
+        let allowed = self.allow_origin(origin);
+        if let Some(ref value) = allowed {
+            response.set_header(Header::new("Access-Control-Allow-Origin", value.clone()));
+            if self.allow_credentials {
+                response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
+            }
+        }
+        if self.varies_by_origin() {
+            let vary = match response.headers().get_one("Vary") {
+                Some(v) if v.split(',').any(|h| h.trim().eq_ignore_ascii_case("Origin")) => v.to_string(),
+                Some(v) => format!("{}, Origin", v),
+                None => "Origin".to_string(),
+            };
+            response.set_header(Header::new("Vary", vary));
+        }
+        response.set_header(Header::new("Access-Control-Allow-Methods", ALLOWED_METHODS));
+        response.set_header(Header::new("Access-Control-Expose-Headers", EXPOSED_HEADERS));
+        response.set_header(Header::new("Access-Control-Allow-Headers", self.allowed_headers.join(", ")));
+
+        if request.method() == Method::Options {
+            if let (Some(secs), Some(_)) = (self.max_age_secs, &allowed) {
+                response.set_header(Header::new("Access-Control-Max-Age", secs.to_string()));
+            }
+            response.set_status(Status::NoContent);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_exact_and_wildcard_origins() {
+        let origins = AllowedOrigins::parse("https://dash.example.com/, https://*.example.org, http://localhost:*");
+        assert!(origins.allows("https://dash.example.com"));
+        assert!(origins.allows("https://a.b.example.org"));
+        assert!(origins.allows("http://localhost:5173"));
+        assert!(!origins.allows("https://example.org"));
+        assert!(!origins.allows("https://evil.com/.example.org"));
+        assert!(!origins.allows("https://a.example.org.evil.com"));
+        assert!(!origins.allows("http://dash.example.com"));
+        assert!(matches!(AllowedOrigins::parse("https://a.com, *"), AllowedOrigins::Any));
+    }
+
+    #[test]
+    fn credentials_echo_the_origin() {
+        let open = Cors::default();
+        assert_eq!(open.allow_origin(Some("https://x.dev")).as_deref(), Some("*"));
+        assert!(!open.varies_by_origin());
+
+        let credentialed = Cors { allow_credentials: true, ..Cors::default() };
+        assert_eq!(credentialed.allow_origin(Some("https://x.dev")).as_deref(), Some("https://x.dev"));
+        assert_eq!(credentialed.allow_origin(None), None);
+        assert!(credentialed.varies_by_origin());
+    }
+}
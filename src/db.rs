@@ -1,10 +1,20 @@
+use std::time::{Duration, Instant};
+
 use rusqlite::Connection;
 
+pub mod seed;
+
+/// How long a connection waits on a lock held by another connection
+/// (scheduler, webhooks, background workers) before failing with SQLITE_BUSY.
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub fn init_db(path: &str) -> Connection {
     let conn = Connection::open(path).expect("Failed to open database");
 
-    conn.execute_batch("PRAGMA journal_mode=WAL;")
+    // WAL lets readers proceed while a writer commits; NORMAL sync is safe under WAL
+    conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")
         .expect("Failed to set WAL mode");
+    conn.busy_timeout(BUSY_TIMEOUT).expect("Failed to set busy timeout");
 
     conn.execute_batch(
         "
@@ -233,6 +243,30 @@ pub fn init_db(path: &str) -> Connection {
         println!("✓ Migration complete");
     }
 
+    // Migration: owner-set success criteria for health probes (JSON)
+    let has_health_expectation: bool = conn.prepare("SELECT health_expectation FROM apps LIMIT 0").is_ok();
+    if !has_health_expectation {
+        conn.execute("ALTER TABLE apps ADD COLUMN health_expectation TEXT", [])
+            .expect("Failed to add health_expectation column");
+    }
+
+    // Migration: owner-pinned key endpoints (JSON array of {method, path, description})
+    let has_key_endpoints: bool = conn.prepare("SELECT key_endpoints FROM apps LIMIT 0").is_ok();
+    if !has_key_endpoints {
+        conn.execute("ALTER TABLE apps ADD COLUMN key_endpoints TEXT", [])
+            .expect("Failed to add key_endpoints column");
+    }
+
+    // Migration: cached logo state (logo_source is the logo_url the status belongs to)
+    let has_logo_status: bool = conn.prepare("SELECT logo_status FROM apps LIMIT 0").is_ok();
+    if !has_logo_status {
+        conn.execute_batch(
+            "ALTER TABLE apps ADD COLUMN logo_status TEXT;
+             ALTER TABLE apps ADD COLUMN logo_source TEXT;",
+        )
+        .expect("Failed to add logo columns");
+    }
+
     // Migration: fix reviews table — remove broken FK on reviewer_key_id,
     // make nullable for anonymous reviews, add reviewer_name field
     let has_reviewer_name: bool = conn
@@ -271,5 +305,702 @@ pub fn init_db(path: &str) -> Connection {
         println!("✓ Reviews migration complete");
     }
 
+    // Migration: reviewer fingerprint (hashed IP + User-Agent) for anonymous review dedup
+    let has_fingerprint: bool = conn
+        .prepare("SELECT reviewer_fingerprint FROM reviews LIMIT 0")
+        .is_ok();
+    if !has_fingerprint {
+        conn.execute_batch(
+            "ALTER TABLE reviews ADD COLUMN reviewer_fingerprint TEXT;",
+        )
+        .expect("Failed to add reviewer_fingerprint column");
+    }
+    conn.execute_batch(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_reviews_unique_fingerprint
+            ON reviews(app_id, reviewer_fingerprint)
+            WHERE reviewer_key_id IS NULL AND reviewer_fingerprint IS NOT NULL;",
+    )
+    .expect("Failed to create reviewer fingerprint index");
+
+    // Migration: API spec verification status + extracted spec metadata
+    let has_spec_status: bool = conn.prepare("SELECT spec_status FROM apps LIMIT 0").is_ok();
+    if !has_spec_status {
+        conn.execute_batch("ALTER TABLE apps ADD COLUMN spec_status TEXT;")
+            .expect("Failed to add spec_status column");
+    }
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS app_specs (
+            app_id TEXT PRIMARY KEY,
+            spec_url TEXT NOT NULL,
+            status TEXT NOT NULL,
+            openapi_version TEXT,
+            title TEXT,
+            version TEXT,
+            servers TEXT NOT NULL DEFAULT '[]',
+            errors TEXT NOT NULL DEFAULT '[]',
+            fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (app_id) REFERENCES apps(id)
+        );",
+    )
+    .expect("Failed to create app_specs table");
+
+    // Migration: endpoint and auth summaries extracted from verified specs
+    let has_spec_endpoints: bool = conn.prepare("SELECT endpoints FROM app_specs LIMIT 0").is_ok();
+    if !has_spec_endpoints {
+        conn.execute_batch(
+            "ALTER TABLE app_specs ADD COLUMN endpoints TEXT NOT NULL DEFAULT '[]';
+             ALTER TABLE app_specs ADD COLUMN auth TEXT NOT NULL DEFAULT '[]';",
+        )
+        .expect("Failed to add app_specs summary columns");
+    }
+
+    // Migration: function-calling tool schemas generated from verified specs
+    let has_spec_tools: bool = conn.prepare("SELECT tools FROM app_specs LIMIT 0").is_ok();
+    if !has_spec_tools {
+        conn.execute_batch("ALTER TABLE app_specs ADD COLUMN tools TEXT NOT NULL DEFAULT '[]';")
+            .expect("Failed to add app_specs tools column");
+    }
+
+    // Migration: optional API key expiry + tracking of the pre-expiry warning
+    let has_expires_at: bool = conn.prepare("SELECT expires_at FROM api_keys LIMIT 0").is_ok();
+    if !has_expires_at {
+        conn.execute_batch(
+            "ALTER TABLE api_keys ADD COLUMN expires_at TEXT;
+             ALTER TABLE api_keys ADD COLUMN expiry_warned_at TEXT;",
+        )
+        .expect("Failed to add api key expiry columns");
+    }
+
+    // Migration: archived status metadata (status to restore on unarchive)
+    let has_archived_at: bool = conn.prepare("SELECT archived_at FROM apps LIMIT 0").is_ok();
+    if !has_archived_at {
+        conn.execute_batch(
+            "ALTER TABLE apps ADD COLUMN archived_at TEXT;
+             ALTER TABLE apps ADD COLUMN archived_by TEXT;
+             ALTER TABLE apps ADD COLUMN archived_from TEXT;",
+        )
+        .expect("Failed to add archive columns");
+    }
+
+    // Migration: revision counter for optimistic concurrency (If-Match / ETag)
+    let has_revision: bool = conn.prepare("SELECT revision FROM apps LIMIT 0").is_ok();
+    if !has_revision {
+        conn.execute_batch("ALTER TABLE apps ADD COLUMN revision INTEGER NOT NULL DEFAULT 1;")
+            .expect("Failed to add revision column");
+    }
+
+    // Listing snapshots per revision, for history, diffs, and rollback
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS app_revisions (
+            app_id TEXT NOT NULL,
+            revision INTEGER NOT NULL,
+            snapshot TEXT NOT NULL,
+            editor_kind TEXT NOT NULL,
+            editor_id TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (app_id, revision),
+            FOREIGN KEY (app_id) REFERENCES apps(id)
+        );",
+    )
+    .expect("Failed to create app_revisions table");
+
+    // Migration: private author contact for email notifications
+    let has_author_email: bool = conn.prepare("SELECT author_email FROM apps LIMIT 0").is_ok();
+    if !has_author_email {
+        conn.execute_batch("ALTER TABLE apps ADD COLUMN author_email TEXT;")
+            .expect("Failed to add author_email column");
+    }
+
+    // Background jobs (queued notification emails), retried with backoff
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS jobs (
+            id TEXT PRIMARY KEY,
+            kind TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            run_after TEXT NOT NULL DEFAULT (datetime('now')),
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            completed_at TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_jobs_due ON jobs(status, run_after);",
+    )
+    .expect("Failed to create jobs table");
+
+    // Admin-defined badges and their assignment to apps
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS badges (
+            slug TEXT PRIMARY KEY,
+            label TEXT NOT NULL,
+            color TEXT NOT NULL,
+            description TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE TABLE IF NOT EXISTS app_badges (
+            app_id TEXT NOT NULL,
+            badge_slug TEXT NOT NULL,
+            awarded_by TEXT,
+            awarded_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (app_id, badge_slug),
+            FOREIGN KEY (app_id) REFERENCES apps(id),
+            FOREIGN KEY (badge_slug) REFERENCES badges(slug)
+        );
+        CREATE INDEX IF NOT EXISTS idx_app_badges_badge ON app_badges(badge_slug);",
+    )
+    .expect("Failed to create badges tables");
+
+    // Per-language overrides for listing text (default language lives on apps)
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS app_translations (
+            app_id TEXT NOT NULL,
+            lang TEXT NOT NULL,
+            name TEXT,
+            short_description TEXT,
+            description TEXT,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (app_id, lang),
+            FOREIGN KEY (app_id) REFERENCES apps(id)
+        );",
+    )
+    .expect("Failed to create app_translations table");
+
+    // Lightweight accounts: email magic-link login codes and session tokens
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS accounts (
+            id TEXT PRIMARY KEY,
+            email TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            last_login_at TEXT
+        );
+        CREATE TABLE IF NOT EXISTS login_codes (
+            email TEXT PRIMARY KEY,
+            code_hash TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            expires_at TEXT NOT NULL,
+            issued INTEGER NOT NULL DEFAULT 1,
+            window_start TEXT
+        );
+        CREATE TABLE IF NOT EXISTS account_sessions (
+            id TEXT PRIMARY KEY,
+            account_id TEXT NOT NULL,
+            token_hash TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            expires_at TEXT NOT NULL,
+            FOREIGN KEY (account_id) REFERENCES accounts(id)
+        );",
+    )
+    .expect("Failed to create account tables");
+    let has_account_id: bool = conn.prepare("SELECT account_id FROM apps LIMIT 0").is_ok();
+    if !has_account_id {
+        conn.execute_batch(
+            "ALTER TABLE apps ADD COLUMN account_id TEXT;
+             CREATE INDEX IF NOT EXISTS idx_apps_account ON apps(account_id);",
+        )
+        .expect("Failed to add account_id column");
+    }
+
+    // Migration: login codes issued per email per window (NULL window_start = no open window)
+    let has_code_window: bool = conn.prepare("SELECT window_start FROM login_codes LIMIT 0").is_ok();
+    if !has_code_window {
+        conn.execute_batch(
+            "ALTER TABLE login_codes ADD COLUMN issued INTEGER NOT NULL DEFAULT 1;
+             ALTER TABLE login_codes ADD COLUMN window_start TEXT;",
+        )
+        .expect("Failed to add login code window columns");
+    }
+
+    // Rate limit windows, snapshotted so quotas survive restarts
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS rate_limits (
+            scope TEXT NOT NULL,
+            bucket_key TEXT NOT NULL,
+            window_start INTEGER NOT NULL,
+            count INTEGER NOT NULL,
+            PRIMARY KEY (scope, bucket_key)
+        );",
+    )
+    .expect("Failed to create rate_limits table");
+
+    // Category tree: ids are full paths ("data/weather"), top-level rows mirror VALID_CATEGORIES
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS categories (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            parent_id TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (parent_id) REFERENCES categories(id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_categories_parent ON categories(parent_id);",
+    )
+    .expect("Failed to create categories table");
+    // Migration: category presentation (icon, description); customized rows aren't reseeded
+    let has_category_icon: bool = conn.prepare("SELECT icon FROM categories LIMIT 0").is_ok();
+    if !has_category_icon {
+        conn.execute_batch(
+            "ALTER TABLE categories ADD COLUMN description TEXT;
+             ALTER TABLE categories ADD COLUMN icon TEXT;
+             ALTER TABLE categories ADD COLUMN customized INTEGER NOT NULL DEFAULT 0;",
+        )
+        .expect("Failed to add category presentation columns");
+    }
+    for category in crate::models::VALID_CATEGORIES {
+        crate::categories::ensure_category(&conn, category)
+            .expect("Failed to seed categories");
+    }
+    crate::categories::seed_presentation(&conn).expect("Failed to seed category presentation");
+
+    // Submissions rejected by the content filter, kept for admin review
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS content_rejections (
+            id TEXT PRIMARY KEY,
+            kind TEXT NOT NULL,
+            app_id TEXT,
+            field TEXT NOT NULL,
+            rule TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            content TEXT NOT NULL DEFAULT '{}',
+            submitted_by_key_id TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_content_rejections_created_at ON content_rejections(created_at);",
+    )
+    .expect("Failed to create content_rejections table");
+
+    // Emitted events, kept for EVENT_RETENTION_DAYS so clients can poll for missed activity
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            event TEXT NOT NULL,
+            app_id TEXT,
+            data TEXT NOT NULL DEFAULT '{}',
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_events_created_at ON events(created_at);
+        CREATE INDEX IF NOT EXISTS idx_events_app ON events(app_id);",
+    )
+    .expect("Failed to create events table");
+
+    // Migration: structured review fields (pros/cons as JSON arrays)
+    let has_pros: bool = conn.prepare("SELECT pros FROM reviews LIMIT 0").is_ok();
+    if !has_pros {
+        conn.execute_batch(
+            "ALTER TABLE reviews ADD COLUMN pros TEXT NOT NULL DEFAULT '[]';
+             ALTER TABLE reviews ADD COLUMN cons TEXT NOT NULL DEFAULT '[]';
+             ALTER TABLE reviews ADD COLUMN use_case TEXT;",
+        )
+        .expect("Failed to add review pros/cons columns");
+    }
+
+    // Migration: webhook secret rotation keeps the previous secret signing until it expires
+    let has_previous_secret: bool = conn.prepare("SELECT previous_secret FROM webhooks LIMIT 0").is_ok();
+    if !has_previous_secret {
+        conn.execute_batch(
+            "ALTER TABLE webhooks ADD COLUMN previous_secret TEXT;
+             ALTER TABLE webhooks ADD COLUMN previous_secret_expires_at TEXT;",
+        )
+        .expect("Failed to add webhook previous_secret columns");
+    }
+
+    // Migration: admin-set ranking boost for default list ordering and search ties
+    let has_rank_boost: bool = conn.prepare("SELECT rank_boost FROM apps LIMIT 0").is_ok();
+    if !has_rank_boost {
+        conn.execute("ALTER TABLE apps ADD COLUMN rank_boost INTEGER NOT NULL DEFAULT 0", [])
+            .expect("Failed to add apps.rank_boost column");
+    }
+
+    // Migration: reviewer assignment for the pending queue
+    let has_assigned_reviewer: bool = conn.prepare("SELECT assigned_reviewer_key_id FROM apps LIMIT 0").is_ok();
+    if !has_assigned_reviewer {
+        conn.execute_batch(
+            "ALTER TABLE apps ADD COLUMN assigned_reviewer_key_id TEXT;
+             ALTER TABLE apps ADD COLUMN assigned_at TEXT;",
+        )
+        .expect("Failed to add apps reviewer assignment columns");
+    }
+
+    // Click-throughs to an app's links, and reviews backed by one
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS app_clicks (
+            id TEXT PRIMARY KEY,
+            app_id TEXT NOT NULL,
+            clicker_key_id TEXT,
+            target TEXT NOT NULL,
+            clicked_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (app_id) REFERENCES apps(id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_app_clicks_app_key ON app_clicks(app_id, clicker_key_id);",
+    )
+    .expect("Failed to create app_clicks table");
+    let has_verified_usage: bool = conn.prepare("SELECT verified_usage FROM reviews LIMIT 0").is_ok();
+    if !has_verified_usage {
+        conn.execute_batch("ALTER TABLE reviews ADD COLUMN verified_usage INTEGER NOT NULL DEFAULT 0;")
+            .expect("Failed to add reviews.verified_usage column");
+    }
+
+    // Per-webhook delivery timeout and a log of recent delivery attempts
+    let has_webhook_timeout: bool = conn.prepare("SELECT timeout_ms FROM webhooks LIMIT 0").is_ok();
+    if !has_webhook_timeout {
+        conn.execute_batch("ALTER TABLE webhooks ADD COLUMN timeout_ms INTEGER;")
+            .expect("Failed to add webhooks.timeout_ms column");
+    }
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS webhook_deliveries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            webhook_id TEXT NOT NULL,
+            event TEXT NOT NULL,
+            outcome TEXT NOT NULL,
+            status_code INTEGER,
+            error_message TEXT,
+            duration_ms INTEGER NOT NULL,
+            timeout_ms INTEGER NOT NULL,
+            delivered_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook ON webhook_deliveries(webhook_id, id);",
+    )
+    .expect("Failed to create webhook_deliveries table");
+
+    // Per-key burst allowance and routes exempt from rate limiting
+    let has_rate_burst: bool = conn.prepare("SELECT rate_burst FROM api_keys LIMIT 0").is_ok();
+    if !has_rate_burst {
+        conn.execute_batch(
+            "ALTER TABLE api_keys ADD COLUMN rate_burst INTEGER NOT NULL DEFAULT 0;
+             ALTER TABLE api_keys ADD COLUMN exempt_routes TEXT NOT NULL DEFAULT '[]';",
+        )
+        .expect("Failed to add api_keys rate limit columns");
+    }
+
+    // Scheduled featured windows
+    let has_featured_window: bool = conn.prepare("SELECT featured_from FROM apps LIMIT 0").is_ok();
+    if !has_featured_window {
+        conn.execute_batch(
+            "ALTER TABLE apps ADD COLUMN featured_from TEXT;
+             ALTER TABLE apps ADD COLUMN featured_until TEXT;",
+        )
+        .expect("Failed to add apps featured window columns");
+    }
+
+    // Incremental sync filters (?updated_after=) scan by modification time
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_apps_updated_at ON apps(updated_at);")
+        .expect("Failed to create apps updated_at index");
+
+    // Owner notification URL on each app, outside the admin webhook system
+    let has_notify_url: bool = conn.prepare("SELECT notify_url FROM apps LIMIT 0").is_ok();
+    if !has_notify_url {
+        conn.execute_batch(
+            "ALTER TABLE apps ADD COLUMN notify_url TEXT;
+             ALTER TABLE apps ADD COLUMN notify_secret TEXT;
+             ALTER TABLE apps ADD COLUMN notify_failure_count INTEGER NOT NULL DEFAULT 0;
+             ALTER TABLE apps ADD COLUMN notify_last_outcome TEXT;
+             ALTER TABLE apps ADD COLUMN notify_last_delivered_at TEXT;",
+        )
+        .expect("Failed to add apps notify columns");
+    }
+
+    // Review edits: an edited marker, a secret for anonymous authors, and prior versions
+    let has_review_edits: bool = conn.prepare("SELECT edited_at FROM reviews LIMIT 0").is_ok();
+    if !has_review_edits {
+        conn.execute_batch(
+            "ALTER TABLE reviews ADD COLUMN edited_at TEXT;
+             ALTER TABLE reviews ADD COLUMN secret_hash TEXT;",
+        )
+        .expect("Failed to add reviews edit columns");
+    }
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS review_versions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            review_id TEXT NOT NULL,
+            rating INTEGER NOT NULL,
+            title TEXT,
+            body TEXT,
+            reviewer_name TEXT,
+            pros TEXT NOT NULL DEFAULT '[]',
+            cons TEXT NOT NULL DEFAULT '[]',
+            use_case TEXT,
+            replaced_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_review_versions_review ON review_versions(review_id);",
+    )
+    .expect("Failed to create review_versions table");
+
+    // Listing visibility: public, unlisted (link only) or private (signed-in callers only)
+    let has_visibility: bool = conn.prepare("SELECT visibility FROM apps LIMIT 0").is_ok();
+    if !has_visibility {
+        conn.execute_batch("ALTER TABLE apps ADD COLUMN visibility TEXT NOT NULL DEFAULT 'public';")
+            .expect("Failed to add apps visibility column");
+    }
+
+    // Long-form Markdown usage docs, sanitized on write
+    let has_docs_markdown: bool = conn.prepare("SELECT docs_markdown FROM apps LIMIT 0").is_ok();
+    if !has_docs_markdown {
+        conn.execute_batch("ALTER TABLE apps ADD COLUMN docs_markdown TEXT;")
+            .expect("Failed to add apps docs_markdown column");
+    }
+
+    // Copies made with POST /apps/<id>/duplicate, and whether publishing one replaces its original
+    let has_duplicated_from: bool = conn.prepare("SELECT duplicated_from FROM apps LIMIT 0").is_ok();
+    if !has_duplicated_from {
+        conn.execute_batch(
+            "ALTER TABLE apps ADD COLUMN duplicated_from TEXT;
+             ALTER TABLE apps ADD COLUMN link_original INTEGER NOT NULL DEFAULT 0;",
+        )
+        .expect("Failed to add apps duplication columns");
+    }
+
+    // Per-viewer lookups for recommendations
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_app_views_viewer ON app_views(viewer_key_id, app_id);")
+        .expect("Failed to create app_views viewer index");
+
+    // Lightweight reactions on reviews, one per emoji per key (or client IP)
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS review_reactions (
+            review_id TEXT NOT NULL,
+            emoji TEXT NOT NULL,
+            reactor TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (review_id, emoji, reactor)
+        );",
+    )
+    .expect("Failed to create review_reactions table");
+
+    // Verified A2A agent cards of a2a listings
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS app_agent_cards (
+            app_id TEXT PRIMARY KEY,
+            card_url TEXT NOT NULL,
+            status TEXT NOT NULL,
+            name TEXT,
+            version TEXT,
+            skills TEXT NOT NULL DEFAULT '[]',
+            errors TEXT NOT NULL DEFAULT '[]',
+            card TEXT,
+            checked_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (app_id) REFERENCES apps(id)
+        );",
+    )
+    .expect("Failed to create app_agent_cards table");
+
+    // Migration: per-key page sizes for list and search (NULL uses the instance limits)
+    let has_page_sizes: bool = conn.prepare("SELECT max_page_size FROM api_keys LIMIT 0").is_ok();
+    if !has_page_sizes {
+        conn.execute_batch(
+            "ALTER TABLE api_keys ADD COLUMN default_page_size INTEGER;
+             ALTER TABLE api_keys ADD COLUMN max_page_size INTEGER;",
+        )
+        .expect("Failed to add api key page size columns");
+    }
+
+    // Discovery crawler: admin-registered seed documents, and every URL a
+    // crawl has proposed (or found already listed), so nothing is proposed twice
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS discovery_seeds (
+            id TEXT PRIMARY KEY,
+            url TEXT NOT NULL UNIQUE,
+            created_by TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            last_crawled_at TEXT,
+            last_format TEXT,
+            last_error TEXT,
+            proposed_count INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE TABLE IF NOT EXISTS discovery_candidates (
+            url TEXT PRIMARY KEY,
+            seed_id TEXT NOT NULL,
+            app_id TEXT,
+            seen_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );",
+    )
+    .expect("Failed to create discovery tables");
+
+    // Provenance of crawler-proposed listings (JSON: seed_id, seed_url, source_url, found_at)
+    let has_discovery: bool = conn.prepare("SELECT discovery FROM apps LIMIT 0").is_ok();
+    if !has_discovery {
+        conn.execute("ALTER TABLE apps ADD COLUMN discovery TEXT", [])
+            .expect("Failed to add apps discovery column");
+    }
+
+    // "Works great with" endorsements between listings, given by the endorsing app's owner
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS app_endorsements (
+            endorser_app_id TEXT NOT NULL,
+            endorsed_app_id TEXT NOT NULL,
+            note TEXT,
+            endorsed_by TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (endorser_app_id, endorsed_app_id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_app_endorsements_endorsed ON app_endorsements(endorsed_app_id);",
+    )
+    .expect("Failed to create app_endorsements table");
+
+    // Events queued by request handlers in the same transaction as their
+    // change; removed once published and delivered (see outbox.rs)
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS event_outbox (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            event TEXT NOT NULL,
+            data TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            attempts INTEGER NOT NULL DEFAULT 0,
+            claimed_at TEXT
+        );",
+    )
+    .expect("Failed to create event_outbox table");
+
+    // Authenticated requests per API key and hour, the baseline for anomaly detection
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS key_usage_hourly (
+            key_id TEXT NOT NULL,
+            hour TEXT NOT NULL,
+            requests INTEGER NOT NULL DEFAULT 0,
+            flagged INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (key_id, hour)
+        );",
+    )
+    .expect("Failed to create key_usage_hourly table");
+
+    // Migration: temporary rate limit cut for keys flagged as anomalous
+    let has_throttled_until: bool = conn.prepare("SELECT throttled_until FROM api_keys LIMIT 0").is_ok();
+    if !has_throttled_until {
+        conn.execute_batch("ALTER TABLE api_keys ADD COLUMN throttled_until TEXT;")
+            .expect("Failed to add api_keys throttled_until column");
+    }
+
+    // Daily directory totals for growth charts (see stats::record_snapshot)
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS directory_snapshots (
+            day TEXT NOT NULL,
+            metric TEXT NOT NULL,
+            value INTEGER NOT NULL,
+            PRIMARY KEY (metric, day)
+        );",
+    )
+    .expect("Failed to create directory_snapshots table");
+
+    // Last protocol conformance check per app (admin-triggered)
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS app_conformance (
+            app_id TEXT PRIMARY KEY,
+            spec_url TEXT NOT NULL,
+            status TEXT NOT NULL,
+            probes TEXT NOT NULL DEFAULT '[]',
+            errors TEXT NOT NULL DEFAULT '[]',
+            checked_at TEXT NOT NULL
+        );",
+    )
+    .expect("Failed to create app_conformance table");
+
+    // Old slugs of renamed apps; GET /apps/<old-slug> redirects to the current one
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS slug_redirects (
+            old_slug TEXT PRIMARY KEY,
+            app_id TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_slug_redirects_app ON slug_redirects(app_id);",
+    )
+    .expect("Failed to create slug_redirects table");
+
+    // Upstream apps an app declares it depends on (see health::composite_health)
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS app_dependencies (
+            app_id TEXT NOT NULL,
+            depends_on_id TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (app_id, depends_on_id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_app_dependencies_upstream ON app_dependencies(depends_on_id);",
+    )
+    .expect("Failed to create app_dependencies table");
+
+    // Short-lived bearer tokens from the OAuth2 client-credentials grant
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS oauth_tokens (
+            token_hash TEXT PRIMARY KEY,
+            key_id TEXT NOT NULL,
+            expires_at TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_oauth_tokens_key ON oauth_tokens(key_id);",
+    )
+    .expect("Failed to create oauth_tokens table");
+
+    // Per-installation values generated on first use (e.g. the stats pseudonym secret)
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS instance_settings (
+            name TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );",
+    )
+    .expect("Failed to create instance_settings table");
+
+    // Audit log for admin moderation actions
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS audit_log (
+            id TEXT PRIMARY KEY,
+            action TEXT NOT NULL,
+            actor_key_id TEXT NOT NULL,
+            details TEXT NOT NULL DEFAULT '{}',
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS idx_audit_log_created_at ON audit_log(created_at);",
+    )
+    .expect("Failed to create audit_log table");
+
     conn
 }
+
+/// Run `PRAGMA quick_check`, or the slower `integrity_check` when `full`.
+/// Returns the problems SQLite reports, if any.
+pub fn integrity_check(conn: &Connection, full: bool) -> rusqlite::Result<Result<(), Vec<String>>> {
+    let pragma = if full { "PRAGMA integrity_check" } else { "PRAGMA quick_check" };
+    let mut stmt = conn.prepare(pragma)?;
+    let rows: Vec<String> = stmt.query_map([], |r| r.get(0))?.collect::<Result<_, _>>()?;
+    if rows.len() == 1 && rows[0] == "ok" {
+        Ok(Ok(()))
+    } else {
+        Ok(Err(rows))
+    }
+}
+
+/// Database file size in bytes (excluding the WAL).
+pub fn size_bytes(conn: &Connection) -> rusqlite::Result<i64> {
+    conn.query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |r| r.get(0),
+    )
+}
+
+/// What `optimize` did.
+#[derive(Debug)]
+pub struct OptimizeReport {
+    pub vacuumed: bool,
+    pub analyzed: bool,
+    pub size_before: i64,
+    pub size_after: i64,
+    pub duration_ms: u64,
+}
+
+/// Maintenance pass: `VACUUM` rebuilds the file to reclaim free pages, and
+/// `ANALYZE` refreshes the query planner's statistics. Both block other
+/// writers while they run, so call this in a maintenance window.
+pub fn optimize(conn: &Connection, vacuum: bool, analyze: bool) -> rusqlite::Result<OptimizeReport> {
+    let started = Instant::now();
+    let size_before = size_bytes(conn)?;
+    if vacuum {
+        conn.execute_batch("VACUUM;")?;
+        // Fold the rewritten pages back into the main file and truncate the WAL
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    }
+    if analyze {
+        conn.execute_batch("ANALYZE;")?;
+    }
+    Ok(OptimizeReport {
+        vacuumed: vacuum,
+        analyzed: analyze,
+        size_before,
+        size_after: size_bytes(conn)?,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
@@ -0,0 +1,340 @@
+//! Demo catalog for local development and integration environments.
+//!
+//! Enabled with `SEED_DEMO_DATA=true` at startup or `app-directory --seed-demo-data`.
+//! Data is deterministic: fixed app ids and a fixed RNG seed for reviews,
+//! views, and health history, with timestamps relative to the time of seeding.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rusqlite::Connection;
+
+/// RNG seed for generated reviews, views, and health checks.
+const RNG_SEED: u64 = 0x00A9_D1EC;
+
+/// Hourly health checks recorded per app with a URL.
+const HEALTH_HISTORY_HOURS: i64 = 48;
+
+/// What a seeding run inserted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeedSummary {
+    pub apps: usize,
+    pub reviews: usize,
+    pub views: usize,
+    pub health_checks: usize,
+}
+
+struct DemoApp {
+    slug: &'static str,
+    name: &'static str,
+    short_description: &'static str,
+    description: &'static str,
+    category: &'static str,
+    protocol: &'static str,
+    tags: &'static [&'static str],
+    api_url: Option<&'static str>,
+    featured: bool,
+    verified: bool,
+}
+
+const DEMO_APPS: &[DemoApp] = &[
+    DemoApp {
+        slug: "skyline-weather",
+        name: "Skyline Weather",
+        short_description: "Hyperlocal forecasts and severe weather alerts",
+        description: "Minute-by-minute precipitation, 14-day forecasts, and push alerts for severe weather. Returns compact JSON designed for agent tool calls.",
+        category: "data",
+        protocol: "rest",
+        tags: &["weather", "forecast", "alerts"],
+        api_url: Some("https://skyline-weather.example.com/api"),
+        featured: true,
+        verified: true,
+    },
+    DemoApp {
+        slug: "ledgerline",
+        name: "Ledgerline",
+        short_description: "Double-entry bookkeeping API for small teams",
+        description: "Create journals, post transactions, and pull balance sheets. Idempotency keys on every write make it safe for autonomous agents to retry.",
+        category: "finance",
+        protocol: "rest",
+        tags: &["accounting", "ledger", "invoices"],
+        api_url: Some("https://ledgerline.example.com/v2"),
+        featured: false,
+        verified: true,
+    },
+    DemoApp {
+        slug: "relay-chat-mcp",
+        name: "Relay Chat MCP",
+        short_description: "MCP server for team chat channels",
+        description: "Read, search, and post to chat channels through the Model Context Protocol. Scoped tokens limit agents to the channels they need.",
+        category: "communication",
+        protocol: "mcp",
+        tags: &["chat", "mcp", "messaging"],
+        api_url: Some("https://relay-chat.example.com/mcp"),
+        featured: true,
+        verified: false,
+    },
+    DemoApp {
+        slug: "codeatlas",
+        name: "CodeAtlas",
+        short_description: "Semantic code search across repositories",
+        description: "Index repositories and query them by meaning, symbol, or call graph. GraphQL API with cursor pagination.",
+        category: "developer-tools",
+        protocol: "graphql",
+        tags: &["code-search", "repositories", "graphql"],
+        api_url: Some("https://codeatlas.example.com/graphql"),
+        featured: false,
+        verified: true,
+    },
+    DemoApp {
+        slug: "framekit",
+        name: "FrameKit",
+        short_description: "Image resizing, cropping, and format conversion",
+        description: "On-the-fly image transforms with smart cropping and WebP/AVIF output. Accepts URLs or uploads.",
+        category: "media",
+        protocol: "rest",
+        tags: &["images", "transform", "cdn"],
+        api_url: Some("https://framekit.example.com/api"),
+        featured: false,
+        verified: false,
+    },
+    DemoApp {
+        slug: "taskpilot",
+        name: "TaskPilot",
+        short_description: "Task lists and reminders agents can manage",
+        description: "Create projects, assign tasks, and schedule reminders. Webhooks notify agents when humans complete or reassign work.",
+        category: "productivity",
+        protocol: "rest",
+        tags: &["tasks", "todo", "reminders"],
+        api_url: Some("https://taskpilot.example.com/api"),
+        featured: true,
+        verified: true,
+    },
+    DemoApp {
+        slug: "deepfind",
+        name: "DeepFind",
+        short_description: "Web search with citations for agents",
+        description: "Search the web and get ranked results with extracted passages and source citations, tuned for retrieval-augmented generation.",
+        category: "search",
+        protocol: "rest",
+        tags: &["search", "rag", "citations"],
+        api_url: Some("https://deepfind.example.com/v1"),
+        featured: false,
+        verified: false,
+    },
+    DemoApp {
+        slug: "vaultguard",
+        name: "VaultGuard",
+        short_description: "Secret scanning and credential rotation",
+        description: "Scan repositories and logs for leaked credentials and rotate them through provider integrations.",
+        category: "security",
+        protocol: "grpc",
+        tags: &["secrets", "scanning", "rotation"],
+        api_url: None,
+        featured: false,
+        verified: true,
+    },
+    DemoApp {
+        slug: "pulseboard",
+        name: "PulseBoard",
+        short_description: "Social media scheduling and analytics",
+        description: "Schedule posts across networks and pull engagement metrics per post or campaign.",
+        category: "social",
+        protocol: "rest",
+        tags: &["social", "scheduling", "analytics"],
+        api_url: Some("https://pulseboard.example.com/api"),
+        featured: false,
+        verified: false,
+    },
+    DemoApp {
+        slug: "modelmesh",
+        name: "ModelMesh",
+        short_description: "Route prompts across hosted model providers",
+        description: "One API for many model providers, with fallbacks, cost tracking, and per-key budgets.",
+        category: "ai-ml",
+        protocol: "rest",
+        tags: &["llm", "routing", "inference"],
+        api_url: Some("https://modelmesh.example.com/v1"),
+        featured: true,
+        verified: false,
+    },
+    DemoApp {
+        slug: "agent-handshake",
+        name: "Agent Handshake",
+        short_description: "A2A registry for agent-to-agent delegation",
+        description: "Publish agent cards and discover peers that accept delegated tasks over the A2A protocol.",
+        category: "ai-ml",
+        protocol: "a2a",
+        tags: &["a2a", "agents", "delegation"],
+        api_url: Some("https://handshake.example.com/a2a"),
+        featured: false,
+        verified: false,
+    },
+    DemoApp {
+        slug: "tidewatch",
+        name: "Tidewatch",
+        short_description: "Live infrastructure metrics over WebSocket",
+        description: "Stream CPU, memory, and request metrics from your fleet in real time, with threshold alerts.",
+        category: "infrastructure",
+        protocol: "websocket",
+        tags: &["monitoring", "metrics", "streaming"],
+        api_url: Some("https://tidewatch.example.com/ws"),
+        featured: false,
+        verified: true,
+    },
+];
+
+const REVIEWERS: &[&str] = &["atlas-agent", "quill", "navigator-bot", "sam", "priya", "orbit", "anonymous"];
+
+/// Indexes into `REVIEW_TEXT`, weighted towards good reviews.
+const RATING_SPREAD: &[usize] = &[0, 0, 0, 1, 1, 1, 2, 3, 4];
+
+const REVIEW_TEXT: &[(i64, &str, &str)] = &[
+    (5, "Exactly what my agent needed", "Clear schema and fast responses. Integrated in an afternoon."),
+    (4, "Solid, minor gaps", "Works well; a couple of endpoints could use better error messages."),
+    (3, "Does the job", "Reliable but the docs are thin in places."),
+    (2, "Flaky under load", "Saw intermittent timeouts during peak hours."),
+    (1, "Couldn't get it working", "Auth flow failed consistently for my use case."),
+];
+
+/// Stable id for a seeded app.
+pub fn demo_app_id(slug: &str) -> String {
+    format!("demo-{}", slug)
+}
+
+/// Populate an empty directory with the demo catalog.
+/// Returns `Ok(None)` without changes if any apps already exist.
+pub fn seed_demo_data(conn: &Connection) -> rusqlite::Result<Option<SeedSummary>> {
+    let existing: i64 = conn.query_row("SELECT COUNT(*) FROM apps", [], |r| r.get(0))?;
+    if existing > 0 {
+        return Ok(None);
+    }
+
+    let mut rng = StdRng::seed_from_u64(RNG_SEED);
+    let mut summary = SeedSummary {
+        apps: 0,
+        reviews: 0,
+        views: 0,
+        health_checks: 0,
+    };
+
+    let tx = conn.unchecked_transaction()?;
+
+    for (i, app) in DEMO_APPS.iter().enumerate() {
+        let id = demo_app_id(app.slug);
+        let tags = serde_json::to_string(app.tags).unwrap();
+        let homepage = format!("https://{}.example.com", app.slug);
+        // Stagger creation dates so "newest"/"oldest" sorting is meaningful
+        let created = format!("-{} days", (DEMO_APPS.len() - i) * 3);
+
+        tx.execute(
+            "INSERT INTO apps (id, name, slug, short_description, description, homepage_url, api_url, protocol, category, tags, author_name, author_url, status, is_featured, is_verified, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, 'approved', ?13, ?14, datetime('now', ?15), datetime('now', ?15))",
+            rusqlite::params![
+                id,
+                app.name,
+                app.slug,
+                app.short_description,
+                app.description,
+                homepage,
+                app.api_url,
+                app.protocol,
+                app.category,
+                tags,
+                format!("{} Team", app.name),
+                homepage,
+                app.featured as i32,
+                app.verified as i32,
+                created,
+            ],
+        )?;
+        summary.apps += 1;
+
+        // Reviews: 0–5 distinct reviewers, skewed positive
+        let review_count = rng.gen_range(0..=5);
+        for (n, reviewer) in REVIEWERS.iter().take(review_count).enumerate() {
+            let (rating, title, body) = REVIEW_TEXT[RATING_SPREAD[rng.gen_range(0..RATING_SPREAD.len())]];
+            let age = format!("-{} hours", rng.gen_range(1..24 * 30));
+            tx.execute(
+                "INSERT INTO reviews (id, app_id, reviewer_name, reviewer_fingerprint, rating, title, body, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now', ?8))",
+                rusqlite::params![
+                    format!("{}-review-{}", id, n),
+                    id,
+                    reviewer,
+                    format!("demo-{}", reviewer),
+                    rating,
+                    title,
+                    body,
+                    age,
+                ],
+            )?;
+            summary.reviews += 1;
+        }
+        tx.execute(
+            "UPDATE apps SET
+               avg_rating = (SELECT COALESCE(AVG(CAST(rating AS REAL)), 0.0) FROM reviews WHERE app_id = ?1),
+               review_count = (SELECT COUNT(*) FROM reviews WHERE app_id = ?1)
+             WHERE id = ?1",
+            rusqlite::params![id],
+        )?;
+
+        // Views spread over the last 30 days; featured apps get more traffic
+        let views = rng.gen_range(5..60) * if app.featured { 4 } else { 1 };
+        for n in 0..views {
+            let age = format!("-{} minutes", rng.gen_range(1..60 * 24 * 30));
+            tx.execute(
+                "INSERT INTO app_views (id, app_id, viewer_key_id, viewed_at) VALUES (?1, ?2, ?3, datetime('now', ?4))",
+                rusqlite::params![
+                    format!("{}-view-{}", id, n),
+                    id,
+                    format!("demo-viewer-{}", rng.gen_range(0..25)),
+                    age,
+                ],
+            )?;
+            summary.views += 1;
+        }
+
+        // Hourly health history for apps with an API URL
+        if let Some(api_url) = app.api_url {
+            let check_url = format!("{}/health", api_url.trim_end_matches('/'));
+            let reliability: f64 = rng.gen_range(0.85..1.0);
+            for hour in (1..=HEALTH_HISTORY_HOURS).rev() {
+                let roll: f64 = rng.gen();
+                let (status, code, error, ms) = if roll < reliability {
+                    ("healthy", Some(200), None, rng.gen_range(40..400))
+                } else if roll < reliability + (1.0 - reliability) / 2.0 {
+                    ("unhealthy", Some(503), Some("HTTP 503 Service Unavailable"), rng.gen_range(100..2000))
+                } else {
+                    ("unreachable", None, Some("Connection timed out (10s)"), 10_000)
+                };
+                tx.execute(
+                    "INSERT INTO health_checks (id, app_id, status, status_code, response_time_ms, error_message, checked_url, checked_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now', ?8))",
+                    rusqlite::params![
+                        format!("{}-check-{}", id, hour),
+                        id,
+                        status,
+                        code,
+                        ms,
+                        error,
+                        check_url,
+                        format!("-{} hours", hour),
+                    ],
+                )?;
+                summary.health_checks += 1;
+            }
+            tx.execute(
+                "UPDATE apps SET
+                   last_health_status = (SELECT status FROM health_checks WHERE app_id = ?1 ORDER BY checked_at DESC LIMIT 1),
+                   last_checked_at = (SELECT MAX(checked_at) FROM health_checks WHERE app_id = ?1),
+                   uptime_pct = (SELECT CAST(SUM(CASE WHEN status = 'healthy' THEN 1 ELSE 0 END) AS REAL) / COUNT(*) * 100.0
+                                 FROM health_checks WHERE app_id = ?1)
+                 WHERE id = ?1",
+                rusqlite::params![id],
+            )?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(Some(summary))
+}
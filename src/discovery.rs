@@ -0,0 +1,390 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use regex::Regex;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::{Orbit, Rocket};
+use serde_json::{json, Value};
+
+use crate::events::{AppEvent, EventBus};
+use crate::models::slugify;
+use crate::revisions;
+use crate::scheduler::init_scheduler_db;
+
+/// HTTP timeout for fetching a seed document.
+const FETCH_TIMEOUT_SECS: u64 = 10;
+
+/// Seed documents larger than this are rejected without parsing.
+const MAX_SEED_BYTES: usize = 1024 * 1024;
+
+/// Most new listings one crawl of a seed proposes; the rest wait for the next crawl.
+pub const MAX_PROPOSALS_PER_CRAWL: usize = 50;
+
+/// A service a seed document points at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub url: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Parse a seed document into its format (`skills`, `sitemap` or `llms`)
+/// and the services it lists, with relative URLs resolved against
+/// `seed_url`. `None` when the format isn't recognized.
+///
+/// - `skills`: a `/.well-known/skills/index.json` style `{"skills": [{name, description, url}]}`
+/// - `sitemap`: an XML `<urlset>`, one candidate per `<loc>`
+/// - `llms`: an llms.txt, one candidate per `- [Title](url): description` line
+pub fn parse_seed(seed_url: &str, body: &str) -> Option<(&'static str, Vec<Candidate>)> {
+    let base = reqwest::Url::parse(seed_url).ok()?;
+    let resolve = |raw: &str| -> Option<String> {
+        let url = base.join(raw.trim()).ok()?;
+        matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+    };
+    let text = |v: &Value| v.as_str().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+
+    let trimmed = body.trim_start();
+    let (format, found): (&'static str, Vec<Candidate>) = if trimmed.starts_with('{') {
+        let doc: Value = serde_json::from_str(trimmed).ok()?;
+        let skills = doc.get("skills")?.as_array()?;
+        let found = skills
+            .iter()
+            .filter_map(|s| {
+                let url = resolve(s.get("url")?.as_str()?)?;
+                let name = text(&s["name"]).unwrap_or_else(|| name_from_url(&url));
+                Some(Candidate { url, name, description: text(&s["description"]) })
+            })
+            .collect();
+        ("skills", found)
+    } else if trimmed.contains("<urlset") {
+        let loc = Regex::new(r"<loc>\s*([^<]+?)\s*</loc>").ok()?;
+        let found = loc
+            .captures_iter(trimmed)
+            .filter_map(|c| resolve(&xml_unescape(&c[1])))
+            .map(|url| Candidate { name: name_from_url(&url), url, description: None })
+            .collect();
+        ("sitemap", found)
+    } else {
+        let link = Regex::new(r"(?m)^\s*[-*]\s*\[([^\]]+)\]\(([^)\s]+)\)(?:\s*:\s*(.+))?$").ok()?;
+        let found: Vec<Candidate> = link
+            .captures_iter(trimmed)
+            .filter_map(|c| {
+                Some(Candidate {
+                    url: resolve(&c[2])?,
+                    name: c[1].trim().to_string(),
+                    description: c.get(3).map(|d| d.as_str().trim().to_string()).filter(|d| !d.is_empty()),
+                })
+            })
+            .collect();
+        if found.is_empty() {
+            return None;
+        }
+        ("llms", found)
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    Some((format, found.into_iter().filter(|c| seen.insert(normalize_url(&c.url))).collect()))
+}
+
+/// Comparable form of a URL: no trailing slash.
+fn normalize_url(url: &str) -> String {
+    url.trim_end_matches('/').to_string()
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&amp;", "&").replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'")
+}
+
+/// A readable name for a bare URL: its last path segment, else its host.
+fn name_from_url(url: &str) -> String {
+    let parsed = reqwest::Url::parse(url).ok();
+    let segment = parsed
+        .as_ref()
+        .and_then(|u| u.path_segments())
+        .and_then(|mut s| s.rfind(|seg| !seg.is_empty()).map(str::to_string));
+    segment
+        .map(|s| s.replace(['-', '_'], " "))
+        .or_else(|| parsed.as_ref().and_then(|u| u.host_str()).map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    s.chars().take(max).collect()
+}
+
+/// Fetch a seed document, returning its body or why it couldn't be read.
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<String, String> {
+    let resp = match client.get(url).send().await {
+        Ok(r) => r,
+        Err(e) if e.is_timeout() => return Err(format!("Connection timed out ({}s)", FETCH_TIMEOUT_SECS)),
+        Err(e) if e.is_connect() => return Err("Connection refused or DNS failure".to_string()),
+        Err(e) => return Err(e.to_string()),
+    };
+    if !resp.status().is_success() {
+        return Err(format!("HTTP {}", resp.status()));
+    }
+    let body = crate::health::read_body_limited(resp, MAX_SEED_BYTES)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Seed document exceeds {} bytes", MAX_SEED_BYTES))?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+pub fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Propose each new candidate as a pending listing, with the seed recorded
+/// in its `discovery` provenance. URLs proposed before (even if the listing
+/// was since rejected or deleted) and URLs already listed as an app's
+/// homepage or API are skipped. Returns (proposed listings, known count).
+pub fn propose(
+    conn: &rusqlite::Connection,
+    bus: &EventBus,
+    seed_id: &str,
+    seed_url: &str,
+    candidates: &[Candidate],
+) -> (Vec<Value>, usize) {
+    let author = reqwest::Url::parse(seed_url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| seed_url.to_string());
+    let mut proposed = Vec::new();
+    let mut known = 0;
+
+    for candidate in candidates {
+        let url = normalize_url(&candidate.url);
+        let seen: bool = conn
+            .query_row("SELECT COUNT(*) > 0 FROM discovery_candidates WHERE url = ?1", [&url], |r| r.get(0))
+            .unwrap_or(true);
+        if seen {
+            known += 1;
+            continue;
+        }
+        let listed: Option<String> = conn
+            .query_row(
+                "SELECT id FROM apps WHERE RTRIM(homepage_url, '/') = ?1 OR RTRIM(api_url, '/') = ?1 LIMIT 1",
+                [&url],
+                |r| r.get(0),
+            )
+            .ok();
+        if let Some(app_id) = listed {
+            let _ = conn.execute(
+                "INSERT INTO discovery_candidates (url, seed_id, app_id) VALUES (?1, ?2, ?3)",
+                rusqlite::params![url, seed_id, app_id],
+            );
+            known += 1;
+            continue;
+        }
+        if proposed.len() >= MAX_PROPOSALS_PER_CRAWL {
+            continue;
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let name = truncate(&candidate.name, 100);
+        let base_slug = slugify(&name);
+        let slug_exists: bool = conn
+            .query_row("SELECT COUNT(*) > 0 FROM apps WHERE slug = ?1", [&base_slug], |r| r.get(0))
+            .unwrap_or(true);
+        let slug = if slug_exists || base_slug.is_empty() { format!("{}-{}", base_slug, &id[..8]) } else { base_slug };
+        let short_description = truncate(
+            candidate.description.as_deref().unwrap_or(&format!("Found in {}", seed_url)),
+            300,
+        );
+        let discovery = json!({
+            "seed_id": seed_id,
+            "seed_url": seed_url,
+            "source_url": candidate.url,
+            "found_at": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        });
+        let inserted = conn.execute(
+            "INSERT INTO apps (id, name, slug, short_description, description, homepage_url, author_name, status, discovery)
+             VALUES (?1, ?2, ?3, ?4, ?4, ?5, ?6, 'pending', ?7)",
+            rusqlite::params![id, name, slug, short_description, candidate.url, author, discovery.to_string()],
+        );
+        if let Err(e) = inserted {
+            rocket::error!("Discovery: failed to propose {}: {}", candidate.url, e);
+            continue;
+        }
+        let _ = conn.execute(
+            "INSERT INTO discovery_candidates (url, seed_id, app_id) VALUES (?1, ?2, ?3)",
+            rusqlite::params![url, seed_id, id],
+        );
+        let _ = revisions::record(conn, &id, "system", None);
+        bus.emit(AppEvent {
+            event: "app.submitted".to_string(),
+            data: json!({ "app_id": id, "name": name, "slug": slug, "status": "pending", "discovered": true }),
+        });
+        proposed.push(json!({ "app_id": id, "name": name, "slug": slug, "url": candidate.url }));
+    }
+    (proposed, known)
+}
+
+/// Fetch one seed, propose what it lists and record the outcome on the
+/// seed. The DB lock is only held around the writes. Returns the crawl
+/// report, or `None` when the seed doesn't exist.
+pub async fn crawl_seed(
+    db: &Mutex<rusqlite::Connection>,
+    bus: &EventBus,
+    client: &reqwest::Client,
+    seed_id: &str,
+) -> Option<Value> {
+    let seed_url: String = {
+        let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+        conn.query_row("SELECT url FROM discovery_seeds WHERE id = ?1", [seed_id], |r| r.get(0))
+            .ok()?
+    };
+
+    let parsed = fetch(client, &seed_url)
+        .await
+        .and_then(|body| parse_seed(&seed_url, &body).ok_or_else(|| "Unrecognized seed format".to_string()));
+
+    let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+    let (format, found, proposed, known, error) = match parsed {
+        Ok((format, candidates)) => {
+            let (proposed, known) = propose(&conn, bus, seed_id, &seed_url, &candidates);
+            (Some(format), candidates.len(), proposed, known, None)
+        }
+        Err(e) => (None, 0, Vec::new(), 0, Some(e)),
+    };
+    let _ = conn.execute(
+        "UPDATE discovery_seeds
+         SET last_crawled_at = datetime('now'), last_format = COALESCE(?1, last_format), last_error = ?2,
+             proposed_count = proposed_count + ?3
+         WHERE id = ?4",
+        rusqlite::params![format, error, proposed.len() as i64, seed_id],
+    );
+
+    Some(json!({
+        "seed_id": seed_id,
+        "url": seed_url,
+        "format": format,
+        "found": found,
+        "proposed": proposed,
+        "known": known,
+        "error": error,
+    }))
+}
+
+/// A registered seed as returned by the admin API.
+pub fn seed_json(row: &rusqlite::Row) -> rusqlite::Result<Value> {
+    Ok(json!({
+        "id": row.get::<_, String>(0)?,
+        "url": row.get::<_, String>(1)?,
+        "created_by": row.get::<_, String>(2)?,
+        "created_at": row.get::<_, String>(3)?,
+        "last_crawled_at": row.get::<_, Option<String>>(4)?,
+        "last_format": row.get::<_, Option<String>>(5)?,
+        "last_error": row.get::<_, Option<String>>(6)?,
+        "proposed_count": row.get::<_, i64>(7)?,
+    }))
+}
+
+/// Columns `seed_json` reads, in order.
+pub const SEED_COLUMNS: &str =
+    "id, url, created_by, created_at, last_crawled_at, last_format, last_error, proposed_count";
+
+/// Rocket fairing that crawls every seed each `DISCOVERY_CRAWL_INTERVAL_SECS`.
+/// Off (0) by default; seeds can still be crawled on demand.
+pub struct DiscoveryCrawler {
+    pub db_path: String,
+    pub interval_secs: u64,
+}
+
+#[rocket::async_trait]
+impl Fairing for DiscoveryCrawler {
+    fn info(&self) -> Info {
+        Info {
+            name: "Discovery Crawler",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        if self.interval_secs == 0 {
+            rocket::info!("Discovery crawler disabled (DISCOVERY_CRAWL_INTERVAL_SECS=0)");
+            return;
+        }
+        let bus = rocket
+            .state::<EventBus>()
+            .expect("EventBus not managed")
+            .clone();
+        let db = init_scheduler_db(&self.db_path);
+        let interval = Duration::from_secs(self.interval_secs);
+        let shutdown = rocket.shutdown();
+        let client = client();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = shutdown.clone() => break,
+                }
+                let seeds: Vec<String> = {
+                    let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+                    conn.prepare("SELECT id FROM discovery_seeds ORDER BY created_at")
+                        .and_then(|mut stmt| stmt.query_map([], |r| r.get(0))?.collect())
+                        .unwrap_or_default()
+                };
+                for seed_id in seeds {
+                    if let Some(report) = crawl_seed(&db, &bus, &client, &seed_id).await {
+                        rocket::info!(
+                            "Discovery crawl of {}: {} found, {} proposed",
+                            report["url"].as_str().unwrap_or_default(),
+                            report["found"],
+                            report["proposed"].as_array().map_or(0, |p| p.len())
+                        );
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_seed_formats() {
+        let (format, found) = parse_seed(
+            "https://example.com/.well-known/skills/index.json",
+            r#"{"skills":[{"name":"weather","description":"Forecasts","url":"/skills/weather/SKILL.md"},{"name":"no-url"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(format, "skills");
+        assert_eq!(
+            found,
+            [Candidate {
+                url: "https://example.com/skills/weather/SKILL.md".to_string(),
+                name: "weather".to_string(),
+                description: Some("Forecasts".to_string()),
+            }]
+        );
+
+        let (format, found) = parse_seed(
+            "https://example.com/sitemap.xml",
+            "<?xml version=\"1.0\"?><urlset><url><loc>https://example.com/tide-tables/</loc></url><url><loc>https://example.com/tide-tables</loc></url></urlset>",
+        )
+        .unwrap();
+        assert_eq!(format, "sitemap");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "tide tables");
+
+        let (format, found) = parse_seed(
+            "https://example.com/llms.txt",
+            "# Example\n\n- [Geo API](https://geo.example.com): Geocoding\n- [Docs](/docs)\nSee also [inline](https://x.example.com)\n",
+        )
+        .unwrap();
+        assert_eq!(format, "llms");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].description.as_deref(), Some("Geocoding"));
+        assert_eq!(found[1].url, "https://example.com/docs");
+
+        assert!(parse_seed("https://example.com/", "<html>nothing here</html>").is_none());
+    }
+}
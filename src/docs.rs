@@ -0,0 +1,385 @@
+/// Images kept in an app's docs; later ones are replaced by their alt text.
+pub const MAX_DOCS_IMAGES: usize = 10;
+
+/// Elements dropped together with everything inside them.
+const DROPPED_ELEMENTS: &[&str] = &["script", "style", "iframe", "object", "embed", "noscript", "template"];
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Whether a link or image target is safe to keep: relative URLs and
+/// `http`, `https` and `mailto` ones.
+fn safe_url(url: &str) -> bool {
+    let url: String = url.chars().filter(|c| !c.is_whitespace() && !c.is_control()).collect::<String>().to_lowercase();
+    match url.find(':') {
+        Some(colon) if !url[..colon].contains(['/', '?', '#']) => {
+            matches!(&url[..colon], "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+/// Length of a link destination at the start of `s`: up to whitespace or
+/// the `)` that closes the link, allowing balanced parentheses inside.
+fn dest_len(s: &str) -> usize {
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return i,
+            ')' => depth -= 1,
+            c if c.is_whitespace() => return i,
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+/// Length of a backtick code span starting at `rest`, if it is closed.
+fn code_span_len(rest: &str) -> Option<usize> {
+    let ticks = rest.len() - rest.trim_start_matches('`').len();
+    let fence = &rest[..ticks];
+    rest[ticks..].find(fence).map(|end| ticks + end + ticks)
+}
+
+/// Clean one line outside fenced code. `dropping` carries an open
+/// `<script>`-like element across lines.
+fn sanitize_line(line: &str, images: &mut usize, dropping: &mut Option<String>) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < line.len() {
+        let rest = &line[i..];
+        if let Some(close) = dropping.as_deref() {
+            match rest.to_lowercase().find(close) {
+                Some(at) => {
+                    let end = rest[at..].find('>').map(|gt| at + gt + 1).unwrap_or(rest.len());
+                    *dropping = None;
+                    i += end;
+                    continue;
+                }
+                None => break,
+            }
+        }
+        if rest.starts_with('`') {
+            let len = code_span_len(rest).unwrap_or_else(|| rest.len() - rest.trim_start_matches('`').len());
+            out.push_str(&rest[..len]);
+            i += len;
+            continue;
+        }
+        if rest.starts_with("<!--") {
+            i += rest.find("-->").map(|end| end + 3).unwrap_or(rest.len());
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix('<') {
+            let is_tag = after.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/');
+            match (is_tag, rest.find('>')) {
+                (true, Some(end)) => {
+                    let inner = &rest[1..end];
+                    // Autolinks (<https://...>) stay when their scheme is safe
+                    if inner.contains(':') && !inner.contains(char::is_whitespace) {
+                        if safe_url(inner) {
+                            out.push_str(&rest[..=end]);
+                        }
+                    } else {
+                        let name: String = inner
+                            .chars()
+                            .take_while(|c| c.is_ascii_alphanumeric())
+                            .collect::<String>()
+                            .to_lowercase();
+                        if DROPPED_ELEMENTS.contains(&name.as_str()) && !inner.ends_with('/') {
+                            *dropping = Some(format!("</{name}"));
+                        }
+                    }
+                    i += end + 1;
+                }
+                // Not a tag, or one that doesn't close on this line
+                _ => {
+                    out.push_str("&lt;");
+                    i += 1;
+                }
+            }
+            continue;
+        }
+        if rest.starts_with("![") {
+            if let Some((alt, len)) = link_parts(&rest[1..]).map(|(text, _, len)| (text, len + 1)) {
+                if *images >= MAX_DOCS_IMAGES {
+                    out.push_str(&alt.replace('<', "&lt;"));
+                    i += len;
+                    continue;
+                }
+                *images += 1;
+            }
+            out.push('!');
+            i += 1;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("](") {
+            let dest_len = dest_len(after);
+            let dest = &after[..dest_len];
+            out.push_str("](");
+            out.push_str(if safe_url(dest) { dest } else { "#" });
+            i += 2 + dest_len;
+            continue;
+        }
+        let c = rest.chars().next().unwrap_or_default();
+        out.push(c);
+        i += c.len_utf8();
+    }
+    out
+}
+
+/// Clean docs Markdown before it's stored: drops `<script>`, `<style>`,
+/// `<iframe>` and similar elements with their content, strips other raw
+/// HTML tags, replaces `javascript:`, `data:` and other unsafe link targets
+/// with `#`, and keeps at most `MAX_DOCS_IMAGES` images. Code blocks and
+/// code spans are left alone.
+pub fn sanitize(markdown: &str) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut images = 0;
+    let mut dropping: Option<String> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            out.push(line.to_string());
+            continue;
+        }
+        if dropping.is_none() {
+            if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+                fence = Some(marker);
+                out.push(line.to_string());
+                continue;
+            }
+            // Reference definitions: [label]: url
+            if let Some(colon) = trimmed.strip_prefix('[').and_then(|t| t.find("]:")) {
+                let dest = trimmed[colon + 3..].split_whitespace().next().unwrap_or("");
+                if !safe_url(dest) {
+                    out.push(format!("{}]: #", &trimmed[..colon + 1]));
+                    continue;
+                }
+            }
+        }
+        out.push(sanitize_line(line, &mut images, &mut dropping));
+    }
+    out.join("\n").trim().to_string()
+}
+
+/// `[text](dest)` at the start of `s`: the text, destination and length.
+fn link_parts(s: &str) -> Option<(&str, &str, usize)> {
+    let text_end = s.find("](")?;
+    let text = s.strip_prefix('[')?.get(..text_end - 1)?;
+    let after = &s[text_end + 2..];
+    let dest = &after[..dest_len(after)];
+    // Skip an optional title up to the closing parenthesis
+    let close = dest.len() + after[dest.len()..].find(')')?;
+    Some((text, dest, text_end + 2 + close + 1))
+}
+
+/// Render inline Markdown (code, links, images, bold, italics) as HTML.
+/// All text is escaped.
+fn render_inline(text: &str) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with('`') {
+            if let Some(len) = code_span_len(rest) {
+                let ticks = rest.len() - rest.trim_start_matches('`').len();
+                out.push_str(&format!("<code>{}</code>", escape(rest[ticks..len - ticks].trim())));
+                i += len;
+                continue;
+            }
+        }
+        if rest.starts_with("![") {
+            if let Some((alt, dest, len)) = link_parts(&rest[1..]) {
+                if safe_url(dest) {
+                    out.push_str(&format!(r#"<img src="{}" alt="{}" loading="lazy">"#, escape(dest), escape(alt)));
+                } else {
+                    out.push_str(&escape(alt));
+                }
+                i += len + 1;
+                continue;
+            }
+        }
+        if rest.starts_with('[') {
+            if let Some((label, dest, len)) = link_parts(rest) {
+                let href = if safe_url(dest) { dest } else { "#" };
+                out.push_str(&format!(r#"<a href="{}" rel="nofollow">{}</a>"#, escape(href), render_inline(label)));
+                i += len;
+                continue;
+            }
+        }
+        if let Some(marker) = ["**", "__"].into_iter().find(|m| rest.starts_with(m)) {
+            if let Some(end) = rest[2..].find(marker).filter(|end| *end > 0) {
+                out.push_str(&format!("<strong>{}</strong>", render_inline(&rest[2..2 + end])));
+                i += end + 4;
+                continue;
+            }
+        }
+        if let Some(marker) = ['*', '_'].into_iter().find(|m| rest.starts_with(*m)) {
+            if let Some(end) = rest[1..].find(marker).filter(|end| *end > 0) {
+                out.push_str(&format!("<em>{}</em>", render_inline(&rest[1..1 + end])));
+                i += end + 2;
+                continue;
+            }
+        }
+        let c = rest.chars().next().unwrap_or_default();
+        out.push_str(&escape(&c.to_string()));
+        i += c.len_utf8();
+    }
+    out
+}
+
+/// Block being collected by `render_html`.
+enum Block {
+    None,
+    Paragraph(Vec<String>),
+    List(&'static str, Vec<String>),
+    Quote(Vec<String>),
+    Code(&'static str, Vec<String>),
+}
+
+fn close(block: &mut Block, out: &mut String) {
+    match std::mem::replace(block, Block::None) {
+        Block::None => {}
+        Block::Paragraph(lines) => out.push_str(&format!("<p>{}</p>\n", render_inline(&lines.join(" ")))),
+        Block::List(tag, items) => {
+            let items: String = items.iter().map(|item| format!("<li>{}</li>", render_inline(item))).collect();
+            out.push_str(&format!("<{tag}>{items}</{tag}>\n"));
+        }
+        Block::Quote(lines) => {
+            out.push_str(&format!("<blockquote><p>{}</p></blockquote>\n", render_inline(&lines.join(" "))))
+        }
+        Block::Code(_, lines) => out.push_str(&format!("<pre><code>{}</code></pre>\n", escape(&lines.join("\n")))),
+    }
+}
+
+/// List item marker at the start of a line: the list tag and the item text.
+fn list_item(line: &str) -> Option<(&'static str, &str)> {
+    if let Some(item) = ["- ", "* ", "+ "].into_iter().find_map(|m| line.strip_prefix(m)) {
+        return Some(("ul", item));
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    (digits > 0).then(|| line[digits..].strip_prefix(". ")).flatten().map(|item| ("ol", item))
+}
+
+/// Render docs Markdown as HTML for the listing page: headings (shifted
+/// down one level under the page's title), paragraphs, lists, block
+/// quotes, fenced code and inline formatting. Raw HTML is shown as text.
+pub fn render_html(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut block = Block::None;
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if let Block::Code(marker, lines) = &mut block {
+            if trimmed.starts_with(*marker) {
+                close(&mut block, &mut out);
+            } else {
+                lines.push(line.to_string());
+            }
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            close(&mut block, &mut out);
+            block = Block::Code(marker, Vec::new());
+            continue;
+        }
+        if trimmed.is_empty() {
+            close(&mut block, &mut out);
+            continue;
+        }
+        let level = trimmed.len() - trimmed.trim_start_matches('#').len();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            close(&mut block, &mut out);
+            let tag = format!("h{}", (level + 1).min(6));
+            out.push_str(&format!("<{tag}>{}</{tag}>\n", render_inline(trimmed[level..].trim())));
+            continue;
+        }
+        if let Some((tag, item)) = list_item(trimmed) {
+            match &mut block {
+                Block::List(current, items) if *current == tag => items.push(item.to_string()),
+                _ => {
+                    close(&mut block, &mut out);
+                    block = Block::List(tag, vec![item.to_string()]);
+                }
+            }
+            continue;
+        }
+        if let Some(quoted) = trimmed.strip_prefix('>') {
+            match &mut block {
+                Block::Quote(lines) => lines.push(quoted.trim().to_string()),
+                _ => {
+                    close(&mut block, &mut out);
+                    block = Block::Quote(vec![quoted.trim().to_string()]);
+                }
+            }
+            continue;
+        }
+        match &mut block {
+            Block::Paragraph(lines) => lines.push(trimmed.to_string()),
+            // A plain line right after a list item continues it
+            Block::List(_, items) => {
+                if let Some(last) = items.last_mut() {
+                    last.push(' ');
+                    last.push_str(trimmed);
+                }
+            }
+            _ => {
+                close(&mut block, &mut out);
+                block = Block::Paragraph(vec![trimmed.to_string()]);
+            }
+        }
+    }
+    close(&mut block, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_strips_scripts_and_unsafe_links() {
+        let md = "# Usage\n<script>alert(1)</script>Call it.\n<style>\nbody{}\n</style>\n<b onclick=\"x()\">bold</b> \
+                  [home](https://example.com) [bad](javascript:alert(1)) <https://example.com/x>\n\
+                  [ref]: javascript:alert(2)\n```html\n<script>kept in code</script>\n```\n`<b>` stays";
+        let clean = sanitize(md);
+        assert!(!clean.contains("alert(1)"));
+        assert!(!clean.contains("body{}"));
+        assert!(!clean.contains("onclick"));
+        assert!(clean.contains("Call it."));
+        assert!(clean.contains("bold [home](https://example.com) [bad](#) <https://example.com/x>"));
+        assert!(clean.contains("[ref]: #"));
+        assert!(clean.contains("<script>kept in code</script>"));
+        assert!(clean.contains("`<b>` stays"));
+
+        // Unterminated tags can't carry on to the next line
+        assert_eq!(sanitize("<img src=x\nonerror=alert(1)>"), "&lt;img src=x\nonerror=alert(1)>");
+    }
+
+    #[test]
+    fn sanitize_limits_images() {
+        let md: String = (0..MAX_DOCS_IMAGES + 2).map(|n| format!("![shot {n}](https://img.example/{n}.png)\n")).collect();
+        let clean = sanitize(&md);
+        assert_eq!(clean.matches("![").count(), MAX_DOCS_IMAGES);
+        assert!(clean.ends_with("shot 11"));
+        assert_eq!(sanitize("![x](data:image/png;base64,AAAA)"), "![x](#)");
+    }
+
+    #[test]
+    fn renders_escaped_html() {
+        let html = render_html(
+            "# Setup\n\nRun **this** and `a<b>`:\n\n```\n<tag>\n```\n\n- one\n- [two](https://x.example)\n\n> note\n\n<i>raw</i>",
+        );
+        assert!(html.contains("<h2>Setup</h2>"));
+        assert!(html.contains("<p>Run <strong>this</strong> and <code>a&lt;b&gt;</code>:</p>"));
+        assert!(html.contains("<pre><code>&lt;tag&gt;</code></pre>"));
+        assert!(html.contains(r#"<ul><li>one</li><li><a href="https://x.example" rel="nofollow">two</a></li></ul>"#));
+        assert!(html.contains("<blockquote><p>note</p></blockquote>"));
+        assert!(html.contains("<p>&lt;i&gt;raw&lt;/i&gt;</p>"));
+    }
+}
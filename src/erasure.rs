@@ -0,0 +1,262 @@
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+/// Placeholder written over identifiers when records are anonymized.
+pub const ERASED: &str = "erased";
+
+/// Author name given to anonymized submissions.
+const ANONYMOUS_AUTHOR: &str = "Anonymous";
+
+/// Whose data an erasure request targets.
+#[derive(Debug, Clone)]
+pub enum Subject {
+    /// An API key id: reviews, review reactions, views, click-throughs,
+    /// submissions and audit entries made with it.
+    Key(String),
+    /// A hashed anonymous reviewer fingerprint: reviews left without a key.
+    Fingerprint(String),
+    /// An author or account email: submissions and the account itself.
+    Email(String),
+}
+
+impl Subject {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Subject::Key(_) => "key_id",
+            Subject::Fingerprint(_) => "fingerprint",
+            Subject::Email(_) => "email",
+        }
+    }
+
+    fn value(&self) -> &str {
+        match self {
+            Subject::Key(v) | Subject::Fingerprint(v) | Subject::Email(v) => v,
+        }
+    }
+}
+
+/// What an erasure touched. Counts are rows deleted, or rows rewritten
+/// when anonymizing.
+#[derive(Debug, Default)]
+pub struct ErasureReport {
+    pub reviews: usize,
+    pub views: usize,
+    pub submissions: Vec<String>,
+    pub content_rejections: usize,
+    pub audit_entries: usize,
+    pub events: usize,
+    pub accounts: usize,
+    pub jobs: usize,
+}
+
+impl ErasureReport {
+    pub fn counts(&self) -> Value {
+        json!({
+            "reviews": self.reviews,
+            "views": self.views,
+            "submissions": self.submissions.len(),
+            "content_rejections": self.content_rejections,
+            "audit_entries": self.audit_entries,
+            "events": self.events,
+            "accounts": self.accounts,
+            "jobs": self.jobs,
+        })
+    }
+}
+
+fn ids(conn: &Connection, sql: &str, value: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(rusqlite::params![value], |r| r.get(0))?;
+    rows.collect()
+}
+
+/// Delete or anonymize everything stored about `subject`. Run inside a
+/// transaction: a failure part way leaves the caller to roll back.
+///
+/// Deleting removes the subject's reviews, views, click-throughs, submitted
+/// apps (with all their dependent records), rejected submissions, audit
+/// entries, stored events and account. Anonymizing keeps the rows but replaces identifiers
+/// with `erased` and author details with `Anonymous`; accounts, login
+/// state and queued emails to the address are always deleted.
+pub fn erase(conn: &Connection, subject: &Subject, anonymize: bool) -> rusqlite::Result<ErasureReport> {
+    let value = subject.value();
+    let mut report = ErasureReport::default();
+
+    // Reviews
+    let review_filter = match subject {
+        Subject::Key(_) => Some("reviewer_key_id = ?1"),
+        Subject::Fingerprint(_) => Some("reviewer_fingerprint = ?1"),
+        Subject::Email(_) => None,
+    };
+    if let Some(filter) = review_filter {
+        let rated_apps = ids(
+            conn,
+            &format!("SELECT DISTINCT app_id FROM reviews WHERE {}", filter),
+            value,
+        )?;
+        if anonymize {
+            conn.execute(
+                &format!(
+                    "UPDATE review_versions SET reviewer_name = NULL WHERE review_id IN (SELECT id FROM reviews WHERE {})",
+                    filter
+                ),
+                rusqlite::params![value],
+            )?;
+        } else {
+            conn.execute(
+                &format!("DELETE FROM review_versions WHERE review_id IN (SELECT id FROM reviews WHERE {})", filter),
+                rusqlite::params![value],
+            )?;
+            conn.execute(
+                &format!("DELETE FROM review_reactions WHERE review_id IN (SELECT id FROM reviews WHERE {})", filter),
+                rusqlite::params![value],
+            )?;
+        }
+        report.reviews = if anonymize {
+            conn.execute(
+                &format!(
+                    "UPDATE reviews SET reviewer_key_id = NULL, reviewer_fingerprint = NULL, reviewer_name = NULL, secret_hash = NULL WHERE {}",
+                    filter
+                ),
+                rusqlite::params![value],
+            )?
+        } else {
+            conn.execute(&format!("DELETE FROM reviews WHERE {}", filter), rusqlite::params![value])?
+        };
+        if !anonymize {
+            for app_id in &rated_apps {
+                conn.execute(
+                    "UPDATE apps SET
+                       avg_rating = (SELECT COALESCE(AVG(CAST(rating AS REAL)), 0.0) FROM reviews WHERE app_id = ?1),
+                       review_count = (SELECT COUNT(*) FROM reviews WHERE app_id = ?1),
+                       updated_at = datetime('now')
+                     WHERE id = ?1",
+                    rusqlite::params![app_id],
+                )?;
+            }
+        }
+    }
+
+    // Views and click-throughs
+    if let Subject::Key(_) = subject {
+        report.views = if anonymize {
+            conn.execute(
+                "UPDATE app_views SET viewer_key_id = ?2 WHERE viewer_key_id = ?1",
+                rusqlite::params![value, ERASED],
+            )? + conn.execute(
+                "UPDATE app_clicks SET clicker_key_id = ?2 WHERE clicker_key_id = ?1",
+                rusqlite::params![value, ERASED],
+            )?
+        } else {
+            conn.execute("DELETE FROM app_views WHERE viewer_key_id = ?1", rusqlite::params![value])?
+                + conn.execute("DELETE FROM app_clicks WHERE clicker_key_id = ?1", rusqlite::params![value])?
+        };
+        // Reactions stay counted when anonymizing, each under its own opaque reactor
+        if anonymize {
+            conn.execute(
+                "UPDATE review_reactions SET reactor = 'erased:' || lower(hex(randomblob(8))) WHERE reactor = 'key:' || ?1",
+                rusqlite::params![value],
+            )?;
+        } else {
+            conn.execute("DELETE FROM review_reactions WHERE reactor = 'key:' || ?1", rusqlite::params![value])?;
+        }
+    }
+
+    // Submissions, and for emails the account behind them
+    report.submissions = match subject {
+        Subject::Key(_) => ids(conn, "SELECT id FROM apps WHERE submitted_by_key_id = ?1", value)?,
+        Subject::Email(_) => ids(
+            conn,
+            "SELECT id FROM apps WHERE LOWER(author_email) = LOWER(?1)
+                OR account_id IN (SELECT id FROM accounts WHERE LOWER(email) = LOWER(?1))",
+            value,
+        )?,
+        Subject::Fingerprint(_) => Vec::new(),
+    };
+    for app_id in &report.submissions {
+        if anonymize {
+            conn.execute(
+                "UPDATE apps SET author_name = ?2, author_url = NULL, author_email = NULL, account_id = NULL,
+                    submitted_by_key_id = CASE WHEN submitted_by_key_id = ?3 THEN ?4 ELSE submitted_by_key_id END,
+                    updated_at = datetime('now')
+                 WHERE id = ?1",
+                rusqlite::params![app_id, ANONYMOUS_AUTHOR, value, ERASED],
+            )?;
+            conn.execute(
+                "UPDATE app_revisions SET snapshot = json_set(snapshot, '$.author_name', ?2, '$.author_url', NULL)
+                 WHERE app_id = ?1",
+                rusqlite::params![app_id, ANONYMOUS_AUTHOR],
+            )?;
+        } else {
+            crate::routes::delete_app_records(conn, app_id)?;
+        }
+    }
+    if anonymize {
+        conn.execute(
+            "UPDATE app_revisions SET editor_id = ?2 WHERE editor_id = ?1",
+            rusqlite::params![value, ERASED],
+        )?;
+    }
+    if let Subject::Email(_) = subject {
+        let accounts = ids(conn, "SELECT id FROM accounts WHERE LOWER(email) = LOWER(?1)", value)?;
+        for account_id in &accounts {
+            conn.execute(
+                "DELETE FROM account_sessions WHERE account_id = ?1",
+                rusqlite::params![account_id],
+            )?;
+            report.accounts += conn.execute("DELETE FROM accounts WHERE id = ?1", rusqlite::params![account_id])?;
+        }
+        conn.execute(
+            "DELETE FROM login_codes WHERE LOWER(email) = LOWER(?1)",
+            rusqlite::params![value],
+        )?;
+        // Notification emails to the address, sent or not
+        report.jobs = conn.execute(
+            "DELETE FROM jobs WHERE kind = 'email' AND LOWER(json_extract(payload, '$.to')) = LOWER(?1)",
+            rusqlite::params![value],
+        )?;
+    }
+
+    // Content the filter rejected before it became a submission or review
+    if let Subject::Key(_) = subject {
+        report.content_rejections = if anonymize {
+            conn.execute(
+                "UPDATE content_rejections SET submitted_by_key_id = NULL WHERE submitted_by_key_id = ?1",
+                rusqlite::params![value],
+            )?
+        } else {
+            conn.execute(
+                "DELETE FROM content_rejections WHERE submitted_by_key_id = ?1",
+                rusqlite::params![value],
+            )?
+        };
+    }
+
+    // Audit entries made by the subject or mentioning it
+    report.audit_entries = if anonymize {
+        conn.execute(
+            "UPDATE audit_log SET
+                actor_key_id = CASE WHEN actor_key_id = ?1 THEN ?2 ELSE actor_key_id END,
+                details = replace(details, ?1, ?2)
+             WHERE actor_key_id = ?1 OR instr(details, ?1) > 0",
+            rusqlite::params![value, ERASED],
+        )?
+    } else {
+        conn.execute(
+            "DELETE FROM audit_log WHERE actor_key_id = ?1 OR instr(details, ?1) > 0",
+            rusqlite::params![value],
+        )?
+    };
+
+    // Stored event history mentioning the subject
+    report.events = if anonymize {
+        conn.execute(
+            "UPDATE events SET data = replace(data, ?1, ?2) WHERE instr(data, ?1) > 0",
+            rusqlite::params![value, ERASED],
+        )?
+    } else {
+        conn.execute("DELETE FROM events WHERE instr(data, ?1) > 0", rusqlite::params![value])?
+    };
+
+    Ok(report)
+}
@@ -0,0 +1,357 @@
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{ContentType, Header};
+use rocket::{Request, Response};
+use serde_json::Value;
+
+use crate::i18n::AcceptLanguage;
+
+/// Translated error messages per language, keyed by error code. English
+/// messages are the code descriptions in `errors.rs`.
+const CATALOG: &[(&str, &[(&str, &str)])] = &[("de", DE), ("es", ES), ("fr", FR)];
+
+/// Languages error messages are available in, besides English.
+pub fn languages() -> impl Iterator<Item = &'static str> {
+    CATALOG.iter().map(|(lang, _)| *lang)
+}
+
+/// The message for `code` in `lang`, if translated.
+pub fn translate(code: &str, lang: &str) -> Option<&'static str> {
+    let (_, messages) = CATALOG.iter().find(|(l, _)| *l == lang)?;
+    messages.iter().find(|(c, _)| *c == code).map(|(_, m)| *m)
+}
+
+/// The first requested language with translations, trying each tag exactly
+/// and then by its primary subtag (`de-at` → `de`). `None` when English is
+/// preferred or nothing matches.
+pub fn negotiate(requested: &AcceptLanguage) -> Option<&'static str> {
+    for lang in &requested.0 {
+        let primary = lang.split('-').next().unwrap_or(lang);
+        if primary == "en" {
+            return None;
+        }
+        if let Some(found) = languages().find(|l| l == lang || *l == primary) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// Rewrites JSON error messages into the caller's `Accept-Language`. The
+/// translation replaces `message`; the English text moves to `message_en`,
+/// since handlers often give more detail than the code's generic message.
+/// Covers handler, guard and catcher errors alike.
+pub struct LocalizedErrors;
+
+#[rocket::async_trait]
+impl Fairing for LocalizedErrors {
+    fn info(&self) -> Info {
+        Info {
+            name: "Localized Error Messages",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if response.status().code < 400 || response.content_type() != Some(ContentType::JSON) {
+            return;
+        }
+        let Some(lang) = request
+            .headers()
+            .get_one("Accept-Language")
+            .map(AcceptLanguage::parse)
+            .and_then(|langs| negotiate(&langs))
+        else {
+            return;
+        };
+
+        let Ok(body) = response.body_mut().to_string().await else {
+            return;
+        };
+        let mut error: Value = match serde_json::from_str(&body) {
+            Ok(v) => v,
+            Err(_) => {
+                response.set_sized_body(body.len(), std::io::Cursor::new(body));
+                return;
+            }
+        };
+        if let Some(message) = error["error"].as_str().and_then(|code| translate(code, lang)) {
+            error["message_en"] = error["message"].take();
+            error["message"] = Value::from(message);
+            response.set_header(Header::new("Content-Language", lang));
+        }
+        let body = error.to_string();
+        response.set_sized_body(body.len(), std::io::Cursor::new(body));
+    }
+}
+
+const DE: &[(&str, &str)] = &[
+    ("BAD_REQUEST", "Ungültige Anfrage"),
+    ("UNAUTHORIZED", "API-Schlüssel fehlt oder ist ungültig"),
+    ("FORBIDDEN", "Keine Berechtigung, diese Ressource zu ändern"),
+    ("NOT_FOUND", "Ressource nicht gefunden"),
+    ("UNPROCESSABLE_ENTITY", "Der Anfragetext konnte nicht verarbeitet werden"),
+    ("PAYLOAD_TOO_LARGE", "Der Anfragetext überschreitet die zulässige Größe"),
+    ("RATE_LIMITED", "Anfragelimit überschritten"),
+    ("INTERNAL_ERROR", "Interner Serverfehler"),
+    ("DB_ERROR", "Datenbankfehler"),
+    ("KEY_EXPIRED", "Der API-Schlüssel ist abgelaufen. Bitte einen Admin, ihn zu erneuern."),
+    ("TOKEN_EXPIRED", "Das Zugriffstoken ist abgelaufen. Fordere unter /oauth/token ein neues an."),
+    ("ADMIN_REQUIRED", "Admin-API-Schlüssel erforderlich"),
+    ("INVALID_EXPIRY", "expires_at muss ein zukünftiger RFC-3339-Zeitstempel sein"),
+    ("INVALID_RATE_LIMIT", "rate_limit, burst oder exempt_routes ist ungültig"),
+    ("INVALID_PAGE_SIZE", "default_page_size und max_page_size müssen zwischen 0 und 1000 liegen"),
+    ("INVALID_EMAIL", "Eine gültige E-Mail-Adresse ist erforderlich"),
+    ("INVALID_CODE", "Der Anmeldecode ist falsch"),
+    ("CODE_EXPIRED", "Der Anmeldecode ist abgelaufen oder wurde nie angefordert"),
+    ("FIELD_TOO_LONG", "Ein Feld überschreitet die maximale Länge"),
+    ("INVALID_PROTOCOL", "Unbekanntes Protokoll"),
+    ("INVALID_CATEGORY", "Unbekannter oder fehlerhafter Kategoriepfad"),
+    ("INVALID_STATUS", "Unbekannter App-Status"),
+    ("INVALID_SLUG", "Der Slug muss aus 3 bis 64 Kleinbuchstaben, Ziffern und Bindestrichen bestehen"),
+    ("INVALID_TAG", "Tags dürfen nicht leer sein und die maximale Tag-Länge nicht überschreiten"),
+    ("INVALID_VISIBILITY", "Sichtbarkeit muss public, unlisted oder private sein"),
+    ("INVALID_RATING", "Die Bewertung muss zwischen 1 und 5 liegen"),
+    ("INVALID_REACTION", "Unbekannte Reaktion auf eine Bewertung"),
+    ("INVALID_URL", "Die URL muss mit http:// oder https:// beginnen"),
+    ("INVALID_EVENT", "Unbekannter Webhook-Ereignistyp"),
+    ("INVALID_PERIOD", "Unbekannter Statistikzeitraum"),
+    ("INVALID_METRIC", "Unbekannte Kennzahl für den Statistikverlauf"),
+    ("INVALID_SINCE", "since muss ein RFC-3339-Zeitstempel sein"),
+    ("INVALID_TIMESTAMP", "Zeitraumfilter müssen RFC-3339-Zeitstempel sein"),
+    ("INVALID_LANGUAGE", "Ungültiger Sprachcode"),
+    ("DEFAULT_LANGUAGE", "Inhalte in der Standardsprache werden direkt an der App bearbeitet"),
+    ("INVALID_REPLACEMENT", "Die Ersatz-App fehlt oder ist ungültig"),
+    ("INVALID_ACTION", "Unbekannte Sammelaktion"),
+    ("INVALID_FIELD", "Unbekanntes Feld im Parameter fields"),
+    ("INVALID_BADGE", "Slug, Bezeichnung, Farbe oder Beschreibung des Abzeichens ist ungültig"),
+    ("REASON_REQUIRED", "Für diese Aktion ist eine Begründung erforderlich"),
+    ("NO_CHANGES", "Keine Felder zum Aktualisieren"),
+    ("NO_APPS", "Keine App-IDs angegeben"),
+    ("INVALID_RANK_BOOST", "rank_boost muss zwischen -10 und 10 liegen"),
+    ("INVALID_FEATURE_WINDOW", "featured_until muss ein zukünftiger RFC-3339-Zeitstempel nach featured_from sein"),
+    ("INVALID_ENDORSEMENT", "Beide Apps müssen freigegeben und verschieden sein"),
+    ("INVALID_KEY_ENDPOINT", "Schlüssel-Endpunkte brauchen eine bekannte Methode, einen Pfad mit / am Anfang und eine Beschreibung"),
+    ("INVALID_DEPENDENCY", "depends_on darf bis zu 20 andere, nicht private Apps enthalten"),
+    ("INVALID_HEALTH_EXPECTATION", "health_expectation braucht ein nicht leeres contains oder einen JSON-Pointer, der mit / beginnt"),
+    ("INVALID_GRACE_PERIOD", "grace_period_secs darf höchstens 30 Tage betragen"),
+    ("INVALID_TIMEOUT", "timeout_ms muss zwischen 100 und 30000 liegen"),
+    ("INVALID_REVIEWER", "Der Prüfer muss ein aktiver Admin-API-Schlüssel sein"),
+    ("INVALID_SUBJECT", "Genau eines von key_id, fingerprint oder email ist erforderlich"),
+    ("TOO_MANY_APPS", "Zu viele Apps in einer Sammelanfrage"),
+    ("NO_URL", "Die App hat keine URL zum Prüfen"),
+    ("PRECONDITION_FAILED", "If-Match stimmt nicht mit der aktuellen Revision der App überein"),
+    ("CONTENT_REJECTED", "Der Text wurde vom Inhaltsfilter abgelehnt"),
+    ("POLICY_REJECTED", "Der Eintrag wurde vom Richtliniendienst des Verzeichnisses abgelehnt"),
+    ("CAPTCHA_REQUIRED", "captcha_token ist für anonyme Anfragen erforderlich"),
+    ("CAPTCHA_FAILED", "CAPTCHA-Prüfung fehlgeschlagen"),
+    ("ALREADY_APPROVED", "Die App ist bereits freigegeben"),
+    ("ALREADY_REJECTED", "Die App ist bereits abgelehnt"),
+    ("ALREADY_DEPRECATED", "Die App ist bereits als veraltet markiert"),
+    ("NOT_DEPRECATED", "Die App ist nicht als veraltet markiert"),
+    ("NOT_PENDING", "Die App wartet nicht auf Prüfung"),
+    ("NOT_DRAFT", "Die App ist kein Entwurf"),
+    ("INVALID_TRANSITION", "Statuswechsel vom aktuellen Status aus nicht erlaubt"),
+    ("ARCHIVED", "Die App ist archiviert und schreibgeschützt"),
+    ("ALREADY_ARCHIVED", "Die App ist bereits archiviert"),
+    ("NOT_ARCHIVED", "Die App ist nicht archiviert"),
+    ("BADGE_EXISTS", "Ein Abzeichen mit diesem Slug existiert bereits"),
+    ("SLUG_TAKEN", "Ein anderer Eintrag verwendet diesen Slug bereits"),
+    ("TAG_EXISTS", "Einträge verwenden diesen Tag bereits; führe stattdessen zusammen"),
+    ("SEED_EXISTS", "Diese Discovery-Seed-URL ist bereits registriert"),
+    ("REPLACEMENT_CYCLE", "Die Ersatzkette führt auf sich selbst zurück"),
+    ("LAST_ADMIN", "Der letzte aktive Admin-Schlüssel muss Admin bleiben"),
+    ("CAPTCHA_UNAVAILABLE", "Der CAPTCHA-Anbieter ist nicht erreichbar; bitte erneut versuchen"),
+    ("MAINTENANCE", "Schreibzugriffe sind wegen Wartung pausiert; bitte später erneut versuchen"),
+    ("READ_ONLY", "Diese Instanz ist ein schreibgeschütztes Replikat; Schreibzugriffe bitte an die primäre Instanz senden"),
+    ("REVIEW_LIMIT_EXCEEDED", "Limit für anonyme Bewertungen erreicht"),
+    ("LOGIN_LIMIT_EXCEEDED", "Zu viele Anmeldeversuche; bitte später erneut versuchen"),
+];
+
+const ES: &[(&str, &str)] = &[
+    ("BAD_REQUEST", "Solicitud mal formada"),
+    ("UNAUTHORIZED", "Falta la clave de API o no es válida"),
+    ("FORBIDDEN", "No tienes permiso para modificar este recurso"),
+    ("NOT_FOUND", "Recurso no encontrado"),
+    ("UNPROCESSABLE_ENTITY", "No se pudo procesar el cuerpo de la solicitud"),
+    ("PAYLOAD_TOO_LARGE", "El cuerpo de la solicitud supera el tamaño máximo permitido"),
+    ("RATE_LIMITED", "Límite de solicitudes superado"),
+    ("INTERNAL_ERROR", "Error interno del servidor"),
+    ("DB_ERROR", "Error de base de datos"),
+    ("KEY_EXPIRED", "La clave de API ha caducado. Pide a un administrador que la renueve."),
+    ("TOKEN_EXPIRED", "El token de acceso ha caducado. Solicita uno nuevo en /oauth/token."),
+    ("ADMIN_REQUIRED", "Se requiere una clave de API de administrador"),
+    ("INVALID_EXPIRY", "expires_at debe ser una marca de tiempo RFC 3339 futura"),
+    ("INVALID_RATE_LIMIT", "rate_limit, burst o exempt_routes no es válido"),
+    ("INVALID_PAGE_SIZE", "default_page_size y max_page_size deben estar entre 0 y 1000"),
+    ("INVALID_EMAIL", "Se requiere una dirección de correo válida"),
+    ("INVALID_CODE", "El código de acceso es incorrecto"),
+    ("CODE_EXPIRED", "El código de acceso caducó o nunca se solicitó"),
+    ("FIELD_TOO_LONG", "Un campo supera su longitud máxima"),
+    ("INVALID_PROTOCOL", "Protocolo desconocido"),
+    ("INVALID_CATEGORY", "Ruta de categoría desconocida o mal formada"),
+    ("INVALID_STATUS", "Estado de app desconocido"),
+    ("INVALID_SLUG", "El slug debe tener de 3 a 64 letras minúsculas, dígitos y guiones"),
+    ("INVALID_TAG", "Las etiquetas no pueden estar vacías ni superar la longitud máxima"),
+    ("INVALID_VISIBILITY", "La visibilidad debe ser public, unlisted o private"),
+    ("INVALID_RATING", "La valoración debe estar entre 1 y 5"),
+    ("INVALID_REACTION", "Reacción a la reseña desconocida"),
+    ("INVALID_URL", "La URL debe empezar por http:// o https://"),
+    ("INVALID_EVENT", "Tipo de evento de webhook desconocido"),
+    ("INVALID_PERIOD", "Periodo de estadísticas desconocido"),
+    ("INVALID_METRIC", "Métrica del historial de estadísticas desconocida"),
+    ("INVALID_SINCE", "since debe ser una marca de tiempo RFC 3339"),
+    ("INVALID_TIMESTAMP", "Los filtros de intervalo de tiempo deben ser marcas de tiempo RFC 3339"),
+    ("INVALID_LANGUAGE", "Etiqueta de idioma no válida"),
+    ("DEFAULT_LANGUAGE", "El contenido en el idioma predeterminado se edita en la propia app"),
+    ("INVALID_REPLACEMENT", "La app de reemplazo falta o no es válida"),
+    ("INVALID_ACTION", "Acción masiva desconocida"),
+    ("INVALID_FIELD", "Campo desconocido en el parámetro fields"),
+    ("INVALID_BADGE", "El slug, la etiqueta, el color o la descripción de la insignia no es válido"),
+    ("REASON_REQUIRED", "Esta acción requiere un motivo"),
+    ("NO_CHANGES", "No hay campos que actualizar"),
+    ("NO_APPS", "No se indicaron IDs de apps"),
+    ("INVALID_RANK_BOOST", "rank_boost debe estar entre -10 y 10"),
+    ("INVALID_FEATURE_WINDOW", "featured_until debe ser una marca de tiempo RFC 3339 futura posterior a featured_from"),
+    ("INVALID_ENDORSEMENT", "Ambas apps deben estar aprobadas y ser distintas"),
+    ("INVALID_KEY_ENDPOINT", "Los endpoints clave necesitan un método conocido, una ruta que empiece por / y una descripción"),
+    ("INVALID_DEPENDENCY", "depends_on debe listar hasta 20 otras apps no privadas"),
+    ("INVALID_HEALTH_EXPECTATION", "health_expectation necesita un contains no vacío o un puntero JSON que empiece por /"),
+    ("INVALID_GRACE_PERIOD", "grace_period_secs no puede superar los 30 días"),
+    ("INVALID_TIMEOUT", "timeout_ms debe estar entre 100 y 30000"),
+    ("INVALID_REVIEWER", "El revisor debe ser una clave de API de administrador activa"),
+    ("INVALID_SUBJECT", "Se requiere exactamente uno de key_id, fingerprint o email"),
+    ("TOO_MANY_APPS", "Demasiadas apps en una solicitud masiva"),
+    ("NO_URL", "La app no tiene ninguna URL que comprobar"),
+    ("PRECONDITION_FAILED", "If-Match no coincide con la revisión actual de la app"),
+    ("CONTENT_REJECTED", "El filtro de contenido rechazó el texto"),
+    ("POLICY_REJECTED", "El servicio de políticas del directorio rechazó la ficha"),
+    ("CAPTCHA_REQUIRED", "captcha_token es obligatorio en las solicitudes anónimas"),
+    ("CAPTCHA_FAILED", "La verificación CAPTCHA falló"),
+    ("ALREADY_APPROVED", "La app ya está aprobada"),
+    ("ALREADY_REJECTED", "La app ya está rechazada"),
+    ("ALREADY_DEPRECATED", "La app ya está obsoleta"),
+    ("NOT_DEPRECATED", "La app no está obsoleta"),
+    ("NOT_PENDING", "La app no está pendiente de revisión"),
+    ("NOT_DRAFT", "La app no es un borrador"),
+    ("INVALID_TRANSITION", "No se permite el cambio de estado desde el estado actual"),
+    ("ARCHIVED", "La app está archivada y es de solo lectura"),
+    ("ALREADY_ARCHIVED", "La app ya está archivada"),
+    ("NOT_ARCHIVED", "La app no está archivada"),
+    ("BADGE_EXISTS", "Ya existe una insignia con este slug"),
+    ("SLUG_TAKEN", "Otra ficha ya usa este slug"),
+    ("TAG_EXISTS", "Ya hay fichas con esta etiqueta; combínalas en ella"),
+    ("SEED_EXISTS", "Esta URL semilla de descubrimiento ya está registrada"),
+    ("REPLACEMENT_CYCLE", "La cadena de reemplazos vuelve sobre sí misma"),
+    ("LAST_ADMIN", "La última clave de administrador activa debe seguir siendo de administrador"),
+    ("CAPTCHA_UNAVAILABLE", "No se pudo contactar con el proveedor de CAPTCHA; inténtalo de nuevo"),
+    ("MAINTENANCE", "Las escrituras están en pausa por mantenimiento; inténtalo más tarde"),
+    ("READ_ONLY", "Esta instancia es una réplica de solo lectura; envía las escrituras a la instancia principal"),
+    ("REVIEW_LIMIT_EXCEEDED", "Se alcanzó el límite de reseñas anónimas"),
+    ("LOGIN_LIMIT_EXCEEDED", "Demasiados intentos de inicio de sesión; inténtelo más tarde"),
+];
+
+const FR: &[(&str, &str)] = &[
+    ("BAD_REQUEST", "Requête mal formée"),
+    ("UNAUTHORIZED", "Clé d'API manquante ou invalide"),
+    ("FORBIDDEN", "Vous n'avez pas le droit de modifier cette ressource"),
+    ("NOT_FOUND", "Ressource introuvable"),
+    ("UNPROCESSABLE_ENTITY", "Le corps de la requête n'a pas pu être analysé"),
+    ("PAYLOAD_TOO_LARGE", "Le corps de la requête dépasse la taille maximale autorisée"),
+    ("RATE_LIMITED", "Limite de requêtes dépassée"),
+    ("INTERNAL_ERROR", "Erreur interne du serveur"),
+    ("DB_ERROR", "Erreur de base de données"),
+    ("KEY_EXPIRED", "La clé d'API a expiré. Demandez à un administrateur de la renouveler."),
+    ("TOKEN_EXPIRED", "Le jeton d’accès a expiré. Demandez-en un nouveau via /oauth/token."),
+    ("ADMIN_REQUIRED", "Clé d'API administrateur requise"),
+    ("INVALID_EXPIRY", "expires_at doit être un horodatage RFC 3339 dans le futur"),
+    ("INVALID_RATE_LIMIT", "rate_limit, burst ou exempt_routes est invalide"),
+    ("INVALID_PAGE_SIZE", "default_page_size et max_page_size doivent être compris entre 0 et 1000"),
+    ("INVALID_EMAIL", "Une adresse e-mail valide est requise"),
+    ("INVALID_CODE", "Le code de connexion est incorrect"),
+    ("CODE_EXPIRED", "Le code de connexion a expiré ou n'a jamais été demandé"),
+    ("FIELD_TOO_LONG", "Un champ dépasse sa longueur maximale"),
+    ("INVALID_PROTOCOL", "Protocole inconnu"),
+    ("INVALID_CATEGORY", "Chemin de catégorie inconnu ou mal formé"),
+    ("INVALID_STATUS", "Statut d'app inconnu"),
+    ("INVALID_SLUG", "Le slug doit compter 3 à 64 lettres minuscules, chiffres et tirets"),
+    ("INVALID_TAG", "Les tags ne peuvent pas être vides ni dépasser la longueur maximale"),
+    ("INVALID_VISIBILITY", "La visibilité doit être public, unlisted ou private"),
+    ("INVALID_RATING", "La note doit être comprise entre 1 et 5"),
+    ("INVALID_REACTION", "Réaction à l'avis inconnue"),
+    ("INVALID_URL", "L'URL doit commencer par http:// ou https://"),
+    ("INVALID_EVENT", "Type d'événement webhook inconnu"),
+    ("INVALID_PERIOD", "Période de statistiques inconnue"),
+    ("INVALID_METRIC", "Métrique d'historique des statistiques inconnue"),
+    ("INVALID_SINCE", "since doit être un horodatage RFC 3339"),
+    ("INVALID_TIMESTAMP", "Les filtres de période doivent être des horodatages RFC 3339"),
+    ("INVALID_LANGUAGE", "Étiquette de langue invalide"),
+    ("DEFAULT_LANGUAGE", "Le contenu dans la langue par défaut se modifie sur l'app elle-même"),
+    ("INVALID_REPLACEMENT", "L'app de remplacement est manquante ou invalide"),
+    ("INVALID_ACTION", "Action groupée inconnue"),
+    ("INVALID_FIELD", "Champ inconnu dans le paramètre fields"),
+    ("INVALID_BADGE", "Le slug, le libellé, la couleur ou la description du badge est invalide"),
+    ("REASON_REQUIRED", "Un motif est requis pour cette action"),
+    ("NO_CHANGES", "Aucun champ à mettre à jour"),
+    ("NO_APPS", "Aucun identifiant d'app fourni"),
+    ("INVALID_RANK_BOOST", "rank_boost doit être compris entre -10 et 10"),
+    ("INVALID_FEATURE_WINDOW", "featured_until doit être un horodatage RFC 3339 futur postérieur à featured_from"),
+    ("INVALID_ENDORSEMENT", "Les deux apps doivent être approuvées et différentes"),
+    ("INVALID_KEY_ENDPOINT", "Les endpoints clés nécessitent une méthode connue, un chemin commençant par / et une description"),
+    ("INVALID_DEPENDENCY", "depends_on doit lister jusqu’à 20 autres apps non privées"),
+    ("INVALID_HEALTH_EXPECTATION", "health_expectation nécessite un contains non vide ou un pointeur JSON commençant par /"),
+    ("INVALID_GRACE_PERIOD", "grace_period_secs ne peut pas dépasser 30 jours"),
+    ("INVALID_TIMEOUT", "timeout_ms doit être compris entre 100 et 30000"),
+    ("INVALID_REVIEWER", "Le relecteur doit être une clé d'API administrateur active"),
+    ("INVALID_SUBJECT", "Exactement un des champs key_id, fingerprint ou email est requis"),
+    ("TOO_MANY_APPS", "Trop d'apps dans une seule requête groupée"),
+    ("NO_URL", "L'app n'a aucune URL à vérifier"),
+    ("PRECONDITION_FAILED", "If-Match ne correspond pas à la révision actuelle de l'app"),
+    ("CONTENT_REJECTED", "Le texte a été refusé par le filtre de contenu"),
+    ("POLICY_REJECTED", "La fiche a été refusée par le service de règles de l'annuaire"),
+    ("CAPTCHA_REQUIRED", "captcha_token est requis pour les requêtes anonymes"),
+    ("CAPTCHA_FAILED", "La vérification CAPTCHA a échoué"),
+    ("ALREADY_APPROVED", "L'app est déjà approuvée"),
+    ("ALREADY_REJECTED", "L'app est déjà refusée"),
+    ("ALREADY_DEPRECATED", "L'app est déjà obsolète"),
+    ("NOT_DEPRECATED", "L'app n'est pas obsolète"),
+    ("NOT_PENDING", "L'app n'est pas en attente de validation"),
+    ("NOT_DRAFT", "L'app n'est pas un brouillon"),
+    ("INVALID_TRANSITION", "Changement de statut impossible depuis le statut actuel"),
+    ("ARCHIVED", "L'app est archivée et en lecture seule"),
+    ("ALREADY_ARCHIVED", "L'app est déjà archivée"),
+    ("NOT_ARCHIVED", "L'app n'est pas archivée"),
+    ("BADGE_EXISTS", "Un badge avec ce slug existe déjà"),
+    ("SLUG_TAKEN", "Une autre fiche utilise déjà ce slug"),
+    ("TAG_EXISTS", "Des fiches utilisent déjà ce tag ; fusionnez plutôt vers lui"),
+    ("SEED_EXISTS", "Cette URL source de découverte est déjà enregistrée"),
+    ("REPLACEMENT_CYCLE", "La chaîne de remplacement revient sur elle-même"),
+    ("LAST_ADMIN", "La dernière clé administrateur active doit rester administrateur"),
+    ("CAPTCHA_UNAVAILABLE", "Le fournisseur CAPTCHA est injoignable ; réessayez"),
+    ("MAINTENANCE", "Les écritures sont suspendues pour maintenance ; réessayez plus tard"),
+    ("READ_ONLY", "Cette instance est une réplique en lecture seule ; envoyez les écritures à l'instance principale"),
+    ("REVIEW_LIMIT_EXCEEDED", "Limite d'avis anonymes atteinte"),
+    ("LOGIN_LIMIT_EXCEEDED", "Trop de tentatives de connexion ; réessayez plus tard"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ErrorCode;
+
+    #[test]
+    fn every_code_is_translated() {
+        for lang in languages() {
+            for code in ErrorCode::ALL {
+                assert!(translate(code.as_str(), lang).is_some(), "{} missing in {lang}", code.as_str());
+            }
+        }
+    }
+
+    #[test]
+    fn negotiates_preferred_language() {
+        assert_eq!(negotiate(&AcceptLanguage::parse("de-AT, fr;q=0.5")), Some("de"));
+        assert_eq!(negotiate(&AcceptLanguage::parse("ja, es;q=0.8")), Some("es"));
+        assert_eq!(negotiate(&AcceptLanguage::parse("en-US, fr;q=0.9")), None);
+        assert_eq!(negotiate(&AcceptLanguage::parse("ja")), None);
+    }
+}
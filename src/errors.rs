@@ -0,0 +1,244 @@
+use rocket::http::Status;
+use rocket::response::{self, Responder};
+use rocket::serde::json::Json;
+use rocket::Request;
+use serde_json::{json, Map, Value};
+
+use crate::error_messages;
+
+macro_rules! error_codes {
+    ($($variant:ident => ($code:literal, $status:ident, $description:literal)),* $(,)?) => {
+        /// Every machine-readable error code the API can return.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum ErrorCode {
+            $($variant),*
+        }
+
+        impl ErrorCode {
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant),*];
+
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $code),*
+                }
+            }
+
+            /// HTTP status this code is returned with.
+            pub fn status(self) -> Status {
+                match self {
+                    $(ErrorCode::$variant => Status::$status),*
+                }
+            }
+
+            /// Human-readable meaning, also used as the default message.
+            pub fn description(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $description),*
+                }
+            }
+
+            /// Stable key for client-side translations, e.g. `errors.not_found`.
+            pub fn message_key(self) -> String {
+                format!("errors.{}", self.as_str().to_lowercase())
+            }
+        }
+    };
+}
+
+error_codes! {
+    // Generic (also produced by catchers)
+    BadRequest => ("BAD_REQUEST", BadRequest, "Malformed request"),
+    Unauthorized => ("UNAUTHORIZED", Unauthorized, "Missing or invalid API key"),
+    Forbidden => ("FORBIDDEN", Forbidden, "Not allowed to modify this resource"),
+    NotFound => ("NOT_FOUND", NotFound, "Resource not found"),
+    UnprocessableEntity => ("UNPROCESSABLE_ENTITY", UnprocessableEntity, "Request body could not be parsed"),
+    PayloadTooLarge => ("PAYLOAD_TOO_LARGE", PayloadTooLarge, "Request body exceeds the maximum allowed size"),
+    RateLimited => ("RATE_LIMITED", TooManyRequests, "Rate limit exceeded"),
+    InternalError => ("INTERNAL_ERROR", InternalServerError, "Internal server error"),
+    DbError => ("DB_ERROR", InternalServerError, "Database error"),
+
+    // Authentication and accounts
+    KeyExpired => ("KEY_EXPIRED", Unauthorized, "API key has expired. Ask an admin to rotate it."),
+    TokenExpired => ("TOKEN_EXPIRED", Unauthorized, "Access token has expired. Request a new one from /oauth/token."),
+    AdminRequired => ("ADMIN_REQUIRED", Forbidden, "Admin API key required"),
+    InvalidExpiry => ("INVALID_EXPIRY", BadRequest, "expires_at must be a future RFC 3339 timestamp"),
+    InvalidRateLimit => ("INVALID_RATE_LIMIT", BadRequest, "rate_limit, burst, or exempt_routes is invalid"),
+    InvalidPageSize => ("INVALID_PAGE_SIZE", BadRequest, "default_page_size and max_page_size must be between 0 and 1000"),
+    InvalidEmail => ("INVALID_EMAIL", BadRequest, "A valid email address is required"),
+    InvalidCode => ("INVALID_CODE", Unauthorized, "Login code is incorrect"),
+    CodeExpired => ("CODE_EXPIRED", Unauthorized, "Login code expired or was never requested"),
+
+    // Input validation
+    FieldTooLong => ("FIELD_TOO_LONG", BadRequest, "A field exceeds its maximum length"),
+    InvalidProtocol => ("INVALID_PROTOCOL", BadRequest, "Unknown protocol"),
+    InvalidCategory => ("INVALID_CATEGORY", BadRequest, "Unknown or malformed category path"),
+    InvalidStatus => ("INVALID_STATUS", BadRequest, "Unknown app status"),
+    InvalidSlug => ("INVALID_SLUG", BadRequest, "Slug must be 3-64 lowercase letters, digits and dashes"),
+    InvalidTag => ("INVALID_TAG", BadRequest, "Tags must be non-empty and within the tag length limit"),
+    InvalidVisibility => ("INVALID_VISIBILITY", BadRequest, "Visibility must be public, unlisted, or private"),
+    InvalidRating => ("INVALID_RATING", BadRequest, "Rating must be between 1 and 5"),
+    InvalidReaction => ("INVALID_REACTION", BadRequest, "Unknown review reaction"),
+    InvalidUrl => ("INVALID_URL", BadRequest, "URL must start with http:// or https://"),
+    InvalidEvent => ("INVALID_EVENT", BadRequest, "Unknown webhook event type"),
+    InvalidPeriod => ("INVALID_PERIOD", BadRequest, "Unknown stats period"),
+    InvalidMetric => ("INVALID_METRIC", BadRequest, "Unknown stats history metric"),
+    InvalidSince => ("INVALID_SINCE", BadRequest, "since must be an RFC 3339 timestamp"),
+    InvalidTimestamp => ("INVALID_TIMESTAMP", BadRequest, "Time range filters must be RFC 3339 timestamps"),
+    InvalidLanguage => ("INVALID_LANGUAGE", BadRequest, "Invalid language tag"),
+    DefaultLanguage => ("DEFAULT_LANGUAGE", BadRequest, "Default-language content is edited on the app itself"),
+    InvalidReplacement => ("INVALID_REPLACEMENT", BadRequest, "Replacement app is missing or invalid"),
+    InvalidAction => ("INVALID_ACTION", BadRequest, "Unknown bulk action"),
+    InvalidField => ("INVALID_FIELD", BadRequest, "Unknown field in the fields parameter"),
+    InvalidBadge => ("INVALID_BADGE", BadRequest, "Badge slug, label, color, or description is invalid"),
+    ReasonRequired => ("REASON_REQUIRED", BadRequest, "A reason is required for this action"),
+    NoChanges => ("NO_CHANGES", BadRequest, "No fields to update"),
+    NoApps => ("NO_APPS", BadRequest, "No app ids given"),
+    InvalidRankBoost => ("INVALID_RANK_BOOST", BadRequest, "rank_boost must be between -10 and 10"),
+    InvalidFeatureWindow => ("INVALID_FEATURE_WINDOW", BadRequest, "featured_until must be a future RFC 3339 timestamp after featured_from"),
+    InvalidHealthExpectation => ("INVALID_HEALTH_EXPECTATION", BadRequest, "health_expectation needs a non-empty contains or a JSON pointer starting with /"),
+    InvalidEndorsement => ("INVALID_ENDORSEMENT", BadRequest, "Both apps must be approved and different"),
+    InvalidKeyEndpoint => ("INVALID_KEY_ENDPOINT", BadRequest, "Key endpoints need a known method, a path starting with / and a description"),
+    InvalidDependency => ("INVALID_DEPENDENCY", BadRequest, "depends_on must list up to 20 other non-private apps"),
+    InvalidGracePeriod => ("INVALID_GRACE_PERIOD", BadRequest, "grace_period_secs must be at most 30 days"),
+    InvalidTimeout => ("INVALID_TIMEOUT", BadRequest, "timeout_ms must be between 100 and 30000"),
+    InvalidReviewer => ("INVALID_REVIEWER", BadRequest, "Reviewer must be an active admin API key"),
+    InvalidSubject => ("INVALID_SUBJECT", BadRequest, "Exactly one of key_id, fingerprint, or email is required"),
+    TooManyApps => ("TOO_MANY_APPS", BadRequest, "Too many apps in one bulk request"),
+    NoUrl => ("NO_URL", UnprocessableEntity, "App has no URL to check"),
+    PreconditionFailed => ("PRECONDITION_FAILED", PreconditionFailed, "If-Match does not match the app's current revision"),
+    ContentRejected => ("CONTENT_REJECTED", UnprocessableEntity, "Text was rejected by the content filter"),
+    PolicyRejected => ("POLICY_REJECTED", UnprocessableEntity, "The listing was rejected by the directory's policy service"),
+    CaptchaRequired => ("CAPTCHA_REQUIRED", BadRequest, "captcha_token is required for anonymous requests"),
+    CaptchaFailed => ("CAPTCHA_FAILED", Forbidden, "CAPTCHA verification failed"),
+
+    // State conflicts
+    AlreadyApproved => ("ALREADY_APPROVED", Conflict, "App is already approved"),
+    AlreadyRejected => ("ALREADY_REJECTED", Conflict, "App is already rejected"),
+    AlreadyDeprecated => ("ALREADY_DEPRECATED", Conflict, "App is already deprecated"),
+    NotDeprecated => ("NOT_DEPRECATED", Conflict, "App is not deprecated"),
+    NotPending => ("NOT_PENDING", Conflict, "App is not pending review"),
+    NotDraft => ("NOT_DRAFT", Conflict, "App is not a draft"),
+    InvalidTransition => ("INVALID_TRANSITION", Conflict, "Status change not allowed from the current status"),
+    Archived => ("ARCHIVED", Conflict, "App is archived and read-only"),
+    AlreadyArchived => ("ALREADY_ARCHIVED", Conflict, "App is already archived"),
+    NotArchived => ("NOT_ARCHIVED", Conflict, "App is not archived"),
+    BadgeExists => ("BADGE_EXISTS", Conflict, "A badge with this slug already exists"),
+    SlugTaken => ("SLUG_TAKEN", Conflict, "Another listing already uses this slug"),
+    TagExists => ("TAG_EXISTS", Conflict, "Listings already use this tag; merge into it instead"),
+    SeedExists => ("SEED_EXISTS", Conflict, "This discovery seed URL is already registered"),
+    ReplacementCycle => ("REPLACEMENT_CYCLE", Conflict, "Replacement chain loops back on itself"),
+    LastAdmin => ("LAST_ADMIN", Conflict, "The last active admin key must stay an admin"),
+
+    // Upstream services
+    CaptchaUnavailable => ("CAPTCHA_UNAVAILABLE", ServiceUnavailable, "CAPTCHA provider could not be reached; try again"),
+    Maintenance => ("MAINTENANCE", ServiceUnavailable, "Writes are paused for maintenance; retry later"),
+    ReadOnly => ("READ_ONLY", Forbidden, "This instance is a read-only replica; send writes to the primary"),
+
+    // Quotas
+    ReviewLimitExceeded => ("REVIEW_LIMIT_EXCEEDED", TooManyRequests, "Anonymous review limit reached"),
+    LoginLimitExceeded => ("LOGIN_LIMIT_EXCEEDED", TooManyRequests, "Too many login attempts; try again later"),
+}
+
+/// Error response with the shared envelope:
+/// `{"error": CODE, "message": "...", "message_key": "errors.code", ...details}`.
+/// `message` is translated per `Accept-Language` by `LocalizedErrors`.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+    details: Map<String, Value>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        ApiError {
+            code,
+            message: message.into(),
+            details: Map::new(),
+        }
+    }
+
+    /// Attach an extra top-level field (e.g. `field`, `retry_after_secs`).
+    pub fn with(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.details.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn status(&self) -> Status {
+        self.code.status()
+    }
+
+    pub fn body(&self) -> Value {
+        let mut body = Map::new();
+        body.insert("error".into(), json!(self.code.as_str()));
+        body.insert("message".into(), json!(self.message));
+        body.insert("message_key".into(), json!(self.code.message_key()));
+        for (k, v) in &self.details {
+            body.insert(k.clone(), v.clone());
+        }
+        Value::Object(body)
+    }
+}
+
+impl From<ErrorCode> for ApiError {
+    fn from(code: ErrorCode) -> Self {
+        ApiError::new(code, code.description())
+    }
+}
+
+impl From<ApiError> for (Status, Json<Value>) {
+    fn from(err: ApiError) -> Self {
+        (err.status(), Json(err.body()))
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        (self.status(), Json(self.body())).respond_to(request)
+    }
+}
+
+/// Registry of all error codes, served at `GET /api/v1/errors`, with the
+/// translated messages for each.
+pub fn registry() -> Value {
+    let codes: Vec<Value> = ErrorCode::ALL
+        .iter()
+        .map(|c| {
+            let translations: Map<String, Value> = error_messages::languages()
+                .filter_map(|lang| error_messages::translate(c.as_str(), lang).map(|m| (lang.to_string(), json!(m))))
+                .collect();
+            json!({
+                "code": c.as_str(),
+                "status": c.status().code,
+                "description": c.description(),
+                "message_key": c.message_key(),
+                "translations": translations,
+            })
+        })
+        .collect();
+    json!({ "errors": codes, "languages": std::iter::once("en").chain(error_messages::languages()).collect::<Vec<_>>() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_unique() {
+        let mut seen = std::collections::HashSet::new();
+        for code in ErrorCode::ALL {
+            assert!(seen.insert(code.as_str()), "duplicate {}", code.as_str());
+        }
+    }
+
+    #[test]
+    fn envelope_includes_details() {
+        let err = ApiError::new(ErrorCode::FieldTooLong, "too long").with("field", "name");
+        let body = err.body();
+        assert_eq!(body["error"], "FIELD_TOO_LONG");
+        assert_eq!(body["message"], "too long");
+        assert_eq!(body["field"], "name");
+        assert_eq!(body["message_key"], "errors.field_too_long");
+        assert_eq!(err.status(), Status::BadRequest);
+    }
+}
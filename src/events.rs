@@ -1,24 +1,49 @@
 use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use tokio::sync::broadcast;
+use rocket::futures::future::join_all;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
 
+use crate::outbox::{self, Queued};
 use crate::webhooks::{self, WebhookDb, WebhookEvent};
 
 /// Maximum events buffered per channel before old events are dropped.
 const CHANNEL_CAPACITY: usize = 256;
 
+/// Days of event history kept when `EVENT_RETENTION_DAYS` is unset.
+pub const DEFAULT_EVENT_RETENTION_DAYS: i64 = 7;
+
+/// Expired history is pruned once every this many stored events.
+const PRUNE_EVERY: u64 = 100;
+
 /// Internal shared state for EventBus.
 struct EventBusInner {
-    /// Global channel for SSE subscribers
+    /// Global channel for SSE subscribers: local events plus relayed ones
     channel: Mutex<Option<broadcast::Sender<AppEvent>>>,
+    /// Events emitted by this process only
+    local_channel: Mutex<Option<broadcast::Sender<AppEvent>>>,
+    /// Outgoing queue of the cross-process relay, when one is attached
+    relay: Mutex<Option<mpsc::UnboundedSender<String>>>,
+    /// Identifies this process in relayed messages so it can skip its own
+    origin: String,
     webhook_db: Option<WebhookDb>,
     http_client: reqwest::Client,
+    delivery: webhooks::DeliverySettings,
+    /// Days of history kept in the `events` table; 0 disables persistence.
+    retention_days: i64,
+    stored: AtomicU64,
 }
 
 /// A global event broadcast system for the app directory.
 ///
 /// Uses a single broadcast channel (all events are global, not per-board).
-/// Also delivers events to registered webhooks.
+/// Also delivers events to registered webhooks and records them in the
+/// `events` table for clients that poll `GET /api/v1/events`. With a relay
+/// attached (see `relay::RedisRelay`), events are shared with other
+/// processes so every replica's subscribers see every event; webhooks and
+/// history stay with the process that emitted the event.
 ///
 /// Cheaply cloneable via internal `Arc`.
 #[derive(Clone)]
@@ -35,6 +60,68 @@ pub struct AppEvent {
     pub data: serde_json::Value,
 }
 
+impl AppEvent {
+    /// Serialize as `{"event": ..., "data": ...}` for message-framed transports (WebSocket).
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// The app this event concerns, if any.
+    pub fn app_id(&self) -> Option<&str> {
+        self.data.get("app_id").and_then(|v| v.as_str())
+    }
+}
+
+/// Payload sent to a subscriber that fell behind and missed events.
+pub const EVENTS_LOST: &str = "events_lost";
+
+/// Subscription filter shared by the SSE and WebSocket endpoints.
+///
+/// `types` entries match an event name exactly, or by prefix when they end
+/// in `.*` (e.g. `app.*`). Empty `types` and `None` app_id match everything.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub types: Vec<String>,
+    pub app_id: Option<String>,
+}
+
+impl EventFilter {
+    /// Build a filter from query params: a comma-separated list of event types and an app ID.
+    pub fn from_query(events: Option<&str>, app_id: Option<&str>) -> Self {
+        let types = events
+            .map(|e| {
+                e.split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        EventFilter {
+            types,
+            app_id: app_id.filter(|a| !a.is_empty()).map(|a| a.to_string()),
+        }
+    }
+
+    /// Whether an event passes this filter.
+    pub fn matches(&self, event: &AppEvent) -> bool {
+        if let Some(ref wanted) = self.app_id {
+            if event.app_id() != Some(wanted.as_str()) {
+                return false;
+            }
+        }
+        if self.types.is_empty() {
+            return true;
+        }
+        self.types.iter().any(|t| match t.strip_suffix(".*") {
+            Some(prefix) => event
+                .event
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with('.')),
+            None => *t == event.event,
+        })
+    }
+}
+
 impl Default for EventBus {
     fn default() -> Self {
         Self::new()
@@ -47,19 +134,32 @@ impl EventBus {
         Self {
             inner: Arc::new(EventBusInner {
                 channel: Mutex::new(None),
+                local_channel: Mutex::new(None),
+                relay: Mutex::new(None),
+                origin: uuid::Uuid::new_v4().to_string(),
                 webhook_db: None,
                 http_client: reqwest::Client::new(),
+                delivery: webhooks::DeliverySettings::default(),
+                retention_days: 0,
+                stored: AtomicU64::new(0),
             }),
         }
     }
 
-    /// Create an EventBus with webhook delivery support.
-    pub fn with_webhooks(webhook_db: WebhookDb) -> Self {
+    /// Create an EventBus with webhook delivery support, keeping
+    /// `retention_days` of event history (0 disables history).
+    pub fn with_webhooks(webhook_db: WebhookDb, retention_days: i64, delivery: webhooks::DeliverySettings) -> Self {
         Self {
             inner: Arc::new(EventBusInner {
                 channel: Mutex::new(None),
+                local_channel: Mutex::new(None),
+                relay: Mutex::new(None),
+                origin: uuid::Uuid::new_v4().to_string(),
                 webhook_db: Some(webhook_db),
                 http_client: reqwest::Client::new(),
+                delivery,
+                retention_days: retention_days.max(0),
+                stored: AtomicU64::new(0),
             }),
         }
     }
@@ -72,26 +172,218 @@ impl EventBus {
         sender.subscribe()
     }
 
+    /// Subscribe to events emitted by this process only, for consumers
+    /// that must act once per event across replicas (e.g. notifications).
+    pub fn subscribe_local(&self) -> broadcast::Receiver<AppEvent> {
+        let mut channel = self.inner.local_channel.lock().unwrap();
+        let sender = channel.get_or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0);
+        sender.subscribe()
+    }
+
+    /// Identifier of this process in relayed messages.
+    pub fn origin(&self) -> &str {
+        &self.inner.origin
+    }
+
+    /// Forward emitted events to `relay` as serialized messages.
+    pub fn attach_relay(&self, relay: mpsc::UnboundedSender<String>) {
+        *self.inner.relay.lock().unwrap() = Some(relay);
+    }
+
+    /// Deliver an event relayed from another process to SSE subscribers only.
+    pub fn deliver_relayed(&self, event: AppEvent) {
+        let channel = self.inner.channel.lock().unwrap();
+        if let Some(sender) = channel.as_ref() {
+            let _ = sender.send(event);
+        }
+    }
+
     /// Emit an event to all SSE subscribers and webhook targets.
     pub fn emit(&self, event: AppEvent) {
+        self.publish(event);
+    }
+
+    /// Publish events queued with `outbox::enqueue` whose transaction has
+    /// committed. Each stays in the outbox until its webhook deliveries
+    /// have all been attempted; if this process stops first, `OutboxRelay`
+    /// publishes it again.
+    pub fn dispatch(&self, queued: impl IntoIterator<Item = Queued>) {
+        for queued in queued {
+            let db = match self.inner.webhook_db {
+                Some(ref db) => db.clone(),
+                None => {
+                    self.emit(queued.event);
+                    continue;
+                }
+            };
+            if !outbox::claim(&db.lock().unwrap_or_else(|e| e.into_inner()), queued.id) {
+                continue;
+            }
+            let deliveries = self.publish(queued.event);
+            tokio::spawn(async move {
+                let delivered = join_all(deliveries).await.iter().all(|r| r.is_ok());
+                // A panicked delivery keeps its claim until the lease lapses
+                if delivered {
+                    outbox::complete(&db.lock().unwrap_or_else(|e| e.into_inner()), queued.id);
+                }
+            });
+        }
+    }
+
+    /// Dispatch up to `limit` outbox entries nobody is publishing. Returns
+    /// how many were picked up.
+    pub fn relay_outbox(&self, limit: i64) -> usize {
+        let Some(ref db) = self.inner.webhook_db else { return 0 };
+        let pending = outbox::claimable(&db.lock().unwrap_or_else(|e| e.into_inner()), limit);
+        let count = pending.len();
+        self.dispatch(pending);
+        count
+    }
+
+    /// Deliver an event everywhere and return the webhook delivery tasks.
+    fn publish(&self, event: AppEvent) -> Vec<JoinHandle<()>> {
+        let mut deliveries = Vec::new();
+
         // Deliver to SSE subscribers
-        {
-            let channel = self.inner.channel.lock().unwrap();
+        for channel in [&self.inner.channel, &self.inner.local_channel] {
+            let channel = channel.lock().unwrap();
             if let Some(sender) = channel.as_ref() {
                 let _ = sender.send(event.clone());
             }
         }
 
+        // Share with other processes
+        if let Some(relay) = self.inner.relay.lock().unwrap().as_ref() {
+            let message = json!({ "origin": self.inner.origin, "event": event.event, "data": event.data });
+            let _ = relay.send(message.to_string());
+        }
+
+        // Record in history, pruning expired rows every PRUNE_EVERY events
+        if let Some(ref db) = self.inner.webhook_db {
+            if self.inner.retention_days > 0 {
+                let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+                if let Err(e) = record(&conn, &event) {
+                    eprintln!("❌ Failed to record {} event: {e}", event.event);
+                }
+                if self.inner.stored.fetch_add(1, Ordering::Relaxed).is_multiple_of(PRUNE_EVERY) {
+                    let _ = prune(&conn, self.inner.retention_days);
+                }
+            }
+        }
+
+        // Notify the app's owner, if they registered a URL (async, non-blocking)
+        if let Some(ref db) = self.inner.webhook_db {
+            if let Some(app_id) = event.app_id().filter(|_| webhooks::OWNER_EVENTS.contains(&event.event.as_str())) {
+                deliveries.push(webhooks::deliver_owner_notification(
+                    db.clone(),
+                    app_id.to_string(),
+                    WebhookEvent {
+                        event: event.event.clone(),
+                        data: event.data.clone(),
+                    },
+                    self.inner.http_client.clone(),
+                    self.inner.delivery.clone(),
+                ));
+            }
+        }
+
         // Deliver to webhooks (async, non-blocking)
         if let Some(ref db) = self.inner.webhook_db {
-            webhooks::deliver_webhooks(
+            deliveries.push(webhooks::deliver_webhooks(
                 db.clone(),
                 WebhookEvent {
                     event: event.event,
                     data: event.data,
                 },
                 self.inner.http_client.clone(),
-            );
+                self.inner.delivery.clone(),
+            ));
+        }
+        deliveries
+    }
+}
+
+/// Store an event in the history table.
+pub fn record(conn: &rusqlite::Connection, event: &AppEvent) -> rusqlite::Result<usize> {
+    conn.execute(
+        "INSERT INTO events (event, app_id, data) VALUES (?1, ?2, ?3)",
+        rusqlite::params![event.event, event.app_id(), event.data.to_string()],
+    )
+}
+
+/// Delete history older than `retention_days`. Returns the rows removed.
+pub fn prune(conn: &rusqlite::Connection, retention_days: i64) -> rusqlite::Result<usize> {
+    conn.execute(
+        "DELETE FROM events WHERE created_at < datetime('now', ?1)",
+        rusqlite::params![format!("-{} days", retention_days)],
+    )
+}
+
+/// One page of stored events matching `filter` and created at or after
+/// `since` (SQLite datetime), oldest first. Returns `(total, events)`.
+pub fn history(
+    conn: &rusqlite::Connection,
+    filter: &EventFilter,
+    since: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> (i64, Vec<Value>) {
+    let mut conditions = vec!["(?1 IS NULL OR app_id = ?1)", "(?2 IS NULL OR created_at >= ?2)"];
+    let mut params: Vec<String> = Vec::new();
+    let mut type_conditions = Vec::new();
+    for t in &filter.types {
+        match t.strip_suffix(".*") {
+            Some(prefix) => {
+                params.push(format!("{}.", prefix));
+                type_conditions.push(format!("substr(event, 1, length(?{p})) = ?{p}", p = params.len() + 2));
+            }
+            None => {
+                params.push(t.clone());
+                type_conditions.push(format!("event = ?{}", params.len() + 2));
+            }
         }
     }
+    let type_clause = format!("({})", type_conditions.join(" OR "));
+    if !type_conditions.is_empty() {
+        conditions.push(&type_clause);
+    }
+    let where_clause = conditions.join(" AND ");
+
+    let mut values: Vec<&dyn rusqlite::ToSql> = vec![&filter.app_id, &since];
+    values.extend(params.iter().map(|p| p as &dyn rusqlite::ToSql));
+
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM events WHERE {}", where_clause),
+            values.as_slice(),
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+
+    let mut paged = values.clone();
+    paged.push(&limit);
+    paged.push(&offset);
+    let sql = format!(
+        "SELECT id, event, app_id, data, created_at FROM events WHERE {} ORDER BY id ASC LIMIT ?{} OFFSET ?{}",
+        where_clause,
+        values.len() + 1,
+        values.len() + 2
+    );
+    let events = match conn.prepare(&sql) {
+        Ok(mut stmt) => stmt
+            .query_map(paged.as_slice(), |row| {
+                let data: String = row.get(3)?;
+                Ok(json!({
+                    "id": row.get::<_, i64>(0)?,
+                    "event": row.get::<_, String>(1)?,
+                    "app_id": row.get::<_, Option<String>>(2)?,
+                    "data": serde_json::from_str::<Value>(&data).unwrap_or(Value::Null),
+                    "created_at": row.get::<_, String>(4)?,
+                }))
+            })
+            .map(|rows| rows.filter_map(|r| r.ok()).collect())
+            .unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+    (total, events)
 }
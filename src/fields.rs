@@ -0,0 +1,171 @@
+use serde_json::{json, Map, Value};
+
+use crate::errors::{ApiError, ErrorCode};
+
+/// How a selectable column is read from a row.
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Text,
+    OptText,
+    Int,
+    Real,
+    OptReal,
+    Bool,
+    Tags,
+}
+
+/// App columns selectable with `?fields=`, named as they appear in responses.
+const COLUMNS: &[(&str, Kind)] = &[
+    ("id", Kind::Text),
+    ("name", Kind::Text),
+    ("slug", Kind::Text),
+    ("short_description", Kind::Text),
+    ("description", Kind::Text),
+    ("homepage_url", Kind::OptText),
+    ("api_url", Kind::OptText),
+    ("api_spec_url", Kind::OptText),
+    ("protocol", Kind::Text),
+    ("category", Kind::Text),
+    ("tags", Kind::Tags),
+    ("logo_url", Kind::OptText),
+    ("author_name", Kind::Text),
+    ("author_url", Kind::OptText),
+    ("status", Kind::Text),
+    ("is_featured", Kind::Bool),
+    ("is_verified", Kind::Bool),
+    ("avg_rating", Kind::Real),
+    ("review_count", Kind::Int),
+    ("created_at", Kind::Text),
+    ("updated_at", Kind::Text),
+    ("last_health_status", Kind::OptText),
+    ("last_checked_at", Kind::OptText),
+    ("uptime_pct", Kind::OptReal),
+    ("review_note", Kind::OptText),
+    ("reviewed_by", Kind::OptText),
+    ("reviewed_at", Kind::OptText),
+    ("deprecated_reason", Kind::OptText),
+    ("deprecated_by", Kind::OptText),
+    ("deprecated_at", Kind::OptText),
+    ("replacement_app_id", Kind::OptText),
+    ("sunset_at", Kind::OptText),
+    ("spec_status", Kind::OptText),
+    ("archived_at", Kind::OptText),
+    ("archived_by", Kind::OptText),
+    ("revision", Kind::Int),
+    ("featured_from", Kind::OptText),
+    ("featured_until", Kind::OptText),
+    ("visibility", Kind::Text),
+];
+
+/// A validated `?fields=` selection. Column fields are read straight from
+/// the row; anything else (e.g. `badges`) is computed by the caller.
+#[derive(Debug, Clone)]
+pub struct FieldSet {
+    fields: Vec<&'static str>,
+}
+
+impl FieldSet {
+    /// Parse a comma-separated field list. `computed` names the extra
+    /// fields the endpoint can fill in. Returns `None` when no list was given.
+    pub fn parse(raw: Option<&str>, computed: &[&'static str]) -> Result<Option<FieldSet>, ApiError> {
+        let raw = match raw.map(str::trim).filter(|r| !r.is_empty()) {
+            Some(raw) => raw,
+            None => return Ok(None),
+        };
+        let mut fields: Vec<&'static str> = Vec::new();
+        for name in raw.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let known = COLUMNS
+                .iter()
+                .map(|(c, _)| *c)
+                .chain(computed.iter().copied())
+                .find(|c| *c == name);
+            match known {
+                Some(field) if !fields.contains(&field) => fields.push(field),
+                Some(_) => {}
+                None => {
+                    return Err(ApiError::new(ErrorCode::InvalidField, format!("Unknown field '{}'", name))
+                        .with("field", name)
+                        .with("allowed", Self::available(computed)));
+                }
+            }
+        }
+        Ok(Some(FieldSet { fields }))
+    }
+
+    /// Every field an endpoint accepts: the app columns, then `computed`.
+    pub fn available(computed: &[&'static str]) -> Vec<&'static str> {
+        COLUMNS.iter().map(|(c, _)| *c).chain(computed.iter().copied()).collect()
+    }
+
+    pub fn contains(&self, field: &str) -> bool {
+        self.fields.contains(&field)
+    }
+
+    fn columns(&self) -> impl Iterator<Item = (&'static str, Kind)> + '_ {
+        COLUMNS.iter().copied().filter(|(c, _)| self.fields.contains(c))
+    }
+
+    /// Column list for the SELECT. `id` always comes first so callers can
+    /// look up computed fields; `retain` drops it again if not requested.
+    pub fn select_list(&self) -> String {
+        std::iter::once("id")
+            .chain(self.columns().map(|(c, _)| c).filter(|c| *c != "id"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Number of columns `select_list` produces.
+    pub fn column_count(&self) -> usize {
+        1 + self.columns().filter(|(c, _)| *c != "id").count()
+    }
+
+    /// Map a row selected with `select_list` to JSON.
+    pub fn row_to_json(&self, row: &rusqlite::Row) -> rusqlite::Result<Value> {
+        let mut app = Map::new();
+        app.insert("id".to_string(), json!(row.get::<_, String>(0)?));
+        for (i, (name, kind)) in self.columns().filter(|(c, _)| *c != "id").enumerate() {
+            let idx = i + 1;
+            let value = match kind {
+                Kind::Text => json!(row.get::<_, String>(idx)?),
+                Kind::OptText => json!(row.get::<_, Option<String>>(idx)?),
+                Kind::Int => json!(row.get::<_, i64>(idx)?),
+                Kind::Real => json!(row.get::<_, f64>(idx)?),
+                Kind::OptReal => json!(row.get::<_, Option<f64>>(idx)?),
+                Kind::Bool => json!(row.get::<_, i32>(idx)? != 0),
+                Kind::Tags => {
+                    let tags: Vec<String> = serde_json::from_str(&row.get::<_, String>(idx)?).unwrap_or_default();
+                    json!(tags)
+                }
+            };
+            app.insert(name.to_string(), value);
+        }
+        Ok(Value::Object(app))
+    }
+
+    /// Drop every key that was not requested.
+    pub fn retain(&self, app: &mut Value) {
+        if let Value::Object(map) = app {
+            map.retain(|k, _| self.fields.contains(&k.as_str()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_validates_fields() {
+        assert!(FieldSet::parse(None, &[]).unwrap().is_none());
+        assert!(FieldSet::parse(Some(" "), &[]).unwrap().is_none());
+
+        let set = FieldSet::parse(Some("name, slug,name,badges"), &["badges"]).unwrap().unwrap();
+        assert!(set.contains("badges"));
+        assert_eq!(set.select_list(), "id, name, slug");
+        assert_eq!(set.column_count(), 3);
+
+        let err = FieldSet::parse(Some("name,secret"), &[]).unwrap_err();
+        assert_eq!(err.code.as_str(), "INVALID_FIELD");
+        assert!(FieldSet::parse(Some("badges"), &[]).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use rusqlite::types::ToSql;
+
+use crate::categories;
+use crate::errors::{ApiError, ErrorCode};
+use crate::search;
+
+/// `health` filter values: a last health check status, or `unknown` for never checked.
+pub const HEALTH_FILTERS: &[&str] = &["healthy", "degraded", "unhealthy", "unreachable", "unknown"];
+
+/// WHERE conditions over `apps` and the positional parameters they bind.
+#[derive(Default)]
+pub struct Query {
+    pub conditions: Vec<String>,
+    params: Vec<Box<dyn ToSql>>,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Query::default()
+    }
+
+    /// Add a condition that binds no parameters.
+    pub fn condition(&mut self, condition: impl Into<String>) {
+        self.conditions.push(condition.into());
+    }
+
+    /// Bind a parameter and return its placeholder number (`?{n}`).
+    pub fn bind(&mut self, value: impl ToSql + 'static) -> usize {
+        self.params.push(Box::new(value));
+        self.params.len()
+    }
+
+    /// The conditions joined with AND, or `1=1` when there are none.
+    pub fn where_clause(&self) -> String {
+        if self.conditions.is_empty() {
+            "1=1".to_string()
+        } else {
+            self.conditions.join(" AND ")
+        }
+    }
+
+    pub fn params(&self) -> impl Iterator<Item = &dyn ToSql> {
+        self.params.iter().map(|p| p.as_ref())
+    }
+}
+
+/// Filters `GET /apps` and `GET /apps/search` both accept. Status,
+/// visibility and sorting stay with each endpoint.
+#[derive(Debug, Default)]
+pub struct AppFilters {
+    /// Category path; matches the category and its subcategories.
+    pub category: Option<String>,
+    pub protocol: Option<String>,
+    /// `true` for featured apps only, `false` for the rest.
+    pub featured: Option<bool>,
+    /// `true` for verified apps only, `false` for the rest.
+    pub verified: Option<bool>,
+    /// One of `HEALTH_FILTERS`; anything else is ignored.
+    pub health: Option<String>,
+    /// Search terms, see [`search::parse_terms`].
+    pub terms: Vec<String>,
+    /// (condition prefix, normalized timestamp) pairs from [`time_range`].
+    pub time_range: Vec<(&'static str, String)>,
+}
+
+impl AppFilters {
+    /// Add every filter but the search terms to `query`.
+    pub fn apply(&self, query: &mut Query) {
+        if let Some(cat) = &self.category {
+            let idx = query.bind(cat.trim_matches('/').to_lowercase());
+            query.condition(categories::subtree_condition("category", idx));
+        }
+        if let Some(proto) = &self.protocol {
+            let idx = query.bind(proto.clone());
+            query.condition(format!("protocol = ?{idx}"));
+        }
+        if let Some(featured) = self.featured {
+            query.condition(format!("is_featured = {}", featured as i32));
+        }
+        if let Some(verified) = self.verified {
+            query.condition(format!("is_verified = {}", verified as i32));
+        }
+        match self.health.as_deref() {
+            Some("unknown") => query.condition("last_health_status IS NULL"),
+            Some(h) if HEALTH_FILTERS.contains(&h) => {
+                let idx = query.bind(h.to_string());
+                query.condition(format!("last_health_status = ?{idx}"));
+            }
+            _ => {}
+        }
+        for (condition, timestamp) in &self.time_range {
+            let idx = query.bind(timestamp.clone());
+            query.condition(format!("{condition} ?{idx}"));
+        }
+    }
+
+    /// Bind one pattern per search term and return a condition for each,
+    /// for the caller to combine.
+    pub fn bind_terms(&self, query: &mut Query) -> Vec<String> {
+        self.terms
+            .iter()
+            .map(|t| search::term_condition(query.bind(format!("%{}%", t))))
+            .collect()
+    }
+}
+
+/// SQL conditions for the `created_after`, `created_before` and
+/// `updated_after` filters, as (condition prefix, normalized timestamp)
+/// pairs. `_after` bounds are inclusive and `_before` exclusive.
+pub fn time_range(
+    created_after: Option<&str>,
+    created_before: Option<&str>,
+    updated_after: Option<&str>,
+) -> Result<Vec<(&'static str, String)>, ApiError> {
+    [
+        ("created_after", "created_at >=", created_after),
+        ("created_before", "created_at <", created_before),
+        ("updated_after", "updated_at >=", updated_after),
+    ]
+    .into_iter()
+    .filter_map(|(param, condition, raw)| {
+        let raw = raw.map(str::trim).filter(|r| !r.is_empty())?;
+        Some((param, condition, raw))
+    })
+    .map(|(param, condition, raw)| {
+        chrono::DateTime::parse_from_rfc3339(raw)
+            .map(|t| (condition, t.with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M:%S").to_string()))
+            .map_err(|_| {
+                ApiError::new(ErrorCode::InvalidTimestamp, format!("{param} must be an RFC 3339 timestamp"))
+                    .with("field", param)
+            })
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_numbered_conditions() {
+        let filters = AppFilters {
+            protocol: Some("mcp".to_string()),
+            featured: Some(false),
+            health: Some("unknown".to_string()),
+            terms: vec!["weather".to_string(), "api".to_string()],
+            time_range: time_range(Some("2026-01-01T00:00:00Z"), None, None).unwrap(),
+            ..Default::default()
+        };
+        let mut query = Query::new();
+        query.condition("status = 'approved'");
+        filters.apply(&mut query);
+        let terms = filters.bind_terms(&mut query);
+        assert_eq!(
+            query.where_clause(),
+            "status = 'approved' AND protocol = ?1 AND is_featured = 0 AND last_health_status IS NULL AND created_at >= ?2"
+        );
+        assert!(terms[1].contains("?4"));
+        assert_eq!(query.params().count(), 4);
+        assert!(time_range(None, Some("yesterday"), None).is_err());
+    }
+}
@@ -1,12 +1,176 @@
 use rocket::http::Status;
 use rocket::serde::json::Json;
 use serde_json::{json, Value};
-use std::time::Instant;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use crate::auth::AuthenticatedKey;
+use crate::auth::{AuthenticatedKey, EditTokenParam, ReadAccess};
+use crate::categories;
+use crate::errors::{ApiError, ErrorCode};
 use crate::events::{AppEvent, EventBus};
+use crate::models::{listed_visibility, VALID_PROTOCOLS};
+use crate::scheduler::{check_apps, BatchStats, CheckTarget, ScheduleConfig, ScheduleStatus};
 use crate::DbState;
 
+/// Largest response body read when checking a health expectation.
+const EXPECTATION_BODY_LIMIT: usize = 256 * 1024;
+
+/// Owner-set success criteria for an app's health probe, on top of a 2xx
+/// status. A response that passes the status check but fails one of these
+/// is `degraded`. Stored as JSON in `apps.health_expectation`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct HealthExpectation {
+    /// Substring the body must contain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contains: Option<String>,
+    /// JSON pointer (RFC 6901) that must resolve in the body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_pointer: Option<String>,
+    /// Value expected at `json_pointer`; omitted only requires it to exist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_value: Option<Value>,
+}
+
+impl HealthExpectation {
+    /// Check the criteria are usable. `Ok(None)` means none were given,
+    /// which clears an app's expectation.
+    pub fn validate(self) -> Result<Option<Self>, ApiError> {
+        let invalid = |field: &str| ApiError::from(ErrorCode::InvalidHealthExpectation).with("field", field);
+        if self == HealthExpectation::default() {
+            return Ok(None);
+        }
+        if let Some(contains) = &self.contains {
+            if contains.is_empty() || contains.len() > 500 {
+                return Err(invalid("contains"));
+            }
+        }
+        match &self.json_pointer {
+            Some(pointer) if !pointer.starts_with('/') => return Err(invalid("json_pointer")),
+            None if self.json_value.is_some() => return Err(invalid("json_pointer")),
+            _ => {}
+        }
+        Ok(Some(self))
+    }
+
+    /// Parse the stored column; unreadable JSON counts as no expectation.
+    pub fn from_column(raw: Option<String>) -> Option<Self> {
+        raw.and_then(|r| serde_json::from_str(&r).ok())
+    }
+
+    /// Why `body` fails the criteria, or `None` when it passes.
+    pub fn mismatch(&self, body: &str) -> Option<String> {
+        if let Some(needle) = &self.contains {
+            if !body.contains(needle.as_str()) {
+                return Some(format!("Response body does not contain {:?}", needle));
+            }
+        }
+        let pointer = self.json_pointer.as_deref()?;
+        let Ok(doc) = serde_json::from_str::<Value>(body) else {
+            return Some("Response body is not JSON".to_string());
+        };
+        match (doc.pointer(pointer), &self.json_value) {
+            (None, _) => Some(format!("No value at {}", pointer)),
+            (Some(found), Some(expected)) if found != expected => {
+                Some(format!("{} is {}, expected {}", pointer, found, expected))
+            }
+            _ => None,
+        }
+    }
+
+    /// Read up to `EXPECTATION_BODY_LIMIT` bytes of a successful response
+    /// and check it, returning the mismatch if any.
+    pub async fn check_response(&self, mut resp: reqwest::Response) -> Option<String> {
+        let mut body = Vec::new();
+        loop {
+            match resp.chunk().await {
+                Ok(Some(chunk)) => {
+                    body.extend_from_slice(&chunk);
+                    if body.len() >= EXPECTATION_BODY_LIMIT {
+                        body.truncate(EXPECTATION_BODY_LIMIT);
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => return Some(format!("Failed to read response body: {}", e)),
+            }
+        }
+        self.mismatch(&String::from_utf8_lossy(&body))
+    }
+}
+
+/// Read a response body chunk by chunk, giving up once it passes `limit`
+/// bytes, so a body without (or lying about) `Content-Length` can't make
+/// us buffer without bound. `Ok(None)` means the body was too large.
+pub async fn read_body_limited(mut resp: reqwest::Response, limit: usize) -> reqwest::Result<Option<Vec<u8>>> {
+    if resp.content_length().is_some_and(|len| len > limit as u64) {
+        return Ok(None);
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(Some(body))
+}
+
+/// Set an app's cached health status and return the status it replaces.
+pub fn update_last_status(conn: &rusqlite::Connection, app_id: &str, status: &str) -> Option<String> {
+    let previous: Option<String> = conn
+        .query_row(
+            "SELECT last_health_status FROM apps WHERE id = ?1",
+            rusqlite::params![app_id],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten();
+    let _ = conn.execute(
+        "UPDATE apps SET last_health_status = ?1, last_checked_at = datetime('now'), updated_at = datetime('now') WHERE id = ?2",
+        rusqlite::params![status, app_id],
+    );
+    previous
+}
+
+/// `app.health_changed` event when a check moves an app to a different status.
+///
+/// `data` carries the check details (`app_id`, `app_name`, `status_code`,
+/// `response_time_ms`, ...); `previous_status`, `status`, and a `transition`
+/// label (`degraded`, `unhealthy`, `unreachable`, or `recovered`) are added here. An app's
+/// first check only counts as a transition when it finds the app down.
+pub fn health_changed_event(previous: Option<&str>, current: &str, mut data: Value) -> Option<AppEvent> {
+    if previous == Some(current) || (previous.is_none() && current == "healthy") {
+        return None;
+    }
+    let transition = if current == "healthy" { "recovered" } else { current };
+    data["previous_status"] = json!(previous);
+    data["status"] = json!(current);
+    data["transition"] = json!(transition);
+    Some(AppEvent {
+        event: "app.health_changed".to_string(),
+        data,
+    })
+}
+
+/// An `app.health_changed` event as sent on the health stream: the app,
+/// `old_status` and `new_status`, and the probe's latency, without the rest
+/// of the check result.
+pub fn transition_payload(data: &Value) -> Value {
+    json!({
+        "app_id": data["app_id"],
+        "app_name": data["app_name"],
+        "old_status": data["previous_status"],
+        "new_status": data["status"],
+        "transition": data["transition"],
+        "latency_ms": data["response_time_ms"],
+        "status_code": data["status_code"],
+        "error_message": data["error_message"],
+        "scheduled": data.get("scheduled").cloned().unwrap_or(json!(false)),
+        "changed_at": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    })
+}
+
 /// Perform a health check on a single app.
 /// Checks the `api_url` (or `homepage_url` if no api_url) with a GET request.
 /// Records the result in the `health_checks` table and updates the app's cached status.
@@ -19,19 +183,18 @@ pub async fn check_app_health(
 ) -> (Status, Json<Value>) {
     // Only admins can trigger health checks
     if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(
-                json!({ "error": "ADMIN_REQUIRED", "message": "Only admins can trigger health checks" }),
-            ),
-        );
+        return ApiError::new(
+            ErrorCode::AdminRequired,
+            "Only admins can trigger health checks",
+        )
+        .into();
     }
 
     // Get app info (need api_url or homepage_url)
     let app_info = {
         let conn = db.conn();
         conn.query_row(
-            "SELECT id, name, api_url, homepage_url FROM apps WHERE id = ?1 OR slug = ?1",
+            "SELECT id, name, api_url, homepage_url, health_expectation FROM apps WHERE id = ?1 OR slug = ?1",
             rusqlite::params![app_id],
             |row| {
                 Ok((
@@ -39,18 +202,16 @@ pub async fn check_app_health(
                     row.get::<_, String>(1)?,
                     row.get::<_, Option<String>>(2)?,
                     row.get::<_, Option<String>>(3)?,
+                    HealthExpectation::from_column(row.get(4)?),
                 ))
             },
         )
     };
 
-    let (id, name, api_url, homepage_url) = match app_info {
+    let (id, name, api_url, homepage_url, expectation) = match app_info {
         Ok(info) => info,
         Err(_) => {
-            return (
-                Status::NotFound,
-                Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-            )
+            return ApiError::new(ErrorCode::NotFound, "App not found").into()
         }
     };
 
@@ -58,13 +219,11 @@ pub async fn check_app_health(
     let check_url = match api_url.as_deref().or(homepage_url.as_deref()) {
         Some(url) => url.to_string(),
         None => {
-            return (
-                Status::UnprocessableEntity,
-                Json(json!({
-                    "error": "NO_URL",
-                    "message": "App has no api_url or homepage_url to check"
-                })),
+            return ApiError::new(
+                ErrorCode::NoUrl,
+                "App has no api_url or homepage_url to check",
             )
+            .into()
         }
     };
 
@@ -75,37 +234,12 @@ pub async fn check_app_health(
         .build()
         .unwrap();
 
-    let start = Instant::now();
-    let result = client.get(&check_url).send().await;
-    let response_time_ms = start.elapsed().as_millis() as i64;
-
-    let (health_status, status_code, error_message) = match result {
-        Ok(resp) => {
-            let code = resp.status().as_u16() as i64;
-            if resp.status().is_success() {
-                ("healthy".to_string(), Some(code), None)
-            } else {
-                (
-                    "unhealthy".to_string(),
-                    Some(code),
-                    Some(format!("HTTP {}", resp.status())),
-                )
-            }
-        }
-        Err(e) => {
-            let msg = if e.is_timeout() {
-                "Connection timed out (10s)".to_string()
-            } else if e.is_connect() {
-                "Connection refused or DNS failure".to_string()
-            } else {
-                format!("{}", e)
-            };
-            ("unreachable".to_string(), None, Some(msg))
-        }
-    };
+    let (health_status, status_code, error_message, response_time_ms) =
+        crate::scheduler::probe(&client, &check_url, 10, expectation.as_ref()).await;
 
     // Record the health check and update app
     let check_id = uuid::Uuid::new_v4().to_string();
+    let previous_status;
     {
         let conn = db.conn();
 
@@ -125,10 +259,7 @@ pub async fn check_app_health(
         );
 
         // Update app's cached health status
-        let _ = conn.execute(
-            "UPDATE apps SET last_health_status = ?1, last_checked_at = datetime('now'), updated_at = datetime('now') WHERE id = ?2",
-            rusqlite::params![health_status, id],
-        );
+        previous_status = update_last_status(&conn, &id, health_status);
 
         // Recalculate uptime percentage (last 100 checks)
         let uptime: Option<f64> = conn
@@ -158,6 +289,20 @@ pub async fn check_app_health(
             "response_time_ms": response_time_ms,
         }),
     });
+    let change = health_changed_event(
+        previous_status.as_deref(),
+        health_status,
+        json!({
+            "app_id": id,
+            "app_name": name,
+            "status_code": status_code,
+            "response_time_ms": response_time_ms,
+            "error_message": error_message,
+        }),
+    );
+    if let Some(event) = change {
+        bus.emit(event);
+    }
 
     (
         Status::Ok,
@@ -174,161 +319,274 @@ pub async fn check_app_health(
     )
 }
 
-/// Batch health check: check all approved apps that have a URL.
-/// Returns a summary of results.
-#[post("/apps/health-check/batch")]
-pub async fn batch_health_check(
-    key: AuthenticatedKey,
-    db: &rocket::State<DbState>,
-) -> (Status, Json<Value>) {
-    if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(
-                json!({ "error": "ADMIN_REQUIRED", "message": "Only admins can trigger health checks" }),
-            ),
-        );
+/// Batches bigger than this run in the background unless the request
+/// says otherwise with `queue: false`.
+pub const SYNC_BATCH_LIMIT: usize = 25;
+
+/// Maximum ids in `app_ids`.
+const BATCH_MAX_IDS: usize = 500;
+
+/// Queued batches kept for `GET /apps/health-check/batch/<job_id>`; the
+/// oldest are dropped first.
+const MAX_TRACKED_JOBS: usize = 50;
+
+/// Optional selection for a batch health check. Filters combine; an empty
+/// body checks every approved app with a URL.
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct BatchHealthCheckRequest {
+    pub app_ids: Option<Vec<String>>,
+    /// Category path; includes its subcategories.
+    pub category: Option<String>,
+    pub protocol: Option<String>,
+    /// Only apps never checked, or last checked more than
+    /// `stale_after_secs` ago (default: the schedule interval, or an hour
+    /// when scheduled checks are off).
+    #[serde(default)]
+    pub stale_only: bool,
+    pub stale_after_secs: Option<u64>,
+    /// `true` always runs in the background, `false` never does; omitted
+    /// queues batches of more than `SYNC_BATCH_LIMIT` apps.
+    pub queue: Option<bool>,
+}
+
+/// Queued batch health checks, by job id. In memory only: jobs are lost
+/// on restart. Queued batches use their own DB connection, like the
+/// scheduler.
+pub struct HealthCheckJobs {
+    db_path: String,
+    jobs: Arc<Mutex<VecDeque<Value>>>,
+}
+
+impl HealthCheckJobs {
+    pub fn new(db_path: &str) -> Self {
+        HealthCheckJobs {
+            db_path: db_path.to_string(),
+            jobs: Arc::new(Mutex::new(VecDeque::new())),
+        }
     }
 
-    // Get all approved apps with URLs
-    let apps: Vec<(String, String, String)> = {
-        let conn = db.conn();
-        let mut stmt = conn
-            .prepare(
-                "SELECT id, name, COALESCE(api_url, homepage_url) as check_url
-                 FROM apps
-                 WHERE status = 'approved'
-                   AND (api_url IS NOT NULL OR homepage_url IS NOT NULL)",
-            )
-            .unwrap();
-
-        stmt.query_map([], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-            ))
-        })
-        .unwrap()
-        .filter_map(|r| r.ok())
-        .collect()
-    };
+    pub fn get(&self, job_id: &str) -> Option<Value> {
+        let jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        jobs.iter().find(|j| j["job_id"] == job_id).cloned()
+    }
 
-    let total = apps.len();
-    let mut healthy = 0;
-    let mut unhealthy = 0;
-    let mut unreachable = 0;
-    let mut results: Vec<Value> = Vec::new();
+    /// Record a new job and run it in the background. Must be called from
+    /// within the Tokio runtime (i.e. a route handler).
+    fn spawn(&self, job: Value, apps: Vec<CheckTarget>, bus: EventBus, config: ScheduleConfig) {
+        let job_id = job["job_id"].as_str().unwrap_or_default().to_string();
+        {
+            let mut jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+            if jobs.len() >= MAX_TRACKED_JOBS {
+                jobs.pop_front();
+            }
+            jobs.push_back(job);
+        }
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .redirect(reqwest::redirect::Policy::limited(5))
-        .build()
-        .unwrap();
+        let db_path = self.db_path.clone();
+        let jobs = self.jobs.clone();
+        tokio::spawn(async move {
+            let outcome = match rusqlite::Connection::open(&db_path) {
+                Ok(conn) => {
+                    let _ = conn.busy_timeout(std::time::Duration::from_secs(5));
+                    Ok(check_apps(&Mutex::new(conn), &bus, &config, apps, false).await)
+                }
+                Err(e) => Err(format!("Failed to open DB: {}", e)),
+            };
 
-    for (app_id, app_name, check_url) in &apps {
-        let start = Instant::now();
-        let result = client.get(check_url.as_str()).send().await;
-        let response_time_ms = start.elapsed().as_millis() as i64;
-
-        let (health_status, status_code, error_message) = match result {
-            Ok(resp) => {
-                let code = resp.status().as_u16() as i64;
-                if resp.status().is_success() {
-                    ("healthy".to_string(), Some(code), None)
-                } else {
-                    (
-                        "unhealthy".to_string(),
-                        Some(code),
-                        Some(format!("HTTP {}", resp.status())),
-                    )
+            let mut jobs = jobs.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(job) = jobs.iter_mut().find(|j| j["job_id"] == job_id.as_str()) else {
+                return;
+            };
+            job["completed_at"] = json!(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+            match outcome {
+                Ok((stats, results)) => {
+                    job["status"] = json!("completed");
+                    summarize(job, &stats, results);
+                }
+                Err(e) => {
+                    job["status"] = json!("failed");
+                    job["error"] = json!(e);
                 }
             }
-            Err(e) => {
-                let msg = if e.is_timeout() {
-                    "Connection timed out (10s)".to_string()
-                } else if e.is_connect() {
-                    "Connection refused or DNS failure".to_string()
-                } else {
-                    format!("{}", e)
-                };
-                ("unreachable".to_string(), None, Some(msg))
-            }
-        };
+        });
+    }
+}
 
-        match health_status.as_str() {
-            "healthy" => healthy += 1,
-            "unhealthy" => unhealthy += 1,
-            _ => unreachable += 1,
-        }
+/// Copy batch totals and per-app results into a response or job.
+fn summarize(out: &mut Value, stats: &BatchStats, results: Vec<Value>) {
+    out["total"] = json!(stats.total);
+    out["checked"] = json!(stats.checked);
+    out["skipped"] = json!(stats.skipped);
+    out["healthy"] = json!(stats.healthy);
+    out["degraded"] = json!(stats.degraded);
+    out["unhealthy"] = json!(stats.unhealthy);
+    out["unreachable"] = json!(stats.unreachable);
+    out["deadline_hit"] = json!(stats.deadline_hit);
+    out["duration_ms"] = json!(stats.duration_ms);
+    out["results"] = json!(results);
+}
 
-        // Record the health check
-        let check_id = uuid::Uuid::new_v4().to_string();
-        {
-            let conn = db.conn();
+/// Approved apps with a URL matching a batch's filters, by name.
+fn select_targets(
+    conn: &rusqlite::Connection,
+    body: &BatchHealthCheckRequest,
+    category: Option<String>,
+    stale_after_secs: Option<u64>,
+) -> rusqlite::Result<Vec<CheckTarget>> {
+    let mut conditions = vec![
+        "status = 'approved'".to_string(),
+        "(api_url IS NOT NULL OR homepage_url IS NOT NULL)".to_string(),
+    ];
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+    if let Some(ids) = &body.app_ids {
+        params.push(Box::new(serde_json::to_string(ids).unwrap_or_default()));
+        conditions.push(format!("id IN (SELECT value FROM json_each(?{}))", params.len()));
+    }
+    if let Some(category) = category {
+        params.push(Box::new(category));
+        conditions.push(categories::subtree_condition("category", params.len()));
+    }
+    if let Some(protocol) = &body.protocol {
+        params.push(Box::new(protocol.clone()));
+        conditions.push(format!("protocol = ?{}", params.len()));
+    }
+    if let Some(secs) = stale_after_secs {
+        params.push(Box::new(format!("-{} seconds", secs)));
+        conditions.push(format!(
+            "(last_checked_at IS NULL OR last_checked_at <= datetime('now', ?{}))",
+            params.len()
+        ));
+    }
 
-            let _ = conn.execute(
-                "INSERT INTO health_checks (id, app_id, status, status_code, response_time_ms, error_message, checked_url)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                rusqlite::params![
-                    check_id,
-                    app_id,
-                    health_status,
-                    status_code,
-                    response_time_ms,
-                    error_message,
-                    check_url,
-                ],
-            );
+    let sql = format!(
+        "SELECT id, name, COALESCE(api_url, homepage_url), health_expectation FROM apps WHERE {} ORDER BY name",
+        conditions.join(" AND ")
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let param_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let rows = stmt.query_map(param_refs.as_slice(), |row| {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?, HealthExpectation::from_column(row.get(3)?)))
+    })?;
+    rows.collect()
+}
 
-            let _ = conn.execute(
-                "UPDATE apps SET last_health_status = ?1, last_checked_at = datetime('now'), updated_at = datetime('now') WHERE id = ?2",
-                rusqlite::params![health_status, app_id],
-            );
+/// Batch health check: check approved apps that have a URL, optionally
+/// narrowed by ids, category, protocol, or staleness. Small batches are
+/// checked inline and return per-app results; large ones (or `queue: true`)
+/// return `202` with a job to poll.
+#[post("/apps/health-check/batch", data = "<body>")]
+pub async fn batch_health_check(
+    key: AuthenticatedKey,
+    body: Option<Json<BatchHealthCheckRequest>>,
+    db: &rocket::State<DbState>,
+    bus: &rocket::State<EventBus>,
+    schedule: &rocket::State<ScheduleStatus>,
+    jobs: &rocket::State<HealthCheckJobs>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::new(
+            ErrorCode::AdminRequired,
+            "Only admins can trigger health checks",
+        )
+        .into();
+    }
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    let config = schedule.config();
 
-            // Recalculate uptime
-            let uptime: Option<f64> = conn
-                .query_row(
-                    "SELECT CAST(SUM(CASE WHEN status = 'healthy' THEN 1 ELSE 0 END) AS REAL) / COUNT(*) * 100.0
-                     FROM (SELECT status FROM health_checks WHERE app_id = ?1 ORDER BY checked_at DESC LIMIT 100)",
-                    rusqlite::params![app_id],
-                    |row| row.get(0),
-                )
-                .ok();
-
-            if let Some(uptime_val) = uptime {
-                let _ = conn.execute(
-                    "UPDATE apps SET uptime_pct = ?1 WHERE id = ?2",
-                    rusqlite::params![uptime_val, app_id],
-                );
-            }
+    if let Some(ids) = &body.app_ids {
+        if ids.is_empty() {
+            return ApiError::new(ErrorCode::NoApps, "app_ids must not be empty").into();
+        }
+        if ids.len() > BATCH_MAX_IDS {
+            return ApiError::new(
+                ErrorCode::TooManyApps,
+                format!("At most {} app_ids per batch", BATCH_MAX_IDS),
+            )
+            .into();
         }
+    }
+    if let Some(protocol) = &body.protocol {
+        if !VALID_PROTOCOLS.contains(&protocol.as_str()) {
+            return ApiError::new(
+                ErrorCode::InvalidProtocol,
+                format!("Valid protocols: {}", VALID_PROTOCOLS.join(", ")),
+            )
+            .into();
+        }
+    }
+    let category = match body.category.as_deref().map(categories::parse_category_path).transpose() {
+        Ok(c) => c,
+        Err(msg) => return ApiError::new(ErrorCode::InvalidCategory, msg).into(),
+    };
 
-        results.push(json!({
-            "app_id": app_id,
-            "app_name": app_name,
-            "status": health_status,
-            "status_code": status_code,
-            "response_time_ms": response_time_ms,
-            "error_message": error_message,
-        }));
+    let stale_after_secs = body.stale_only.then(|| {
+        body.stale_after_secs.unwrap_or(match config.interval_secs {
+            0 => 3600,
+            secs => secs,
+        })
+    });
+    let apps = match select_targets(&db.conn(), &body, category, stale_after_secs) {
+        Ok(apps) => apps,
+        Err(e) => return ApiError::new(ErrorCode::DbError, e.to_string()).into(),
+    };
+
+    // Requested ids that aren't approved apps with a URL, or that the
+    // other filters ruled out
+    let unmatched: Vec<&String> = body
+        .app_ids
+        .iter()
+        .flatten()
+        .filter(|id| !apps.iter().any(|(app_id, ..)| app_id == *id))
+        .collect();
+
+    if body.queue.unwrap_or(apps.len() > SYNC_BATCH_LIMIT) {
+        let job_id = uuid::Uuid::new_v4().to_string();
+        let job = json!({
+            "job_id": job_id,
+            "status": "running",
+            "total": apps.len(),
+            "unmatched": unmatched,
+            "created_at": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "completed_at": null,
+        });
+        let response = json!({
+            "job_id": job_id,
+            "status": "running",
+            "total": apps.len(),
+            "unmatched": unmatched,
+            "status_url": format!("/api/v1/apps/health-check/batch/{}", job_id),
+        });
+        jobs.spawn(job, apps, bus.inner().clone(), config);
+        return (Status::Accepted, Json(response));
     }
 
-    (
-        Status::Ok,
-        Json(json!({
-            "total": total,
-            "healthy": healthy,
-            "unhealthy": unhealthy,
-            "unreachable": unreachable,
-            "results": results,
-        })),
-    )
+    let (stats, results) = check_apps(&db.0, bus, &config, apps, false).await;
+    let mut response = json!({ "unmatched": unmatched });
+    summarize(&mut response, &stats, results);
+    (Status::Ok, Json(response))
+}
+
+/// Progress and results of a queued batch health check.
+#[get("/apps/health-check/batch/<job_id>")]
+pub fn get_batch_health_check(
+    key: AuthenticatedKey,
+    job_id: &str,
+    jobs: &rocket::State<HealthCheckJobs>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::new(ErrorCode::AdminRequired, "Only admins can view batch health checks").into();
+    }
+    match jobs.get(job_id) {
+        Some(job) => (Status::Ok, Json(job)),
+        None => ApiError::new(ErrorCode::NotFound, "Batch health check not found").into(),
+    }
 }
 
 /// Get health check history for an app.
 #[get("/apps/<app_id>/health?<page>&<per_page>")]
 pub fn get_health_history(
+    reader: ReadAccess,
+    token: EditTokenParam,
     app_id: &str,
     page: Option<i64>,
     per_page: Option<i64>,
@@ -336,21 +594,10 @@ pub fn get_health_history(
 ) -> (Status, Json<Value>) {
     let conn = db.conn();
 
-    // Resolve app ID (support slug lookup)
-    let resolved_id: Result<String, _> = conn.query_row(
-        "SELECT id FROM apps WHERE id = ?1 OR slug = ?1",
-        rusqlite::params![app_id],
-        |row| row.get(0),
-    );
-
-    let resolved_id = match resolved_id {
-        Ok(id) => id,
-        Err(_) => {
-            return (
-                Status::NotFound,
-                Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-            )
-        }
+    // Resolve app ID (support slug lookup); hidden listings read as missing
+    let resolved_id = match crate::routes::visible_app_id(&conn, app_id, &reader, token.0.as_deref()) {
+        Some(id) => id,
+        None => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
     };
 
     let page = page.unwrap_or(1).max(1);
@@ -388,21 +635,21 @@ pub fn get_health_history(
         .filter_map(|r| r.ok())
         .collect();
 
-    // Get current uptime
-    let uptime: Option<f64> = conn
+    // Get current uptime and the owner's success criteria
+    let (uptime, expectation): (Option<f64>, Option<HealthExpectation>) = conn
         .query_row(
-            "SELECT uptime_pct FROM apps WHERE id = ?1",
+            "SELECT uptime_pct, health_expectation FROM apps WHERE id = ?1",
             rusqlite::params![resolved_id],
-            |row| row.get(0),
+            |row| Ok((row.get(0)?, HealthExpectation::from_column(row.get(1)?))),
         )
-        .ok()
-        .flatten();
+        .unwrap_or((None, None));
 
     (
         Status::Ok,
         Json(json!({
             "app_id": resolved_id,
             "uptime_pct": uptime,
+            "expectation": expectation,
             "checks": checks,
             "total": total,
             "page": page,
@@ -411,15 +658,396 @@ pub fn get_health_history(
     )
 }
 
+/// Nearest-rank percentile of an ascending slice.
+pub fn percentile(sorted: &[i64], p: f64) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Daily uptime buckets for status-page style bars.
+/// `days` defaults to 30 (max 90); days without checks have `uptime_pct: null`.
+#[get("/apps/<app_id>/uptime?<days>")]
+pub fn get_uptime(
+    reader: ReadAccess,
+    token: EditTokenParam,
+    app_id: &str,
+    days: Option<i64>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+
+    let resolved_id = match crate::routes::visible_app_id(&conn, app_id, &reader, token.0.as_deref()) {
+        Some(id) => id,
+        None => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+
+    let days = days.unwrap_or(30).clamp(1, 90);
+    let since = format!("-{} days", days - 1);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT date(checked_at), COUNT(*),
+                    SUM(CASE WHEN status = 'healthy' THEN 1 ELSE 0 END),
+                    AVG(response_time_ms),
+                    SUM(CASE WHEN status = 'degraded' THEN 1 ELSE 0 END)
+             FROM health_checks
+             WHERE app_id = ?1 AND checked_at >= date('now', ?2)
+             GROUP BY date(checked_at)",
+        )
+        .unwrap();
+    let by_day: std::collections::HashMap<String, (i64, i64, Option<f64>, i64)> = stmt
+        .query_map(rusqlite::params![resolved_id, since], |row| {
+            Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let today = chrono::Utc::now().date_naive();
+    let mut total_checks = 0;
+    let mut total_healthy = 0;
+    let buckets: Vec<Value> = (0..days)
+        .rev()
+        .map(|ago| {
+            let date = (today - chrono::Duration::days(ago)).format("%Y-%m-%d").to_string();
+            let (checks, healthy, avg_ms, degraded) = by_day.get(&date).cloned().unwrap_or((0, 0, None, 0));
+            total_checks += checks;
+            total_healthy += healthy;
+            json!({
+                "date": date,
+                "checks": checks,
+                "healthy": healthy,
+                "degraded": degraded,
+                "uptime_pct": (checks > 0).then(|| healthy as f64 / checks as f64 * 100.0),
+                "avg_response_time_ms": avg_ms.map(|v| v.round() as i64),
+            })
+        })
+        .collect();
+
+    let mut latencies: Vec<i64> = conn
+        .prepare(
+            "SELECT response_time_ms FROM health_checks
+             WHERE app_id = ?1 AND checked_at >= date('now', ?2) AND response_time_ms IS NOT NULL",
+        )
+        .unwrap()
+        .query_map(rusqlite::params![resolved_id, since], |row| row.get(0))
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+    latencies.sort_unstable();
+    let avg = (!latencies.is_empty())
+        .then(|| (latencies.iter().sum::<i64>() as f64 / latencies.len() as f64).round() as i64);
+
+    (
+        Status::Ok,
+        Json(json!({
+            "app_id": resolved_id,
+            "days": days,
+            "checks": total_checks,
+            "uptime_pct": (total_checks > 0).then(|| total_healthy as f64 / total_checks as f64 * 100.0),
+            "latency_ms": {
+                "avg": avg,
+                "p50": percentile(&latencies, 50.0),
+                "p95": percentile(&latencies, 95.0),
+                "p99": percentile(&latencies, 99.0),
+            },
+            "buckets": buckets,
+        })),
+    )
+}
+
+/// Windows `GET /apps/<id>/health/metrics` reports on, with their length in seconds.
+pub const METRIC_WINDOWS: &[(&str, i64)] = &[("1h", 3_600), ("24h", 86_400), ("7d", 604_800), ("30d", 2_592_000)];
+
+/// How long a computed metrics response is reused.
+const METRICS_TTL: Duration = Duration::from_secs(30);
+
+/// Relative p95 change beyond which the latency trend counts as moving.
+const TREND_THRESHOLD: f64 = 0.10;
+
+/// Recent `GET /apps/<id>/health/metrics` responses by app and windows.
+#[derive(Default)]
+pub struct HealthMetricsCache(Mutex<HashMap<(String, String), (Instant, Value)>>);
+
+/// Check counts and latency percentiles for one period.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct PeriodMetrics {
+    checks: i64,
+    failures: i64,
+    avg: Option<f64>,
+    p50: Option<i64>,
+    p95: Option<i64>,
+    p99: Option<i64>,
+}
+
+impl PeriodMetrics {
+    fn failure_rate(&self) -> Option<f64> {
+        (self.checks > 0).then(|| self.failures as f64 / self.checks as f64)
+    }
+}
+
+/// Metrics for each window and the period of equal length before it, in
+/// one pass: checks are ranked by latency per (window, period) and the
+/// nearest-rank percentile is the smallest latency whose rank reaches it.
+fn period_metrics(
+    conn: &rusqlite::Connection,
+    app_id: &str,
+    windows: &[(&str, i64)],
+) -> rusqlite::Result<HashMap<(String, bool), PeriodMetrics>> {
+    let values: Vec<String> = windows
+        .iter()
+        .map(|(name, secs)| format!("('{name}', datetime('now', '-{secs} seconds'), datetime('now', '-{} seconds'))", secs * 2))
+        .collect();
+    let percentile = |p: u32| {
+        format!("MIN(CASE WHEN response_time_ms IS NOT NULL AND rn * 100 >= {p} * timed THEN response_time_ms END)")
+    };
+    let sql = format!(
+        "WITH windows(name, since, previous_since) AS (VALUES {}),
+         ranked AS (
+             SELECT w.name, h.checked_at >= w.since AS current, h.status, h.response_time_ms,
+                    ROW_NUMBER() OVER (PARTITION BY w.name, h.checked_at >= w.since
+                                       ORDER BY h.response_time_ms IS NULL, h.response_time_ms) AS rn,
+                    COUNT(h.response_time_ms) OVER (PARTITION BY w.name, h.checked_at >= w.since) AS timed
+             FROM windows w
+             JOIN health_checks h ON h.app_id = ?1 AND h.checked_at >= w.previous_since
+         )
+         SELECT name, current, COUNT(*), SUM(status != 'healthy'), AVG(response_time_ms), {}, {}, {}
+         FROM ranked
+         GROUP BY name, current",
+        values.join(", "),
+        percentile(50),
+        percentile(95),
+        percentile(99),
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(rusqlite::params![app_id], |row| {
+        Ok((
+            (row.get::<_, String>(0)?, row.get::<_, bool>(1)?),
+            PeriodMetrics {
+                checks: row.get(2)?,
+                failures: row.get(3)?,
+                avg: row.get(4)?,
+                p50: row.get(5)?,
+                p95: row.get(6)?,
+                p99: row.get(7)?,
+            },
+        ))
+    })?;
+    rows.collect()
+}
+
+/// `improving`, `degrading` or `stable` by how p95 latency moved against
+/// the previous period; `null` without latency in both.
+fn latency_trend(current: &PeriodMetrics, previous: &PeriodMetrics) -> Option<&'static str> {
+    let (now, before) = (current.p95? as f64, previous.p95? as f64);
+    let change = (now - before) / before.max(1.0);
+    Some(if change > TREND_THRESHOLD {
+        "degrading"
+    } else if change < -TREND_THRESHOLD {
+        "improving"
+    } else {
+        "stable"
+    })
+}
+
+/// Latency percentiles and failure rate for an app over `window` (comma-
+/// separated: 1h, 24h, 7d, 30d; default all), each compared with the
+/// period before it. Cached for 30 seconds.
+#[get("/apps/<app_id>/health/metrics?<window>")]
+pub fn get_health_metrics(
+    reader: ReadAccess,
+    token: EditTokenParam,
+    app_id: &str,
+    window: Option<&str>,
+    db: &rocket::State<DbState>,
+    cache: &rocket::State<HealthMetricsCache>,
+) -> (Status, Json<Value>) {
+    let mut windows: Vec<(&str, i64)> = Vec::new();
+    for name in window.unwrap_or_default().split(',').map(str::trim).filter(|w| !w.is_empty()) {
+        match METRIC_WINDOWS.iter().find(|(w, _)| *w == name) {
+            Some(found) if !windows.contains(found) => windows.push(*found),
+            Some(_) => {}
+            None => {
+                let names: Vec<&str> = METRIC_WINDOWS.iter().map(|(w, _)| *w).collect();
+                return ApiError::new(ErrorCode::InvalidPeriod, format!("window must be one of: {}", names.join(", ")))
+                    .with("window", name)
+                    .into();
+            }
+        }
+    }
+    if windows.is_empty() {
+        windows = METRIC_WINDOWS.to_vec();
+    }
+
+    let conn = db.conn();
+    let resolved_id = match crate::routes::visible_app_id(&conn, app_id, &reader, token.0.as_deref()) {
+        Some(id) => id,
+        None => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+
+    let cache_key = (resolved_id.clone(), windows.iter().map(|(w, _)| *w).collect::<Vec<_>>().join(","));
+    let mut cached = cache.0.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((at, body)) = cached.get(&cache_key) {
+        if at.elapsed() < METRICS_TTL {
+            return (Status::Ok, Json(body.clone()));
+        }
+    }
+
+    let metrics = match period_metrics(&conn, &resolved_id, &windows) {
+        Ok(metrics) => metrics,
+        Err(e) => {
+            eprintln!("❌ Health metrics query failed: {e}");
+            return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+        }
+    };
+    let results: Vec<Value> = windows
+        .iter()
+        .map(|(name, secs)| {
+            let current = metrics.get(&(name.to_string(), true)).copied().unwrap_or_default();
+            let previous = metrics.get(&(name.to_string(), false)).copied().unwrap_or_default();
+            json!({
+                "window": name,
+                "seconds": secs,
+                "checks": current.checks,
+                "failures": current.failures,
+                "failure_rate": current.failure_rate(),
+                "latency_ms": {
+                    "avg": current.avg.map(|v| v.round() as i64),
+                    "p50": current.p50,
+                    "p95": current.p95,
+                    "p99": current.p99,
+                },
+                "previous": {
+                    "checks": previous.checks,
+                    "failure_rate": previous.failure_rate(),
+                    "p50": previous.p50,
+                    "p95": previous.p95,
+                },
+                "latency_trend": latency_trend(&current, &previous),
+            })
+        })
+        .collect();
+
+    let body = json!({
+        "app_id": resolved_id,
+        "windows": results,
+        "generated_at": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    });
+    cached.retain(|_, (at, _)| at.elapsed() < METRICS_TTL);
+    cached.insert(cache_key, (Instant::now(), body.clone()));
+    (Status::Ok, Json(body))
+}
+
+/// Most upstream apps one app can declare in `depends_on`.
+pub const MAX_DEPENDENCIES: usize = 20;
+
+/// How bad a health status is for roll-ups. Never checked sits between
+/// healthy and degraded.
+fn severity(status: Option<&str>) -> u8 {
+    match status {
+        Some("healthy") => 0,
+        Some("degraded") => 2,
+        Some("unhealthy") => 3,
+        Some("unreachable") => 4,
+        _ => 1,
+    }
+}
+
+/// Combined status of an app and the apps it depends on: `healthy` only when
+/// every one of them is, otherwise the worst of them, with `unknown` for
+/// apps not checked yet.
+pub fn roll_up<'a>(statuses: impl IntoIterator<Item = Option<&'a str>>) -> &'a str {
+    statuses
+        .into_iter()
+        .max_by_key(|status| severity(*status))
+        .map_or("healthy", |status| status.filter(|s| severity(Some(*s)) != 1).unwrap_or("unknown"))
+}
+
+/// Resolve `depends_on` ids or slugs to app ids for `app_id`, rejecting the
+/// app itself, unknown and private apps, and more than `MAX_DEPENDENCIES`.
+pub fn resolve_dependencies(conn: &rusqlite::Connection, app_id: &str, depends_on: &[String]) -> Result<Vec<String>, ApiError> {
+    let mut ids: Vec<String> = Vec::new();
+    for id_or_slug in depends_on {
+        let id: Option<String> = conn
+            .query_row(
+                "SELECT id FROM apps WHERE (id = ?1 OR slug = ?1) AND visibility != 'private'",
+                rusqlite::params![id_or_slug.trim()],
+                |r| r.get(0),
+            )
+            .ok();
+        match id {
+            Some(id) if id != app_id => {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+            _ => return Err(ApiError::from(ErrorCode::InvalidDependency).with("dependency", id_or_slug.as_str())),
+        }
+    }
+    if ids.len() > MAX_DEPENDENCIES {
+        return Err(ApiError::from(ErrorCode::InvalidDependency).with("max", MAX_DEPENDENCIES));
+    }
+    Ok(ids)
+}
+
+/// Replace the apps `app_id` depends on.
+pub fn set_dependencies(conn: &rusqlite::Connection, app_id: &str, ids: &[String]) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM app_dependencies WHERE app_id = ?1", rusqlite::params![app_id])?;
+    for id in ids {
+        conn.execute(
+            "INSERT INTO app_dependencies (app_id, depends_on_id) VALUES (?1, ?2)",
+            rusqlite::params![app_id, id],
+        )?;
+    }
+    Ok(())
+}
+
+/// The apps `app_id` declares it depends on, with their last health status,
+/// in the order declared.
+pub fn dependencies(conn: &rusqlite::Connection, app_id: &str) -> Vec<Value> {
+    conn.prepare(
+        "SELECT a.id, a.name, a.slug, a.last_health_status
+         FROM app_dependencies d JOIN apps a ON a.id = d.depends_on_id
+         WHERE d.app_id = ?1 ORDER BY d.rowid",
+    )
+    .and_then(|mut stmt| {
+        stmt.query_map(rusqlite::params![app_id], |r| {
+            Ok(json!({
+                "id": r.get::<_, String>(0)?,
+                "name": r.get::<_, String>(1)?,
+                "slug": r.get::<_, String>(2)?,
+                "last_health_status": r.get::<_, Option<String>>(3)?,
+            }))
+        })?
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Composite health of an app with declared dependencies (see [`roll_up`]),
+/// or `None` when it declares none. Only direct dependencies count.
+pub fn composite_health(own_status: Option<&str>, dependencies: &[Value]) -> Option<String> {
+    if dependencies.is_empty() {
+        return None;
+    }
+    let statuses = dependencies.iter().map(|d| d["last_health_status"].as_str());
+    Some(roll_up(std::iter::once(own_status).chain(statuses)).to_string())
+}
+
 /// Health summary: overview of all apps' health status.
 #[get("/apps/health/summary")]
-pub fn health_summary(db: &rocket::State<DbState>) -> Json<Value> {
+pub fn health_summary(db: &rocket::State<DbState>, reader: ReadAccess) -> Json<Value> {
     let conn = db.conn();
+    // Private listings only count for signed-in readers, as in the app list
+    let visible = listed_visibility("visibility", reader.is_signed_in());
 
     // Get summary counts
     let total_apps: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM apps WHERE status = 'approved'",
+            &format!("SELECT COUNT(*) FROM apps WHERE status = 'approved' AND {visible}"),
             [],
             |r| r.get(0),
         )
@@ -427,7 +1055,7 @@ pub fn health_summary(db: &rocket::State<DbState>) -> Json<Value> {
 
     let monitored: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM apps WHERE status = 'approved' AND last_health_status IS NOT NULL",
+            &format!("SELECT COUNT(*) FROM apps WHERE status = 'approved' AND {visible} AND last_health_status IS NOT NULL"),
             [],
             |r| r.get(0),
         )
@@ -435,7 +1063,15 @@ pub fn health_summary(db: &rocket::State<DbState>) -> Json<Value> {
 
     let healthy: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM apps WHERE status = 'approved' AND last_health_status = 'healthy'",
+            &format!("SELECT COUNT(*) FROM apps WHERE status = 'approved' AND {visible} AND last_health_status = 'healthy'"),
+            [],
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+
+    let degraded: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM apps WHERE status = 'approved' AND {visible} AND last_health_status = 'degraded'"),
             [],
             |r| r.get(0),
         )
@@ -443,7 +1079,7 @@ pub fn health_summary(db: &rocket::State<DbState>) -> Json<Value> {
 
     let unhealthy: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM apps WHERE status = 'approved' AND last_health_status = 'unhealthy'",
+            &format!("SELECT COUNT(*) FROM apps WHERE status = 'approved' AND {visible} AND last_health_status = 'unhealthy'"),
             [],
             |r| r.get(0),
         )
@@ -451,20 +1087,20 @@ pub fn health_summary(db: &rocket::State<DbState>) -> Json<Value> {
 
     let unreachable: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM apps WHERE status = 'approved' AND last_health_status = 'unreachable'",
+            &format!("SELECT COUNT(*) FROM apps WHERE status = 'approved' AND {visible} AND last_health_status = 'unreachable'"),
             [],
             |r| r.get(0),
         )
         .unwrap_or(0);
 
-    // Get apps with issues (unhealthy or unreachable)
+    // Get apps with issues (degraded, unhealthy or unreachable)
     let mut stmt = conn
-        .prepare(
+        .prepare(&format!(
             "SELECT id, name, slug, last_health_status, last_checked_at, uptime_pct
              FROM apps
-             WHERE status = 'approved' AND last_health_status IN ('unhealthy', 'unreachable')
-             ORDER BY last_checked_at DESC",
-        )
+             WHERE status = 'approved' AND {visible} AND last_health_status IN ('degraded', 'unhealthy', 'unreachable')
+             ORDER BY last_checked_at DESC"
+        ))
         .unwrap();
 
     let issues: Vec<Value> = stmt
@@ -482,12 +1118,109 @@ pub fn health_summary(db: &rocket::State<DbState>) -> Json<Value> {
         .filter_map(|r| r.ok())
         .collect();
 
+    // Apps whose declared dependencies drag their composite health down
+    let with_dependencies: Vec<(String, String, String, Option<String>)> = conn
+        .prepare(&format!(
+            "SELECT id, name, slug, last_health_status FROM apps
+             WHERE status = 'approved' AND {visible} AND id IN (SELECT app_id FROM app_dependencies)
+             ORDER BY name"
+        ))
+        .and_then(|mut stmt| stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))?.collect())
+        .unwrap_or_default();
+    let mut composite_healthy = 0;
+    let mut composite_issues = Vec::new();
+    for (id, name, slug, status) in &with_dependencies {
+        let dependencies = dependencies(&conn, id);
+        let composite = composite_health(status.as_deref(), &dependencies);
+        if composite.as_deref() == Some("healthy") {
+            composite_healthy += 1;
+            continue;
+        }
+        let failing: Vec<&Value> = dependencies.iter().filter(|d| d["last_health_status"] != "healthy").collect();
+        composite_issues.push(json!({
+            "id": id,
+            "name": name,
+            "slug": slug,
+            "last_health_status": status,
+            "composite_health": composite,
+            "failing_dependencies": failing,
+        }));
+    }
+
     Json(json!({
         "total_approved_apps": total_apps,
         "monitored": monitored,
         "healthy": healthy,
+        "degraded": degraded,
         "unhealthy": unhealthy,
         "unreachable": unreachable,
         "issues": issues,
+        "composite": {
+            "apps_with_dependencies": with_dependencies.len(),
+            "healthy": composite_healthy,
+            "issues": composite_issues,
+        },
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn composite_health_is_the_worst_status() {
+        assert_eq!(roll_up([Some("healthy"), Some("healthy")]), "healthy");
+        assert_eq!(roll_up([Some("healthy"), None]), "unknown");
+        assert_eq!(roll_up([None, Some("degraded"), Some("healthy")]), "degraded");
+        assert_eq!(roll_up([Some("unreachable"), Some("unhealthy")]), "unreachable");
+        assert_eq!(composite_health(Some("healthy"), &[]), None);
+    }
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let v: Vec<i64> = (1..=100).collect();
+        assert_eq!(percentile(&v, 50.0), Some(50));
+        assert_eq!(percentile(&v, 95.0), Some(95));
+        assert_eq!(percentile(&[7], 99.0), Some(7));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn matches_expectations() {
+        let body = r#"{"status":"ok","checks":[{"db":"up"}]}"#;
+        let expect = |json: Value| serde_json::from_value::<HealthExpectation>(json).unwrap();
+        assert!(expect(json!({ "contains": "\"ok\"" })).mismatch(body).is_none());
+        assert!(expect(json!({ "json_pointer": "/checks/0/db", "json_value": "up" })).mismatch(body).is_none());
+        assert_eq!(
+            expect(json!({ "json_pointer": "/status", "json_value": "down" })).mismatch(body).unwrap(),
+            r#"/status is "ok", expected "down""#
+        );
+        assert_eq!(
+            expect(json!({ "json_pointer": "/status" })).mismatch("OK").unwrap(),
+            "Response body is not JSON"
+        );
+        assert!(expect(json!({})).validate().unwrap().is_none());
+        assert!(expect(json!({ "json_value": 1 })).validate().is_err());
+    }
+
+    #[test]
+    fn labels_transitions() {
+        let data = || json!({ "app_id": "a" });
+        assert!(health_changed_event(None, "healthy", data()).is_none());
+        assert!(health_changed_event(Some("unhealthy"), "unhealthy", data()).is_none());
+
+        let down = health_changed_event(None, "unhealthy", data()).unwrap();
+        assert_eq!(down.event, "app.health_changed");
+        assert_eq!(down.data["transition"], "unhealthy");
+
+        let up = health_changed_event(Some("unreachable"), "healthy", data()).unwrap();
+        assert_eq!(up.data["transition"], "recovered");
+        assert_eq!(up.data["previous_status"], "unreachable");
+        assert_eq!(up.data["app_id"], "a");
+
+        let sent = transition_payload(&up.data);
+        assert_eq!(sent["old_status"], "unreachable");
+        assert_eq!(sent["new_status"], "healthy");
+        assert_eq!(sent["scheduled"], false);
+    }
+}
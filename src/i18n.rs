@@ -0,0 +1,174 @@
+use rocket::request::{FromRequest, Outcome, Request};
+use rusqlite::Connection;
+use serde_json::Value;
+
+/// Language of the base listing fields, configurable via `DEFAULT_LANGUAGE`.
+pub struct DefaultLanguage(pub String);
+
+/// Fields that can be translated per language.
+pub const TRANSLATABLE_FIELDS: &[&str] = &["name", "short_description", "description"];
+
+/// Languages from the request's `Accept-Language` header, most preferred first.
+/// Wildcards and `q=0` entries are dropped.
+#[derive(Debug, Default)]
+pub struct AcceptLanguage(pub Vec<String>);
+
+impl AcceptLanguage {
+    pub fn parse(header: &str) -> Self {
+        let mut langs: Vec<(String, f32)> = header
+            .split(',')
+            .filter_map(|part| {
+                let mut pieces = part.split(';');
+                let tag = pieces.next()?.trim();
+                if tag.is_empty() || tag == "*" {
+                    return None;
+                }
+                let q = pieces
+                    .filter_map(|p| p.trim().strip_prefix("q="))
+                    .find_map(|v| v.trim().parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                if q <= 0.0 {
+                    return None;
+                }
+                Some((normalize_lang(tag), q))
+            })
+            .collect();
+
+        // Stable sort keeps header order for equal weights
+        langs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        AcceptLanguage(langs.into_iter().map(|(l, _)| l).collect())
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AcceptLanguage {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let langs = request
+            .headers()
+            .get_one("Accept-Language")
+            .map(AcceptLanguage::parse)
+            .unwrap_or_default();
+        Outcome::Success(langs)
+    }
+}
+
+/// Lowercase a language tag (`pt-BR` → `pt-br`) for storage and matching.
+pub fn normalize_lang(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Check a language tag looks like BCP 47: a 2–3 letter primary subtag
+/// followed by optional alphanumeric subtags of up to 8 characters.
+pub fn is_valid_lang(tag: &str) -> bool {
+    let mut parts = tag.split('-');
+    let primary = parts.next().unwrap_or("");
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|p| (1..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Pick the stored translation that best matches the requested languages.
+/// Each requested tag is tried exactly, then by its primary subtag (`de-at` → `de`).
+fn best_translation(conn: &Connection, app_id: &str, requested: &[String]) -> Option<String> {
+    let available: Vec<String> = available_languages(conn, app_id);
+    if available.is_empty() {
+        return None;
+    }
+
+    for lang in requested {
+        if available.contains(lang) {
+            return Some(lang.clone());
+        }
+        let primary = lang.split('-').next().unwrap_or(lang);
+        if available.iter().any(|a| a == primary) {
+            return Some(primary.to_string());
+        }
+    }
+    None
+}
+
+/// Languages with a stored translation for an app, sorted.
+pub fn available_languages(conn: &Connection, app_id: &str) -> Vec<String> {
+    let mut stmt = match conn.prepare("SELECT lang FROM app_translations WHERE app_id = ?1 ORDER BY lang") {
+        Ok(s) => s,
+        Err(_) => return Vec::new(),
+    };
+    stmt.query_map(rusqlite::params![app_id], |r| r.get::<_, String>(0))
+        .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Overlay the best matching translation onto an app JSON object and set its `lang`.
+///
+/// Fields missing from the translation keep their default-language values.
+/// The default language wins if it's preferred over every translated one.
+pub fn localize(conn: &Connection, app: &mut Value, requested: &AcceptLanguage, default_lang: &str) {
+    app["lang"] = Value::String(default_lang.to_string());
+
+    let app_id = match app.get("id").and_then(|v| v.as_str()) {
+        Some(id) => id.to_string(),
+        None => return,
+    };
+
+    // Only consider languages preferred over the default one
+    let default_primary = default_lang.split('-').next().unwrap_or(default_lang);
+    let candidates: Vec<String> = requested
+        .0
+        .iter()
+        .take_while(|l| *l != default_lang && l.split('-').next() != Some(default_primary))
+        .cloned()
+        .collect();
+    if candidates.is_empty() {
+        return;
+    }
+
+    let lang = match best_translation(conn, &app_id, &candidates) {
+        Some(l) => l,
+        None => return,
+    };
+
+    let row = conn.query_row(
+        "SELECT name, short_description, description FROM app_translations WHERE app_id = ?1 AND lang = ?2",
+        rusqlite::params![app_id, lang],
+        |r| {
+            Ok([
+                r.get::<_, Option<String>>(0)?,
+                r.get::<_, Option<String>>(1)?,
+                r.get::<_, Option<String>>(2)?,
+            ])
+        },
+    );
+
+    if let Ok(values) = row {
+        for (field, value) in TRANSLATABLE_FIELDS.iter().zip(values) {
+            if let Some(v) = value {
+                app[*field] = Value::String(v);
+            }
+        }
+        app["lang"] = Value::String(lang);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_accept_language_by_weight() {
+        let langs = AcceptLanguage::parse("en;q=0.5, de-DE, fr;q=0.8, *;q=0.1, es;q=0");
+        assert_eq!(langs.0, vec!["de-de", "fr", "en"]);
+    }
+
+    #[test]
+    fn validates_language_tags() {
+        assert!(is_valid_lang("de"));
+        assert!(is_valid_lang("pt-br"));
+        assert!(is_valid_lang("zh-hant-tw"));
+        assert!(!is_valid_lang("d"));
+        assert!(!is_valid_lang("english"));
+        assert!(!is_valid_lang("de_DE"));
+        assert!(!is_valid_lang("de-"));
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use crate::events::{AppEvent, EventBus};
+
+/// Default multiple of a key's baseline that counts as an anomaly.
+pub const DEFAULT_ANOMALY_FACTOR: f64 = 5.0;
+
+/// Default floor: hours with fewer requests than this are never flagged.
+pub const DEFAULT_ANOMALY_MIN_REQUESTS: i64 = 100;
+
+/// Trailing hours averaged into a key's baseline.
+pub const BASELINE_HOURS: i64 = 24;
+
+/// A throttled key gets this fraction of its rate limit (and no burst).
+pub const THROTTLE_DIVISOR: i64 = 10;
+
+/// Anomaly detection settings, part of the reloadable scheduler config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnomalyConfig {
+    /// Flag a key when its current hour reaches this multiple of its
+    /// baseline; 0 disables detection.
+    pub factor: f64,
+    /// Minimum requests in the hour before a key can be flagged.
+    pub min_requests: i64,
+    /// Minutes a flagged non-admin key runs at a reduced rate limit; 0 only
+    /// emits the event.
+    pub throttle_mins: i64,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        AnomalyConfig {
+            factor: DEFAULT_ANOMALY_FACTOR,
+            min_requests: DEFAULT_ANOMALY_MIN_REQUESTS,
+            throttle_mins: 0,
+        }
+    }
+}
+
+impl AnomalyConfig {
+    /// Defaults, overridden by `KEY_ANOMALY_FACTOR`, `KEY_ANOMALY_MIN_REQUESTS`
+    /// and `KEY_ANOMALY_THROTTLE_MINS`.
+    pub fn from_env() -> Self {
+        fn env<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+        AnomalyConfig {
+            factor: env("KEY_ANOMALY_FACTOR").filter(|f: &f64| *f >= 0.0).unwrap_or(DEFAULT_ANOMALY_FACTOR),
+            min_requests: env("KEY_ANOMALY_MIN_REQUESTS").unwrap_or(DEFAULT_ANOMALY_MIN_REQUESTS).max(1),
+            throttle_mins: env("KEY_ANOMALY_THROTTLE_MINS").unwrap_or(0).max(0),
+        }
+    }
+}
+
+/// `key_usage_hourly.hour` value for the hour containing `at`.
+pub fn hour_start(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:00:00").to_string()
+}
+
+/// Authenticated requests per key and hour, counted in memory and written
+/// to `key_usage_hourly` by the scheduler. Only the current and previous
+/// hour are kept between flushes. Cheaply cloneable.
+#[derive(Clone, Default)]
+pub struct KeyUsage {
+    hours: Arc<Mutex<BTreeMap<String, HashMap<String, i64>>>>,
+}
+
+impl KeyUsage {
+    pub fn record(&self, key_id: &str) {
+        let mut hours = self.hours.lock().unwrap_or_else(|e| e.into_inner());
+        *hours.entry(hour_start(Utc::now())).or_default().entry(key_id.to_string()).or_default() += 1;
+        while hours.len() > 2 {
+            hours.pop_first();
+        }
+    }
+
+    /// Add the counts so far to `key_usage_hourly` and reset them.
+    pub fn flush(&self, conn: &rusqlite::Connection) {
+        let hours = std::mem::take(&mut *self.hours.lock().unwrap_or_else(|e| e.into_inner()));
+        for (hour, counts) in hours {
+            for (key_id, requests) in counts {
+                if let Err(e) = conn.execute(
+                    "INSERT INTO key_usage_hourly (key_id, hour, requests) VALUES (?1, ?2, ?3)
+                     ON CONFLICT (key_id, hour) DO UPDATE SET requests = requests + excluded.requests",
+                    rusqlite::params![key_id, hour, requests],
+                ) {
+                    rocket::error!("Key usage flush failed: {}", e);
+                }
+            }
+        }
+    }
+}
+
+/// Compare each key's requests in the current hour with its average hour
+/// over the previous `BASELINE_HOURS` (hours without requests count as 0).
+/// A key at `factor` times its baseline and at least `min_requests` gets one
+/// `key.anomaly` event for the hour and, when `throttle_mins` is set and it
+/// isn't an admin key, a reduced rate limit until `throttled_until`.
+/// Returns the ids of the keys flagged.
+pub fn detect_anomalies(conn: &rusqlite::Connection, bus: &EventBus, config: &AnomalyConfig) -> Vec<String> {
+    let now = Utc::now();
+    let _ = conn.execute(
+        "DELETE FROM key_usage_hourly WHERE hour < ?1",
+        rusqlite::params![hour_start(now - chrono::Duration::hours(2 * BASELINE_HOURS))],
+    );
+    if config.factor <= 0.0 {
+        return Vec::new();
+    }
+
+    let hour = hour_start(now);
+    let candidates: Vec<(String, String, bool, i64, i64)> = conn
+        .prepare(
+            "SELECT u.key_id, k.name, k.is_admin, u.requests,
+                    (SELECT COALESCE(SUM(b.requests), 0) FROM key_usage_hourly b
+                     WHERE b.key_id = u.key_id AND b.hour >= ?2 AND b.hour < ?1)
+             FROM key_usage_hourly u JOIN api_keys k ON k.id = u.key_id
+             WHERE u.hour = ?1 AND u.flagged = 0 AND k.revoked = 0 AND u.requests >= ?3",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(
+                rusqlite::params![hour, hour_start(now - chrono::Duration::hours(BASELINE_HOURS)), config.min_requests],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get::<_, i32>(2)? != 0, r.get(3)?, r.get(4)?)),
+            )?
+            .collect()
+        })
+        .unwrap_or_else(|e| {
+            rocket::error!("Key anomaly check: query error: {}", e);
+            Vec::new()
+        });
+
+    let mut flagged = Vec::new();
+    for (key_id, name, is_admin, requests, trailing) in candidates {
+        let baseline = trailing as f64 / BASELINE_HOURS as f64;
+        if (requests as f64) < config.factor * baseline {
+            continue;
+        }
+        let _ = conn.execute(
+            "UPDATE key_usage_hourly SET flagged = 1 WHERE key_id = ?1 AND hour = ?2",
+            rusqlite::params![key_id, hour],
+        );
+        let throttled_until: Option<String> = (config.throttle_mins > 0 && !is_admin)
+            .then(|| {
+                conn.query_row(
+                    "UPDATE api_keys SET throttled_until = datetime('now', ?2)
+                     WHERE id = ?1 RETURNING throttled_until",
+                    rusqlite::params![key_id, format!("+{} minutes", config.throttle_mins)],
+                    |r| r.get(0),
+                )
+                .ok()
+            })
+            .flatten();
+        rocket::warn!("Key {} ({}) made {} requests this hour against a baseline of {:.1}", name, key_id, requests, baseline);
+        bus.emit(AppEvent {
+            event: "key.anomaly".to_string(),
+            data: json!({
+                "key_id": key_id,
+                "name": name,
+                "hour": hour,
+                "requests": requests,
+                "baseline": (baseline * 10.0).round() / 10.0,
+                "factor": config.factor,
+                "throttled_until": throttled_until,
+            }),
+        });
+        flagged.push(key_id);
+    }
+    flagged
+}
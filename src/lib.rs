@@ -1,54 +1,64 @@
 #[macro_use]
 extern crate rocket;
 
+pub mod a2a;
+pub mod accounts;
 pub mod auth;
+pub mod backup;
+pub mod captcha;
+pub mod cors;
+pub mod categories;
+pub mod client_spec;
+pub mod conformance;
 pub mod db;
+pub mod discovery;
+pub mod docs;
+pub mod erasure;
+pub mod error_messages;
+pub mod errors;
 pub mod events;
+pub mod fields;
+pub mod filters;
 pub mod health;
+pub mod i18n;
+pub mod key_usage;
+pub mod llms;
+pub mod logos;
+pub mod maintenance;
 pub mod models;
+pub mod moderation;
+pub mod negotiate;
+pub mod notifications;
+pub mod oauth;
+pub mod og;
+pub mod outbox;
+pub mod pagination;
+pub mod policy;
+pub mod query_stats;
 pub mod rate_limit;
+pub mod relay;
+pub mod reload;
+pub mod replica;
+pub mod retention;
+pub mod revisions;
 pub mod routes;
 pub mod scheduler;
+pub mod search;
+pub mod skills;
+pub mod specs;
 pub mod stats;
+pub mod tags;
+pub mod validation;
 pub mod webhooks;
 
-use rate_limit::{RateLimitHeaders, RateLimiter};
-use rocket::fairing::{Fairing, Info, Kind};
-use rocket::fs::{FileServer, Options};
-use rocket::http::Header;
-use rocket::{Request, Response};
+pub use cors::Cors;
+use errors::{ApiError, ErrorCode};
+use rate_limit::{LoginThrottle, RateLimitHeaders, RateLimitPersistence, RateLimiter, ReviewThrottle};
+use rocket::Request;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 
-pub struct Cors;
-
-#[rocket::async_trait]
-impl Fairing for Cors {
-    fn info(&self) -> Info {
-        Info {
-            name: "CORS",
-            kind: Kind::Response,
-        }
-    }
-
-    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
-        response.set_header(Header::new("Access-Control-Allow-Origin", "*"));
-        response.set_header(Header::new(
-            "Access-Control-Allow-Methods",
-            "GET, POST, PUT, PATCH, DELETE, OPTIONS",
-        ));
-        response.set_header(Header::new(
-            "Access-Control-Allow-Headers",
-            "Content-Type, Authorization, X-API-Key",
-        ));
-
-        if request.method() == rocket::http::Method::Options {
-            response.set_status(rocket::http::Status::NoContent);
-        }
-    }
-}
-
 pub struct DbState(pub Mutex<rusqlite::Connection>);
 
 impl DbState {
@@ -60,28 +70,152 @@ impl DbState {
     }
 }
 
+// Catchers: every error Rocket produces on its own (guards, data limits,
+// unmatched routes, panics) uses the same envelope as handler errors.
+
+#[catch(400)]
+pub fn bad_request() -> ApiError {
+    ApiError::from(ErrorCode::BadRequest)
+}
+
+/// Rejected by the API key guard.
+/// Expired keys get `KEY_EXPIRED` so clients know to rotate rather than retry,
+/// and expired OAuth access tokens `TOKEN_EXPIRED` so clients fetch a new one.
+#[catch(401)]
+pub fn unauthorized(request: &Request<'_>) -> ApiError {
+    match request.local_cache(auth::AuthFailure::default).0 {
+        Some("KEY_EXPIRED") => ApiError::from(ErrorCode::KeyExpired),
+        Some("TOKEN_EXPIRED") => ApiError::from(ErrorCode::TokenExpired),
+        _ => ApiError::from(ErrorCode::Unauthorized),
+    }
+}
+
+#[catch(404)]
+pub fn not_found(request: &Request<'_>) -> ApiError {
+    ApiError::new(ErrorCode::NotFound, format!("No route for {} {}", request.method(), request.uri().path()))
+}
+
+/// Rejected by the Rocket data limit (`MAX_BODY_BYTES`)
+#[catch(413)]
+pub fn payload_too_large() -> ApiError {
+    ApiError::from(ErrorCode::PayloadTooLarge)
+}
+
+/// JSON that parsed but didn't match the expected shape (missing or mistyped fields)
+#[catch(422)]
+pub fn unprocessable_entity() -> ApiError {
+    ApiError::from(ErrorCode::UnprocessableEntity)
+}
+
+/// Carries the refused request's cost, which can exceed what's `remaining`
+#[catch(429)]
+pub fn too_many_requests(request: &Request) -> ApiError {
+    let error = ApiError::from(ErrorCode::RateLimited);
+    match request.local_cache(|| Option::<rate_limit::RateLimitResult>::None) {
+        Some(rl) => error.with("cost", rl.cost).with("remaining", rl.remaining),
+        None => error,
+    }
+}
+
+#[catch(500)]
+pub fn internal_error() -> ApiError {
+    ApiError::from(ErrorCode::InternalError)
+}
+
+/// Frontend directory (`STATIC_DIR`). Shared so a config reload can point
+/// the file routes somewhere else without a restart.
+#[derive(Clone)]
+pub struct StaticDir(Arc<RwLock<PathBuf>>);
+
+impl StaticDir {
+    pub fn new(path: PathBuf) -> Self {
+        StaticDir(Arc::new(RwLock::new(path)))
+    }
+
+    pub fn get(&self) -> PathBuf {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn set(&self, path: PathBuf) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = path;
+    }
+}
+
+/// Frontend files from `STATIC_DIR`; a directory serves its index.html.
+/// Dotfiles and `..` segments never match.
+#[get("/<path..>", rank = 10)]
+pub async fn static_files(path: PathBuf, static_dir: &rocket::State<StaticDir>) -> Option<rocket::fs::NamedFile> {
+    let mut file = static_dir.get().join(path);
+    if file.is_dir() {
+        file.push("index.html");
+    }
+    match rocket::fs::NamedFile::open(&file).await {
+        Ok(file) => Some(file),
+        Err(_) => spa_index(static_dir).await,
+    }
+}
+
 /// SPA catch-all: serves index.html for any unmatched GET (client-side routing)
 #[get("/<_..>", rank = 20)]
-pub async fn spa_fallback() -> Option<rocket::fs::NamedFile> {
-    let static_dir: PathBuf = std::env::var("STATIC_DIR")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("frontend/dist"));
-    rocket::fs::NamedFile::open(static_dir.join("index.html"))
+pub async fn spa_fallback(static_dir: &rocket::State<StaticDir>) -> Option<rocket::fs::NamedFile> {
+    spa_index(static_dir).await
+}
+
+async fn spa_index(static_dir: &StaticDir) -> Option<rocket::fs::NamedFile> {
+    rocket::fs::NamedFile::open(static_dir.get().join("index.html"))
         .await
         .ok()
 }
 
 pub fn rocket() -> rocket::Rocket<rocket::Build> {
+    reload::remember_process_env();
     dotenvy::dotenv().ok();
+    rocket_with_path(&database_path())
+}
+
+/// Database path from `DATABASE_PATH` (default: app_directory.db).
+pub fn database_path() -> String {
+    std::env::var("DATABASE_PATH").unwrap_or_else(|_| "app_directory.db".to_string())
+}
 
-    let db_path = std::env::var("DATABASE_PATH").unwrap_or_else(|_| "app_directory.db".to_string());
-    rocket_with_path(&db_path)
+/// Seed the demo catalog into an empty database and report what was added.
+pub fn seed_demo_data(conn: &rusqlite::Connection) {
+    match db::seed::seed_demo_data(conn) {
+        Ok(Some(s)) => println!(
+            "🌱 Seeded demo data: {} apps, {} reviews, {} views, {} health checks",
+            s.apps, s.reviews, s.views, s.health_checks
+        ),
+        Ok(None) => println!("🌱 Demo data skipped: directory already has apps"),
+        Err(e) => eprintln!("❌ Seeding demo data failed: {e}"),
+    }
 }
 
 /// Build a Rocket instance with the given database path.
 /// Prefer this over `rocket()` in tests to avoid process-global env var races.
 pub fn rocket_with_path(db_path: &str) -> rocket::Rocket<rocket::Build> {
-    let conn = db::init_db(db_path);
+    let mut conn = db::init_db(db_path);
+
+    // Startup integrity check: DB_INTEGRITY_CHECK=quick|full (default: off)
+    if let Ok(mode @ ("quick" | "full")) = std::env::var("DB_INTEGRITY_CHECK").as_deref() {
+        match db::integrity_check(&conn, mode == "full") {
+            Ok(Ok(())) => println!("✅ Database integrity check ({mode}) passed"),
+            Ok(Err(problems)) => {
+                eprintln!("❌ Database integrity check ({mode}) failed:");
+                for problem in problems.iter().take(20) {
+                    eprintln!("   {problem}");
+                }
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("❌ Database integrity check ({mode}) could not run: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if matches!(std::env::var("SEED_DEMO_DATA").as_deref(), Ok("1") | Ok("true")) {
+        seed_demo_data(&conn);
+    }
 
     // Create admin key if none exist
     let key_count: i64 = conn
@@ -124,86 +258,384 @@ pub fn rocket_with_path(db_path: &str) -> rocket::Rocket<rocket::Build> {
         .and_then(|p| p.parse().ok())
         .unwrap_or(8000);
 
-    // Rate limit window: configurable via RATE_LIMIT_WINDOW_SECS (default: 60s)
-    let window_secs: u64 = std::env::var("RATE_LIMIT_WINDOW_SECS")
+    // Settings a SIGHUP or POST /admin/reload can change without a restart:
+    // RATE_LIMIT_WINDOW_SECS (default: 60s), RATE_LIMIT_WARNING_PCT (default: 80,
+    // 0 disables), ANON_REVIEW_DAILY_CAP (default: 20), the HEALTH_CHECK_* scheduler
+    // settings and STATIC_DIR (default: frontend/dist)
+    let reloadable = reload::ReloadableConfig::from_env();
+
+    // Rate limit persistence: RATE_LIMIT_FLUSH_SECS (default: 30s, 0 keeps counters in memory only)
+    let rate_limit_flush_secs: u64 = std::env::var("RATE_LIMIT_FLUSH_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(30);
+
+    // View recording: VIEW_FLUSH_SECS (default: 5s) between batched writes of queued app views
+    let view_flush_secs: u64 = std::env::var("VIEW_FLUSH_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5);
+
+    // Featured windows: FEATURED_ROTATION_SECS (default: 60s, 0 disables) between checks
+    let featured_rotation_secs: u64 = std::env::var("FEATURED_ROTATION_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(scheduler::DEFAULT_FEATURED_ROTATION_SECS);
+
+    // Discovery crawls: DISCOVERY_CRAWL_INTERVAL_SECS (default: 0, off) between crawls of every seed
+    let discovery_crawl_secs: u64 = std::env::var("DISCOVERY_CRAWL_INTERVAL_SECS")
         .ok()
         .and_then(|v| v.parse().ok())
-        .unwrap_or(60);
+        .unwrap_or(0);
+
+    // Read-only replica: READ_ONLY (default: false) rejects writes and syncs listings
+    // from READ_ONLY_PRIMARY_URL; jobs that write listing data stay off on a replica
+    let replica = replica::Replica::from_env();
+    let (featured_rotation_secs, discovery_crawl_secs) = if replica.is_enabled() {
+        (0, 0)
+    } else {
+        (featured_rotation_secs, discovery_crawl_secs)
+    };
+
+    // Event outbox: OUTBOX_RELAY_SECS (default: 5s, 0 disables) between passes republishing undelivered events
+    let outbox_relay_secs: u64 = std::env::var("OUTBOX_RELAY_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(outbox::DEFAULT_OUTBOX_RELAY_SECS);
+
+    let rate_limiter = RateLimiter::new(Duration::from_secs(reloadable.rate_limit_window_secs))
+        .with_warning_pct(reloadable.rate_limit_warning_pct)
+        .with_route_costs(reloadable.route_costs.clone());
+    // Privacy mode: PRIVACY_MODE (default: false) stores views without viewer keys
+    // and keeps hashed IPs out of the database
+    let privacy = retention::PrivacyMode::from_env(retention::RetentionPolicy::from_env());
+    let review_throttle = ReviewThrottle::new(reloadable.anon_review_daily_cap).with_privacy(privacy.enabled);
+    // Per-IP hourly cap on magic-link and verify requests: LOGIN_IP_HOURLY_CAP (default: 20)
+    let login_ip_hourly_cap: u64 = std::env::var("LOGIN_IP_HOURLY_CAP")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(rate_limit::DEFAULT_LOGIN_IP_HOURLY_CAP);
+    let login_throttle = LoginThrottle::new(login_ip_hourly_cap).with_privacy(privacy.enabled);
+    if privacy.enabled {
+        // Drop IP windows saved before privacy mode was turned on
+        let _ = conn.execute("DELETE FROM rate_limits WHERE scope = ?1", [rate_limit::SCOPE_REVIEWS]);
+    }
+    if rate_limit_flush_secs > 0 {
+        let _ = rate_limiter.load(&conn, rate_limit::SCOPE_API_KEYS);
+        if !privacy.enabled {
+            let _ = review_throttle.limiter.load(&conn, rate_limit::SCOPE_REVIEWS);
+        }
+    }
+
+    // Language of the base listing text: DEFAULT_LANGUAGE (default: en)
+    let default_lang = std::env::var("DEFAULT_LANGUAGE")
+        .ok()
+        .map(|v| i18n::normalize_lang(&v))
+        .filter(|v| i18n::is_valid_lang(v))
+        .unwrap_or_else(|| "en".to_string());
+
+    // Max JSON request body: MAX_BODY_BYTES (default: 256 KiB)
+    let max_body_bytes: u64 = std::env::var("MAX_BODY_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(256 * 1024);
+
+    // Require an API key or session for list/get/search: REQUIRE_AUTH_FOR_READS (default: false)
+    let require_auth_for_reads = matches!(
+        std::env::var("REQUIRE_AUTH_FOR_READS").as_deref(),
+        Ok("1") | Ok("true")
+    );
+
+    // Hold non-admin submissions for approval: MANUAL_REVIEW (default: false)
+    let manual_review = matches!(std::env::var("MANUAL_REVIEW").as_deref(), Ok("1") | Ok("true"));
+
+    // Pending-queue review target in hours: REVIEW_SLA_HOURS (default: 48)
+    let review_sla_hours: i64 = std::env::var("REVIEW_SLA_HOURS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|h| *h > 0)
+        .unwrap_or(moderation::DEFAULT_REVIEW_SLA_HOURS);
+
+    // Client IPs for per-IP review caps and fingerprints: TRUSTED_IP_HEADER (default: off,
+    // use the socket peer). Only set it behind a proxy that overwrites the header.
+    let ip_header: Option<String> = std::env::var("TRUSTED_IP_HEADER")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
 
     let figment = rocket::Config::figment()
         .merge(("address", addr))
-        .merge(("port", port));
+        .merge(("port", port))
+        .merge(("ip_header", ip_header.map_or(rocket::figment::value::Value::from(false), Into::into)))
+        .merge((
+            "limits",
+            rocket::data::Limits::default().limit("json", rocket::data::ByteUnit::from(max_body_bytes)),
+        ));
 
-    let webhook_db = webhooks::init_webhook_db();
-    let event_bus = events::EventBus::with_webhooks(webhook_db);
+    // Log statements slower than SLOW_QUERY_MS (default: 100, 0 disables)
+    let slow_query_ms: u64 = std::env::var("SLOW_QUERY_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(query_stats::DEFAULT_SLOW_QUERY_MS);
+    query_stats::set_slow_query_ms(slow_query_ms);
+    query_stats::install(&mut conn);
+
+    // Per-request X-DB-Time/X-DB-Queries headers: DB_DEBUG_HEADERS (default: false)
+    let db_debug_headers = matches!(std::env::var("DB_DEBUG_HEADERS").as_deref(), Ok("1") | Ok("true"));
+
+    // Event history retention: EVENT_RETENTION_DAYS (default: 7, 0 disables history)
+    let event_retention_days: i64 = std::env::var("EVENT_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(events::DEFAULT_EVENT_RETENTION_DAYS);
 
-    // Frontend static files directory
-    let static_dir: PathBuf = std::env::var("STATIC_DIR")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("frontend/dist"));
+    // Dual-signing window after a webhook secret rotation: WEBHOOK_SECRET_GRACE_SECS (default: 24h)
+    let webhook_secret_grace_secs: u64 = std::env::var("WEBHOOK_SECRET_GRACE_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(webhooks::DEFAULT_SECRET_GRACE_SECS)
+        .min(webhooks::MAX_SECRET_GRACE_SECS);
+
+    let webhook_db = webhooks::init_webhook_db(db_path);
+    let event_bus =
+        events::EventBus::with_webhooks(webhook_db, event_retention_days, webhooks::DeliverySettings::from_env());
+
+    let static_dir = reloadable.static_dir.clone();
 
     let mut rocket = rocket::custom(figment)
         .manage(DbState(Mutex::new(conn)))
-        .manage(RateLimiter::new(Duration::from_secs(window_secs)))
+        .manage(rate_limiter)
+        .manage(key_usage::KeyUsage::default())
+        .manage(review_throttle)
+        .manage(login_throttle)
+        .manage(specs::SpecVerifier::new(db_path))
+        .manage(a2a::AgentCardVerifier::new(db_path))
+        .manage(i18n::DefaultLanguage(default_lang))
+        .manage(webhooks::SecretGrace(webhook_secret_grace_secs))
+        .manage(validation::FieldLimits::from_env())
+        .manage(pagination::PageLimits::from_env())
+        .manage(accounts::Mailer::from_env())
+        .manage(moderation::ContentFilter::from_env())
+        .manage(policy::PolicyHook::from_env())
+        .manage(oauth::OAuthConfig::from_env())
+        .manage(captcha::Captcha::from_env())
+        .manage(maintenance::Maintenance::from_env())
+        .manage(replica)
         .manage(event_bus)
-        .attach(Cors)
+        .manage(llms::LlmsTxtCache::from_env())
+        .manage(stats::SiteStatsCache::default())
+        .manage(health::HealthMetricsCache::default())
+        .manage(stats::ViewRecorder::with_privacy(privacy.enabled))
+        .manage(scheduler::ScheduleStatus::new(reloadable.schedule))
+        .manage(StaticDir::new(reloadable.static_dir))
+        .manage(health::HealthCheckJobs::new(db_path))
+        .manage(backup::BackupConfig::from_env(db_path))
+        .manage(og::OgImages::from_env(db_path))
+        .manage(logos::LogoCache::from_env(db_path))
+        .manage(privacy.clone())
+        .manage(auth::ReadPolicy {
+            require_auth: require_auth_for_reads,
+        })
+        .manage(moderation::ReviewPolicy {
+            manual: manual_review,
+            sla_hours: review_sla_hours,
+        })
+        .attach(Cors::from_env())
         .attach(RateLimitHeaders)
-        .attach(scheduler::ScheduledHealthChecks)
+        .attach(query_stats::QueryInstrumentation {
+            headers: db_debug_headers,
+        })
+        .attach(error_messages::LocalizedErrors)
+        .attach(RateLimitPersistence {
+            db_path: db_path.to_string(),
+            flush_secs: rate_limit_flush_secs,
+        })
+        .attach(stats::ViewFlusher {
+            db_path: db_path.to_string(),
+            flush_secs: view_flush_secs,
+        })
+        .attach(relay::RedisRelay::from_env())
+        .attach(outbox::OutboxRelay {
+            interval_secs: outbox_relay_secs,
+        })
+        .attach(scheduler::ScheduledHealthChecks {
+            db_path: db_path.to_string(),
+        })
+        .attach(scheduler::FeaturedRotation {
+            db_path: db_path.to_string(),
+            interval_secs: featured_rotation_secs,
+        })
+        .attach(discovery::DiscoveryCrawler {
+            db_path: db_path.to_string(),
+            interval_secs: discovery_crawl_secs,
+        })
+        .attach(replica::ReplicaSync {
+            db_path: db_path.to_string(),
+        })
+        .attach(retention::StatsRetention {
+            db_path: db_path.to_string(),
+            policy: privacy.retention.clone(),
+        })
+        .attach(llms::LlmsTxtRoutes)
+        .attach(reload::ConfigReload)
+        .attach(notifications::Notifications {
+            db_path: db_path.to_string(),
+            config: notifications::NotifyConfig::from_env(),
+        })
+        .register(
+            "/",
+            catchers![
+                bad_request,
+                unauthorized,
+                not_found,
+                payload_too_large,
+                unprocessable_entity,
+                too_many_requests,
+                internal_error
+            ],
+        )
         .mount(
             "/api/v1",
             routes![
                 routes::health,
                 routes::llms_txt,
                 routes::openapi,
+                routes::client_spec,
+                routes::error_codes,
                 routes::submit_app,
                 routes::list_apps,
                 routes::list_pending_apps,
                 routes::get_app,
+                routes::app_json_ld,
+                routes::app_docs,
+                routes::app_og_image,
+                routes::get_replacement,
+                routes::suggest_tags,
                 routes::list_my_apps,
                 routes::update_app,
+                routes::rename_slug,
+                routes::publish_app,
+                routes::duplicate_app,
                 routes::delete_app,
+                routes::export_app,
+                routes::app_agent_card,
+                routes::app_tools,
+                routes::put_translation,
                 routes::approve_app,
                 routes::reject_app,
+                routes::assign_reviewer,
                 routes::deprecate_app,
                 routes::undeprecate_app,
+                routes::archive_app,
+                routes::unarchive_app,
+                routes::bulk_moderate,
+                routes::list_content_rejections,
+                routes::erase_subject,
+                routes::optimize_db,
+                routes::reload_config,
+                routes::create_backup,
+                routes::list_backups,
+                routes::get_maintenance,
+                routes::set_maintenance,
+                routes::add_discovery_seed,
+                routes::list_discovery_seeds,
+                routes::delete_discovery_seed,
+                routes::crawl_discovery_seed,
+                routes::rename_tag,
+                routes::merge_tags,
                 routes::search_apps,
+                routes::search_meta,
+                routes::captcha_config,
                 routes::submit_review,
                 routes::get_reviews,
+                routes::list_my_reviews,
+                routes::review_summary,
+                routes::update_my_review,
+                routes::delete_my_review,
+                routes::review_history,
+                routes::add_reaction,
+                routes::remove_reaction,
                 routes::list_categories,
+                routes::update_category,
                 routes::list_keys,
                 routes::create_key,
                 routes::delete_key,
+                routes::rotate_key,
+                routes::lift_throttle,
+                routes::rate_limit_info,
+                routes::update_key,
+                routes::request_magic_link,
+                routes::verify_magic_link,
+                routes::get_account,
+                oauth::token,
+                replica::feed,
                 routes::cors_preflight,
                 routes::create_webhook,
                 routes::list_webhooks,
                 routes::update_webhook,
+                routes::rotate_webhook_secret,
                 routes::delete_webhook,
+                routes::list_webhook_deliveries,
+                routes::put_notify_webhook,
+                routes::get_notify_webhook,
+                routes::delete_notify_webhook,
+                routes::list_badges,
+                routes::create_badge,
+                routes::update_badge,
+                routes::delete_badge,
+                routes::award_badge,
+                routes::revoke_badge,
+                routes::endorse_app,
+                routes::withdraw_endorsement,
+                routes::list_endorsements,
                 routes::event_stream,
+                routes::health_event_stream,
+                routes::list_events,
+                routes::event_ws,
                 health::health_summary,
                 health::batch_health_check,
+                health::get_batch_health_check,
                 health::check_app_health,
+                conformance::check_app_conformance,
                 health::get_health_history,
+                health::get_uptime,
+                health::get_health_metrics,
                 scheduler::get_schedule,
                 stats::get_app_stats,
+                stats::visit_app,
                 stats::trending_apps,
+                stats::recommended_apps,
+                stats::stats_overview,
+                stats::site_stats,
+                stats::stats_history,
+                stats::category_leaderboard,
+                revisions::list_revisions,
+                revisions::revision_diff,
+                revisions::rollback_revision,
                 routes::api_skills_skill_md,
             ],
-        );
+        )
+        .mount("/api/v1", maintenance::routes())
+        .mount("/api/v1", replica::routes());
 
     // Mount SKILL.md, llms.txt + well-known skills at root level for standard discovery
     rocket = rocket.mount("/", routes![
         routes::skill_md,
         routes::root_llms_txt,
+        routes::opensearch_xml,
+        routes::app_preview,
+        routes::app_logo,
         routes::skills_index,
+        routes::app_skill_md,
         routes::skills_skill_md,
+        routes::agent_card,
     ]);
 
-    // Serve frontend static files if the directory exists
+    // Serve frontend static files if the directory exists. A reload can
+    // switch to another directory, but API-only mode needs a restart.
     if static_dir.is_dir() {
         println!("📦 Serving frontend from: {}", static_dir.display());
-        rocket = rocket
-            .mount("/", FileServer::new(&static_dir, Options::Index))
-            .mount("/", routes![spa_fallback]);
+        rocket = rocket.mount("/", routes![static_files, spa_fallback]);
     } else {
         println!(
             "⚡ API-only mode (no frontend at {})",
@@ -0,0 +1,184 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Method;
+use rocket::{Orbit, Rocket};
+
+/// Rendered `llms.txt`, reused until it is older than `ttl`.
+/// `LLMS_TXT_CACHE_SECS` sets the lifetime (default 60; 0 disables caching).
+pub struct LlmsTxtCache {
+    ttl: Duration,
+    cached: Mutex<Option<(Instant, String)>>,
+    endpoints: OnceLock<Vec<String>>,
+}
+
+impl LlmsTxtCache {
+    pub fn new(ttl: Duration) -> Self {
+        LlmsTxtCache {
+            ttl,
+            cached: Mutex::new(None),
+            endpoints: OnceLock::new(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let secs = std::env::var("LLMS_TXT_CACHE_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(60);
+        LlmsTxtCache::new(Duration::from_secs(secs))
+    }
+
+    /// Endpoint list captured from the mounted routes at liftoff.
+    pub fn endpoints(&self) -> &[String] {
+        self.endpoints.get().map(|e| e.as_slice()).unwrap_or(&[])
+    }
+
+    /// Return the cached body if still fresh, otherwise render and store a new one.
+    pub fn get_or_render(&self, render: impl FnOnce() -> String) -> String {
+        let mut cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((at, body)) = cached.as_ref() {
+            if at.elapsed() < self.ttl {
+                return body.clone();
+            }
+        }
+        let body = render();
+        if !self.ttl.is_zero() {
+            *cached = Some((Instant::now(), body.clone()));
+        }
+        body
+    }
+}
+
+/// Liftoff fairing that records the mounted `/api/v1` routes into the managed
+/// `LlmsTxtCache`, since handlers cannot see the route table themselves.
+pub struct LlmsTxtRoutes;
+
+#[rocket::async_trait]
+impl Fairing for LlmsTxtRoutes {
+    fn info(&self) -> Info {
+        Info {
+            name: "llms.txt Routes",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        if let Some(cache) = rocket.state::<LlmsTxtCache>() {
+            let _ = cache.endpoints.set(endpoints(rocket));
+        }
+    }
+}
+
+/// Canonical `METHOD /path` list of everything mounted under `/api/v1`.
+pub fn endpoints(rocket: &Rocket<Orbit>) -> Vec<String> {
+    let set: BTreeSet<(String, String)> = rocket
+        .routes()
+        .filter(|r| r.method != Method::Options)
+        .filter(|r| r.uri.path().starts_with("/api/v1"))
+        .map(|r| (r.uri.path().to_string(), r.method.as_str().to_string()))
+        .collect();
+    set.into_iter().map(|(path, method)| format!("{} {}", method, path)).collect()
+}
+
+fn count(conn: &rusqlite::Connection, sql: &str) -> i64 {
+    conn.query_row(sql, [], |r| r.get(0)).unwrap_or(0)
+}
+
+/// Render a snapshot of the catalog for LLM agents: live counts, the largest
+/// top-level categories, featured apps and the endpoint list. Only public
+/// listings are included.
+pub fn render(conn: &rusqlite::Connection, endpoints: &[String]) -> String {
+    let approved = count(conn, "SELECT COUNT(*) FROM apps WHERE status = 'approved' AND visibility = 'public'");
+    let featured = count(conn, "SELECT COUNT(*) FROM apps WHERE status = 'approved' AND visibility = 'public' AND is_featured = 1");
+    let reviews = count(
+        conn,
+        "SELECT COUNT(*) FROM reviews r JOIN apps a ON a.id = r.app_id WHERE a.status = 'approved' AND a.visibility = 'public'",
+    );
+    let categories = count(
+        conn,
+        "SELECT COUNT(DISTINCT category) FROM apps WHERE status = 'approved' AND visibility = 'public'",
+    );
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# App Directory");
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "> A catalog of AI-native applications and services. Agents can search, submit, review and monitor apps through the REST API at /api/v1."
+    );
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Generated: {}", chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Catalog");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "- Approved apps: {}", approved);
+    let _ = writeln!(out, "- Featured apps: {}", featured);
+    let _ = writeln!(out, "- Reviews: {}", reviews);
+    let _ = writeln!(out, "- Categories in use: {}", categories);
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Top categories");
+    let _ = writeln!(out);
+    let mut stmt = conn
+        .prepare(
+            "SELECT CASE WHEN instr(category, '/') > 0
+                         THEN substr(category, 1, instr(category, '/') - 1)
+                         ELSE category END AS grp,
+                    COUNT(*)
+             FROM apps WHERE status = 'approved' AND visibility = 'public'
+             GROUP BY grp ORDER BY COUNT(*) DESC, grp ASC LIMIT 5",
+        )
+        .unwrap();
+    let top: Vec<(String, i64)> = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+    if top.is_empty() {
+        let _ = writeln!(out, "- (none yet)");
+    }
+    for (name, apps) in top {
+        let _ = writeln!(out, "- {} ({} apps): /api/v1/apps?category={}", name, apps, name);
+    }
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Featured apps");
+    let _ = writeln!(out);
+    let mut stmt = conn
+        .prepare(
+            "SELECT name, slug, short_description FROM apps
+             WHERE status = 'approved' AND visibility = 'public' AND is_featured = 1
+             ORDER BY avg_rating DESC, name ASC LIMIT 10",
+        )
+        .unwrap();
+    let apps: Vec<(String, String, String)> = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+    if apps.is_empty() {
+        let _ = writeln!(out, "- (none yet)");
+    }
+    for (name, slug, short) in apps {
+        let _ = writeln!(out, "- [{}](/api/v1/apps/{}): {}", name, slug, short);
+    }
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Endpoints");
+    let _ = writeln!(out);
+    for endpoint in endpoints {
+        let _ = writeln!(out, "- {}", endpoint);
+    }
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Docs");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "- [SKILL.md](/SKILL.md): full usage guide");
+    let _ = writeln!(out, "- [OpenAPI spec](/api/v1/openapi.json)");
+    out
+}
@@ -0,0 +1,402 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+/// Cached logos are square PNGs of this many pixels per side.
+pub const SIZE: u32 = 256;
+
+/// HTTP timeout for fetching a logo.
+const FETCH_TIMEOUT_SECS: u64 = 10;
+
+/// Logos larger than this are rejected without decoding.
+pub const MAX_LOGO_BYTES: usize = 1024 * 1024;
+
+/// Source images wider or taller than this are rejected before decoding.
+pub const MAX_SOURCE_DIMENSION: u32 = 4096;
+
+/// Identicon grid: 5×5 cells, mirrored left to right.
+const GRID: u32 = 5;
+const IDENTICON_PADDING: u32 = 28;
+const IDENTICON_BACKGROUND: [u8; 4] = [240, 240, 240, 255];
+
+/// Local copies of app logos, served from `/media/logos/<app id>.png` so
+/// the frontend never hotlinks `logo_url`.
+///
+/// Setting `logo_url` fetches the image in the background, checks it is a
+/// PNG or JPEG of at most `MAX_LOGO_BYTES`, fits it into a transparent
+/// `SIZE`×`SIZE` square and stores it as `<app id>.png`. The app's
+/// `logo_status` follows the fetch (`pending` → `cached` / `invalid` /
+/// `unreachable`); `logo_source` records the URL that status belongs to.
+/// Apps without a cached logo get a generated identicon. `LOGO_DIR`
+/// defaults to `<db name>-logos` next to the database.
+pub struct LogoCache {
+    dir: PathBuf,
+    db_path: String,
+    client: reqwest::Client,
+}
+
+impl LogoCache {
+    pub fn new(dir: impl Into<PathBuf>, db_path: &str) -> Self {
+        LogoCache {
+            dir: dir.into(),
+            db_path: db_path.to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+                .redirect(reqwest::redirect::Policy::limited(5))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn from_env(db_path: &str) -> Self {
+        let dir = std::env::var("LOGO_DIR")
+            .ok()
+            .filter(|d| !d.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                let path = Path::new(db_path);
+                let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("app_directory");
+                path.with_file_name(format!("{stem}-logos"))
+            });
+        LogoCache::new(dir, db_path)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, app_id: &str) -> PathBuf {
+        self.dir.join(format!("{app_id}.png"))
+    }
+
+    /// Mark the app's logo as pending and fetch `url` in the background.
+    /// Must be called from within the Tokio runtime (i.e. a route handler).
+    pub fn fetch_async(&self, conn: &rusqlite::Connection, app_id: &str, url: &str) {
+        let _ = conn.execute(
+            "UPDATE apps SET logo_status = 'pending', logo_source = ?2 WHERE id = ?1",
+            rusqlite::params![app_id, url],
+        );
+
+        let db_path = self.db_path.clone();
+        let client = self.client.clone();
+        let path = self.path(app_id);
+        let app_id = app_id.to_string();
+        let url = url.to_string();
+
+        tokio::spawn(async move {
+            let result = fetch(&client, &url).await;
+
+            let conn = match rusqlite::Connection::open(&db_path) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("❌ Logo fetch for {app_id}: failed to open DB: {e}");
+                    return;
+                }
+            };
+            let _ = conn.busy_timeout(Duration::from_secs(5));
+            // Drop the result if the logo URL changed mid-flight
+            let current: Option<String> = conn
+                .query_row("SELECT logo_source FROM apps WHERE id = ?1", rusqlite::params![app_id], |r| r.get(0))
+                .unwrap_or(None);
+            if current.as_deref() != Some(url.as_str()) {
+                return;
+            }
+
+            let status = match result {
+                Ok(png) if write_atomic(&path, &png) => "cached",
+                Ok(_) => "unreachable",
+                Err((status, reason)) => {
+                    eprintln!("⚠️  Logo for {app_id} not cached ({status}): {reason}");
+                    let _ = std::fs::remove_file(&path);
+                    status
+                }
+            };
+            let _ = conn.execute(
+                "UPDATE apps SET logo_status = ?1 WHERE id = ?2 AND logo_source = ?3",
+                rusqlite::params![status, app_id, url],
+            );
+        });
+    }
+
+    /// Forget an app's logo after its `logo_url` was removed.
+    pub fn clear(&self, conn: &rusqlite::Connection, app_id: &str) {
+        let _ = conn.execute(
+            "UPDATE apps SET logo_status = NULL, logo_source = NULL WHERE id = ?1",
+            rusqlite::params![app_id],
+        );
+        self.remove(app_id);
+    }
+
+    /// Delete an app's cached logo file.
+    pub fn remove(&self, app_id: &str) {
+        let _ = std::fs::remove_file(self.path(app_id));
+    }
+
+    /// Give a copied app the original's cached logo.
+    pub fn copy(&self, from_app_id: &str, to_app_id: &str) {
+        let _ = std::fs::copy(self.path(from_app_id), self.path(to_app_id));
+    }
+
+    /// PNG logo for an app: the cached copy when there is one, otherwise an
+    /// identicon. A `logo_url` changed without a fetch (e.g. by a rollback)
+    /// is fetched now and shows the identicon meanwhile.
+    pub fn get(&self, conn: &rusqlite::Connection, app_id: &str) -> Vec<u8> {
+        let state: Option<(Option<String>, Option<String>, Option<String>)> = conn
+            .query_row(
+                "SELECT logo_url, logo_status, logo_source FROM apps WHERE id = ?1",
+                rusqlite::params![app_id],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .ok();
+        if let Some((url, status, source)) = state {
+            match url.filter(|u| !u.trim().is_empty()) {
+                Some(url) if source.as_deref() != Some(url.as_str()) => self.fetch_async(conn, app_id, &url),
+                Some(_) if status.as_deref() == Some("cached") => {
+                    if let Ok(png) = std::fs::read(self.path(app_id)) {
+                        return png;
+                    }
+                }
+                Some(_) => {}
+                None if source.is_some() => self.clear(conn, app_id),
+                None => {}
+            }
+        }
+        identicon(app_id)
+    }
+}
+
+/// Write then rename so concurrent readers never see half a file.
+fn write_atomic(path: &Path, data: &[u8]) -> bool {
+    let Some(dir) = path.parent() else { return false };
+    if std::fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    let tmp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    if std::fs::write(&tmp, data).is_ok() && std::fs::rename(&tmp, path).is_ok() {
+        return true;
+    }
+    let _ = std::fs::remove_file(&tmp);
+    false
+}
+
+/// Fetch a logo and normalize it. Errors carry the logo status
+/// (`unreachable` or `invalid`) and a reason.
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, (&'static str, String)> {
+    let resp = client.get(url).send().await.map_err(|e| {
+        let msg = if e.is_timeout() {
+            format!("Connection timed out ({}s)", FETCH_TIMEOUT_SECS)
+        } else if e.is_connect() {
+            "Connection refused or DNS failure".to_string()
+        } else {
+            format!("{}", e)
+        };
+        ("unreachable", msg)
+    })?;
+    if !resp.status().is_success() {
+        return Err(("unreachable", format!("HTTP {}", resp.status())));
+    }
+    let body = crate::health::read_body_limited(resp, MAX_LOGO_BYTES)
+        .await
+        .map_err(|e| ("unreachable", format!("{}", e)))?
+        .ok_or_else(|| ("invalid", format!("Logo exceeds {} bytes", MAX_LOGO_BYTES)))?;
+    normalize(&body).map_err(|reason| ("invalid", reason))
+}
+
+/// Decode a PNG or JPEG (recognized by its signature, not its
+/// Content-Type) and re-encode it as a `SIZE`×`SIZE` PNG.
+pub fn normalize(data: &[u8]) -> Result<Vec<u8>, String> {
+    let (width, height, rgba) = if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        decode_png(data)?
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        decode_jpeg(data)?
+    } else {
+        return Err("Logo must be a PNG or JPEG image".to_string());
+    };
+    Ok(encode(&fit(width, height, &rgba)))
+}
+
+fn check_dimensions(width: u32, height: u32) -> Result<(), String> {
+    if width == 0 || height == 0 {
+        return Err("Logo has no pixels".to_string());
+    }
+    if width > MAX_SOURCE_DIMENSION || height > MAX_SOURCE_DIMENSION {
+        return Err(format!(
+            "Logo is {width}×{height}; at most {MAX_SOURCE_DIMENSION}×{MAX_SOURCE_DIMENSION} is accepted"
+        ));
+    }
+    Ok(())
+}
+
+fn decode_png(data: &[u8]) -> Result<(u32, u32, Vec<u8>), String> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().map_err(|e| format!("Invalid PNG: {e}"))?;
+    let (width, height) = (reader.info().width, reader.info().height);
+    check_dimensions(width, height)?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buf).map_err(|e| format!("Invalid PNG: {e}"))?;
+    let pixels = &buf[..frame.buffer_size()];
+    let rgba = match frame.color_type {
+        png::ColorType::Rgba => pixels.to_vec(),
+        png::ColorType::Rgb => pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => pixels.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale => pixels.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => return Err("Invalid PNG: unexpanded palette".to_string()),
+    };
+    Ok((width, height, rgba))
+}
+
+fn decode_jpeg(data: &[u8]) -> Result<(u32, u32, Vec<u8>), String> {
+    let mut decoder = jpeg_decoder::Decoder::new(data);
+    decoder.read_info().map_err(|e| format!("Invalid JPEG: {e}"))?;
+    let info = decoder.info().ok_or_else(|| "Invalid JPEG".to_string())?;
+    let (width, height) = (info.width as u32, info.height as u32);
+    check_dimensions(width, height)?;
+    let pixels = decoder.decode().map_err(|e| format!("Invalid JPEG: {e}"))?;
+    let rgba = match info.pixel_format {
+        jpeg_decoder::PixelFormat::RGB24 => pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        jpeg_decoder::PixelFormat::L8 => pixels.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        // Big-endian samples: keep the high byte
+        jpeg_decoder::PixelFormat::L16 => pixels.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], 255]).collect(),
+        jpeg_decoder::PixelFormat::CMYK32 => return Err("CMYK JPEGs are not supported".to_string()),
+    };
+    Ok((width, height, rgba))
+}
+
+/// Scale an RGBA image to fit a transparent `SIZE`×`SIZE` square, keeping
+/// its aspect ratio. Shrinking averages each destination pixel's source box
+/// (weighted by alpha); enlarging repeats pixels.
+fn fit(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let scale = (SIZE as f64 / width as f64).min(SIZE as f64 / height as f64);
+    let dw = ((width as f64 * scale).round() as u32).clamp(1, SIZE);
+    let dh = ((height as f64 * scale).round() as u32).clamp(1, SIZE);
+    let (ox, oy) = ((SIZE - dw) / 2, (SIZE - dh) / 2);
+    let span = |d: u32, dst: u32, src: u32| {
+        let start = d as u64 * src as u64 / dst as u64;
+        let end = ((d as u64 + 1) * src as u64 / dst as u64).max(start + 1);
+        start as u32..end as u32
+    };
+
+    let mut out = vec![0u8; (SIZE * SIZE * 4) as usize];
+    for dy in 0..dh {
+        let rows = span(dy, dh, height);
+        for dx in 0..dw {
+            let (mut sum, mut alpha, mut count) = ([0u64; 3], 0u64, 0u64);
+            for sy in rows.clone() {
+                for sx in span(dx, dw, width) {
+                    let i = ((sy * width + sx) * 4) as usize;
+                    let a = rgba[i + 3] as u64;
+                    for (c, total) in sum.iter_mut().enumerate() {
+                        *total += rgba[i + c] as u64 * a;
+                    }
+                    alpha += a;
+                    count += 1;
+                }
+            }
+            let o = (((oy + dy) * SIZE + ox + dx) * 4) as usize;
+            for c in 0..3 {
+                out[o + c] = sum[c].checked_div(alpha).unwrap_or(0) as u8;
+            }
+            out[o + 3] = (alpha / count) as u8;
+        }
+    }
+    out
+}
+
+/// Deterministic placeholder for an app without a cached logo: a mirrored
+/// 5×5 pattern in a colour derived from the app id.
+pub fn identicon(app_id: &str) -> Vec<u8> {
+    let hash = Sha256::digest(app_id.as_bytes());
+    // Keep colours mid-range so they stand out on the light background
+    let color = [48 + hash[0] % 160, 48 + hash[1] % 160, 48 + hash[2] % 160, 255];
+    let cell = (SIZE - 2 * IDENTICON_PADDING) / GRID;
+    let offset = (SIZE - cell * GRID) / 2;
+
+    let mut out = IDENTICON_BACKGROUND.repeat((SIZE * SIZE) as usize);
+    for row in 0..GRID {
+        for col in 0..GRID.div_ceil(2) {
+            let bit = row * GRID.div_ceil(2) + col;
+            if hash[3 + (bit / 8) as usize] >> (bit % 8) & 1 == 0 {
+                continue;
+            }
+            for mirrored in [col, GRID - 1 - col] {
+                for y in 0..cell {
+                    for x in 0..cell {
+                        let px = offset + mirrored * cell + x;
+                        let py = offset + row * cell + y;
+                        let i = ((py * SIZE + px) * 4) as usize;
+                        out[i..i + 4].copy_from_slice(&color);
+                    }
+                }
+            }
+        }
+    }
+    encode(&out)
+}
+
+fn encode(rgba: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, SIZE, SIZE);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    if let Ok(mut writer) = encoder.write_header() {
+        let _ = writer.write_image_data(rgba);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_of(width: u32, height: u32, color: png::ColorType, pixels: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(color);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header().unwrap().write_image_data(pixels).unwrap();
+        out
+    }
+
+    fn decode(png: &[u8]) -> (u32, u32, Vec<u8>) {
+        let reader = png::Decoder::new(png);
+        let mut reader = reader.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut buf).unwrap();
+        (reader.info().width, reader.info().height, buf)
+    }
+
+    #[test]
+    fn fits_logos_into_a_transparent_square() {
+        // 4×2 red RGB image: scaled to 256×128, centred vertically
+        let source = png_of(4, 2, png::ColorType::Rgb, &[255, 0, 0].repeat(8));
+        let (width, height, pixels) = decode(&normalize(&source).unwrap());
+        assert_eq!((width, height), (SIZE, SIZE));
+        let at = |x: u32, y: u32| &pixels[((y * SIZE + x) * 4) as usize..][..4];
+        assert_eq!(at(0, 0), [0, 0, 0, 0]);
+        assert_eq!(at(0, 64), [255, 0, 0, 255]);
+        assert_eq!(at(255, 191), [255, 0, 0, 255]);
+        assert_eq!(at(128, 192), [0, 0, 0, 0]);
+
+        // Shrinking averages, ignoring transparent pixels
+        let big = png_of(512, 512, png::ColorType::GrayscaleAlpha, &[200, 255, 0, 0].repeat(512 * 256));
+        let (_, _, pixels) = decode(&normalize(&big).unwrap());
+        assert_eq!(&pixels[..4], [200, 200, 200, 127]);
+    }
+
+    #[test]
+    fn rejects_other_formats_and_oversized_images() {
+        assert!(normalize(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>").unwrap_err().contains("PNG or JPEG"));
+        assert!(normalize(b"\x89PNG\r\n\x1a\ngarbage").unwrap_err().starts_with("Invalid PNG"));
+        let wide = png_of(MAX_SOURCE_DIMENSION + 1, 1, png::ColorType::Grayscale, &vec![0; MAX_SOURCE_DIMENSION as usize + 1]);
+        assert!(normalize(&wide).unwrap_err().contains("at most"));
+    }
+
+    #[test]
+    fn identicons_are_stable_per_app() {
+        assert_eq!(identicon("app-1"), identicon("app-1"));
+        assert_ne!(identicon("app-1"), identicon("app-2"));
+        assert_eq!(decode(&identicon("app-1")).0, SIZE);
+    }
+}
@@ -1,4 +1,48 @@
-#[rocket::launch]
-fn rocket() -> _ {
-    app_directory::rocket()
+fn main() {
+    // `--seed-demo-data`: populate the database with the demo catalog and exit
+    if std::env::args().any(|a| a == "--seed-demo-data") {
+        dotenvy::dotenv().ok();
+        let conn = app_directory::db::init_db(&app_directory::database_path());
+        app_directory::seed_demo_data(&conn);
+        return;
+    }
+
+    // `--restore-backup <file>`: replace the database with a snapshot and exit
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|a| a == "--restore-backup") {
+        dotenvy::dotenv().ok();
+        std::process::exit(restore_backup(args.get(i + 1).map(String::as_str)));
+    }
+
+    if let Err(e) = rocket::execute(app_directory::rocket().launch()) {
+        eprintln!("❌ Server failed: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn restore_backup(file: Option<&str>) -> i32 {
+    use app_directory::backup;
+
+    let Some(file) = file else {
+        eprintln!("Usage: app-directory --restore-backup <backup file or name>");
+        return 2;
+    };
+    let db_path = app_directory::database_path();
+    let config = backup::BackupConfig::from_env(&db_path);
+    // A bare snapshot name refers to the backup directory
+    let mut source = std::path::PathBuf::from(file);
+    if !source.exists() && backup::is_backup_name(file) {
+        source = config.dir.join(file);
+    }
+    match backup::restore(&source, &db_path, &config) {
+        Ok(safety) => {
+            println!("✅ Restored {} from {}", db_path, source.display());
+            println!("   The previous database was saved as {}", config.dir.join(&safety.name).display());
+            0
+        }
+        Err(e) => {
+            eprintln!("❌ Restore failed: {e}");
+            1
+        }
+    }
 }
@@ -0,0 +1,154 @@
+use std::sync::RwLock;
+
+use rocket::http::{Header, Status};
+use rocket::request::{self, FromRequest, Request};
+use rocket::response::{self, Responder};
+use serde::Serialize;
+
+use crate::errors::{ApiError, ErrorCode};
+
+/// `Retry-After` sent while writes are paused when
+/// `MAINTENANCE_RETRY_AFTER_SECS` is unset.
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 300;
+
+/// Message shown when maintenance is enabled without one.
+pub const DEFAULT_MESSAGE: &str = "Scheduled maintenance in progress; changes are paused";
+
+/// Write endpoints that stay open: the toggle, so admins can end
+/// maintenance, and the OAuth token endpoint, so token clients can keep reading.
+const OPEN_PATHS: &[&str] = &["/api/v1/admin/maintenance", crate::oauth::TOKEN_PATH];
+
+/// Current maintenance settings.
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceState {
+    pub enabled: bool,
+    pub message: String,
+    pub retry_after_secs: u64,
+    /// When maintenance was last switched on (RFC 3339).
+    pub since: Option<String>,
+}
+
+/// Maintenance mode: while enabled, every write under `/api/v1` answers
+/// `503 MAINTENANCE` and reads keep working. Seeded from the environment
+/// and toggled at runtime by admins; runtime changes last until restart
+/// and apply to this process only.
+pub struct Maintenance(RwLock<MaintenanceState>);
+
+impl Maintenance {
+    pub fn new(enabled: bool, message: Option<String>, retry_after_secs: u64) -> Self {
+        Maintenance(RwLock::new(MaintenanceState {
+            enabled,
+            message: message.unwrap_or_else(|| DEFAULT_MESSAGE.to_string()),
+            retry_after_secs,
+            since: enabled.then(now),
+        }))
+    }
+
+    /// `MAINTENANCE_MODE` (`1`/`true`) starts the server with writes paused;
+    /// `MAINTENANCE_MESSAGE` and `MAINTENANCE_RETRY_AFTER_SECS` (default 300)
+    /// set the banner and `Retry-After`.
+    pub fn from_env() -> Self {
+        let enabled = matches!(std::env::var("MAINTENANCE_MODE").as_deref(), Ok("1") | Ok("true"));
+        let message = std::env::var("MAINTENANCE_MESSAGE").ok().filter(|m| !m.trim().is_empty());
+        let retry_after_secs = std::env::var("MAINTENANCE_RETRY_AFTER_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+        if enabled {
+            println!("🚧 Maintenance mode: write endpoints are paused");
+        }
+        Maintenance::new(enabled, message, retry_after_secs)
+    }
+
+    pub fn state(&self) -> MaintenanceState {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Switch maintenance on or off. `message` and `retry_after_secs`
+    /// replace the current values when given.
+    pub fn set(&self, enabled: bool, message: Option<String>, retry_after_secs: Option<u64>) -> MaintenanceState {
+        let mut state = self.0.write().unwrap_or_else(|e| e.into_inner());
+        if enabled && !state.enabled {
+            state.since = Some(now());
+        } else if !enabled {
+            state.since = None;
+        }
+        state.enabled = enabled;
+        if let Some(message) = message.filter(|m| !m.trim().is_empty()) {
+            state.message = message;
+        }
+        if let Some(secs) = retry_after_secs {
+            state.retry_after_secs = secs;
+        }
+        state.clone()
+    }
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Present only while maintenance is on; forwards otherwise so the
+/// request reaches its real route.
+pub struct WritesPaused(MaintenanceState);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for WritesPaused {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, ()> {
+        let Some(maintenance) = request.rocket().state::<Maintenance>() else {
+            return request::Outcome::Forward(Status::NotFound);
+        };
+        let state = maintenance.state();
+        if state.enabled && !OPEN_PATHS.contains(&request.uri().path().as_str()) {
+            request::Outcome::Success(WritesPaused(state))
+        } else {
+            request::Outcome::Forward(Status::NotFound)
+        }
+    }
+}
+
+/// `503 MAINTENANCE` with a `Retry-After` header.
+pub struct MaintenanceResponse(MaintenanceState);
+
+impl<'r> Responder<'r, 'static> for MaintenanceResponse {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = ApiError::new(ErrorCode::Maintenance, self.0.message)
+            .with("retry_after_secs", self.0.retry_after_secs)
+            .respond_to(request)?;
+        response.set_header(Header::new("Retry-After", self.0.retry_after_secs.to_string()));
+        Ok(response)
+    }
+}
+
+/// Catch-all write routes, ranked ahead of every other route so paused
+/// writes never reach a handler (or its body parsing). Attribute ranks
+/// can't be negative, hence the override.
+pub fn routes() -> Vec<rocket::Route> {
+    let mut routes = routes![pause_post, pause_put, pause_patch, pause_delete];
+    for route in &mut routes {
+        route.rank = -100;
+    }
+    routes
+}
+
+#[post("/<_..>")]
+pub fn pause_post(paused: WritesPaused) -> MaintenanceResponse {
+    MaintenanceResponse(paused.0)
+}
+
+#[put("/<_..>")]
+pub fn pause_put(paused: WritesPaused) -> MaintenanceResponse {
+    MaintenanceResponse(paused.0)
+}
+
+#[patch("/<_..>")]
+pub fn pause_patch(paused: WritesPaused) -> MaintenanceResponse {
+    MaintenanceResponse(paused.0)
+}
+
+#[delete("/<_..>")]
+pub fn pause_delete(paused: WritesPaused) -> MaintenanceResponse {
+    MaintenanceResponse(paused.0)
+}
@@ -17,6 +17,48 @@ pub struct CreateKeyRequest {
     pub name: String,
     pub is_admin: Option<bool>,
     pub rate_limit: Option<i64>,
+    /// RFC 3339 timestamp after which the key stops working
+    pub expires_at: Option<String>,
+    /// Requests allowed above `rate_limit` in a short spike (admin only)
+    pub burst: Option<i64>,
+    /// Route patterns that bypass rate limiting (admin only)
+    pub exempt_routes: Option<Vec<String>>,
+    /// List/search page size when `per_page` is omitted (admin only)
+    pub default_page_size: Option<i64>,
+    /// Largest `per_page` honored for this key (admin only)
+    pub max_page_size: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateKeyRequest {
+    pub name: Option<String>,
+    pub is_admin: Option<bool>,
+    pub rate_limit: Option<i64>,
+    pub burst: Option<i64>,
+    pub exempt_routes: Option<Vec<String>>,
+    /// 0 resets to the instance default
+    pub default_page_size: Option<i64>,
+    /// 0 resets to the instance max
+    pub max_page_size: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RotateKeyRequest {
+    /// New expiry for the rotated key; omitted keeps the current one
+    pub expires_at: Option<String>,
+}
+
+// === Account Models ===
+
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyCodeRequest {
+    pub email: String,
+    pub code: String,
 }
 
 // === App Models ===
@@ -59,6 +101,17 @@ pub struct SubmitAppRequest {
     pub logo_url: Option<String>,
     pub author_name: String,
     pub author_url: Option<String>,
+    /// Private contact for moderation and health notifications; never shown publicly.
+    pub author_email: Option<String>,
+    /// hCaptcha/Turnstile response token, required from anonymous callers when CAPTCHA is enabled.
+    pub captcha_token: Option<String>,
+    /// `draft` keeps the listing private to its owner until it's published.
+    /// Requires an API key or account session.
+    pub status: Option<String>,
+    /// `public` (default), `unlisted` or `private`.
+    pub visibility: Option<String>,
+    /// Custom slug (see `check_slug`); generated from the name when omitted.
+    pub slug: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -75,9 +128,68 @@ pub struct UpdateAppRequest {
     pub logo_url: Option<String>,
     pub author_name: Option<String>,
     pub author_url: Option<String>,
+    /// Empty string clears it.
+    pub author_email: Option<String>,
     pub status: Option<String>,
     pub is_featured: Option<bool>,
     pub is_verified: Option<bool>,
+    /// Admin only. RFC 3339 start of a scheduled featured window; empty string clears it.
+    pub featured_from: Option<String>,
+    /// Admin only. RFC 3339 end of a scheduled featured window; empty string clears it.
+    pub featured_until: Option<String>,
+    /// Admin only, -10..=10. Raises (or lowers) the app in the default
+    /// list order and breaks ties in search.
+    pub rank_boost: Option<i64>,
+    /// `public`, `unlisted` or `private`.
+    pub visibility: Option<String>,
+    /// Long-form Markdown usage docs, sanitized before storing. Empty string clears them.
+    pub docs_markdown: Option<String>,
+    /// What a healthy probe response body must hold; `{}` clears it.
+    pub health_expectation: Option<crate::health::HealthExpectation>,
+    /// Ids or slugs of apps this one needs to work; replaces the declared
+    /// set, `[]` clears it. Feeds `composite_health`.
+    pub depends_on: Option<Vec<String>>,
+    /// The endpoints an agent most likely needs, as a quick capability map;
+    /// replaces the list, `[]` clears it.
+    pub key_endpoints: Option<Vec<KeyEndpoint>>,
+}
+
+/// An endpoint the owner pins on the listing. Stored as JSON in
+/// `apps.key_endpoints`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct KeyEndpoint {
+    pub method: String,
+    pub path: String,
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenameSlugRequest {
+    pub slug: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DuplicateAppRequest {
+    /// Name of the copy; defaults to the original's name.
+    pub name: Option<String>,
+    /// Point the original's `replacement_app_id` at the copy once the copy is published.
+    #[serde(default)]
+    pub link_original: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TranslationRequest {
+    pub name: Option<String>,
+    pub short_description: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Admin edit of a category's presentation. Omitted fields are kept; an
+/// empty string clears one.
+#[derive(Debug, Deserialize)]
+pub struct UpdateCategoryRequest {
+    pub description: Option<String>,
+    pub icon: Option<String>,
 }
 
 // === Review Models ===
@@ -99,8 +211,34 @@ pub struct SubmitReviewRequest {
     pub title: Option<String>,
     pub body: Option<String>,
     pub reviewer_name: Option<String>,
+    pub pros: Option<Vec<String>>,
+    pub cons: Option<Vec<String>>,
+    pub use_case: Option<String>,
+    /// hCaptcha/Turnstile response token, required from anonymous callers when CAPTCHA is enabled.
+    pub captcha_token: Option<String>,
+}
+
+/// Author edit of their own review; omitted fields keep their value.
+#[derive(Debug, Deserialize)]
+pub struct UpdateReviewRequest {
+    pub rating: Option<i64>,
+    pub title: Option<String>,
+    pub body: Option<String>,
+    pub reviewer_name: Option<String>,
+    pub pros: Option<Vec<String>>,
+    pub cons: Option<Vec<String>>,
+    pub use_case: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ReactionRequest {
+    /// One of `REVIEW_REACTIONS`, as the emoji or its name.
+    pub emoji: String,
+}
+
+/// Reactions a review can get, with the names accepted in their place.
+pub const REVIEW_REACTIONS: &[(&str, &str)] = &[("👍", "thumbs_up"), ("🎯", "on_point"), ("❗", "important")];
+
 // === Search / List Models ===
 
 #[derive(Debug, Deserialize, FromForm)]
@@ -139,6 +277,7 @@ pub struct PaginatedResponse<T: Serialize> {
 #[allow(dead_code)]
 pub struct CategoryInfo {
     pub name: String,
+    pub parent_id: Option<String>,
     pub count: i64,
 }
 
@@ -154,6 +293,21 @@ pub const VALID_PROTOCOLS: &[&str] = &[
     "other",
 ];
 
+/// Who can find a listing. `unlisted` apps open by id or slug but stay out
+/// of lists, search, category counts and trending; `private` apps are only
+/// shown to callers with an API key or account session.
+pub const VALID_VISIBILITIES: &[&str] = &["public", "unlisted", "private"];
+
+/// SQL condition on `column` keeping the listings a caller may find in
+/// lists and aggregates: public ones, plus private ones when signed in.
+pub fn listed_visibility(column: &str, signed_in: bool) -> String {
+    if signed_in {
+        format!("{column} IN ('public', 'private')")
+    } else {
+        format!("{column} = 'public'")
+    }
+}
+
 pub const VALID_CATEGORIES: &[&str] = &[
     "communication",
     "data",
@@ -169,7 +323,72 @@ pub const VALID_CATEGORIES: &[&str] = &[
     "other",
 ];
 
-pub const VALID_STATUSES: &[&str] = &["pending", "approved", "rejected", "deprecated"];
+pub const VALID_STATUSES: &[&str] = &["draft", "pending", "approved", "rejected", "deprecated", "archived"];
+
+/// Slugs a listing can't take because a fixed `/apps/...` route answers them.
+pub const RESERVED_SLUGS: &[&str] = &["bulk", "mine", "pending", "recommended", "search", "trending"];
+
+/// Shortest and longest custom slug.
+pub const SLUG_LENGTH: std::ops::RangeInclusive<usize> = 3..=64;
+
+/// Check a submitter-chosen slug: 3-64 lowercase letters, digits and single
+/// dashes, not starting or ending with a dash, and not reserved.
+pub fn check_slug(slug: &str) -> Result<(), String> {
+    if !SLUG_LENGTH.contains(&slug.len()) {
+        return Err(format!("slug must be {} to {} characters", SLUG_LENGTH.start(), SLUG_LENGTH.end()));
+    }
+    if !slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        || slug.starts_with('-')
+        || slug.ends_with('-')
+        || slug.contains("--")
+    {
+        return Err("slug may only contain lowercase letters, digits and single dashes".to_string());
+    }
+    if RESERVED_SLUGS.contains(&slug) {
+        return Err(format!("slug '{slug}' is reserved"));
+    }
+    Ok(())
+}
+
+/// HTTP methods a key endpoint can declare.
+pub const KEY_ENDPOINT_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// Normalize pinned endpoints: methods uppercased, paths and descriptions
+/// trimmed. Rejects unknown methods, paths that don't start with `/` or
+/// contain whitespace, empty descriptions and repeated method/path pairs,
+/// naming the offending `index` and `field`. Lengths are checked by
+/// `FieldLimits::check_update`.
+pub fn normalize_key_endpoints(endpoints: &[KeyEndpoint]) -> Result<Vec<KeyEndpoint>, crate::errors::ApiError> {
+    let invalid = |index: usize, field: &str, message: &str| {
+        crate::errors::ApiError::new(crate::errors::ErrorCode::InvalidKeyEndpoint, message)
+            .with("index", index)
+            .with("field", field)
+    };
+    let mut normalized: Vec<KeyEndpoint> = Vec::with_capacity(endpoints.len());
+    for (index, endpoint) in endpoints.iter().enumerate() {
+        let method = endpoint.method.trim().to_ascii_uppercase();
+        if !KEY_ENDPOINT_METHODS.contains(&method.as_str()) {
+            return Err(invalid(index, "method", "method must be GET, POST, PUT, PATCH, DELETE, HEAD or OPTIONS"));
+        }
+        let path = endpoint.path.trim();
+        if !path.starts_with('/') || path.contains(char::is_whitespace) {
+            return Err(invalid(index, "path", "path must start with / and contain no spaces"));
+        }
+        let description = endpoint.description.trim();
+        if description.is_empty() {
+            return Err(invalid(index, "description", "description is required"));
+        }
+        if normalized.iter().any(|e| e.method == method && e.path == path) {
+            return Err(invalid(index, "path", "Each method and path can only be pinned once"));
+        }
+        normalized.push(KeyEndpoint {
+            method,
+            path: path.to_string(),
+            description: description.to_string(),
+        });
+    }
+    Ok(normalized)
+}
 
 /// Generate a URL-safe slug from a name
 pub fn slugify(name: &str) -> String {
@@ -0,0 +1,348 @@
+use std::time::Duration;
+
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+use crate::errors::{ApiError, ErrorCode};
+
+/// HTTP timeout for the external moderation webhook.
+const WEBHOOK_TIMEOUT_SECS: u64 = 5;
+
+/// Why a piece of text was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejection {
+    pub field: String,
+    pub rule: String,
+    pub reason: String,
+}
+
+impl Rejection {
+    pub fn to_error(&self) -> ApiError {
+        ApiError::new(ErrorCode::ContentRejected, self.reason.clone())
+            .with("field", self.field.clone())
+            .with("rule", self.rule.clone())
+    }
+}
+
+/// A local check applied to each filtered text field.
+/// Returns the human-readable reason when the text should be rejected.
+pub trait ContentRule: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn check(&self, text: &str) -> Option<String>;
+}
+
+/// Rejects text with more than `max` links.
+pub struct MaxLinks(pub usize);
+
+impl ContentRule for MaxLinks {
+    fn name(&self) -> &'static str {
+        "max_links"
+    }
+
+    fn check(&self, text: &str) -> Option<String> {
+        let count = link_hosts(text).len();
+        (count > self.0).then(|| format!("Too many links ({}, max {})", count, self.0))
+    }
+}
+
+/// Rejects links to any of the listed domains or their subdomains.
+pub struct BannedDomains(pub Vec<String>);
+
+impl ContentRule for BannedDomains {
+    fn name(&self) -> &'static str {
+        "banned_domain"
+    }
+
+    fn check(&self, text: &str) -> Option<String> {
+        link_hosts(text).into_iter().find_map(|host| {
+            self.0
+                .iter()
+                .find(|d| host == **d || host.ends_with(&format!(".{}", d)))
+                .map(|d| format!("Links to {} are not allowed", d))
+        })
+    }
+}
+
+/// Rejects runs of the same character longer than `max` (e.g. "!!!!!!!!").
+pub struct RepeatedChars(pub usize);
+
+impl ContentRule for RepeatedChars {
+    fn name(&self) -> &'static str {
+        "repeated_chars"
+    }
+
+    fn check(&self, text: &str) -> Option<String> {
+        let mut run = 0;
+        let mut prev = None;
+        for c in text.chars() {
+            if Some(c) == prev && !c.is_whitespace() {
+                run += 1;
+            } else {
+                run = 1;
+                prev = Some(c);
+            }
+            if run > self.0 {
+                return Some(format!("Repeats '{}' more than {} times", c, self.0));
+            }
+        }
+        None
+    }
+}
+
+/// Lowercased hosts of every `http(s)://` or `www.` link in `text`.
+fn link_hosts(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            let lower = word.to_lowercase();
+            let start = match lower.find("://") {
+                Some(i) if lower[..i].ends_with("http") || lower[..i].ends_with("https") => i + 3,
+                _ => lower.find("www.")?,
+            };
+            let host: String = lower[start..]
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '.' || *c == '-')
+                .collect();
+            let host = host.trim_end_matches('.').to_string();
+            (!host.is_empty()).then_some(host)
+        })
+        .collect()
+}
+
+/// Spam filter for review and app description text.
+///
+/// Built-in rules run first; if they pass and `MODERATION_WEBHOOK_URL` is set,
+/// the text is POSTed there as `{"kind", "fields"}` and the webhook can veto it
+/// by answering `{"allow": false, "reason": "..."}`. Webhook errors and timeouts
+/// fail open so an outage doesn't block submissions.
+pub struct ContentFilter {
+    rules: Vec<Box<dyn ContentRule>>,
+    webhook_url: Option<String>,
+    client: reqwest::Client,
+}
+
+impl ContentFilter {
+    pub fn new(rules: Vec<Box<dyn ContentRule>>, webhook_url: Option<String>) -> Self {
+        ContentFilter {
+            rules,
+            webhook_url,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Built-in rules configured by `CONTENT_MAX_LINKS` (default 5),
+    /// `CONTENT_MAX_REPEATED_CHARS` (default 20), and `CONTENT_BANNED_DOMAINS`
+    /// (comma-separated), plus the optional `MODERATION_WEBHOOK_URL`.
+    pub fn from_env() -> Self {
+        let env_usize = |name: &str, default: usize| -> usize {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+        let banned: Vec<String> = std::env::var("CONTENT_BANNED_DOMAINS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|d| d.trim().to_lowercase())
+            .filter(|d| !d.is_empty())
+            .collect();
+
+        let mut rules: Vec<Box<dyn ContentRule>> = vec![
+            Box::new(MaxLinks(env_usize("CONTENT_MAX_LINKS", 5))),
+            Box::new(RepeatedChars(env_usize("CONTENT_MAX_REPEATED_CHARS", 20))),
+        ];
+        if !banned.is_empty() {
+            rules.push(Box::new(BannedDomains(banned)));
+        }
+
+        let webhook_url = std::env::var("MODERATION_WEBHOOK_URL")
+            .ok()
+            .filter(|u| !u.is_empty());
+        ContentFilter::new(rules, webhook_url)
+    }
+
+    /// Run the local rules over each `(field, text)` pair.
+    pub fn check_rules(&self, fields: &[(&str, Option<&str>)]) -> Result<(), Rejection> {
+        for (field, text) in fields {
+            let text = match text {
+                Some(t) if !t.is_empty() => t,
+                _ => continue,
+            };
+            for rule in &self.rules {
+                if let Some(reason) = rule.check(text) {
+                    return Err(Rejection {
+                        field: field.to_string(),
+                        rule: rule.name().to_string(),
+                        reason,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Local rules, then the moderation webhook (if configured).
+    /// `kind` is `"app"`, `"review"` or `"endorsement"`.
+    pub async fn check(&self, kind: &str, fields: &[(&str, Option<&str>)]) -> Result<(), Rejection> {
+        self.check_rules(fields)?;
+
+        let url = match &self.webhook_url {
+            Some(u) => u,
+            None => return Ok(()),
+        };
+        let present = fields_json(fields);
+        if present.is_empty() {
+            return Ok(());
+        }
+
+        let verdict: Value = match self
+            .client
+            .post(url)
+            .json(&json!({ "kind": kind, "fields": present }))
+            .send()
+            .await
+        {
+            Ok(resp) if resp.status().is_success() => resp.json().await.unwrap_or(Value::Null),
+            Ok(resp) => {
+                eprintln!("⚠️  Moderation webhook returned {} — allowing", resp.status());
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("⚠️  Moderation webhook failed: {} — allowing", e);
+                return Ok(());
+            }
+        };
+
+        if verdict.get("allow").and_then(|v| v.as_bool()) == Some(false) {
+            return Err(Rejection {
+                field: verdict
+                    .get("field")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("*")
+                    .to_string(),
+                rule: "webhook".to_string(),
+                reason: verdict
+                    .get("reason")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("Rejected by content moderation")
+                    .to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Filtered fields as a JSON object, skipping absent ones.
+fn fields_json(fields: &[(&str, Option<&str>)]) -> serde_json::Map<String, Value> {
+    fields
+        .iter()
+        .filter_map(|(f, t)| t.map(|t| (f.to_string(), json!(t))))
+        .collect()
+}
+
+/// Hours a pending app may wait before it's flagged overdue when
+/// `REVIEW_SLA_HOURS` is unset.
+pub const DEFAULT_REVIEW_SLA_HOURS: i64 = 48;
+
+/// How new submissions reach the catalog. With `manual` set, apps
+/// submitted by non-admins start `pending` until an admin approves them.
+#[derive(Debug, Clone, Copy)]
+pub struct ReviewPolicy {
+    pub manual: bool,
+    /// Review target for pending apps, from submission.
+    pub sla_hours: i64,
+}
+
+impl Default for ReviewPolicy {
+    fn default() -> Self {
+        ReviewPolicy {
+            manual: false,
+            sla_hours: DEFAULT_REVIEW_SLA_HOURS,
+        }
+    }
+}
+
+/// Record a moderation decision with the reviewer who made it and the
+/// reviewer the app was assigned to, if any.
+pub fn log_review(conn: &Connection, action: &str, reviewer_key_id: &str, app_id: &str, details: Value) {
+    let assigned: Option<String> = conn
+        .query_row(
+            "SELECT assigned_reviewer_key_id FROM apps WHERE id = ?1",
+            rusqlite::params![app_id],
+            |r| r.get(0),
+        )
+        .ok()
+        .flatten();
+    let mut details = details;
+    details["app_id"] = json!(app_id);
+    details["assigned_reviewer_key_id"] = json!(assigned);
+    let _ = conn.execute(
+        "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![uuid::Uuid::new_v4().to_string(), action, reviewer_key_id, details.to_string()],
+    );
+}
+
+/// Record a rejected submission for admin review.
+pub fn log_rejection(
+    conn: &Connection,
+    kind: &str,
+    app_id: Option<&str>,
+    key_id: Option<&str>,
+    rejection: &Rejection,
+    fields: &[(&str, Option<&str>)],
+) {
+    let _ = conn.execute(
+        "INSERT INTO content_rejections (id, kind, app_id, field, rule, reason, content, submitted_by_key_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            kind,
+            app_id,
+            rejection.field,
+            rejection.rule,
+            rejection.reason,
+            Value::Object(fields_json(fields)).to_string(),
+            key_id,
+        ],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_link_hosts() {
+        assert_eq!(
+            link_hosts("see https://Spam.example.com/x and www.foo.org, or http://bar.net."),
+            vec!["spam.example.com", "www.foo.org", "bar.net"]
+        );
+        assert!(link_hosts("no links here").is_empty());
+    }
+
+    #[test]
+    fn built_in_rules() {
+        assert!(MaxLinks(1).check("http://a.com http://b.com").is_some());
+        assert!(MaxLinks(2).check("http://a.com http://b.com").is_none());
+
+        let banned = BannedDomains(vec!["spam.com".into()]);
+        assert!(banned.check("visit https://cheap.spam.com/deal").is_some());
+        assert!(banned.check("visit https://notspam.com").is_none());
+
+        assert!(RepeatedChars(5).check("wow!!!!!!").is_some());
+        assert!(RepeatedChars(5).check("wow!!!!").is_none());
+        assert!(RepeatedChars(2).check("a   b").is_none());
+    }
+
+    #[test]
+    fn reports_first_failing_field() {
+        let filter = ContentFilter::new(vec![Box::new(RepeatedChars(3))], None);
+        let err = filter
+            .check_rules(&[("title", Some("fine")), ("body", Some("aaaaa")), ("x", None)])
+            .unwrap_err();
+        assert_eq!(err.field, "body");
+        assert_eq!(err.rule, "repeated_chars");
+    }
+}
@@ -0,0 +1,258 @@
+use rocket::http::{ContentType, Header, MediaType, Status};
+use rocket::response::{self, Responder};
+use rocket::serde::json::Json;
+use rocket::Request;
+use serde_json::Value;
+
+use crate::errors::ApiError;
+
+/// Representation chosen from the request's `Accept` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Markdown,
+    Text,
+}
+
+impl Format {
+    /// Pick the best format for a request. Falls back to JSON for missing,
+    /// wildcard, or unsupported Accept values.
+    pub fn from_request(request: &Request<'_>) -> Format {
+        let accept = match request.accept() {
+            Some(a) => a,
+            None => return Format::Json,
+        };
+
+        // Walk media types by preference (q-value, then order) and take the first we support
+        let mut media: Vec<_> = accept.iter().collect();
+        media.sort_by(|a, b| {
+            b.weight_or(1.0)
+                .partial_cmp(&a.weight_or(1.0))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        for m in media {
+            let mt = m.media_type();
+            if mt.top() == "application" && mt.sub() == "json" {
+                return Format::Json;
+            }
+            if mt.top() == "text" && (mt.sub() == "markdown" || mt.sub() == "x-markdown") {
+                return Format::Markdown;
+            }
+            if mt.top() == "text" && mt.sub() == "plain" {
+                return Format::Text;
+            }
+            if mt.top() == "*" {
+                return Format::Json;
+            }
+        }
+
+        Format::Json
+    }
+}
+
+/// A single app listing rendered as JSON, Markdown, or plain text
+/// depending on the request's `Accept` header. Localized content also
+/// varies with `Accept-Language`.
+///
+/// Error responses (non-200) are always JSON so clients can parse error codes.
+pub struct NegotiatedApp {
+    pub status: Status,
+    pub body: Value,
+}
+
+impl NegotiatedApp {
+    pub fn new(status: Status, body: Value) -> Self {
+        NegotiatedApp { status, body }
+    }
+}
+
+impl From<ApiError> for NegotiatedApp {
+    fn from(err: ApiError) -> Self {
+        NegotiatedApp::new(err.status(), err.body())
+    }
+}
+
+impl<'r> Responder<'r, 'static> for NegotiatedApp {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let format = if self.status == Status::Ok {
+            Format::from_request(request)
+        } else {
+            Format::Json
+        };
+
+        let revision = self.body.get("revision").and_then(|v| v.as_i64());
+        let mut response = match format {
+            Format::Json => (self.status, Json(self.body)).respond_to(request)?,
+            Format::Markdown => (
+                self.status,
+                (
+                    ContentType(MediaType::new("text", "markdown").with_params(("charset", "utf-8"))),
+                    app_to_markdown(&self.body),
+                ),
+            )
+                .respond_to(request)?,
+            Format::Text => {
+                (self.status, (ContentType::Plain, app_to_text(&self.body))).respond_to(request)?
+            }
+        };
+
+        response.set_header(Header::new("Vary", "Accept, Accept-Language"));
+        if let Some(revision) = revision {
+            response.set_header(Header::new("ETag", crate::revisions::etag(revision)));
+        }
+        Ok(response)
+    }
+}
+
+fn str_field<'a>(app: &'a Value, key: &str) -> Option<&'a str> {
+    app.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty())
+}
+
+fn tags_of(app: &Value) -> Vec<&str> {
+    app.get("tags")
+        .and_then(|t| t.as_array())
+        .map(|a| a.iter().filter_map(|t| t.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// `(method, path, description)` of the app's pinned key endpoints.
+fn key_endpoints_of(app: &Value) -> Vec<(&str, &str, &str)> {
+    app.get("key_endpoints")
+        .and_then(|v| v.as_array())
+        .map(|list| {
+            list.iter()
+                .filter_map(|e| Some((e["method"].as_str()?, e["path"].as_str()?, e["description"].as_str()?)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Render an app JSON object as a Markdown document.
+pub fn app_to_markdown(app: &Value) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("# {}\n\n", str_field(app, "name").unwrap_or("")));
+    if let Some(short) = str_field(app, "short_description") {
+        out.push_str(&format!("> {}\n\n", short));
+    }
+    if let Some(desc) = str_field(app, "description") {
+        out.push_str(desc);
+        out.push_str("\n\n");
+    }
+
+    out.push_str("## Details\n\n");
+    for (label, key) in [
+        ("Protocol", "protocol"),
+        ("Category", "category"),
+        ("Status", "status"),
+    ] {
+        if let Some(v) = str_field(app, key) {
+            out.push_str(&format!("- **{}:** {}\n", label, v));
+        }
+    }
+
+    let tags = tags_of(app);
+    if !tags.is_empty() {
+        let rendered: Vec<String> = tags.iter().map(|t| format!("`{}`", t)).collect();
+        out.push_str(&format!("- **Tags:** {}\n", rendered.join(", ")));
+    }
+
+    let avg = app.get("avg_rating").and_then(|v| v.as_f64()).unwrap_or(0.0);
+    let count = app.get("review_count").and_then(|v| v.as_i64()).unwrap_or(0);
+    out.push_str(&format!("- **Rating:** {:.1} ({} reviews)\n", avg, count));
+    if let Some(status) = app.pointer("/conformance/status").and_then(|v| v.as_str()) {
+        out.push_str(&format!("- **API conformance:** {}\n", status));
+    }
+    if let Some(status) = str_field(app, "composite_health") {
+        out.push_str(&format!("- **Composite health:** {}\n", status));
+    }
+
+    match (str_field(app, "author_name"), str_field(app, "author_url")) {
+        (Some(name), Some(url)) => out.push_str(&format!("- **Author:** [{}]({})\n", name, url)),
+        (Some(name), None) => out.push_str(&format!("- **Author:** {}\n", name)),
+        _ => {}
+    }
+
+    let endpoints = key_endpoints_of(app);
+    if !endpoints.is_empty() {
+        out.push_str("\n## Key endpoints\n\n");
+        for (method, path, description) in endpoints {
+            out.push_str(&format!("- `{} {}` — {}\n", method, path, description));
+        }
+    }
+
+    let links: Vec<(&str, &str)> = [
+        ("Homepage", "homepage_url"),
+        ("API", "api_url"),
+        ("API spec", "api_spec_url"),
+    ]
+    .into_iter()
+    .filter_map(|(label, key)| str_field(app, key).map(|url| (label, url)))
+    .collect();
+
+    if !links.is_empty() {
+        out.push_str("\n## Links\n\n");
+        for (label, url) in links {
+            out.push_str(&format!("- {}: <{}>\n", label, url));
+        }
+    }
+
+    out
+}
+
+/// Render an app JSON object as plain text.
+pub fn app_to_text(app: &Value) -> String {
+    let mut out = String::new();
+
+    out.push_str(str_field(app, "name").unwrap_or(""));
+    out.push('\n');
+    if let Some(short) = str_field(app, "short_description") {
+        out.push_str(short);
+        out.push('\n');
+    }
+    out.push('\n');
+    if let Some(desc) = str_field(app, "description") {
+        out.push_str(desc);
+        out.push_str("\n\n");
+    }
+
+    for (label, key) in [
+        ("Protocol", "protocol"),
+        ("Category", "category"),
+        ("Status", "status"),
+        ("Author", "author_name"),
+        ("Homepage", "homepage_url"),
+        ("API", "api_url"),
+        ("API spec", "api_spec_url"),
+    ] {
+        if let Some(v) = str_field(app, key) {
+            out.push_str(&format!("{}: {}\n", label, v));
+        }
+    }
+
+    let tags = tags_of(app);
+    if !tags.is_empty() {
+        out.push_str(&format!("Tags: {}\n", tags.join(", ")));
+    }
+
+    let avg = app.get("avg_rating").and_then(|v| v.as_f64()).unwrap_or(0.0);
+    let count = app.get("review_count").and_then(|v| v.as_i64()).unwrap_or(0);
+    out.push_str(&format!("Rating: {:.1} ({} reviews)\n", avg, count));
+    if let Some(status) = app.pointer("/conformance/status").and_then(|v| v.as_str()) {
+        out.push_str(&format!("API conformance: {}\n", status));
+    }
+    if let Some(status) = str_field(app, "composite_health") {
+        out.push_str(&format!("Composite health: {}\n", status));
+    }
+
+    let endpoints = key_endpoints_of(app);
+    if !endpoints.is_empty() {
+        out.push_str("\nKey endpoints:\n");
+        for (method, path, description) in endpoints {
+            out.push_str(&format!("  {} {} - {}\n", method, path, description));
+        }
+    }
+
+    out
+}
@@ -0,0 +1,372 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::{Orbit, Rocket};
+use rusqlite::Connection;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::accounts::Mailer;
+use crate::events::{AppEvent, EventBus};
+
+/// How often the worker looks for due jobs.
+const POLL_INTERVAL_SECS: u64 = 15;
+
+/// Jobs picked up per poll.
+const BATCH_SIZE: i64 = 50;
+
+/// Failed sends are retried with exponential backoff, then marked `failed`.
+pub const MAX_ATTEMPTS: i64 = 5;
+
+/// Default admin digest interval: daily.
+const DEFAULT_DIGEST_INTERVAL_SECS: u64 = 86_400;
+
+/// Email notification settings.
+#[derive(Debug, Clone, Default)]
+pub struct NotifyConfig {
+    pub enabled: bool,
+    pub admin_emails: Vec<String>,
+    pub digest_interval_secs: u64,
+}
+
+impl NotifyConfig {
+    /// `NOTIFICATIONS_ENABLED` (default false), `NOTIFY_ADMIN_EMAILS`
+    /// (comma-separated), and `NOTIFY_DIGEST_INTERVAL_SECS` (default 86400, 0 disables).
+    pub fn from_env() -> Self {
+        NotifyConfig {
+            enabled: std::env::var("NOTIFICATIONS_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            admin_emails: std::env::var("NOTIFY_ADMIN_EMAILS")
+                .unwrap_or_default()
+                .split(',')
+                .map(crate::accounts::normalize_email)
+                .filter(|e| crate::accounts::is_valid_email(e))
+                .collect(),
+            digest_interval_secs: std::env::var("NOTIFY_DIGEST_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_DIGEST_INTERVAL_SECS),
+        }
+    }
+}
+
+/// Queue an email in the `jobs` table.
+pub fn enqueue_email(conn: &Connection, to: &str, subject: &str, body: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO jobs (id, kind, payload) VALUES (?1, 'email', ?2)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            json!({ "to": to, "subject": subject, "body": body }).to_string(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Author-facing email for a moderation or health event, as `(subject, body)`.
+/// `None` for events authors aren't notified about.
+pub fn author_message(app_name: &str, event: &AppEvent) -> Option<(String, String)> {
+    let str_field = |key: &str| event.data.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+
+    match event.event.as_str() {
+        "app.approved" => {
+            let mut body = format!("Your listing \"{}\" has been approved and is now live.\n", app_name);
+            if let Some(note) = str_field("note") {
+                body.push_str(&format!("\nNote from the moderator: {}\n", note));
+            }
+            Some((format!("\"{}\" was approved", app_name), body))
+        }
+        "app.rejected" => Some((
+            format!("\"{}\" was rejected", app_name),
+            format!(
+                "Your listing \"{}\" was rejected.\n\nReason: {}\n\nYou can edit the listing and ask for another review.\n",
+                app_name,
+                str_field("reason").unwrap_or("no reason given")
+            ),
+        )),
+        "app.deprecated" => {
+            let mut body = format!(
+                "Your listing \"{}\" has been marked as deprecated.\n\nReason: {}\n",
+                app_name,
+                str_field("reason").unwrap_or("no reason given")
+            );
+            if let Some(replacement) = str_field("replacement_app_id") {
+                body.push_str(&format!("Replacement: {}\n", replacement));
+            }
+            if let Some(sunset) = str_field("sunset_at") {
+                body.push_str(&format!("Sunset date: {}\n", sunset));
+            }
+            Some((format!("\"{}\" was deprecated", app_name), body))
+        }
+        "app.health_changed" => {
+            let status = str_field("status").unwrap_or("unknown");
+            if str_field("transition") == Some("recovered") {
+                return Some((
+                    format!("\"{}\" has recovered", app_name),
+                    format!("Health checks for \"{}\" are passing again.\n", app_name),
+                ));
+            }
+            let mut body = format!("Health checks for \"{}\" report it as {}.\n", app_name, status);
+            if let Some(code) = event.data.get("status_code").and_then(|v| v.as_i64()) {
+                body.push_str(&format!("Last HTTP status: {}\n", code));
+            }
+            if let Some(reason) = str_field("error_message") {
+                body.push_str(&format!("Details: {}\n", reason));
+            }
+            Some((format!("\"{}\" is {}", app_name, status), body))
+        }
+        _ => None,
+    }
+}
+
+/// Queue the author notification for an event, if the app has an author email.
+/// Returns whether an email was queued.
+pub fn enqueue_for_event(conn: &Connection, event: &AppEvent) -> bool {
+    let app_id = match event.app_id() {
+        Some(id) => id,
+        None => return false,
+    };
+    let (name, email): (String, Option<String>) = match conn.query_row(
+        "SELECT name, author_email FROM apps WHERE id = ?1",
+        rusqlite::params![app_id],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    ) {
+        Ok(v) => v,
+        Err(_) => return false,
+    };
+    let (email, (subject, body)) = match (email, author_message(&name, event)) {
+        (Some(email), Some(message)) => (email, message),
+        _ => return false,
+    };
+    enqueue_email(conn, &email, &subject, &body).is_ok()
+}
+
+/// Queue a digest of apps awaiting review and recent content-filter rejections
+/// for each admin. Nothing is queued when there is nothing to review.
+pub fn enqueue_admin_digest(conn: &Connection, admin_emails: &[String], since_secs: u64) -> usize {
+    if admin_emails.is_empty() {
+        return 0;
+    }
+
+    let pending: Vec<(String, String)> = conn
+        .prepare("SELECT name, created_at FROM apps WHERE status = 'pending' ORDER BY created_at ASC")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .unwrap_or_default();
+    let rejections: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM content_rejections WHERE created_at >= datetime('now', ?1)",
+            rusqlite::params![format!("-{} seconds", since_secs)],
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+
+    if pending.is_empty() && rejections == 0 {
+        return 0;
+    }
+
+    let mut body = format!("{} app(s) awaiting review:\n", pending.len());
+    for (name, created_at) in pending.iter().take(20) {
+        body.push_str(&format!("- {} (submitted {})\n", name, created_at));
+    }
+    if pending.len() > 20 {
+        body.push_str(&format!("- ...and {} more\n", pending.len() - 20));
+    }
+    body.push_str(&format!(
+        "\n{} submission(s) rejected by the content filter since the last digest.\n",
+        rejections
+    ));
+    let subject = format!("App Directory digest: {} pending, {} flagged", pending.len(), rejections);
+
+    admin_emails
+        .iter()
+        .filter(|to| enqueue_email(conn, to, &subject, &body).is_ok())
+        .count()
+}
+
+/// Seconds to wait before retry number `attempts` (1 min, 2, 4, 8, ...).
+pub fn retry_delay_secs(attempts: i64) -> i64 {
+    60 * 2_i64.pow(attempts.clamp(1, 10) as u32 - 1)
+}
+
+/// Record a failed attempt: reschedule with backoff, or give up after `MAX_ATTEMPTS`.
+pub fn record_failure(conn: &Connection, job_id: &str, attempts: i64, error: &str) {
+    let _ = if attempts >= MAX_ATTEMPTS {
+        conn.execute(
+            "UPDATE jobs SET status = 'failed', attempts = ?1, last_error = ?2 WHERE id = ?3",
+            rusqlite::params![attempts, error, job_id],
+        )
+    } else {
+        conn.execute(
+            "UPDATE jobs SET attempts = ?1, last_error = ?2,
+                run_after = datetime('now', ?3) WHERE id = ?4",
+            rusqlite::params![
+                attempts,
+                error,
+                format!("+{} seconds", retry_delay_secs(attempts)),
+                job_id
+            ],
+        )
+    };
+}
+
+/// Send every due email job. Returns the number delivered.
+pub async fn process_due_jobs(db: &Mutex<Connection>, mailer: &Mailer) -> usize {
+    let due: Vec<(String, String, i64)> = {
+        let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+        conn.prepare(
+            "SELECT id, payload, attempts FROM jobs
+             WHERE kind = 'email' AND status = 'pending' AND run_after <= datetime('now')
+             ORDER BY run_after ASC LIMIT ?1",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![BATCH_SIZE], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .unwrap_or_default()
+    };
+
+    let mut sent = 0;
+    for (id, payload, attempts) in due {
+        let mail: Value = serde_json::from_str(&payload).unwrap_or(Value::Null);
+        let field = |key: &str| mail.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+        let result = mailer.deliver(&field("to"), &field("subject"), field("body")).await;
+
+        let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(()) => {
+                sent += 1;
+                let _ = conn.execute(
+                    "UPDATE jobs SET status = 'done', attempts = attempts + 1, completed_at = datetime('now')
+                     WHERE id = ?1",
+                    rusqlite::params![id],
+                );
+            }
+            Err(e) => {
+                eprintln!("❌ Notification to {} failed (attempt {}): {}", field("to"), attempts + 1, e);
+                record_failure(&conn, &id, attempts + 1, &e);
+            }
+        }
+    }
+    sent
+}
+
+/// Rocket fairing that turns moderation and health events into queued emails
+/// and runs the delivery worker. Does nothing unless `NOTIFICATIONS_ENABLED` is set.
+pub struct Notifications {
+    pub db_path: String,
+    pub config: NotifyConfig,
+}
+
+#[rocket::async_trait]
+impl Fairing for Notifications {
+    fn info(&self) -> Info {
+        Info {
+            name: "Email Notifications",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        if !self.config.enabled {
+            return;
+        }
+
+        let conn = match Connection::open(&self.db_path) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("❌ Notifications disabled: cannot open database: {e}");
+                return;
+            }
+        };
+        let db = Arc::new(Mutex::new(conn));
+        let mailer = rocket.state::<Mailer>().expect("Mailer not managed").clone();
+        let mut rx = rocket.state::<EventBus>().expect("EventBus not managed").subscribe_local();
+        let shutdown = rocket.shutdown();
+        let config = self.config.clone();
+
+        rocket::info!(
+            "Email notifications enabled ({} admin digest recipient(s))",
+            config.admin_emails.len()
+        );
+
+        let listener_db = db.clone();
+        let listener_shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    received = rx.recv() => match received {
+                        Ok(event) => {
+                            let conn = listener_db.lock().unwrap_or_else(|e| e.into_inner());
+                            enqueue_for_event(&conn, &event);
+                        }
+                        Err(RecvError::Lagged(missed)) => {
+                            eprintln!("⚠️  Notification listener missed {missed} events");
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                    _ = listener_shutdown.clone() => break,
+                }
+            }
+        });
+
+        tokio::spawn(async move {
+            let poll = Duration::from_secs(POLL_INTERVAL_SECS);
+            let digest_every = Duration::from_secs(config.digest_interval_secs);
+            let mut last_digest = Instant::now();
+
+            loop {
+                if config.digest_interval_secs > 0 && last_digest.elapsed() >= digest_every {
+                    let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+                    enqueue_admin_digest(&conn, &config.admin_emails, config.digest_interval_secs);
+                    last_digest = Instant::now();
+                }
+                process_due_jobs(&db, &mailer).await;
+
+                tokio::select! {
+                    _ = tokio::time::sleep(poll) => {},
+                    _ = shutdown.clone() => break,
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str, data: Value) -> AppEvent {
+        AppEvent {
+            event: name.to_string(),
+            data,
+        }
+    }
+
+    #[test]
+    fn builds_author_messages() {
+        let (subject, body) =
+            author_message("Weather", &event("app.rejected", json!({ "reason": "Broken link" }))).unwrap();
+        assert_eq!(subject, "\"Weather\" was rejected");
+        assert!(body.contains("Reason: Broken link"));
+
+        let (subject, _) = author_message(
+            "Weather",
+            &event("app.health_changed", json!({ "status": "unreachable", "transition": "unreachable" })),
+        )
+        .unwrap();
+        assert_eq!(subject, "\"Weather\" is unreachable");
+
+        assert!(author_message("Weather", &event("app.updated", json!({}))).is_none());
+    }
+
+    #[test]
+    fn backs_off_exponentially() {
+        assert_eq!(retry_delay_secs(1), 60);
+        assert_eq!(retry_delay_secs(2), 120);
+        assert_eq!(retry_delay_secs(4), 480);
+    }
+}
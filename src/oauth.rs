@@ -0,0 +1,214 @@
+use base64::Engine;
+use rocket::form::{Form, FromForm};
+use rocket::http::{ContentType, Header, Status};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::response::{self, Responder};
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+use crate::auth::hash_key;
+use crate::DbState;
+
+/// Path of the token endpoint. Stays open during maintenance so OAuth
+/// clients can keep reading.
+pub const TOKEN_PATH: &str = "/api/v1/oauth/token";
+
+/// Prefix that distinguishes access tokens from API keys (`ad_`) and
+/// session tokens (`sess_`).
+pub const TOKEN_PREFIX: &str = "at_";
+
+/// Access token lifetime when `OAUTH_TOKEN_TTL_SECS` is unset.
+pub const DEFAULT_TOKEN_TTL_SECS: i64 = 3600;
+
+/// OAuth2 client-credentials settings.
+#[derive(Debug, Clone, Copy)]
+pub struct OAuthConfig {
+    /// Seconds an access token is valid; 0 turns the token endpoint off.
+    pub token_ttl_secs: i64,
+}
+
+impl OAuthConfig {
+    /// `OAUTH_TOKEN_TTL_SECS` (default 3600, at most a day).
+    pub fn from_env() -> Self {
+        let token_ttl_secs = std::env::var("OAUTH_TOKEN_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(DEFAULT_TOKEN_TTL_SECS)
+            .clamp(0, 86_400);
+        OAuthConfig { token_ttl_secs }
+    }
+}
+
+/// An issued access token. `expires_in` is capped by the key's own expiry.
+#[derive(Debug)]
+pub struct IssuedToken {
+    pub access_token: String,
+    pub expires_in: i64,
+}
+
+/// Exchange an API key's id and secret for an access token. Returns `None`
+/// when they don't match an active, unexpired key. Expired tokens are
+/// pruned along the way.
+pub fn issue_token(
+    conn: &Connection,
+    client_id: &str,
+    client_secret: &str,
+    ttl_secs: i64,
+) -> rusqlite::Result<Option<IssuedToken>> {
+    let _ = conn.execute("DELETE FROM oauth_tokens WHERE expires_at <= datetime('now')", []);
+    let remaining: Option<Option<i64>> = match conn.query_row(
+        "SELECT CAST(strftime('%s', expires_at) - strftime('%s', 'now') AS INTEGER) FROM api_keys
+         WHERE id = ?1 AND key_hash = ?2 AND revoked = 0
+           AND (expires_at IS NULL OR expires_at > datetime('now'))",
+        rusqlite::params![client_id, hash_key(client_secret)],
+        |r| r.get(0),
+    ) {
+        Ok(remaining) => Some(remaining),
+        Err(rusqlite::Error::QueryReturnedNoRows) => None,
+        Err(e) => return Err(e),
+    };
+    let Some(remaining) = remaining else { return Ok(None) };
+
+    let expires_in = remaining.map_or(ttl_secs, |secs| secs.min(ttl_secs));
+    let access_token = format!("{TOKEN_PREFIX}{}", uuid::Uuid::new_v4().simple());
+    conn.execute(
+        "INSERT INTO oauth_tokens (token_hash, key_id, expires_at) VALUES (?1, ?2, datetime('now', ?3))",
+        rusqlite::params![hash_key(&access_token), client_id, format!("+{expires_in} seconds")],
+    )?;
+    Ok(Some(IssuedToken { access_token, expires_in }))
+}
+
+/// The key an access token was issued for, with whether the token has
+/// expired, or `None` for an unknown token.
+pub fn token_key(conn: &Connection, token: &str) -> Option<(String, bool)> {
+    conn.query_row(
+        "SELECT key_id, expires_at <= datetime('now') FROM oauth_tokens WHERE token_hash = ?1",
+        rusqlite::params![hash_key(token)],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    )
+    .ok()
+}
+
+/// Drop every access token of a key, e.g. after its secret was rotated.
+pub fn revoke_tokens(conn: &Connection, key_id: &str) {
+    let _ = conn.execute("DELETE FROM oauth_tokens WHERE key_id = ?1", rusqlite::params![key_id]);
+}
+
+#[derive(Debug, FromForm)]
+pub struct TokenRequest {
+    pub grant_type: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    /// Accepted and ignored: tokens carry the key's own permissions.
+    pub scope: Option<String>,
+}
+
+/// Client credentials sent as `Authorization: Basic`, the method RFC 6749
+/// asks servers to support.
+pub struct BasicCredentials(Option<(String, String)>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for BasicCredentials {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, ()> {
+        let credentials = request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|h| h.strip_prefix("Basic "))
+            .and_then(|b64| base64::engine::general_purpose::STANDARD.decode(b64.trim()).ok())
+            .and_then(|raw| String::from_utf8(raw).ok())
+            .and_then(|pair| pair.split_once(':').map(|(id, secret)| (id.to_string(), secret.to_string())));
+        Outcome::Success(BasicCredentials(credentials))
+    }
+}
+
+/// Token endpoint response in the RFC 6749 shape (`error` codes are the
+/// spec's lowercase ones, not the directory's), never cached.
+pub struct TokenResponse(Status, Value);
+
+impl TokenResponse {
+    fn error(status: Status, error: &str, description: &str) -> Self {
+        TokenResponse(status, json!({ "error": error, "error_description": description }))
+    }
+}
+
+impl<'r> Responder<'r, 'static> for TokenResponse {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = (self.0, (ContentType::JSON, self.1.to_string())).respond_to(request)?;
+        response.set_header(Header::new("Cache-Control", "no-store"));
+        if self.0 == Status::Unauthorized {
+            response.set_header(Header::new("WWW-Authenticate", "Basic realm=\"app-directory\""));
+        }
+        Ok(response)
+    }
+}
+
+/// OAuth2 client-credentials grant: trade an API key's id (`client_id`) and
+/// secret (`client_secret`), sent in the form body or as HTTP Basic, for a
+/// short-lived bearer token that works wherever the key does.
+#[post("/oauth/token", data = "<form>")]
+pub fn token(
+    form: Form<TokenRequest>,
+    basic: BasicCredentials,
+    config: &rocket::State<OAuthConfig>,
+    db: &rocket::State<DbState>,
+) -> TokenResponse {
+    if config.token_ttl_secs == 0 {
+        return TokenResponse::error(Status::NotFound, "unsupported_grant_type", "OAuth token endpoint is disabled");
+    }
+    if form.grant_type.as_deref() != Some("client_credentials") {
+        return TokenResponse::error(
+            Status::BadRequest,
+            "unsupported_grant_type",
+            "Only grant_type=client_credentials is supported",
+        );
+    }
+    let credentials = basic.0.or_else(|| form.client_id.clone().zip(form.client_secret.clone()));
+    let Some((client_id, client_secret)) = credentials else {
+        return TokenResponse::error(Status::BadRequest, "invalid_request", "client_id and client_secret are required");
+    };
+
+    let conn = db.conn();
+    match issue_token(&conn, &client_id, &client_secret, config.token_ttl_secs) {
+        Ok(Some(issued)) => TokenResponse(
+            Status::Ok,
+            json!({
+                "access_token": issued.access_token,
+                "token_type": "Bearer",
+                "expires_in": issued.expires_in,
+            }),
+        ),
+        Ok(None) => TokenResponse::error(Status::Unauthorized, "invalid_client", "Unknown, revoked or expired client credentials"),
+        Err(e) => {
+            eprintln!("❌ Failed to issue access token: {e}");
+            TokenResponse::error(Status::InternalServerError, "server_error", "Internal server error")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issues_tokens_for_matching_credentials_only() {
+        let conn = crate::db::init_db(":memory:");
+        let (id, secret) = crate::auth::create_expiring_api_key(&conn, "client", false, None, None);
+
+        assert!(issue_token(&conn, &id, "ad_wrong", 600).unwrap().is_none());
+        let issued = issue_token(&conn, &id, &secret, 600).unwrap().unwrap();
+        assert!(issued.access_token.starts_with(TOKEN_PREFIX));
+        assert_eq!(issued.expires_in, 600);
+        assert_eq!(token_key(&conn, &issued.access_token), Some((id.clone(), false)));
+
+        revoke_tokens(&conn, &id);
+        assert_eq!(token_key(&conn, &issued.access_token), None);
+
+        // A key expiring sooner than the TTL caps the token
+        conn.execute("UPDATE api_keys SET expires_at = datetime('now', '+60 seconds') WHERE id = ?1", [&id])
+            .unwrap();
+        let issued = issue_token(&conn, &id, &secret, 600).unwrap().unwrap();
+        assert!(issued.expires_in <= 60);
+    }
+}
@@ -0,0 +1,372 @@
+use std::path::{Path, PathBuf};
+
+use ab_glyph::{point, Font, FontRef, PxScale, ScaleFont};
+use sha2::{Digest, Sha256};
+
+/// Card size recommended for OpenGraph and Twitter large images.
+pub const WIDTH: u32 = 1200;
+pub const HEIGHT: u32 = 630;
+
+/// Bumped whenever the card layout changes so cached files are redrawn.
+const RENDER_VERSION: u32 = 1;
+
+const SITE_NAME: &str = "App Directory";
+const MARGIN: f32 = 80.0;
+
+type Rgb = [u8; 3];
+const BACKGROUND: Rgb = [15, 23, 42];
+const ACCENT: Rgb = [99, 102, 241];
+const TEXT: Rgb = [248, 250, 252];
+const MUTED: Rgb = [148, 163, 184];
+const CHIP: Rgb = [30, 41, 59];
+const STAR: Rgb = [250, 204, 21];
+const STAR_EMPTY: Rgb = [51, 65, 85];
+
+/// What a share card shows.
+#[derive(Debug, Clone)]
+pub struct OgCard<'a> {
+    pub name: &'a str,
+    pub short_description: &'a str,
+    pub category: &'a str,
+    pub avg_rating: f64,
+    pub review_count: i64,
+}
+
+impl OgCard<'_> {
+    /// Changes whenever anything drawn on the card does.
+    fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(format!(
+            "{}\0{}\0{}\0{}\0{:.1}\0{}",
+            RENDER_VERSION, self.name, self.short_description, self.category, self.avg_rating, self.review_count
+        ));
+        hex::encode(&hasher.finalize()[..8])
+    }
+}
+
+/// Rendered cards cached on disk as `<app id>-<fingerprint>.png`, so an
+/// app's card is redrawn only after its name, summary, category or rating
+/// changes. `OG_IMAGE_DIR` defaults to `<db name>-og` next to the database.
+pub struct OgImages {
+    dir: PathBuf,
+}
+
+impl OgImages {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        OgImages { dir: dir.into() }
+    }
+
+    pub fn from_env(db_path: &str) -> Self {
+        let dir = std::env::var("OG_IMAGE_DIR")
+            .ok()
+            .filter(|d| !d.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                let path = Path::new(db_path);
+                let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("app_directory");
+                path.with_file_name(format!("{stem}-og"))
+            });
+        OgImages::new(dir)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// PNG card for an app: from the cache when current, otherwise rendered
+    /// and stored, replacing the app's older cards. A card that can't be
+    /// written to disk is still returned.
+    pub fn get(&self, app_id: &str, card: &OgCard) -> Vec<u8> {
+        let file_name = format!("{}-{}.png", app_id, card.fingerprint());
+        let path = self.dir.join(&file_name);
+        if let Ok(png) = std::fs::read(&path) {
+            return png;
+        }
+
+        let png = render(card);
+        if std::fs::create_dir_all(&self.dir).is_ok() {
+            self.remove_stale(app_id, &file_name);
+            // Write then rename so concurrent readers never see half a file
+            let tmp = self.dir.join(format!("{}.tmp-{}", file_name, uuid::Uuid::new_v4()));
+            if std::fs::write(&tmp, &png).is_ok() && std::fs::rename(&tmp, &path).is_err() {
+                let _ = std::fs::remove_file(&tmp);
+            }
+        }
+        png
+    }
+
+    /// Delete an app's cached cards other than `keep`.
+    fn remove_stale(&self, app_id: &str, keep: &str) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let prefix = format!("{}-", app_id);
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(&prefix) && name.ends_with(".png") && name != keep {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+/// RGB pixel buffer with alpha blending.
+struct Canvas {
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(background: Rgb) -> Self {
+        Canvas {
+            pixels: background.repeat((WIDTH * HEIGHT) as usize),
+        }
+    }
+
+    fn blend(&mut self, x: i32, y: i32, color: Rgb, alpha: f32) {
+        if x < 0 || y < 0 || x >= WIDTH as i32 || y >= HEIGHT as i32 || alpha <= 0.0 {
+            return;
+        }
+        let alpha = alpha.min(1.0);
+        let i = ((y as u32 * WIDTH + x as u32) * 3) as usize;
+        for (c, channel) in color.iter().enumerate() {
+            let old = self.pixels[i + c] as f32;
+            self.pixels[i + c] = (old + (*channel as f32 - old) * alpha).round() as u8;
+        }
+    }
+
+    fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, color: Rgb) {
+        for py in y..y + h {
+            for px in x..x + w {
+                self.blend(px, py, color, 1.0);
+            }
+        }
+    }
+
+    /// Draw one line of text with its baseline at `y`. Returns the width.
+    fn text(&mut self, font: &FontRef, size: f32, x: f32, y: f32, text: &str, color: Rgb) -> f32 {
+        let scale = PxScale::from(size);
+        let scaled = font.as_scaled(scale);
+        let mut caret = x;
+        let mut previous = None;
+        for c in text.chars() {
+            let id = scaled.glyph_id(c);
+            if let Some(prev) = previous {
+                caret += scaled.kern(prev, id);
+            }
+            let glyph = id.with_scale_and_position(scale, point(caret, y));
+            if let Some(outlined) = font.outline_glyph(glyph) {
+                let bounds = outlined.px_bounds();
+                outlined.draw(|gx, gy, coverage| {
+                    self.blend(bounds.min.x as i32 + gx as i32, bounds.min.y as i32 + gy as i32, color, coverage);
+                });
+            }
+            caret += scaled.h_advance(id);
+            previous = Some(id);
+        }
+        caret - x
+    }
+
+    /// Five-point star centred on `(cx, cy)`, filled left to right up to
+    /// `fill` (0–1) with the rest drawn as an empty star.
+    fn star(&mut self, cx: f32, cy: f32, radius: f32, fill: f32) {
+        let points: Vec<(f32, f32)> = (0..10)
+            .map(|i| {
+                let r = if i % 2 == 0 { radius } else { radius * 0.45 };
+                let angle = std::f32::consts::PI * (i as f32 / 5.0 - 0.5);
+                (cx + r * angle.cos(), cy + r * angle.sin())
+            })
+            .collect();
+        let split = cx - radius + 2.0 * radius * fill.clamp(0.0, 1.0);
+        const SAMPLES: usize = 4;
+        for py in (cy - radius).floor() as i32..=(cy + radius).ceil() as i32 {
+            for px in (cx - radius).floor() as i32..=(cx + radius).ceil() as i32 {
+                let (mut full, mut empty) = (0, 0);
+                for sy in 0..SAMPLES {
+                    for sx in 0..SAMPLES {
+                        let x = px as f32 + (sx as f32 + 0.5) / SAMPLES as f32;
+                        let y = py as f32 + (sy as f32 + 0.5) / SAMPLES as f32;
+                        if inside(&points, x, y) {
+                            if x < split {
+                                full += 1;
+                            } else {
+                                empty += 1;
+                            }
+                        }
+                    }
+                }
+                let total = (SAMPLES * SAMPLES) as f32;
+                self.blend(px, py, STAR_EMPTY, empty as f32 / total);
+                self.blend(px, py, STAR, full as f32 / total);
+            }
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, WIDTH, HEIGHT);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        if let Ok(mut writer) = encoder.write_header() {
+            let _ = writer.write_image_data(&self.pixels);
+        }
+        out
+    }
+}
+
+/// Even-odd point-in-polygon test.
+fn inside(polygon: &[(f32, f32)], x: f32, y: f32) -> bool {
+    let mut result = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (xi, yi) = polygon[i];
+        let (xj, yj) = polygon[j];
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            result = !result;
+        }
+        j = i;
+    }
+    result
+}
+
+fn text_width(font: &FontRef, size: f32, text: &str) -> f32 {
+    let scaled = font.as_scaled(PxScale::from(size));
+    let mut width = 0.0;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(prev) = previous {
+            width += scaled.kern(prev, id);
+        }
+        width += scaled.h_advance(id);
+        previous = Some(id);
+    }
+    width
+}
+
+/// Break `text` into at most `max_lines` lines no wider than `max_width`,
+/// ending with an ellipsis when it doesn't fit.
+fn wrap(font: &FontRef, size: f32, text: &str, max_width: f32, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if text_width(font, size, &format!("{line} {word}")) <= max_width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(ellipsize(font, size, word, max_width, false)),
+        }
+    }
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            *last = ellipsize(font, size, last, max_width, true);
+        }
+    }
+    lines
+}
+
+/// `text` cut to fit `max_width`, with `…` when anything was cut (or when
+/// `force` says more text follows).
+fn ellipsize(font: &FontRef, size: f32, text: &str, max_width: f32, force: bool) -> String {
+    if !force && text_width(font, size, text) <= max_width {
+        return text.to_string();
+    }
+    let mut chars: Vec<char> = text.chars().collect();
+    loop {
+        let candidate = format!("{}…", chars.iter().collect::<String>().trim_end());
+        if chars.is_empty() || text_width(font, size, &candidate) <= max_width {
+            return candidate;
+        }
+        chars.pop();
+    }
+}
+
+/// Draw a 1200×630 share card and encode it as PNG.
+pub fn render(card: &OgCard) -> Vec<u8> {
+    let bold = FontRef::try_from_slice(notosans::BOLD_TTF).expect("bundled font parses");
+    let regular = FontRef::try_from_slice(notosans::REGULAR_TTF).expect("bundled font parses");
+    let mut canvas = Canvas::new(BACKGROUND);
+    let content_width = WIDTH as f32 - 2.0 * MARGIN;
+
+    // Brand strip and site name
+    canvas.fill_rect(0, 0, WIDTH as i32, 12, ACCENT);
+    canvas.text(&bold, 34.0, MARGIN, 110.0, SITE_NAME, ACCENT);
+
+    let mut y = 215.0;
+    for line in wrap(&bold, 76.0, card.name, content_width, 2) {
+        canvas.text(&bold, 76.0, MARGIN, y, &line, TEXT);
+        y += 90.0;
+    }
+    y += 5.0;
+    for line in wrap(&regular, 34.0, card.short_description, content_width, 2) {
+        canvas.text(&regular, 34.0, MARGIN, y, &line, MUTED);
+        y += 46.0;
+    }
+
+    // Rating row
+    let baseline = 545.0;
+    let radius = 24.0;
+    for i in 0..5 {
+        let cx = MARGIN + radius + i as f32 * (2.0 * radius + 10.0);
+        canvas.star(cx, baseline - 14.0, radius, card.avg_rating as f32 - i as f32);
+    }
+    let rating = match card.review_count {
+        0 => "No reviews yet".to_string(),
+        1 => format!("{:.1} · 1 review", card.avg_rating),
+        n => format!("{:.1} · {} reviews", card.avg_rating, n),
+    };
+    canvas.text(&regular, 32.0, MARGIN + 5.0 * (2.0 * radius + 10.0) + 14.0, baseline, &rating, MUTED);
+
+    // Category chip, right-aligned
+    let category = card.category.replace('/', " / ");
+    let chip_text = ellipsize(&bold, 28.0, &category, 420.0, false);
+    let chip_width = text_width(&bold, 28.0, &chip_text) + 48.0;
+    let chip_x = WIDTH as f32 - MARGIN - chip_width;
+    canvas.fill_rect(chip_x as i32, baseline as i32 - 39, chip_width as i32, 58, CHIP);
+    canvas.text(&bold, 28.0, chip_x + 24.0, baseline, &chip_text, TEXT);
+
+    canvas.encode()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card<'a>(name: &'a str, avg_rating: f64) -> OgCard<'a> {
+        OgCard {
+            name,
+            short_description: "Forecasts for agents",
+            category: "data/weather",
+            avg_rating,
+            review_count: 3,
+        }
+    }
+
+    #[test]
+    fn renders_png_cards() {
+        let png = render(&card("Weather Feed", 4.5));
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let decoder = png::Decoder::new(png.as_slice());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (WIDTH, HEIGHT));
+    }
+
+    #[test]
+    fn wraps_and_ellipsizes_long_text() {
+        let font = FontRef::try_from_slice(notosans::BOLD_TTF).unwrap();
+        let long = "word ".repeat(60);
+        let lines = wrap(&font, 76.0, &long, 1040.0, 2);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].ends_with('…'));
+        assert!(lines.iter().all(|l| text_width(&font, 76.0, l) <= 1040.0));
+        assert_eq!(wrap(&font, 76.0, "Short", 1040.0, 2), vec!["Short".to_string()]);
+    }
+
+    #[test]
+    fn fingerprint_tracks_drawn_fields() {
+        assert_eq!(card("A", 4.0).fingerprint(), card("A", 4.0).fingerprint());
+        assert_ne!(card("A", 4.0).fingerprint(), card("B", 4.0).fingerprint());
+        assert_ne!(card("A", 4.0).fingerprint(), card("A", 4.5).fingerprint());
+    }
+}
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::{Orbit, Rocket};
+use serde_json::Value;
+
+use crate::errors::{ApiError, ErrorCode};
+use crate::events::{AppEvent, EventBus};
+
+/// Seconds between outbox relay passes when `OUTBOX_RELAY_SECS` is unset.
+pub const DEFAULT_OUTBOX_RELAY_SECS: u64 = 5;
+
+/// A claimed entry that hasn't been completed after this long is assumed
+/// lost (crashed process or panicked delivery) and published again.
+pub const LEASE_SECS: i64 = 60;
+
+/// Publish attempts after which an entry is left in the outbox for an
+/// operator to look at instead of being retried.
+pub const MAX_ATTEMPTS: i64 = 10;
+
+/// Entries published per relay pass.
+const RELAY_BATCH: i64 = 100;
+
+/// An event written to `event_outbox`, to be handed to
+/// [`EventBus::dispatch`] once its transaction has committed.
+#[derive(Debug)]
+pub struct Queued {
+    pub id: i64,
+    pub event: AppEvent,
+}
+
+/// Write `event` to the outbox on `conn`. Inside a transaction, the event
+/// is only ever published if the transaction commits.
+pub fn enqueue(conn: &rusqlite::Connection, event: AppEvent) -> rusqlite::Result<Queued> {
+    conn.execute(
+        "INSERT INTO event_outbox (event, data) VALUES (?1, ?2)",
+        rusqlite::params![event.event, event.data.to_string()],
+    )?;
+    Ok(Queued {
+        id: conn.last_insert_rowid(),
+        event,
+    })
+}
+
+/// Queue `events` in `tx` and commit them together with the data change
+/// the transaction already holds.
+pub fn commit(
+    tx: rusqlite::Transaction<'_>,
+    events: impl IntoIterator<Item = AppEvent>,
+) -> Result<Vec<Queued>, ApiError> {
+    events
+        .into_iter()
+        .map(|event| enqueue(&tx, event))
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .and_then(|queued| tx.commit().map(|_| queued))
+        .map_err(|e| {
+            eprintln!("❌ Failed to commit queued events: {e}");
+            ApiError::new(ErrorCode::DbError, "Internal server error")
+        })
+}
+
+/// Start the transaction a handler's change and its events share. It takes
+/// the write lock up front, so a concurrent writer (webhook delivery logs,
+/// the relay) makes it wait out the busy timeout instead of failing later.
+pub fn begin(conn: &rusqlite::Connection) -> Result<rusqlite::Transaction<'_>, ApiError> {
+    rusqlite::Transaction::new_unchecked(conn, rusqlite::TransactionBehavior::Immediate)
+        .map_err(|_| ApiError::new(ErrorCode::DbError, "Internal server error"))
+}
+
+/// Take an entry for publishing. Returns false when another publisher holds
+/// an unexpired claim, the entry is already done, or it ran out of attempts.
+pub fn claim(conn: &rusqlite::Connection, id: i64) -> bool {
+    conn.execute(
+        "UPDATE event_outbox SET claimed_at = datetime('now'), attempts = attempts + 1
+         WHERE id = ?1 AND attempts < ?2
+           AND (claimed_at IS NULL OR claimed_at < datetime('now', ?3))",
+        rusqlite::params![id, MAX_ATTEMPTS, format!("-{LEASE_SECS} seconds")],
+    )
+    .is_ok_and(|n| n == 1)
+}
+
+/// Remove an entry once it has been published and delivered.
+pub fn complete(conn: &rusqlite::Connection, id: i64) {
+    let _ = conn.execute("DELETE FROM event_outbox WHERE id = ?1", rusqlite::params![id]);
+}
+
+/// Unclaimed (or lapsed) entries with attempts left, oldest first.
+pub fn claimable(conn: &rusqlite::Connection, limit: i64) -> Vec<Queued> {
+    conn.prepare(
+        "SELECT id, event, data FROM event_outbox
+         WHERE attempts < ?1 AND (claimed_at IS NULL OR claimed_at < datetime('now', ?2))
+         ORDER BY id LIMIT ?3",
+    )
+    .and_then(|mut stmt| {
+        stmt.query_map(
+            rusqlite::params![MAX_ATTEMPTS, format!("-{LEASE_SECS} seconds"), limit],
+            |r| {
+                let data: String = r.get(2)?;
+                Ok(Queued {
+                    id: r.get(0)?,
+                    event: AppEvent {
+                        event: r.get(1)?,
+                        data: serde_json::from_str(&data).unwrap_or(Value::Null),
+                    },
+                })
+            },
+        )?
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Publishes outbox entries that weren't dispatched after their request
+/// committed (the process stopped in between, or a delivery task
+/// panicked), so every queued event reaches the bus and webhooks at least
+/// once. Runs at liftoff and then every `interval_secs`.
+pub struct OutboxRelay {
+    pub interval_secs: u64,
+}
+
+#[rocket::async_trait]
+impl Fairing for OutboxRelay {
+    fn info(&self) -> Info {
+        Info {
+            name: "Event Outbox Relay",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        if self.interval_secs == 0 {
+            rocket::info!("Event outbox relay disabled (OUTBOX_RELAY_SECS=0)");
+            return;
+        }
+        let bus = rocket
+            .state::<EventBus>()
+            .expect("EventBus not managed")
+            .clone();
+        let interval = Duration::from_secs(self.interval_secs);
+        let shutdown = rocket.shutdown();
+
+        tokio::spawn(async move {
+            loop {
+                let relayed = bus.relay_outbox(RELAY_BATCH);
+                if relayed > 0 {
+                    println!("📤 Relayed {relayed} queued events");
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = shutdown.clone() => break,
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn claims_each_entry_once_until_the_lease_lapses() {
+        let mut conn = crate::db::init_db(":memory:");
+
+        // A rolled back transaction leaves nothing to publish
+        let tx = conn.transaction().unwrap();
+        enqueue(&tx, AppEvent { event: "app.updated".into(), data: json!({ "app_id": "a" }) }).unwrap();
+        drop(tx);
+        assert!(claimable(&conn, 10).is_empty());
+
+        let tx = conn.transaction().unwrap();
+        let queued = commit(tx, [AppEvent { event: "app.deleted".into(), data: json!({ "app_id": "b" }) }]).unwrap().remove(0);
+        let pending = claimable(&conn, 10);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event.data["app_id"], "b");
+
+        assert!(claim(&conn, queued.id));
+        assert!(!claim(&conn, queued.id));
+        assert!(claimable(&conn, 10).is_empty());
+
+        conn.execute("UPDATE event_outbox SET claimed_at = datetime('now', '-2 minutes')", []).unwrap();
+        assert_eq!(claimable(&conn, 10).len(), 1);
+        assert!(claim(&conn, queued.id));
+
+        complete(&conn, queued.id);
+        conn.execute("UPDATE event_outbox SET claimed_at = NULL", []).unwrap();
+        assert!(claimable(&conn, 10).is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+use crate::auth::AuthenticatedKey;
+
+/// Apps per page when the request doesn't ask for a size and `DEFAULT_PAGE_SIZE` is unset.
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+
+/// Largest page when `MAX_PAGE_SIZE` is unset.
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Hard ceiling for page sizes, including per-key overrides.
+pub const PAGE_SIZE_CEILING: i64 = 1000;
+
+/// Page sizes for list and search. The instance-wide limits apply to
+/// anonymous callers and to keys without their own `default_page_size`
+/// or `max_page_size`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageLimits {
+    pub default_size: i64,
+    pub max_size: i64,
+}
+
+impl Default for PageLimits {
+    fn default() -> Self {
+        PageLimits {
+            default_size: DEFAULT_PAGE_SIZE,
+            max_size: MAX_PAGE_SIZE,
+        }
+    }
+}
+
+impl PageLimits {
+    /// `DEFAULT_PAGE_SIZE` (default 20) and `MAX_PAGE_SIZE` (default 100),
+    /// both between 1 and 1000.
+    pub fn from_env() -> Self {
+        let d = PageLimits::default();
+        let env = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<i64>().ok())
+                .map(|v| v.clamp(1, PAGE_SIZE_CEILING))
+        };
+        PageLimits {
+            default_size: env("DEFAULT_PAGE_SIZE").unwrap_or(d.default_size),
+            max_size: env("MAX_PAGE_SIZE").unwrap_or(d.max_size),
+        }
+        .normalized()
+    }
+
+    /// The default never exceeds the max.
+    fn normalized(self) -> Self {
+        PageLimits {
+            default_size: self.default_size.min(self.max_size),
+            ..self
+        }
+    }
+
+    /// The limits for `key`: its own sizes where set, the instance ones otherwise.
+    pub fn for_key(&self, conn: &Connection, key: Option<&AuthenticatedKey>) -> PageLimits {
+        let Some(key) = key else { return *self };
+        let custom: Option<(Option<i64>, Option<i64>)> = conn
+            .query_row(
+                "SELECT default_page_size, max_page_size FROM api_keys WHERE id = ?1",
+                rusqlite::params![key.id],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .ok();
+        match custom {
+            Some((default_size, max_size)) => PageLimits {
+                default_size: default_size.unwrap_or(self.default_size),
+                max_size: max_size.unwrap_or(self.max_size),
+            }
+            .normalized(),
+            None => *self,
+        }
+    }
+
+    /// Page size for a request: `requested`, or the default, clamped to the max.
+    pub fn per_page(&self, requested: Option<i64>) -> i64 {
+        requested.unwrap_or(self.default_size).clamp(1, self.max_size)
+    }
+
+    /// The effective limits, as reported in list and search responses.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "default_per_page": self.default_size,
+            "max_per_page": self.max_size,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamps_to_the_max() {
+        let limits = PageLimits { default_size: 50, max_size: 500 };
+        assert_eq!(limits.per_page(None), 50);
+        assert_eq!(limits.per_page(Some(0)), 1);
+        assert_eq!(limits.per_page(Some(900)), 500);
+        let odd = PageLimits { default_size: 200, max_size: 100 }.normalized();
+        assert_eq!(odd.per_page(None), 100);
+    }
+}
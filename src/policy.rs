@@ -0,0 +1,228 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::auth::Actor;
+use crate::errors::{ApiError, ErrorCode};
+use crate::models::{SubmitAppRequest, TranslationRequest, UpdateAppRequest};
+use crate::moderation::Rejection;
+
+/// HTTP timeout for the external policy webhook.
+const POLICY_TIMEOUT_SECS: u64 = 5;
+
+/// Stored listing fields sent to the policy service.
+const LISTING_FIELDS: &[&str] = &[
+    "name",
+    "short_description",
+    "description",
+    "homepage_url",
+    "api_url",
+    "api_spec_url",
+    "protocol",
+    "category",
+    "tags",
+    "logo_url",
+    "author_name",
+    "author_url",
+    "author_email",
+    "status",
+    "visibility",
+];
+
+/// Operator-run policy engine consulted before a listing is written.
+///
+/// With `POLICY_WEBHOOK_URL` set, submissions and edits POST
+/// `{"action", "app_id", "listing", "changed", "actor"}` there, where `listing`
+/// is the listing as it would be stored. A 2xx answer allows the write unless
+/// it is `{"allow": false, "reason": "...", "field"?: "..."}`; any other
+/// status blocks it. Unlike the moderation webhook this fails closed: an
+/// unreachable service blocks writes unless `POLICY_WEBHOOK_FAIL_OPEN=true`.
+#[derive(Default)]
+pub struct PolicyHook {
+    url: Option<String>,
+    fail_open: bool,
+    client: reqwest::Client,
+}
+
+impl PolicyHook {
+    pub fn new(url: Option<String>, fail_open: bool) -> Self {
+        PolicyHook {
+            url,
+            fail_open,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(POLICY_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// `POLICY_WEBHOOK_URL` and `POLICY_WEBHOOK_FAIL_OPEN` (default false).
+    pub fn from_env() -> Self {
+        let url = std::env::var("POLICY_WEBHOOK_URL").ok().filter(|u| !u.is_empty());
+        let fail_open = std::env::var("POLICY_WEBHOOK_FAIL_OPEN")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+        PolicyHook::new(url, fail_open)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.url.is_some()
+    }
+
+    /// Ask the policy service about a write. `action` is `"submit"` or
+    /// `"update"`; `changed` names the fields an update sets.
+    pub async fn check(
+        &self,
+        action: &str,
+        app_id: Option<&str>,
+        listing: Value,
+        changed: &[&str],
+        actor: &Actor,
+    ) -> Result<(), Rejection> {
+        let Some(ref url) = self.url else { return Ok(()) };
+        let payload = json!({
+            "action": action,
+            "app_id": app_id,
+            "listing": listing,
+            "changed": changed,
+            "actor": {
+                "key_id": actor.key_id(),
+                "account_id": actor.account_id(),
+                "is_admin": actor.is_admin(),
+            },
+        });
+
+        let reject = |field: Option<&str>, reason: String| Rejection {
+            field: field.unwrap_or("*").to_string(),
+            rule: "policy".to_string(),
+            reason,
+        };
+        let verdict: Value = match self.client.post(url).json(&payload).send().await {
+            Ok(resp) if resp.status().is_success() => resp.json().await.unwrap_or(Value::Null),
+            Ok(resp) => return Err(reject(None, format!("Policy service answered {}", resp.status()))),
+            Err(e) if self.fail_open => {
+                eprintln!("⚠️  Policy webhook failed: {} — allowing", e);
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("⚠️  Policy webhook failed: {} — blocking", e);
+                return Err(reject(None, "Policy service unavailable".to_string()));
+            }
+        };
+
+        if verdict.get("allow").and_then(Value::as_bool) == Some(false) {
+            return Err(reject(
+                verdict.get("field").and_then(Value::as_str),
+                verdict
+                    .get("reason")
+                    .and_then(Value::as_str)
+                    .unwrap_or("Rejected by directory policy")
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// `422 POLICY_REJECTED` for a blocked write.
+pub fn to_error(rejection: &Rejection) -> ApiError {
+    ApiError::new(ErrorCode::PolicyRejected, rejection.reason.clone()).with("field", rejection.field.clone())
+}
+
+/// A submission as it would be stored.
+pub fn submitted_listing(body: &SubmitAppRequest) -> Value {
+    json!({
+        "name": body.name,
+        "short_description": body.short_description,
+        "description": body.description,
+        "homepage_url": body.homepage_url,
+        "api_url": body.api_url,
+        "api_spec_url": body.api_spec_url,
+        "protocol": body.protocol.as_deref().unwrap_or("rest"),
+        "category": body.category.as_deref().unwrap_or("other"),
+        "tags": body.tags.clone().unwrap_or_default(),
+        "logo_url": body.logo_url,
+        "author_name": body.author_name,
+        "author_url": body.author_url,
+        "author_email": body.author_email,
+        "status": body.status,
+        "visibility": body.visibility.as_deref().unwrap_or("public"),
+    })
+}
+
+fn stored_listing(conn: &rusqlite::Connection, app_id: &str) -> rusqlite::Result<serde_json::Map<String, Value>> {
+    conn.query_row(
+        &format!("SELECT {} FROM apps WHERE id = ?1", LISTING_FIELDS.join(", ")),
+        rusqlite::params![app_id],
+        |r| {
+            let mut listing = serde_json::Map::new();
+            for (i, field) in LISTING_FIELDS.iter().enumerate() {
+                let value = match *field {
+                    "tags" => serde_json::from_str(&r.get::<_, String>(i)?).unwrap_or(json!([])),
+                    _ => json!(r.get::<_, Option<String>>(i)?),
+                };
+                listing.insert(field.to_string(), value);
+            }
+            Ok(listing)
+        },
+    )
+}
+
+/// The stored listing of `app_id` with an update's fields applied, and the
+/// names of the listing fields it changes.
+pub fn updated_listing(
+    conn: &rusqlite::Connection,
+    app_id: &str,
+    body: &UpdateAppRequest,
+) -> rusqlite::Result<(Value, Vec<&'static str>)> {
+    let mut listing = stored_listing(conn, app_id)?;
+    let updates = [
+        ("name", body.name.as_ref().map(|v| json!(v))),
+        ("short_description", body.short_description.as_ref().map(|v| json!(v))),
+        ("description", body.description.as_ref().map(|v| json!(v))),
+        ("homepage_url", body.homepage_url.as_ref().map(|v| json!(v))),
+        ("api_url", body.api_url.as_ref().map(|v| json!(v))),
+        ("api_spec_url", body.api_spec_url.as_ref().map(|v| json!(v))),
+        ("protocol", body.protocol.as_ref().map(|v| json!(v))),
+        ("category", body.category.as_ref().map(|v| json!(v))),
+        ("tags", body.tags.as_ref().map(|v| json!(v))),
+        ("logo_url", body.logo_url.as_ref().map(|v| json!(v))),
+        ("author_name", body.author_name.as_ref().map(|v| json!(v))),
+        ("author_url", body.author_url.as_ref().map(|v| json!(v))),
+        ("author_email", body.author_email.as_ref().map(|v| json!(v))),
+        ("status", body.status.as_ref().map(|v| json!(v))),
+        ("visibility", body.visibility.as_ref().map(|v| json!(v))),
+    ];
+    let mut changed = Vec::new();
+    for (field, value) in updates {
+        if let Some(value) = value {
+            listing.insert(field.to_string(), value);
+            changed.push(field);
+        }
+    }
+    Ok((Value::Object(listing), changed))
+}
+
+/// The stored listing of `app_id` as readers of `lang` would see it once a
+/// translation is applied (with `lang` added), and the fields it sets.
+pub fn translated_listing(
+    conn: &rusqlite::Connection,
+    app_id: &str,
+    lang: &str,
+    body: &TranslationRequest,
+) -> rusqlite::Result<(Value, Vec<&'static str>)> {
+    let mut listing = stored_listing(conn, app_id)?;
+    let mut changed = Vec::new();
+    for (field, value) in [
+        ("name", &body.name),
+        ("short_description", &body.short_description),
+        ("description", &body.description),
+    ] {
+        if let Some(value) = value {
+            listing.insert(field.to_string(), json!(value));
+            changed.push(field);
+        }
+    }
+    listing.insert("lang".to_string(), json!(lang));
+    Ok((Value::Object(listing), changed))
+}
@@ -0,0 +1,137 @@
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::{Data, Request, Response};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::thread::ThreadId;
+use std::time::Duration;
+
+/// Queries at least this slow are logged unless `SLOW_QUERY_MS` says otherwise.
+pub const DEFAULT_SLOW_QUERY_MS: u64 = 100;
+
+/// Longest SQL text printed in a slow query log line.
+const MAX_LOGGED_SQL: usize = 500;
+
+/// Slow query threshold in milliseconds; 0 turns the log off. Global
+/// because SQLite's profile hook is a plain function.
+static SLOW_QUERY_MS: AtomicU64 = AtomicU64::new(DEFAULT_SLOW_QUERY_MS);
+
+/// Query totals for the requests in flight, keyed by who is serving them.
+static IN_FLIGHT: LazyLock<Mutex<HashMap<Caller, QueryStats>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Queries run and time spent in SQLite while serving one request.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QueryStats {
+    pub queries: u64,
+    pub elapsed: Duration,
+}
+
+/// What a request runs on: its Tokio task when served over HTTP, or the
+/// thread when dispatched outside one (the local test client). The
+/// connection mutex is never held across an `.await`, so every query a
+/// handler runs happens on its own task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Caller {
+    Task(tokio::task::Id),
+    Thread(ThreadId),
+}
+
+impl Caller {
+    fn current() -> Self {
+        match tokio::task::try_id() {
+            Some(id) => Caller::Task(id),
+            None => Caller::Thread(std::thread::current().id()),
+        }
+    }
+}
+
+fn in_flight() -> std::sync::MutexGuard<'static, HashMap<Caller, QueryStats>> {
+    IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Set the slow query threshold in milliseconds (0 disables the log).
+pub fn set_slow_query_ms(ms: u64) {
+    SLOW_QUERY_MS.store(ms, Ordering::Relaxed);
+}
+
+/// Time every statement run on `conn`.
+pub fn install(conn: &mut Connection) {
+    conn.profile(Some(on_query));
+}
+
+/// SQL on a single line, cut to `MAX_LOGGED_SQL` characters.
+fn compact_sql(sql: &str) -> String {
+    let sql = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    match sql.char_indices().nth(MAX_LOGGED_SQL) {
+        Some((end, _)) => format!("{}…", &sql[..end]),
+        None => sql,
+    }
+}
+
+fn on_query(sql: &str, elapsed: Duration) {
+    if let Some(stats) = in_flight().get_mut(&Caller::current()) {
+        stats.queries += 1;
+        stats.elapsed += elapsed;
+    }
+    let threshold = SLOW_QUERY_MS.load(Ordering::Relaxed);
+    if threshold > 0 && elapsed >= Duration::from_millis(threshold) {
+        eprintln!("🐢 Slow query ({:.1}ms): {}", elapsed.as_secs_f64() * 1000.0, compact_sql(sql));
+    }
+}
+
+/// Counts the queries each request runs. With `headers` set, responses
+/// carry `X-DB-Queries` and `X-DB-Time` (milliseconds).
+pub struct QueryInstrumentation {
+    pub headers: bool,
+}
+
+#[rocket::async_trait]
+impl Fairing for QueryInstrumentation {
+    fn info(&self) -> Info {
+        Info {
+            name: "SQL query instrumentation",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, _request: &mut Request<'_>, _data: &mut Data<'_>) {
+        in_flight().insert(Caller::current(), QueryStats::default());
+    }
+
+    async fn on_response<'r>(&self, _request: &'r Request<'_>, response: &mut Response<'r>) {
+        let stats = in_flight().remove(&Caller::current()).unwrap_or_default();
+        if self.headers {
+            response.set_header(Header::new("X-DB-Queries", stats.queries.to_string()));
+            response.set_header(Header::new(
+                "X-DB-Time",
+                format!("{:.3}", stats.elapsed.as_secs_f64() * 1000.0),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_queries_for_registered_callers() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        install(&mut conn);
+        conn.execute_batch("CREATE TABLE t (x INTEGER)").unwrap();
+
+        // Nothing is recorded before a request registers
+        conn.execute("INSERT INTO t VALUES (1)", []).unwrap();
+        assert!(!in_flight().contains_key(&Caller::current()));
+
+        in_flight().insert(Caller::current(), QueryStats::default());
+        conn.execute("INSERT INTO t VALUES (2)", []).unwrap();
+        conn.query_row("SELECT COUNT(*) FROM t", [], |r| r.get::<_, i64>(0)).unwrap();
+        let stats = in_flight().remove(&Caller::current()).unwrap();
+        assert_eq!(stats.queries, 2);
+
+        assert_eq!(compact_sql("SELECT *\n   FROM t"), "SELECT * FROM t");
+    }
+}
@@ -1,20 +1,42 @@
 use std::collections::HashMap;
-use std::sync::Mutex;
-use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use rocket::fairing::{Fairing, Info, Kind};
 use rocket::http::Header;
-use rocket::{Request, Response};
+use rocket::{Orbit, Request, Response, Rocket};
+use rusqlite::Connection;
 
-/// Fixed-window rate limiter.
+/// Fixed-window rate limiter with optional burst allowances.
 ///
 /// Each API key gets a counter that resets every `window` duration.
 /// The per-key limit is stored in the database (`api_keys.rate_limit`),
-/// so callers pass it in when checking.
+/// so callers pass it in when checking. Keys with a `burst` allowance
+/// (`api_keys.rate_burst`) may go over the limit by drawing on a token
+/// bucket that holds up to `burst` tokens and refills at `burst` per
+/// window, so short spikes pass but a key can't sustain more than
+/// `limit + burst` requests per window.
+///
+/// Requests may cost more than one: a route matching one of the limiter's
+/// route costs uses up that many requests of the window (see `cost_of`).
+///
+/// Cheaply cloneable via internal `Arc`, so the persistence task can share it.
+#[derive(Clone)]
 pub struct RateLimiter {
-    window: Duration,
+    /// Window length in milliseconds; shared so a config reload reaches every clone
+    window_ms: Arc<AtomicU64>,
     /// key_id → (window_start, count)
-    buckets: Mutex<HashMap<String, (Instant, u64)>>,
+    buckets: Arc<Mutex<HashMap<String, (Instant, u64)>>>,
+    /// key_id → (last_refill, tokens) for keys with a burst allowance.
+    /// Not persisted: burst credit starts full after a restart.
+    bursts: Arc<Mutex<HashMap<String, (Instant, f64)>>>,
+    /// Set when counters change; cleared by `save`
+    dirty: Arc<AtomicBool>,
+    /// Usage (percent of the limit) at which `RateLimitResult::warning` fires; 0 disables
+    warning_pct: Arc<AtomicU64>,
+    /// (route pattern, cost) pairs, first match wins; shared like `window_ms`
+    route_costs: Arc<RwLock<Vec<(String, u64)>>>,
 }
 
 /// Result of a rate limit check.
@@ -29,6 +51,163 @@ pub struct RateLimitResult {
     pub remaining: u64,
     /// Seconds until the current window resets.
     pub reset_secs: u64,
+    /// Set on the one request per window that brings usage up to the warning threshold.
+    pub warning: bool,
+    /// Length of the fixed window.
+    pub window_secs: u64,
+    /// Burst allowance above `limit`; 0 when the key has none.
+    pub burst: u64,
+    /// Whole burst tokens left.
+    pub burst_remaining: u64,
+    /// Requests this one counted as; 0 when it wasn't counted.
+    pub cost: u64,
+}
+
+impl RateLimitResult {
+    /// `X-RateLimit-Policy` value: the limit and window in seconds, plus
+    /// the burst allowance when there is one (e.g. `100;w=60;burst=20`).
+    pub fn policy(&self) -> String {
+        let mut policy = format!("{};w={}", self.limit, self.window_secs);
+        if self.burst > 0 {
+            policy.push_str(&format!(";burst={}", self.burst));
+        }
+        policy
+    }
+}
+
+/// Most exempt route patterns a key can carry.
+pub const MAX_EXEMPT_ROUTES: usize = 20;
+/// Largest burst allowance a key can be given.
+pub const MAX_BURST: i64 = 100_000;
+
+const METHODS: [&str; 6] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD"];
+
+/// Normalize a route pattern for rate-limit exemptions and route costs,
+/// such as `GET /api/v1/apps/*` or `/api/v1/apps/**`: an optional HTTP
+/// method, then an absolute path where `*` stands for one segment and a
+/// final `**` for any remainder.
+pub fn parse_route_pattern(pattern: &str) -> Result<String, String> {
+    let pattern = pattern.trim();
+    let (method, path) = match pattern.split_once(char::is_whitespace) {
+        Some((m, p)) => (Some(m.to_ascii_uppercase()), p.trim()),
+        None => (None, pattern),
+    };
+    if let Some(m) = &method {
+        if !METHODS.contains(&m.as_str()) {
+            return Err(format!("Unknown method '{m}' in route pattern '{pattern}'"));
+        }
+    }
+    if !path.starts_with('/') || path.contains(char::is_whitespace) {
+        return Err(format!("Route pattern '{pattern}' must be an absolute path"));
+    }
+    let segments: Vec<&str> = path.split('/').skip(1).collect();
+    if segments.iter().rev().skip(1).any(|s| *s == "**") {
+        return Err(format!("'**' may only end route pattern '{pattern}'"));
+    }
+    Ok(match method {
+        Some(m) => format!("{m} {path}"),
+        None => path.to_string(),
+    })
+}
+
+/// Whether a request matches a pattern from `parse_route_pattern`.
+pub fn route_matches(pattern: &str, method: &str, path: &str) -> bool {
+    let request: Vec<&str> = path.split('/').skip(1).collect();
+    let (pat_method, pat_path) = match pattern.split_once(' ') {
+        Some((m, p)) => (Some(m), p),
+        None => (None, pattern),
+    };
+    if pat_method.is_some_and(|m| !m.eq_ignore_ascii_case(method)) {
+        return false;
+    }
+    let segments: Vec<&str> = pat_path.split('/').skip(1).collect();
+    match segments.split_last() {
+        Some((&"**", prefix)) => {
+            request.len() >= prefix.len() && prefix.iter().zip(&request).all(|(p, r)| *p == "*" || p == r)
+        }
+        _ => segments.len() == request.len() && segments.iter().zip(&request).all(|(p, r)| *p == "*" || p == r),
+    }
+}
+
+/// Whether a request matches any of a key's exempt route patterns.
+pub fn is_exempt(patterns: &[String], method: &str, path: &str) -> bool {
+    patterns.iter().any(|pattern| route_matches(pattern, method, path))
+}
+
+/// Highest cost a route can be given.
+pub const MAX_ROUTE_COST: u64 = 1000;
+
+/// Route costs when `RATE_LIMIT_ROUTE_COSTS` is unset: endpoints that do far
+/// more work per request than a lookup.
+pub const DEFAULT_ROUTE_COSTS: &[(&str, u64)] = &[
+    ("GET /api/v1/apps/search", 5),
+    ("GET /api/v1/apps/*/export", 10),
+    ("POST /api/v1/apps/health-check/batch", 20),
+];
+
+/// Parse `RATE_LIMIT_ROUTE_COSTS`-style costs: comma-separated
+/// `<route pattern>=<cost>` entries with costs from 1 to `MAX_ROUTE_COST`.
+/// Invalid entries are returned as errors next to the valid ones.
+pub fn parse_route_costs(spec: &str) -> (Vec<(String, u64)>, Vec<String>) {
+    let mut costs = Vec::new();
+    let mut errors = Vec::new();
+    for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let parsed = entry
+            .rsplit_once('=')
+            .ok_or_else(|| format!("Route cost '{entry}' must look like '<route>=<cost>'"))
+            .and_then(|(route, cost)| {
+                let cost = cost
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|c| (1..=MAX_ROUTE_COST).contains(c))
+                    .ok_or_else(|| format!("Cost in '{entry}' must be a number from 1 to {MAX_ROUTE_COST}"))?;
+                Ok((parse_route_pattern(route)?, cost))
+            });
+        match parsed {
+            Ok(cost) => costs.push(cost),
+            Err(e) => errors.push(e),
+        }
+    }
+    (costs, errors)
+}
+
+/// `RATE_LIMIT_ROUTE_COSTS`, or `DEFAULT_ROUTE_COSTS` when unset. An empty
+/// value makes every request cost 1; invalid entries are skipped with a
+/// warning.
+pub fn route_costs_from_env() -> Vec<(String, u64)> {
+    let Ok(spec) = std::env::var("RATE_LIMIT_ROUTE_COSTS") else {
+        return DEFAULT_ROUTE_COSTS.iter().map(|(route, cost)| (route.to_string(), *cost)).collect();
+    };
+    let (costs, errors) = parse_route_costs(&spec);
+    for error in errors {
+        eprintln!("⚠️  RATE_LIMIT_ROUTE_COSTS: {error} — skipped");
+    }
+    costs
+}
+
+/// Route costs in `RATE_LIMIT_ROUTE_COSTS` form.
+pub fn format_route_costs(costs: &[(String, u64)]) -> String {
+    costs.iter().map(|(route, cost)| format!("{route}={cost}")).collect::<Vec<_>>().join(",")
+}
+
+/// Path of the endpoint that reports a key's rate limit. The auth guard
+/// only looks at the key's window for it, without counting the request.
+pub const INFO_PATH: &str = "/api/v1/rate-limit";
+
+/// The rate limit state the auth guard stored for this request, if it
+/// checked one.
+pub struct CurrentRateLimit(pub Option<RateLimitResult>);
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for CurrentRateLimit {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> rocket::request::Outcome<Self, Self::Error> {
+        rocket::request::Outcome::Success(CurrentRateLimit(
+            request.local_cache(|| Option::<RateLimitResult>::None).clone(),
+        ))
+    }
 }
 
 /// Rocket fairing that attaches rate limit headers to every response.
@@ -52,6 +231,11 @@ impl Fairing for RateLimitHeaders {
                 rl.remaining.to_string(),
             ));
             response.set_header(Header::new("X-RateLimit-Reset", rl.reset_secs.to_string()));
+            response.set_header(Header::new("X-RateLimit-Policy", rl.policy()));
+            response.set_header(Header::new("X-RateLimit-Cost", rl.cost.to_string()));
+            if rl.burst > 0 {
+                response.set_header(Header::new("X-RateLimit-Burst-Remaining", rl.burst_remaining.to_string()));
+            }
         }
     }
 }
@@ -60,17 +244,84 @@ impl RateLimiter {
     /// Create a new rate limiter with the given window duration.
     pub fn new(window: Duration) -> Self {
         RateLimiter {
-            window,
-            buckets: Mutex::new(HashMap::new()),
+            window_ms: Arc::new(AtomicU64::new(window.as_millis() as u64)),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            bursts: Arc::new(Mutex::new(HashMap::new())),
+            dirty: Arc::new(AtomicBool::new(false)),
+            warning_pct: Arc::new(AtomicU64::new(80)),
+            route_costs: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
+    /// Charge requests matching a route pattern more than one request each.
+    pub fn with_route_costs(self, costs: Vec<(String, u64)>) -> Self {
+        self.set_route_costs(costs);
+        self
+    }
+
+    pub fn route_costs(&self) -> Vec<(String, u64)> {
+        self.route_costs.read().unwrap().clone()
+    }
+
+    /// Replace the route costs; applies from the next request.
+    pub fn set_route_costs(&self, costs: Vec<(String, u64)>) {
+        *self.route_costs.write().unwrap() = costs;
+    }
+
+    /// What a request counts as: the cost of the first matching route, or 1.
+    pub fn cost_of(&self, method: &str, path: &str) -> u64 {
+        self.route_costs
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(pattern, _)| route_matches(pattern, method, path))
+            .map_or(1, |(_, cost)| *cost)
+    }
+
+    /// Warn once per window when usage reaches `pct` percent of the limit (0 disables).
+    pub fn with_warning_pct(self, pct: u64) -> Self {
+        self.warning_pct.store(pct.min(100), Ordering::Relaxed);
+        self
+    }
+
+    pub fn window(&self) -> Duration {
+        Duration::from_millis(self.window_ms.load(Ordering::Relaxed))
+    }
+
+    pub fn warning_pct(&self) -> u64 {
+        self.warning_pct.load(Ordering::Relaxed)
+    }
+
+    /// Change the window and warning threshold in place. Current windows
+    /// keep their start time and count; a shorter window may end them early.
+    pub fn reconfigure(&self, window: Duration, warning_pct: u64) {
+        self.window_ms.store(window.as_millis() as u64, Ordering::Relaxed);
+        self.warning_pct.store(warning_pct.min(100), Ordering::Relaxed);
+    }
+
     /// Check (and consume) one request for `key_id` with the given `limit`.
     ///
     /// Returns a `RateLimitResult` indicating whether the request is allowed
     /// and the current rate limit state for response headers.
     pub fn check(&self, key_id: &str, limit: u64) -> RateLimitResult {
+        self.check_with_burst(key_id, limit, 0)
+    }
+
+    /// Like `check`, but once the window's `limit` is used up the request
+    /// may spend one of the key's `burst` tokens instead of being refused.
+    pub fn check_with_burst(&self, key_id: &str, limit: u64, burst: u64) -> RateLimitResult {
+        self.check_with_cost(key_id, limit, burst, 1)
+    }
+
+    /// Like `check_with_burst` for a request counting as `cost` requests.
+    /// What the window can't cover comes from burst tokens; without enough
+    /// of either the request is refused and nothing is used up. The cost is
+    /// capped at `limit`, so every route stays reachable.
+    pub fn check_with_cost(&self, key_id: &str, limit: u64, burst: u64, cost: u64) -> RateLimitResult {
+        let cost = cost.clamp(1, limit.max(1));
         let now = Instant::now();
+        let window = self.window();
+        let warning_pct = self.warning_pct();
         let mut buckets = self.buckets.lock().unwrap();
 
         let entry = buckets
@@ -78,31 +329,355 @@ impl RateLimiter {
             .or_insert_with(|| (now, 0));
 
         // If the window has elapsed, reset.
-        if now.duration_since(entry.0) >= self.window {
+        if now.duration_since(entry.0) >= window {
             *entry = (now, 0);
         }
 
-        let reset_secs = self
-            .window
+        let reset_secs = window
             .checked_sub(now.duration_since(entry.0))
             .unwrap_or(Duration::ZERO)
             .as_secs();
+        let mut result = RateLimitResult {
+            allowed: true,
+            limit,
+            remaining: 0,
+            reset_secs,
+            warning: false,
+            window_secs: window.as_secs(),
+            burst,
+            burst_remaining: 0,
+            cost,
+        };
 
-        if entry.1 >= limit {
-            RateLimitResult {
-                allowed: false,
-                limit,
-                remaining: 0,
-                reset_secs,
-            }
+        // Refill the burst bucket for the time since it was last touched
+        let mut bursts = self.bursts.lock().unwrap();
+        let tokens = (burst > 0).then(|| {
+            let bucket = bursts.entry(key_id.to_string()).or_insert((now, burst as f64));
+            let refill = now.duration_since(bucket.0).as_secs_f64() / window.as_secs_f64().max(1.0);
+            *bucket = (now, (bucket.1 + refill * burst as f64).min(burst as f64));
+            &mut bucket.1
+        });
+
+        let used = entry.1;
+        let shortfall = (used + cost).saturating_sub(limit);
+        if shortfall == 0 {
+            entry.1 += cost;
+            self.dirty.store(true, Ordering::Relaxed);
+            // Ceiling so e.g. 80% of 5 warns at the 4th request, not the 5th
+            let threshold = (limit * warning_pct).div_ceil(100);
+            result.remaining = limit.saturating_sub(entry.1);
+            result.warning = warning_pct > 0 && used < threshold && entry.1 >= threshold;
+            result.burst_remaining = tokens.map_or(0, |t| *t as u64);
         } else {
-            entry.1 += 1;
-            RateLimitResult {
-                allowed: true,
-                limit,
-                remaining: limit.saturating_sub(entry.1),
-                reset_secs,
+            match tokens {
+                Some(tokens) if *tokens >= shortfall as f64 => {
+                    *tokens -= shortfall as f64;
+                    result.burst_remaining = *tokens as u64;
+                    if entry.1 < limit {
+                        entry.1 = limit;
+                        self.dirty.store(true, Ordering::Relaxed);
+                    }
+                }
+                _ => {
+                    result.allowed = false;
+                    result.remaining = limit.saturating_sub(used);
+                }
+            }
+        }
+        result
+    }
+
+    /// The state `check_with_burst` would report for `key_id`, without
+    /// counting a request or spending a burst token.
+    pub fn peek(&self, key_id: &str, limit: u64, burst: u64) -> RateLimitResult {
+        let now = Instant::now();
+        let window = self.window();
+        let (elapsed, used) = match self.buckets.lock().unwrap().get(key_id) {
+            Some((start, count)) if now.duration_since(*start) < window => (now.duration_since(*start), *count),
+            _ => (Duration::ZERO, 0),
+        };
+        let tokens = match self.bursts.lock().unwrap().get(key_id) {
+            Some((refilled, tokens)) => {
+                let refill = now.duration_since(*refilled).as_secs_f64() / window.as_secs_f64().max(1.0);
+                (tokens + refill * burst as f64).min(burst as f64)
+            }
+            None => burst as f64,
+        };
+        let remaining = limit.saturating_sub(used);
+        RateLimitResult {
+            allowed: remaining > 0 || tokens >= 1.0,
+            limit,
+            remaining,
+            reset_secs: window.saturating_sub(elapsed).as_secs(),
+            warning: false,
+            window_secs: window.as_secs(),
+            burst,
+            burst_remaining: tokens as u64,
+            cost: 0,
+        }
+    }
+
+    /// Forget `key_id`'s current window and burst tokens, so its next
+    /// request starts fresh under whatever limit it now has.
+    pub fn reset(&self, key_id: &str) {
+        self.buckets.lock().unwrap().remove(key_id);
+        self.bursts.lock().unwrap().remove(key_id);
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Drop windows that have ended, so nothing outlives its window.
+    pub fn prune(&self) {
+        let now = Instant::now();
+        let window = self.window();
+        self.buckets.lock().unwrap().retain(|_, (start, _)| now.duration_since(*start) < window);
+    }
+
+    /// Write all live windows to `rate_limits` under `scope`, replacing the
+    /// previous snapshot. Skips the write if nothing changed since the last save.
+    pub fn save(&self, conn: &Connection, scope: &str) -> rusqlite::Result<()> {
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let now_unix = unix_now();
+        let live: Vec<(String, i64, i64)> = {
+            let mut buckets = self.buckets.lock().unwrap();
+            let window = self.window();
+            buckets.retain(|_, (start, _)| now.duration_since(*start) < window);
+            buckets
+                .iter()
+                .map(|(k, (start, count))| {
+                    let started = now_unix - now.duration_since(*start).as_secs() as i64;
+                    (k.clone(), started, *count as i64)
+                })
+                .collect()
+        };
+
+        let result = (|| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM rate_limits WHERE scope = ?1", rusqlite::params![scope])?;
+            for (key, started, count) in &live {
+                tx.execute(
+                    "INSERT INTO rate_limits (scope, bucket_key, window_start, count) VALUES (?1, ?2, ?3, ?4)",
+                    rusqlite::params![scope, key, started, count],
+                )?;
+            }
+            tx.commit()
+        })();
+
+        if result.is_err() {
+            // Retry on the next flush
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+
+    /// Restore windows saved under `scope` that haven't expired yet.
+    /// Returns the number of buckets restored.
+    pub fn load(&self, conn: &Connection, scope: &str) -> rusqlite::Result<usize> {
+        let mut stmt = conn.prepare(
+            "SELECT bucket_key, window_start, count FROM rate_limits WHERE scope = ?1",
+        )?;
+        let rows: Vec<(String, i64, i64)> = stmt
+            .query_map(rusqlite::params![scope], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let now = Instant::now();
+        let now_unix = unix_now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let mut restored = 0;
+        for (key, started, count) in rows {
+            let age = Duration::from_secs(now_unix.saturating_sub(started).max(0) as u64);
+            if age >= self.window() {
+                continue;
+            }
+            if let Some(start) = now.checked_sub(age) {
+                buckets.insert(key, (start, count.max(0) as u64));
+                restored += 1;
+            }
+        }
+        Ok(restored)
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// `rate_limits` scope for per-API-key request windows.
+pub const SCOPE_API_KEYS: &str = "api_key";
+
+/// `rate_limits` scope for anonymous review caps.
+pub const SCOPE_REVIEWS: &str = "anon_review";
+
+/// Per-IP daily cap on anonymous review submissions.
+///
+/// A separate fixed-window limiter (24h window) keyed by hashed client IP,
+/// so review bombing from one address is bounded even without an API key.
+#[derive(Clone)]
+pub struct ReviewThrottle {
+    pub limiter: RateLimiter,
+    /// Maximum anonymous reviews per IP per day.
+    daily_cap: Arc<AtomicU64>,
+    /// Privacy mode: IP hashes are forgotten as soon as their window ends.
+    private: bool,
+}
+
+impl ReviewThrottle {
+    pub fn new(daily_cap: u64) -> Self {
+        ReviewThrottle {
+            limiter: RateLimiter::new(Duration::from_secs(24 * 60 * 60)),
+            daily_cap: Arc::new(AtomicU64::new(daily_cap)),
+            private: false,
+        }
+    }
+
+    /// With `private`, ended windows are dropped on every check instead of
+    /// lingering until the same IP comes back.
+    pub fn with_privacy(self, private: bool) -> Self {
+        ReviewThrottle { private, ..self }
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
+    pub fn daily_cap(&self) -> u64 {
+        self.daily_cap.load(Ordering::Relaxed)
+    }
+
+    pub fn set_daily_cap(&self, daily_cap: u64) {
+        self.daily_cap.store(daily_cap, Ordering::Relaxed);
+    }
+
+    /// Check (and consume) one anonymous review for the given IP hash.
+    pub fn check(&self, ip_hash: &str) -> RateLimitResult {
+        if self.private {
+            self.limiter.prune();
+        }
+        self.limiter.check(ip_hash, self.daily_cap())
+    }
+}
+
+/// Default for `LOGIN_IP_HOURLY_CAP`.
+pub const DEFAULT_LOGIN_IP_HOURLY_CAP: u64 = 20;
+
+/// Per-IP hourly caps on `POST /auth/magic-link` and `POST /auth/verify`.
+///
+/// One in-memory limiter keyed by endpoint and hashed client IP, so a single
+/// address can't spray login emails or spread code guesses across many
+/// inboxes. Windows aren't persisted; a restart starts them over.
+#[derive(Clone)]
+pub struct LoginThrottle {
+    limiter: RateLimiter,
+    /// Maximum requests per IP per hour, for each endpoint.
+    hourly_cap: u64,
+    /// Privacy mode: IP hashes are forgotten as soon as their window ends.
+    private: bool,
+}
+
+impl LoginThrottle {
+    pub fn new(hourly_cap: u64) -> Self {
+        LoginThrottle {
+            limiter: RateLimiter::new(Duration::from_secs(60 * 60)),
+            hourly_cap,
+            private: false,
+        }
+    }
+
+    /// Same as `ReviewThrottle::with_privacy`.
+    pub fn with_privacy(self, private: bool) -> Self {
+        LoginThrottle { private, ..self }
+    }
+
+    /// Check (and consume) one request to `endpoint` for the given IP hash.
+    pub fn check(&self, endpoint: &str, ip_hash: &str) -> RateLimitResult {
+        if self.private {
+            self.limiter.prune();
+        }
+        self.limiter.check(&format!("{endpoint}:{ip_hash}"), self.hourly_cap)
+    }
+}
+
+/// Rocket fairing that periodically saves rate limit windows to the database
+/// (every `RATE_LIMIT_FLUSH_SECS`) and once more on shutdown, so quotas survive
+/// restarts. Counters are restored at startup in `rocket_with_path`.
+/// Uses its own DB connection, like the scheduler and spec verifier.
+/// In privacy mode the per-IP review windows stay in memory.
+pub struct RateLimitPersistence {
+    pub db_path: String,
+    pub flush_secs: u64,
+}
+
+impl RateLimitPersistence {
+    fn flush(db_path: &str, keys: &RateLimiter, reviews: Option<&RateLimiter>) {
+        let conn = match Connection::open(db_path) {
+            Ok(c) => c,
+            Err(e) => {
+                rocket::error!("Rate limit flush: failed to open DB: {}", e);
+                return;
+            }
+        };
+        let scopes = [Some((keys, SCOPE_API_KEYS)), reviews.map(|r| (r, SCOPE_REVIEWS))];
+        for (limiter, scope) in scopes.into_iter().flatten() {
+            if let Err(e) = limiter.save(&conn, scope) {
+                rocket::error!("Rate limit flush ({}) failed: {}", scope, e);
+            }
+        }
+    }
+
+    /// The key limiter, and the review limiter unless it's private.
+    fn limiters(rocket: &Rocket<Orbit>) -> Option<(RateLimiter, Option<RateLimiter>)> {
+        let keys = rocket.state::<RateLimiter>()?.clone();
+        let throttle = rocket.state::<ReviewThrottle>()?;
+        let reviews = (!throttle.is_private()).then(|| throttle.limiter.clone());
+        Some((keys, reviews))
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for RateLimitPersistence {
+    fn info(&self) -> Info {
+        Info {
+            name: "Rate Limit Persistence",
+            kind: Kind::Liftoff | Kind::Shutdown,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        if self.flush_secs == 0 {
+            return;
+        }
+        let (keys, reviews) = match Self::limiters(rocket) {
+            Some(l) => l,
+            None => return,
+        };
+        let db_path = self.db_path.clone();
+        let interval = Duration::from_secs(self.flush_secs);
+        let shutdown = rocket.shutdown();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = shutdown.clone() => break,
+                }
+                Self::flush(&db_path, &keys, reviews.as_ref());
             }
+        });
+    }
+
+    async fn on_shutdown(&self, rocket: &Rocket<Orbit>) {
+        if self.flush_secs == 0 {
+            return;
+        }
+        if let Some((keys, reviews)) = Self::limiters(rocket) {
+            Self::flush(&self.db_path, &keys, reviews.as_ref());
         }
     }
 }
@@ -111,6 +686,31 @@ impl RateLimiter {
 mod tests {
     use super::*;
 
+    #[test]
+    fn login_throttle_counts_endpoints_separately() {
+        let throttle = LoginThrottle::new(2);
+        assert!(throttle.check("magic-link", "ip-a").allowed);
+        assert!(throttle.check("magic-link", "ip-a").allowed);
+        assert!(!throttle.check("magic-link", "ip-a").allowed);
+        assert!(throttle.check("verify", "ip-a").allowed);
+        assert!(throttle.check("magic-link", "ip-b").allowed);
+    }
+
+    #[test]
+    fn private_throttle_forgets_ended_windows() {
+        let throttle = ReviewThrottle {
+            limiter: RateLimiter::new(Duration::from_millis(20)),
+            ..ReviewThrottle::new(5)
+        }
+        .with_privacy(true);
+        throttle.check("ip-a");
+        std::thread::sleep(Duration::from_millis(30));
+        throttle.check("ip-b");
+        let buckets = throttle.limiter.buckets.lock().unwrap();
+        assert!(!buckets.contains_key("ip-a"));
+        assert!(buckets.contains_key("ip-b"));
+    }
+
     #[test]
     fn allows_under_limit() {
         let rl = RateLimiter::new(Duration::from_secs(60));
@@ -140,4 +740,163 @@ mod tests {
         assert!(!rl.check("key1", 5).allowed);
         assert!(rl.check("key2", 5).allowed);
     }
+
+    #[test]
+    fn warns_once_at_threshold() {
+        let rl = RateLimiter::new(Duration::from_secs(60));
+        let warnings: Vec<bool> = (0..10).map(|_| rl.check("key1", 10).warning).collect();
+        assert_eq!(warnings.iter().filter(|w| **w).count(), 1);
+        assert!(warnings[7], "8th of 10 requests is 80%");
+
+        let off = RateLimiter::new(Duration::from_secs(60)).with_warning_pct(0);
+        assert!((0..5).all(|_| !off.check("key1", 5).warning));
+    }
+
+    #[test]
+    fn bursts_above_the_window_limit() {
+        let rl = RateLimiter::new(Duration::from_secs(60));
+        for _ in 0..5 {
+            assert!(rl.check_with_burst("key1", 5, 2).allowed);
+        }
+        let first = rl.check_with_burst("key1", 5, 2);
+        assert!(first.allowed);
+        assert_eq!((first.remaining, first.burst_remaining), (0, 1));
+        assert!(rl.check_with_burst("key1", 5, 2).allowed);
+        assert!(!rl.check_with_burst("key1", 5, 2).allowed);
+        assert_eq!(first.policy(), "5;w=60;burst=2");
+        assert_eq!(rl.check("key2", 5).policy(), "5;w=60");
+    }
+
+    #[test]
+    fn burst_tokens_refill_over_the_window() {
+        let rl = RateLimiter::new(Duration::from_secs(1));
+        rl.check_with_burst("key1", 1, 1);
+        assert!(rl.check_with_burst("key1", 1, 1).allowed);
+        assert!(!rl.check_with_burst("key1", 1, 1).allowed);
+        std::thread::sleep(Duration::from_millis(1100));
+        // New window and a refilled bucket
+        assert!(rl.check_with_burst("key1", 1, 1).allowed);
+        assert!(rl.check_with_burst("key1", 1, 1).allowed);
+    }
+
+    #[test]
+    fn peeking_does_not_count() {
+        let rl = RateLimiter::new(Duration::from_secs(60));
+        assert_eq!(rl.peek("k", 3, 2).remaining, 3);
+        rl.check_with_burst("k", 3, 2);
+        for _ in 0..5 {
+            let state = rl.peek("k", 3, 2);
+            assert_eq!((state.remaining, state.burst_remaining), (2, 2));
+        }
+        for _ in 0..4 {
+            rl.check_with_burst("k", 3, 2);
+        }
+        let state = rl.peek("k", 3, 2);
+        assert_eq!((state.remaining, state.burst_remaining, state.allowed), (0, 0, false));
+    }
+
+    #[test]
+    fn weighted_requests_use_up_the_window() {
+        let rl = RateLimiter::new(Duration::from_secs(60)).with_route_costs(vec![
+            ("GET /api/v1/apps/search".to_string(), 4),
+            ("/api/v1/apps/*/export".to_string(), 50),
+        ]);
+        assert_eq!(rl.cost_of("GET", "/api/v1/apps/search"), 4);
+        assert_eq!(rl.cost_of("POST", "/api/v1/apps/search"), 1);
+        assert_eq!(rl.cost_of("GET", "/api/v1/apps/abc/export"), 50);
+
+        let first = rl.check_with_cost("k", 10, 0, 3);
+        assert_eq!((first.allowed, first.remaining, first.cost), (true, 7, 3));
+        assert!(!rl.check_with_cost("k", 10, 0, 3).warning);
+        // Going from 6 to 9 of 10 passes the 80% threshold
+        assert!(rl.check_with_cost("k", 10, 0, 3).warning);
+        let refused = rl.check_with_cost("k", 10, 0, 3);
+        assert_eq!((refused.allowed, refused.remaining), (false, 1));
+        assert!(rl.check("k", 10).allowed);
+
+        // Burst tokens cover what the window can't; the cost is capped at the limit
+        let rl = RateLimiter::new(Duration::from_secs(60));
+        rl.check("k", 10);
+        let burst = rl.check_with_cost("k", 10, 3, 12);
+        assert_eq!((burst.allowed, burst.cost, burst.remaining, burst.burst_remaining), (true, 10, 0, 2));
+        assert!(!rl.check_with_cost("k", 10, 3, 3).allowed);
+        assert_eq!(rl.peek("k", 10, 3).burst_remaining, 2);
+    }
+
+    #[test]
+    fn parses_route_costs() {
+        let (costs, errors) =
+            parse_route_costs("get /api/v1/apps/search=5, /api/v1/apps/*/export = 10,/api/v1/x=0,nope,/a=b,api/v1=3");
+        assert_eq!(costs, vec![("GET /api/v1/apps/search".to_string(), 5), ("/api/v1/apps/*/export".to_string(), 10)]);
+        assert_eq!(errors.len(), 4);
+        assert_eq!(format_route_costs(&costs), "GET /api/v1/apps/search=5,/api/v1/apps/*/export=10");
+        assert_eq!(parse_route_costs(""), (vec![], vec![]));
+    }
+
+    #[test]
+    fn matches_exempt_routes() {
+        let patterns: Vec<String> = ["GET /api/v1/apps/*", "/api/v1/health/**", "post /api/v1/reviews"]
+            .iter()
+            .map(|p| parse_route_pattern(p).unwrap())
+            .collect();
+        assert_eq!(patterns[2], "POST /api/v1/reviews");
+        assert!(is_exempt(&patterns, "GET", "/api/v1/apps/abc"));
+        assert!(!is_exempt(&patterns, "DELETE", "/api/v1/apps/abc"));
+        assert!(!is_exempt(&patterns, "GET", "/api/v1/apps/abc/reviews"));
+        assert!(is_exempt(&patterns, "PUT", "/api/v1/health"));
+        assert!(is_exempt(&patterns, "GET", "/api/v1/health/a/b"));
+        assert!(is_exempt(&patterns, "POST", "/api/v1/reviews"));
+        assert!(!is_exempt(&patterns, "GET", "/api/v1/reviews"));
+
+        assert!(parse_route_pattern("api/v1/apps").is_err());
+        assert!(parse_route_pattern("FETCH /api/v1/apps").is_err());
+        assert!(parse_route_pattern("/api/**/apps").is_err());
+    }
+
+    #[test]
+    fn review_throttle_caps_per_ip() {
+        let throttle = ReviewThrottle::new(2);
+        assert!(throttle.check("ip-a").allowed);
+        assert!(throttle.check("ip-a").allowed);
+        assert!(!throttle.check("ip-a").allowed);
+        assert!(throttle.check("ip-b").allowed);
+    }
+
+    fn rate_limit_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE rate_limits (scope TEXT NOT NULL, bucket_key TEXT NOT NULL,
+             window_start INTEGER NOT NULL, count INTEGER NOT NULL, PRIMARY KEY (scope, bucket_key));",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let conn = rate_limit_db();
+        let rl = RateLimiter::new(Duration::from_secs(60));
+        for _ in 0..3 {
+            rl.check("key1", 5);
+        }
+        rl.save(&conn, SCOPE_API_KEYS).unwrap();
+
+        let restored = RateLimiter::new(Duration::from_secs(60));
+        assert_eq!(restored.load(&conn, SCOPE_API_KEYS).unwrap(), 1);
+        assert_eq!(restored.check("key1", 5).remaining, 1);
+        assert_eq!(restored.load(&conn, SCOPE_REVIEWS).unwrap(), 0);
+    }
+
+    #[test]
+    fn load_skips_expired_windows() {
+        let conn = rate_limit_db();
+        conn.execute(
+            "INSERT INTO rate_limits VALUES ('api_key', 'old', ?1, 5)",
+            rusqlite::params![unix_now() - 120],
+        )
+        .unwrap();
+        let rl = RateLimiter::new(Duration::from_secs(60));
+        assert_eq!(rl.load(&conn, SCOPE_API_KEYS).unwrap(), 0);
+        assert!(rl.check("old", 5).allowed);
+    }
 }
@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::futures::StreamExt;
+use rocket::{Orbit, Rocket};
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::events::{AppEvent, EventBus};
+
+/// Pub/sub channel used when `EVENT_BUS_CHANNEL` is unset.
+pub const DEFAULT_EVENT_BUS_CHANNEL: &str = "app-directory:events";
+
+/// Wait before reconnecting a dropped subscription.
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Parse a relayed message, skipping ones this process published itself.
+pub fn decode(payload: &str, own_origin: &str) -> Option<AppEvent> {
+    let message: Value = serde_json::from_str(payload).ok()?;
+    if message.get("origin").and_then(|o| o.as_str()) == Some(own_origin) {
+        return None;
+    }
+    Some(AppEvent {
+        event: message.get("event")?.as_str()?.to_string(),
+        data: message.get("data").cloned().unwrap_or(Value::Null),
+    })
+}
+
+/// Shares events between processes over Redis pub/sub, so SSE and
+/// WebSocket subscribers on every replica see events emitted by any of
+/// them. Without a URL the in-process broadcast is used alone.
+pub struct RedisRelay {
+    pub url: Option<String>,
+    pub channel: String,
+}
+
+impl RedisRelay {
+    pub fn from_env() -> Self {
+        RedisRelay {
+            url: std::env::var("EVENT_BUS_URL").ok().filter(|u| !u.trim().is_empty()),
+            channel: std::env::var("EVENT_BUS_CHANNEL")
+                .ok()
+                .filter(|c| !c.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_EVENT_BUS_CHANNEL.to_string()),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for RedisRelay {
+    fn info(&self) -> Info {
+        Info {
+            name: "Redis Event Relay",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let url = match self.url.as_deref() {
+            Some(url) => url,
+            None => return,
+        };
+        let client = match redis::Client::open(url) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("❌ Event relay disabled: invalid EVENT_BUS_URL: {e}");
+                return;
+            }
+        };
+        let bus = match rocket.state::<EventBus>() {
+            Some(bus) => bus.clone(),
+            None => return,
+        };
+        let (tx, rx) = mpsc::unbounded_channel();
+        bus.attach_relay(tx);
+        println!("📡 Relaying events through Redis channel {}", self.channel);
+
+        tokio::spawn(publish(client.clone(), self.channel.clone(), rx, rocket.shutdown()));
+        tokio::spawn(listen(client, self.channel.clone(), bus, rocket.shutdown()));
+    }
+}
+
+/// Publish queued messages in order. A message that fails to publish is
+/// dropped and the connection is re-established for the next one.
+async fn publish(
+    client: redis::Client,
+    channel: String,
+    mut rx: mpsc::UnboundedReceiver<String>,
+    shutdown: rocket::Shutdown,
+) {
+    let mut conn = None;
+    loop {
+        let message = tokio::select! {
+            message = rx.recv() => match message {
+                Some(m) => m,
+                None => break,
+            },
+            _ = shutdown.clone() => break,
+        };
+        if conn.is_none() {
+            conn = client.get_multiplexed_async_connection().await.ok();
+        }
+        let Some(c) = conn.as_mut() else {
+            eprintln!("❌ Event relay: Redis unreachable, event not shared");
+            continue;
+        };
+        let published: redis::RedisResult<i64> = redis::AsyncCommands::publish(c, &channel, message).await;
+        if let Err(e) = published {
+            eprintln!("❌ Event relay: publish failed: {e}");
+            conn = None;
+        }
+    }
+}
+
+/// Deliver events published by other processes, resubscribing after
+/// connection loss. Events sent while disconnected are missed.
+async fn listen(client: redis::Client, channel: String, bus: EventBus, shutdown: rocket::Shutdown) {
+    loop {
+        match client.get_async_pubsub().await {
+            Ok(mut pubsub) => match pubsub.subscribe(&channel).await {
+                Ok(()) => {
+                    let mut messages = pubsub.on_message();
+                    loop {
+                        tokio::select! {
+                            message = messages.next() => match message {
+                                Some(msg) => {
+                                    let payload: String = msg.get_payload().unwrap_or_default();
+                                    if let Some(event) = decode(&payload, bus.origin()) {
+                                        bus.deliver_relayed(event);
+                                    }
+                                }
+                                None => break,
+                            },
+                            _ = shutdown.clone() => return,
+                        }
+                    }
+                    eprintln!("❌ Event relay: subscription lost, reconnecting");
+                }
+                Err(e) => eprintln!("❌ Event relay: subscribe failed: {e}"),
+            },
+            Err(e) => eprintln!("❌ Event relay: cannot connect to Redis: {e}"),
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(RECONNECT_DELAY) => {},
+            _ = shutdown.clone() => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_relayed_events() {
+        let payload = r#"{"origin":"a","event":"app.approved","data":{"app_id":"x"}}"#;
+        let event = decode(payload, "b").unwrap();
+        assert_eq!(event.event, "app.approved");
+        assert_eq!(event.app_id(), Some("x"));
+
+        // Our own messages come back through the subscription; skip them
+        assert!(decode(payload, "a").is_none());
+        assert!(decode("not json", "b").is_none());
+        assert!(decode(r#"{"origin":"a"}"#, "b").is_none());
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::{Orbit, Rocket};
+use serde_json::{json, Map, Value};
+
+use crate::events::{AppEvent, EventBus};
+use crate::rate_limit::{self, RateLimiter, ReviewThrottle};
+use crate::scheduler::{ScheduleConfig, ScheduleStatus};
+use crate::StaticDir;
+
+/// Default rate limit window in seconds.
+pub const DEFAULT_RATE_LIMIT_WINDOW_SECS: u64 = 60;
+
+/// Default share of a key's quota at which `rate_limit.warning` fires.
+pub const DEFAULT_RATE_LIMIT_WARNING_PCT: u64 = 80;
+
+/// Default anonymous reviews per client IP per day.
+pub const DEFAULT_ANON_REVIEW_DAILY_CAP: u64 = 20;
+
+/// Names of the variables the process was started with. A reload takes
+/// new values from `.env` but never overrides these.
+static PROCESS_ENV: LazyLock<HashSet<String>> = LazyLock::new(|| std::env::vars_os().filter_map(|(k, _)| k.into_string().ok()).collect());
+
+/// Record the process environment before `.env` is loaded, so a reload
+/// can tell the two apart.
+pub fn remember_process_env() {
+    LazyLock::force(&PROCESS_ENV);
+}
+
+/// Settings that can change without a restart.
+#[derive(Debug, Clone)]
+pub struct ReloadableConfig {
+    pub rate_limit_window_secs: u64,
+    pub rate_limit_warning_pct: u64,
+    pub route_costs: Vec<(String, u64)>,
+    pub anon_review_daily_cap: u64,
+    pub schedule: ScheduleConfig,
+    pub static_dir: PathBuf,
+}
+
+impl ReloadableConfig {
+    /// `RATE_LIMIT_WINDOW_SECS`, `RATE_LIMIT_WARNING_PCT`, `RATE_LIMIT_ROUTE_COSTS`,
+    /// `ANON_REVIEW_DAILY_CAP`, the health check scheduler variables and `STATIC_DIR`.
+    pub fn from_env() -> Self {
+        let env = |name: &str, default: u64| -> u64 {
+            std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+        };
+        ReloadableConfig {
+            rate_limit_window_secs: env("RATE_LIMIT_WINDOW_SECS", DEFAULT_RATE_LIMIT_WINDOW_SECS),
+            rate_limit_warning_pct: env("RATE_LIMIT_WARNING_PCT", DEFAULT_RATE_LIMIT_WARNING_PCT),
+            route_costs: rate_limit::route_costs_from_env(),
+            anon_review_daily_cap: env("ANON_REVIEW_DAILY_CAP", DEFAULT_ANON_REVIEW_DAILY_CAP),
+            schedule: ScheduleConfig::from_env(),
+            static_dir: std::env::var("STATIC_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("frontend/dist")),
+        }
+    }
+}
+
+/// Re-read `.env`, skipping variables set in the process environment.
+fn reload_env_file() {
+    let entries = match dotenvy::dotenv_iter() {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for (key, value) in entries.flatten() {
+        if !PROCESS_ENV.contains(&key) {
+            std::env::set_var(key, value);
+        }
+    }
+}
+
+/// The managed state a reload updates. Every part is shared, so a clone
+/// (like the one the SIGHUP listener holds) changes what handlers read.
+#[derive(Clone)]
+pub struct Reloader {
+    pub limiter: RateLimiter,
+    pub throttle: ReviewThrottle,
+    pub schedule: ScheduleStatus,
+    pub static_dir: StaticDir,
+    pub bus: EventBus,
+}
+
+impl Reloader {
+    fn from_rocket(rocket: &Rocket<Orbit>) -> Option<Self> {
+        Some(Reloader {
+            limiter: rocket.state::<RateLimiter>()?.clone(),
+            throttle: rocket.state::<ReviewThrottle>()?.clone(),
+            schedule: rocket.state::<ScheduleStatus>()?.clone(),
+            static_dir: rocket.state::<StaticDir>()?.clone(),
+            bus: rocket.state::<EventBus>()?.clone(),
+        })
+    }
+
+    /// Current values, keyed by the variable that sets them.
+    fn settings(&self) -> Vec<(&'static str, Value)> {
+        let schedule = self.schedule.config();
+        vec![
+            ("RATE_LIMIT_WINDOW_SECS", json!(self.limiter.window().as_secs())),
+            ("RATE_LIMIT_WARNING_PCT", json!(self.limiter.warning_pct())),
+            ("RATE_LIMIT_ROUTE_COSTS", json!(rate_limit::format_route_costs(&self.limiter.route_costs()))),
+            ("ANON_REVIEW_DAILY_CAP", json!(self.throttle.daily_cap())),
+            ("HEALTH_CHECK_INTERVAL_SECS", json!(schedule.interval_secs)),
+            ("HEALTH_CHECK_CONCURRENCY", json!(schedule.concurrency)),
+            ("HEALTH_CHECK_TIMEOUT_SECS", json!(schedule.probe_timeout_secs)),
+            ("HEALTH_CHECK_BATCH_DEADLINE_SECS", json!(schedule.batch_deadline_secs)),
+            ("KEY_EXPIRY_WARNING_DAYS", json!(schedule.key_warning_days)),
+            ("KEY_ANOMALY_FACTOR", json!(schedule.anomaly.factor)),
+            ("KEY_ANOMALY_MIN_REQUESTS", json!(schedule.anomaly.min_requests)),
+            ("KEY_ANOMALY_THROTTLE_MINS", json!(schedule.anomaly.throttle_mins)),
+            ("STATIC_DIR", json!(self.static_dir.get().display().to_string())),
+        ]
+    }
+
+    /// Re-read `.env` and the environment and apply them (see `apply`).
+    pub fn reload(&self, source: &str) -> Value {
+        reload_env_file();
+        self.apply(ReloadableConfig::from_env(), source)
+    }
+
+    /// Switch to `config`, emit `config.reloaded` and report which
+    /// settings changed along with all current values.
+    pub fn apply(&self, config: ReloadableConfig, source: &str) -> Value {
+        let before = self.settings();
+        self.limiter
+            .reconfigure(Duration::from_secs(config.rate_limit_window_secs), config.rate_limit_warning_pct);
+        self.limiter.set_route_costs(config.route_costs);
+        self.throttle.set_daily_cap(config.anon_review_daily_cap);
+        if self.schedule.config() != config.schedule {
+            self.schedule.set_config(config.schedule);
+        }
+        self.static_dir.set(config.static_dir);
+        let after = self.settings();
+
+        let changed: Vec<&str> = after
+            .iter()
+            .zip(&before)
+            .filter(|(new, old)| new.1 != old.1)
+            .map(|(new, _)| new.0)
+            .collect();
+        println!(
+            "🔄 Config reloaded ({source}): {}",
+            if changed.is_empty() { "no changes".to_string() } else { changed.join(", ") }
+        );
+        self.bus.emit(AppEvent {
+            event: "config.reloaded".to_string(),
+            data: json!({ "source": source, "changed": changed }),
+        });
+        json!({
+            "changed": changed,
+            "config": after.into_iter().map(|(name, value)| (name.to_string(), value)).collect::<Map<_, _>>(),
+        })
+    }
+}
+
+/// Reloads configuration when the process receives `SIGHUP` (Unix only).
+pub struct ConfigReload;
+
+#[rocket::async_trait]
+impl Fairing for ConfigReload {
+    fn info(&self) -> Info {
+        Info {
+            name: "Config reload on SIGHUP",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    #[cfg(unix)]
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let reloader = match Reloader::from_rocket(rocket) {
+            Some(r) => r,
+            None => return,
+        };
+        let mut hangups = match signal(SignalKind::hangup()) {
+            Ok(s) => s,
+            Err(e) => {
+                rocket::error!("Config reload: can't listen for SIGHUP: {}", e);
+                return;
+            }
+        };
+        let shutdown = rocket.shutdown();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    Some(()) = hangups.recv() => {
+                        reloader.reload("signal");
+                    }
+                    _ = shutdown.clone() => break,
+                }
+            }
+        });
+    }
+
+    #[cfg(not(unix))]
+    async fn on_liftoff(&self, _rocket: &Rocket<Orbit>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_reports_changed_settings() {
+        let config = ReloadableConfig {
+            rate_limit_window_secs: 60,
+            rate_limit_warning_pct: 80,
+            route_costs: vec![],
+            anon_review_daily_cap: 20,
+            schedule: ScheduleConfig::default(),
+            static_dir: PathBuf::from("frontend/dist"),
+        };
+        let reloader = Reloader {
+            limiter: RateLimiter::new(Duration::from_secs(60)),
+            throttle: ReviewThrottle::new(20),
+            schedule: ScheduleStatus::new(config.schedule.clone()),
+            static_dir: StaticDir::new(config.static_dir.clone()),
+            bus: EventBus::new(),
+        };
+        let mut events = reloader.bus.subscribe();
+
+        let unchanged = reloader.apply(config.clone(), "api");
+        assert_eq!(unchanged["changed"], json!([]));
+        assert_eq!(unchanged["config"]["RATE_LIMIT_WINDOW_SECS"], 60);
+
+        let report = reloader.apply(
+            ReloadableConfig {
+                rate_limit_window_secs: 120,
+                route_costs: vec![("GET /api/v1/apps/search".to_string(), 5)],
+                anon_review_daily_cap: 5,
+                schedule: ScheduleConfig { interval_secs: 0, ..ScheduleConfig::default() },
+                static_dir: PathBuf::from("/srv/www"),
+                ..config
+            },
+            "signal",
+        );
+        assert_eq!(
+            report["changed"],
+            json!([
+                "RATE_LIMIT_WINDOW_SECS",
+                "RATE_LIMIT_ROUTE_COSTS",
+                "ANON_REVIEW_DAILY_CAP",
+                "HEALTH_CHECK_INTERVAL_SECS",
+                "STATIC_DIR"
+            ])
+        );
+        assert_eq!(reloader.limiter.window(), Duration::from_secs(120));
+        assert_eq!(reloader.limiter.cost_of("GET", "/api/v1/apps/search"), 5);
+        assert_eq!(reloader.throttle.daily_cap(), 5);
+        assert_eq!(reloader.schedule.config().interval_secs, 0);
+        assert_eq!(reloader.static_dir.get(), PathBuf::from("/srv/www"));
+
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.event, "config.reloaded");
+        assert_eq!(event.data["source"], "api");
+        assert_eq!(events.try_recv().unwrap().data["source"], "signal");
+    }
+}
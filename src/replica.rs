@@ -0,0 +1,445 @@
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Status;
+use rocket::request::{self, FromRequest, Request};
+use rocket::serde::json::Json;
+use rocket::{Orbit, Rocket};
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::auth::ReadAccess;
+use crate::errors::{ApiError, ErrorCode};
+use crate::DbState;
+
+/// Seconds between syncs when `READ_ONLY_SYNC_SECS` is unset.
+pub const DEFAULT_SYNC_SECS: u64 = 300;
+
+/// Path of the replication feed, relative to the primary's base URL.
+pub const FEED_PATH: &str = "/api/v1/replication/feed";
+
+/// Listings per feed page when `limit` is unset, and the most allowed.
+const DEFAULT_FEED_LIMIT: usize = 100;
+const MAX_FEED_LIMIT: usize = 500;
+
+/// Owner recorded on replicated listings: keys and accounts stay on the primary.
+pub const REPLICA_OWNER: &str = "replica";
+
+/// Listing columns carried by the feed. Owner, reviewer-assignment,
+/// notification and edit-token columns stay on the primary.
+const APP_COLUMNS: &[&str] = &[
+    "id", "name", "slug", "short_description", "description", "homepage_url", "api_url", "api_spec_url",
+    "protocol", "category", "tags", "logo_url", "author_name", "author_url", "status", "is_featured",
+    "is_verified", "avg_rating", "review_count", "created_at", "updated_at", "last_health_status",
+    "last_checked_at", "uptime_pct", "health_expectation", "deprecated_reason", "deprecated_at",
+    "replacement_app_id", "sunset_at", "spec_status", "archived_at", "revision", "featured_from",
+    "featured_until", "visibility", "docs_markdown", "key_endpoints", "duplicated_from", "link_original",
+];
+
+/// Review columns carried by the feed; reviewer identities are left out.
+const REVIEW_COLUMNS: &[&str] = &[
+    "id", "rating", "title", "body", "reviewer_name", "pros", "cons", "use_case", "verified_usage",
+    "created_at", "edited_at",
+];
+
+const TRANSLATION_COLUMNS: &[&str] = &["lang", "name", "short_description", "description", "updated_at"];
+
+/// Where a replica pulls its listings from.
+#[derive(Debug, Clone)]
+pub struct ReplicaConfig {
+    /// Base URL of the primary, e.g. `https://apps.example.com`.
+    pub primary_url: Option<String>,
+    /// Sent as `X-API-Key`, for primaries that require auth for reads.
+    pub api_key: Option<String>,
+    pub sync_secs: u64,
+}
+
+/// Outcome of the latest sync, shown in `GET /health`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncStatus {
+    pub last_attempt_at: Option<String>,
+    pub last_synced_at: Option<String>,
+    /// Listings copied by the last successful sync.
+    pub apps: usize,
+    pub last_error: Option<String>,
+}
+
+/// Read-only replica mode: while on, every write under `/api/v1` answers
+/// `403 READ_ONLY` and the listings are kept in step with a primary's
+/// replication feed.
+#[derive(Clone)]
+pub struct Replica {
+    config: Option<ReplicaConfig>,
+    status: Arc<RwLock<SyncStatus>>,
+}
+
+impl Replica {
+    pub fn new(config: Option<ReplicaConfig>) -> Self {
+        Replica {
+            config,
+            status: Arc::default(),
+        }
+    }
+
+    /// A normal, writable instance.
+    pub fn disabled() -> Self {
+        Replica::new(None)
+    }
+
+    /// `READ_ONLY` (`1`/`true`) turns the instance into a replica of
+    /// `READ_ONLY_PRIMARY_URL`, synced every `READ_ONLY_SYNC_SECS` (default
+    /// 300). `READ_ONLY_PRIMARY_KEY` is sent to the primary as `X-API-Key`.
+    /// Without a primary URL the local copy is served as is.
+    pub fn from_env() -> Self {
+        if !matches!(std::env::var("READ_ONLY").as_deref(), Ok("1") | Ok("true")) {
+            return Replica::disabled();
+        }
+        let primary_url = std::env::var("READ_ONLY_PRIMARY_URL")
+            .ok()
+            .map(|v| v.trim().trim_end_matches('/').to_string())
+            .filter(|v| !v.is_empty());
+        let api_key = std::env::var("READ_ONLY_PRIMARY_KEY").ok().filter(|v| !v.trim().is_empty());
+        let sync_secs = std::env::var("READ_ONLY_SYNC_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_SYNC_SECS)
+            .max(1);
+        match &primary_url {
+            Some(url) => println!("📖 Read-only replica of {url}: writes are rejected"),
+            None => println!("📖 Read-only mode without READ_ONLY_PRIMARY_URL: serving the local copy"),
+        }
+        Replica::new(Some(ReplicaConfig {
+            primary_url,
+            api_key,
+            sync_secs,
+        }))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_some()
+    }
+
+    pub fn config(&self) -> Option<&ReplicaConfig> {
+        self.config.as_ref()
+    }
+
+    pub fn status(&self) -> SyncStatus {
+        self.status.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replica details for `GET /health`, or null on a writable instance.
+    pub fn health(&self) -> Value {
+        let Some(config) = &self.config else { return Value::Null };
+        let status = self.status();
+        json!({
+            "primary": config.primary_url,
+            "sync_secs": config.sync_secs,
+            "last_attempt_at": status.last_attempt_at,
+            "last_synced_at": status.last_synced_at,
+            "apps": status.apps,
+            "last_error": status.last_error,
+        })
+    }
+
+    fn record(&self, result: &Result<usize, String>) {
+        let mut status = self.status.write().unwrap_or_else(|e| e.into_inner());
+        let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        status.last_attempt_at = Some(now.clone());
+        match result {
+            Ok(apps) => {
+                status.last_synced_at = Some(now);
+                status.apps = *apps;
+                status.last_error = None;
+            }
+            Err(e) => status.last_error = Some(e.clone()),
+        }
+    }
+}
+
+// === Primary side ===
+
+fn row_object(row: &rusqlite::Row, columns: &[&str]) -> rusqlite::Result<Value> {
+    let mut object = Map::new();
+    for (i, column) in columns.iter().enumerate() {
+        let value = match row.get_ref(i)? {
+            ValueRef::Integer(n) => json!(n),
+            ValueRef::Real(f) => json!(f),
+            ValueRef::Text(t) => json!(String::from_utf8_lossy(t)),
+            ValueRef::Null | ValueRef::Blob(_) => Value::Null,
+        };
+        object.insert(column.to_string(), value);
+    }
+    Ok(Value::Object(object))
+}
+
+fn child_rows(conn: &Connection, table: &str, columns: &[&str], app_id: &str) -> rusqlite::Result<Value> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM {table} WHERE app_id = ?1 ORDER BY {}",
+        columns.join(", "),
+        columns[0]
+    ))?;
+    let rows = stmt
+        .query_map([app_id], |r| row_object(r, columns))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(Value::Array(rows))
+}
+
+/// One page of listings in id order, each with its reviews and translations.
+/// Drafts and private listings are left out. Returns the page and the
+/// cursor for the next one, if any.
+pub fn feed_page(conn: &Connection, after: Option<&str>, limit: usize) -> rusqlite::Result<(Vec<Value>, Option<String>)> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM apps WHERE status != 'draft' AND visibility != 'private' AND id > ?1 ORDER BY id LIMIT ?2",
+        APP_COLUMNS.join(", ")
+    ))?;
+    let mut apps = stmt
+        .query_map(rusqlite::params![after.unwrap_or_default(), limit as i64], |r| row_object(r, APP_COLUMNS))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for app in &mut apps {
+        let id = app["id"].as_str().unwrap_or_default().to_string();
+        app["reviews"] = child_rows(conn, "reviews", REVIEW_COLUMNS, &id)?;
+        app["translations"] = child_rows(conn, "app_translations", TRANSLATION_COLUMNS, &id)?;
+    }
+    let next_cursor = (apps.len() == limit)
+        .then(|| apps.last().and_then(|a| a["id"].as_str()).map(String::from))
+        .flatten();
+    Ok((apps, next_cursor))
+}
+
+/// Replication feed: every public listing with its reviews and
+/// translations, paged by id. Read replicas poll it to stay in step.
+#[get("/replication/feed?<after>&<limit>")]
+pub fn feed(_reader: ReadAccess, after: Option<&str>, limit: Option<usize>, db: &rocket::State<DbState>) -> (Status, Json<Value>) {
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT).clamp(1, MAX_FEED_LIMIT);
+    let conn = db.conn();
+    match feed_page(&conn, after, limit) {
+        Ok((apps, next_cursor)) => (Status::Ok, Json(json!({ "apps": apps, "next_cursor": next_cursor }))),
+        Err(e) => {
+            eprintln!("❌ Failed to build replication feed: {e}");
+            ApiError::new(ErrorCode::InternalError, "Failed to build replication feed").into()
+        }
+    }
+}
+
+// === Replica side ===
+
+fn sql_value(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => n.as_i64().map_or_else(|| SqlValue::Real(n.as_f64().unwrap_or_default()), SqlValue::Integer),
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn insert_rows(conn: &Connection, table: &str, columns: &[&str], app_id: &str, rows: &Value) -> rusqlite::Result<()> {
+    let sql = format!(
+        "INSERT INTO {table} (app_id, {}) VALUES (?1, {})",
+        columns.join(", "),
+        (2..=columns.len() + 1).map(|i| format!("?{i}")).collect::<Vec<_>>().join(", ")
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    for row in rows.as_array().into_iter().flatten() {
+        let values = std::iter::once(SqlValue::Text(app_id.to_string())).chain(columns.iter().map(|c| sql_value(&row[*c])));
+        stmt.execute(rusqlite::params_from_iter(values))?;
+    }
+    Ok(())
+}
+
+/// Make the local listings match a full feed snapshot in one transaction:
+/// listings missing from it are deleted, the rest are inserted or updated
+/// and their reviews and translations replaced. Returns the listing count.
+pub fn apply_snapshot(conn: &mut Connection, apps: &[Value]) -> rusqlite::Result<usize> {
+    let tx = conn.transaction()?;
+    let keep: HashSet<&str> = apps.iter().filter_map(|a| a["id"].as_str()).collect();
+    let local: Vec<String> = tx
+        .prepare("SELECT id FROM apps")?
+        .query_map([], |r| r.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for id in local.iter().filter(|id| !keep.contains(id.as_str())) {
+        crate::routes::delete_app_records(&tx, id)?;
+    }
+
+    // Park every slug first so listings that swapped slugs can't collide mid-sync
+    tx.execute("UPDATE apps SET slug = 'replica-sync:' || id", [])?;
+    let upsert = format!(
+        "INSERT INTO apps ({}, submitted_by_key_id) VALUES ({}, '{REPLICA_OWNER}')
+         ON CONFLICT(id) DO UPDATE SET {}",
+        APP_COLUMNS.join(", "),
+        (1..=APP_COLUMNS.len()).map(|i| format!("?{i}")).collect::<Vec<_>>().join(", "),
+        APP_COLUMNS[1..].iter().map(|c| format!("{c} = excluded.{c}")).collect::<Vec<_>>().join(", ")
+    );
+    let mut count = 0;
+    for app in apps {
+        let Some(id) = app["id"].as_str() else { continue };
+        tx.execute(&upsert, rusqlite::params_from_iter(APP_COLUMNS.iter().map(|c| sql_value(&app[*c]))))?;
+        tx.execute(
+            "DELETE FROM review_versions WHERE review_id IN (SELECT id FROM reviews WHERE app_id = ?1)",
+            [id],
+        )?;
+        tx.execute(
+            "DELETE FROM review_reactions WHERE review_id IN (SELECT id FROM reviews WHERE app_id = ?1)",
+            [id],
+        )?;
+        tx.execute("DELETE FROM reviews WHERE app_id = ?1", [id])?;
+        insert_rows(&tx, "reviews", REVIEW_COLUMNS, id, &app["reviews"])?;
+        tx.execute("DELETE FROM app_translations WHERE app_id = ?1", [id])?;
+        insert_rows(&tx, "app_translations", TRANSLATION_COLUMNS, id, &app["translations"])?;
+        count += 1;
+    }
+    tx.commit()?;
+    Ok(count)
+}
+
+/// Page through the primary's feed. Any failure aborts the whole pass so a
+/// partial snapshot never deletes listings.
+pub async fn fetch_snapshot(client: &reqwest::Client, primary_url: &str, api_key: Option<&str>) -> Result<Vec<Value>, String> {
+    let mut apps = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let mut url = format!("{primary_url}{FEED_PATH}?limit={MAX_FEED_LIMIT}");
+        if let Some(after) = &after {
+            url.push_str(&format!("&after={after}"));
+        }
+        let mut request = client.get(&url);
+        if let Some(key) = api_key {
+            request = request.header("X-API-Key", key);
+        }
+        let response = request.send().await.map_err(|e| format!("primary unreachable: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("primary answered {}", response.status()));
+        }
+        let page: Value = response.json().await.map_err(|e| format!("invalid feed page: {e}"))?;
+        let Some(page_apps) = page["apps"].as_array() else {
+            return Err("invalid feed page: missing apps".to_string());
+        };
+        apps.extend(page_apps.iter().cloned());
+        match page["next_cursor"].as_str() {
+            Some(cursor) if after.as_deref() != Some(cursor) => after = Some(cursor.to_string()),
+            _ => return Ok(apps),
+        }
+    }
+}
+
+/// Rocket fairing that syncs a replica from its primary at liftoff and
+/// then every `sync_secs`.
+pub struct ReplicaSync {
+    pub db_path: String,
+}
+
+#[rocket::async_trait]
+impl Fairing for ReplicaSync {
+    fn info(&self) -> Info {
+        Info {
+            name: "Replica Sync",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let Some(replica) = rocket.state::<Replica>().cloned() else { return };
+        let Some(config) = replica.config().cloned() else { return };
+        let Some(primary_url) = config.primary_url.clone() else { return };
+        let mut conn = match Connection::open(&self.db_path) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("❌ Replica sync: failed to open DB: {e}");
+                return;
+            }
+        };
+        let _ = conn.busy_timeout(Duration::from_secs(5));
+        let interval = Duration::from_secs(config.sync_secs);
+        let shutdown = rocket.shutdown();
+        let client = crate::discovery::client();
+
+        tokio::spawn(async move {
+            loop {
+                let result = match fetch_snapshot(&client, &primary_url, config.api_key.as_deref()).await {
+                    Ok(apps) => apply_snapshot(&mut conn, &apps).map_err(|e| format!("failed to apply snapshot: {e}")),
+                    Err(e) => Err(e),
+                };
+                match &result {
+                    Ok(apps) => rocket::info!("Replica synced {apps} listings from {primary_url}"),
+                    Err(e) => eprintln!("❌ Replica sync from {primary_url}: {e}"),
+                }
+                replica.record(&result);
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = shutdown.clone() => break,
+                }
+            }
+        });
+    }
+}
+
+/// Present only on a replica; forwards otherwise so the request reaches
+/// its real route.
+pub struct WritesRejected(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for WritesRejected {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, ()> {
+        match request.rocket().state::<Replica>().and_then(Replica::config) {
+            Some(config) => request::Outcome::Success(WritesRejected(config.primary_url.clone())),
+            None => request::Outcome::Forward(Status::NotFound),
+        }
+    }
+}
+
+impl From<WritesRejected> for ApiError {
+    fn from(rejected: WritesRejected) -> Self {
+        ApiError::new(ErrorCode::ReadOnly, "This instance is a read-only replica; send writes to the primary")
+            .with("primary", rejected.0)
+    }
+}
+
+/// Catch-all write routes, ranked ahead of the maintenance ones so a
+/// replica answers `READ_ONLY` before any handler (or body parsing) runs.
+pub fn routes() -> Vec<rocket::Route> {
+    let mut routes = routes![reject_post, reject_put, reject_patch, reject_delete];
+    for route in &mut routes {
+        route.rank = -101;
+    }
+    routes
+}
+
+#[post("/<_..>")]
+pub fn reject_post(rejected: WritesRejected) -> ApiError {
+    rejected.into()
+}
+
+#[put("/<_..>")]
+pub fn reject_put(rejected: WritesRejected) -> ApiError {
+    rejected.into()
+}
+
+#[patch("/<_..>")]
+pub fn reject_patch(rejected: WritesRejected) -> ApiError {
+    rejected.into()
+}
+
+#[delete("/<_..>")]
+pub fn reject_delete(rejected: WritesRejected) -> ApiError {
+    rejected.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sql_values_round_trip_feed_json() {
+        assert_eq!(sql_value(&json!(null)), SqlValue::Null);
+        assert_eq!(sql_value(&json!(true)), SqlValue::Integer(1));
+        assert_eq!(sql_value(&json!(42)), SqlValue::Integer(42));
+        assert_eq!(sql_value(&json!(99.5)), SqlValue::Real(99.5));
+        assert_eq!(sql_value(&json!("[\"a\"]")), SqlValue::Text("[\"a\"]".to_string()));
+    }
+}
@@ -0,0 +1,239 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::{Orbit, Rocket};
+use rusqlite::Connection;
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::erasure::ERASED;
+use crate::stats::VERIFIED_USAGE_WINDOW_DAYS;
+
+/// Days views and click-throughs are kept when `STATS_RETENTION_DAYS` is unset.
+pub const DEFAULT_RETENTION_DAYS: i64 = 180;
+
+/// Days before viewer keys are pseudonymized when `STATS_ANONYMIZE_AFTER_DAYS` is unset.
+pub const DEFAULT_ANONYMIZE_AFTER_DAYS: i64 = 90;
+
+/// Seconds between retention runs when `STATS_RETENTION_INTERVAL_SECS` is unset.
+pub const DEFAULT_INTERVAL_SECS: u64 = 3600;
+
+/// Prefix of pseudonymized viewer and clicker ids.
+pub const PSEUDONYM_PREFIX: &str = "anon:";
+
+/// How long raw usage data is kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionPolicy {
+    /// Views and click-throughs older than this are deleted (0 keeps them).
+    pub retention_days: i64,
+    /// Key ids on views older than this are replaced by a pseudonym (0 never).
+    pub anonymize_after_days: i64,
+    /// Seconds between runs (0 disables the job).
+    pub interval_secs: u64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            retention_days: DEFAULT_RETENTION_DAYS,
+            anonymize_after_days: DEFAULT_ANONYMIZE_AFTER_DAYS,
+            interval_secs: DEFAULT_INTERVAL_SECS,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// `STATS_RETENTION_DAYS` (default 180), `STATS_ANONYMIZE_AFTER_DAYS`
+    /// (default 90) and `STATS_RETENTION_INTERVAL_SECS` (default 3600).
+    pub fn from_env() -> Self {
+        let d = RetentionPolicy::default();
+        let env = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<i64>().ok()).map(|v| v.max(0));
+        RetentionPolicy {
+            retention_days: env("STATS_RETENTION_DAYS").unwrap_or(d.retention_days),
+            anonymize_after_days: env("STATS_ANONYMIZE_AFTER_DAYS").unwrap_or(d.anonymize_after_days),
+            interval_secs: env("STATS_RETENTION_INTERVAL_SECS").map(|v| v as u64).unwrap_or(d.interval_secs),
+        }
+    }
+}
+
+/// `PRIVACY_MODE`: views are stored without the viewer's key and only to
+/// the day (still one row per view), and hashed client IPs used for rate limiting are kept in memory
+/// for the active window only, never written to the database.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrivacyMode {
+    pub enabled: bool,
+    /// The retention policy in force, reported alongside.
+    pub retention: RetentionPolicy,
+}
+
+impl PrivacyMode {
+    /// `PRIVACY_MODE` (default off), with the given retention policy.
+    pub fn from_env(retention: RetentionPolicy) -> Self {
+        PrivacyMode {
+            enabled: matches!(std::env::var("PRIVACY_MODE").as_deref(), Ok("1") | Ok("true")),
+            retention,
+        }
+    }
+
+    /// What this instance keeps about its visitors, for `GET /api/v1/health`.
+    pub fn posture(&self) -> serde_json::Value {
+        let days = |d: i64| (d > 0).then_some(d);
+        serde_json::json!({
+            "privacy_mode": self.enabled,
+            "viewer_ids_stored": !self.enabled,
+            "view_granularity": if self.enabled { "day" } else { "second" },
+            "unique_viewers_reported": !self.enabled,
+            "rate_limit_ips_persisted": !self.enabled,
+            "stats_retention_days": days(self.retention.retention_days),
+            "stats_anonymize_after_days": days(self.retention.anonymize_after_days),
+        })
+    }
+}
+
+/// What one retention run changed.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct RetentionReport {
+    pub views_deleted: usize,
+    pub clicks_deleted: usize,
+    pub views_anonymized: usize,
+    pub clicks_anonymized: usize,
+}
+
+/// This installation's secret for pseudonyms, created on first use. Kept
+/// in the database so a key maps to the same pseudonym across restarts.
+fn pseudonym_secret(conn: &Connection) -> rusqlite::Result<String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO instance_settings (name, value) VALUES ('stats_pseudonym_secret', ?1)",
+        rusqlite::params![format!("{:032x}", rand::random::<u128>())],
+    )?;
+    conn.query_row(
+        "SELECT value FROM instance_settings WHERE name = 'stats_pseudonym_secret'",
+        [],
+        |r| r.get(0),
+    )
+}
+
+/// Stable, non-reversible stand-in for a key id: the same key always gets
+/// the same pseudonym, so distinct-viewer counts and co-viewing survive.
+pub fn pseudonym(secret: &str, key_id: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(key_id.as_bytes());
+    format!("{}{}", PSEUDONYM_PREFIX, &hex::encode(mac.finalize().into_bytes())[..16])
+}
+
+/// Replace key ids in `column` of `table` on rows older than `days`.
+fn anonymize(conn: &Connection, secret: &str, table: &str, column: &str, at: &str, days: i64) -> rusqlite::Result<usize> {
+    let cutoff = format!("-{} days", days);
+    let keys: Vec<String> = {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT DISTINCT {column} FROM {table}
+             WHERE {at} < datetime('now', ?1) AND {column} IS NOT NULL
+               AND {column} NOT IN ('anonymous', ?2) AND {column} NOT LIKE ?3"
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![cutoff, ERASED, format!("{PSEUDONYM_PREFIX}%")], |r| r.get(0))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+    let mut changed = 0;
+    for key in keys {
+        changed += conn.execute(
+            &format!("UPDATE {table} SET {column} = ?1 WHERE {column} = ?2 AND {at} < datetime('now', ?3)"),
+            rusqlite::params![pseudonym(secret, &key), key, cutoff],
+        )?;
+    }
+    Ok(changed)
+}
+
+/// Delete views and click-throughs past `retention_days` and pseudonymize
+/// the keys on the rest past `anonymize_after_days`. Click-throughs keep
+/// their key for at least the verified-usage window, so reviews can still
+/// be verified.
+pub fn apply(conn: &Connection, policy: &RetentionPolicy) -> rusqlite::Result<RetentionReport> {
+    let mut report = RetentionReport::default();
+    if policy.retention_days > 0 {
+        let cutoff = format!("-{} days", policy.retention_days);
+        report.views_deleted =
+            conn.execute("DELETE FROM app_views WHERE viewed_at < datetime('now', ?1)", rusqlite::params![cutoff])?;
+        report.clicks_deleted =
+            conn.execute("DELETE FROM app_clicks WHERE clicked_at < datetime('now', ?1)", rusqlite::params![cutoff])?;
+    }
+    if policy.anonymize_after_days > 0 {
+        let secret = pseudonym_secret(conn)?;
+        let days = policy.anonymize_after_days;
+        report.views_anonymized = anonymize(conn, &secret, "app_views", "viewer_key_id", "viewed_at", days)?;
+        report.clicks_anonymized = anonymize(
+            conn,
+            &secret,
+            "app_clicks",
+            "clicker_key_id",
+            "clicked_at",
+            days.max(VERIFIED_USAGE_WINDOW_DAYS),
+        )?;
+    }
+    Ok(report)
+}
+
+/// Rocket fairing that applies the retention policy every `interval_secs`,
+/// starting shortly after launch.
+pub struct StatsRetention {
+    pub db_path: String,
+    pub policy: RetentionPolicy,
+}
+
+#[rocket::async_trait]
+impl Fairing for StatsRetention {
+    fn info(&self) -> Info {
+        Info {
+            name: "Stats Retention",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let policy = self.policy.clone();
+        if policy.interval_secs == 0 || (policy.retention_days == 0 && policy.anonymize_after_days == 0) {
+            rocket::info!("Stats retention disabled");
+            return;
+        }
+        let db = crate::scheduler::init_scheduler_db(&self.db_path);
+        let shutdown = rocket.shutdown();
+
+        tokio::spawn(async move {
+            let mut wait = Duration::from_secs(60);
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {},
+                    _ = shutdown.clone() => break,
+                }
+                wait = Duration::from_secs(policy.interval_secs);
+                let conn = match db.lock() {
+                    Ok(conn) => conn,
+                    Err(_) => continue,
+                };
+                match apply(&conn, &policy) {
+                    Ok(report) if report != RetentionReport::default() => println!(
+                        "🧹 Stats retention: deleted {} views and {} clicks, anonymized {} views and {} clicks",
+                        report.views_deleted, report.clicks_deleted, report.views_anonymized, report.clicks_anonymized
+                    ),
+                    Ok(_) => {}
+                    Err(e) => rocket::error!("Stats retention failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pseudonyms_are_stable_per_secret() {
+        let a = pseudonym("secret", "key-1");
+        assert!(a.starts_with(PSEUDONYM_PREFIX));
+        assert_eq!(a.len(), PSEUDONYM_PREFIX.len() + 16);
+        assert_eq!(a, pseudonym("secret", "key-1"));
+        assert_ne!(a, pseudonym("secret", "key-2"));
+        assert_ne!(a, pseudonym("other", "key-1"));
+    }
+}
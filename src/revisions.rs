@@ -0,0 +1,415 @@
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::serde::json::Json;
+use rocket::Request;
+use rusqlite::Connection;
+use serde_json::{json, Map, Value};
+
+use crate::auth::{AuthenticatedKey, EditTokenParam, ReadAccess};
+use crate::errors::{ApiError, ErrorCode};
+use crate::events::{AppEvent, EventBus};
+use crate::outbox;
+use crate::DbState;
+
+/// Listing fields captured in every revision snapshot.
+const SNAPSHOT_FIELDS: &[&str] = &[
+    "name",
+    "short_description",
+    "description",
+    "homepage_url",
+    "api_url",
+    "api_spec_url",
+    "protocol",
+    "category",
+    "tags",
+    "logo_url",
+    "author_name",
+    "author_url",
+    "status",
+    "is_featured",
+    "is_verified",
+];
+
+/// Snapshot fields a rollback restores. Status and badges only change
+/// through the moderation endpoints.
+const RESTORABLE_FIELDS: &[&str] = &[
+    "name",
+    "short_description",
+    "description",
+    "homepage_url",
+    "api_url",
+    "api_spec_url",
+    "protocol",
+    "category",
+    "tags",
+    "logo_url",
+    "author_name",
+    "author_url",
+];
+
+/// Strong ETag for an app revision (`"3"`).
+pub fn etag(revision: i64) -> String {
+    format!("\"{}\"", revision)
+}
+
+/// Current revision of an app, `None` if it doesn't exist.
+pub fn current_revision(conn: &Connection, app_id: &str) -> Option<i64> {
+    conn.query_row(
+        "SELECT revision FROM apps WHERE id = ?1",
+        rusqlite::params![app_id],
+        |r| r.get(0),
+    )
+    .ok()
+}
+
+/// Current listing fields of an app as a snapshot object.
+fn snapshot(conn: &Connection, app_id: &str) -> rusqlite::Result<(i64, Value)> {
+    let sql = format!(
+        "SELECT revision, {} FROM apps WHERE id = ?1",
+        SNAPSHOT_FIELDS.join(", ")
+    );
+    conn.query_row(&sql, rusqlite::params![app_id], |row| {
+        let mut fields = Map::new();
+        for (i, field) in SNAPSHOT_FIELDS.iter().enumerate() {
+            let value = match row.get::<_, rusqlite::types::Value>(i + 1)? {
+                rusqlite::types::Value::Text(t) if *field == "tags" => {
+                    serde_json::from_str(&t).unwrap_or_else(|_| json!([]))
+                }
+                rusqlite::types::Value::Text(t) => json!(t),
+                rusqlite::types::Value::Integer(n) if field.starts_with("is_") => json!(n != 0),
+                rusqlite::types::Value::Integer(n) => json!(n),
+                rusqlite::types::Value::Real(f) => json!(f),
+                _ => Value::Null,
+            };
+            fields.insert(field.to_string(), value);
+        }
+        Ok((row.get(0)?, Value::Object(fields)))
+    })
+}
+
+/// Store the app's current state as its current revision.
+/// Call after every write that bumps `revision`.
+pub fn record(
+    conn: &Connection,
+    app_id: &str,
+    editor_kind: &str,
+    editor_id: Option<&str>,
+) -> rusqlite::Result<()> {
+    let (revision, snapshot) = snapshot(conn, app_id)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO app_revisions (app_id, revision, snapshot, editor_kind, editor_id)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![app_id, revision, snapshot.to_string(), editor_kind, editor_id],
+    )?;
+    Ok(())
+}
+
+/// Fields that differ between two snapshots, as `{field: {"from", "to"}}`.
+pub fn diff(previous: Option<&Value>, current: &Value) -> Map<String, Value> {
+    SNAPSHOT_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let from = previous.and_then(|p| p.get(*field)).cloned().unwrap_or(Value::Null);
+            let to = current.get(*field).cloned().unwrap_or(Value::Null);
+            (from != to).then(|| (field.to_string(), json!({ "from": from, "to": to })))
+        })
+        .collect()
+}
+
+/// Snapshot of one stored revision.
+fn load(conn: &Connection, app_id: &str, revision: i64) -> Option<Value> {
+    conn.query_row(
+        "SELECT snapshot FROM app_revisions WHERE app_id = ?1 AND revision = ?2",
+        rusqlite::params![app_id, revision],
+        |r| r.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|s| serde_json::from_str(&s).ok())
+}
+
+/// The stored revision just before `revision`, if any.
+fn load_previous(conn: &Connection, app_id: &str, revision: i64) -> Option<(i64, Value)> {
+    conn.query_row(
+        "SELECT revision, snapshot FROM app_revisions
+         WHERE app_id = ?1 AND revision < ?2 ORDER BY revision DESC LIMIT 1",
+        rusqlite::params![app_id, revision],
+        |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?)),
+    )
+    .ok()
+    .and_then(|(n, s)| Some((n, serde_json::from_str(&s).ok()?)))
+}
+
+fn resolve_app(conn: &Connection, id_or_slug: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT id FROM apps WHERE id = ?1 OR slug = ?1",
+        rusqlite::params![id_or_slug],
+        |r| r.get(0),
+    )
+    .ok()
+}
+
+/// Revision history of an app, newest first. Drafts and private listings
+/// are only visible to those who may view the listing itself.
+#[get("/apps/<app_id>/revisions?<page>&<per_page>")]
+pub fn list_revisions(
+    reader: ReadAccess,
+    token: EditTokenParam,
+    app_id: &str,
+    page: Option<i64>,
+    per_page: Option<i64>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+
+    let app_id = match crate::routes::visible_app_id(&conn, app_id, &reader, token.0.as_deref()) {
+        Some(id) => id,
+        None => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+
+    let page = page.unwrap_or(1).max(1);
+    let per_page = per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * per_page;
+
+    let total: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM app_revisions WHERE app_id = ?1",
+            rusqlite::params![app_id],
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+
+    let rows: Vec<(i64, String, Option<String>, String, String)> = conn
+        .prepare(
+            "SELECT revision, editor_kind, editor_id, created_at, snapshot FROM app_revisions
+             WHERE app_id = ?1 ORDER BY revision DESC LIMIT ?2 OFFSET ?3",
+        )
+        .unwrap()
+        .query_map(rusqlite::params![app_id, per_page, offset], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let revisions: Vec<Value> = rows
+        .into_iter()
+        .map(|(revision, editor_kind, editor_id, created_at, snapshot)| {
+            let current: Value = serde_json::from_str(&snapshot).unwrap_or(Value::Null);
+            let previous = load_previous(&conn, &app_id, revision).map(|(_, s)| s);
+            let changed: Vec<String> = diff(previous.as_ref(), &current).keys().cloned().collect();
+            json!({
+                "revision": revision,
+                "editor": { "kind": editor_kind, "id": editor_id },
+                "created_at": created_at,
+                "changed_fields": changed,
+            })
+        })
+        .collect();
+
+    (
+        Status::Ok,
+        Json(json!({
+            "app_id": app_id,
+            "revisions": revisions,
+            "total": total,
+            "page": page,
+            "per_page": per_page,
+        })),
+    )
+}
+
+/// Changes introduced by one revision relative to the previous stored one.
+#[get("/apps/<app_id>/revisions/<revision>/diff")]
+pub fn revision_diff(
+    reader: ReadAccess,
+    token: EditTokenParam,
+    app_id: &str,
+    revision: i64,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+
+    let app_id = match crate::routes::visible_app_id(&conn, app_id, &reader, token.0.as_deref()) {
+        Some(id) => id,
+        None => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+
+    let current = match load(&conn, &app_id, revision) {
+        Some(s) => s,
+        None => return ApiError::new(ErrorCode::NotFound, "Revision not found").into(),
+    };
+    let previous = load_previous(&conn, &app_id, revision);
+
+    (
+        Status::Ok,
+        Json(json!({
+            "app_id": app_id,
+            "revision": revision,
+            "previous_revision": previous.as_ref().map(|(n, _)| n),
+            "changes": diff(previous.as_ref().map(|(_, s)| s), &current),
+        })),
+    )
+}
+
+/// Restore an app's listing fields from a stored revision. Admin only.
+/// The rollback itself becomes a new revision.
+#[post("/apps/<app_id>/revisions/<revision>/rollback")]
+pub fn rollback_revision(
+    key: AuthenticatedKey,
+    app_id: &str,
+    revision: i64,
+    db: &rocket::State<DbState>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::new(ErrorCode::AdminRequired, "Only admins can roll back apps").into();
+    }
+
+    let conn = db.conn();
+
+    let app_id = match resolve_app(&conn, app_id) {
+        Some(id) => id,
+        None => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+
+    if let Err(err) = crate::routes::ensure_not_archived(&conn, &app_id) {
+        return err.into();
+    }
+
+    let target = match load(&conn, &app_id, revision) {
+        Some(s) => s,
+        None => return ApiError::new(ErrorCode::NotFound, "Revision not found").into(),
+    };
+
+    let mut sets: Vec<String> = Vec::new();
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+    for field in RESTORABLE_FIELDS {
+        let value = match target.get(*field) {
+            Some(v) => v,
+            None => continue,
+        };
+        let sql_value: Option<String> = match value {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        };
+        if *field == "category" {
+            if let Some(category) = &sql_value {
+                let _ = crate::categories::ensure_category(&conn, category);
+            }
+        }
+        params.push(Box::new(sql_value));
+        sets.push(format!("{} = ?{}", field, params.len()));
+    }
+    sets.push("revision = revision + 1".to_string());
+    sets.push("updated_at = datetime('now')".to_string());
+    params.push(Box::new(app_id.clone()));
+    let sql = format!("UPDATE apps SET {} WHERE id = ?{}", sets.join(", "), params.len());
+
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    let restored = tx.execute(&sql, rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())));
+    if let Err(e) = restored {
+        eprintln!("❌ Rollback of {app_id} to revision {revision} failed: {e}");
+        return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+    }
+    let _ = record(&tx, &app_id, "admin", Some(&key.id));
+    let new_revision = current_revision(&tx, &app_id).unwrap_or(0);
+
+    let event = AppEvent {
+        event: "app.updated".to_string(),
+        data: json!({
+            "app_id": app_id,
+            "rolled_back_to": revision,
+            "revision": new_revision,
+        }),
+    };
+    match outbox::commit(tx, [event]) {
+        Ok(queued) => bus.dispatch(queued),
+        Err(err) => return err.into(),
+    }
+
+    (
+        Status::Ok,
+        Json(json!({
+            "message": "App rolled back",
+            "app_id": app_id,
+            "rolled_back_to": revision,
+            "revision": new_revision,
+        })),
+    )
+}
+
+/// The request's `If-Match` header, if any.
+#[derive(Debug, Default)]
+pub struct IfMatch(pub Option<String>);
+
+impl IfMatch {
+    /// Whether the header allows a write against `revision`.
+    /// A missing header or `*` always matches; otherwise any listed ETag
+    /// (weak `W/` prefixes are ignored) must equal the revision.
+    pub fn matches(&self, revision: i64) -> bool {
+        let header = match &self.0 {
+            Some(h) => h,
+            None => return true,
+        };
+        let expected = revision.to_string();
+        header.split(',').any(|tag| {
+            let tag = tag.trim();
+            let tag = tag.strip_prefix("W/").unwrap_or(tag);
+            tag == "*" || tag.trim_matches('"') == expected
+        })
+    }
+
+    /// `412 PRECONDITION_FAILED` unless the header matches `revision`.
+    pub fn check(&self, revision: i64) -> Result<(), ApiError> {
+        if self.matches(revision) {
+            return Ok(());
+        }
+        Err(ApiError::new(
+            ErrorCode::PreconditionFailed,
+            "App was modified since it was fetched. Re-fetch it and retry.",
+        )
+        .with("current_revision", revision))
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfMatch {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let header = request.headers().get_one("If-Match").map(|h| h.to_string());
+        Outcome::Success(IfMatch(header))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_snapshots() {
+        let before = json!({ "name": "A", "tags": ["x"], "is_featured": false });
+        let after = json!({ "name": "B", "tags": ["x"], "is_featured": false });
+        let changes = diff(Some(&before), &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes["name"], json!({ "from": "A", "to": "B" }));
+
+        let initial = diff(None, &after);
+        assert_eq!(initial["tags"]["to"], json!(["x"]));
+    }
+
+    #[test]
+    fn matches_if_match_values() {
+        assert!(IfMatch(None).matches(3));
+        assert!(IfMatch(Some("*".into())).matches(3));
+        assert!(IfMatch(Some("\"3\"".into())).matches(3));
+        assert!(IfMatch(Some("W/\"3\"".into())).matches(3));
+        assert!(IfMatch(Some("\"1\", \"3\"".into())).matches(3));
+        assert!(!IfMatch(Some("\"2\"".into())).matches(3));
+        assert!(!IfMatch(Some("garbage".into())).matches(3));
+    }
+}
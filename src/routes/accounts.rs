@@ -0,0 +1,144 @@
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use serde_json::{json, Value};
+
+use crate::accounts::{self, AccountSession, CodeError, Mailer};
+use crate::auth::ClientFingerprint;
+use crate::errors::{ApiError, ErrorCode};
+use crate::models::{MagicLinkRequest, VerifyCodeRequest};
+use crate::rate_limit::LoginThrottle;
+use crate::DbState;
+
+// === Accounts (email magic links) ===
+
+/// 429 for a client IP over its `LOGIN_IP_HOURLY_CAP` on `endpoint`.
+fn throttled(throttle: &LoginThrottle, endpoint: &str, client: &ClientFingerprint) -> Option<(Status, Json<Value>)> {
+    let rl = throttle.check(endpoint, &client.ip_hash);
+    if rl.allowed {
+        return None;
+    }
+    Some(
+        ApiError::new(
+            ErrorCode::LoginLimitExceeded,
+            format!("Too many login requests from this address ({} per hour). Try again later.", rl.limit),
+        )
+        .with("retry_after_secs", rl.reset_secs)
+        .into(),
+    )
+}
+
+/// Email a one-time login code. Always answers 202 for well-formed addresses
+/// so the endpoint can't be used to probe which emails have accounts.
+/// Capped per client IP per hour and per email per `CODE_WINDOW_SECS`.
+#[post("/auth/magic-link", data = "<body>")]
+pub fn request_magic_link(
+    body: Json<MagicLinkRequest>,
+    db: &rocket::State<DbState>,
+    mailer: &rocket::State<Mailer>,
+    throttle: &rocket::State<LoginThrottle>,
+    client: ClientFingerprint,
+) -> (Status, Json<Value>) {
+    let email = accounts::normalize_email(&body.email);
+    if !accounts::is_valid_email(&email) {
+        return ApiError::new(ErrorCode::InvalidEmail, "A valid email address is required").into();
+    }
+    if let Some(resp) = throttled(throttle, "magic-link", &client) {
+        return resp;
+    }
+
+    let code = accounts::generate_code();
+    let conn = db.conn();
+    match accounts::store_code(&conn, &email, &code) {
+        Ok(true) => {}
+        Ok(false) => {
+            return ApiError::new(
+                ErrorCode::LoginLimitExceeded,
+                format!(
+                    "Too many login codes sent to this address ({} per hour). Try again later.",
+                    accounts::MAX_CODES_PER_WINDOW
+                ),
+            )
+            .with("retry_after_secs", accounts::CODE_WINDOW_SECS)
+            .into()
+        }
+        Err(e) => {
+            eprintln!("❌ Store login code failed: {e}");
+            return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+        }
+    }
+    drop(conn);
+
+    mailer.send(
+        &email,
+        "Your App Directory login code",
+        format!(
+            "Your login code is {}\n\nIt expires in {} minutes. If you didn't request it, you can ignore this email.\n",
+            code,
+            accounts::CODE_TTL_SECS / 60
+        ),
+    );
+
+    (
+        Status::Accepted,
+        Json(json!({
+            "message": "A login code has been sent. Exchange it at POST /api/v1/auth/verify.",
+            "expires_in_secs": accounts::CODE_TTL_SECS,
+        })),
+    )
+}
+
+/// Exchange an emailed login code for a session token. Creates the account on first login.
+/// Capped per client IP per hour, on top of the per-code attempt limit.
+#[post("/auth/verify", data = "<body>")]
+pub fn verify_magic_link(
+    body: Json<VerifyCodeRequest>,
+    db: &rocket::State<DbState>,
+    throttle: &rocket::State<LoginThrottle>,
+    client: ClientFingerprint,
+) -> (Status, Json<Value>) {
+    if let Some(resp) = throttled(throttle, "verify", &client) {
+        return resp;
+    }
+    let email = accounts::normalize_email(&body.email);
+    let conn = db.conn();
+
+    match accounts::consume_code(&conn, &email, &body.code) {
+        Ok(()) => {}
+        Err(CodeError::Invalid) => {
+            return ApiError::new(ErrorCode::InvalidCode, "Login code is incorrect").into()
+        }
+        Err(CodeError::Expired) => {
+            return ApiError::new(
+                ErrorCode::CodeExpired,
+                "Login code expired or used up. Request a new one.",
+            )
+            .into()
+        }
+    }
+
+    match accounts::start_session(&conn, &email) {
+        Ok((account_id, token, expires_at)) => (
+            Status::Ok,
+            Json(json!({
+                "account_id": account_id,
+                "email": email,
+                "session_token": token,
+                "expires_at": expires_at,
+                "message": "Signed in. Send the token as X-Session-Token or Authorization: Bearer."
+            })),
+        ),
+        Err(e) => {
+            eprintln!("❌ Start session failed: {e}");
+            ApiError::new(ErrorCode::DbError, "Internal server error").into()
+        }
+    }
+}
+
+/// The signed-in account.
+#[get("/auth/me")]
+pub fn get_account(session: AccountSession) -> Json<Value> {
+    Json(json!({
+        "account_id": session.account_id,
+        "email": session.email,
+    }))
+}
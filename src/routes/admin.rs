@@ -2,9 +2,22 @@ use rocket::http::Status;
 use rocket::serde::json::Json;
 use serde_json::{json, Value};
 
-use crate::auth::AuthenticatedKey;
+use crate::auth::{Actor, AuthenticatedKey};
+use crate::backup::BackupConfig;
+use crate::discovery;
+use crate::erasure::{self, Subject};
+use crate::errors::{ApiError, ErrorCode};
 use crate::events::{AppEvent, EventBus};
-use crate::DbState;
+use crate::maintenance::Maintenance;
+use crate::moderation;
+use crate::outbox;
+use crate::rate_limit::{RateLimiter, ReviewThrottle};
+use crate::reload::Reloader;
+use crate::revisions;
+use crate::scheduler::ScheduleStatus;
+use crate::tags;
+use crate::validation::FieldLimits;
+use crate::{DbState, StaticDir};
 
 #[derive(Debug, serde::Deserialize)]
 pub struct ApproveRequest {
@@ -16,6 +29,25 @@ pub struct RejectRequest {
     pub reason: String,
 }
 
+#[derive(Debug, serde::Deserialize)]
+pub struct AssignRequest {
+    /// Admin API key id to review the app; `null` clears the assignment.
+    pub reviewer_key_id: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct BulkModerationRequest {
+    pub action: String,
+    pub app_ids: Vec<String>,
+    pub reason: Option<String>,
+}
+
+/// Actions supported by the bulk moderation endpoint.
+const BULK_ACTIONS: &[&str] = &["reject", "delete", "deprecate", "feature"];
+
+/// Maximum apps per bulk request.
+const BULK_MAX_ITEMS: usize = 100;
+
 #[derive(Debug, serde::Deserialize)]
 pub struct DeprecateRequest {
     pub reason: String,
@@ -26,18 +58,16 @@ pub struct DeprecateRequest {
 /// Approve a pending app. Admin only.
 #[post("/apps/<id>/approve", format = "json", data = "<body>")]
 pub fn approve_app(
-    key: AuthenticatedKey,
+    actor: Actor,
     id: &str,
     body: Json<ApproveRequest>,
     db: &rocket::State<DbState>,
     bus: &rocket::State<EventBus>,
 ) -> (Status, Json<Value>) {
-    if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(json!({ "error": "ADMIN_REQUIRED", "message": "Only admins can approve apps" })),
-        );
-    }
+    let key = match actor.require_admin("Only admins can approve apps") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
 
     let conn = db.conn();
 
@@ -50,35 +80,57 @@ pub fn approve_app(
     let (current_status, app_name) = match current {
         Ok(v) => v,
         Err(_) => {
-            return (
-                Status::NotFound,
-                Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-            )
+            return ApiError::new(ErrorCode::NotFound, "App not found").into()
         }
     };
 
     if current_status == "approved" {
-        return (
-            Status::Conflict,
-            Json(json!({ "error": "ALREADY_APPROVED", "message": "App is already approved" })),
-        );
+        return ApiError::new(ErrorCode::AlreadyApproved, "App is already approved").into();
+    }
+
+    if current_status == "draft" {
+        return ApiError::new(
+            ErrorCode::InvalidTransition,
+            "Cannot approve a draft. Its owner must publish it first.",
+        )
+        .into();
+    }
+
+    if current_status == "archived" {
+        return ApiError::new(
+            ErrorCode::Archived,
+            "Cannot approve an archived app. Unarchive it first.",
+        )
+        .into();
     }
 
     if current_status == "deprecated" {
-        return (
-            Status::Conflict,
-            Json(
-                json!({ "error": "INVALID_TRANSITION", "message": "Cannot approve a deprecated app. Unset deprecated status first." }),
-            ),
-        );
+        return ApiError::new(
+            ErrorCode::InvalidTransition,
+            "Cannot approve a deprecated app. Unset deprecated status first.",
+        )
+        .into();
     }
 
-    match conn.execute(
-        "UPDATE apps SET status = 'approved', review_note = ?1, reviewed_by = ?2, reviewed_at = datetime('now'), updated_at = datetime('now') WHERE id = ?3",
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    match tx.execute(
+        "UPDATE apps SET status = 'approved', review_note = ?1, reviewed_by = ?2, reviewed_at = datetime('now'), revision = revision + 1, updated_at = datetime('now') WHERE id = ?3",
         rusqlite::params![body.note, key.id, id],
     ) {
         Ok(1) => {
-            bus.emit(AppEvent {
+            let _ = revisions::record(&tx, id, "admin", Some(&key.id));
+            super::apps::link_original(&tx, id);
+            moderation::log_review(
+                &tx,
+                "review.approve",
+                &key.id,
+                id,
+                json!({ "previous_status": current_status, "note": body.note }),
+            );
+            let event = AppEvent {
                 event: "app.approved".to_string(),
                 data: json!({
                     "app_id": id,
@@ -87,7 +139,11 @@ pub fn approve_app(
                     "reviewed_by": key.id,
                     "note": body.note,
                 }),
-            });
+            };
+            match outbox::commit(tx, [event]) {
+                Ok(queued) => bus.dispatch(queued),
+                Err(err) => return err.into(),
+            }
 
             (
                 Status::Ok,
@@ -98,40 +154,105 @@ pub fn approve_app(
                 })),
             )
         }
-        Ok(_) => (
-            Status::NotFound,
-            Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-        ),
-        Err(_) => (
-            Status::InternalServerError,
-            Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
-        ),
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "App not found").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
     }
 }
 
+/// Assign a pending app to an admin key for review, or clear the
+/// assignment with `null`. Admin only.
+#[post("/apps/<id>/assign", format = "json", data = "<body>")]
+pub fn assign_reviewer(
+    actor: Actor,
+    id: &str,
+    body: Json<AssignRequest>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can assign reviewers") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+
+    let conn = db.conn();
+
+    let current: Result<(String, Option<String>), _> = conn.query_row(
+        "SELECT status, assigned_reviewer_key_id FROM apps WHERE id = ?1",
+        rusqlite::params![id],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    );
+    let (status, previous) = match current {
+        Ok(v) => v,
+        Err(_) => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+    if status != "pending" {
+        return ApiError::from(ErrorCode::NotPending).with("status", status).into();
+    }
+
+    let reviewer = body.reviewer_key_id.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if let Some(reviewer) = reviewer {
+        let is_active_admin: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM api_keys WHERE id = ?1 AND is_admin = 1 AND revoked = 0",
+                rusqlite::params![reviewer],
+                |r| r.get::<_, i64>(0),
+            )
+            .unwrap_or(0)
+            > 0;
+        if !is_active_admin {
+            return ApiError::from(ErrorCode::InvalidReviewer).with("reviewer_key_id", reviewer).into();
+        }
+    }
+
+    let assigned_at: Option<String> = match conn.query_row(
+        "UPDATE apps SET assigned_reviewer_key_id = ?1, assigned_at = CASE WHEN ?1 IS NULL THEN NULL ELSE datetime('now') END
+         WHERE id = ?2 RETURNING assigned_at",
+        rusqlite::params![reviewer, id],
+        |r| r.get(0),
+    ) {
+        Ok(at) => at,
+        Err(_) => return ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    };
+
+    let _ = conn.execute(
+        "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, 'review.assign', ?2, ?3)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            key.id,
+            json!({ "app_id": id, "reviewer_key_id": reviewer, "previous_reviewer_key_id": previous }).to_string()
+        ],
+    );
+
+    (
+        Status::Ok,
+        Json(json!({
+            "app_id": id,
+            "assigned_reviewer_key_id": reviewer,
+            "assigned_at": assigned_at,
+            "previous_reviewer_key_id": previous,
+        })),
+    )
+}
+
 /// Reject a pending app. Admin only. Requires a reason.
 #[post("/apps/<id>/reject", format = "json", data = "<body>")]
 pub fn reject_app(
-    key: AuthenticatedKey,
+    actor: Actor,
     id: &str,
     body: Json<RejectRequest>,
     db: &rocket::State<DbState>,
     bus: &rocket::State<EventBus>,
 ) -> (Status, Json<Value>) {
-    if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(json!({ "error": "ADMIN_REQUIRED", "message": "Only admins can reject apps" })),
-        );
-    }
+    let key = match actor.require_admin("Only admins can reject apps") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
 
     if body.reason.trim().is_empty() {
-        return (
-            Status::BadRequest,
-            Json(
-                json!({ "error": "REASON_REQUIRED", "message": "A reason is required when rejecting an app" }),
-            ),
-        );
+        return ApiError::new(
+            ErrorCode::ReasonRequired,
+            "A reason is required when rejecting an app",
+        )
+        .into();
     }
 
     let conn = db.conn();
@@ -145,35 +266,43 @@ pub fn reject_app(
     let (current_status, app_name) = match current {
         Ok(v) => v,
         Err(_) => {
-            return (
-                Status::NotFound,
-                Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-            )
+            return ApiError::new(ErrorCode::NotFound, "App not found").into()
         }
     };
 
     if current_status == "rejected" {
-        return (
-            Status::Conflict,
-            Json(json!({ "error": "ALREADY_REJECTED", "message": "App is already rejected" })),
-        );
+        return ApiError::new(ErrorCode::AlreadyRejected, "App is already rejected").into();
     }
 
     if current_status == "deprecated" {
-        return (
-            Status::Conflict,
-            Json(
-                json!({ "error": "INVALID_TRANSITION", "message": "Cannot reject a deprecated app" }),
-            ),
-        );
+        return ApiError::new(ErrorCode::InvalidTransition, "Cannot reject a deprecated app").into();
+    }
+    if current_status == "draft" {
+        return ApiError::new(ErrorCode::InvalidTransition, "Cannot reject a draft that hasn't been published").into();
     }
 
-    match conn.execute(
-        "UPDATE apps SET status = 'rejected', review_note = ?1, reviewed_by = ?2, reviewed_at = datetime('now'), updated_at = datetime('now') WHERE id = ?3",
+    if current_status == "archived" {
+        return ApiError::new(ErrorCode::Archived, "Cannot reject an archived app").into();
+    }
+
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    match tx.execute(
+        "UPDATE apps SET status = 'rejected', review_note = ?1, reviewed_by = ?2, reviewed_at = datetime('now'), revision = revision + 1, updated_at = datetime('now') WHERE id = ?3",
         rusqlite::params![body.reason, key.id, id],
     ) {
         Ok(1) => {
-            bus.emit(AppEvent {
+            let _ = revisions::record(&tx, id, "admin", Some(&key.id));
+            moderation::log_review(
+                &tx,
+                "review.reject",
+                &key.id,
+                id,
+                json!({ "previous_status": current_status, "reason": body.reason }),
+            );
+            let event = AppEvent {
                 event: "app.rejected".to_string(),
                 data: json!({
                     "app_id": id,
@@ -182,7 +311,11 @@ pub fn reject_app(
                     "reviewed_by": key.id,
                     "reason": body.reason,
                 }),
-            });
+            };
+            match outbox::commit(tx, [event]) {
+                Ok(queued) => bus.dispatch(queued),
+                Err(err) => return err.into(),
+            }
 
             (
                 Status::Ok,
@@ -194,40 +327,31 @@ pub fn reject_app(
                 })),
             )
         }
-        Ok(_) => (
-            Status::NotFound,
-            Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-        ),
-        Err(_) => (
-            Status::InternalServerError,
-            Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
-        ),
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "App not found").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
     }
 }
 
 /// Deprecate an app. Admin only.
 #[post("/apps/<id>/deprecate", format = "json", data = "<body>")]
 pub fn deprecate_app(
-    key: AuthenticatedKey,
+    actor: Actor,
     id: &str,
     body: Json<DeprecateRequest>,
     db: &rocket::State<DbState>,
     bus: &rocket::State<EventBus>,
 ) -> (Status, Json<Value>) {
-    if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(json!({ "error": "ADMIN_REQUIRED", "message": "Only admins can deprecate apps" })),
-        );
-    }
+    let key = match actor.require_admin("Only admins can deprecate apps") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
 
     if body.reason.trim().is_empty() {
-        return (
-            Status::BadRequest,
-            Json(
-                json!({ "error": "REASON_REQUIRED", "message": "A reason is required when deprecating an app" }),
-            ),
-        );
+        return ApiError::new(
+            ErrorCode::ReasonRequired,
+            "A reason is required when deprecating an app",
+        )
+        .into();
     }
 
     let conn = db.conn();
@@ -243,23 +367,15 @@ pub fn deprecate_app(
             .unwrap_or(false);
 
         if !exists {
-            return (
-                Status::BadRequest,
-                Json(json!({
-                    "error": "INVALID_REPLACEMENT",
-                    "message": "Replacement app not found"
-                })),
-            );
+            return ApiError::new(ErrorCode::InvalidReplacement, "Replacement app not found").into();
         }
 
         if replacement_id == id {
-            return (
-                Status::BadRequest,
-                Json(json!({
-                    "error": "INVALID_REPLACEMENT",
-                    "message": "An app cannot replace itself"
-                })),
-            );
+            return ApiError::new(
+                ErrorCode::InvalidReplacement,
+                "An app cannot replace itself",
+            )
+            .into();
         }
     }
 
@@ -272,26 +388,33 @@ pub fn deprecate_app(
     let (current_status, app_name) = match current {
         Ok(v) => v,
         Err(_) => {
-            return (
-                Status::NotFound,
-                Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-            )
+            return ApiError::new(ErrorCode::NotFound, "App not found").into()
         }
     };
 
     if current_status == "deprecated" {
-        return (
-            Status::Conflict,
-            Json(json!({ "error": "ALREADY_DEPRECATED", "message": "App is already deprecated" })),
-        );
+        return ApiError::new(ErrorCode::AlreadyDeprecated, "App is already deprecated").into();
+    }
+
+    if current_status == "archived" {
+        return ApiError::new(ErrorCode::Archived, "Cannot deprecate an archived app").into();
+    }
+
+    if current_status == "draft" {
+        return ApiError::new(ErrorCode::InvalidTransition, "Cannot deprecate a draft that hasn't been published").into();
     }
 
-    match conn.execute(
-        "UPDATE apps SET status = 'deprecated', deprecated_reason = ?1, deprecated_by = ?2, deprecated_at = datetime('now'), replacement_app_id = ?3, sunset_at = ?4, updated_at = datetime('now') WHERE id = ?5",
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    match tx.execute(
+        "UPDATE apps SET status = 'deprecated', deprecated_reason = ?1, deprecated_by = ?2, deprecated_at = datetime('now'), replacement_app_id = ?3, sunset_at = ?4, revision = revision + 1, updated_at = datetime('now') WHERE id = ?5",
         rusqlite::params![body.reason, key.id, body.replacement_app_id, body.sunset_at, id],
     ) {
         Ok(1) => {
-            bus.emit(AppEvent {
+            let _ = revisions::record(&tx, id, "admin", Some(&key.id));
+            let event = AppEvent {
                 event: "app.deprecated".to_string(),
                 data: json!({
                     "app_id": id,
@@ -302,7 +425,11 @@ pub fn deprecate_app(
                     "replacement_app_id": body.replacement_app_id,
                     "sunset_at": body.sunset_at,
                 }),
-            });
+            };
+            match outbox::commit(tx, [event]) {
+                Ok(queued) => bus.dispatch(queued),
+                Err(err) => return err.into(),
+            }
 
             (
                 Status::Ok,
@@ -316,33 +443,23 @@ pub fn deprecate_app(
                 })),
             )
         }
-        Ok(_) => (
-            Status::NotFound,
-            Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-        ),
-        Err(_) => (
-            Status::InternalServerError,
-            Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
-        ),
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "App not found").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
     }
 }
 
 /// Undeprecate an app. Admin only.
 #[post("/apps/<id>/undeprecate")]
 pub fn undeprecate_app(
-    key: AuthenticatedKey,
+    actor: Actor,
     id: &str,
     db: &rocket::State<DbState>,
     bus: &rocket::State<EventBus>,
 ) -> (Status, Json<Value>) {
-    if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(
-                json!({ "error": "ADMIN_REQUIRED", "message": "Only admins can undeprecate apps" }),
-            ),
-        );
-    }
+    let key = match actor.require_admin("Only admins can undeprecate apps") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
 
     let conn = db.conn();
 
@@ -355,26 +472,25 @@ pub fn undeprecate_app(
     let (current_status, app_name) = match current {
         Ok(v) => v,
         Err(_) => {
-            return (
-                Status::NotFound,
-                Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-            )
+            return ApiError::new(ErrorCode::NotFound, "App not found").into()
         }
     };
 
     if current_status != "deprecated" {
-        return (
-            Status::Conflict,
-            Json(json!({ "error": "NOT_DEPRECATED", "message": "App is not deprecated" })),
-        );
+        return ApiError::new(ErrorCode::NotDeprecated, "App is not deprecated").into();
     }
 
-    match conn.execute(
-        "UPDATE apps SET status = 'approved', deprecated_reason = NULL, deprecated_by = NULL, deprecated_at = NULL, replacement_app_id = NULL, sunset_at = NULL, updated_at = datetime('now') WHERE id = ?1",
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    match tx.execute(
+        "UPDATE apps SET status = 'approved', deprecated_reason = NULL, deprecated_by = NULL, deprecated_at = NULL, replacement_app_id = NULL, sunset_at = NULL, revision = revision + 1, updated_at = datetime('now') WHERE id = ?1",
         rusqlite::params![id],
     ) {
         Ok(1) => {
-            bus.emit(AppEvent {
+            let _ = revisions::record(&tx, id, "admin", Some(&key.id));
+            let event = AppEvent {
                 event: "app.undeprecated".to_string(),
                 data: json!({
                     "app_id": id,
@@ -382,7 +498,11 @@ pub fn undeprecate_app(
                     "restored_to": "approved",
                     "undeprecated_by": key.id,
                 }),
-            });
+            };
+            match outbox::commit(tx, [event]) {
+                Ok(queued) => bus.dispatch(queued),
+                Err(err) => return err.into(),
+            }
 
             (
                 Status::Ok,
@@ -393,13 +513,943 @@ pub fn undeprecate_app(
                 })),
             )
         }
-        Ok(_) => (
-            Status::NotFound,
-            Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-        ),
-        Err(_) => (
-            Status::InternalServerError,
-            Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "App not found").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+/// Archive an app. Admin only.
+///
+/// Archived apps are frozen (no edits, translations, or reviews) and hidden
+/// from default listings and search, but stay reachable by id or slug.
+#[post("/apps/<id>/archive")]
+pub fn archive_app(
+    actor: Actor,
+    id: &str,
+    db: &rocket::State<DbState>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can archive apps") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+
+    let conn = db.conn();
+
+    let current: Result<(String, String), _> = conn.query_row(
+        "SELECT status, name FROM apps WHERE id = ?1",
+        rusqlite::params![id],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    );
+
+    let (current_status, app_name) = match current {
+        Ok(v) => v,
+        Err(_) => {
+            return ApiError::new(ErrorCode::NotFound, "App not found").into()
+        }
+    };
+
+    if current_status == "archived" {
+        return ApiError::new(ErrorCode::AlreadyArchived, "App is already archived").into();
+    }
+
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    match tx.execute(
+        "UPDATE apps SET status = 'archived', archived_at = datetime('now'), archived_by = ?1, archived_from = ?2, is_featured = 0, revision = revision + 1, updated_at = datetime('now') WHERE id = ?3",
+        rusqlite::params![key.id, current_status, id],
+    ) {
+        Ok(1) => {
+            let _ = revisions::record(&tx, id, "admin", Some(&key.id));
+            let event = AppEvent {
+                event: "app.archived".to_string(),
+                data: json!({
+                    "app_id": id,
+                    "name": app_name,
+                    "previous_status": current_status,
+                    "archived_by": key.id,
+                }),
+            };
+            match outbox::commit(tx, [event]) {
+                Ok(queued) => bus.dispatch(queued),
+                Err(err) => return err.into(),
+            }
+
+            (
+                Status::Ok,
+                Json(json!({
+                    "message": "App archived",
+                    "app_id": id,
+                    "previous_status": current_status,
+                })),
+            )
+        }
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "App not found").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+/// Unarchive an app, restoring the status it had before archiving. Admin only.
+#[post("/apps/<id>/unarchive")]
+pub fn unarchive_app(
+    actor: Actor,
+    id: &str,
+    db: &rocket::State<DbState>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can unarchive apps") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+
+    let conn = db.conn();
+
+    let current: Result<(String, String, Option<String>), _> = conn.query_row(
+        "SELECT status, name, archived_from FROM apps WHERE id = ?1",
+        rusqlite::params![id],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    );
+
+    let (current_status, app_name, archived_from) = match current {
+        Ok(v) => v,
+        Err(_) => {
+            return ApiError::new(ErrorCode::NotFound, "App not found").into()
+        }
+    };
+
+    if current_status != "archived" {
+        return ApiError::new(ErrorCode::NotArchived, "App is not archived").into();
+    }
+
+    let restored_to = archived_from
+        .filter(|s| s != "archived")
+        .unwrap_or_else(|| "approved".to_string());
+
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    match tx.execute(
+        "UPDATE apps SET status = ?1, archived_at = NULL, archived_by = NULL, archived_from = NULL, revision = revision + 1, updated_at = datetime('now') WHERE id = ?2",
+        rusqlite::params![restored_to, id],
+    ) {
+        Ok(1) => {
+            let _ = revisions::record(&tx, id, "admin", Some(&key.id));
+            let event = AppEvent {
+                event: "app.unarchived".to_string(),
+                data: json!({
+                    "app_id": id,
+                    "name": app_name,
+                    "restored_to": restored_to,
+                    "unarchived_by": key.id,
+                }),
+            };
+            match outbox::commit(tx, [event]) {
+                Ok(queued) => bus.dispatch(queued),
+                Err(err) => return err.into(),
+            }
+
+            (
+                Status::Ok,
+                Json(json!({
+                    "message": "App unarchived",
+                    "app_id": id,
+                    "restored_to": restored_to,
+                })),
+            )
+        }
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "App not found").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+/// Apply one moderation action to many apps at once. Admin only.
+///
+/// All changes are applied in a single transaction. Apps that don't exist or
+/// can't make the transition are skipped and reported per item; a single
+/// audit log entry records the whole batch.
+#[post("/apps/bulk", format = "json", data = "<body>")]
+pub fn bulk_moderate(
+    actor: Actor,
+    body: Json<BulkModerationRequest>,
+    db: &rocket::State<DbState>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can perform bulk moderation") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+
+    let action = body.action.as_str();
+    if !BULK_ACTIONS.contains(&action) {
+        return ApiError::new(
+            ErrorCode::InvalidAction,
+            format!("Valid actions: {}", BULK_ACTIONS.join(", ")),
+        )
+        .into();
+    }
+
+    if body.app_ids.is_empty() {
+        return ApiError::new(ErrorCode::NoApps, "app_ids must not be empty").into();
+    }
+
+    if body.app_ids.len() > BULK_MAX_ITEMS {
+        return ApiError::new(
+            ErrorCode::TooManyApps,
+            format!("At most {} apps per bulk request", BULK_MAX_ITEMS),
+        )
+        .into();
+    }
+
+    let reason = body.reason.as_deref().map(str::trim).unwrap_or("");
+    if (action == "reject" || action == "deprecate") && reason.is_empty() {
+        return ApiError::new(
+            ErrorCode::ReasonRequired,
+            format!("A reason is required for bulk {}", action),
+        )
+        .into();
+    }
+
+    let conn = db.conn();
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+
+    let mut results: Vec<Value> = Vec::new();
+    let mut events: Vec<AppEvent> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    for app_id in &body.app_ids {
+        if !seen.insert(app_id.as_str()) {
+            continue;
+        }
+
+        let current: Result<(String, String), _> = tx.query_row(
+            "SELECT status, name FROM apps WHERE id = ?1",
+            rusqlite::params![app_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        );
+
+        let (current_status, app_name) = match current {
+            Ok(v) => v,
+            Err(_) => {
+                results.push(json!({ "app_id": app_id, "ok": false, "error": "NOT_FOUND" }));
+                continue;
+            }
+        };
+
+        // Same transition rules as the single-app endpoints
+        let skip = match action {
+            "reject" if current_status == "rejected" => Some("ALREADY_REJECTED"),
+            "reject" if current_status == "deprecated" || current_status == "draft" => Some("INVALID_TRANSITION"),
+            "deprecate" if current_status == "deprecated" => Some("ALREADY_DEPRECATED"),
+            "deprecate" if current_status == "draft" => Some("INVALID_TRANSITION"),
+            "reject" | "deprecate" | "feature" if current_status == "archived" => Some("ARCHIVED"),
+            _ => None,
+        };
+        if let Some(code) = skip {
+            results.push(json!({ "app_id": app_id, "ok": false, "error": code }));
+            continue;
+        }
+
+        let applied = match action {
+            "reject" => tx.execute(
+                "UPDATE apps SET status = 'rejected', review_note = ?1, reviewed_by = ?2, reviewed_at = datetime('now'), revision = revision + 1, updated_at = datetime('now') WHERE id = ?3",
+                rusqlite::params![reason, key.id, app_id],
+            ),
+            "deprecate" => tx.execute(
+                "UPDATE apps SET status = 'deprecated', deprecated_reason = ?1, deprecated_by = ?2, deprecated_at = datetime('now'), revision = revision + 1, updated_at = datetime('now') WHERE id = ?3",
+                rusqlite::params![reason, key.id, app_id],
+            ),
+            "feature" => tx.execute(
+                "UPDATE apps SET is_featured = 1, revision = revision + 1, updated_at = datetime('now') WHERE id = ?1",
+                rusqlite::params![app_id],
+            ),
+            _ => super::apps::delete_app_records(&tx, app_id),
+        };
+
+        if let Err(e) = applied {
+            eprintln!("❌ Bulk {action} failed on {app_id}: {e}");
+            return ApiError::new(
+                ErrorCode::DbError,
+                "Internal server error; no changes were applied",
+            )
+            .into();
+        }
+
+        if action != "delete" {
+            let _ = revisions::record(&tx, app_id, "admin", Some(&key.id));
+        }
+
+        let (event, data) = match action {
+            "reject" => (
+                "app.rejected",
+                json!({ "app_id": app_id, "name": app_name, "previous_status": current_status, "reviewed_by": key.id, "reason": reason, "bulk": true }),
+            ),
+            "deprecate" => (
+                "app.deprecated",
+                json!({ "app_id": app_id, "name": app_name, "previous_status": current_status, "deprecated_by": key.id, "reason": reason, "bulk": true }),
+            ),
+            "feature" => ("app.updated", json!({ "app_id": app_id, "is_featured": true, "bulk": true })),
+            _ => ("app.deleted", json!({ "app_id": app_id, "bulk": true })),
+        };
+        events.push(AppEvent {
+            event: event.to_string(),
+            data,
+        });
+
+        results.push(json!({ "app_id": app_id, "ok": true, "previous_status": current_status }));
+    }
+
+    let succeeded = results.iter().filter(|r| r["ok"] == true).count();
+    let audit_id = uuid::Uuid::new_v4().to_string();
+    let details = json!({
+        "app_ids": body.app_ids,
+        "reason": body.reason,
+        "succeeded": succeeded,
+        "failed": results.len() - succeeded,
+    });
+
+    let queued = tx
+        .execute(
+            "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![audit_id, format!("bulk.{}", action), key.id, details.to_string()],
+        )
+        .ok()
+        .and_then(|_| outbox::commit(tx, events).ok());
+    match queued {
+        Some(queued) => bus.dispatch(queued),
+        None => {
+            return ApiError::new(
+                ErrorCode::DbError,
+                "Internal server error; no changes were applied",
+            )
+            .into()
+        }
+    }
+
+    (
+        Status::Ok,
+        Json(json!({
+            "action": action,
+            "audit_id": audit_id,
+            "succeeded": succeeded,
+            "failed": results.len() - succeeded,
+            "results": results,
+        })),
+    )
+}
+
+/// Submissions rejected by the content filter, newest first. Admin only.
+#[get("/admin/content-rejections?<kind>&<page>&<per_page>")]
+pub fn list_content_rejections(
+    actor: Actor,
+    kind: Option<String>,
+    page: Option<i64>,
+    per_page: Option<i64>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    if let Err(err) = actor.require_admin("Only admins can view rejected content") {
+        return err.into();
+    }
+
+    let conn = db.conn();
+
+    let page = page.unwrap_or(1).max(1);
+    let per_page = per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * per_page;
+
+    let total: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM content_rejections WHERE ?1 IS NULL OR kind = ?1",
+            rusqlite::params![kind],
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, kind, app_id, field, rule, reason, content, submitted_by_key_id, created_at
+             FROM content_rejections WHERE ?1 IS NULL OR kind = ?1
+             ORDER BY created_at DESC, rowid DESC LIMIT ?2 OFFSET ?3",
+        )
+        .unwrap();
+
+    let rejections: Vec<Value> = stmt
+        .query_map(rusqlite::params![kind, per_page, offset], |row| {
+            let content: String = row.get(6)?;
+            Ok(json!({
+                "id": row.get::<_, String>(0)?,
+                "kind": row.get::<_, String>(1)?,
+                "app_id": row.get::<_, Option<String>>(2)?,
+                "field": row.get::<_, String>(3)?,
+                "rule": row.get::<_, String>(4)?,
+                "reason": row.get::<_, String>(5)?,
+                "content": serde_json::from_str::<Value>(&content).unwrap_or(Value::Null),
+                "submitted_by_key_id": row.get::<_, Option<String>>(7)?,
+                "created_at": row.get::<_, String>(8)?,
+            }))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+
+    (
+        Status::Ok,
+        Json(json!({
+            "rejections": rejections,
+            "total": total,
+            "page": page,
+            "per_page": per_page,
+        })),
+    )
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct EraseRequest {
+    pub key_id: Option<String>,
+    pub fingerprint: Option<String>,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
+/// Erase a data subject's records for GDPR-style requests. Admin only.
+///
+/// Takes exactly one of `key_id`, `fingerprint` or `email`. By default the
+/// subject's reviews, views, submissions, rejected content and audit entries
+/// are deleted; with `anonymize` they are kept with identifiers scrubbed.
+/// The erasure itself is audited without the subject's identifier.
+#[post("/admin/erase", format = "json", data = "<body>")]
+pub fn erase_subject(
+    actor: Actor,
+    body: Json<EraseRequest>,
+    db: &rocket::State<DbState>,
+    views: &rocket::State<crate::stats::ViewRecorder>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can erase data") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+
+    let given = |v: &Option<String>| v.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+    let subjects: Vec<Subject> = [
+        given(&body.key_id).map(Subject::Key),
+        given(&body.fingerprint).map(Subject::Fingerprint),
+        given(&body.email).map(Subject::Email),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let subject = match subjects.as_slice() {
+        [subject] => subject.clone(),
+        _ => return ApiError::from(ErrorCode::InvalidSubject).into(),
+    };
+
+    let conn = db.conn();
+    // Write queued views first so the subject's latest views are erased too
+    views.flush(&conn);
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+
+    let report = match erasure::erase(&tx, &subject, body.anonymize) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("❌ Erasure of {} failed: {e}", subject.kind());
+            return ApiError::new(
+                ErrorCode::DbError,
+                "Internal server error; no changes were applied",
+            )
+            .into();
+        }
+    };
+
+    let mode = if body.anonymize { "anonymize" } else { "delete" };
+    let audit_id = uuid::Uuid::new_v4().to_string();
+    let details = json!({
+        "subject": subject.kind(),
+        "mode": mode,
+        "counts": report.counts(),
+    });
+    let events = report.submissions.iter().filter(|_| !body.anonymize).map(|app_id| AppEvent {
+        event: "app.deleted".to_string(),
+        data: json!({ "app_id": app_id, "erasure": true }),
+    });
+    let queued = tx
+        .execute(
+            "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, 'admin.erase', ?2, ?3)",
+            rusqlite::params![audit_id, key.id, details.to_string()],
+        )
+        .ok()
+        .and_then(|_| outbox::commit(tx, events).ok());
+    match queued {
+        Some(queued) => bus.dispatch(queued),
+        None => {
+            return ApiError::new(
+                ErrorCode::DbError,
+                "Internal server error; no changes were applied",
+            )
+            .into()
+        }
+    }
+
+    (
+        Status::Ok,
+        Json(json!({
+            "subject": subject.kind(),
+            "mode": mode,
+            "audit_id": audit_id,
+            "counts": report.counts(),
+            "app_ids": report.submissions,
+        })),
+    )
+}
+
+/// Reclaim free space and refresh planner statistics. Admin only.
+///
+/// `vacuum` and `analyze` both default to true. The database is locked
+/// while this runs, so schedule it for a maintenance window.
+#[post("/admin/db/optimize?<vacuum>&<analyze>")]
+pub fn optimize_db(
+    actor: Actor,
+    vacuum: Option<bool>,
+    analyze: Option<bool>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can optimize the database") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+    let (vacuum, analyze) = (vacuum.unwrap_or(true), analyze.unwrap_or(true));
+    if !vacuum && !analyze {
+        return ApiError::new(ErrorCode::NoChanges, "Nothing to do: vacuum and analyze are both false").into();
+    }
+
+    let conn = db.conn();
+    let report = match crate::db::optimize(&conn, vacuum, analyze) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("❌ Database optimize failed: {e}");
+            return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+        }
+    };
+
+    let result = json!({
+        "vacuumed": report.vacuumed,
+        "analyzed": report.analyzed,
+        "size_bytes_before": report.size_before,
+        "size_bytes_after": report.size_after,
+        "reclaimed_bytes": (report.size_before - report.size_after).max(0),
+        "duration_ms": report.duration_ms,
+    });
+    let _ = conn.execute(
+        "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, 'admin.db_optimize', ?2, ?3)",
+        rusqlite::params![uuid::Uuid::new_v4().to_string(), key.id, result.to_string()],
+    );
+
+    (Status::Ok, Json(result))
+}
+
+/// Re-read reloadable settings from `.env` and the environment without a
+/// restart, like sending the process `SIGHUP`. Admin only.
+#[post("/admin/reload")]
+pub fn reload_config(
+    actor: Actor,
+    db: &rocket::State<DbState>,
+    limiter: &rocket::State<RateLimiter>,
+    throttle: &rocket::State<ReviewThrottle>,
+    schedule: &rocket::State<ScheduleStatus>,
+    static_dir: &rocket::State<StaticDir>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can reload the configuration") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+    let reloader = Reloader {
+        limiter: limiter.inner().clone(),
+        throttle: throttle.inner().clone(),
+        schedule: schedule.inner().clone(),
+        static_dir: static_dir.inner().clone(),
+        bus: bus.inner().clone(),
+    };
+    let report = reloader.reload("api");
+    let _ = db.conn().execute(
+        "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, 'admin.config_reload', ?2, ?3)",
+        rusqlite::params![uuid::Uuid::new_v4().to_string(), key.id, json!({ "changed": report["changed"] }).to_string()],
+    );
+    (Status::Ok, Json(report))
+}
+
+/// Snapshot the database into the backup directory, then drop snapshots
+/// beyond `BACKUP_KEEP`. Admin only.
+#[post("/admin/backup")]
+pub fn create_backup(
+    actor: Actor,
+    db: &rocket::State<DbState>,
+    config: &rocket::State<BackupConfig>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can create backups") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+
+    let conn = db.conn();
+    let (backup, removed) = match crate::backup::create(&conn, config) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("❌ Backup failed: {e}");
+            return ApiError::new(ErrorCode::DbError, "Backup failed").into();
+        }
+    };
+    let _ = conn.execute(
+        "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, 'admin.backup', ?2, ?3)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            key.id,
+            json!({ "name": backup.name, "size_bytes": backup.size_bytes, "removed": removed }).to_string()
+        ],
+    );
+
+    (Status::Created, Json(json!({ "backup": backup, "removed": removed })))
+}
+
+/// List snapshots in the backup directory, newest first. Admin only.
+#[get("/admin/backups")]
+pub fn list_backups(actor: Actor, config: &rocket::State<BackupConfig>) -> (Status, Json<Value>) {
+    if let Err(err) = actor.require_admin("Only admins can list backups") {
+        return err.into();
+    }
+    match crate::backup::list(config) {
+        Ok(backups) => (
+            Status::Ok,
+            Json(json!({
+                "backups": backups,
+                "total": backups.len(),
+                "keep": config.keep,
+            })),
         ),
+        Err(e) => {
+            eprintln!("❌ Listing backups failed: {e}");
+            ApiError::new(ErrorCode::DbError, "Could not read the backup directory").into()
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct MaintenanceRequest {
+    pub enabled: bool,
+    pub message: Option<String>,
+    pub retry_after_secs: Option<u64>,
+}
+
+/// Current maintenance mode settings. Admin only.
+#[get("/admin/maintenance")]
+pub fn get_maintenance(actor: Actor, maintenance: &rocket::State<Maintenance>) -> (Status, Json<Value>) {
+    if let Err(err) = actor.require_admin("Only admins can view maintenance settings") {
+        return err.into();
+    }
+    (Status::Ok, Json(json!(maintenance.state())))
+}
+
+/// Turn maintenance mode on or off. Admin only.
+///
+/// While enabled every other write endpoint answers `503 MAINTENANCE` with
+/// `Retry-After`; this endpoint stays open so maintenance can be ended.
+#[put("/admin/maintenance", format = "json", data = "<body>")]
+pub fn set_maintenance(
+    actor: Actor,
+    body: Json<MaintenanceRequest>,
+    db: &rocket::State<DbState>,
+    maintenance: &rocket::State<Maintenance>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can change maintenance mode") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+    let body = body.into_inner();
+    let state = maintenance.set(body.enabled, body.message, body.retry_after_secs);
+
+    let _ = db.conn().execute(
+        "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, 'admin.maintenance', ?2, ?3)",
+        rusqlite::params![uuid::Uuid::new_v4().to_string(), key.id, json!(state).to_string()],
+    );
+
+    (Status::Ok, Json(json!(state)))
+}
+
+// === Discovery Crawler ===
+
+#[derive(Debug, serde::Deserialize)]
+pub struct DiscoverySeedRequest {
+    /// A skills index, sitemap or llms.txt listing services to propose.
+    pub url: String,
+}
+
+/// Register a seed document for the discovery crawler. Admin only.
+#[post("/admin/discovery/seeds", format = "json", data = "<body>")]
+pub fn add_discovery_seed(
+    actor: Actor,
+    body: Json<DiscoverySeedRequest>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can manage discovery seeds") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+    let url = body.url.trim();
+    if reqwest::Url::parse(url).map_or(true, |u| !matches!(u.scheme(), "http" | "https")) {
+        return ApiError::from(ErrorCode::InvalidUrl).with("field", "url").into();
+    }
+
+    let conn = db.conn();
+    let id = uuid::Uuid::new_v4().to_string();
+    if conn
+        .execute(
+            "INSERT INTO discovery_seeds (id, url, created_by) VALUES (?1, ?2, ?3)",
+            rusqlite::params![id, url, key.id],
+        )
+        .is_err()
+    {
+        return ApiError::from(ErrorCode::SeedExists).with("url", url).into();
+    }
+    let _ = conn.execute(
+        "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, 'discovery.seed_add', ?2, ?3)",
+        rusqlite::params![uuid::Uuid::new_v4().to_string(), key.id, json!({ "seed_id": id, "url": url }).to_string()],
+    );
+    let seed = conn
+        .query_row(
+            &format!("SELECT {} FROM discovery_seeds WHERE id = ?1", discovery::SEED_COLUMNS),
+            [&id],
+            discovery::seed_json,
+        )
+        .unwrap_or_default();
+    (Status::Created, Json(seed))
+}
+
+/// Registered discovery seeds with their last crawl. Admin only.
+#[get("/admin/discovery/seeds")]
+pub fn list_discovery_seeds(actor: Actor, db: &rocket::State<DbState>) -> (Status, Json<Value>) {
+    if let Err(err) = actor.require_admin("Only admins can manage discovery seeds") {
+        return err.into();
+    }
+    let conn = db.conn();
+    let seeds: Vec<Value> = conn
+        .prepare(&format!("SELECT {} FROM discovery_seeds ORDER BY created_at", discovery::SEED_COLUMNS))
+        .and_then(|mut stmt| stmt.query_map([], discovery::seed_json)?.collect())
+        .unwrap_or_default();
+    (Status::Ok, Json(json!({ "seeds": seeds, "total": seeds.len() })))
+}
+
+/// Stop crawling a seed. Listings it proposed stay, and so does the
+/// record of their URLs. Admin only.
+#[delete("/admin/discovery/seeds/<id>")]
+pub fn delete_discovery_seed(actor: Actor, id: &str, db: &rocket::State<DbState>) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can manage discovery seeds") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+    let conn = db.conn();
+    let url: Option<String> = conn
+        .query_row("SELECT url FROM discovery_seeds WHERE id = ?1", [id], |r| r.get(0))
+        .ok();
+    let Some(url) = url else {
+        return ApiError::new(ErrorCode::NotFound, "Discovery seed not found").into();
+    };
+    let _ = conn.execute("DELETE FROM discovery_seeds WHERE id = ?1", [id]);
+    let _ = conn.execute(
+        "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, 'discovery.seed_delete', ?2, ?3)",
+        rusqlite::params![uuid::Uuid::new_v4().to_string(), key.id, json!({ "seed_id": id, "url": url }).to_string()],
+    );
+    (Status::Ok, Json(json!({ "message": "Discovery seed deleted", "id": id })))
+}
+
+/// Crawl a seed now instead of waiting for the scheduler. Admin only.
+#[post("/admin/discovery/seeds/<id>/crawl")]
+pub async fn crawl_discovery_seed(
+    actor: Actor,
+    id: &str,
+    db: &rocket::State<DbState>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    if let Err(err) = actor.require_admin("Only admins can manage discovery seeds") {
+        return err.into();
+    }
+    match discovery::crawl_seed(&db.0, bus, &discovery::client(), id).await {
+        Some(report) => (Status::Ok, Json(report)),
+        None => ApiError::new(ErrorCode::NotFound, "Discovery seed not found").into(),
+    }
+}
+
+// === Tag Maintenance ===
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TagRenameRequest {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TagMergeRequest {
+    pub from: Vec<String>,
+    pub into: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Rename a tag on every listing that has it, matching case-insensitively.
+/// Renaming onto a tag listings already use is refused with 409 `TAG_EXISTS`
+/// (use merge for that); a case-only rename is allowed. Admin only.
+#[post("/admin/tags/rename", format = "json", data = "<body>")]
+pub fn rename_tag(
+    actor: Actor,
+    body: Json<TagRenameRequest>,
+    db: &rocket::State<DbState>,
+    limits: &rocket::State<FieldLimits>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can rename tags") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+    let (from, to) = (body.from.trim(), body.to.trim());
+    if let Err(err) = check_tag("from", from, limits).and(check_tag("to", to, limits)) {
+        return err.into();
+    }
+    if from == to {
+        return ApiError::new(ErrorCode::NoChanges, "from and to are the same tag").into();
+    }
+    if tags::normalize(from) != tags::normalize(to) {
+        let conn = db.conn();
+        match tags::usage(&conn, to) {
+            Ok(0) => {}
+            Ok(app_count) => {
+                return ApiError::from(ErrorCode::TagExists).with("tag", to).with("app_count", app_count).into()
+            }
+            Err(e) => {
+                eprintln!("❌ Tag usage lookup failed: {e}");
+                return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+            }
+        }
+    }
+
+    let summary = json!({ "action": "rename", "from": from, "to": to });
+    rewrite_tags(key, summary, &[tags::normalize(from)], to, body.dry_run, db, bus)
+}
+
+/// Merge several tags into one across all listings, matching
+/// case-insensitively. The target may already be in use. Admin only.
+#[post("/admin/tags/merge", format = "json", data = "<body>")]
+pub fn merge_tags(
+    actor: Actor,
+    body: Json<TagMergeRequest>,
+    db: &rocket::State<DbState>,
+    limits: &rocket::State<FieldLimits>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can merge tags") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+    let into = body.into.trim();
+    let from: Vec<&str> = body.from.iter().map(|t| t.trim()).collect();
+    if from.is_empty() {
+        return ApiError::new(ErrorCode::InvalidTag, "from needs at least one tag").with("field", "from").into();
+    }
+    if let Err(err) = from
+        .iter()
+        .try_for_each(|tag| check_tag("from", tag, limits))
+        .and(check_tag("into", into, limits))
+    {
+        return err.into();
+    }
+
+    let mut sources: Vec<String> = from.iter().map(|t| tags::normalize(t)).collect();
+    sources.sort();
+    sources.dedup();
+    let summary = json!({ "action": "merge", "from": from, "into": into });
+    rewrite_tags(key, summary, &sources, into, body.dry_run, db, bus)
+}
+
+fn check_tag(field: &str, tag: &str, limits: &FieldLimits) -> Result<(), ApiError> {
+    if tag.is_empty() || tag.chars().count() > limits.tag_length {
+        return Err(ApiError::from(ErrorCode::InvalidTag)
+            .with("field", field)
+            .with("max_length", limits.tag_length));
+    }
+    Ok(())
+}
+
+/// Apply (or with `dry_run`, only report) a tag rewrite to every affected
+/// listing in one transaction: each app gets a new revision and an
+/// `app.updated` event, and the whole operation one audit entry.
+fn rewrite_tags(
+    key: &AuthenticatedKey,
+    summary: Value,
+    sources: &[String],
+    target: &str,
+    dry_run: bool,
+    db: &DbState,
+    bus: &EventBus,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    let plan = match tags::plan_rewrite(&tx, sources, target) {
+        Ok(plan) => plan,
+        Err(e) => {
+            eprintln!("❌ Tag rewrite lookup failed: {e}");
+            return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+        }
+    };
+    let mut result = summary;
+    result["dry_run"] = json!(dry_run);
+    result["affected"] = json!(plan.len());
+    result["apps"] = json!(plan);
+    if dry_run {
+        return (Status::Ok, Json(result));
+    }
+
+    let written = plan.iter().try_for_each(|app| {
+        tx.execute(
+            "UPDATE apps SET tags = ?1, revision = revision + 1, updated_at = datetime('now') WHERE id = ?2",
+            rusqlite::params![serde_json::to_string(&app.after).unwrap_or_default(), app.app_id],
+        )?;
+        revisions::record(&tx, &app.app_id, "admin", Some(&key.id))
+    });
+    let action = format!("admin.tag_{}", result["action"].as_str().unwrap_or_default());
+    let details = json!({
+        "from": result["from"],
+        "to": target,
+        "app_ids": plan.iter().map(|app| &app.app_id).collect::<Vec<_>>(),
+    });
+    let audited = written.and_then(|_| {
+        tx.execute(
+            "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), action, key.id, details.to_string()],
+        )
+    });
+    if let Err(e) = audited {
+        eprintln!("❌ Tag rewrite failed: {e}");
+        return ApiError::new(ErrorCode::DbError, "Internal server error; no changes were applied").into();
+    }
+    let events = plan.iter().map(|app| AppEvent {
+        event: "app.updated".to_string(),
+        data: json!({ "app_id": app.app_id, "tags": app.after }),
+    });
+    match outbox::commit(tx, events) {
+        Ok(queued) => bus.dispatch(queued),
+        Err(err) => return err.into(),
     }
+    (Status::Ok, Json(result))
 }
@@ -1,79 +1,163 @@
 use rocket::http::Status;
+use rocket::response::Redirect;
 use rocket::serde::json::Json;
 use serde_json::{json, Value};
 
-use crate::auth::{self, AuthenticatedKey, EditTokenParam, OptionalKey, check_edit_access};
+use crate::a2a::{self, AgentCardVerifier};
+use crate::accounts::Principal;
+use crate::auth::{self, Actor, EditAccess, EditTokenParam, ReadAccess};
+use crate::captcha::{Captcha, CaptchaRoute};
+use crate::categories;
+use crate::conformance;
+use crate::docs;
+use crate::errors::{ApiError, ErrorCode};
 use crate::events::{AppEvent, EventBus};
+use crate::fields::FieldSet;
+use crate::health;
+use crate::filters::{self, AppFilters, Query};
+use crate::i18n::{self, AcceptLanguage, DefaultLanguage};
 use crate::models::*;
+use crate::moderation::{self, ContentFilter, ReviewPolicy};
+use crate::negotiate::NegotiatedApp;
+use crate::outbox;
+use crate::pagination::PageLimits;
+use crate::policy::{self, PolicyHook};
+use crate::revisions::{self, IfMatch};
+use crate::scheduler;
+use crate::search;
+use crate::logos::LogoCache;
+use crate::specs::{self, SpecVerifier};
+use crate::tags;
+use crate::validation::FieldLimits;
 use crate::DbState;
 
 // === App Submission (NO AUTH REQUIRED) ===
 
 #[post("/apps", data = "<body>")]
-pub fn submit_app(
-    opt_key: OptionalKey,
+#[allow(clippy::too_many_arguments)]
+pub async fn submit_app(
+    actor: Actor,
     body: Json<SubmitAppRequest>,
     db: &rocket::State<DbState>,
+    verifier: &rocket::State<SpecVerifier>,
+    logos: &rocket::State<LogoCache>,
+    agent_cards: &rocket::State<AgentCardVerifier>,
+    limits: &rocket::State<FieldLimits>,
+    filter: &rocket::State<ContentFilter>,
+    policy: &rocket::State<PolicyHook>,
     bus: &rocket::State<EventBus>,
+    captcha: &rocket::State<Captcha>,
+    review: &rocket::State<ReviewPolicy>,
+    remote_ip: Option<std::net::IpAddr>,
 ) -> (Status, Json<Value>) {
+    if let Err(err) = limits.check_submission(&body) {
+        return err.into();
+    }
+    if let Some(Err(message)) = body.slug.as_deref().map(check_slug) {
+        return ApiError::new(ErrorCode::InvalidSlug, message).with("field", "slug").into();
+    }
+    if let Err(err) = captcha
+        .verify(CaptchaRoute::Apps, &actor, body.captcha_token.as_deref(), remote_ip)
+        .await
+    {
+        return err.into();
+    }
+
+    let draft = match body.status.as_deref() {
+        None => false,
+        Some("draft") if actor.key().is_some() || actor.account_id().is_some() => true,
+        Some("draft") => {
+            return ApiError::new(ErrorCode::Unauthorized, "Drafts require an API key or account session").into();
+        }
+        Some(_) => {
+            return ApiError::new(ErrorCode::InvalidStatus, "Submissions can only set status to draft").into();
+        }
+    };
+
+    let key_id = actor.key_id();
+    let text = [
+        ("short_description", Some(body.short_description.as_str())),
+        ("description", Some(body.description.as_str())),
+    ];
+    if let Err(rejection) = filter.check("app", &text).await {
+        moderation::log_rejection(&db.conn(), "app", None, key_id, &rejection, &text);
+        return rejection.to_error().into();
+    }
+    if let Err(rejection) = policy.check("submit", None, policy::submitted_listing(&body), &[], &actor).await {
+        moderation::log_rejection(&db.conn(), "app", None, key_id, &rejection, &text);
+        return policy::to_error(&rejection).into();
+    }
+
+    let author_email = match parse_author_email(body.author_email.as_deref()) {
+        Ok(e) => e,
+        Err(err) => return err.into(),
+    };
+
     let conn = db.conn();
 
+    let visibility = body.visibility.as_deref().unwrap_or("public");
+    if let Err(err) = check_visibility(visibility) {
+        return err.into();
+    }
+
     let protocol = body.protocol.as_deref().unwrap_or("rest");
     if !VALID_PROTOCOLS.contains(&protocol) {
-        return (
-            Status::BadRequest,
-            Json(json!({
-                "error": "INVALID_PROTOCOL",
-                "message": format!("Valid protocols: {}", VALID_PROTOCOLS.join(", "))
-            })),
-        );
+        return ApiError::new(
+            ErrorCode::InvalidProtocol,
+            format!("Valid protocols: {}", VALID_PROTOCOLS.join(", ")),
+        )
+        .into();
     }
 
-    let category = body.category.as_deref().unwrap_or("other");
-    if !VALID_CATEGORIES.contains(&category) {
-        return (
-            Status::BadRequest,
-            Json(json!({
-                "error": "INVALID_CATEGORY",
-                "message": format!("Valid categories: {}", VALID_CATEGORIES.join(", "))
-            })),
-        );
-    }
+    let category = match categories::parse_category_path(body.category.as_deref().unwrap_or("other")) {
+        Ok(c) => c,
+        Err(message) => {
+            return ApiError::new(ErrorCode::InvalidCategory, message).into();
+        }
+    };
+    let _ = categories::ensure_category(&conn, &category);
 
     let id = uuid::Uuid::new_v4().to_string();
-    let slug = slugify(&body.name);
     let tags_json = serde_json::to_string(&body.tags.clone().unwrap_or_default()).unwrap();
 
-    // Check slug uniqueness
-    let slug_exists: bool = conn
-        .query_row(
-            "SELECT COUNT(*) FROM apps WHERE slug = ?1",
-            rusqlite::params![slug],
-            |r| r.get::<_, i64>(0),
-        )
-        .unwrap()
-        > 0;
-
-    let final_slug = if slug_exists {
-        format!("{}-{}", slug, &id[..8])
-    } else {
-        slug
+    // A chosen slug must be free; a generated one gets a suffix instead
+    let final_slug = match body.slug.as_deref() {
+        Some(slug) if slug_taken(&conn, slug, None) => {
+            return ApiError::from(ErrorCode::SlugTaken).with("slug", slug).into();
+        }
+        Some(slug) => slug.to_string(),
+        None => {
+            let slug = slugify(&body.name);
+            if slug_taken(&conn, &slug, None) {
+                format!("{}-{}", slug, &id[..8])
+            } else {
+                slug
+            }
+        }
     };
 
     // Generate edit token for this specific app
     let edit_token = format!("ad_{}", uuid::Uuid::new_v4().to_string().replace('-', ""));
     let edit_token_hash = auth::hash_key(&edit_token);
 
-    // Determine status and key association
-    let (status, submitted_by_key_id) = match &opt_key.0 {
-        Some(key) if key.is_admin => ("approved", Some(key.id.clone())),
-        Some(key) => ("approved", Some(key.id.clone())),
-        None => ("approved", None),
+    // Determine status and key or account association
+    let status = if draft {
+        "draft"
+    } else if review.manual && !actor.is_admin() {
+        "pending"
+    } else {
+        "approved"
     };
+    let submitted_by_key_id = actor.key_id();
+    let account_id = actor.account_id();
 
-    let result = conn.execute(
-        "INSERT INTO apps (id, name, slug, short_description, description, homepage_url, api_url, api_spec_url, protocol, category, tags, logo_url, author_name, author_url, submitted_by_key_id, status, edit_token_hash)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    let result = tx.execute(
+        "INSERT INTO apps (id, name, slug, short_description, description, homepage_url, api_url, api_spec_url, protocol, category, tags, logo_url, author_name, author_url, submitted_by_key_id, status, edit_token_hash, account_id, author_email, visibility)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
         rusqlite::params![
             id,
             body.name,
@@ -92,13 +176,25 @@ pub fn submit_app(
             submitted_by_key_id,
             status,
             edit_token_hash,
+            account_id,
+            author_email,
+            visibility,
         ],
     );
 
     match result {
         Ok(_) => {
-            bus.emit(AppEvent {
-                event: "app.approved".to_string(),
+            let editor = match &actor {
+                Actor::AdminKey(key) => ("admin", Some(key.id.as_str())),
+                Actor::Key(key) => ("api_key", Some(key.id.as_str())),
+                Actor::Account(session) => ("account", Some(session.account_id.as_str())),
+                Actor::EditToken(_) | Actor::Anonymous => ("anonymous", None),
+            };
+            let _ = revisions::record(&tx, &id, editor.0, editor.1);
+
+            // Drafts stay quiet until they're published
+            let event = (!draft).then(|| AppEvent {
+                event: if status == "pending" { "app.submitted" } else { "app.approved" }.to_string(),
                 data: json!({
                     "app_id": id,
                     "name": body.name,
@@ -106,34 +202,147 @@ pub fn submit_app(
                     "status": status,
                 }),
             });
+            match outbox::commit(tx, event) {
+                Ok(queued) => bus.dispatch(queued),
+                Err(err) => return err.into(),
+            }
+
+            if let Some(spec_url) = body.api_spec_url.as_deref().filter(|u| !u.trim().is_empty()) {
+                verifier.verify_async(&conn, &id, spec_url);
+            }
+            if let Some(logo_url) = body.logo_url.as_deref().filter(|u| !u.trim().is_empty()) {
+                logos.fetch_async(&conn, &id, logo_url);
+            }
+            if protocol == "a2a" {
+                agent_cards.verify_async(&conn, &id);
+            }
 
             let edit_url = format!("/apps/{}/edit?token={}", id, edit_token);
             let listing_url = format!("/apps/{}", id);
 
-            (
-                Status::Created,
-                Json(json!({
-                    "app_id": id,
-                    "slug": final_slug,
-                    "status": status,
-                    "edit_token": edit_token,
-                    "edit_url": edit_url,
-                    "listing_url": listing_url,
-                    "message": "App listing created! Save your edit token to modify or delete this listing later."
-                })),
-            )
+            let mut response = json!({
+                "app_id": id,
+                "slug": final_slug,
+                "status": status,
+                "visibility": visibility,
+                "account_id": account_id,
+                "edit_token": edit_token,
+                "edit_url": edit_url,
+                "listing_url": listing_url,
+                "message": "App listing created! Save your edit token to modify or delete this listing later."
+            });
+            if draft {
+                response["publish_url"] = json!(format!("/apps/{}/publish", id));
+                response["message"] = json!("Draft saved. It stays private until you publish it; save your edit token to keep editing.");
+            }
+            // Untagged submissions get tags to accept via PATCH
+            if body.tags.as_ref().is_none_or(|t| t.is_empty()) {
+                let vocabulary = tags::vocabulary(&conn).unwrap_or_default();
+                let suggested: Vec<String> = tags::suggest(
+                    &body.name,
+                    &body.short_description,
+                    &body.description,
+                    &[],
+                    &vocabulary,
+                    tags::DEFAULT_SUGGESTION_LIMIT,
+                )
+                .into_iter()
+                .map(|s| s.tag)
+                .collect();
+                response["suggested_tags"] = json!(suggested);
+            }
+
+            (Status::Created, Json(response))
         }
-        Err(_) => (
-            Status::InternalServerError,
-            Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
-        ),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
     }
 }
 
 // === List Apps (NO AUTH REQUIRED) ===
 
+/// `sort` values for the app list and their ORDER BY. The first is the default.
+pub(crate) const LIST_SORTS: &[(&str, &str)] = &[
+    ("newest", "created_at DESC"),
+    ("oldest", "created_at ASC"),
+    ("rating", "avg_rating DESC, review_count DESC"),
+    ("name", "name ASC"),
+];
+
+/// Reject anything but the known visibility values.
+fn check_visibility(visibility: &str) -> Result<(), ApiError> {
+    if VALID_VISIBILITIES.contains(&visibility) {
+        return Ok(());
+    }
+    Err(ApiError::new(
+        ErrorCode::InvalidVisibility,
+        format!("Valid visibilities: {}", VALID_VISIBILITIES.join(", ")),
+    ))
+}
+
+/// Bounds of the admin-set `rank_boost`.
+pub(crate) const RANK_BOOST_RANGE: std::ops::RangeInclusive<i64> = -10..=10;
+
+/// Validate a featured window from an app update against the stored one.
+/// Each side comes back as `Some(None)` to clear it, `Some(Some(ts))` to set
+/// it (normalized to SQLite's `datetime` format) or `None` to leave it.
+#[allow(clippy::type_complexity)]
+fn feature_window(
+    conn: &rusqlite::Connection,
+    id: &str,
+    from: Option<&str>,
+    until: Option<&str>,
+) -> Result<(Option<Option<String>>, Option<Option<String>>), ApiError> {
+    let parse = |field: &str, raw: Option<&str>| -> Result<Option<Option<String>>, ApiError> {
+        match raw.map(str::trim) {
+            None => Ok(None),
+            Some("") => Ok(Some(None)),
+            Some(raw) => chrono::DateTime::parse_from_rfc3339(raw)
+                .map(|t| Some(Some(t.with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M:%S").to_string())))
+                .map_err(|_| {
+                    ApiError::new(ErrorCode::InvalidFeatureWindow, format!("{field} must be an RFC 3339 timestamp"))
+                        .with("field", field)
+                }),
+        }
+    };
+    let from = parse("featured_from", from)?;
+    let until = parse("featured_until", until)?;
+
+    let (stored_from, stored_until): (Option<String>, Option<String>) = conn
+        .query_row(
+            "SELECT featured_from, featured_until FROM apps WHERE id = ?1",
+            rusqlite::params![id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap_or((None, None));
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    if let Some(Some(end)) = &until {
+        if *end <= now {
+            return Err(ApiError::new(ErrorCode::InvalidFeatureWindow, "featured_until must be in the future")
+                .with("field", "featured_until"));
+        }
+    }
+    let effective_from = from.clone().unwrap_or(stored_from);
+    let effective_until = until.clone().unwrap_or(stored_until);
+    if let (Some(start), Some(end)) = (effective_from, effective_until) {
+        if start >= end {
+            return Err(ApiError::new(ErrorCode::InvalidFeatureWindow, "featured_until must be after featured_from")
+                .with("field", "featured_until"));
+        }
+    }
+    Ok((from, until))
+}
+
+/// Computed fields `?fields=` accepts on the app list and search.
+pub(crate) const LIST_COMPUTED_FIELDS: &[&str] = &["badges"];
+pub(crate) const SEARCH_COMPUTED_FIELDS: &[&str] = &["badges", "matched_terms"];
+
+/// `fields` (comma-separated) limits each app to the named fields.
+/// `created_after`, `created_before` and `updated_after` (RFC 3339) narrow
+/// by time, so sync agents can fetch only what changed since their last crawl.
+/// `q` (or its older alias `search`) keeps apps matching every search term,
+/// parsed as in `search_apps`.
 #[get(
-    "/apps?<category>&<protocol>&<status>&<featured>&<verified>&<health>&<sort>&<page>&<per_page>&<search>"
+    "/apps?<category>&<protocol>&<status>&<featured>&<verified>&<health>&<sort>&<page>&<per_page>&<q>&<search>&<fields>&<created_after>&<created_before>&<updated_after>"
 )]
 #[allow(clippy::too_many_arguments)]
 pub fn list_apps(
@@ -146,164 +355,430 @@ pub fn list_apps(
     sort: Option<String>,
     page: Option<i64>,
     per_page: Option<i64>,
+    q: Option<String>,
     search: Option<String>,
+    fields: Option<String>,
+    created_after: Option<&str>,
+    created_before: Option<&str>,
+    updated_after: Option<&str>,
+    reader: ReadAccess,
+    langs: AcceptLanguage,
     db: &rocket::State<DbState>,
-) -> Json<Value> {
+    default_lang: &rocket::State<DefaultLanguage>,
+    page_limits: &rocket::State<PageLimits>,
+) -> (Status, Json<Value>) {
+    let fieldset = match FieldSet::parse(fields.as_deref(), LIST_COMPUTED_FIELDS) {
+        Ok(f) => f,
+        Err(e) => return e.into(),
+    };
+    let time_range = match filters::time_range(created_after, created_before, updated_after) {
+        Ok(f) => f,
+        Err(e) => return e.into(),
+    };
+    let filters = AppFilters {
+        category,
+        protocol,
+        featured,
+        verified,
+        health,
+        terms: search::parse_terms(q.or(search).as_deref().unwrap_or_default()),
+        time_range,
+    };
+
     let conn = db.conn();
 
     let page = page.unwrap_or(1).max(1);
-    let per_page = per_page.unwrap_or(20).clamp(1, 100);
+    let limits = page_limits.for_key(&conn, reader.0.as_ref());
+    let per_page = limits.per_page(per_page);
     let offset = (page - 1) * per_page;
 
-    let mut conditions = vec!["1=1".to_string()];
-    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+    let mut query = Query::new();
 
     let status_filter = status.unwrap_or_else(|| "approved".to_string());
     if status_filter != "all" {
-        conditions.push(format!("status = ?{}", params.len() + 1));
-        params.push(Box::new(status_filter));
-    }
-
-    if let Some(ref q) = search {
-        if !q.is_empty() {
-            let pattern = format!("%{}%", q.to_lowercase());
-            conditions.push(format!(
-                "(LOWER(name) LIKE ?{p} OR LOWER(short_description) LIKE ?{p} OR LOWER(description) LIKE ?{p} OR LOWER(tags) LIKE ?{p})",
-                p = params.len() + 1
-            ));
-            params.push(Box::new(pattern));
-        }
-    }
-
-    if let Some(ref cat) = category {
-        conditions.push(format!("category = ?{}", params.len() + 1));
-        params.push(Box::new(cat.clone()));
-    }
-
-    if let Some(ref proto) = protocol {
-        conditions.push(format!("protocol = ?{}", params.len() + 1));
-        params.push(Box::new(proto.clone()));
-    }
-
-    if let Some(true) = featured {
-        conditions.push("is_featured = 1".to_string());
+        let idx = query.bind(status_filter);
+        query.condition(format!("status = ?{idx}"));
     }
-
-    if let Some(true) = verified {
-        conditions.push("is_verified = 1".to_string());
+    // Drafts are private to their owners (see GET /apps/mine); unlisted and
+    // private listings stay out of lists for everyone but admins
+    if !reader.is_admin() {
+        query.condition("status != 'draft'");
+        query.condition(listed_visibility("visibility", reader.is_signed_in()));
     }
 
-    if let Some(ref h) = health {
-        match h.as_str() {
-            "healthy" | "unhealthy" | "unreachable" => {
-                conditions.push(format!("last_health_status = ?{}", params.len() + 1));
-                params.push(Box::new(h.clone()));
-            }
-            "unknown" => {
-                conditions.push("last_health_status IS NULL".to_string());
-            }
-            _ => {}
-        }
+    filters.apply(&mut query);
+    for term in filters.bind_terms(&mut query) {
+        query.condition(term);
     }
 
-    let where_clause = conditions.join(" AND ");
+    let where_clause = query.where_clause();
 
-    let order = match sort.as_deref() {
-        Some("rating") => "avg_rating DESC, review_count DESC",
-        Some("name") => "name ASC",
-        Some("oldest") => "created_at ASC",
-        _ => "created_at DESC",
+    // Without an explicit sort, admin rank boosts lead the default order
+    let order = match LIST_SORTS.iter().find(|(name, _)| Some(*name) == sort.as_deref()) {
+        Some((_, order)) => order.to_string(),
+        None => format!("rank_boost DESC, {}", LIST_SORTS[0].1),
     };
 
     let count_sql = format!("SELECT COUNT(*) FROM apps WHERE {}", where_clause);
     let total: i64 = conn
-        .query_row(
-            &count_sql,
-            rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
-            |r| r.get(0),
-        )
+        .query_row(&count_sql, rusqlite::params_from_iter(query.params()), |r| r.get(0))
         .unwrap_or(0);
 
-    let query = format!(
-        "SELECT id, name, slug, short_description, description, homepage_url, api_url, api_spec_url, protocol, category, tags, logo_url, author_name, author_url, status, is_featured, is_verified, avg_rating, review_count, created_at, updated_at, last_health_status, last_checked_at, uptime_pct, review_note, reviewed_by, reviewed_at, deprecated_reason, deprecated_by, deprecated_at, replacement_app_id, sunset_at
-         FROM apps WHERE {} ORDER BY {} LIMIT ?{} OFFSET ?{}",
-        where_clause,
-        order,
-        params.len() + 1,
-        params.len() + 2,
+    let columns = match &fieldset {
+        Some(f) => f.select_list(),
+        None => "id, name, slug, short_description, description, homepage_url, api_url, api_spec_url, protocol, category, tags, logo_url, author_name, author_url, status, is_featured, is_verified, avg_rating, review_count, created_at, updated_at, last_health_status, last_checked_at, uptime_pct, review_note, reviewed_by, reviewed_at, deprecated_reason, deprecated_by, deprecated_at, replacement_app_id, sunset_at, spec_status, archived_at, archived_by, revision, featured_from, featured_until, visibility".to_string(),
+    };
+    let limit_idx = query.bind(per_page);
+    let offset_idx = query.bind(offset);
+    let sql = format!(
+        "SELECT {} FROM apps WHERE {} ORDER BY {} LIMIT ?{} OFFSET ?{}",
+        columns, where_clause, order, limit_idx, offset_idx,
     );
 
-    params.push(Box::new(per_page));
-    params.push(Box::new(offset));
-
-    let mut stmt = conn.prepare(&query).unwrap();
-    let apps: Vec<Value> = stmt
+    let mut stmt = conn.prepare(&sql).unwrap();
+    let mut apps: Vec<Value> = stmt
         .query_map(
-            rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
-            app_row_to_json,
+            rusqlite::params_from_iter(query.params()),
+            |row| match &fieldset {
+                Some(f) => f.row_to_json(row),
+                None => app_row_to_json(row),
+            },
         )
         .unwrap()
         .filter_map(|r| r.ok())
         .collect();
 
-    Json(json!({
-        "apps": apps,
-        "total": total,
-        "page": page,
-        "per_page": per_page,
-    }))
+    let want_badges = fieldset.as_ref().is_none_or(|f| f.contains("badges"));
+    let is_admin = reader.is_admin();
+    for app in apps.iter_mut() {
+        if let Some(app_id) = app["id"].as_str().map(|s| s.to_string()) {
+            if want_badges {
+                app["badges"] = super::badges::app_badges(&conn, &app_id);
+            }
+            if is_admin && fieldset.is_none() {
+                app["rank_boost"] = json!(rank_boost(&conn, &app_id));
+            }
+        }
+        i18n::localize(&conn, app, &langs, &default_lang.0);
+        if let Some(f) = &fieldset {
+            f.retain(app);
+        }
+    }
+
+    (
+        Status::Ok,
+        Json(json!({
+            "apps": apps,
+            "total": total,
+            "page": page,
+            "per_page": per_page,
+            "page_limits": limits.to_json(),
+        })),
+    )
 }
 
 // === Get Single App (NO AUTH REQUIRED) ===
 
+/// A listing, or a redirect from an old slug of a renamed app.
+#[derive(Responder)]
+pub enum AppLookup {
+    Found(NegotiatedApp),
+    Moved(Box<Redirect>),
+}
+
+/// Returns JSON by default; `Accept: text/markdown` or `text/plain` render the listing as a document.
+/// Text fields are localized from `Accept-Language` when a translation exists.
+/// Drafts are only visible to callers who could edit them, private listings
+/// to signed-in callers. Old slugs of renamed apps answer `301` with the
+/// current slug.
 #[get("/apps/<id_or_slug>")]
+#[allow(clippy::too_many_arguments)]
 pub fn get_app(
-    opt_key: OptionalKey,
+    reader: ReadAccess,
+    token: EditTokenParam,
     id_or_slug: &str,
+    langs: AcceptLanguage,
     db: &rocket::State<DbState>,
-) -> (Status, Json<Value>) {
+    views: &rocket::State<crate::stats::ViewRecorder>,
+    default_lang: &rocket::State<DefaultLanguage>,
+) -> AppLookup {
     let conn = db.conn();
 
     let result = conn.query_row(
-        "SELECT id, name, slug, short_description, description, homepage_url, api_url, api_spec_url, protocol, category, tags, logo_url, author_name, author_url, status, is_featured, is_verified, avg_rating, review_count, created_at, updated_at, last_health_status, last_checked_at, uptime_pct, review_note, reviewed_by, reviewed_at, deprecated_reason, deprecated_by, deprecated_at, replacement_app_id, sunset_at
+        "SELECT id, name, slug, short_description, description, homepage_url, api_url, api_spec_url, protocol, category, tags, logo_url, author_name, author_url, status, is_featured, is_verified, avg_rating, review_count, created_at, updated_at, last_health_status, last_checked_at, uptime_pct, review_note, reviewed_by, reviewed_at, deprecated_reason, deprecated_by, deprecated_at, replacement_app_id, sunset_at, spec_status, archived_at, archived_by, revision, featured_from, featured_until, visibility
          FROM apps WHERE id = ?1 OR slug = ?1",
         rusqlite::params![id_or_slug],
         app_row_to_json,
     );
 
     match result {
-        Ok(app) => {
-            if let Some(app_id) = app.get("id").and_then(|v| v.as_str()) {
-                let viewer_id = opt_key.0.as_ref().map(|k| k.id.as_str()).unwrap_or("anonymous");
-                crate::stats::record_view(&conn, app_id, viewer_id);
+        Ok(app) if !can_view(&conn, &app, &reader, token.0.as_deref()) => {
+            AppLookup::Found(ApiError::new(ErrorCode::NotFound, "App not found").into())
+        }
+        Ok(mut app) => {
+            if let Some(app_id) = app.get("id").and_then(|v| v.as_str()).map(|s| s.to_string()) {
+                let viewer_id = reader.0.as_ref().map(|k| k.id.as_str()).unwrap_or("anonymous");
+                views.record(&app_id, viewer_id);
+                app["spec"] = specs::spec_details(&conn, &app_id).unwrap_or(Value::Null);
+                app["conformance"] = conformance::conformance_details(&conn, &app_id).unwrap_or(Value::Null);
+                app["badges"] = super::badges::app_badges(&conn, &app_id);
+                app["endorsements"] = super::endorsements::endorsement_counts(&conn, &app_id);
+                let dependencies = health::dependencies(&conn, &app_id);
+                app["composite_health"] = json!(health::composite_health(app["last_health_status"].as_str(), &dependencies));
+                app["depends_on"] = json!(dependencies);
+                app["key_endpoints"] = key_endpoints(&conn, &app_id);
+                app["logo_status"] = conn
+                    .query_row("SELECT logo_status FROM apps WHERE id = ?1", rusqlite::params![app_id], |r| {
+                        r.get::<_, Option<String>>(0)
+                    })
+                    .map_or(Value::Null, |status| json!(status));
+                if app["protocol"] == "a2a" {
+                    app["agent_card"] = a2a::card_details(&conn, &app_id).unwrap_or(Value::Null);
+                }
+                if app["status"] == "deprecated" && !app["replacement_app_id"].is_null() {
+                    app["resolved_replacement"] = resolve_replacement(&conn, &app_id)
+                        .ok()
+                        .filter(|(final_id, _)| visible_app_id(&conn, final_id, &reader, None).is_some())
+                        .and_then(|(final_id, chain)| {
+                            conn.query_row(
+                                "SELECT id, name, slug FROM apps WHERE id = ?1",
+                                rusqlite::params![final_id],
+                                |r| {
+                                    Ok(json!({
+                                        "id": r.get::<_, String>(0)?,
+                                        "name": r.get::<_, String>(1)?,
+                                        "slug": r.get::<_, String>(2)?,
+                                        "hops": chain.len(),
+                                    }))
+                                },
+                            )
+                            .ok()
+                        })
+                        .unwrap_or(Value::Null);
+                }
+                app["available_languages"] = json!(i18n::available_languages(&conn, &app_id));
+                let has_docs = conn
+                    .query_row("SELECT docs_markdown IS NOT NULL FROM apps WHERE id = ?1", rusqlite::params![app_id], |r| {
+                        r.get::<_, bool>(0)
+                    })
+                    .unwrap_or(false);
+                app["docs_url"] = match has_docs {
+                    true => json!(format!("/api/v1/apps/{app_id}/docs.md")),
+                    false => Value::Null,
+                };
+                if reader.is_admin() {
+                    app["rank_boost"] = json!(rank_boost(&conn, &app_id));
+                }
+                i18n::localize(&conn, &mut app, &langs, &default_lang.0);
             }
-            (Status::Ok, Json(app))
+            AppLookup::Found(NegotiatedApp::new(Status::Ok, app))
         }
-        Err(_) => (
-            Status::NotFound,
-            Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-        ),
+        // Old slug of a renamed app
+        Err(_) => match conn.query_row(
+            "SELECT a.slug FROM slug_redirects r JOIN apps a ON a.id = r.app_id WHERE r.old_slug = ?1",
+            rusqlite::params![id_or_slug],
+            |r| r.get::<_, String>(0),
+        ) {
+            Ok(slug) => AppLookup::Moved(Box::new(Redirect::moved(format!("/api/v1/apps/{slug}")))),
+            Err(_) => AppLookup::Found(ApiError::new(ErrorCode::NotFound, "App not found").into()),
+        },
     }
 }
 
-// === List My Apps (API Key Required) ===
+/// The verified A2A agent card of an `a2a` listing, as fetched from the
+/// agent. Drafts and private listings don't expose theirs.
+#[get("/apps/<id_or_slug>/agent.json")]
+pub fn app_agent_card(id_or_slug: &str, db: &rocket::State<DbState>) -> Result<Json<Value>, ApiError> {
+    let conn = db.conn();
+    conn.query_row(
+        "SELECT c.card FROM app_agent_cards c JOIN apps a ON a.id = c.app_id
+         WHERE (a.id = ?1 OR a.slug = ?1) AND c.status = 'valid'
+           AND a.status != 'draft' AND a.visibility != 'private'",
+        rusqlite::params![id_or_slug],
+        |r| r.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|card| serde_json::from_str(&card).ok())
+    .map(Json)
+    .ok_or_else(|| ApiError::new(ErrorCode::NotFound, "No verified agent card for this app"))
+}
+
+/// Function-calling tool schemas for the operations of an app's verified
+/// OpenAPI spec, generated when the spec was last fetched.
+#[get("/apps/<id_or_slug>/tools.json")]
+pub fn app_tools(id_or_slug: &str, db: &rocket::State<DbState>) -> Result<Json<Value>, ApiError> {
+    let conn = db.conn();
+    conn.query_row(
+        "SELECT a.id, a.name, s.spec_url, s.title, s.version, s.servers, s.tools, s.fetched_at
+         FROM app_specs s JOIN apps a ON a.id = s.app_id
+         WHERE (a.id = ?1 OR a.slug = ?1) AND s.status = 'valid'
+           AND a.status != 'draft' AND a.visibility != 'private'",
+        rusqlite::params![id_or_slug],
+        |r| {
+            let servers: Value = serde_json::from_str(&r.get::<_, String>(5)?).unwrap_or_else(|_| json!([]));
+            let tools: Value = serde_json::from_str(&r.get::<_, String>(6)?).unwrap_or_else(|_| json!([]));
+            Ok(json!({
+                "app_id": r.get::<_, String>(0)?,
+                "app_name": r.get::<_, String>(1)?,
+                "spec_url": r.get::<_, String>(2)?,
+                "spec_title": r.get::<_, Option<String>>(3)?,
+                "spec_version": r.get::<_, Option<String>>(4)?,
+                "servers": servers,
+                "tools": tools,
+                "generated_at": r.get::<_, String>(7)?,
+            }))
+        },
+    )
+    .map(Json)
+    .map_err(|_| ApiError::new(ErrorCode::NotFound, "No valid API spec for this app"))
+}
+
+/// Whether a reader may see `app`. Drafts, and private listings when the
+/// reader isn't signed in, are limited to those who could edit them:
+/// admins, its owner (key or account) and holders of its edit token.
+fn can_view(conn: &rusqlite::Connection, app: &Value, reader: &ReadAccess, token: Option<&str>) -> bool {
+    let hidden = app["status"] == "draft" || (app["visibility"] == "private" && !reader.is_signed_in());
+    if !hidden {
+        return true;
+    }
+    let viewer = match (&reader.0, &reader.1, token) {
+        (Some(key), _, _) if key.is_admin => Actor::AdminKey(key.clone()),
+        (Some(key), _, _) => Actor::Key(key.clone()),
+        (None, Some(session), _) => Actor::Account(session.clone()),
+        (None, None, Some(token)) => Actor::EditToken(token.to_string()),
+        (None, None, None) => return false,
+    };
+    viewer.can_edit(conn, app["id"].as_str().unwrap_or_default()).is_ok()
+}
+
+/// Id of the app `id_or_slug` names, or `None` when it doesn't exist or
+/// the reader may not see it (see `can_view`).
+pub(crate) fn visible_app_id(
+    conn: &rusqlite::Connection,
+    id_or_slug: &str,
+    reader: &ReadAccess,
+    token: Option<&str>,
+) -> Option<String> {
+    let app = conn
+        .query_row(
+            "SELECT id, status, visibility FROM apps WHERE id = ?1 OR slug = ?1",
+            rusqlite::params![id_or_slug],
+            |r| {
+                Ok(json!({
+                    "id": r.get::<_, String>(0)?,
+                    "status": r.get::<_, String>(1)?,
+                    "visibility": r.get::<_, String>(2)?,
+                }))
+            },
+        )
+        .ok()?;
+    can_view(conn, &app, reader, token).then(|| app["id"].as_str().unwrap_or_default().to_string())
+}
+
+/// Resolve a deprecated app's replacement chain to the final active listing.
+#[get("/apps/<id>/replacement")]
+pub fn get_replacement(reader: ReadAccess, token: EditTokenParam, id: &str, db: &rocket::State<DbState>) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    if visible_app_id(&conn, id, &reader, token.0.as_deref()).is_none() {
+        return ApiError::new(ErrorCode::NotFound, "App not found").into();
+    }
+    let (final_id, chain) = match resolve_replacement(&conn, id) {
+        Ok(resolved) => resolved,
+        Err(e) => return e.into(),
+    };
+    // A replacement the reader can't see is reported as missing, not leaked
+    if visible_app_id(&conn, &final_id, &reader, None).is_none() {
+        return ApiError::new(ErrorCode::NotFound, "No active replacement for this app").into();
+    }
+    let result = conn.query_row(
+        "SELECT id, name, slug, short_description, description, homepage_url, api_url, api_spec_url, protocol, category, tags, logo_url, author_name, author_url, status, is_featured, is_verified, avg_rating, review_count, created_at, updated_at, last_health_status, last_checked_at, uptime_pct, review_note, reviewed_by, reviewed_at, deprecated_reason, deprecated_by, deprecated_at, replacement_app_id, sunset_at, spec_status, archived_at, archived_by, revision, featured_from, featured_until, visibility
+         FROM apps WHERE id = ?1",
+        rusqlite::params![final_id],
+        app_row_to_json,
+    );
+    match result {
+        Ok(app) => (Status::Ok, Json(json!({ "chain": chain, "replacement": app }))),
+        Err(_) => ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    }
+}
+
+/// Tags from the existing vocabulary that the app's name and descriptions
+/// mention but it doesn't carry yet, best first. Owners accept them by
+/// PATCHing `tags`.
+#[get("/apps/<id_or_slug>/suggest-tags?<limit>")]
+pub fn suggest_tags(
+    reader: ReadAccess,
+    token: EditTokenParam,
+    id_or_slug: &str,
+    limit: Option<usize>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    if visible_app_id(&conn, id_or_slug, &reader, token.0.as_deref()).is_none() {
+        return ApiError::new(ErrorCode::NotFound, "App not found").into();
+    }
+    let app = conn.query_row(
+        "SELECT id, name, short_description, description, tags FROM apps WHERE id = ?1 OR slug = ?1",
+        rusqlite::params![id_or_slug],
+        |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, String>(3)?,
+                r.get::<_, String>(4)?,
+            ))
+        },
+    );
+    let Ok((id, name, short_description, description, tags_json)) = app else {
+        return ApiError::new(ErrorCode::NotFound, "App not found").into();
+    };
+    let current: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+
+    let limit = limit.unwrap_or(tags::DEFAULT_SUGGESTION_LIMIT).clamp(1, tags::MAX_SUGGESTION_LIMIT);
+    let vocabulary = match tags::vocabulary(&conn) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("❌ Tag vocabulary query failed: {e}");
+            return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+        }
+    };
+    let suggestions = tags::suggest(&name, &short_description, &description, &current, &vocabulary, limit);
+
+    (
+        Status::Ok,
+        Json(json!({
+            "app_id": id,
+            "tags": current,
+            "suggestions": suggestions,
+        })),
+    )
+}
+
+// === List My Apps (API Key or Account Session Required) ===
 
 #[get("/apps/mine")]
 pub fn list_my_apps(
-    key: AuthenticatedKey,
+    principal: Principal,
     db: &rocket::State<DbState>,
 ) -> (Status, Json<Value>) {
     let conn = db.conn();
 
+    let (owner_column, owner_id) = match principal {
+        Principal::Key(key) => ("submitted_by_key_id", key.id),
+        Principal::Account(session) => ("account_id", session.account_id),
+    };
+
     let mut stmt = conn
-        .prepare(
+        .prepare(&format!(
             "SELECT id, name, slug, short_description, status, created_at, updated_at
-             FROM apps WHERE submitted_by_key_id = ?1 ORDER BY created_at DESC",
-        )
+             FROM apps WHERE {} = ?1 ORDER BY created_at DESC",
+            owner_column
+        ))
         .unwrap();
 
     let apps: Vec<Value> = stmt
-        .query_map(rusqlite::params![key.id], |row| {
+        .query_map(rusqlite::params![owner_id], |row| {
             Ok(json!({
                 "id": row.get::<_, String>(0)?,
                 "name": row.get::<_, String>(1)?,
@@ -330,73 +805,131 @@ pub fn list_my_apps(
 // === Update App ===
 
 #[patch("/apps/<id>", data = "<body>")]
-pub fn update_app(
-    opt_key: OptionalKey,
-    edit_token: EditTokenParam,
+#[allow(clippy::too_many_arguments)]
+pub async fn update_app(
+    actor: Actor,
+    if_match: IfMatch,
     id: &str,
     body: Json<UpdateAppRequest>,
     db: &rocket::State<DbState>,
+    verifier: &rocket::State<SpecVerifier>,
+    logos: &rocket::State<LogoCache>,
+    agent_cards: &rocket::State<AgentCardVerifier>,
+    limits: &rocket::State<FieldLimits>,
+    filter: &rocket::State<ContentFilter>,
+    policy: &rocket::State<PolicyHook>,
     bus: &rocket::State<EventBus>,
 ) -> (Status, Json<Value>) {
-    let conn = db.conn();
-
     // Check edit access via edit token, API key owner, or admin
-    let access = match check_edit_access(&conn, id, &edit_token.0, &opt_key.0) {
+    let access = match actor.can_edit(&db.conn(), id) {
         Ok(a) => a,
-        Err((status, err)) => return (status, Json(err)),
+        Err(err) => return err.into(),
     };
 
+    if let Err(err) = ensure_not_archived(&db.conn(), id) {
+        return err.into();
+    }
+
+    if let Err(err) = limits.check_update(&body) {
+        return err.into();
+    }
+
+    let key_id = actor.key_id();
+    let text = [
+        ("short_description", body.short_description.as_deref()),
+        ("description", body.description.as_deref()),
+    ];
+    if let Err(rejection) = filter.check("app", &text).await {
+        moderation::log_rejection(&db.conn(), "app", Some(id), key_id, &rejection, &text);
+        return rejection.to_error().into();
+    }
+    if policy.is_enabled() {
+        let (listing, changed) = match policy::updated_listing(&db.conn(), id, &body) {
+            Ok(proposed) => proposed,
+            Err(_) => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+        };
+        if !changed.is_empty() {
+            if let Err(rejection) = policy.check("update", Some(id), listing, &changed, &actor).await {
+                moderation::log_rejection(&db.conn(), "app", Some(id), key_id, &rejection, &text);
+                return policy::to_error(&rejection).into();
+            }
+        }
+    }
+
+    let conn = db.conn();
+
+    // Optimistic concurrency: the lock is held from here through the UPDATE
+    let revision = revisions::current_revision(&conn, id).unwrap_or(0);
+    if let Err(err) = if_match.check(revision) {
+        return err.into();
+    }
+
     // Admin-only fields: status, featured, verified badges
     if body.status.is_some() && !access.is_admin() {
-        return (
-            Status::Forbidden,
-            Json(json!({ "error": "FORBIDDEN", "message": "Only admins can change app status" })),
-        );
+        return ApiError::new(ErrorCode::Forbidden, "Only admins can change app status").into();
     }
-    if (body.is_featured.is_some() || body.is_verified.is_some()) && !access.is_admin() {
-        return (
-            Status::Forbidden,
-            Json(
-                json!({ "error": "FORBIDDEN", "message": "Only admins can set featured/verified badges" }),
-            ),
-        );
+    if (body.is_featured.is_some()
+        || body.is_verified.is_some()
+        || body.featured_from.is_some()
+        || body.featured_until.is_some())
+        && !access.is_admin()
+    {
+        return ApiError::new(
+            ErrorCode::Forbidden,
+            "Only admins can set featured/verified badges",
+        )
+        .into();
+    }
+    let (featured_from, featured_until) =
+        match feature_window(&conn, id, body.featured_from.as_deref(), body.featured_until.as_deref()) {
+            Ok(window) => window,
+            Err(err) => return err.into(),
+        };
+    if let Some(boost) = body.rank_boost {
+        if !access.is_admin() {
+            return ApiError::new(ErrorCode::Forbidden, "Only admins can set rank_boost").into();
+        }
+        if !RANK_BOOST_RANGE.contains(&boost) {
+            return ApiError::from(ErrorCode::InvalidRankBoost).with("rank_boost", boost).into();
+        }
     }
 
     if let Some(ref status) = body.status {
         if !VALID_STATUSES.contains(&status.as_str()) {
-            return (
-                Status::BadRequest,
-                Json(json!({
-                    "error": "INVALID_STATUS",
-                    "message": format!("Valid statuses: {}", VALID_STATUSES.join(", "))
-                })),
-            );
+            return ApiError::new(
+                ErrorCode::InvalidStatus,
+                format!("Valid statuses: {}", VALID_STATUSES.join(", ")),
+            )
+            .into();
+        }
+    }
+
+    if let Some(ref visibility) = body.visibility {
+        if let Err(err) = check_visibility(visibility) {
+            return err.into();
         }
     }
 
     if let Some(ref protocol) = body.protocol {
         if !VALID_PROTOCOLS.contains(&protocol.as_str()) {
-            return (
-                Status::BadRequest,
-                Json(json!({
-                    "error": "INVALID_PROTOCOL",
-                    "message": format!("Valid protocols: {}", VALID_PROTOCOLS.join(", "))
-                })),
-            );
+            return ApiError::new(
+                ErrorCode::InvalidProtocol,
+                format!("Valid protocols: {}", VALID_PROTOCOLS.join(", ")),
+            )
+            .into();
         }
     }
 
-    if let Some(ref category) = body.category {
-        if !VALID_CATEGORIES.contains(&category.as_str()) {
-            return (
-                Status::BadRequest,
-                Json(json!({
-                    "error": "INVALID_CATEGORY",
-                    "message": format!("Valid categories: {}", VALID_CATEGORIES.join(", "))
-                })),
-            );
+    let category = match body.category.as_deref().map(categories::parse_category_path) {
+        Some(Ok(category)) => {
+            let _ = categories::ensure_category(&conn, &category);
+            Some(category)
         }
-    }
+        Some(Err(message)) => {
+            return ApiError::new(ErrorCode::InvalidCategory, message).into();
+        }
+        None => None,
+    };
 
     let mut sets: Vec<String> = Vec::new();
     let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
@@ -417,11 +950,57 @@ pub fn update_app(
     maybe_set!(api_url, "api_url");
     maybe_set!(api_spec_url, "api_spec_url");
     maybe_set!(protocol, "protocol");
-    maybe_set!(category, "category");
+    if let Some(category) = category {
+        params.push(Box::new(category));
+        sets.push(format!("category = ?{}", params.len()));
+    }
     maybe_set!(logo_url, "logo_url");
     maybe_set!(author_name, "author_name");
     maybe_set!(author_url, "author_url");
+    if body.author_email.is_some() {
+        match parse_author_email(body.author_email.as_deref()) {
+            Ok(email) => {
+                params.push(Box::new(email));
+                sets.push(format!("author_email = ?{}", params.len()));
+            }
+            Err(err) => return err.into(),
+        }
+    }
     maybe_set!(status, "status");
+    maybe_set!(visibility, "visibility");
+    if let Some(ref markdown) = body.docs_markdown {
+        let clean = docs::sanitize(markdown);
+        params.push(Box::new((!clean.is_empty()).then_some(clean)));
+        sets.push(format!("docs_markdown = ?{}", params.len()));
+    }
+
+    if let Some(ref expectation) = body.health_expectation {
+        match expectation.clone().validate() {
+            Ok(expectation) => {
+                params.push(Box::new(expectation.map(|e| json!(e).to_string())));
+                sets.push(format!("health_expectation = ?{}", params.len()));
+            }
+            Err(err) => return err.into(),
+        }
+    }
+
+    if let Some(ref endpoints) = body.key_endpoints {
+        match normalize_key_endpoints(endpoints) {
+            Ok(endpoints) => {
+                params.push(Box::new((!endpoints.is_empty()).then(|| json!(endpoints).to_string())));
+                sets.push(format!("key_endpoints = ?{}", params.len()));
+            }
+            Err(err) => return err.into(),
+        }
+    }
+
+    let dependencies = match body.depends_on {
+        Some(ref depends_on) => match health::resolve_dependencies(&conn, id, depends_on) {
+            Ok(ids) => Some(ids),
+            Err(err) => return err.into(),
+        },
+        None => None,
+    };
 
     if let Some(ref tags) = body.tags {
         let tags_json = serde_json::to_string(tags).unwrap();
@@ -439,13 +1018,23 @@ pub fn update_app(
         sets.push(format!("is_verified = ?{}", params.len()));
     }
 
-    if sets.is_empty() {
-        return (
-            Status::BadRequest,
-            Json(json!({ "error": "NO_CHANGES", "message": "No fields to update" })),
-        );
+    let window_changed = featured_from.is_some() || featured_until.is_some();
+    if let Some(from) = featured_from {
+        params.push(Box::new(from));
+        sets.push(format!("featured_from = ?{}", params.len()));
+    }
+    if let Some(until) = featured_until {
+        params.push(Box::new(until));
+        sets.push(format!("featured_until = ?{}", params.len()));
+    }
+
+    maybe_set!(rank_boost, "rank_boost");
+
+    if sets.is_empty() && dependencies.is_none() {
+        return ApiError::new(ErrorCode::NoChanges, "No fields to update").into();
     }
 
+    sets.push("revision = revision + 1".to_string());
     sets.push("updated_at = datetime('now')".to_string());
 
     params.push(Box::new(id.to_string()));
@@ -455,143 +1044,874 @@ pub fn update_app(
         params.len()
     );
 
-    match conn.execute(
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    match tx.execute(
         &sql,
         rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
     ) {
         Ok(_) => {
+            if let Some(ref ids) = dependencies {
+                if let Err(e) = health::set_dependencies(&tx, id, ids) {
+                    eprintln!("❌ Failed to store dependencies of {id}: {e}");
+                    return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+                }
+            }
+            let (editor_kind, editor_id) = access.editor();
+            let _ = revisions::record(&tx, id, editor_kind, editor_id);
+
             let event_name = if body.status.as_deref() == Some("approved") {
                 "app.approved"
             } else {
                 "app.updated"
             };
-            bus.emit(AppEvent {
+            let event = AppEvent {
                 event: event_name.to_string(),
                 data: json!({ "app_id": id }),
-            });
-
-            (Status::Ok, Json(json!({ "message": "App updated" })))
-        }
-        Err(_) => (
-            Status::InternalServerError,
-            Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
-        ),
-    }
-}
+            };
+            match outbox::commit(tx, [event]) {
+                Ok(queued) => bus.dispatch(queued),
+                Err(err) => return err.into(),
+            }
+
+            match body.api_spec_url.as_deref() {
+                Some(url) if url.trim().is_empty() => {
+                    let _ = conn.execute("UPDATE apps SET spec_status = NULL WHERE id = ?1", rusqlite::params![id]);
+                    let _ = conn.execute("DELETE FROM app_specs WHERE app_id = ?1", rusqlite::params![id]);
+                }
+                Some(spec_url) => verifier.verify_async(&conn, id, spec_url),
+                None => {}
+            }
+            match body.logo_url.as_deref() {
+                Some(url) if url.trim().is_empty() => logos.clear(&conn, id),
+                Some(logo_url) => logos.fetch_async(&conn, id, logo_url),
+                None => {}
+            }
+            if body.protocol.is_some() || body.api_url.is_some() || body.homepage_url.is_some() {
+                agent_cards.verify_async(&conn, id);
+            }
+
+            // A window that has already started takes effect right away
+            let mut revision = revision + 1;
+            if window_changed {
+                scheduler::apply_featured_schedule(&conn, bus);
+                revision = revisions::current_revision(&conn, id).unwrap_or(revision);
+            }
+
+            (
+                Status::Ok,
+                Json(json!({ "message": "App updated", "revision": revision })),
+            )
+        }
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+// === Rename Slug ===
+
+/// Whether `slug` is an app's slug or id, or an old slug kept as a
+/// redirect, other than `app_id`'s own.
+fn slug_taken(conn: &rusqlite::Connection, slug: &str, app_id: Option<&str>) -> bool {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM apps WHERE (slug = ?1 OR id = ?1) AND id IS NOT ?2)
+             OR EXISTS (SELECT 1 FROM slug_redirects WHERE old_slug = ?1 AND app_id IS NOT ?2)",
+        rusqlite::params![slug, app_id],
+        |r| r.get(0),
+    )
+    .unwrap_or(true)
+}
+
+/// Change an app's slug. The old slug keeps answering `GET /apps/<slug>`
+/// with a redirect to the new one; an app can take back its own old slugs.
+#[post("/apps/<id>/slug", format = "json", data = "<body>")]
+pub fn rename_slug(
+    actor: Actor,
+    id: &str,
+    body: Json<RenameSlugRequest>,
+    db: &rocket::State<DbState>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    let access = match actor.can_edit(&conn, id) {
+        Ok(a) => a,
+        Err(err) => return err.into(),
+    };
+    if let Err(err) = ensure_not_archived(&conn, id) {
+        return err.into();
+    }
+    let slug = body.slug.trim();
+    if let Err(message) = check_slug(slug) {
+        return ApiError::new(ErrorCode::InvalidSlug, message).with("field", "slug").into();
+    }
+    let previous: String = match conn.query_row("SELECT slug FROM apps WHERE id = ?1", rusqlite::params![id], |r| r.get(0)) {
+        Ok(s) => s,
+        Err(_) => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+    if previous == slug {
+        return ApiError::new(ErrorCode::NoChanges, "The app already has this slug").into();
+    }
+    if slug_taken(&conn, slug, Some(id)) {
+        return ApiError::from(ErrorCode::SlugTaken).with("slug", slug).into();
+    }
+
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    let renamed = tx
+        .execute("DELETE FROM slug_redirects WHERE old_slug = ?1", rusqlite::params![slug])
+        .and_then(|_| {
+            tx.execute(
+                "INSERT OR REPLACE INTO slug_redirects (old_slug, app_id) VALUES (?1, ?2)",
+                rusqlite::params![previous, id],
+            )
+        })
+        .and_then(|_| {
+            tx.execute(
+                "UPDATE apps SET slug = ?1, revision = revision + 1, updated_at = datetime('now') WHERE id = ?2",
+                rusqlite::params![slug, id],
+            )
+        });
+    if renamed.is_err() {
+        return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+    }
+    let (editor_kind, editor_id) = access.editor();
+    let _ = revisions::record(&tx, id, editor_kind, editor_id);
+    let event = AppEvent {
+        event: "app.updated".to_string(),
+        data: json!({ "app_id": id, "slug": slug, "previous_slug": previous }),
+    };
+    match outbox::commit(tx, [event]) {
+        Ok(queued) => bus.dispatch(queued),
+        Err(err) => return err.into(),
+    }
+
+    let redirects: Vec<String> = conn
+        .prepare("SELECT old_slug FROM slug_redirects WHERE app_id = ?1 ORDER BY created_at, old_slug")
+        .and_then(|mut stmt| stmt.query_map(rusqlite::params![id], |r| r.get(0))?.collect())
+        .unwrap_or_default();
+    (
+        Status::Ok,
+        Json(json!({
+            "app_id": id,
+            "slug": slug,
+            "previous_slug": previous,
+            "redirects": redirects,
+        })),
+    )
+}
+
+// === Publish Draft ===
+
+/// Submit a draft: it goes to the review queue under manual review
+/// (unless an admin publishes it), otherwise it's approved straight away.
+#[post("/apps/<id>/publish")]
+pub fn publish_app(
+    actor: Actor,
+    id: &str,
+    db: &rocket::State<DbState>,
+    bus: &rocket::State<EventBus>,
+    review: &rocket::State<ReviewPolicy>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+
+    let access = match actor.can_edit(&conn, id) {
+        Ok(a) => a,
+        Err(err) => return err.into(),
+    };
+
+    let current: Result<(String, String, String), _> = conn.query_row(
+        "SELECT status, name, slug FROM apps WHERE id = ?1",
+        rusqlite::params![id],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    );
+    let (current_status, name, slug) = match current {
+        Ok(v) => v,
+        Err(_) => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+    if current_status != "draft" {
+        return ApiError::from(ErrorCode::NotDraft).with("status", current_status).into();
+    }
+
+    let status = if review.manual && !access.is_admin() { "pending" } else { "approved" };
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    // Listed as new from the day it goes public, not the day it was drafted
+    match tx.execute(
+        "UPDATE apps SET status = ?1, created_at = datetime('now'), revision = revision + 1, updated_at = datetime('now')
+         WHERE id = ?2 AND status = 'draft'",
+        rusqlite::params![status, id],
+    ) {
+        Ok(1) => {
+            let (editor_kind, editor_id) = access.editor();
+            let _ = revisions::record(&tx, id, editor_kind, editor_id);
+            if status == "approved" {
+                link_original(&tx, id);
+            }
+
+            let event = AppEvent {
+                event: if status == "pending" { "app.submitted" } else { "app.approved" }.to_string(),
+                data: json!({
+                    "app_id": id,
+                    "name": name,
+                    "slug": slug,
+                    "status": status,
+                }),
+            };
+            match outbox::commit(tx, [event]) {
+                Ok(queued) => bus.dispatch(queued),
+                Err(err) => return err.into(),
+            }
+
+            (
+                Status::Ok,
+                Json(json!({
+                    "message": if status == "pending" { "Draft submitted for review" } else { "Draft published" },
+                    "app_id": id,
+                    "status": status,
+                    "previous_status": "draft",
+                })),
+            )
+        }
+        Ok(_) => ApiError::from(ErrorCode::NotDraft).into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+// === Duplicate App ===
+
+/// Copy a listing into a new draft with its own slug and edit token, for
+/// publishers releasing a new version as a separate listing. Same access
+/// rules as editing the app. The copy keeps the original's owner, text,
+/// URLs, tags, docs and translations; reviews, stats and badges stay behind.
+#[post("/apps/<id>/duplicate", data = "<body>")]
+pub fn duplicate_app(
+    actor: Actor,
+    id: &str,
+    body: Option<Json<DuplicateAppRequest>>,
+    db: &rocket::State<DbState>,
+    verifier: &rocket::State<SpecVerifier>,
+    logos: &rocket::State<LogoCache>,
+    limits: &rocket::State<FieldLimits>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    let original: (String, String, Option<String>) = match conn.query_row(
+        "SELECT id, name, api_spec_url FROM apps WHERE id = ?1 OR slug = ?1",
+        rusqlite::params![id],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    ) {
+        Ok(v) => v,
+        Err(_) => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+    let (original_id, original_name, spec_url) = original;
+    let access = match actor.can_edit(&conn, &original_id) {
+        Ok(a) => a,
+        Err(err) => return err.into(),
+    };
+
+    let name = match body.as_ref().and_then(|b| b.name.as_deref()).map(str::trim) {
+        Some("") => return ApiError::new(ErrorCode::BadRequest, "'name' must not be empty").into(),
+        Some(name) => name.to_string(),
+        None => original_name,
+    };
+    if let Err(err) = crate::validation::check("name", Some(&name), limits.name) {
+        return err.into();
+    }
+    let link = body.as_ref().is_some_and(|b| b.link_original);
+
+    let new_id = uuid::Uuid::new_v4().to_string();
+    let base_slug = slugify(&name);
+    let slug = if slug_taken(&conn, &base_slug, None) { format!("{}-{}", base_slug, &new_id[..8]) } else { base_slug };
+
+    let edit_token = format!("ad_{}", uuid::Uuid::new_v4().to_string().replace('-', ""));
+
+    let result = conn.execute(
+        "INSERT INTO apps (id, name, slug, short_description, description, homepage_url, api_url, api_spec_url, protocol, category, tags, logo_url, author_name, author_url, submitted_by_key_id, status, edit_token_hash, account_id, author_email, visibility, docs_markdown, health_expectation, key_endpoints, logo_status, logo_source, duplicated_from, link_original)
+         SELECT ?1, ?2, ?3, short_description, description, homepage_url, api_url, api_spec_url, protocol, category, tags, logo_url, author_name, author_url, submitted_by_key_id, 'draft', ?4, account_id, author_email, visibility, docs_markdown, health_expectation, key_endpoints, logo_status, logo_source, id, ?5
+         FROM apps WHERE id = ?6",
+        rusqlite::params![new_id, name, slug, auth::hash_key(&edit_token), link, original_id],
+    );
+    if result.is_err() {
+        return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+    }
+    let _ = conn.execute(
+        "INSERT INTO app_translations (app_id, lang, name, short_description, description)
+         SELECT ?1, lang, name, short_description, description FROM app_translations WHERE app_id = ?2",
+        rusqlite::params![new_id, original_id],
+    );
+
+    let (editor_kind, editor_id) = access.editor();
+    let _ = revisions::record(&conn, &new_id, editor_kind, editor_id);
+    if let Some(spec_url) = spec_url.as_deref().filter(|u| !u.trim().is_empty()) {
+        verifier.verify_async(&conn, &new_id, spec_url);
+    }
+    logos.copy(&original_id, &new_id);
+
+    (
+        Status::Created,
+        Json(json!({
+            "app_id": new_id,
+            "slug": slug,
+            "status": "draft",
+            "duplicated_from": original_id,
+            "link_original": link,
+            "edit_token": edit_token,
+            "edit_url": format!("/apps/{}/edit?token={}", new_id, edit_token),
+            "publish_url": format!("/apps/{}/publish", new_id),
+            "message": "Draft copy created. Edit it, then publish it when it's ready; save your edit token to keep editing.",
+        })),
+    )
+}
+
+/// Once a copy made with `link_original` goes live, its original points at
+/// it through `replacement_app_id`. Deprecating the original stays a
+/// separate, admin decision.
+pub(crate) fn link_original(conn: &rusqlite::Connection, id: &str) {
+    let _ = conn.execute(
+        "UPDATE apps SET replacement_app_id = ?1, revision = revision + 1, updated_at = datetime('now')
+         WHERE id = (SELECT duplicated_from FROM apps WHERE id = ?1 AND link_original = 1)",
+        rusqlite::params![id],
+    );
+}
 
 // === Delete App ===
 
 #[delete("/apps/<id>")]
 pub fn delete_app(
-    opt_key: OptionalKey,
-    edit_token: EditTokenParam,
+    actor: Actor,
+    if_match: IfMatch,
     id: &str,
     db: &rocket::State<DbState>,
+    logos: &rocket::State<LogoCache>,
     bus: &rocket::State<EventBus>,
 ) -> (Status, Json<Value>) {
     let conn = db.conn();
 
     // Check edit access via edit token, API key owner, or admin
-    match check_edit_access(&conn, id, &edit_token.0, &opt_key.0) {
+    match actor.can_edit(&conn, id) {
         Ok(_) => {}
-        Err((status, err)) => return (status, Json(err)),
+        Err(err) => return err.into(),
     }
 
-    // Clean up all dependent records before deleting the app
-    conn.execute("DELETE FROM reviews WHERE app_id = ?1", rusqlite::params![id]).ok();
-    conn.execute("DELETE FROM app_views WHERE app_id = ?1", rusqlite::params![id]).ok();
-    conn.execute("DELETE FROM health_checks WHERE app_id = ?1", rusqlite::params![id]).ok();
+    if let Some(revision) = revisions::current_revision(&conn, id) {
+        if let Err(err) = if_match.check(revision) {
+            return err.into();
+        }
+    }
 
-    match conn.execute("DELETE FROM apps WHERE id = ?1", rusqlite::params![id]) {
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    match delete_app_records(&tx, id) {
         Ok(1) => {
-            bus.emit(AppEvent {
+            let event = AppEvent {
                 event: "app.deleted".to_string(),
                 data: json!({ "app_id": id }),
-            });
+            };
+            match outbox::commit(tx, [event]) {
+                Ok(queued) => bus.dispatch(queued),
+                Err(err) => return err.into(),
+            }
+            logos.remove(id);
             (Status::Ok, Json(json!({ "message": "App deleted" })))
         }
-        Ok(_) => (
-            Status::NotFound,
-            Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-        ),
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "App not found").into(),
         Err(e) => {
             eprintln!("❌ Delete app {id} failed: {e}");
+            ApiError::new(ErrorCode::DbError, "Internal server error").into()
+        }
+    }
+}
+
+/// Run `sql` with the app id as its only parameter, mapping every row.
+fn rows_for_app(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    app_id: &str,
+    map: impl FnMut(&rusqlite::Row) -> rusqlite::Result<Value>,
+) -> rusqlite::Result<Vec<Value>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(rusqlite::params![app_id], map)?;
+    rows.collect()
+}
+
+/// The app's pinned key endpoints, `[]` when it has none.
+fn key_endpoints(conn: &rusqlite::Connection, app_id: &str) -> Value {
+    conn.query_row("SELECT key_endpoints FROM apps WHERE id = ?1", rusqlite::params![app_id], |r| {
+        r.get::<_, Option<String>>(0)
+    })
+    .ok()
+    .flatten()
+    .and_then(|raw| serde_json::from_str(&raw).ok())
+    .unwrap_or_else(|| json!([]))
+}
+
+/// Everything stored about a listing in one document: the listing itself
+/// (with private fields such as `author_email` and the raw docs),
+/// translations, reviews, view/click/review aggregates and the full health
+/// check history. Reviewer identities are left out.
+fn export_bundle(conn: &rusqlite::Connection, app_id: &str) -> rusqlite::Result<Value> {
+    let mut app = conn.query_row(
+        "SELECT id, name, slug, short_description, description, homepage_url, api_url, api_spec_url, protocol, category, tags, logo_url, author_name, author_url, status, is_featured, is_verified, avg_rating, review_count, created_at, updated_at, last_health_status, last_checked_at, uptime_pct, review_note, reviewed_by, reviewed_at, deprecated_reason, deprecated_by, deprecated_at, replacement_app_id, sunset_at, spec_status, archived_at, archived_by, revision, featured_from, featured_until, visibility
+         FROM apps WHERE id = ?1",
+        rusqlite::params![app_id],
+        app_row_to_json,
+    )?;
+    let (author_email, docs_markdown): (Option<String>, Option<String>) = conn.query_row(
+        "SELECT author_email, docs_markdown FROM apps WHERE id = ?1",
+        rusqlite::params![app_id],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    )?;
+    app["author_email"] = json!(author_email);
+    app["docs_markdown"] = json!(docs_markdown);
+    app["key_endpoints"] = key_endpoints(conn, app_id);
+    app["spec"] = specs::spec_details(conn, app_id).unwrap_or(Value::Null);
+    app["badges"] = super::badges::app_badges(conn, app_id);
+
+    let translations = rows_for_app(
+        conn,
+        "SELECT lang, name, short_description, description, updated_at FROM app_translations WHERE app_id = ?1 ORDER BY lang",
+        app_id,
+        |r| {
+            Ok(json!({
+                "lang": r.get::<_, String>(0)?,
+                "name": r.get::<_, Option<String>>(1)?,
+                "short_description": r.get::<_, Option<String>>(2)?,
+                "description": r.get::<_, Option<String>>(3)?,
+                "updated_at": r.get::<_, String>(4)?,
+            }))
+        },
+    )?;
+
+    let mut reviews = rows_for_app(
+        conn,
+        "SELECT id, rating, title, body, reviewer_name, pros, cons, use_case, verified_usage, created_at, edited_at
+         FROM reviews WHERE app_id = ?1 ORDER BY created_at",
+        app_id,
+        |r| {
+            Ok(json!({
+                "id": r.get::<_, String>(0)?,
+                "rating": r.get::<_, i64>(1)?,
+                "title": r.get::<_, Option<String>>(2)?,
+                "body": r.get::<_, Option<String>>(3)?,
+                "reviewer_name": r.get::<_, Option<String>>(4)?,
+                "pros": serde_json::from_str::<Value>(&r.get::<_, String>(5)?).unwrap_or(json!([])),
+                "cons": serde_json::from_str::<Value>(&r.get::<_, String>(6)?).unwrap_or(json!([])),
+                "use_case": r.get::<_, Option<String>>(7)?,
+                "verified_usage": r.get::<_, bool>(8)?,
+                "created_at": r.get::<_, String>(9)?,
+                "edited_at": r.get::<_, Option<String>>(10)?,
+            }))
+        },
+    )?;
+    for review in &mut reviews {
+        let id = review["id"].as_str().unwrap_or_default().to_string();
+        review["reactions"] = super::reviews::reaction_counts(conn, &id);
+    }
+
+    let views = conn.query_row(
+        "SELECT COUNT(*),
+                COUNT(*) FILTER (WHERE viewed_at >= datetime('now', '-1 day')),
+                COUNT(*) FILTER (WHERE viewed_at >= datetime('now', '-7 days')),
+                COUNT(*) FILTER (WHERE viewed_at >= datetime('now', '-30 days')),
+                COUNT(DISTINCT viewer_key_id)
+         FROM app_views WHERE app_id = ?1",
+        rusqlite::params![app_id],
+        |r| {
+            Ok(json!({
+                "total": r.get::<_, i64>(0)?,
+                "last_24h": r.get::<_, i64>(1)?,
+                "last_7d": r.get::<_, i64>(2)?,
+                "last_30d": r.get::<_, i64>(3)?,
+                "unique_viewers": r.get::<_, i64>(4)?,
+            }))
+        },
+    )?;
+    let mut clicks = serde_json::Map::new();
+    for (target, _) in crate::stats::CLICK_TARGETS {
+        clicks.insert(target.to_string(), json!(0));
+    }
+    for row in rows_for_app(
+        conn,
+        "SELECT target, COUNT(*) FROM app_clicks WHERE app_id = ?1 GROUP BY target",
+        app_id,
+        |r| Ok(json!([r.get::<_, String>(0)?, r.get::<_, i64>(1)?])),
+    )? {
+        clicks.insert(row[0].as_str().unwrap_or_default().to_string(), row[1].clone());
+    }
+    let mut distribution = serde_json::Map::new();
+    for rating in 1..=5 {
+        distribution.insert(rating.to_string(), json!(0));
+    }
+    for row in rows_for_app(
+        conn,
+        "SELECT rating, COUNT(*) FROM reviews WHERE app_id = ?1 GROUP BY rating",
+        app_id,
+        |r| Ok(json!([r.get::<_, i64>(0)?, r.get::<_, i64>(1)?])),
+    )? {
+        distribution.insert(row[0].to_string(), row[1].clone());
+    }
+
+    let health = rows_for_app(
+        conn,
+        "SELECT status, status_code, response_time_ms, error_message, checked_url, checked_at
+         FROM health_checks WHERE app_id = ?1 ORDER BY checked_at",
+        app_id,
+        |r| {
+            Ok(json!({
+                "status": r.get::<_, String>(0)?,
+                "status_code": r.get::<_, Option<i64>>(1)?,
+                "response_time_ms": r.get::<_, Option<i64>>(2)?,
+                "error_message": r.get::<_, Option<String>>(3)?,
+                "checked_url": r.get::<_, Option<String>>(4)?,
+                "checked_at": r.get::<_, String>(5)?,
+            }))
+        },
+    )?;
+
+    Ok(json!({
+        "exported_at": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        "app": app,
+        "translations": translations,
+        "reviews": reviews,
+        "stats": {
+            "views": views,
+            "clicks": clicks,
+            "reviews": {
+                "count": reviews.len(),
+                "avg_rating": app["avg_rating"],
+                "distribution": distribution,
+            },
+        },
+        "health_checks": health,
+    }))
+}
+
+/// Download a listing's data as one JSON bundle. Same access rules as
+/// editing the app; admin exports are recorded in the audit log.
+#[get("/apps/<id>/export")]
+pub fn export_app(
+    actor: Actor,
+    id: &str,
+    db: &rocket::State<DbState>,
+    views: &rocket::State<crate::stats::ViewRecorder>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    let app_id: String = match conn.query_row(
+        "SELECT id FROM apps WHERE id = ?1 OR slug = ?1",
+        rusqlite::params![id],
+        |r| r.get(0),
+    ) {
+        Ok(app_id) => app_id,
+        Err(_) => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+    let access = match actor.can_edit(&conn, &app_id) {
+        Ok(access) => access,
+        Err(err) => return err.into(),
+    };
+    views.flush(&conn);
+
+    match export_bundle(&conn, &app_id) {
+        Ok(bundle) => {
+            // Owners exporting their own listing aren't audited; admins are
+            if let EditAccess::Admin(key_id) = &access {
+                let _ = conn.execute(
+                    "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, 'app.export', ?2, ?3)",
+                    rusqlite::params![uuid::Uuid::new_v4().to_string(), key_id, json!({ "app_id": app_id }).to_string()],
+                );
+            }
+            (Status::Ok, Json(bundle))
+        }
+        Err(e) => {
+            eprintln!("❌ Export of app {app_id} failed: {e}");
+            ApiError::new(ErrorCode::DbError, "Internal server error").into()
+        }
+    }
+}
+
+// === Translations ===
+
+/// Create or replace the translation of an app's text fields for one language.
+/// Same access rules as editing the app. Omitted fields fall back to the default language.
+#[put("/apps/<id>/translations/<lang>", data = "<body>")]
+#[allow(clippy::too_many_arguments)]
+pub async fn put_translation(
+    actor: Actor,
+    id: &str,
+    lang: &str,
+    body: Json<TranslationRequest>,
+    db: &rocket::State<DbState>,
+    default_lang: &rocket::State<DefaultLanguage>,
+    limits: &rocket::State<FieldLimits>,
+    filter: &rocket::State<ContentFilter>,
+    policy: &rocket::State<PolicyHook>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    if let Err(err) = actor.can_edit(&db.conn(), id) {
+        return err.into();
+    }
+
+    if let Err(err) = ensure_not_archived(&db.conn(), id) {
+        return err.into();
+    }
+
+    let lang = i18n::normalize_lang(lang);
+    if !i18n::is_valid_lang(&lang) {
+        return ApiError::new(
+            ErrorCode::InvalidLanguage,
+            "Language must be a BCP 47 tag such as 'de' or 'pt-BR'",
+        )
+        .into();
+    }
+
+    if lang == default_lang.0 {
+        return ApiError::new(
+            ErrorCode::DefaultLanguage,
+            format!("'{}' is the default language; use PATCH /apps/<id> to edit it", lang),
+        )
+        .into();
+    }
+
+    if body.name.is_none() && body.short_description.is_none() && body.description.is_none() {
+        return ApiError::new(
+            ErrorCode::NoChanges,
+            "Provide at least one of: name, short_description, description",
+        )
+        .into();
+    }
+
+    if let Err(err) = limits.check_translation(&body) {
+        return err.into();
+    }
+
+    // Translated text is shown in place of the listing's own, so it passes
+    // the same content filter and policy checks as an edit
+    let key_id = actor.key_id();
+    let text = [
+        ("short_description", body.short_description.as_deref()),
+        ("description", body.description.as_deref()),
+    ];
+    if let Err(rejection) = filter.check("app", &text).await {
+        moderation::log_rejection(&db.conn(), "app", Some(id), key_id, &rejection, &text);
+        return rejection.to_error().into();
+    }
+    if policy.is_enabled() {
+        let (listing, changed) = match policy::translated_listing(&db.conn(), id, &lang, &body) {
+            Ok(proposed) => proposed,
+            Err(_) => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+        };
+        if let Err(rejection) = policy.check("update", Some(id), listing, &changed, &actor).await {
+            moderation::log_rejection(&db.conn(), "app", Some(id), key_id, &rejection, &text);
+            return policy::to_error(&rejection).into();
+        }
+    }
+
+    let conn = db.conn();
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    let result = tx.execute(
+        "INSERT INTO app_translations (app_id, lang, name, short_description, description, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))
+         ON CONFLICT(app_id, lang) DO UPDATE SET
+            name = excluded.name,
+            short_description = excluded.short_description,
+            description = excluded.description,
+            updated_at = excluded.updated_at",
+        rusqlite::params![id, lang, body.name, body.short_description, body.description],
+    );
+
+    match result {
+        Ok(_) => {
+            let event = AppEvent {
+                event: "app.updated".to_string(),
+                data: json!({ "app_id": id, "lang": lang }),
+            };
+            match outbox::commit(tx, [event]) {
+                Ok(queued) => bus.dispatch(queued),
+                Err(err) => return err.into(),
+            }
             (
-                Status::InternalServerError,
-                Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
+                Status::Ok,
+                Json(json!({
+                    "app_id": id,
+                    "lang": lang,
+                    "name": body.name,
+                    "short_description": body.short_description,
+                    "description": body.description,
+                })),
             )
         }
+        Err(e) => {
+            eprintln!("❌ Save translation {id}/{lang} failed: {e}");
+            ApiError::new(ErrorCode::DbError, "Internal server error").into()
+        }
     }
 }
 
 // === Search (NO AUTH REQUIRED) ===
 
-#[get("/apps/search?<q>&<category>&<protocol>&<page>&<per_page>")]
+/// `fields` (comma-separated) limits each app to the named fields; the
+/// remaining filters match `list_apps`.
+#[get(
+    "/apps/search?<q>&<category>&<protocol>&<featured>&<verified>&<health>&<page>&<per_page>&<fields>&<created_after>&<created_before>&<updated_after>"
+)]
+#[allow(clippy::too_many_arguments)]
 pub fn search_apps(
     q: &str,
     category: Option<String>,
     protocol: Option<String>,
+    featured: Option<bool>,
+    verified: Option<bool>,
+    health: Option<String>,
     page: Option<i64>,
     per_page: Option<i64>,
+    fields: Option<String>,
+    created_after: Option<&str>,
+    created_before: Option<&str>,
+    updated_after: Option<&str>,
+    reader: ReadAccess,
     db: &rocket::State<DbState>,
-) -> Json<Value> {
+    page_limits: &rocket::State<PageLimits>,
+) -> (Status, Json<Value>) {
+    let fieldset = match FieldSet::parse(fields.as_deref(), SEARCH_COMPUTED_FIELDS) {
+        Ok(f) => f,
+        Err(e) => return e.into(),
+    };
+    let time_range = match filters::time_range(created_after, created_before, updated_after) {
+        Ok(f) => f,
+        Err(e) => return e.into(),
+    };
+    let filters = AppFilters {
+        category,
+        protocol,
+        featured,
+        verified,
+        health,
+        terms: search::parse_terms(q),
+        time_range,
+    };
+    let terms = &filters.terms;
+
     let conn = db.conn();
 
     let page = page.unwrap_or(1).max(1);
-    let per_page = per_page.unwrap_or(20).clamp(1, 100);
+    let limits = page_limits.for_key(&conn, reader.0.as_ref());
+    let per_page = limits.per_page(per_page);
     let offset = (page - 1) * per_page;
 
-    let search_pattern = format!("%{}%", q.to_lowercase());
-
-    let mut conditions = vec![
-        "status = 'approved'".to_string(),
-        "(LOWER(name) LIKE ?1 OR LOWER(short_description) LIKE ?1 OR LOWER(description) LIKE ?1 OR LOWER(tags) LIKE ?1)".to_string(),
-    ];
-    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![Box::new(search_pattern)];
-
-    if let Some(ref cat) = category {
-        params.push(Box::new(cat.clone()));
-        conditions.push(format!("category = ?{}", params.len()));
+    let mut query = Query::new();
+    query.condition("status = 'approved'");
+    if !reader.is_admin() {
+        query.condition(listed_visibility("visibility", reader.is_signed_in()));
     }
+    filters.apply(&mut query);
+    let base_conditions = query.conditions.clone();
+    let term_conditions = filters.bind_terms(&mut query);
+
+    // All terms must match; if nothing does, fall back to any term matching
+    let mut match_mode = "all";
+    let mut where_clause = String::new();
+    let mut total: i64 = 0;
+    let mut by_category = serde_json::Map::new();
+    let mut by_protocol = serde_json::Map::new();
+    let mut by_health = serde_json::Map::new();
+    let (mut verified, mut unverified) = (0i64, 0i64);
+    for mode in ["all", "any"] {
+        if mode == "any" && (total > 0 || terms.len() < 2) {
+            break;
+        }
+        match_mode = mode;
+        let mut conditions = base_conditions.clone();
+        if !term_conditions.is_empty() {
+            let joiner = if mode == "all" { " AND " } else { " OR " };
+            conditions.push(format!("({})", term_conditions.join(joiner)));
+        }
+        where_clause = conditions.join(" AND ");
 
-    if let Some(ref proto) = protocol {
-        params.push(Box::new(proto.clone()));
-        conditions.push(format!("protocol = ?{}", params.len()));
+        // One grouped pass over the matches yields both the total and the facet counts
+        let facet_sql = format!(
+            "SELECT category, protocol, COALESCE(last_health_status, 'unknown'), is_verified, COUNT(*)
+             FROM apps WHERE {} GROUP BY 1, 2, 3, 4",
+            where_clause
+        );
+        let mut stmt = conn.prepare(&facet_sql).unwrap();
+        let groups = stmt
+            .query_map(rusqlite::params_from_iter(query.params()), |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i32>(3)? != 0,
+                    row.get::<_, i64>(4)?,
+                ))
+            })
+            .unwrap()
+            .filter_map(|r| r.ok());
+        for (category, protocol, health, is_verified, n) in groups {
+            total += n;
+            for (facet, name) in [(&mut by_category, category), (&mut by_protocol, protocol), (&mut by_health, health)] {
+                let count = facet.entry(name).or_insert(json!(0));
+                *count = json!(count.as_i64().unwrap_or(0) + n);
+            }
+            if is_verified {
+                verified += n;
+            } else {
+                unverified += n;
+            }
+        }
     }
 
-    let where_clause = conditions.join(" AND ");
-
-    let count_sql = format!("SELECT COUNT(*) FROM apps WHERE {}", where_clause);
-    let total: i64 = conn
-        .query_row(
-            &count_sql,
-            rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
-            |r| r.get(0),
-        )
-        .unwrap_or(0);
-
-    let query = format!(
-        "SELECT id, name, slug, short_description, protocol, category, tags, is_featured, is_verified, avg_rating, review_count
-         FROM apps WHERE {} ORDER BY avg_rating DESC, review_count DESC LIMIT ?{} OFFSET ?{}",
-        where_clause,
-        params.len() + 1,
-        params.len() + 2,
+    // Sparse fieldsets still read the searchable text, after the requested columns
+    let columns = match &fieldset {
+        Some(f) => format!("{}, name, short_description, description, tags", f.select_list()),
+        None => "id, name, slug, short_description, protocol, category, tags, is_featured, is_verified, avg_rating, review_count, description".to_string(),
+    };
+    let limit_idx = query.bind(per_page);
+    let offset_idx = query.bind(offset);
+    let sql = format!(
+        "SELECT {} FROM apps WHERE {} ORDER BY avg_rating DESC, review_count DESC, rank_boost DESC LIMIT ?{} OFFSET ?{}",
+        columns, where_clause, limit_idx, offset_idx,
     );
-    params.push(Box::new(per_page));
-    params.push(Box::new(offset));
 
-    let mut stmt = conn.prepare(&query).unwrap();
+    let mut stmt = conn.prepare(&sql).unwrap();
     let apps: Vec<Value> = stmt
         .query_map(
-            rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
+            rusqlite::params_from_iter(query.params()),
             |row| {
+                if let Some(f) = &fieldset {
+                    let mut app = f.row_to_json(row)?;
+                    let base = f.column_count();
+                    let text: Vec<String> = (base..base + 4).map(|i| row.get(i)).collect::<Result<_, _>>()?;
+                    let text: Vec<&str> = text.iter().map(String::as_str).collect();
+                    if f.contains("matched_terms") {
+                        app["matched_terms"] = json!(search::matched_terms(terms, &text));
+                    }
+                    if f.contains("badges") {
+                        let id = app["id"].as_str().unwrap_or_default().to_string();
+                        app["badges"] = super::badges::app_badges(&conn, &id);
+                    }
+                    f.retain(&mut app);
+                    return Ok(app);
+                }
+                let name: String = row.get(1)?;
+                let short_description: String = row.get(3)?;
                 let tags_str: String = row.get(6)?;
+                let description: String = row.get(11)?;
                 let tags: Vec<String> = serde_json::from_str(&tags_str).unwrap_or_default();
+                let matched = search::matched_terms(terms, &[&name, &short_description, &description, &tags_str]);
+                let id: String = row.get(0)?;
                 Ok(json!({
-                    "id": row.get::<_, String>(0)?,
-                    "name": row.get::<_, String>(1)?,
+                    "id": id,
+                    "name": name,
                     "slug": row.get::<_, String>(2)?,
-                    "short_description": row.get::<_, String>(3)?,
+                    "short_description": short_description,
                     "protocol": row.get::<_, String>(4)?,
                     "category": row.get::<_, String>(5)?,
                     "tags": tags,
@@ -599,6 +1919,8 @@ pub fn search_apps(
                     "is_verified": row.get::<_, i32>(8)? != 0,
                     "avg_rating": row.get::<_, f64>(9)?,
                     "review_count": row.get::<_, i64>(10)?,
+                    "matched_terms": matched,
+                    "badges": super::badges::app_badges(&conn, &id),
                 }))
             },
         )
@@ -606,30 +1928,45 @@ pub fn search_apps(
         .filter_map(|r| r.ok())
         .collect();
 
-    Json(json!({
-        "apps": apps,
-        "total": total,
-        "page": page,
-        "per_page": per_page,
-    }))
+    (
+        Status::Ok,
+        Json(json!({
+            "apps": apps,
+            "total": total,
+            "page": page,
+            "per_page": per_page,
+            "page_limits": limits.to_json(),
+            "terms": terms,
+            "match": match_mode,
+            "facets": {
+                "category": by_category,
+                "protocol": by_protocol,
+                "health_status": by_health,
+                "verified": { "verified": verified, "unverified": unverified },
+            },
+        })),
+    )
 }
 
-/// List pending apps. Admin only. Convenience endpoint.
-#[get("/apps/pending?<page>&<per_page>")]
+/// List pending apps, oldest first. Admin only.
+///
+/// Each app carries its reviewer assignment and an SLA timer counted from
+/// submission (`REVIEW_SLA_HOURS`). Listings the discovery crawler
+/// proposed carry their `discovery` provenance. `reviewer` filters the
+/// queue: `me`, `none` for unassigned apps, or an admin key id.
+#[get("/apps/pending?<reviewer>&<page>&<per_page>")]
 pub fn list_pending_apps(
-    key: AuthenticatedKey,
+    actor: Actor,
+    reviewer: Option<String>,
     page: Option<i64>,
     per_page: Option<i64>,
     db: &rocket::State<DbState>,
+    review: &rocket::State<ReviewPolicy>,
 ) -> (Status, Json<Value>) {
-    if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(
-                json!({ "error": "ADMIN_REQUIRED", "message": "Only admins can view pending apps" }),
-            ),
-        );
-    }
+    let key = match actor.require_admin("Only admins can view pending apps") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
 
     let conn = db.conn();
 
@@ -637,25 +1974,55 @@ pub fn list_pending_apps(
     let per_page = per_page.unwrap_or(20).clamp(1, 100);
     let offset = (page - 1) * per_page;
 
-    let total: i64 = conn
+    let mut conditions = vec!["status = 'pending'".to_string()];
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+    match reviewer.as_deref() {
+        None => {}
+        Some("none") => conditions.push("assigned_reviewer_key_id IS NULL".to_string()),
+        Some(reviewer) => {
+            let reviewer = if reviewer == "me" { key.id.clone() } else { reviewer.to_string() };
+            params.push(Box::new(reviewer));
+            conditions.push(format!("assigned_reviewer_key_id = ?{}", params.len()));
+        }
+    }
+    let where_clause = conditions.join(" AND ");
+
+    let sla = format!("+{} hours", review.sla_hours);
+    params.push(Box::new(sla));
+    let sla_param = params.len();
+    let (total, overdue): (i64, i64) = conn
         .query_row(
-            "SELECT COUNT(*) FROM apps WHERE status = 'pending'",
-            [],
-            |r| r.get(0),
+            &format!(
+                "SELECT COUNT(*), COALESCE(SUM(datetime(created_at, ?{sla_param}) < datetime('now')), 0)
+                 FROM apps WHERE {where_clause}"
+            ),
+            rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
+            |r| Ok((r.get(0)?, r.get(1)?)),
         )
-        .unwrap_or(0);
+        .unwrap_or((0, 0));
 
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, name, slug, short_description, protocol, category, tags, author_name, created_at, submitted_by_key_id
-             FROM apps WHERE status = 'pending' ORDER BY created_at ASC LIMIT ?1 OFFSET ?2",
-        )
-        .unwrap();
+    let query = format!(
+        "SELECT id, name, slug, short_description, protocol, category, tags, author_name, created_at, submitted_by_key_id,
+                assigned_reviewer_key_id, assigned_at,
+                (julianday('now') - julianday(created_at)) * 24,
+                datetime(created_at, ?{sla_param}),
+                datetime(created_at, ?{sla_param}) < datetime('now'),
+                discovery
+         FROM apps WHERE {where_clause} ORDER BY created_at ASC LIMIT ?{} OFFSET ?{}",
+        params.len() + 1,
+        params.len() + 2,
+    );
+    params.push(Box::new(per_page));
+    params.push(Box::new(offset));
 
+    let mut stmt = conn.prepare(&query).unwrap();
     let apps: Vec<Value> = stmt
-        .query_map(rusqlite::params![per_page, offset], |row| {
+        .query_map(rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())), |row| {
             let tags_str: String = row.get(6)?;
             let tags: Vec<String> = serde_json::from_str(&tags_str).unwrap_or_default();
+            let waiting_hours: f64 = row.get(12)?;
+            let discovery: Option<Value> =
+                row.get::<_, Option<String>>(15)?.and_then(|d| serde_json::from_str(&d).ok());
             Ok(json!({
                 "id": row.get::<_, String>(0)?,
                 "name": row.get::<_, String>(1)?,
@@ -666,7 +2033,15 @@ pub fn list_pending_apps(
                 "tags": tags,
                 "author_name": row.get::<_, String>(7)?,
                 "created_at": row.get::<_, String>(8)?,
-                "submitted_by_key_id": row.get::<_, String>(9)?,
+                "submitted_by_key_id": row.get::<_, Option<String>>(9)?,
+                "assigned_reviewer_key_id": row.get::<_, Option<String>>(10)?,
+                "assigned_at": row.get::<_, Option<String>>(11)?,
+                "discovery": discovery,
+                "sla": {
+                    "waiting_hours": (waiting_hours * 10.0).round() / 10.0,
+                    "due_at": row.get::<_, String>(13)?,
+                    "overdue": row.get::<_, bool>(14)?,
+                },
             }))
         })
         .unwrap()
@@ -678,12 +2053,123 @@ pub fn list_pending_apps(
         Json(json!({
             "apps": apps,
             "total": total,
+            "overdue": overdue,
+            "sla_hours": review.sla_hours,
+            "manual_review": review.manual,
             "page": page,
             "per_page": per_page,
         })),
     )
 }
 
+/// Normalize an optional author email; blank means none.
+fn parse_author_email(raw: Option<&str>) -> Result<Option<String>, ApiError> {
+    let email = match raw.map(crate::accounts::normalize_email) {
+        Some(e) if !e.is_empty() => e,
+        _ => return Ok(None),
+    };
+    if !crate::accounts::is_valid_email(&email) {
+        return Err(ApiError::new(ErrorCode::InvalidEmail, "author_email is not a valid email address")
+            .with("field", "author_email"));
+    }
+    Ok(Some(email))
+}
+
+/// Archived apps are read-only: edits, translations, and new reviews get `409 ARCHIVED`.
+pub(crate) fn ensure_not_archived(conn: &rusqlite::Connection, id: &str) -> Result<(), ApiError> {
+    let status: Option<String> = conn
+        .query_row("SELECT status FROM apps WHERE id = ?1", rusqlite::params![id], |r| r.get(0))
+        .ok();
+    if status.as_deref() == Some("archived") {
+        return Err(ApiError::new(
+            ErrorCode::Archived,
+            "App is archived and read-only. Unarchive it first.",
+        ));
+    }
+    Ok(())
+}
+
+/// Follow `replacement_app_id` from a deprecated app through any further
+/// deprecated replacements. Returns the final active (approved) app's id and
+/// the ids visited after the starting app, ending with that final id.
+pub(crate) fn resolve_replacement(conn: &rusqlite::Connection, id: &str) -> Result<(String, Vec<String>), ApiError> {
+    let lookup = |id: &str| -> Option<(String, String, Option<String>)> {
+        conn.query_row(
+            "SELECT id, status, replacement_app_id FROM apps WHERE id = ?1 OR slug = ?1",
+            rusqlite::params![id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )
+        .ok()
+    };
+
+    let (start, status, mut next) =
+        lookup(id).ok_or_else(|| ApiError::new(ErrorCode::NotFound, "App not found"))?;
+    if status != "deprecated" {
+        return Err(ApiError::from(ErrorCode::NotDeprecated));
+    }
+
+    let mut seen = std::collections::HashSet::from([start]);
+    let mut chain = Vec::new();
+    while let Some(current) = next {
+        if !seen.insert(current.clone()) {
+            return Err(ApiError::from(ErrorCode::ReplacementCycle).with("chain", chain));
+        }
+        chain.push(current.clone());
+        let Some((_, status, replacement)) = lookup(&current) else {
+            break;
+        };
+        match status.as_str() {
+            "approved" => return Ok((current, chain)),
+            "deprecated" => next = replacement,
+            _ => break,
+        }
+    }
+    Err(ApiError::new(ErrorCode::NotFound, "No active replacement for this app").with("chain", chain))
+}
+
+/// Delete an app and all its dependent records. Returns the number of app rows deleted.
+pub(crate) fn delete_app_records(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<usize> {
+    // Clean up all dependent records before deleting the app
+    conn.execute(
+        "DELETE FROM review_versions WHERE review_id IN (SELECT id FROM reviews WHERE app_id = ?1)",
+        rusqlite::params![id],
+    )
+    .ok();
+    conn.execute(
+        "DELETE FROM review_reactions WHERE review_id IN (SELECT id FROM reviews WHERE app_id = ?1)",
+        rusqlite::params![id],
+    )
+    .ok();
+    conn.execute("DELETE FROM reviews WHERE app_id = ?1", rusqlite::params![id]).ok();
+    conn.execute("DELETE FROM app_views WHERE app_id = ?1", rusqlite::params![id]).ok();
+    conn.execute("DELETE FROM app_clicks WHERE app_id = ?1", rusqlite::params![id]).ok();
+    conn.execute("DELETE FROM health_checks WHERE app_id = ?1", rusqlite::params![id]).ok();
+    conn.execute("DELETE FROM app_specs WHERE app_id = ?1", rusqlite::params![id]).ok();
+    conn.execute("DELETE FROM app_agent_cards WHERE app_id = ?1", rusqlite::params![id]).ok();
+    conn.execute("DELETE FROM app_translations WHERE app_id = ?1", rusqlite::params![id]).ok();
+    conn.execute("DELETE FROM app_revisions WHERE app_id = ?1", rusqlite::params![id]).ok();
+    conn.execute("DELETE FROM app_badges WHERE app_id = ?1", rusqlite::params![id]).ok();
+    conn.execute("DELETE FROM slug_redirects WHERE app_id = ?1", rusqlite::params![id]).ok();
+    conn.execute("DELETE FROM app_conformance WHERE app_id = ?1", rusqlite::params![id]).ok();
+    conn.execute(
+        "DELETE FROM app_dependencies WHERE app_id = ?1 OR depends_on_id = ?1",
+        rusqlite::params![id],
+    )
+    .ok();
+    conn.execute(
+        "DELETE FROM app_endorsements WHERE endorser_app_id = ?1 OR endorsed_app_id = ?1",
+        rusqlite::params![id],
+    )
+    .ok();
+    conn.execute("DELETE FROM apps WHERE id = ?1", rusqlite::params![id])
+}
+
+/// Admin-set ranking boost; only shown to admins.
+fn rank_boost(conn: &rusqlite::Connection, app_id: &str) -> i64 {
+    conn.query_row("SELECT rank_boost FROM apps WHERE id = ?1", rusqlite::params![app_id], |r| r.get(0))
+        .unwrap_or(0)
+}
+
 /// Helper to map a full app row to JSON.
 fn app_row_to_json(row: &rusqlite::Row) -> Result<Value, rusqlite::Error> {
     let tags_str: String = row.get(10)?;
@@ -721,5 +2207,13 @@ fn app_row_to_json(row: &rusqlite::Row) -> Result<Value, rusqlite::Error> {
         "deprecated_at": row.get::<_, Option<String>>(29)?,
         "replacement_app_id": row.get::<_, Option<String>>(30)?,
         "sunset_at": row.get::<_, Option<String>>(31)?,
+        "spec_status": row.get::<_, Option<String>>(32)?,
+        "archived_at": row.get::<_, Option<String>>(33)?,
+        "archived_by": row.get::<_, Option<String>>(34)?,
+        "revision": row.get::<_, i64>(35)?,
+        "featured_from": row.get::<_, Option<String>>(36)?,
+        "featured_until": row.get::<_, Option<String>>(37)?,
+        "visibility": row.get::<_, String>(38)?,
+        "logo": format!("/media/logos/{}.png", row.get::<_, String>(0)?),
     }))
 }
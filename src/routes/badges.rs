@@ -0,0 +1,303 @@
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use serde_json::{json, Value};
+
+use crate::auth::AuthenticatedKey;
+use crate::errors::{ApiError, ErrorCode};
+use crate::DbState;
+
+/// Length limits for badge fields, in characters.
+const MAX_BADGE_SLUG: usize = 50;
+const MAX_BADGE_LABEL: usize = 50;
+const MAX_BADGE_DESCRIPTION: usize = 300;
+
+/// Color used when a badge is created without one.
+const DEFAULT_BADGE_COLOR: &str = "#6b7280";
+
+#[derive(Debug, serde::Deserialize)]
+pub struct CreateBadgeRequest {
+    pub slug: String,
+    pub label: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct UpdateBadgeRequest {
+    pub label: Option<String>,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+fn invalid(field: &str, message: &str) -> ApiError {
+    ApiError::new(ErrorCode::InvalidBadge, message).with("field", field)
+}
+
+fn validate_slug(slug: &str) -> Result<(), ApiError> {
+    let ok = !slug.is_empty()
+        && slug.len() <= MAX_BADGE_SLUG
+        && slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !slug.starts_with('-')
+        && !slug.ends_with('-');
+    if ok {
+        Ok(())
+    } else {
+        Err(invalid(
+            "slug",
+            "slug must be 1-50 lowercase letters, digits, or inner hyphens",
+        ))
+    }
+}
+
+fn validate_label(label: &str) -> Result<(), ApiError> {
+    let len = label.trim().chars().count();
+    if len == 0 || len > MAX_BADGE_LABEL {
+        return Err(invalid("label", "label must be 1-50 characters"));
+    }
+    Ok(())
+}
+
+fn validate_color(color: &str) -> Result<(), ApiError> {
+    let hex = color.strip_prefix('#').unwrap_or("");
+    if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(invalid("color", "color must be a hex value like #1a7f37"))
+    }
+}
+
+fn validate_description(description: &str) -> Result<(), ApiError> {
+    if description.chars().count() > MAX_BADGE_DESCRIPTION {
+        return Err(invalid("description", "description must be at most 300 characters"));
+    }
+    Ok(())
+}
+
+fn badge_row_to_json(row: &rusqlite::Row) -> Result<Value, rusqlite::Error> {
+    Ok(json!({
+        "slug": row.get::<_, String>(0)?,
+        "label": row.get::<_, String>(1)?,
+        "color": row.get::<_, String>(2)?,
+        "description": row.get::<_, Option<String>>(3)?,
+        "created_at": row.get::<_, String>(4)?,
+    }))
+}
+
+fn get_badge(conn: &rusqlite::Connection, slug: &str) -> Option<Value> {
+    conn.query_row(
+        "SELECT slug, label, color, description, created_at FROM badges WHERE slug = ?1",
+        rusqlite::params![slug],
+        badge_row_to_json,
+    )
+    .ok()
+}
+
+/// Badges awarded to an app, in the order they were awarded.
+pub(crate) fn app_badges(conn: &rusqlite::Connection, app_id: &str) -> Value {
+    let mut stmt = conn
+        .prepare(
+            "SELECT b.slug, b.label, b.color, b.description
+             FROM app_badges ab JOIN badges b ON b.slug = ab.badge_slug
+             WHERE ab.app_id = ?1 ORDER BY ab.awarded_at, ab.rowid",
+        )
+        .unwrap();
+    let badges: Vec<Value> = stmt
+        .query_map(rusqlite::params![app_id], |row| {
+            Ok(json!({
+                "slug": row.get::<_, String>(0)?,
+                "label": row.get::<_, String>(1)?,
+                "color": row.get::<_, String>(2)?,
+                "description": row.get::<_, Option<String>>(3)?,
+            }))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+    json!(badges)
+}
+
+/// List badge definitions with how many apps carry each.
+#[get("/badges")]
+pub fn list_badges(db: &rocket::State<DbState>) -> Json<Value> {
+    let conn = db.conn();
+    let mut stmt = conn
+        .prepare(
+            "SELECT b.slug, b.label, b.color, b.description, b.created_at,
+                    (SELECT COUNT(*) FROM app_badges ab WHERE ab.badge_slug = b.slug)
+             FROM badges b ORDER BY b.slug",
+        )
+        .unwrap();
+    let badges: Vec<Value> = stmt
+        .query_map([], |row| {
+            let mut badge = badge_row_to_json(row)?;
+            badge["app_count"] = json!(row.get::<_, i64>(5)?);
+            Ok(badge)
+        })
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+    Json(json!({ "badges": badges }))
+}
+
+/// Define a badge. Admin only.
+#[post("/badges", format = "json", data = "<body>")]
+pub fn create_badge(
+    key: AuthenticatedKey,
+    body: Json<CreateBadgeRequest>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::from(ErrorCode::AdminRequired).into();
+    }
+
+    let slug = body.slug.trim();
+    let color = body.color.as_deref().map(str::trim).unwrap_or(DEFAULT_BADGE_COLOR).to_lowercase();
+    let description = body.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+    let checks = validate_slug(slug)
+        .and_then(|_| validate_label(&body.label))
+        .and_then(|_| validate_color(&color))
+        .and_then(|_| description.map_or(Ok(()), validate_description));
+    if let Err(e) = checks {
+        return e.into();
+    }
+
+    let conn = db.conn();
+    if get_badge(&conn, slug).is_some() {
+        return ApiError::from(ErrorCode::BadgeExists).with("slug", slug).into();
+    }
+    match conn.execute(
+        "INSERT INTO badges (slug, label, color, description) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![slug, body.label.trim(), color, description],
+    ) {
+        Ok(_) => (Status::Created, Json(get_badge(&conn, slug).unwrap_or(Value::Null))),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+/// Update a badge's label, color, or description. Admin only.
+#[patch("/badges/<slug>", format = "json", data = "<body>")]
+pub fn update_badge(
+    key: AuthenticatedKey,
+    slug: &str,
+    body: Json<UpdateBadgeRequest>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::from(ErrorCode::AdminRequired).into();
+    }
+
+    let conn = db.conn();
+    if get_badge(&conn, slug).is_none() {
+        return ApiError::new(ErrorCode::NotFound, "Badge not found").into();
+    }
+    if body.label.is_none() && body.color.is_none() && body.description.is_none() {
+        return ApiError::from(ErrorCode::NoChanges).into();
+    }
+
+    let label = body.label.as_deref().map(str::trim);
+    let color = body.color.as_deref().map(|c| c.trim().to_lowercase());
+    let description = body.description.as_deref().map(str::trim);
+    let checks = label
+        .map_or(Ok(()), validate_label)
+        .and_then(|_| color.as_deref().map_or(Ok(()), validate_color))
+        .and_then(|_| description.map_or(Ok(()), validate_description));
+    if let Err(e) = checks {
+        return e.into();
+    }
+
+    let _ = conn.execute(
+        "UPDATE badges SET label = COALESCE(?1, label), color = COALESCE(?2, color),
+                description = CASE WHEN ?3 IS NULL THEN description ELSE NULLIF(?3, '') END
+         WHERE slug = ?4",
+        rusqlite::params![label, color, description, slug],
+    );
+
+    (Status::Ok, Json(get_badge(&conn, slug).unwrap_or(Value::Null)))
+}
+
+/// Delete a badge and remove it from every app. Admin only.
+#[delete("/badges/<slug>")]
+pub fn delete_badge(key: AuthenticatedKey, slug: &str, db: &rocket::State<DbState>) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::from(ErrorCode::AdminRequired).into();
+    }
+
+    let conn = db.conn();
+    let removed = conn
+        .execute("DELETE FROM app_badges WHERE badge_slug = ?1", rusqlite::params![slug])
+        .unwrap_or(0);
+    match conn.execute("DELETE FROM badges WHERE slug = ?1", rusqlite::params![slug]) {
+        Ok(1) => (
+            Status::Ok,
+            Json(json!({ "message": "Badge deleted", "removed_from_apps": removed })),
+        ),
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "Badge not found").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+/// Resolve an app id or slug and a badge slug, or the matching 404.
+fn resolve(conn: &rusqlite::Connection, id: &str, slug: &str) -> Result<String, ApiError> {
+    let app_id: String = conn
+        .query_row(
+            "SELECT id FROM apps WHERE id = ?1 OR slug = ?1",
+            rusqlite::params![id],
+            |r| r.get(0),
+        )
+        .map_err(|_| ApiError::new(ErrorCode::NotFound, "App not found"))?;
+    if get_badge(conn, slug).is_none() {
+        return Err(ApiError::new(ErrorCode::NotFound, "Badge not found"));
+    }
+    Ok(app_id)
+}
+
+/// Award a badge to an app. Idempotent. Admin only.
+#[post("/apps/<id>/badges/<slug>")]
+pub fn award_badge(
+    key: AuthenticatedKey,
+    id: &str,
+    slug: &str,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::from(ErrorCode::AdminRequired).into();
+    }
+
+    let conn = db.conn();
+    let app_id = match resolve(&conn, id, slug) {
+        Ok(app_id) => app_id,
+        Err(e) => return e.into(),
+    };
+    let _ = conn.execute(
+        "INSERT OR IGNORE INTO app_badges (app_id, badge_slug, awarded_by) VALUES (?1, ?2, ?3)",
+        rusqlite::params![app_id, slug, key.id],
+    );
+    (Status::Ok, Json(json!({ "app_id": app_id, "badges": app_badges(&conn, &app_id) })))
+}
+
+/// Remove a badge from an app. Admin only.
+#[delete("/apps/<id>/badges/<slug>")]
+pub fn revoke_badge(
+    key: AuthenticatedKey,
+    id: &str,
+    slug: &str,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::from(ErrorCode::AdminRequired).into();
+    }
+
+    let conn = db.conn();
+    let app_id = match resolve(&conn, id, slug) {
+        Ok(app_id) => app_id,
+        Err(e) => return e.into(),
+    };
+    match conn.execute(
+        "DELETE FROM app_badges WHERE app_id = ?1 AND badge_slug = ?2",
+        rusqlite::params![app_id, slug],
+    ) {
+        Ok(1) => (Status::Ok, Json(json!({ "app_id": app_id, "badges": app_badges(&conn, &app_id) }))),
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "App does not have this badge").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
@@ -0,0 +1,214 @@
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use serde_json::{json, Value};
+
+use crate::auth::{Actor, ReadAccess};
+use crate::errors::{ApiError, ErrorCode};
+use crate::models::listed_visibility;
+use crate::moderation::{self, ContentFilter};
+use crate::DbState;
+
+/// Longest endorsement note, in characters.
+const MAX_ENDORSEMENT_NOTE: usize = 280;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct EndorseRequest {
+    /// Id or slug of the endorsing app; the caller must be able to edit it.
+    pub from_app_id: String,
+    /// Short "works great with" note, shown on both listings.
+    pub note: Option<String>,
+}
+
+/// Id, status and visibility of an app by id or slug.
+fn resolve(conn: &rusqlite::Connection, id_or_slug: &str) -> Result<(String, String, String), ApiError> {
+    conn.query_row(
+        "SELECT id, status, visibility FROM apps WHERE id = ?1 OR slug = ?1",
+        rusqlite::params![id_or_slug],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    )
+    .map_err(|_| ApiError::new(ErrorCode::NotFound, "App not found"))
+}
+
+/// Endorsements given or received by `app_id`, newest first, showing the
+/// other app when it's approved and listed for the caller.
+fn endorsement_list(conn: &rusqlite::Connection, app_id: &str, received: bool, signed_in: bool) -> Vec<Value> {
+    let (own, other) = match received {
+        true => ("endorsed_app_id", "endorser_app_id"),
+        false => ("endorser_app_id", "endorsed_app_id"),
+    };
+    let sql = format!(
+        "SELECT a.id, a.name, a.slug, e.note, e.created_at
+         FROM app_endorsements e JOIN apps a ON a.id = e.{other}
+         WHERE e.{own} = ?1 AND a.status = 'approved' AND {}
+         ORDER BY e.created_at DESC, e.rowid DESC",
+        listed_visibility("a.visibility", signed_in)
+    );
+    conn.prepare(&sql)
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![app_id], |r| {
+                Ok(json!({
+                    "app": {
+                        "id": r.get::<_, String>(0)?,
+                        "name": r.get::<_, String>(1)?,
+                        "slug": r.get::<_, String>(2)?,
+                    },
+                    "note": r.get::<_, Option<String>>(3)?,
+                    "created_at": r.get::<_, String>(4)?,
+                }))
+            })?
+            .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `{received, given}` endorsement counts for an app, over approved apps.
+pub(crate) fn endorsement_counts(conn: &rusqlite::Connection, app_id: &str) -> Value {
+    let count = |own: &str, other: &str| -> i64 {
+        conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM app_endorsements e JOIN apps a ON a.id = e.{other}
+                 WHERE e.{own} = ?1 AND a.status = 'approved'"
+            ),
+            rusqlite::params![app_id],
+            |r| r.get(0),
+        )
+        .unwrap_or(0)
+    };
+    json!({
+        "received": count("endorsed_app_id", "endorser_app_id"),
+        "given": count("endorser_app_id", "endorsed_app_id"),
+    })
+}
+
+/// Endorse an app from another listing ("works great with X"). Authorized
+/// by the endorsing app's edit token, owner API key or account session.
+/// Both apps must be approved. Endorsing again replaces the note.
+#[post("/apps/<id>/endorsements", format = "json", data = "<body>")]
+pub async fn endorse_app(
+    actor: Actor,
+    id: &str,
+    body: Json<EndorseRequest>,
+    db: &rocket::State<DbState>,
+    filter: &rocket::State<ContentFilter>,
+) -> (Status, Json<Value>) {
+    let note = body.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if note.is_some_and(|n| n.chars().count() > MAX_ENDORSEMENT_NOTE) {
+        return ApiError::new(ErrorCode::FieldTooLong, "note must be at most 280 characters")
+            .with("field", "note")
+            .with("max", MAX_ENDORSEMENT_NOTE)
+            .into();
+    }
+
+    let (endorser_id, endorsed_id, endorsed_by) = {
+        let conn = db.conn();
+        let (endorsed_id, endorsed_status, _) = match resolve(&conn, id) {
+            Ok(app) => app,
+            Err(e) => return e.into(),
+        };
+        let (endorser_id, endorser_status, _) = match resolve(&conn, &body.from_app_id) {
+            Ok(app) => app,
+            Err(e) => return e.with("field", "from_app_id").into(),
+        };
+        let access = match actor.can_edit(&conn, &endorser_id) {
+            Ok(access) => access,
+            Err(e) => return e.into(),
+        };
+        if endorser_id == endorsed_id {
+            return ApiError::new(ErrorCode::InvalidEndorsement, "An app can't endorse itself").into();
+        }
+        if endorser_status != "approved" || endorsed_status != "approved" {
+            return ApiError::new(ErrorCode::InvalidEndorsement, "Only approved apps can endorse or be endorsed").into();
+        }
+        (endorser_id, endorsed_id, access.editor().1.map(str::to_string))
+    };
+
+    let text = [("note", note)];
+    if let Err(rejection) = filter.check("endorsement", &text).await {
+        moderation::log_rejection(&db.conn(), "endorsement", Some(&endorsed_id), actor.key_id(), &rejection, &text);
+        return rejection.to_error().into();
+    }
+
+    let conn = db.conn();
+    let existed = conn
+        .query_row(
+            "SELECT 1 FROM app_endorsements WHERE endorser_app_id = ?1 AND endorsed_app_id = ?2",
+            rusqlite::params![endorser_id, endorsed_id],
+            |_| Ok(()),
+        )
+        .is_ok();
+    let saved = conn.execute(
+        "INSERT INTO app_endorsements (endorser_app_id, endorsed_app_id, note, endorsed_by)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (endorser_app_id, endorsed_app_id) DO UPDATE SET note = excluded.note",
+        rusqlite::params![endorser_id, endorsed_id, note, endorsed_by],
+    );
+    if saved.is_err() {
+        return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+    }
+    (
+        if existed { Status::Ok } else { Status::Created },
+        Json(json!({
+            "endorser_app_id": endorser_id,
+            "endorsed_app_id": endorsed_id,
+            "note": note,
+            "endorsements": endorsement_counts(&conn, &endorsed_id),
+        })),
+    )
+}
+
+/// Withdraw an endorsement. Same authorization as giving it.
+#[delete("/apps/<id>/endorsements/<from_app_id>")]
+pub fn withdraw_endorsement(
+    actor: Actor,
+    id: &str,
+    from_app_id: &str,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    let (endorsed_id, endorser_id) = match (resolve(&conn, id), resolve(&conn, from_app_id)) {
+        (Ok((endorsed, ..)), Ok((endorser, ..))) => (endorsed, endorser),
+        (Err(e), _) | (_, Err(e)) => return e.into(),
+    };
+    if let Err(e) = actor.can_edit(&conn, &endorser_id) {
+        return e.into();
+    }
+    match conn.execute(
+        "DELETE FROM app_endorsements WHERE endorser_app_id = ?1 AND endorsed_app_id = ?2",
+        rusqlite::params![endorser_id, endorsed_id],
+    ) {
+        Ok(1) => (
+            Status::Ok,
+            Json(json!({
+                "withdrawn": true,
+                "endorser_app_id": endorser_id,
+                "endorsed_app_id": endorsed_id,
+                "endorsements": endorsement_counts(&conn, &endorsed_id),
+            })),
+        ),
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "This app has not endorsed that app").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+/// Endorsements an app received and gave, each naming the other app.
+#[get("/apps/<id_or_slug>/endorsements")]
+pub fn list_endorsements(reader: ReadAccess, id_or_slug: &str, db: &rocket::State<DbState>) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    let app_id = match resolve(&conn, id_or_slug) {
+        Ok((app_id, status, visibility))
+            if status == "approved" && (visibility != "private" || reader.is_signed_in()) =>
+        {
+            app_id
+        }
+        Ok(_) | Err(_) => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+    let signed_in = reader.is_signed_in();
+    (
+        Status::Ok,
+        Json(json!({
+            "app_id": app_id,
+            "received": endorsement_list(&conn, &app_id, true, signed_in),
+            "given": endorsement_list(&conn, &app_id, false, signed_in),
+        })),
+    )
+}
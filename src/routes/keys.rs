@@ -3,37 +3,94 @@ use rocket::serde::json::Json;
 use serde_json::{json, Value};
 
 use crate::auth::{self, AuthenticatedKey, OptionalKey};
+use crate::oauth;
+use crate::errors::{ApiError, ErrorCode};
 use crate::models;
+use crate::pagination::PAGE_SIZE_CEILING;
+use crate::rate_limit::{self, CurrentRateLimit, RateLimiter, ReviewThrottle, MAX_BURST, MAX_EXEMPT_ROUTES};
 use crate::DbState;
 
+/// Validate rate limit settings from a create or update request. Exempt
+/// routes come back normalized and serialized for `api_keys.exempt_routes`.
+fn rate_settings(
+    rate_limit: Option<i64>,
+    burst: Option<i64>,
+    exempt_routes: Option<&[String]>,
+) -> Result<Option<String>, ApiError> {
+    if rate_limit.is_some_and(|l| l < 1) {
+        return Err(ApiError::new(ErrorCode::InvalidRateLimit, "rate_limit must be at least 1").with("field", "rate_limit"));
+    }
+    if burst.is_some_and(|b| !(0..=MAX_BURST).contains(&b)) {
+        return Err(ApiError::new(ErrorCode::InvalidRateLimit, format!("burst must be between 0 and {MAX_BURST}"))
+            .with("field", "burst"));
+    }
+    let Some(routes) = exempt_routes else { return Ok(None) };
+    if routes.len() > MAX_EXEMPT_ROUTES {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRateLimit,
+            format!("At most {MAX_EXEMPT_ROUTES} exempt routes per key"),
+        )
+        .with("field", "exempt_routes"));
+    }
+    let routes = routes
+        .iter()
+        .map(|r| rate_limit::parse_route_pattern(r))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|msg| ApiError::new(ErrorCode::InvalidRateLimit, msg).with("field", "exempt_routes"))?;
+    Ok(Some(serde_json::to_string(&routes).unwrap()))
+}
+
+/// Validate per-key page sizes; 0 means "use the instance limit".
+fn page_settings(default_page_size: Option<i64>, max_page_size: Option<i64>) -> Result<(), ApiError> {
+    for (field, value) in [("default_page_size", default_page_size), ("max_page_size", max_page_size)] {
+        if value.is_some_and(|v| !(0..=PAGE_SIZE_CEILING).contains(&v)) {
+            return Err(ApiError::new(
+                ErrorCode::InvalidPageSize,
+                format!("{field} must be between 0 and {PAGE_SIZE_CEILING}"),
+            )
+            .with("field", field));
+        }
+    }
+    Ok(())
+}
+
+/// A key's rate limit and page size settings as returned by the key endpoints.
+fn key_json(row: &rusqlite::Row) -> rusqlite::Result<Value> {
+    let exempt: Vec<String> = serde_json::from_str(&row.get::<_, String>(7)?).unwrap_or_default();
+    Ok(json!({
+        "id": row.get::<_, String>(0)?,
+        "name": row.get::<_, String>(1)?,
+        "is_admin": row.get::<_, i32>(2)? != 0,
+        "rate_limit": row.get::<_, i64>(3)?,
+        "created_at": row.get::<_, String>(4)?,
+        "expires_at": row.get::<_, Option<String>>(5)?,
+        "burst": row.get::<_, i64>(6)?,
+        "exempt_routes": exempt,
+        "default_page_size": row.get::<_, Option<i64>>(8)?,
+        "max_page_size": row.get::<_, Option<i64>>(9)?,
+        "throttled_until": row.get::<_, Option<String>>(10)?,
+    }))
+}
+
+const KEY_COLUMNS: &str =
+    "id, name, is_admin, rate_limit, created_at, expires_at, rate_burst, exempt_routes, default_page_size, max_page_size,
+     CASE WHEN throttled_until > datetime('now') THEN throttled_until END";
+
 // === Admin: API Keys ===
 
 #[get("/keys")]
 pub fn list_keys(key: AuthenticatedKey, db: &rocket::State<DbState>) -> (Status, Json<Value>) {
     if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(json!({ "error": "ADMIN_REQUIRED" })),
-        );
+        return ApiError::from(ErrorCode::AdminRequired).into();
     }
 
     let conn = db.conn();
     let mut stmt = conn
-        .prepare(
-            "SELECT id, name, is_admin, rate_limit, created_at FROM api_keys WHERE revoked = 0",
-        )
+        .prepare(&format!("SELECT {KEY_COLUMNS} FROM api_keys WHERE revoked = 0"))
         .unwrap();
 
     let keys: Vec<Value> = stmt
-        .query_map([], |row| {
-            Ok(json!({
-                "id": row.get::<_, String>(0)?,
-                "name": row.get::<_, String>(1)?,
-                "is_admin": row.get::<_, i32>(2)? != 0,
-                "rate_limit": row.get::<_, i64>(3)?,
-                "created_at": row.get::<_, String>(4)?,
-            }))
-        })
+        .query_map([], key_json)
         .unwrap()
         .filter_map(|r| r.ok())
         .collect();
@@ -51,29 +108,240 @@ pub fn create_key(
     let requester_is_admin = opt_key.0.as_ref().map(|k| k.is_admin).unwrap_or(false);
 
     if is_admin_request && !requester_is_admin {
-        return (
-            Status::Forbidden,
-            Json(json!({ "error": "ADMIN_REQUIRED", "message": "Only admins can create admin keys" })),
-        );
+        return ApiError::new(ErrorCode::AdminRequired, "Only admins can create admin keys").into();
+    }
+
+    // Bursts, exemptions and page sizes loosen limits, so only admins hand them out
+    if (body.burst.is_some() || body.exempt_routes.is_some()) && !requester_is_admin {
+        return ApiError::new(ErrorCode::AdminRequired, "Only admins can set burst or exempt_routes").into();
+    }
+    if (body.default_page_size.is_some() || body.max_page_size.is_some()) && !requester_is_admin {
+        return ApiError::new(ErrorCode::AdminRequired, "Only admins can set page sizes").into();
+    }
+
+    let expires_at = match body.expires_at.as_deref().map(auth::parse_expiry).transpose() {
+        Ok(e) => e,
+        Err(msg) => {
+            return ApiError::new(ErrorCode::InvalidExpiry, msg).into()
+        }
+    };
+    let exempt_routes = match rate_settings(body.rate_limit, body.burst, body.exempt_routes.as_deref()) {
+        Ok(r) => r,
+        Err(e) => return e.into(),
+    };
+    if let Err(e) = page_settings(body.default_page_size, body.max_page_size) {
+        return e.into();
     }
 
     let conn = db.conn();
-    let raw_key = auth::create_api_key(
+    let (id, raw_key) = auth::create_expiring_api_key(
         &conn,
         &body.name,
         is_admin_request,
         body.rate_limit,
+        expires_at.as_deref(),
     );
+    if body.burst.is_some() || exempt_routes.is_some() {
+        let _ = conn.execute(
+            "UPDATE api_keys SET rate_burst = COALESCE(?1, rate_burst), exempt_routes = COALESCE(?2, exempt_routes)
+             WHERE id = ?3",
+            rusqlite::params![body.burst, exempt_routes, id],
+        );
+    }
+    if body.default_page_size.is_some() || body.max_page_size.is_some() {
+        let _ = conn.execute(
+            "UPDATE api_keys SET default_page_size = NULLIF(?1, 0), max_page_size = NULLIF(?2, 0) WHERE id = ?3",
+            rusqlite::params![body.default_page_size, body.max_page_size, id],
+        );
+    }
 
     (
         Status::Created,
         Json(json!({
+            "id": id,
             "api_key": raw_key,
+            "expires_at": expires_at,
             "message": "Save this key — it won't be shown again"
         })),
     )
 }
 
+/// Change a key's name, admin flag, rate limit, burst allowance, exempt
+/// routes or page sizes. Omitted fields keep their current values;
+/// `exempt_routes: []` clears them and a page size of 0 falls back to the
+/// instance limit. The change is recorded in the audit log, and a new rate
+/// limit or burst restarts the key's current window.
+#[patch("/keys/<id>", data = "<body>")]
+pub fn update_key(
+    key: AuthenticatedKey,
+    id: &str,
+    body: Json<models::UpdateKeyRequest>,
+    db: &rocket::State<DbState>,
+    limiter: &rocket::State<RateLimiter>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::from(ErrorCode::AdminRequired).into();
+    }
+    if body.name.is_none()
+        && body.is_admin.is_none()
+        && body.rate_limit.is_none()
+        && body.burst.is_none()
+        && body.exempt_routes.is_none()
+        && body.default_page_size.is_none()
+        && body.max_page_size.is_none()
+    {
+        return ApiError::from(ErrorCode::NoChanges).into();
+    }
+    let name = body.name.as_deref().map(str::trim);
+    if name.is_some_and(str::is_empty) {
+        return ApiError::new(ErrorCode::BadRequest, "name must not be empty").with("field", "name").into();
+    }
+    let exempt_routes = match rate_settings(body.rate_limit, body.burst, body.exempt_routes.as_deref()) {
+        Ok(r) => r,
+        Err(e) => return e.into(),
+    };
+    if let Err(e) = page_settings(body.default_page_size, body.max_page_size) {
+        return e.into();
+    }
+
+    let conn = db.conn();
+    let key_query = format!("SELECT {KEY_COLUMNS} FROM api_keys WHERE id = ?1 AND revoked = 0");
+    let before = match conn.query_row(&key_query, rusqlite::params![id], key_json) {
+        Ok(k) => k,
+        Err(_) => return ApiError::from(ErrorCode::NotFound).into(),
+    };
+
+    // Demoting the last admin would leave nobody able to manage keys
+    if body.is_admin == Some(false) && before["is_admin"] == true {
+        let other_admins: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM api_keys
+                 WHERE is_admin = 1 AND revoked = 0 AND id != ?1
+                   AND (expires_at IS NULL OR expires_at > datetime('now'))",
+                rusqlite::params![id],
+                |r| r.get(0),
+            )
+            .unwrap_or(0);
+        if other_admins == 0 {
+            return ApiError::from(ErrorCode::LastAdmin).into();
+        }
+    }
+
+    let result = conn.execute(
+        "UPDATE api_keys SET name = COALESCE(?1, name), is_admin = COALESCE(?2, is_admin),
+            rate_limit = COALESCE(?3, rate_limit), rate_burst = COALESCE(?4, rate_burst),
+            exempt_routes = COALESCE(?5, exempt_routes),
+            default_page_size = CASE WHEN ?6 IS NULL THEN default_page_size ELSE NULLIF(?6, 0) END,
+            max_page_size = CASE WHEN ?7 IS NULL THEN max_page_size ELSE NULLIF(?7, 0) END
+         WHERE id = ?8 AND revoked = 0",
+        rusqlite::params![
+            name,
+            body.is_admin,
+            body.rate_limit,
+            body.burst,
+            exempt_routes,
+            body.default_page_size,
+            body.max_page_size,
+            id
+        ],
+    );
+    match result {
+        Ok(1) => {}
+        Ok(_) => return ApiError::from(ErrorCode::NotFound).into(),
+        Err(_) => return ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+    let after = match conn.query_row(&key_query, rusqlite::params![id], key_json) {
+        Ok(k) => k,
+        Err(_) => return ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    };
+
+    if body.rate_limit.is_some() || body.burst.is_some() {
+        limiter.reset(id);
+    }
+
+    // Only the fields that actually changed, old and new
+    let changes: serde_json::Map<String, Value> = after
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(field, value)| before[field.as_str()] != **value)
+        .map(|(field, value)| (field.clone(), json!({ "from": before[field.as_str()], "to": value })))
+        .collect();
+    let _ = conn.execute(
+        "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, 'key.update', ?2, ?3)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            key.id,
+            json!({ "key_id": id, "changes": changes }).to_string()
+        ],
+    );
+
+    (Status::Ok, Json(after))
+}
+
+/// Issue a new secret for a key, keeping its id, name, admin flag, and rate limit settings.
+/// The old secret stops working immediately. Admins can rotate any key; other
+/// callers only their own.
+#[post("/keys/<id>/rotate", data = "<body>")]
+pub fn rotate_key(
+    key: AuthenticatedKey,
+    id: &str,
+    body: Option<Json<models::RotateKeyRequest>>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin && key.id != id {
+        return ApiError::new(ErrorCode::Forbidden, "You can only rotate your own key").into();
+    }
+
+    let new_expiry = match body
+        .as_ref()
+        .and_then(|b| b.expires_at.as_deref())
+        .map(auth::parse_expiry)
+        .transpose()
+    {
+        Ok(e) => e,
+        Err(msg) => {
+            return ApiError::new(ErrorCode::InvalidExpiry, msg).into()
+        }
+    };
+
+    let conn = db.conn();
+    let raw_key = auth::generate_raw_key();
+
+    // A new expiry also re-arms the pre-expiry warning
+    let result = conn.execute(
+        "UPDATE api_keys SET key_hash = ?1,
+            expires_at = COALESCE(?2, expires_at),
+            expiry_warned_at = CASE WHEN ?2 IS NULL THEN expiry_warned_at ELSE NULL END
+         WHERE id = ?3 AND revoked = 0",
+        rusqlite::params![auth::hash_key(&raw_key), new_expiry, id],
+    );
+
+    match result {
+        Ok(1) => {
+            oauth::revoke_tokens(&conn, id);
+            let expires_at: Option<String> = conn
+                .query_row(
+                    "SELECT expires_at FROM api_keys WHERE id = ?1",
+                    rusqlite::params![id],
+                    |r| r.get(0),
+                )
+                .unwrap_or(None);
+            (
+                Status::Ok,
+                Json(json!({
+                    "id": id,
+                    "api_key": raw_key,
+                    "expires_at": expires_at,
+                    "message": "Key rotated. The previous secret no longer works — save this one."
+                })),
+            )
+        }
+        Ok(_) => ApiError::from(ErrorCode::NotFound).into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
 #[delete("/keys/<id>")]
 pub fn delete_key(
     key: AuthenticatedKey,
@@ -81,10 +349,7 @@ pub fn delete_key(
     db: &rocket::State<DbState>,
 ) -> (Status, Json<Value>) {
     if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(json!({ "error": "ADMIN_REQUIRED" })),
-        );
+        return ApiError::from(ErrorCode::AdminRequired).into();
     }
 
     let conn = db.conn();
@@ -92,11 +357,94 @@ pub fn delete_key(
         "UPDATE api_keys SET revoked = 1 WHERE id = ?1",
         rusqlite::params![id],
     ) {
-        Ok(1) => (Status::Ok, Json(json!({ "message": "Key revoked" }))),
-        Ok(_) => (Status::NotFound, Json(json!({ "error": "NOT_FOUND" }))),
-        Err(_) => (
-            Status::InternalServerError,
-            Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
-        ),
+        Ok(1) => {
+            oauth::revoke_tokens(&conn, id);
+            (Status::Ok, Json(json!({ "message": "Key revoked" })))
+        }
+        Ok(_) => ApiError::from(ErrorCode::NotFound).into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+/// Lift an anomaly throttle before it runs out, restoring the key's full
+/// rate limit.
+#[delete("/keys/<id>/throttle")]
+pub fn lift_throttle(key: AuthenticatedKey, id: &str, db: &rocket::State<DbState>) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::from(ErrorCode::AdminRequired).into();
     }
+
+    let conn = db.conn();
+    match conn.execute(
+        "UPDATE api_keys SET throttled_until = NULL WHERE id = ?1 AND revoked = 0",
+        rusqlite::params![id],
+    ) {
+        Ok(1) => match conn.query_row(&format!("SELECT {KEY_COLUMNS} FROM api_keys WHERE id = ?1"), [id], key_json) {
+            Ok(key) => (Status::Ok, Json(key)),
+            Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+        },
+        Ok(_) => ApiError::from(ErrorCode::NotFound).into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+// === Caller: Rate Limit ===
+
+/// The calling key's rate limit window, so agents can pace themselves
+/// instead of reading headers. Asking doesn't count against the limit and
+/// works even when the window is used up. `class` is `admin`, `standard`,
+/// `throttled` (anomaly throttle in force) or `anonymous`; anonymous callers
+/// have no per-key limit, only the daily cap on anonymous reviews.
+/// `route_costs` lists the routes that count as more than one request.
+#[get("/rate-limit")]
+pub fn rate_limit_info(
+    key: OptionalKey,
+    current: CurrentRateLimit,
+    db: &rocket::State<DbState>,
+    limiter: &rocket::State<RateLimiter>,
+    reviews: &rocket::State<ReviewThrottle>,
+) -> Json<Value> {
+    let (Some(key), Some(rl)) = (key.0, current.0) else {
+        return Json(json!({
+            "class": "anonymous",
+            "limit": null,
+            "remaining": null,
+            "reset_secs": null,
+            "anonymous_review_daily_cap": reviews.daily_cap(),
+        }));
+    };
+    let (throttled_until, exempt_routes): (Option<String>, String) = db
+        .conn()
+        .query_row(
+            "SELECT CASE WHEN throttled_until > datetime('now') THEN throttled_until END, exempt_routes
+             FROM api_keys WHERE id = ?1",
+            [&key.id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap_or((None, "[]".to_string()));
+    let class = match (key.is_admin, &throttled_until) {
+        (_, Some(_)) => "throttled",
+        (true, None) => "admin",
+        (false, None) => "standard",
+    };
+    let reset_at = chrono::Utc::now() + chrono::Duration::seconds(rl.reset_secs as i64);
+    Json(json!({
+        "class": class,
+        "key_id": key.id,
+        "limit": rl.limit,
+        "remaining": rl.remaining,
+        "reset_secs": rl.reset_secs,
+        "reset_at": reset_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        "window_secs": rl.window_secs,
+        "burst": rl.burst,
+        "burst_remaining": rl.burst_remaining,
+        "policy": rl.policy(),
+        "throttled_until": throttled_until,
+        "exempt_routes": serde_json::from_str::<Value>(&exempt_routes).unwrap_or(json!([])),
+        "route_costs": limiter
+            .route_costs()
+            .iter()
+            .map(|(route, cost)| json!({ "route": route, "cost": cost }))
+            .collect::<Vec<_>>(),
+    }))
 }
@@ -1,17 +1,38 @@
+mod accounts;
 mod admin;
 mod apps;
+mod badges;
+mod endorsements;
 mod keys;
+mod preview;
 mod reviews;
 mod system;
 mod webhook_routes;
 
 // Re-export all route handlers for mounting in lib.rs
-pub use admin::{approve_app, deprecate_app, reject_app, undeprecate_app};
+pub use accounts::{get_account, request_magic_link, verify_magic_link};
+pub use admin::{
+    add_discovery_seed, approve_app, archive_app, assign_reviewer, bulk_moderate, crawl_discovery_seed, create_backup,
+    delete_discovery_seed, deprecate_app, erase_subject, get_maintenance, list_backups, list_content_rejections,
+    list_discovery_seeds, merge_tags, optimize_db, reject_app, reload_config, rename_tag, set_maintenance, unarchive_app,
+    undeprecate_app,
+};
 pub use apps::{
-    delete_app, get_app, list_apps, list_my_apps, list_pending_apps, search_apps, submit_app,
-    update_app,
+    app_agent_card, app_tools, delete_app, duplicate_app, export_app, get_app, get_replacement, list_apps, list_my_apps, list_pending_apps, publish_app, put_translation, rename_slug,
+    search_apps,
+    submit_app, suggest_tags, update_app,
+};
+pub(crate) use apps::{delete_app_records, ensure_not_archived, visible_app_id};
+pub use badges::{award_badge, create_badge, delete_badge, list_badges, revoke_badge, update_badge};
+pub use endorsements::{endorse_app, list_endorsements, withdraw_endorsement};
+pub use keys::{create_key, delete_key, lift_throttle, list_keys, rate_limit_info, rotate_key, update_key};
+pub use preview::{app_docs, app_json_ld, app_logo, app_og_image, app_preview};
+pub use reviews::{
+    add_reaction, delete_my_review, get_reviews, list_categories, list_my_reviews, remove_reaction, review_history, review_summary, submit_review, update_category,
+    update_my_review,
+};
+pub use system::{agent_card, cors_preflight, error_codes, event_stream, health_event_stream, list_events, event_ws, health, skill_md, llms_txt, openapi, client_spec, root_llms_txt, app_skill_md, skills_index, skills_skill_md, api_skills_skill_md, opensearch_xml, search_meta, captcha_config};
+pub use webhook_routes::{
+    create_webhook, delete_notify_webhook, delete_webhook, get_notify_webhook, list_webhook_deliveries, list_webhooks,
+    put_notify_webhook, rotate_webhook_secret, update_webhook,
 };
-pub use keys::{create_key, delete_key, list_keys};
-pub use reviews::{get_reviews, list_categories, submit_review};
-pub use system::{cors_preflight, event_stream, health, skill_md, llms_txt, openapi, root_llms_txt, skills_index, skills_skill_md, api_skills_skill_md};
-pub use webhook_routes::{create_webhook, delete_webhook, list_webhooks, update_webhook};
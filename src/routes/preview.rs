@@ -0,0 +1,316 @@
+use rocket::http::{ContentType, Status};
+use rocket::response::content::RawHtml;
+use serde_json::{json, Value};
+
+use super::system::{xml_escape as html_escape, RequestOrigin};
+use crate::auth::ReadAccess;
+use crate::docs;
+use crate::errors::{ApiError, ErrorCode};
+use crate::logos::LogoCache;
+use crate::og::{self, OgCard, OgImages};
+use crate::DbState;
+
+/// Site name used in titles and OpenGraph tags.
+const SITE_NAME: &str = "App Directory";
+
+/// Embed JSON in a `<script>` element without letting a value close it.
+fn script_json(value: &Value) -> String {
+    value.to_string().replace("</", "<\\/")
+}
+
+/// schema.org description of a listing: a `WebAPI` when it exposes an API
+/// or spec, otherwise a `SoftwareApplication`. `page_url` is the listing's
+/// HTML page, used when the app has no homepage.
+pub fn json_ld(app: &Value, page_url: &str) -> Value {
+    let str_of = |key: &str| app.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+    let is_api = str_of("api_url").is_some() || str_of("api_spec_url").is_some();
+    let mut ld = json!({
+        "@context": "https://schema.org",
+        "@type": if is_api { "WebAPI" } else { "SoftwareApplication" },
+        "identifier": app["id"],
+        "name": app["name"],
+        "description": app["short_description"],
+        "url": str_of("homepage_url").unwrap_or(page_url),
+        "mainEntityOfPage": page_url,
+        "dateModified": app["updated_at"],
+    });
+    if is_api {
+        ld["category"] = app["category"].clone();
+        if let Some(spec) = str_of("api_spec_url") {
+            ld["documentation"] = json!(spec);
+        }
+        if let Some(endpoint) = str_of("api_url") {
+            ld["potentialAction"] = json!({ "@type": "ConsumeAction", "target": endpoint });
+        }
+    } else {
+        ld["applicationCategory"] = app["category"].clone();
+        ld["operatingSystem"] = json!("Any");
+    }
+    if let Some(logo) = str_of("logo_url") {
+        ld["image"] = json!(logo);
+    }
+    if let Some(tags) = app["tags"].as_array().filter(|t| !t.is_empty()) {
+        let keywords: Vec<&str> = tags.iter().filter_map(|t| t.as_str()).collect();
+        ld["keywords"] = json!(keywords.join(", "));
+    }
+    if let Some(author) = str_of("author_name") {
+        ld["provider"] = json!({ "@type": "Organization", "name": author });
+        if let Some(url) = str_of("author_url") {
+            ld["provider"]["url"] = json!(url);
+        }
+    }
+    if app["review_count"].as_i64().unwrap_or(0) > 0 {
+        ld["aggregateRating"] = json!({
+            "@type": "AggregateRating",
+            "ratingValue": app["avg_rating"],
+            "ratingCount": app["review_count"],
+            "bestRating": 5,
+            "worstRating": 1,
+        });
+    }
+    ld
+}
+
+/// URL of a listing's HTML page.
+fn page_url(app: &Value, origin: &str) -> String {
+    format!("{}/apps/{}", origin, app["slug"].as_str().unwrap_or(""))
+}
+
+/// Render a listing as a standalone HTML page with OpenGraph, Twitter Card
+/// and JSON-LD metadata.
+pub fn render_app(app: &Value, origin: &str) -> String {
+    let text = |key: &str| html_escape(app.get(key).and_then(|v| v.as_str()).unwrap_or(""));
+    let id = app["id"].as_str().unwrap_or("");
+    let page_url = page_url(app, origin);
+    let card_url = format!("{origin}/api/v1/apps/{id}/og.png");
+    let name = text("name");
+    let summary = text("short_description");
+
+    let mut meta = vec![
+        format!(r#"<meta name="description" content="{summary}">"#),
+        format!(r#"<link rel="canonical" href="{}">"#, html_escape(&page_url)),
+        format!(
+            r#"<link rel="alternate" type="application/json" href="{}">"#,
+            html_escape(&format!("{origin}/api/v1/apps/{id}"))
+        ),
+        format!(
+            r#"<link rel="alternate" type="application/ld+json" href="{}">"#,
+            html_escape(&format!("{origin}/api/v1/apps/{id}/jsonld"))
+        ),
+        r#"<meta property="og:type" content="website">"#.to_string(),
+        format!(r#"<meta property="og:site_name" content="{SITE_NAME}">"#),
+        format!(r#"<meta property="og:title" content="{name}">"#),
+        format!(r#"<meta property="og:description" content="{summary}">"#),
+        format!(r#"<meta property="og:url" content="{}">"#, html_escape(&page_url)),
+        format!(r#"<meta property="og:image" content="{}">"#, html_escape(&card_url)),
+        format!(r#"<meta property="og:image:width" content="{}">"#, og::WIDTH),
+        format!(r#"<meta property="og:image:height" content="{}">"#, og::HEIGHT),
+        format!(r#"<meta name="twitter:title" content="{name}">"#),
+        format!(r#"<meta name="twitter:description" content="{summary}">"#),
+    ];
+    // The generated card leads; an uploaded logo is offered as well, and
+    // Twitter (one image only) keeps showing it in a small card
+    match app["logo_url"].as_str().filter(|l| !l.is_empty()) {
+        Some(logo) => {
+            meta.push(format!(r#"<meta property="og:image" content="{}">"#, html_escape(logo)));
+            meta.push(r#"<meta name="twitter:card" content="summary">"#.to_string());
+            meta.push(format!(r#"<meta name="twitter:image" content="{}">"#, html_escape(logo)));
+        }
+        None => {
+            meta.push(r#"<meta name="twitter:card" content="summary_large_image">"#.to_string());
+            meta.push(format!(r#"<meta name="twitter:image" content="{}">"#, html_escape(&card_url)));
+        }
+    }
+    // Only approved, public listings should show up in search engines
+    if app["status"] != "approved" || app["visibility"] != "public" {
+        meta.push(r#"<meta name="robots" content="noindex">"#.to_string());
+    }
+
+    let mut details = vec![
+        ("Category", text("category")),
+        ("Protocol", text("protocol")),
+        ("Status", text("status")),
+        (
+            "Rating",
+            format!(
+                "{:.1} ({} reviews)",
+                app["avg_rating"].as_f64().unwrap_or(0.0),
+                app["review_count"].as_i64().unwrap_or(0)
+            ),
+        ),
+        ("Author", text("author_name")),
+    ];
+    if let Some(tags) = app["tags"].as_array().filter(|t| !t.is_empty()) {
+        let tags: Vec<String> = tags.iter().filter_map(|t| t.as_str()).map(html_escape).collect();
+        details.push(("Tags", tags.join(", ")));
+    }
+    let details: String = details
+        .into_iter()
+        .map(|(label, value)| format!("<dt>{label}</dt><dd>{value}</dd>"))
+        .collect();
+
+    let links: String = [("Homepage", "homepage_url"), ("API", "api_url"), ("API spec", "api_spec_url")]
+        .into_iter()
+        .filter_map(|(label, key)| {
+            app[key]
+                .as_str()
+                .filter(|u| !u.is_empty())
+                .map(|url| format!(r#"<li><a href="{}" rel="nofollow">{label}</a></li>"#, html_escape(url)))
+        })
+        .collect();
+
+    format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{name} — {SITE_NAME}</title>
+{meta}
+<script type="application/ld+json">{ld}</script>
+<style>body{{font-family:system-ui,sans-serif;max-width:42rem;margin:2rem auto;padding:0 1rem;line-height:1.5;color:#1f2328}}dt{{font-weight:600}}dd{{margin:0 0 .5rem}}p.summary{{font-size:1.15rem;color:#57606a}}</style>
+</head>
+<body>
+<main>
+<h1>{name}</h1>
+<p class="summary">{summary}</p>
+<p>{description}</p>
+<dl>{details}</dl>
+<ul>{links}</ul>
+{docs}<p><a href="/">Browse the {SITE_NAME}</a></p>
+</main>
+</body>
+</html>
+"#,
+        meta = meta.join("\n"),
+        ld = script_json(&json_ld(app, &page_url)),
+        description = text("description"),
+        docs = app["docs_markdown"]
+            .as_str()
+            .map(|md| format!("<section class=\"docs\">\n{}</section>\n", docs::render_html(md)))
+            .unwrap_or_default(),
+    )
+}
+
+fn not_found_page() -> String {
+    format!(
+        "<!doctype html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"robots\" content=\"noindex\">\n<title>App not found — {SITE_NAME}</title>\n</head>\n<body>\n<h1>App not found</h1>\n<p><a href=\"/\">Browse the {SITE_NAME}</a></p>\n</body>\n</html>\n"
+    )
+}
+
+/// The listing fields the page and JSON-LD use, by id or slug. Drafts
+/// aren't public, so they're never found; private listings only when the
+/// reader is signed in.
+fn load_app(conn: &rusqlite::Connection, id_or_slug: &str, reader: &ReadAccess) -> Option<Value> {
+    conn.query_row(
+        "SELECT id, name, slug, short_description, description, homepage_url, api_url, api_spec_url, protocol,
+                category, tags, logo_url, author_name, author_url, status, avg_rating, review_count, updated_at,
+                visibility, docs_markdown
+         FROM apps WHERE (id = ?1 OR slug = ?1) AND status != 'draft' AND (visibility != 'private' OR ?2)",
+        rusqlite::params![id_or_slug, reader.is_signed_in()],
+        |r| {
+            let tags: Vec<String> = serde_json::from_str(&r.get::<_, String>(10)?).unwrap_or_default();
+            Ok(json!({
+                "id": r.get::<_, String>(0)?,
+                "name": r.get::<_, String>(1)?,
+                "slug": r.get::<_, String>(2)?,
+                "short_description": r.get::<_, String>(3)?,
+                "description": r.get::<_, String>(4)?,
+                "homepage_url": r.get::<_, Option<String>>(5)?,
+                "api_url": r.get::<_, Option<String>>(6)?,
+                "api_spec_url": r.get::<_, Option<String>>(7)?,
+                "protocol": r.get::<_, String>(8)?,
+                "category": r.get::<_, String>(9)?,
+                "tags": tags,
+                "logo_url": r.get::<_, Option<String>>(11)?,
+                "author_name": r.get::<_, String>(12)?,
+                "author_url": r.get::<_, Option<String>>(13)?,
+                "status": r.get::<_, String>(14)?,
+                "avg_rating": r.get::<_, f64>(15)?,
+                "review_count": r.get::<_, i64>(16)?,
+                "updated_at": r.get::<_, String>(17)?,
+                "visibility": r.get::<_, String>(18)?,
+                "docs_markdown": r.get::<_, Option<String>>(19)?,
+            }))
+        },
+    )
+    .ok()
+}
+
+/// GET /apps/<id_or_slug> — server-rendered listing page so shared links
+/// unfurl with a title, description and logo even without the SPA.
+#[get("/apps/<id_or_slug>")]
+pub fn app_preview(
+    reader: ReadAccess,
+    id_or_slug: &str,
+    origin: RequestOrigin,
+    db: &rocket::State<DbState>,
+) -> (Status, RawHtml<String>) {
+    match load_app(&db.conn(), id_or_slug, &reader) {
+        Some(app) => (Status::Ok, RawHtml(render_app(&app, &origin.0))),
+        None => (Status::NotFound, RawHtml(not_found_page())),
+    }
+}
+
+/// GET /api/v1/apps/<id_or_slug>/jsonld — the listing as schema.org JSON-LD,
+/// the same block embedded in its HTML page
+#[get("/apps/<id_or_slug>/jsonld")]
+pub fn app_json_ld(
+    reader: ReadAccess,
+    id_or_slug: &str,
+    origin: RequestOrigin,
+    db: &rocket::State<DbState>,
+) -> Result<(ContentType, String), ApiError> {
+    let app = load_app(&db.conn(), id_or_slug, &reader).ok_or_else(|| ApiError::new(ErrorCode::NotFound, "App not found"))?;
+    let ld = json_ld(&app, &page_url(&app, &origin.0));
+    Ok((ContentType::new("application", "ld+json"), ld.to_string()))
+}
+
+/// GET /api/v1/apps/<id_or_slug>/docs.md — the listing's usage docs as
+/// stored (already sanitized), or 404 when it has none
+#[get("/apps/<id_or_slug>/docs.md")]
+pub fn app_docs(reader: ReadAccess, id_or_slug: &str, db: &rocket::State<DbState>) -> Result<(ContentType, String), ApiError> {
+    load_app(&db.conn(), id_or_slug, &reader)
+        .and_then(|app| app["docs_markdown"].as_str().map(str::to_string))
+        .map(|md| (ContentType::new("text", "markdown").with_params(("charset", "utf-8")), md))
+        .ok_or_else(|| ApiError::new(ErrorCode::NotFound, "No docs for this app"))
+}
+
+/// GET /api/v1/apps/<id_or_slug>/og.png — 1200×630 share card with the
+/// app's name, summary, category and rating, cached on disk
+#[get("/apps/<id_or_slug>/og.png")]
+pub fn app_og_image(
+    reader: ReadAccess,
+    id_or_slug: &str,
+    db: &rocket::State<DbState>,
+    images: &rocket::State<OgImages>,
+) -> Result<(ContentType, Vec<u8>), ApiError> {
+    let app = load_app(&db.conn(), id_or_slug, &reader).ok_or_else(|| ApiError::new(ErrorCode::NotFound, "App not found"))?;
+    let str_of = |key: &str| app[key].as_str().unwrap_or("");
+    let card = OgCard {
+        name: str_of("name"),
+        short_description: str_of("short_description"),
+        category: str_of("category"),
+        avg_rating: app["avg_rating"].as_f64().unwrap_or(0.0),
+        review_count: app["review_count"].as_i64().unwrap_or(0),
+    };
+    Ok((ContentType::PNG, images.get(str_of("id"), &card)))
+}
+
+/// GET /media/logos/<app_id>.png — the app's logo as cached from its
+/// `logo_url` (256×256 PNG), or a generated identicon
+#[get("/media/logos/<file>")]
+pub fn app_logo(
+    reader: ReadAccess,
+    file: &str,
+    db: &rocket::State<DbState>,
+    logos: &rocket::State<LogoCache>,
+) -> Result<(ContentType, Vec<u8>), ApiError> {
+    let conn = db.conn();
+    // Ids only (not slugs), so each logo has one URL
+    let id = file.strip_suffix(".png").unwrap_or_default();
+    load_app(&conn, id, &reader)
+        .filter(|app| app["id"] == id)
+        .ok_or_else(|| ApiError::new(ErrorCode::NotFound, "App not found"))?;
+    Ok((ContentType::PNG, logos.get(&conn, id)))
+}
@@ -2,30 +2,104 @@ use rocket::http::Status;
 use rocket::serde::json::Json;
 use serde_json::{json, Value};
 
-use crate::auth::OptionalKey;
+use crate::accounts::Principal;
+use crate::auth::{self, Actor, AuthenticatedKey, ClientFingerprint, EditTokenParam, ReadAccess, ReviewSecret};
+use crate::captcha::{Captcha, CaptchaRoute};
+use crate::categories;
+use crate::errors::{ApiError, ErrorCode};
 use crate::events::{AppEvent, EventBus};
 use crate::models::*;
+use crate::moderation::{self, ContentFilter};
+use crate::outbox;
+use crate::rate_limit::ReviewThrottle;
+use crate::stats;
+use crate::validation::{self, FieldLimits};
 use crate::DbState;
 
 // === Reviews (NO AUTH REQUIRED) ===
 
+/// Trim pros/cons entries, dropping blanks and case-insensitive repeats.
+fn review_points(points: &Option<Vec<String>>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for point in points.iter().flatten().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        if !out.iter().any(|o| o.eq_ignore_ascii_case(point)) {
+            out.push(point.to_string());
+        }
+    }
+    out
+}
+
+/// Recompute an app's average rating and review count.
+fn refresh_rating(conn: &rusqlite::Connection, app_id: &str) -> rusqlite::Result<usize> {
+    conn.execute(
+        "UPDATE apps SET
+           avg_rating = (SELECT COALESCE(AVG(CAST(rating AS REAL)), 0.0) FROM reviews WHERE app_id = ?1),
+           review_count = (SELECT COUNT(*) FROM reviews WHERE app_id = ?1),
+           updated_at = datetime('now')
+         WHERE id = ?1",
+        rusqlite::params![app_id],
+    )
+}
+
+/// Keep a review's current content in its history before it's overwritten.
+fn snapshot_review(conn: &rusqlite::Connection, review_id: &str) -> rusqlite::Result<usize> {
+    conn.execute(
+        "INSERT INTO review_versions (review_id, rating, title, body, reviewer_name, pros, cons, use_case)
+         SELECT id, rating, title, body, reviewer_name, pros, cons, use_case FROM reviews WHERE id = ?1",
+        rusqlite::params![review_id],
+    )
+}
+
 #[post("/apps/<app_id>/reviews", data = "<body>")]
-pub fn submit_review(
-    opt_key: OptionalKey,
+#[allow(clippy::too_many_arguments)]
+pub async fn submit_review(
+    actor: Actor,
+    client: ClientFingerprint,
     app_id: &str,
     body: Json<SubmitReviewRequest>,
     db: &rocket::State<DbState>,
+    throttle: &rocket::State<ReviewThrottle>,
+    limits: &rocket::State<FieldLimits>,
+    filter: &rocket::State<ContentFilter>,
     bus: &rocket::State<EventBus>,
+    captcha: &rocket::State<Captcha>,
+    remote_ip: Option<std::net::IpAddr>,
 ) -> (Status, Json<Value>) {
-    let conn = db.conn();
-
     if body.rating < 1 || body.rating > 5 {
-        return (
-            Status::BadRequest,
-            Json(json!({ "error": "INVALID_RATING", "message": "Rating must be 1-5" })),
-        );
+        return ApiError::new(ErrorCode::InvalidRating, "Rating must be 1-5").into();
+    }
+
+    if let Err(err) = limits.check_review(&body) {
+        return err.into();
+    }
+
+    if let Err(err) = captcha
+        .verify(CaptchaRoute::Reviews, &actor, body.captcha_token.as_deref(), remote_ip)
+        .await
+    {
+        return err.into();
     }
 
+    let pros = review_points(&body.pros);
+    let cons = review_points(&body.cons);
+    let use_case = body.use_case.as_deref().map(str::trim).filter(|u| !u.is_empty());
+    let (pros_text, cons_text) = (pros.join("\n"), cons.join("\n"));
+
+    let key_id = actor.key_id();
+    let text = [
+        ("title", body.title.as_deref()),
+        ("body", body.body.as_deref()),
+        ("use_case", use_case),
+        ("pros", Some(pros_text.as_str()).filter(|t| !t.is_empty())),
+        ("cons", Some(cons_text.as_str()).filter(|t| !t.is_empty())),
+    ];
+    if let Err(rejection) = filter.check("review", &text).await {
+        moderation::log_rejection(&db.conn(), "review", Some(app_id), key_id, &rejection, &text);
+        return rejection.to_error().into();
+    }
+
+    let conn = db.conn();
+
     let app_exists: bool = conn
         .query_row(
             "SELECT COUNT(*) FROM apps WHERE id = ?1",
@@ -36,107 +110,527 @@ pub fn submit_review(
         > 0;
 
     if !app_exists {
-        return (
-            Status::NotFound,
-            Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-        );
+        return ApiError::new(ErrorCode::NotFound, "App not found").into();
     }
 
-    let id = uuid::Uuid::new_v4().to_string();
-    let reviewer_key_id: Option<String> = opt_key.0.as_ref().map(|k| k.id.clone());
+    if let Err(err) = super::apps::ensure_not_archived(&conn, app_id) {
+        return err.into();
+    }
+
+    let reviewer_key_id: Option<String> = actor.key_id().map(str::to_string);
     let reviewer_name = body.reviewer_name.as_deref().unwrap_or("anonymous");
 
-    // If authenticated, upsert (one review per key per app).
-    // If anonymous, always insert a new review.
-    let result = if let Some(ref key_id) = reviewer_key_id {
-        // Check for existing review by this key on this app
-        let existing: Option<String> = conn
+    // Anonymous reviews are capped per client IP per day
+    if reviewer_key_id.is_none() {
+        let rl = throttle.check(&client.ip_hash);
+        if !rl.allowed {
+            return ApiError::new(
+                ErrorCode::ReviewLimitExceeded,
+                format!("Anonymous review limit reached ({} per day). Use an API key or try again later.", rl.limit),
+            )
+            .with("retry_after_secs", rl.reset_secs)
+            .into();
+        }
+    }
+
+    // One review per reviewer per app (upsert): authenticated reviewers are
+    // identified by API key, anonymous ones by IP + User-Agent fingerprint.
+    let existing: Option<String> = match reviewer_key_id {
+        Some(ref key_id) => conn
             .query_row(
                 "SELECT id FROM reviews WHERE app_id = ?1 AND reviewer_key_id = ?2",
                 rusqlite::params![app_id, key_id],
                 |r| r.get(0),
             )
-            .ok();
+            .ok(),
+        None => conn
+            .query_row(
+                "SELECT id FROM reviews WHERE app_id = ?1 AND reviewer_key_id IS NULL AND reviewer_fingerprint = ?2",
+                rusqlite::params![app_id, client.fingerprint],
+                |r| r.get(0),
+            )
+            .ok(),
+    };
 
-        if let Some(existing_id) = existing {
-            // Update existing review
-            conn.execute(
+    let fingerprint: Option<&str> = if reviewer_key_id.is_none() {
+        Some(client.fingerprint.as_str())
+    } else {
+        None
+    };
+
+    // Checked on every submission, so an edited review reflects current usage
+    let verified_usage = reviewer_key_id
+        .as_deref()
+        .is_some_and(|key_id| stats::has_verified_usage(&conn, app_id, key_id));
+
+    let (pros, cons) = (json!(pros).to_string(), json!(cons).to_string());
+    // Anonymous reviewers get a secret to edit or delete their review later
+    let mut review_secret: Option<String> = None;
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    let (id, result) = match existing {
+        Some(existing_id) => {
+            let _ = snapshot_review(&tx, &existing_id);
+            let r = tx.execute(
                 "UPDATE reviews SET rating = ?1, title = ?2, body = ?3, reviewer_name = ?4,
-                 created_at = datetime('now') WHERE id = ?5",
-                rusqlite::params![body.rating, body.title, body.body, reviewer_name, existing_id],
-            )
-        } else {
-            conn.execute(
-                "INSERT INTO reviews (id, app_id, reviewer_key_id, reviewer_name, rating, title, body)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                rusqlite::params![id, app_id, key_id, reviewer_name, body.rating, body.title, body.body],
-            )
+                 pros = ?5, cons = ?6, use_case = ?7, verified_usage = ?8, edited_at = datetime('now') WHERE id = ?9",
+                rusqlite::params![
+                    body.rating, body.title, body.body, reviewer_name, pros, cons, use_case, verified_usage, existing_id
+                ],
+            );
+            (existing_id, r)
+        }
+        None => {
+            let new_id = uuid::Uuid::new_v4().to_string();
+            if reviewer_key_id.is_none() {
+                review_secret = Some(format!("rvs_{}", uuid::Uuid::new_v4().to_string().replace('-', "")));
+            }
+            let secret_hash = review_secret.as_deref().map(auth::hash_key);
+            let r = tx.execute(
+                "INSERT INTO reviews (id, app_id, reviewer_key_id, reviewer_name, rating, title, body, reviewer_fingerprint,
+                                      pros, cons, use_case, verified_usage, secret_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                rusqlite::params![
+                    new_id, app_id, reviewer_key_id, reviewer_name, body.rating, body.title, body.body, fingerprint,
+                    pros, cons, use_case, verified_usage, secret_hash
+                ],
+            );
+            (new_id, r)
         }
-    } else {
-        conn.execute(
-            "INSERT INTO reviews (id, app_id, reviewer_key_id, reviewer_name, rating, title, body)
-             VALUES (?1, ?2, NULL, ?3, ?4, ?5, ?6)",
-            rusqlite::params![id, app_id, reviewer_name, body.rating, body.title, body.body],
-        )
     };
 
     if let Err(e) = &result {
         eprintln!("Review insert error: {e}");
-        return (
-            Status::InternalServerError,
-            Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
-        );
+        return ApiError::new(ErrorCode::DbError, "Internal server error").into();
     }
 
-    let _ = conn.execute(
-        "UPDATE apps SET
-           avg_rating = (SELECT COALESCE(AVG(CAST(rating AS REAL)), 0.0) FROM reviews WHERE app_id = ?1),
-           review_count = (SELECT COUNT(*) FROM reviews WHERE app_id = ?1),
-           updated_at = datetime('now')
-         WHERE id = ?1",
-        rusqlite::params![app_id],
-    );
+    let _ = refresh_rating(&tx, app_id);
 
-    bus.emit(AppEvent {
+    let event = AppEvent {
         event: "review.submitted".to_string(),
         data: json!({
             "app_id": app_id,
             "review_id": id,
             "rating": body.rating,
+            "verified_usage": verified_usage,
         }),
-    });
+    };
+    match outbox::commit(tx, [event]) {
+        Ok(queued) => bus.dispatch(queued),
+        Err(err) => return err.into(),
+    }
+
+    let mut response = json!({ "message": "Review submitted", "id": id, "verified_usage": verified_usage });
+    if let Some(secret) = review_secret {
+        response["review_secret"] = json!(secret);
+    }
+    (Status::Created, Json(response))
+}
+
+/// The caller's review of an app: found by API key, or by the review secret
+/// issued to an anonymous reviewer.
+fn own_review(
+    conn: &rusqlite::Connection,
+    app_id: &str,
+    actor: &Actor,
+    secret: &ReviewSecret,
+) -> Result<String, ApiError> {
+    let found = match (actor.key_id(), secret.0.as_deref()) {
+        (Some(key_id), _) => conn.query_row(
+            "SELECT id FROM reviews WHERE app_id = ?1 AND reviewer_key_id = ?2",
+            rusqlite::params![app_id, key_id],
+            |r| r.get(0),
+        ),
+        (None, Some(secret)) => conn.query_row(
+            "SELECT id FROM reviews WHERE app_id = ?1 AND secret_hash = ?2",
+            rusqlite::params![app_id, auth::hash_key(secret)],
+            |r| r.get(0),
+        ),
+        (None, None) => {
+            return Err(ApiError::new(
+                ErrorCode::Unauthorized,
+                "Provide an API key or the X-Review-Secret returned when the review was created",
+            ))
+        }
+    };
+    found.map_err(|_| ApiError::new(ErrorCode::NotFound, "You have no review for this app"))
+}
+
+/// Edit your own review. The previous version is kept in its history and
+/// the review is marked `edited_at`.
+#[patch("/apps/<app_id>/reviews/mine", format = "json", data = "<body>")]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_my_review(
+    actor: Actor,
+    secret: ReviewSecret,
+    app_id: &str,
+    body: Json<UpdateReviewRequest>,
+    db: &rocket::State<DbState>,
+    limits: &rocket::State<FieldLimits>,
+    filter: &rocket::State<ContentFilter>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    if body.rating.is_none()
+        && body.title.is_none()
+        && body.body.is_none()
+        && body.reviewer_name.is_none()
+        && body.pros.is_none()
+        && body.cons.is_none()
+        && body.use_case.is_none()
+    {
+        return ApiError::new(ErrorCode::NoChanges, "Provide at least one field to change").into();
+    }
+    if body.rating.is_some_and(|r| !(1..=5).contains(&r)) {
+        return ApiError::new(ErrorCode::InvalidRating, "Rating must be 1-5").into();
+    }
+    let as_submission = SubmitReviewRequest {
+        rating: body.rating.unwrap_or(1),
+        title: body.title.clone(),
+        body: body.body.clone(),
+        reviewer_name: body.reviewer_name.clone(),
+        pros: body.pros.clone(),
+        cons: body.cons.clone(),
+        use_case: body.use_case.clone(),
+        captcha_token: None,
+    };
+    if let Err(err) = limits.check_review(&as_submission) {
+        return err.into();
+    }
+
+    let review_id = {
+        let conn = db.conn();
+        if let Err(err) = super::apps::ensure_not_archived(&conn, app_id) {
+            return err.into();
+        }
+        match own_review(&conn, app_id, &actor, &secret) {
+            Ok(id) => id,
+            Err(err) => return err.into(),
+        }
+    };
+
+    // Empty strings clear the optional text fields
+    let cleared = |v: &Option<String>| v.as_deref().map(str::trim).map(|v| (!v.is_empty()).then(|| v.to_string()));
+    let (title, text_body, use_case) = (cleared(&body.title), cleared(&body.body), cleared(&body.use_case));
+    let pros = body.pros.is_some().then(|| review_points(&body.pros).join("\n"));
+    let cons = body.cons.is_some().then(|| review_points(&body.cons).join("\n"));
+    let text = [
+        ("title", title.clone().flatten()),
+        ("body", text_body.clone().flatten()),
+        ("use_case", use_case.clone().flatten()),
+        ("pros", pros.filter(|t| !t.is_empty())),
+        ("cons", cons.filter(|t| !t.is_empty())),
+    ];
+    let text: Vec<(&str, Option<&str>)> = text.iter().map(|(k, v)| (*k, v.as_deref())).collect();
+    if let Err(rejection) = filter.check("review", &text).await {
+        moderation::log_rejection(&db.conn(), "review", Some(app_id), actor.key_id(), &rejection, &text);
+        return rejection.to_error().into();
+    }
+
+    let conn = db.conn();
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    let _ = snapshot_review(&tx, &review_id);
+    let points = |v: &Option<Vec<String>>| v.is_some().then(|| json!(review_points(v)).to_string());
+    let updated = tx.query_row(
+        "UPDATE reviews SET
+            rating = COALESCE(?2, rating),
+            title = CASE WHEN ?3 THEN ?4 ELSE title END,
+            body = CASE WHEN ?5 THEN ?6 ELSE body END,
+            use_case = CASE WHEN ?7 THEN ?8 ELSE use_case END,
+            reviewer_name = COALESCE(?9, reviewer_name),
+            pros = COALESCE(?10, pros),
+            cons = COALESCE(?11, cons),
+            edited_at = datetime('now')
+         WHERE id = ?1 RETURNING rating, edited_at",
+        rusqlite::params![
+            review_id,
+            body.rating,
+            title.is_some(),
+            title.flatten(),
+            text_body.is_some(),
+            text_body.flatten(),
+            use_case.is_some(),
+            use_case.flatten(),
+            body.reviewer_name.as_deref().map(str::trim).filter(|n| !n.is_empty()),
+            points(&body.pros),
+            points(&body.cons),
+        ],
+        |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?)),
+    );
+    let (rating, edited_at) = match updated {
+        Ok(row) => row,
+        Err(_) => return ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    };
+    let _ = refresh_rating(&tx, app_id);
+
+    let event = AppEvent {
+        event: "review.updated".to_string(),
+        data: json!({ "app_id": app_id, "review_id": review_id, "rating": rating }),
+    };
+    match outbox::commit(tx, [event]) {
+        Ok(queued) => bus.dispatch(queued),
+        Err(err) => return err.into(),
+    }
+
+    (
+        Status::Ok,
+        Json(json!({ "message": "Review updated", "id": review_id, "rating": rating, "edited_at": edited_at })),
+    )
+}
+
+/// Delete your own review, along with its history.
+#[delete("/apps/<app_id>/reviews/mine")]
+pub fn delete_my_review(
+    actor: Actor,
+    secret: ReviewSecret,
+    app_id: &str,
+    db: &rocket::State<DbState>,
+    bus: &rocket::State<EventBus>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    let review_id = match own_review(&conn, app_id, &actor, &secret) {
+        Ok(id) => id,
+        Err(err) => return err.into(),
+    };
+    let tx = match outbox::begin(&conn) {
+        Ok(tx) => tx,
+        Err(err) => return err.into(),
+    };
+    let _ = tx.execute("DELETE FROM review_versions WHERE review_id = ?1", rusqlite::params![review_id]);
+    let _ = tx.execute("DELETE FROM review_reactions WHERE review_id = ?1", rusqlite::params![review_id]);
+    if tx.execute("DELETE FROM reviews WHERE id = ?1", rusqlite::params![review_id]).is_err() {
+        return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+    }
+    let _ = refresh_rating(&tx, app_id);
+
+    let event = AppEvent {
+        event: "review.deleted".to_string(),
+        data: json!({ "app_id": app_id, "review_id": review_id }),
+    };
+    match outbox::commit(tx, [event]) {
+        Ok(queued) => bus.dispatch(queued),
+        Err(err) => return err.into(),
+    }
+
+    (Status::Ok, Json(json!({ "message": "Review deleted", "id": review_id })))
+}
+
+/// Earlier versions of a review, newest first.
+#[get("/apps/<app_id>/reviews/<review_id>/history")]
+pub fn review_history(
+    reader: ReadAccess,
+    token: EditTokenParam,
+    app_id: &str,
+    review_id: &str,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    let app_id = match crate::routes::visible_app_id(&conn, app_id, &reader, token.0.as_deref()) {
+        Some(id) => id,
+        None => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+    let edited_at: Option<String> = match conn.query_row(
+        "SELECT edited_at FROM reviews WHERE id = ?1 AND app_id = ?2",
+        rusqlite::params![review_id, app_id],
+        |r| r.get(0),
+    ) {
+        Ok(edited_at) => edited_at,
+        Err(_) => return ApiError::new(ErrorCode::NotFound, "Review not found").into(),
+    };
+    let versions: Vec<Value> = conn
+        .prepare(
+            "SELECT rating, title, body, reviewer_name, pros, cons, use_case, replaced_at
+             FROM review_versions WHERE review_id = ?1 ORDER BY id DESC",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![review_id], |row| {
+                Ok(json!({
+                    "rating": row.get::<_, i64>(0)?,
+                    "title": row.get::<_, Option<String>>(1)?,
+                    "body": row.get::<_, Option<String>>(2)?,
+                    "reviewer_name": row.get::<_, Option<String>>(3)?,
+                    "pros": serde_json::from_str::<Value>(&row.get::<_, String>(4)?).unwrap_or(json!([])),
+                    "cons": serde_json::from_str::<Value>(&row.get::<_, String>(5)?).unwrap_or(json!([])),
+                    "use_case": row.get::<_, Option<String>>(6)?,
+                    "replaced_at": row.get::<_, String>(7)?,
+                }))
+            })
+            .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default();
+
+    (
+        Status::Ok,
+        Json(json!({ "review_id": review_id, "edited_at": edited_at, "versions": versions })),
+    )
+}
+
+/// Reaction counts for a review, zero-filled for every allowed emoji.
+pub(crate) fn reaction_counts(conn: &rusqlite::Connection, review_id: &str) -> Value {
+    let mut counts = serde_json::Map::new();
+    for (emoji, _) in REVIEW_REACTIONS {
+        counts.insert(emoji.to_string(), json!(0));
+    }
+    if let Ok(mut stmt) = conn.prepare("SELECT emoji, COUNT(*) FROM review_reactions WHERE review_id = ?1 GROUP BY emoji") {
+        if let Ok(rows) = stmt.query_map(rusqlite::params![review_id], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?))) {
+            for (emoji, count) in rows.filter_map(|r| r.ok()) {
+                counts.insert(emoji, json!(count));
+            }
+        }
+    }
+    Value::Object(counts)
+}
+
+/// The review a reaction targets and the reaction's emoji, checking both.
+fn reaction_target(conn: &rusqlite::Connection, review_id: &str, emoji: &str) -> Result<&'static str, ApiError> {
+    let emoji = REVIEW_REACTIONS
+        .iter()
+        .find(|(e, name)| *e == emoji || name.eq_ignore_ascii_case(emoji))
+        .map(|(e, _)| *e)
+        .ok_or_else(|| {
+            ApiError::new(ErrorCode::InvalidReaction, "Reaction must be one of 👍 🎯 ❗")
+                .with("allowed", REVIEW_REACTIONS.iter().map(|(e, _)| *e).collect::<Vec<_>>())
+        })?;
+    let app_id: String = conn
+        .query_row("SELECT app_id FROM reviews WHERE id = ?1", rusqlite::params![review_id], |r| r.get(0))
+        .map_err(|_| ApiError::new(ErrorCode::NotFound, "Review not found"))?;
+    super::apps::ensure_not_archived(conn, &app_id)?;
+    Ok(emoji)
+}
 
+/// Who is reacting: the API key, or the client IP for anonymous callers.
+fn reactor(actor: &Actor, client: &ClientFingerprint) -> String {
+    match actor.key_id() {
+        Some(key_id) => format!("key:{key_id}"),
+        None => format!("ip:{}", client.ip_hash),
+    }
+}
+
+/// React to a review with 👍, 🎯 or ❗. Each key (or client IP, without
+/// one) counts once per emoji; repeating a reaction changes nothing.
+#[post("/reviews/<review_id>/reactions", format = "json", data = "<body>")]
+pub fn add_reaction(
+    actor: Actor,
+    client: ClientFingerprint,
+    review_id: &str,
+    body: Json<ReactionRequest>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    let emoji = match reaction_target(&conn, review_id, body.emoji.trim()) {
+        Ok(emoji) => emoji,
+        Err(err) => return err.into(),
+    };
+    let added = match conn.execute(
+        "INSERT OR IGNORE INTO review_reactions (review_id, emoji, reactor) VALUES (?1, ?2, ?3)",
+        rusqlite::params![review_id, emoji, reactor(&actor, &client)],
+    ) {
+        Ok(n) => n > 0,
+        Err(e) => {
+            eprintln!("Reaction insert error: {e}");
+            return ApiError::new(ErrorCode::DbError, "Internal server error").into();
+        }
+    };
+    (
+        if added { Status::Created } else { Status::Ok },
+        Json(json!({
+            "review_id": review_id,
+            "emoji": emoji,
+            "added": added,
+            "reactions": reaction_counts(&conn, review_id),
+        })),
+    )
+}
+
+/// Take back your reaction to a review.
+#[delete("/reviews/<review_id>/reactions/<emoji>")]
+pub fn remove_reaction(
+    actor: Actor,
+    client: ClientFingerprint,
+    review_id: &str,
+    emoji: &str,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    let emoji = match reaction_target(&conn, review_id, emoji.trim()) {
+        Ok(emoji) => emoji,
+        Err(err) => return err.into(),
+    };
+    let removed = conn
+        .execute(
+            "DELETE FROM review_reactions WHERE review_id = ?1 AND emoji = ?2 AND reactor = ?3",
+            rusqlite::params![review_id, emoji, reactor(&actor, &client)],
+        )
+        .unwrap_or(0)
+        > 0;
     (
-        Status::Created,
-        Json(json!({ "message": "Review submitted", "id": id })),
+        Status::Ok,
+        Json(json!({
+            "review_id": review_id,
+            "emoji": emoji,
+            "removed": removed,
+            "reactions": reaction_counts(&conn, review_id),
+        })),
     )
 }
 
-#[get("/apps/<app_id>/reviews?<page>&<per_page>")]
+/// `sort` values for the review list and their ORDER BY. The first is the default.
+const REVIEW_SORTS: &[(&str, &str)] = &[
+    ("newest", "created_at DESC"),
+    ("verified", "verified_usage DESC, created_at DESC"),
+];
+
+/// `verified_usage=true` keeps only reviews from keys that used the app;
+/// `sort=verified` lists them first.
+#[get("/apps/<app_id>/reviews?<page>&<per_page>&<verified_usage>&<sort>")]
+#[allow(clippy::too_many_arguments)]
 pub fn get_reviews(
+    reader: ReadAccess,
+    token: EditTokenParam,
     app_id: &str,
     page: Option<i64>,
     per_page: Option<i64>,
+    verified_usage: Option<bool>,
+    sort: Option<&str>,
     db: &rocket::State<DbState>,
-) -> Json<Value> {
+) -> (Status, Json<Value>) {
     let conn = db.conn();
+    let app_id = match crate::routes::visible_app_id(&conn, app_id, &reader, token.0.as_deref()) {
+        Some(id) => id,
+        None => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
 
     let page = page.unwrap_or(1).max(1);
     let per_page = per_page.unwrap_or(20).clamp(1, 100);
     let offset = (page - 1) * per_page;
 
+    let filter = match verified_usage {
+        Some(true) => " AND verified_usage = 1",
+        Some(false) => " AND verified_usage = 0",
+        None => "",
+    };
+    let order = REVIEW_SORTS
+        .iter()
+        .find(|(name, _)| Some(*name) == sort)
+        .unwrap_or(&REVIEW_SORTS[0])
+        .1;
+
     let total: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM reviews WHERE app_id = ?1",
+            &format!("SELECT COUNT(*) FROM reviews WHERE app_id = ?1{filter}"),
             rusqlite::params![app_id],
             |r| r.get(0),
         )
         .unwrap_or(0);
 
-    let reviews: Vec<Value> = match conn.prepare(
-        "SELECT id, app_id, rating, title, body, created_at, reviewer_name
-         FROM reviews WHERE app_id = ?1 ORDER BY created_at DESC LIMIT ?2 OFFSET ?3",
-    ) {
+    let reviews: Vec<Value> = match conn.prepare(&format!(
+        "SELECT id, app_id, rating, title, body, created_at, reviewer_name, pros, cons, use_case, verified_usage, edited_at
+         FROM reviews WHERE app_id = ?1{filter} ORDER BY {order} LIMIT ?2 OFFSET ?3"
+    )) {
         Ok(mut stmt) => {
             match stmt.query_map(rusqlite::params![app_id, per_page, offset], |row| {
                 Ok(json!({
@@ -147,6 +641,11 @@ pub fn get_reviews(
                     "body": row.get::<_, Option<String>>(4)?,
                     "created_at": row.get::<_, String>(5)?,
                     "reviewer_name": row.get::<_, Option<String>>(6)?,
+                    "pros": serde_json::from_str::<Value>(&row.get::<_, String>(7)?).unwrap_or(json!([])),
+                    "cons": serde_json::from_str::<Value>(&row.get::<_, String>(8)?).unwrap_or(json!([])),
+                    "use_case": row.get::<_, Option<String>>(9)?,
+                    "verified_usage": row.get::<_, bool>(10)?,
+                    "edited_at": row.get::<_, Option<String>>(11)?,
                 }))
             }) {
                 Ok(rows) => rows.filter_map(|r| r.ok()).collect(),
@@ -156,6 +655,95 @@ pub fn get_reviews(
         Err(_) => Vec::new(),
     };
 
+    let reviews: Vec<Value> = reviews
+        .into_iter()
+        .map(|mut review| {
+            let id = review["id"].as_str().unwrap_or_default().to_string();
+            review["reactions"] = reaction_counts(&conn, &id);
+            review
+        })
+        .collect();
+
+    (
+        Status::Ok,
+        Json(json!({
+            "reviews": reviews,
+            "total": total,
+            "page": page,
+            "per_page": per_page,
+        })),
+    )
+}
+
+/// Reviews written with the calling API key, newest first, each with a
+/// summary of the app it's about, for finding reviews to edit or delete.
+#[get("/reviews/mine?<page>&<per_page>")]
+pub fn list_my_reviews(
+    key: AuthenticatedKey,
+    page: Option<i64>,
+    per_page: Option<i64>,
+    db: &rocket::State<DbState>,
+) -> Json<Value> {
+    let conn = db.conn();
+
+    let page = page.unwrap_or(1).max(1);
+    let per_page = per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * per_page;
+
+    let total: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM reviews WHERE reviewer_key_id = ?1",
+            rusqlite::params![key.id],
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+
+    let reviews: Vec<Value> = conn
+        .prepare(
+            "SELECT r.id, r.rating, r.title, r.body, r.created_at, r.reviewer_name, r.pros, r.cons, r.use_case,
+                    r.verified_usage, r.edited_at, a.id, a.name, a.slug, a.status, a.logo_url, a.avg_rating
+             FROM reviews r JOIN apps a ON a.id = r.app_id
+             WHERE r.reviewer_key_id = ?1
+             ORDER BY r.created_at DESC, r.rowid DESC LIMIT ?2 OFFSET ?3",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![key.id, per_page, offset], |row| {
+                Ok(json!({
+                    "id": row.get::<_, String>(0)?,
+                    "app_id": row.get::<_, String>(11)?,
+                    "rating": row.get::<_, i64>(1)?,
+                    "title": row.get::<_, Option<String>>(2)?,
+                    "body": row.get::<_, Option<String>>(3)?,
+                    "created_at": row.get::<_, String>(4)?,
+                    "reviewer_name": row.get::<_, Option<String>>(5)?,
+                    "pros": serde_json::from_str::<Value>(&row.get::<_, String>(6)?).unwrap_or(json!([])),
+                    "cons": serde_json::from_str::<Value>(&row.get::<_, String>(7)?).unwrap_or(json!([])),
+                    "use_case": row.get::<_, Option<String>>(8)?,
+                    "verified_usage": row.get::<_, bool>(9)?,
+                    "edited_at": row.get::<_, Option<String>>(10)?,
+                    "app": {
+                        "id": row.get::<_, String>(11)?,
+                        "name": row.get::<_, String>(12)?,
+                        "slug": row.get::<_, String>(13)?,
+                        "status": row.get::<_, String>(14)?,
+                        "logo_url": row.get::<_, Option<String>>(15)?,
+                        "avg_rating": row.get::<_, f64>(16)?,
+                    },
+                }))
+            })?
+            .collect()
+        })
+        .unwrap_or_default();
+
+    let reviews: Vec<Value> = reviews
+        .into_iter()
+        .map(|mut review| {
+            let id = review["id"].as_str().unwrap_or_default().to_string();
+            review["reactions"] = reaction_counts(&conn, &id);
+            review
+        })
+        .collect();
+
     Json(json!({
         "reviews": reviews,
         "total": total,
@@ -164,23 +752,109 @@ pub fn get_reviews(
     }))
 }
 
+/// Most common pros or cons across an app's reviews, matched case-insensitively.
+fn top_points(conn: &rusqlite::Connection, app_id: &str, column: &str, limit: i64) -> Vec<Value> {
+    let sql = format!(
+        "SELECT MIN(TRIM(p.value)), COUNT(*) FROM reviews r, json_each(r.{}) p
+         WHERE r.app_id = ?1 GROUP BY LOWER(TRIM(p.value)) ORDER BY 2 DESC, 1 LIMIT ?2",
+        column
+    );
+    let mut stmt = match conn.prepare(&sql) {
+        Ok(s) => s,
+        Err(_) => return Vec::new(),
+    };
+    stmt.query_map(rusqlite::params![app_id, limit], |row| {
+        Ok(json!({ "text": row.get::<_, String>(0)?, "count": row.get::<_, i64>(1)? }))
+    })
+    .map(|rows| rows.filter_map(|r| r.ok()).collect())
+    .unwrap_or_default()
+}
+
+/// Rating distribution and the most mentioned pros and cons for an app.
+#[get("/apps/<app_id>/reviews/summary?<limit>")]
+pub fn review_summary(
+    reader: ReadAccess,
+    token: EditTokenParam,
+    app_id: &str,
+    limit: Option<i64>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    let app_id = match crate::routes::visible_app_id(&conn, app_id, &reader, token.0.as_deref()) {
+        Some(id) => id,
+        None => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
+    let limit = limit.unwrap_or(5).clamp(1, 20);
+
+    let mut distribution = serde_json::Map::new();
+    for stars in 1..=5 {
+        distribution.insert(stars.to_string(), json!(0));
+    }
+    let (mut count, mut sum) = (0i64, 0i64);
+    if let Ok(mut stmt) = conn.prepare("SELECT rating, COUNT(*) FROM reviews WHERE app_id = ?1 GROUP BY rating") {
+        let rows = stmt
+            .query_map(rusqlite::params![app_id], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, i64>(1)?)))
+            .map(|rows| rows.filter_map(|r| r.ok()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        for (rating, n) in rows {
+            distribution.insert(rating.to_string(), json!(n));
+            count += n;
+            sum += rating * n;
+        }
+    }
+
+    let (verified_count, verified_avg): (i64, f64) = conn
+        .query_row(
+            "SELECT COUNT(*), COALESCE(AVG(CAST(rating AS REAL)), 0.0) FROM reviews WHERE app_id = ?1 AND verified_usage = 1",
+            rusqlite::params![app_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap_or((0, 0.0));
+
+    (
+        Status::Ok,
+        Json(json!({
+            "app_id": app_id,
+            "review_count": count,
+            "avg_rating": if count > 0 { sum as f64 / count as f64 } else { 0.0 },
+            "verified_usage_count": verified_count,
+            "verified_avg_rating": verified_avg,
+            "distribution": distribution,
+            "top_pros": top_points(&conn, &app_id, "pros", limit),
+            "top_cons": top_points(&conn, &app_id, "cons", limit),
+        })),
+    )
+}
+
 // === Categories (NO AUTH REQUIRED) ===
 
+/// Counts cover listed apps only: public ones, plus private ones for
+/// signed-in callers.
 #[get("/categories")]
-pub fn list_categories(db: &rocket::State<DbState>) -> Json<Value> {
+pub fn list_categories(viewer: Option<Principal>, db: &rocket::State<DbState>) -> Json<Value> {
     let conn = db.conn();
 
     let mut stmt = conn
-        .prepare(
-            "SELECT category, COUNT(*) as count FROM apps WHERE status = 'approved' GROUP BY category ORDER BY count DESC",
-        )
+        .prepare(&format!(
+            "SELECT id, parent_id, count, description, icon FROM (
+                SELECT c.id, c.parent_id, c.description, c.icon,
+                    (SELECT COUNT(*) FROM apps a WHERE a.status = 'approved' AND {}
+                        AND (a.category = c.id OR a.category LIKE c.id || '/%')) AS count
+                FROM categories c
+             ) WHERE count > 0 ORDER BY count DESC, id",
+            listed_visibility("a.visibility", viewer.is_some())
+        ))
         .unwrap();
 
+    // Counts include subcategories, matching `GET /apps?category=`
     let categories: Vec<Value> = stmt
         .query_map([], |row| {
             Ok(json!({
                 "name": row.get::<_, String>(0)?,
-                "count": row.get::<_, i64>(1)?,
+                "parent_id": row.get::<_, Option<String>>(1)?,
+                "count": row.get::<_, i64>(2)?,
+                "description": row.get::<_, Option<String>>(3)?,
+                "icon": row.get::<_, Option<String>>(4)?,
             }))
         })
         .unwrap()
@@ -193,3 +867,63 @@ pub fn list_categories(db: &rocket::State<DbState>) -> Json<Value> {
         "valid_protocols": VALID_PROTOCOLS,
     }))
 }
+
+/// Set a category's icon and description, shown by `GET /categories` so
+/// frontends don't hardcode them. Admin only. Edited categories are no
+/// longer updated from the built-in defaults.
+#[patch("/categories/<path..>", format = "json", data = "<body>")]
+pub fn update_category(
+    actor: Actor,
+    path: std::path::PathBuf,
+    body: Json<UpdateCategoryRequest>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let key = match actor.require_admin("Only admins can edit categories") {
+        Ok(key) => key,
+        Err(err) => return err.into(),
+    };
+    if body.description.is_none() && body.icon.is_none() {
+        return ApiError::new(ErrorCode::NoChanges, "Provide description and/or icon").into();
+    }
+    if let Err(err) = validation::check("icon", body.icon.as_ref(), categories::MAX_ICON_LENGTH)
+        .and_then(|_| validation::check("description", body.description.as_ref(), categories::MAX_DESCRIPTION_LENGTH))
+    {
+        return err.into();
+    }
+
+    let id = path.to_string_lossy().trim_matches('/').to_lowercase();
+    let conn = db.conn();
+    // Empty strings clear a field; omitted fields keep their value
+    let cleared = |v: &Option<String>| v.as_deref().map(str::trim).map(|v| (!v.is_empty()).then(|| v.to_string()));
+    let (description, icon) = (cleared(&body.description), cleared(&body.icon));
+    let updated = conn.query_row(
+        "UPDATE categories SET
+            description = CASE WHEN ?2 THEN ?3 ELSE description END,
+            icon = CASE WHEN ?4 THEN ?5 ELSE icon END,
+            customized = 1
+         WHERE id = ?1 RETURNING id, parent_id, description, icon",
+        rusqlite::params![id, description.is_some(), description.flatten(), icon.is_some(), icon.flatten()],
+        |r| {
+            Ok(json!({
+                "name": r.get::<_, String>(0)?,
+                "parent_id": r.get::<_, Option<String>>(1)?,
+                "description": r.get::<_, Option<String>>(2)?,
+                "icon": r.get::<_, Option<String>>(3)?,
+            }))
+        },
+    );
+    let category = match updated {
+        Ok(category) => category,
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+            return ApiError::new(ErrorCode::NotFound, "Category not found").into()
+        }
+        Err(_) => return ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    };
+
+    let _ = conn.execute(
+        "INSERT INTO audit_log (id, action, actor_key_id, details) VALUES (?1, 'category.update', ?2, ?3)",
+        rusqlite::params![uuid::Uuid::new_v4().to_string(), key.id, category.to_string()],
+    );
+
+    (Status::Ok, Json(category))
+}
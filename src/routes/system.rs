@@ -7,7 +7,15 @@ use rocket::tokio::select;
 use rocket::tokio::time::Duration;
 use rocket::Shutdown;
 
-use crate::events::EventBus;
+use crate::events::{AppEvent, EventBus, EventFilter, EVENTS_LOST};
+use crate::llms::LlmsTxtCache;
+use crate::maintenance::Maintenance;
+use crate::replica::Replica;
+use crate::retention::PrivacyMode;
+use crate::errors::{ApiError, ErrorCode};
+use crate::DbState;
+use rocket::futures::{SinkExt, StreamExt};
+use rocket_ws::Message;
 
 // === SKILL.md / llms.txt ===
 
@@ -17,29 +25,211 @@ pub fn skill_md() -> (ContentType, &'static str) {
     (ContentType::Plain, include_str!("../../SKILL.md"))
 }
 
-/// GET /llms.txt — alias for SKILL.md (backward-compatible)
+/// GET /llms.txt — catalog snapshot generated from live data (cached briefly)
 #[get("/llms.txt")]
-pub fn llms_txt() -> (ContentType, &'static str) {
-    (ContentType::Plain, include_str!("../../SKILL.md"))
+pub fn llms_txt(
+    db: &rocket::State<DbState>,
+    cache: &rocket::State<LlmsTxtCache>,
+) -> (ContentType, String) {
+    (ContentType::Plain, render_llms_txt(db, cache))
 }
 
 /// Root-level /llms.txt for standard discovery (outside /api/v1)
 #[get("/llms.txt", rank = 2)]
-pub fn root_llms_txt() -> (ContentType, &'static str) {
-    (ContentType::Plain, include_str!("../../SKILL.md"))
+pub fn root_llms_txt(
+    db: &rocket::State<DbState>,
+    cache: &rocket::State<LlmsTxtCache>,
+) -> (ContentType, String) {
+    (ContentType::Plain, render_llms_txt(db, cache))
+}
+
+fn render_llms_txt(db: &DbState, cache: &LlmsTxtCache) -> String {
+    cache.get_or_render(|| {
+        let conn = db.conn();
+        crate::llms::render(&conn, cache.endpoints())
+    })
+}
+
+// === Search Discovery ===
+
+/// Absolute origin of the request (`http://host:port`) built from `Host` and
+/// `X-Forwarded-Proto`. Empty when there is no `Host` header, which leaves
+/// URLs relative.
+pub struct RequestOrigin(pub(super) String);
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for RequestOrigin {
+    type Error = ();
+
+    async fn from_request(request: &'r rocket::Request<'_>) -> rocket::request::Outcome<Self, ()> {
+        let origin = match request.headers().get_one("Host") {
+            Some(host) => {
+                let scheme = request.headers().get_one("X-Forwarded-Proto").unwrap_or("http");
+                format!("{}://{}", scheme, host)
+            }
+            None => String::new(),
+        };
+        rocket::request::Outcome::Success(RequestOrigin(origin))
+    }
+}
+
+pub(super) fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// GET /opensearch.xml — OpenSearch description of the search endpoint
+#[get("/opensearch.xml")]
+pub fn opensearch_xml(origin: RequestOrigin) -> (ContentType, String) {
+    let base = xml_escape(&origin.0);
+    let body = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<OpenSearchDescription xmlns="http://a9.com/-/spec/opensearch/1.1/">
+  <ShortName>App Directory</ShortName>
+  <Description>Search agent-native apps and APIs by name, description, and tags</Description>
+  <Tags>apps api agents mcp directory</Tags>
+  <InputEncoding>UTF-8</InputEncoding>
+  <OutputEncoding>UTF-8</OutputEncoding>
+  <Url type="application/json" rel="results" template="{base}/api/v1/apps/search?q={{searchTerms}}&amp;page={{startPage?}}&amp;per_page={{count?}}" indexOffset="1" pageOffset="1"/>
+  <Url type="application/opensearchdescription+xml" rel="self" template="{base}/opensearch.xml"/>
+</OpenSearchDescription>
+"#,
+        base = base
+    );
+    (ContentType::new("application", "opensearchdescription+xml"), body)
+}
+
+fn filter(name: &str, kind: &str, description: &str, values: Option<Vec<&str>>) -> Value {
+    let mut f = json!({ "name": name, "type": kind, "description": description });
+    if let Some(values) = values {
+        f["values"] = json!(values);
+    }
+    f
+}
+
+/// GET /api/v1/search/meta — filters, sorts, facets, and fields accepted by
+/// the search and list endpoints, for clients that build queries programmatically
+#[get("/search/meta")]
+pub fn search_meta() -> Json<Value> {
+    use super::apps::{LIST_COMPUTED_FIELDS, LIST_SORTS, SEARCH_COMPUTED_FIELDS};
+    use crate::filters::HEALTH_FILTERS;
+    use crate::models::{VALID_CATEGORIES, VALID_PROTOCOLS, VALID_STATUSES};
+
+    let category = filter(
+        "category",
+        "string",
+        "Category path; matches the category and its subcategories. Full tree at /api/v1/categories",
+        Some(VALID_CATEGORIES.to_vec()),
+    );
+    let protocol = filter("protocol", "string", "Exact protocol", Some(VALID_PROTOCOLS.to_vec()));
+    let pagination = json!({
+        "page": { "default": 1, "min": 1 },
+        "per_page": { "default": 20, "min": 1, "max": 100 },
+    });
+    let statuses: Vec<&str> = VALID_STATUSES.iter().copied().chain(["all"]).collect();
+    let time_ranges = [
+        filter("created_after", "date-time", "RFC 3339; apps created at or after this time", None),
+        filter("created_before", "date-time", "RFC 3339; apps created before this time", None),
+        filter("updated_after", "date-time", "RFC 3339; apps changed at or after this time, for incremental sync", None),
+    ];
+
+    let shared = [
+        category,
+        protocol,
+        filter("featured", "boolean", "true for featured apps only, false for the rest", None),
+        filter("verified", "boolean", "true for verified apps only, false for the rest", None),
+        filter("health", "string", "Last health check status; unknown means never checked", Some(HEALTH_FILTERS.to_vec())),
+        time_ranges[0].clone(),
+        time_ranges[1].clone(),
+        time_ranges[2].clone(),
+    ];
+    let mut list_filters = shared.to_vec();
+    list_filters.insert(2, filter("status", "string", "Listing status; defaults to approved", Some(statuses)));
+
+    Json(json!({
+        "opensearch": "/opensearch.xml",
+        "search": {
+            "method": "GET",
+            "path": "/api/v1/apps/search",
+            "query": {
+                "param": "q",
+                "required": true,
+                "syntax": "Whitespace-separated terms matched case-insensitively against name, descriptions, and tags. Double quotes keep a phrase together. All terms must match; if nothing does, apps matching any term are returned and `match` is `any`.",
+            },
+            "filters": shared,
+            "sort": { "param": null, "default": "rating", "options": ["rating"] },
+            "facets": [
+                { "name": "category", "description": "Match counts per category" },
+                { "name": "protocol", "description": "Match counts per protocol" },
+                { "name": "health_status", "description": "Match counts per last health status", "values": HEALTH_FILTERS },
+                { "name": "verified", "description": "Match counts of verified and unverified apps", "values": ["verified", "unverified"] },
+            ],
+            "pagination": pagination,
+            "fields": crate::fields::FieldSet::available(SEARCH_COMPUTED_FIELDS),
+        },
+        "list": {
+            "method": "GET",
+            "path": "/api/v1/apps",
+            "query": {
+                "param": "q",
+                "aliases": ["search"],
+                "required": false,
+                "syntax": "Same terms and phrases as search; every term must match.",
+            },
+            "filters": list_filters,
+            "sort": {
+                "param": "sort",
+                "default": LIST_SORTS[0].0,
+                "options": LIST_SORTS.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            },
+            "facets": [],
+            "pagination": pagination,
+            "fields": crate::fields::FieldSet::available(LIST_COMPUTED_FIELDS),
+        },
+    }))
+}
+
+// === CAPTCHA ===
+
+/// GET /api/v1/captcha — whether anonymous submissions and reviews need a
+/// CAPTCHA token, and the provider and site key to render the widget with
+#[get("/captcha")]
+pub fn captcha_config(captcha: &rocket::State<crate::captcha::Captcha>) -> Json<Value> {
+    Json(captcha.describe())
 }
 
 // === Health ===
 
+/// `banner` carries the maintenance message while writes are paused so
+/// frontends can show it; it is null otherwise. `privacy` says what the
+/// instance keeps about its visitors. On a read-only replica, `replica`
+/// reports the primary and how the last sync went.
 #[get("/health")]
-pub fn health() -> Json<Value> {
+pub fn health(
+    maintenance: &rocket::State<Maintenance>,
+    privacy: &rocket::State<PrivacyMode>,
+    replica: &rocket::State<Replica>,
+) -> Json<Value> {
+    let maintenance = maintenance.state();
     Json(json!({
         "status": "ok",
         "service": "app-directory",
-        "version": "0.1.0"
+        "version": "0.1.0",
+        "maintenance": maintenance.enabled,
+        "banner": maintenance.enabled.then_some(maintenance.message),
+        "privacy": privacy.posture(),
+        "read_only": replica.is_enabled(),
+        "replica": replica.health(),
     }))
 }
 
+// === Error Code Registry ===
+
+/// Every error code the API can return, with its HTTP status and meaning.
+#[get("/errors")]
+pub fn error_codes() -> Json<Value> {
+    Json(crate::errors::registry())
+}
+
 // === CORS Preflight ===
 
 #[options("/<_path..>")]
@@ -47,25 +237,73 @@ pub fn cors_preflight(_path: std::path::PathBuf) -> Status {
     Status::NoContent
 }
 
+// === Event History ===
+
+/// Stored events, oldest first, for clients catching up on missed activity.
+/// `type` takes the same comma-separated list and `app.*` wildcards as the
+/// stream; `since` is an RFC 3339 timestamp (inclusive).
+#[get("/events?<type>&<app_id>&<since>&<page>&<per_page>")]
+pub fn list_events(
+    r#type: Option<&str>,
+    app_id: Option<&str>,
+    since: Option<&str>,
+    page: Option<i64>,
+    per_page: Option<i64>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let since = match since.filter(|s| !s.trim().is_empty()) {
+        Some(raw) => match chrono::DateTime::parse_from_rfc3339(raw.trim()) {
+            Ok(t) => Some(t.with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M:%S").to_string()),
+            Err(_) => return ApiError::from(ErrorCode::InvalidSince).into(),
+        },
+        None => None,
+    };
+    let filter = EventFilter::from_query(r#type, app_id);
+
+    let page = page.unwrap_or(1).max(1);
+    let per_page = per_page.unwrap_or(50).clamp(1, 200);
+    let offset = (page - 1) * per_page;
+
+    let conn = db.conn();
+    let (total, events) = crate::events::history(&conn, &filter, since.as_deref(), per_page, offset);
+
+    (
+        Status::Ok,
+        Json(json!({
+            "events": events,
+            "total": total,
+            "page": page,
+            "per_page": per_page,
+        })),
+    )
+}
+
 // === SSE Event Stream ===
 
-#[get("/events/stream")]
+/// Server-sent event stream. Optional `events` (comma-separated, `app.*` wildcards)
+/// and `app_id` params restrict which events are delivered.
+#[get("/events/stream?<events>&<app_id>")]
 pub fn event_stream(
+    events: Option<&str>,
+    app_id: Option<&str>,
     bus: &rocket::State<EventBus>,
     mut shutdown: Shutdown,
 ) -> EventStream![] {
     let mut rx = bus.subscribe();
+    let filter = EventFilter::from_query(events, app_id);
 
     EventStream! {
         loop {
             select! {
                 msg = rx.recv() => match msg {
                     Ok(event) => {
-                        yield Event::json(&event.data).event(event.event);
+                        if filter.matches(&event) {
+                            yield Event::json(&event.data).event(event.event);
+                        }
                     }
                     Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
                     Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
-                        yield Event::data("events_lost").event("warning".to_string());
+                        yield Event::data(EVENTS_LOST).event("warning".to_string());
                     }
                 },
                 _ = &mut shutdown => break,
@@ -75,22 +313,136 @@ pub fn event_stream(
     .heartbeat(Duration::from_secs(15))
 }
 
+/// Server-sent stream of `app.health_changed` events only, each reduced to
+/// the app, old and new status and latency (see
+/// [`crate::health::transition_payload`]), for monitoring dashboards. Optional
+/// `app_id` narrows it to one app.
+#[get("/events/health?<app_id>")]
+pub fn health_event_stream(
+    app_id: Option<&str>,
+    bus: &rocket::State<EventBus>,
+    mut shutdown: Shutdown,
+) -> EventStream![] {
+    let mut rx = bus.subscribe();
+    let filter = EventFilter::from_query(Some("app.health_changed"), app_id);
+
+    EventStream! {
+        loop {
+            select! {
+                msg = rx.recv() => match msg {
+                    Ok(event) => {
+                        if filter.matches(&event) {
+                            yield Event::json(&crate::health::transition_payload(&event.data)).event(event.event);
+                        }
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                        yield Event::data(EVENTS_LOST).event("warning".to_string());
+                    }
+                },
+                _ = &mut shutdown => break,
+            }
+        }
+    }
+    .heartbeat(Duration::from_secs(15))
+}
+
+// === WebSocket Event Channel ===
+
+/// WebSocket alternative to the SSE stream for clients whose proxies drop EventSource.
+/// Each text frame is `{"event": ..., "data": ...}`; accepts the same filters as SSE.
+#[get("/events/ws?<events>&<app_id>")]
+pub fn event_ws(
+    ws: rocket_ws::WebSocket,
+    events: Option<&str>,
+    app_id: Option<&str>,
+    bus: &rocket::State<EventBus>,
+    mut shutdown: Shutdown,
+) -> rocket_ws::Channel<'static> {
+    let mut rx = bus.subscribe();
+    let filter = EventFilter::from_query(events, app_id);
+
+    ws.channel(move |mut stream| {
+        Box::pin(async move {
+            loop {
+                select! {
+                    msg = rx.recv() => match msg {
+                        Ok(event) => {
+                            if filter.matches(&event) {
+                                stream.send(Message::Text(event.to_json_string())).await?;
+                            }
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                            let warning = AppEvent {
+                                event: "warning".to_string(),
+                                data: json!(EVENTS_LOST),
+                            };
+                            stream.send(Message::Text(warning.to_json_string())).await?;
+                        }
+                    },
+                    incoming = stream.next() => match incoming {
+                        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                        _ => {}
+                    },
+                    _ = &mut shutdown => break,
+                }
+            }
+            Ok(())
+        })
+    })
+}
+
 // === OpenAPI Spec ===
 
 #[get("/openapi.json")]
 pub fn openapi() -> (Status, (rocket::http::ContentType, String)) {
-    let spec = include_str!("../../openapi.json");
+    let spec = crate::client_spec::OPENAPI_JSON;
     (
         Status::Ok,
         (rocket::http::ContentType::JSON, spec.to_string()),
     )
 }
 
+/// GET /client-spec — every endpoint with its params, auth mode and error
+/// codes, condensed from the OpenAPI document for client and tool generation
+#[get("/client-spec")]
+pub fn client_spec() -> Json<&'static Value> {
+    Json(crate::client_spec::get())
+}
+
+// === A2A Agent Card ===
+
+/// GET /.well-known/agent.json — the directory described as an A2A agent
+#[get("/.well-known/agent.json")]
+pub fn agent_card(origin: RequestOrigin) -> Json<Value> {
+    Json(crate::a2a::directory_card(&origin.0))
+}
+
 // === Well-Known Skills Discovery (Cloudflare RFC) ===
 
+/// GET /.well-known/skills/index.json — this service plus one entry per
+/// approved app that publishes an API spec
 #[get("/.well-known/skills/index.json")]
-pub fn skills_index() -> (ContentType, &'static str) {
-    (ContentType::JSON, SKILLS_INDEX_JSON)
+pub fn skills_index(db: &rocket::State<DbState>) -> Json<Value> {
+    let mut skills = vec![json!({
+        "name": "app-directory",
+        "description": "Discover, submit, and review agent-native applications. A curated registry for AI agent tools and services with categories, search, deprecation tracking, and admin workflows.",
+        "url": "/SKILL.md",
+        "files": ["SKILL.md"],
+    })];
+    let conn = db.conn();
+    skills.extend(crate::skills::app_skill_entries(&conn));
+    Json(json!({ "skills": skills }))
+}
+
+/// GET /.well-known/skills/apps/<slug>/SKILL.md — generated manifest for a listed app
+#[get("/.well-known/skills/apps/<slug>/SKILL.md")]
+pub fn app_skill_md(slug: &str, db: &rocket::State<DbState>) -> Result<(ContentType, String), ApiError> {
+    let conn = db.conn();
+    crate::skills::app_skill_md(&conn, slug)
+        .map(|body| (ContentType::Plain, body))
+        .ok_or_else(|| ApiError::new(ErrorCode::NotFound, "No skill manifest for this app"))
 }
 
 #[get("/.well-known/skills/app-directory/SKILL.md")]
@@ -104,17 +456,4 @@ pub fn api_skills_skill_md() -> (ContentType, &'static str) {
     (ContentType::Plain, include_str!("../../SKILL.md"))
 }
 
-const SKILLS_INDEX_JSON: &str = r#"{
-  "skills": [
-    {
-      "name": "app-directory",
-      "description": "Discover, submit, and review agent-native applications. A curated registry for AI agent tools and services with categories, search, deprecation tracking, and admin workflows.",
-      "url": "/SKILL.md",
-      "files": [
-        "SKILL.md"
-      ]
-    }
-  ]
-}"#;
-
 // SKILL_MD_CONTENT removed — now served via include_str!("../../SKILL.md")
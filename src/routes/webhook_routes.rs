@@ -2,13 +2,17 @@ use rocket::http::Status;
 use rocket::serde::json::Json;
 use serde_json::{json, Value};
 
-use crate::auth::AuthenticatedKey;
+use crate::auth::{Actor, AuthenticatedKey};
+use crate::errors::{ApiError, ErrorCode};
+use crate::webhooks::{self, SecretGrace};
 use crate::DbState;
 
 #[derive(Debug, serde::Deserialize)]
 pub struct CreateWebhookRequest {
     pub url: String,
     pub events: Option<Vec<String>>,
+    /// Delivery timeout; omitted uses `WEBHOOK_TIMEOUT_MS`.
+    pub timeout_ms: Option<u64>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -19,9 +23,14 @@ pub struct WebhookResponse {
     pub active: bool,
     pub failure_count: i64,
     pub last_triggered_at: Option<String>,
+    /// Delivery timeout; null uses the server default.
+    pub timeout_ms: Option<i64>,
     pub created_at: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub secret: Option<String>,
+    /// Set while a rotated-out secret is still signing deliveries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_secret_expires_at: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -29,6 +38,12 @@ pub struct UpdateWebhookRequest {
     pub url: Option<String>,
     pub events: Option<Vec<String>>,
     pub active: Option<bool>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct RotateSecretRequest {
+    pub grace_period_secs: Option<u64>,
 }
 
 static VALID_WEBHOOK_EVENTS: &[&str] = &[
@@ -37,10 +52,22 @@ static VALID_WEBHOOK_EVENTS: &[&str] = &[
     "app.rejected",
     "app.deprecated",
     "app.undeprecated",
+    "app.archived",
+    "app.unarchived",
+    "app.featured",
+    "app.unfeatured",
     "app.updated",
     "app.deleted",
     "review.submitted",
+    "review.updated",
+    "review.deleted",
     "health.checked",
+    "app.health_changed",
+    "health.batch_completed",
+    "key.expiring",
+    "key.anomaly",
+    "rate_limit.warning",
+    "config.reloaded",
 ];
 
 /// Register a webhook. Admin only.
@@ -51,46 +78,42 @@ pub fn create_webhook(
     db: &rocket::State<DbState>,
 ) -> (Status, Json<Value>) {
     if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(json!({ "error": "ADMIN_REQUIRED" })),
-        );
+        return ApiError::from(ErrorCode::AdminRequired).into();
     }
 
     let url = body.url.trim();
     if !url.starts_with("http://") && !url.starts_with("https://") {
-        return (
-            Status::BadRequest,
-            Json(
-                json!({ "error": "INVALID_URL", "message": "URL must start with http:// or https://" }),
-            ),
-        );
+        return ApiError::new(
+            ErrorCode::InvalidUrl,
+            "URL must start with http:// or https://",
+        )
+        .into();
     }
 
     let events = body.events.clone().unwrap_or_default();
     for evt in &events {
         if !VALID_WEBHOOK_EVENTS.contains(&evt.as_str()) {
-            return (
-                Status::BadRequest,
-                Json(json!({
-                    "error": "INVALID_EVENT",
-                    "message": format!("Invalid event '{}'. Valid: {}", evt, VALID_WEBHOOK_EVENTS.join(", "))
-                })),
-            );
+            return ApiError::new(
+                ErrorCode::InvalidEvent,
+                format!("Invalid event '{}'. Valid: {}", evt, VALID_WEBHOOK_EVENTS.join(", ")),
+            )
+            .into();
         }
     }
 
+    if let Some(Err(msg)) = body.timeout_ms.map(webhooks::validate_timeout) {
+        return ApiError::new(ErrorCode::InvalidTimeout, msg).into();
+    }
+
     let conn = db.conn();
     let id = uuid::Uuid::new_v4().to_string();
-    let secret = format!(
-        "whsec_{}",
-        uuid::Uuid::new_v4().to_string().replace('-', "")
-    );
+    let secret = webhooks::generate_secret();
     let events_json = serde_json::to_string(&events).unwrap();
+    let timeout_ms = body.timeout_ms.map(|t| t as i64);
 
     match conn.execute(
-        "INSERT INTO webhooks (id, url, secret, events, created_by) VALUES (?1, ?2, ?3, ?4, ?5)",
-        rusqlite::params![id, url, secret, events_json, key.id],
+        "INSERT INTO webhooks (id, url, secret, events, created_by, timeout_ms) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![id, url, secret, events_json, key.id, timeout_ms],
     ) {
         Ok(_) => (
             Status::Created,
@@ -101,14 +124,13 @@ pub fn create_webhook(
                 active: true,
                 failure_count: 0,
                 last_triggered_at: None,
+                timeout_ms,
                 created_at: chrono::Utc::now().to_rfc3339(),
                 secret: Some(secret),
+                previous_secret_expires_at: None,
             })),
         ),
-        Err(_) => (
-            Status::InternalServerError,
-            Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
-        ),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
     }
 }
 
@@ -116,16 +138,15 @@ pub fn create_webhook(
 #[get("/webhooks")]
 pub fn list_webhooks(key: AuthenticatedKey, db: &rocket::State<DbState>) -> (Status, Json<Value>) {
     if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(json!({ "error": "ADMIN_REQUIRED" })),
-        );
+        return ApiError::from(ErrorCode::AdminRequired).into();
     }
 
     let conn = db.conn();
     let mut stmt = conn
         .prepare(
-            "SELECT id, url, events, active, failure_count, last_triggered_at, created_at
+            "SELECT id, url, events, active, failure_count, last_triggered_at, created_at,
+                    CASE WHEN previous_secret_expires_at > datetime('now') THEN previous_secret_expires_at END,
+                    timeout_ms
              FROM webhooks ORDER BY created_at DESC",
         )
         .unwrap();
@@ -141,8 +162,10 @@ pub fn list_webhooks(key: AuthenticatedKey, db: &rocket::State<DbState>) -> (Sta
                 active: row.get::<_, i32>(3)? != 0,
                 failure_count: row.get(4)?,
                 last_triggered_at: row.get(5)?,
+                timeout_ms: row.get(8)?,
                 created_at: row.get(6)?,
                 secret: None,
+                previous_secret_expires_at: row.get(7)?,
             })
         })
         .unwrap()
@@ -152,7 +175,7 @@ pub fn list_webhooks(key: AuthenticatedKey, db: &rocket::State<DbState>) -> (Sta
     (Status::Ok, Json(json!({ "webhooks": webhooks })))
 }
 
-/// Update a webhook (URL, events, active). Admin only.
+/// Update a webhook (URL, events, active, timeout). Admin only.
 #[patch("/webhooks/<webhook_id>", format = "json", data = "<body>")]
 pub fn update_webhook(
     key: AuthenticatedKey,
@@ -161,10 +184,7 @@ pub fn update_webhook(
     db: &rocket::State<DbState>,
 ) -> (Status, Json<Value>) {
     if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(json!({ "error": "ADMIN_REQUIRED" })),
-        );
+        return ApiError::from(ErrorCode::AdminRequired).into();
     }
 
     let conn = db.conn();
@@ -178,21 +198,27 @@ pub fn update_webhook(
         .unwrap_or(false);
 
     if !exists {
-        return (
-            Status::NotFound,
-            Json(json!({ "error": "NOT_FOUND", "message": "Webhook not found" })),
+        return ApiError::new(ErrorCode::NotFound, "Webhook not found").into();
+    }
+
+    if let Some(timeout_ms) = body.timeout_ms {
+        if let Err(msg) = webhooks::validate_timeout(timeout_ms) {
+            return ApiError::new(ErrorCode::InvalidTimeout, msg).into();
+        }
+        let _ = conn.execute(
+            "UPDATE webhooks SET timeout_ms = ?1 WHERE id = ?2",
+            rusqlite::params![timeout_ms as i64, webhook_id],
         );
     }
 
     if let Some(ref url) = body.url {
         let url = url.trim();
         if !url.starts_with("http://") && !url.starts_with("https://") {
-            return (
-                Status::BadRequest,
-                Json(
-                    json!({ "error": "INVALID_URL", "message": "URL must start with http:// or https://" }),
-                ),
-            );
+            return ApiError::new(
+                ErrorCode::InvalidUrl,
+                "URL must start with http:// or https://",
+            )
+            .into();
         }
         let _ = conn.execute(
             "UPDATE webhooks SET url = ?1 WHERE id = ?2",
@@ -203,13 +229,11 @@ pub fn update_webhook(
     if let Some(ref events) = body.events {
         for evt in events {
             if !VALID_WEBHOOK_EVENTS.contains(&evt.as_str()) {
-                return (
-                    Status::BadRequest,
-                    Json(json!({
-                        "error": "INVALID_EVENT",
-                        "message": format!("Invalid event '{}'. Valid: {}", evt, VALID_WEBHOOK_EVENTS.join(", "))
-                    })),
-                );
+                return ApiError::new(
+                    ErrorCode::InvalidEvent,
+                    format!("Invalid event '{}'. Valid: {}", evt, VALID_WEBHOOK_EVENTS.join(", ")),
+                )
+                .into();
             }
         }
         let events_json = serde_json::to_string(events).unwrap();
@@ -234,7 +258,10 @@ pub fn update_webhook(
     }
 
     let result = conn.query_row(
-        "SELECT id, url, events, active, failure_count, last_triggered_at, created_at FROM webhooks WHERE id = ?1",
+        "SELECT id, url, events, active, failure_count, last_triggered_at, created_at,
+                CASE WHEN previous_secret_expires_at > datetime('now') THEN previous_secret_expires_at END,
+                timeout_ms
+         FROM webhooks WHERE id = ?1",
         rusqlite::params![webhook_id],
         |row| {
             let events_str: String = row.get(2)?;
@@ -246,18 +273,85 @@ pub fn update_webhook(
                 active: row.get::<_, i32>(3)? != 0,
                 failure_count: row.get(4)?,
                 last_triggered_at: row.get(5)?,
+                timeout_ms: row.get(8)?,
                 created_at: row.get(6)?,
                 secret: None,
+                previous_secret_expires_at: row.get(7)?,
             })
         },
     );
 
     match result {
         Ok(wh) => (Status::Ok, Json(json!(wh))),
-        Err(_) => (
-            Status::InternalServerError,
-            Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
-        ),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+/// Issue a new signing secret. Admin only.
+///
+/// For `grace_period_secs` (default `WEBHOOK_SECRET_GRACE_SECS`) deliveries
+/// are signed with both the new and the old secret, so receivers can switch
+/// over without rejecting anything. A grace period of 0 retires the old
+/// secret immediately. Rotating again during a grace period drops the
+/// oldest secret.
+#[post("/webhooks/<webhook_id>/rotate-secret", data = "<body>")]
+pub fn rotate_webhook_secret(
+    key: AuthenticatedKey,
+    webhook_id: &str,
+    body: Option<Json<RotateSecretRequest>>,
+    db: &rocket::State<DbState>,
+    grace: &rocket::State<SecretGrace>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::from(ErrorCode::AdminRequired).into();
+    }
+
+    let grace_secs = body.as_ref().and_then(|b| b.grace_period_secs).unwrap_or(grace.0);
+    if grace_secs > webhooks::MAX_SECRET_GRACE_SECS {
+        return ApiError::from(ErrorCode::InvalidGracePeriod)
+            .with("max", webhooks::MAX_SECRET_GRACE_SECS)
+            .into();
+    }
+
+    let conn = db.conn();
+    let secret = webhooks::generate_secret();
+    // Every right-hand side sees the row before the update, so the old secret moves over
+    let result = conn.execute(
+        "UPDATE webhooks SET
+            previous_secret = CASE WHEN ?2 > 0 THEN secret END,
+            previous_secret_expires_at = CASE WHEN ?2 > 0 THEN datetime('now', '+' || ?2 || ' seconds') END,
+            secret = ?1
+         WHERE id = ?3",
+        rusqlite::params![secret, grace_secs as i64, webhook_id],
+    );
+
+    match result {
+        Ok(1) => {
+            let expires_at: Option<String> = conn
+                .query_row(
+                    "SELECT previous_secret_expires_at FROM webhooks WHERE id = ?1",
+                    rusqlite::params![webhook_id],
+                    |r| r.get(0),
+                )
+                .unwrap_or(None);
+            let message = if expires_at.is_some() {
+                "Secret rotated. Deliveries carry signatures from both secrets until previous_secret_expires_at."
+            } else {
+                "Secret rotated. The previous secret no longer signs deliveries."
+            };
+            (
+                Status::Ok,
+                Json(json!({
+                    "id": webhook_id,
+                    "secret": secret,
+                    "grace_period_secs": grace_secs,
+                    "previous_secret_expires_at": expires_at,
+                    "message": message,
+                })),
+            )
+        }
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "Webhook not found").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
     }
 }
 
@@ -269,10 +363,7 @@ pub fn delete_webhook(
     db: &rocket::State<DbState>,
 ) -> (Status, Json<Value>) {
     if !key.is_admin {
-        return (
-            Status::Forbidden,
-            Json(json!({ "error": "ADMIN_REQUIRED" })),
-        );
+        return ApiError::from(ErrorCode::AdminRequired).into();
     }
 
     let conn = db.conn();
@@ -280,14 +371,211 @@ pub fn delete_webhook(
         "DELETE FROM webhooks WHERE id = ?1",
         rusqlite::params![webhook_id],
     ) {
-        Ok(1) => (Status::Ok, Json(json!({ "message": "Webhook deleted" }))),
-        Ok(_) => (
-            Status::NotFound,
-            Json(json!({ "error": "NOT_FOUND", "message": "Webhook not found" })),
-        ),
-        Err(_) => (
-            Status::InternalServerError,
-            Json(json!({ "error": "DB_ERROR", "message": "Internal server error" })),
-        ),
+        Ok(1) => {
+            let _ = conn.execute(
+                "DELETE FROM webhook_deliveries WHERE webhook_id = ?1",
+                rusqlite::params![webhook_id],
+            );
+            (Status::Ok, Json(json!({ "message": "Webhook deleted" })))
+        }
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "Webhook not found").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+/// Outcomes recorded in the delivery log.
+const DELIVERY_OUTCOMES: &[&str] = &["success", "http_error", "timeout", "network_error"];
+
+/// Recent delivery attempts for a webhook, newest first, with a count per
+/// outcome. Admin only.
+#[get("/webhooks/<webhook_id>/deliveries?<outcome>&<limit>")]
+pub fn list_webhook_deliveries(
+    key: AuthenticatedKey,
+    webhook_id: &str,
+    outcome: Option<&str>,
+    limit: Option<i64>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    if !key.is_admin {
+        return ApiError::from(ErrorCode::AdminRequired).into();
+    }
+    if let Some(outcome) = outcome.filter(|o| !DELIVERY_OUTCOMES.contains(o)) {
+        return ApiError::new(
+            ErrorCode::BadRequest,
+            format!("Unknown outcome '{}'. Valid: {}", outcome, DELIVERY_OUTCOMES.join(", ")),
+        )
+        .into();
+    }
+    let limit = limit.unwrap_or(50).clamp(1, webhooks::DELIVERY_LOG_LIMIT);
+
+    let conn = db.conn();
+    let exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM webhooks WHERE id = ?1",
+            rusqlite::params![webhook_id],
+            |r| r.get(0),
+        )
+        .unwrap_or(false);
+    if !exists {
+        return ApiError::new(ErrorCode::NotFound, "Webhook not found").into();
+    }
+
+    let mut counts = serde_json::Map::new();
+    for name in DELIVERY_OUTCOMES {
+        counts.insert(name.to_string(), json!(0));
+    }
+    if let Ok(mut stmt) =
+        conn.prepare("SELECT outcome, COUNT(*) FROM webhook_deliveries WHERE webhook_id = ?1 GROUP BY outcome")
+    {
+        let rows = stmt.query_map(rusqlite::params![webhook_id], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?)));
+        for (name, n) in rows.into_iter().flatten().flatten() {
+            counts.insert(name, json!(n));
+        }
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT event, outcome, status_code, error_message, duration_ms, timeout_ms, delivered_at
+             FROM webhook_deliveries
+             WHERE webhook_id = ?1 AND (?2 IS NULL OR outcome = ?2)
+             ORDER BY id DESC LIMIT ?3",
+        )
+        .unwrap();
+    let deliveries: Vec<Value> = stmt
+        .query_map(rusqlite::params![webhook_id, outcome, limit], |r| {
+            Ok(json!({
+                "event": r.get::<_, String>(0)?,
+                "outcome": r.get::<_, String>(1)?,
+                "status_code": r.get::<_, Option<i64>>(2)?,
+                "error_message": r.get::<_, Option<String>>(3)?,
+                "duration_ms": r.get::<_, i64>(4)?,
+                "timeout_ms": r.get::<_, i64>(5)?,
+                "delivered_at": r.get::<_, String>(6)?,
+            }))
+        })
+        .unwrap()
+        .filter_map(|r| r.ok())
+        .collect();
+
+    (
+        Status::Ok,
+        Json(json!({
+            "webhook_id": webhook_id,
+            "counts": counts,
+            "deliveries": deliveries,
+        })),
+    )
+}
+
+// === Per-app owner notifications ===
+
+#[derive(Debug, serde::Deserialize)]
+pub struct NotifyWebhookRequest {
+    pub url: String,
+    /// Issue a new signing secret for an already registered URL.
+    #[serde(default)]
+    pub rotate_secret: bool,
+}
+
+/// The app's notification URL and delivery state; `None` when none is set.
+fn notify_webhook_json(conn: &rusqlite::Connection, app_id: &str) -> Option<Value> {
+    conn.query_row(
+        "SELECT notify_url, notify_failure_count, notify_last_outcome, notify_last_delivered_at
+         FROM apps WHERE id = ?1 AND notify_url IS NOT NULL",
+        rusqlite::params![app_id],
+        |r| {
+            let failure_count: i64 = r.get(1)?;
+            Ok(json!({
+                "app_id": app_id,
+                "url": r.get::<_, String>(0)?,
+                "events": webhooks::OWNER_EVENTS,
+                "active": failure_count < webhooks::OWNER_MAX_FAILURES,
+                "failure_count": failure_count,
+                "last_outcome": r.get::<_, Option<String>>(2)?,
+                "last_delivered_at": r.get::<_, Option<String>>(3)?,
+            }))
+        },
+    )
+    .ok()
+}
+
+/// Register (or replace) the URL notified of `review.submitted`,
+/// `app.health_changed` and `app.deprecated` for one app. Anyone who can
+/// edit the app may set it; no admin rights needed. The signing secret is
+/// returned when first issued or rotated.
+#[put("/apps/<id>/notify-webhook", format = "json", data = "<body>")]
+pub fn put_notify_webhook(
+    actor: Actor,
+    id: &str,
+    body: Json<NotifyWebhookRequest>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    if let Err(err) = actor.can_edit(&conn, id) {
+        return err.into();
+    }
+
+    let url = body.url.trim();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return ApiError::new(ErrorCode::InvalidUrl, "URL must start with http:// or https://").into();
+    }
+
+    let has_secret: bool = conn
+        .query_row(
+            "SELECT notify_secret IS NOT NULL FROM apps WHERE id = ?1",
+            rusqlite::params![id],
+            |r| r.get(0),
+        )
+        .unwrap_or(false);
+    let new_secret = (!has_secret || body.rotate_secret).then(webhooks::generate_secret);
+
+    // Re-registering also re-arms a URL that was switched off after failures
+    let result = conn.execute(
+        "UPDATE apps SET notify_url = ?1, notify_secret = COALESCE(?2, notify_secret), notify_failure_count = 0
+         WHERE id = ?3",
+        rusqlite::params![url, new_secret, id],
+    );
+    match result {
+        Ok(1) => {
+            let mut webhook = notify_webhook_json(&conn, id).unwrap_or_default();
+            if let Some(secret) = new_secret {
+                webhook["secret"] = json!(secret);
+            }
+            (Status::Ok, Json(webhook))
+        }
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "App not found").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
+    }
+}
+
+/// The app's owner notification URL and last delivery outcome.
+#[get("/apps/<id>/notify-webhook")]
+pub fn get_notify_webhook(actor: Actor, id: &str, db: &rocket::State<DbState>) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    if let Err(err) = actor.can_edit(&conn, id) {
+        return err.into();
+    }
+    match notify_webhook_json(&conn, id) {
+        Some(webhook) => (Status::Ok, Json(webhook)),
+        None => ApiError::new(ErrorCode::NotFound, "No notification URL registered").into(),
+    }
+}
+
+/// Stop owner notifications for an app and forget its secret.
+#[delete("/apps/<id>/notify-webhook")]
+pub fn delete_notify_webhook(actor: Actor, id: &str, db: &rocket::State<DbState>) -> (Status, Json<Value>) {
+    let conn = db.conn();
+    if let Err(err) = actor.can_edit(&conn, id) {
+        return err.into();
+    }
+    match conn.execute(
+        "UPDATE apps SET notify_url = NULL, notify_secret = NULL, notify_failure_count = 0,
+            notify_last_outcome = NULL, notify_last_delivered_at = NULL
+         WHERE id = ?1 AND notify_url IS NOT NULL",
+        rusqlite::params![id],
+    ) {
+        Ok(1) => (Status::Ok, Json(json!({ "message": "Notification URL removed" }))),
+        Ok(_) => ApiError::new(ErrorCode::NotFound, "No notification URL registered").into(),
+        Err(_) => ApiError::new(ErrorCode::DbError, "Internal server error").into(),
     }
 }
@@ -1,10 +1,17 @@
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 
 use rocket::fairing::{Fairing, Info, Kind};
+use rocket::futures::stream::{self, StreamExt};
 use rocket::{Orbit, Rocket};
+use serde::Serialize;
+use serde_json::{json, Value};
 
+use crate::errors::{ApiError, ErrorCode};
 use crate::events::{AppEvent, EventBus};
+use crate::health::HealthExpectation;
+use crate::key_usage::{self, AnomalyConfig, KeyUsage};
+use crate::revisions;
 
 /// Shared database connection for the scheduler (separate from main).
 pub type SchedulerDb = Arc<Mutex<rusqlite::Connection>>;
@@ -12,24 +19,167 @@ pub type SchedulerDb = Arc<Mutex<rusqlite::Connection>>;
 /// Default health check interval: 5 minutes.
 const DEFAULT_INTERVAL_SECS: u64 = 300;
 
-/// HTTP timeout for health check requests.
-const CHECK_TIMEOUT_SECS: u64 = 10;
+/// Default number of probes in flight at once.
+const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Default HTTP timeout for each health check request.
+const DEFAULT_PROBE_TIMEOUT_SECS: u64 = 10;
 
 /// Maximum redirects to follow.
 const MAX_REDIRECTS: usize = 5;
 
+/// Default lead time for `key.expiring` warnings: 7 days.
+const DEFAULT_KEY_WARNING_DAYS: i64 = 7;
+
+/// How often a disabled scheduler looks again, in case a reload enabled it.
+const DISABLED_RECHECK_SECS: u64 = 60;
+
+/// Default seconds between featured-window checks.
+pub const DEFAULT_FEATURED_ROTATION_SECS: u64 = 60;
+
+/// Scheduler settings, read at startup and on a config reload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleConfig {
+    /// Seconds between batches; 0 disables scheduled checks.
+    pub interval_secs: u64,
+    /// Probes run at once.
+    pub concurrency: usize,
+    /// Timeout for each probe.
+    pub probe_timeout_secs: u64,
+    /// Wall-clock budget for a whole batch; apps not checked in time are
+    /// skipped until the next run. 0 means no deadline.
+    pub batch_deadline_secs: u64,
+    pub key_warning_days: i64,
+    /// Per-key request spike detection, run with each batch.
+    pub anomaly: AnomalyConfig,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        ScheduleConfig {
+            interval_secs: DEFAULT_INTERVAL_SECS,
+            concurrency: DEFAULT_CONCURRENCY,
+            probe_timeout_secs: DEFAULT_PROBE_TIMEOUT_SECS,
+            batch_deadline_secs: DEFAULT_INTERVAL_SECS,
+            key_warning_days: DEFAULT_KEY_WARNING_DAYS,
+            anomaly: AnomalyConfig::default(),
+        }
+    }
+}
+
+impl ScheduleConfig {
+    /// Defaults, overridden by `HEALTH_CHECK_INTERVAL_SECS`, `HEALTH_CHECK_CONCURRENCY`,
+    /// `HEALTH_CHECK_TIMEOUT_SECS`, `HEALTH_CHECK_BATCH_DEADLINE_SECS` (defaults to
+    /// the interval), `KEY_EXPIRY_WARNING_DAYS` and the `KEY_ANOMALY_*` settings.
+    pub fn from_env() -> Self {
+        fn env<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+        let interval_secs = env("HEALTH_CHECK_INTERVAL_SECS").unwrap_or(DEFAULT_INTERVAL_SECS);
+        ScheduleConfig {
+            interval_secs,
+            concurrency: env("HEALTH_CHECK_CONCURRENCY").filter(|n| *n > 0).unwrap_or(DEFAULT_CONCURRENCY),
+            probe_timeout_secs: env("HEALTH_CHECK_TIMEOUT_SECS")
+                .filter(|n| *n > 0)
+                .unwrap_or(DEFAULT_PROBE_TIMEOUT_SECS),
+            batch_deadline_secs: env("HEALTH_CHECK_BATCH_DEADLINE_SECS").unwrap_or(interval_secs),
+            key_warning_days: env("KEY_EXPIRY_WARNING_DAYS").unwrap_or(DEFAULT_KEY_WARNING_DAYS),
+            anomaly: AnomalyConfig::from_env(),
+        }
+    }
+}
+
+/// Outcome of one scheduled batch.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchStats {
+    pub started_at: String,
+    pub duration_ms: u64,
+    /// Apps due for a check.
+    pub total: usize,
+    pub checked: usize,
+    /// Apps left unchecked when the batch deadline passed.
+    pub skipped: usize,
+    pub healthy: usize,
+    /// 2xx responses whose body failed the app's health expectation.
+    pub degraded: usize,
+    pub unhealthy: usize,
+    pub unreachable: usize,
+    pub deadline_hit: bool,
+}
+
+#[derive(Debug, Default)]
+struct RunHistory {
+    runs: u64,
+    total_duration_ms: u64,
+    max_duration_ms: u64,
+    last: Option<BatchStats>,
+}
+
+/// Scheduler config and batch timings, shared between the background task
+/// and `GET /health-check/schedule`. Cheaply cloneable.
+#[derive(Clone)]
+pub struct ScheduleStatus {
+    config: Arc<RwLock<ScheduleConfig>>,
+    /// Wakes the background task when the config changes.
+    changed: Arc<tokio::sync::Notify>,
+    history: Arc<Mutex<RunHistory>>,
+}
+
+impl ScheduleStatus {
+    pub fn new(config: ScheduleConfig) -> Self {
+        ScheduleStatus {
+            config: Arc::new(RwLock::new(config)),
+            changed: Arc::new(tokio::sync::Notify::new()),
+            history: Arc::new(Mutex::new(RunHistory::default())),
+        }
+    }
+
+    pub fn config(&self) -> ScheduleConfig {
+        self.config.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the settings; the background task restarts its wait with
+    /// the new interval.
+    pub fn set_config(&self, config: ScheduleConfig) {
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) = config;
+        self.changed.notify_waiters();
+    }
+
+    pub fn record(&self, stats: BatchStats) {
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        history.runs += 1;
+        history.total_duration_ms += stats.duration_ms;
+        history.max_duration_ms = history.max_duration_ms.max(stats.duration_ms);
+        history.last = Some(stats);
+    }
+
+    /// Run counts and durations since startup.
+    pub fn stats(&self) -> Value {
+        let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        json!({
+            "runs": history.runs,
+            "avg_duration_ms": history.total_duration_ms.checked_div(history.runs),
+            "max_duration_ms": (history.runs > 0).then_some(history.max_duration_ms),
+            "last_run": history.last,
+        })
+    }
+}
+
 /// Open a separate database connection for the scheduler.
-pub fn init_scheduler_db() -> SchedulerDb {
-    let db_path = std::env::var("DATABASE_PATH").unwrap_or_else(|_| "app_directory.db".to_string());
-    let conn = rusqlite::Connection::open(&db_path).expect("Failed to open scheduler DB");
+pub fn init_scheduler_db(db_path: &str) -> SchedulerDb {
+    let conn = rusqlite::Connection::open(db_path).expect("Failed to open scheduler DB");
     conn.execute_batch("PRAGMA journal_mode=WAL;")
         .expect("Failed to set WAL mode for scheduler DB");
+    let _ = conn.busy_timeout(Duration::from_secs(2));
     Arc::new(Mutex::new(conn))
 }
 
 /// Rocket fairing that spawns a background task to periodically
-/// check the health of all approved apps.
-pub struct ScheduledHealthChecks;
+/// check the health of all approved apps. Settings come from the
+/// managed `ScheduleStatus`.
+pub struct ScheduledHealthChecks {
+    pub db_path: String,
+}
 
 #[rocket::async_trait]
 impl Fairing for ScheduledHealthChecks {
@@ -41,15 +191,26 @@ impl Fairing for ScheduledHealthChecks {
     }
 
     async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
-        let interval_secs: u64 = std::env::var("HEALTH_CHECK_INTERVAL_SECS")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(DEFAULT_INTERVAL_SECS);
+        let status = rocket
+            .state::<ScheduleStatus>()
+            .expect("ScheduleStatus not managed")
+            .clone();
+        // A replica takes health results from its primary
+        if rocket.state::<crate::replica::Replica>().is_some_and(|r| r.is_enabled()) {
+            rocket::info!("Scheduled health checks disabled on a read-only replica");
+            return;
+        }
+        let config = status.config();
 
         // 0 disables scheduled checks
-        if interval_secs == 0 {
+        if config.interval_secs == 0 {
             rocket::info!("Scheduled health checks disabled (HEALTH_CHECK_INTERVAL_SECS=0)");
-            return;
+        } else {
+            rocket::info!(
+                "Scheduled health checks enabled: every {} seconds, {} at a time",
+                config.interval_secs,
+                config.concurrency
+            );
         }
 
         // Clone the EventBus (cheap — internally Arc-wrapped)
@@ -58,48 +219,271 @@ impl Fairing for ScheduledHealthChecks {
             .expect("EventBus not managed")
             .clone();
 
+        let usage = rocket.state::<KeyUsage>().cloned();
+
         // Create a separate DB connection for the scheduler
-        let scheduler_db = init_scheduler_db();
+        let scheduler_db = init_scheduler_db(&self.db_path);
 
         // Clone the shutdown handle to stop gracefully
         let shutdown = rocket.shutdown();
 
-        rocket::info!(
-            "Scheduled health checks enabled: every {} seconds",
-            interval_secs
-        );
-
         tokio::spawn(async move {
-            let interval = Duration::from_secs(interval_secs);
-
-            // Wait one full interval before the first check
-            tokio::time::sleep(interval).await;
-
             loop {
-                run_scheduled_checks(&scheduler_db, &bus).await;
+                // Settings are read each round so a config reload takes effect
+                // without a restart; a reload also cuts the current wait short.
+                let interval = match status.config().interval_secs {
+                    0 => Duration::from_secs(DISABLED_RECHECK_SECS),
+                    secs => Duration::from_secs(secs),
+                };
 
                 // Use tokio::select to handle graceful shutdown
                 tokio::select! {
                     _ = tokio::time::sleep(interval) => {},
+                    _ = status.changed.notified() => continue,
                     _ = shutdown.clone() => {
                         rocket::info!("Scheduled health checks stopping (server shutdown)");
                         break;
                     }
                 }
+
+                let config = status.config();
+                if config.interval_secs == 0 {
+                    continue;
+                }
+                if let Ok(conn) = scheduler_db.lock() {
+                    warn_expiring_keys(&conn, &bus, config.key_warning_days);
+                    if let Err(e) = crate::stats::record_snapshot(&conn) {
+                        rocket::error!("Directory snapshot failed: {}", e);
+                    }
+                    if let Some(ref usage) = usage {
+                        usage.flush(&conn);
+                        key_usage::detect_anomalies(&conn, &bus, &config.anomaly);
+                    }
+                }
+                let stats = run_scheduled_checks(&scheduler_db, &bus, &config).await;
+                status.record(stats);
+            }
+        });
+    }
+}
+
+/// Rocket fairing that starts and ends scheduled featured windows every
+/// `interval_secs` (0 disables it; windows then only apply on edit).
+pub struct FeaturedRotation {
+    pub db_path: String,
+    pub interval_secs: u64,
+}
+
+#[rocket::async_trait]
+impl Fairing for FeaturedRotation {
+    fn info(&self) -> Info {
+        Info {
+            name: "Featured Rotation",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        if self.interval_secs == 0 {
+            rocket::info!("Featured rotation disabled (FEATURED_ROTATION_SECS=0)");
+            return;
+        }
+        let bus = rocket
+            .state::<EventBus>()
+            .expect("EventBus not managed")
+            .clone();
+        let db = init_scheduler_db(&self.db_path);
+        let interval = Duration::from_secs(self.interval_secs);
+        let shutdown = rocket.shutdown();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = shutdown.clone() => break,
+                }
+                if let Ok(conn) = db.lock() {
+                    apply_featured_schedule(&conn, &bus);
+                }
+            }
+        });
+    }
+}
+
+/// Feature approved apps whose `featured_from` has passed and unfeature
+/// apps whose `featured_until` has passed, emitting `app.featured` and
+/// `app.unfeatured`. Each boundary is cleared once applied, so a manual
+/// `is_featured` change afterwards sticks. Returns (featured, unfeatured).
+pub fn apply_featured_schedule(conn: &rusqlite::Connection, bus: &EventBus) -> (usize, usize) {
+    let due = |sql: &str| -> Vec<(String, String, String, Option<String>)> {
+        let mut stmt = match conn.prepare(sql) {
+            Ok(s) => s,
+            Err(e) => {
+                rocket::error!("Featured rotation: query error: {}", e);
+                return Vec::new();
+            }
+        };
+        stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))
+            .map(|rows| rows.filter_map(|r| r.ok()).collect())
+            .unwrap_or_default()
+    };
+
+    // Windows that already ended are skipped and cleared below
+    let starting = due(
+        "SELECT id, name, slug, featured_until FROM apps
+         WHERE status = 'approved' AND featured_from IS NOT NULL AND featured_from <= datetime('now')
+           AND (featured_until IS NULL OR featured_until > datetime('now'))",
+    );
+    for (id, name, slug, until) in &starting {
+        let _ = conn.execute(
+            "UPDATE apps SET featured_from = NULL, is_featured = 1, revision = revision + 1, updated_at = datetime('now')
+             WHERE id = ?1",
+            rusqlite::params![id],
+        );
+        let _ = revisions::record(conn, id, "system", None);
+        bus.emit(AppEvent {
+            event: "app.featured".to_string(),
+            data: json!({ "app_id": id, "name": name, "slug": slug, "featured_until": until, "scheduled": true }),
+        });
+    }
+
+    let ending = due(
+        "SELECT id, name, slug, NULL FROM apps
+         WHERE featured_until IS NOT NULL AND featured_until <= datetime('now')",
+    );
+    let mut unfeatured = 0;
+    for (id, name, slug, _) in &ending {
+        let was_featured = conn
+            .execute(
+                "UPDATE apps SET is_featured = 0, revision = revision + 1, updated_at = datetime('now')
+                 WHERE id = ?1 AND is_featured = 1",
+                rusqlite::params![id],
+            )
+            .unwrap_or(0)
+            > 0;
+        let _ = conn.execute(
+            "UPDATE apps SET featured_from = NULL, featured_until = NULL WHERE id = ?1",
+            rusqlite::params![id],
+        );
+        if was_featured {
+            let _ = revisions::record(conn, id, "system", None);
+            unfeatured += 1;
+            bus.emit(AppEvent {
+                event: "app.unfeatured".to_string(),
+                data: json!({ "app_id": id, "name": name, "slug": slug, "scheduled": true }),
+            });
+        }
+    }
+
+    (starting.len(), unfeatured)
+}
+
+/// Emit `key.expiring` once for each active key that expires within `within_days`.
+/// Returns the number of warnings sent.
+pub fn warn_expiring_keys(conn: &rusqlite::Connection, bus: &EventBus, within_days: i64) -> usize {
+    let keys: Vec<(String, String, String)> = {
+        let mut stmt = match conn.prepare(
+            "SELECT id, name, expires_at FROM api_keys
+             WHERE revoked = 0
+               AND expires_at IS NOT NULL
+               AND expiry_warned_at IS NULL
+               AND expires_at > datetime('now')
+               AND expires_at <= datetime('now', ?1)",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                rocket::error!("Key expiry check: query error: {}", e);
+                return 0;
             }
+        };
+        let window = format!("+{} days", within_days);
+        let result: Vec<(String, String, String)> = match stmt.query_map(rusqlite::params![window], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        }) {
+            Ok(rows) => rows.filter_map(|r| r.ok()).collect(),
+            Err(_) => return 0,
+        };
+        result
+    };
+
+    for (key_id, name, expires_at) in &keys {
+        let _ = conn.execute(
+            "UPDATE api_keys SET expiry_warned_at = datetime('now') WHERE id = ?1",
+            rusqlite::params![key_id],
+        );
+        bus.emit(AppEvent {
+            event: "key.expiring".to_string(),
+            data: serde_json::json!({
+                "key_id": key_id,
+                "name": name,
+                "expires_at": expires_at,
+            }),
         });
     }
+
+    keys.len()
+}
+
+/// Result of one probe: status, HTTP code, error message and response time.
+pub type ProbeOutcome = (&'static str, Option<i64>, Option<String>, i64);
+
+/// GET `url` once. A 2xx response is `healthy`, or `degraded` when its body
+/// fails `expect`; other statuses are `unhealthy` and transport errors
+/// `unreachable`. The response time covers the headers only.
+pub async fn probe(
+    client: &reqwest::Client,
+    url: &str,
+    timeout_secs: u64,
+    expect: Option<&HealthExpectation>,
+) -> ProbeOutcome {
+    let start = std::time::Instant::now();
+    let result = client.get(url).send().await;
+    let response_time_ms = start.elapsed().as_millis() as i64;
+
+    match result {
+        Ok(resp) => {
+            let code = resp.status().as_u16() as i64;
+            if resp.status().is_success() {
+                let mismatch = match expect {
+                    Some(expect) => expect.check_response(resp).await,
+                    None => None,
+                };
+                match mismatch {
+                    Some(reason) => ("degraded", Some(code), Some(reason), response_time_ms),
+                    None => ("healthy", Some(code), None, response_time_ms),
+                }
+            } else {
+                ("unhealthy", Some(code), Some(format!("HTTP {}", resp.status())), response_time_ms)
+            }
+        }
+        Err(e) => {
+            let msg = if e.is_timeout() {
+                format!("Connection timed out ({}s)", timeout_secs)
+            } else if e.is_connect() {
+                "Connection refused or DNS failure".to_string()
+            } else {
+                format!("{}", e)
+            };
+            ("unreachable", None, Some(msg), response_time_ms)
+        }
+    }
 }
 
-/// Run health checks on all approved apps that have a URL.
-async fn run_scheduled_checks(db: &SchedulerDb, bus: &EventBus) {
+/// Run health checks on all approved apps that have a URL, up to
+/// `config.concurrency` at a time, stopping at the batch deadline.
+pub async fn run_scheduled_checks(db: &SchedulerDb, bus: &EventBus, config: &ScheduleConfig) -> BatchStats {
+    let stats = BatchStats {
+        started_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        ..Default::default()
+    };
+
     // Collect apps to check
-    let apps: Vec<(String, String, String)> = {
+    let apps: Vec<CheckTarget> = {
         let conn = match db.lock() {
             Ok(c) => c,
             Err(_) => {
                 rocket::error!("Scheduled health check: failed to acquire DB lock");
-                return;
+                return stats;
             }
         };
 
@@ -108,7 +492,8 @@ async fn run_scheduled_checks(db: &SchedulerDb, bus: &EventBus) {
                     CASE
                       WHEN api_url IS NOT NULL THEN RTRIM(api_url, '/') || '/health'
                       ELSE homepage_url
-                    END as check_url
+                    END as check_url,
+                    health_expectation
              FROM apps
              WHERE status = 'approved'
                AND (api_url IS NOT NULL OR homepage_url IS NOT NULL)",
@@ -116,79 +501,151 @@ async fn run_scheduled_checks(db: &SchedulerDb, bus: &EventBus) {
             Ok(s) => s,
             Err(e) => {
                 rocket::error!("Scheduled health check: query error: {}", e);
-                return;
+                return stats;
             }
         };
 
-        let result: Vec<(String, String, String)> = match stmt.query_map([], |row| {
+        let result: Vec<CheckTarget> = match stmt.query_map([], |row| {
             Ok((
                 row.get::<_, String>(0)?,
                 row.get::<_, String>(1)?,
                 row.get::<_, String>(2)?,
+                HealthExpectation::from_column(row.get(3)?),
             ))
         }) {
             Ok(rows) => rows.filter_map(|r| r.ok()).collect(),
             Err(e) => {
                 rocket::error!("Scheduled health check: row mapping error: {}", e);
-                return;
+                return stats;
             }
         };
         result
     };
 
+    let (stats, results) = check_apps(db, bus, config, apps, true).await;
+    bus.emit(batch_completed_event(&stats, &results));
+
+    rocket::info!(
+        "Scheduled health check complete in {}ms: {}/{} healthy, {} degraded, {} unhealthy, {} unreachable, {} skipped",
+        stats.duration_ms,
+        stats.healthy,
+        stats.total,
+        stats.degraded,
+        stats.unhealthy,
+        stats.unreachable,
+        stats.skipped
+    );
+    stats
+}
+
+/// `health.batch_completed` digest of a scheduled batch: its totals, plus
+/// the apps that went down (`newly_broken`) and came back (`recovered`)
+/// in this run.
+pub fn batch_completed_event(stats: &BatchStats, results: &[Value]) -> AppEvent {
+    let with_transition = |pick: fn(&str) -> bool| -> Vec<Value> {
+        results
+            .iter()
+            .filter(|r| r["transition"].as_str().is_some_and(pick))
+            .map(|r| {
+                json!({
+                    "app_id": r["app_id"],
+                    "app_name": r["app_name"],
+                    "status": r["status"],
+                    "status_code": r["status_code"],
+                    "error_message": r["error_message"],
+                })
+            })
+            .collect()
+    };
+    let mut data = serde_json::to_value(stats).unwrap_or_else(|_| json!({}));
+    data["newly_broken"] = json!(with_transition(|t| t != "recovered"));
+    data["recovered"] = json!(with_transition(|t| t == "recovered"));
+    AppEvent {
+        event: "health.batch_completed".to_string(),
+        data,
+    }
+}
+
+/// An app to probe: id, name, the URL to check, and the owner's success criteria.
+pub type CheckTarget = (String, String, String, Option<HealthExpectation>);
+
+/// Probe `apps` up to `config.concurrency` at a time, stopping at the batch
+/// deadline. Each result is recorded with the app's new uptime, and emits
+/// `health.checked` (tagged with `scheduled`) plus `app.health_changed`.
+/// Returns the batch stats and one result per app checked.
+pub async fn check_apps(
+    db: &Mutex<rusqlite::Connection>,
+    bus: &EventBus,
+    config: &ScheduleConfig,
+    apps: Vec<CheckTarget>,
+    scheduled: bool,
+) -> (BatchStats, Vec<Value>) {
+    let started = std::time::Instant::now();
+    let mut stats = BatchStats {
+        started_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        total: apps.len(),
+        ..Default::default()
+    };
+    let mut results = Vec::new();
     if apps.is_empty() {
-        return;
+        return (stats, results);
     }
 
     let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(CHECK_TIMEOUT_SECS))
+        .timeout(Duration::from_secs(config.probe_timeout_secs))
         .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
         .build()
         .unwrap_or_default();
 
-    let total = apps.len();
-    let mut healthy = 0usize;
-    let mut unhealthy = 0usize;
-    let mut unreachable_count = 0usize;
-
-    for (app_id, app_name, check_url) in &apps {
-        let start = std::time::Instant::now();
-        let result = client.get(check_url.as_str()).send().await;
-        let response_time_ms = start.elapsed().as_millis() as i64;
-
-        let (health_status, status_code, error_message) = match result {
-            Ok(resp) => {
-                let code = resp.status().as_u16() as i64;
-                if resp.status().is_success() {
-                    ("healthy".to_string(), Some(code), None)
-                } else {
-                    (
-                        "unhealthy".to_string(),
-                        Some(code),
-                        Some(format!("HTTP {}", resp.status())),
-                    )
-                }
-            }
-            Err(e) => {
-                let msg = if e.is_timeout() {
-                    "Connection timed out (10s)".to_string()
-                } else if e.is_connect() {
-                    "Connection refused or DNS failure".to_string()
-                } else {
-                    format!("{}", e)
-                };
-                ("unreachable".to_string(), None, Some(msg))
+    let deadline = (config.batch_deadline_secs > 0)
+        .then(|| tokio::time::Instant::now() + Duration::from_secs(config.batch_deadline_secs));
+    let timeout_secs = config.probe_timeout_secs;
+    let mut probes = stream::iter(apps)
+        .map(|(app_id, app_name, check_url, expect)| {
+            let client = client.clone();
+            async move {
+                let outcome = probe(&client, &check_url, timeout_secs, expect.as_ref()).await;
+                (app_id, app_name, check_url, outcome)
             }
+        })
+        .buffer_unordered(config.concurrency.max(1));
+
+    loop {
+        let next = match deadline {
+            Some(at) => match tokio::time::timeout_at(at, probes.next()).await {
+                Ok(next) => next,
+                Err(_) => {
+                    stats.deadline_hit = true;
+                    break;
+                }
+            },
+            None => probes.next().await,
+        };
+        let Some((app_id, app_name, check_url, (health_status, status_code, error_message, response_time_ms))) = next
+        else {
+            break;
         };
 
-        match health_status.as_str() {
-            "healthy" => healthy += 1,
-            "unhealthy" => unhealthy += 1,
-            _ => unreachable_count += 1,
+        stats.checked += 1;
+        match health_status {
+            "healthy" => stats.healthy += 1,
+            "degraded" => stats.degraded += 1,
+            "unhealthy" => stats.unhealthy += 1,
+            _ => stats.unreachable += 1,
         }
+        results.push(json!({
+            "app_id": app_id,
+            "app_name": app_name,
+            "status": health_status,
+            "status_code": status_code,
+            "response_time_ms": response_time_ms,
+            "error_message": error_message,
+            "transition": null,
+        }));
 
         // Record result in database
         let check_id = uuid::Uuid::new_v4().to_string();
+        let previous_status;
         {
             let conn = match db.lock() {
                 Ok(c) => c,
@@ -209,10 +666,7 @@ async fn run_scheduled_checks(db: &SchedulerDb, bus: &EventBus) {
                 ],
             );
 
-            let _ = conn.execute(
-                "UPDATE apps SET last_health_status = ?1, last_checked_at = datetime('now'), updated_at = datetime('now') WHERE id = ?2",
-                rusqlite::params![health_status, app_id],
-            );
+            previous_status = crate::health::update_last_status(&conn, &app_id, health_status);
 
             // Recalculate uptime from last 100 checks
             let uptime: Option<f64> = conn
@@ -232,7 +686,7 @@ async fn run_scheduled_checks(db: &SchedulerDb, bus: &EventBus) {
             }
         }
 
-        // Emit event (includes `scheduled: true` to distinguish from manual checks)
+        // `scheduled` tells scheduler runs apart from admin-triggered checks
         bus.emit(AppEvent {
             event: "health.checked".to_string(),
             data: serde_json::json!({
@@ -241,43 +695,49 @@ async fn run_scheduled_checks(db: &SchedulerDb, bus: &EventBus) {
                 "status": health_status,
                 "status_code": status_code,
                 "response_time_ms": response_time_ms,
-                "scheduled": true,
+                "scheduled": scheduled,
             }),
         });
+        let change = crate::health::health_changed_event(
+            previous_status.as_deref(),
+            health_status,
+            serde_json::json!({
+                "app_id": app_id,
+                "app_name": app_name,
+                "status_code": status_code,
+                "response_time_ms": response_time_ms,
+                "error_message": error_message,
+                "scheduled": scheduled,
+            }),
+        );
+        if let Some(event) = change {
+            if let Some(result) = results.last_mut() {
+                result["transition"] = event.data["transition"].clone();
+            }
+            bus.emit(event);
+        }
     }
 
-    rocket::info!(
-        "Scheduled health check complete: {}/{} healthy, {} unhealthy, {} unreachable",
-        healthy,
-        total,
-        unhealthy,
-        unreachable_count
-    );
+    stats.skipped = stats.total - stats.checked;
+    stats.duration_ms = started.elapsed().as_millis() as u64;
+    (stats, results)
 }
 
 /// API endpoint to view scheduler configuration and status.
 #[get("/health-check/schedule")]
 pub fn get_schedule(
     key: crate::auth::AuthenticatedKey,
+    status: &rocket::State<ScheduleStatus>,
 ) -> (
     rocket::http::Status,
     rocket::serde::json::Json<serde_json::Value>,
 ) {
     if !key.is_admin {
-        return (
-            rocket::http::Status::Forbidden,
-            rocket::serde::json::Json(serde_json::json!({
-                "error": "ADMIN_REQUIRED",
-                "message": "Only admins can view scheduler status"
-            })),
-        );
+        return ApiError::new(ErrorCode::AdminRequired, "Only admins can view scheduler status").into();
     }
 
-    let interval_secs: u64 = std::env::var("HEALTH_CHECK_INTERVAL_SECS")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(DEFAULT_INTERVAL_SECS);
-
+    let config = status.config();
+    let interval_secs = config.interval_secs;
     let enabled = interval_secs > 0;
 
     (
@@ -292,6 +752,10 @@ pub fn get_schedule(
             },
             "config_var": "HEALTH_CHECK_INTERVAL_SECS",
             "default_interval": DEFAULT_INTERVAL_SECS,
+            "concurrency": config.concurrency,
+            "probe_timeout_seconds": config.probe_timeout_secs,
+            "batch_deadline_seconds": config.batch_deadline_secs,
+            "stats": status.stats(),
         })),
     )
 }
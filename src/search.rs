@@ -0,0 +1,65 @@
+/// Split a search query into lowercase terms. Double-quoted runs are kept
+/// together as phrases; duplicates are dropped, order is preserved.
+pub fn parse_terms(q: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    let mut push = |raw: &str| {
+        let term = raw.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        if !term.is_empty() && !terms.contains(&term) {
+            terms.push(term);
+        }
+    };
+    for (i, part) in q.split('"').enumerate() {
+        if i % 2 == 1 {
+            push(part);
+        } else {
+            part.split_whitespace().for_each(&mut push);
+        }
+    }
+    terms
+}
+
+/// SQL condition matching one term in any searchable field,
+/// bound to positional parameter `?{idx}` (a `%term%` pattern).
+pub fn term_condition(idx: usize) -> String {
+    format!(
+        "(LOWER(name) LIKE ?{p} OR LOWER(short_description) LIKE ?{p} OR LOWER(description) LIKE ?{p} OR LOWER(tags) LIKE ?{p})",
+        p = idx
+    )
+}
+
+/// Which of `terms` appear in any of `fields` (case-insensitive).
+pub fn matched_terms(terms: &[String], fields: &[&str]) -> Vec<String> {
+    let fields: Vec<String> = fields.iter().map(|f| f.to_lowercase()).collect();
+    terms
+        .iter()
+        .filter(|t| fields.iter().any(|f| f.contains(t.as_str())))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_terms_and_phrases() {
+        assert_eq!(parse_terms("Weather  forecast API"), vec!["weather", "forecast", "api"]);
+        assert_eq!(
+            parse_terms(r#"api "Weather   Forecast" api"#),
+            vec!["api", "weather forecast"]
+        );
+        // Unterminated quote still groups the tail
+        assert_eq!(parse_terms(r#"qr "code gen"#), vec!["qr", "code gen"]);
+        assert!(parse_terms(r#"  "" "#).is_empty());
+    }
+
+    #[test]
+    fn matches_terms_across_fields() {
+        let terms = parse_terms(r#"weather "real time" radar"#);
+        assert_eq!(
+            matched_terms(&terms, &["Weather Service", "Real-time data", "Real time radar"]),
+            vec!["weather", "real time", "radar"]
+        );
+        assert_eq!(matched_terms(&terms, &["Weather"]), vec!["weather"]);
+    }
+}
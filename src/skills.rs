@@ -0,0 +1,151 @@
+use std::fmt::Write;
+
+use serde_json::{json, Value};
+
+/// Where a listed app's generated skill manifest is served.
+pub fn app_skill_url(slug: &str) -> String {
+    format!("/.well-known/skills/apps/{}/SKILL.md", slug)
+}
+
+/// Skills index entries for every approved public app with an `api_spec_url`.
+pub fn app_skill_entries(conn: &rusqlite::Connection) -> Vec<Value> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT slug, short_description FROM apps
+             WHERE status = 'approved' AND visibility = 'public' AND api_spec_url IS NOT NULL AND api_spec_url != ''
+             ORDER BY slug ASC",
+        )
+        .unwrap();
+    stmt.query_map([], |row| {
+        let slug: String = row.get(0)?;
+        Ok(json!({
+            "name": format!("apps/{}", slug),
+            "description": row.get::<_, String>(1)?,
+            "url": app_skill_url(&slug),
+            "files": ["SKILL.md"],
+        }))
+    })
+    .unwrap()
+    .filter_map(|r| r.ok())
+    .collect()
+}
+
+/// Render a SKILL.md for one listed app from its listing and verified spec.
+/// Returns `None` unless the app is approved, not private, and has an `api_spec_url`.
+#[allow(clippy::type_complexity)]
+pub fn app_skill_md(conn: &rusqlite::Connection, slug: &str) -> Option<String> {
+    let (id, name, short, description, protocol, category, api_url, spec_url, homepage, key_endpoints): (
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+        Option<String>,
+        String,
+        Option<String>,
+        Option<String>,
+    ) = conn
+        .query_row(
+            "SELECT id, name, short_description, description, protocol, category, api_url, api_spec_url, homepage_url, key_endpoints
+             FROM apps
+             WHERE slug = ?1 AND status = 'approved' AND visibility != 'private'
+               AND api_spec_url IS NOT NULL AND api_spec_url != ''",
+            rusqlite::params![slug],
+            |r| {
+                Ok((
+                    r.get(0)?,
+                    r.get(1)?,
+                    r.get(2)?,
+                    r.get(3)?,
+                    r.get(4)?,
+                    r.get(5)?,
+                    r.get(6)?,
+                    r.get(7)?,
+                    r.get(8)?,
+                    r.get(9)?,
+                ))
+            },
+        )
+        .ok()?;
+
+    let spec = crate::specs::spec_details(conn, &id);
+    let strings = |key: &str| -> Vec<String> {
+        spec.as_ref()
+            .and_then(|s| s[key].as_array().cloned())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect()
+    };
+    let status = spec
+        .as_ref()
+        .and_then(|s| s["status"].as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| "pending".to_string());
+    let verified = status == "valid";
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}", name);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "> {}", short);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{}", description.trim());
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Details");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "- Protocol: {}", protocol);
+    let _ = writeln!(out, "- Category: {}", category);
+    if let Some(url) = api_url.filter(|u| !u.is_empty()) {
+        let _ = writeln!(out, "- API: {}", url);
+    }
+    if let Some(url) = homepage.filter(|u| !u.is_empty()) {
+        let _ = writeln!(out, "- Homepage: {}", url);
+    }
+    let _ = writeln!(out, "- OpenAPI spec: {} ({})", spec_url, status);
+    let _ = writeln!(out, "- Listing: /api/v1/apps/{}", slug);
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Authentication");
+    let _ = writeln!(out);
+    let auth = strings("auth");
+    if !verified {
+        let _ = writeln!(out, "Unknown until the spec is verified; check the spec directly.");
+    } else if auth.is_empty() {
+        let _ = writeln!(out, "The spec declares no security schemes.");
+    } else {
+        for scheme in auth {
+            let _ = writeln!(out, "- {}", scheme);
+        }
+    }
+    let _ = writeln!(out);
+
+    let key_endpoints: Vec<crate::models::KeyEndpoint> =
+        key_endpoints.and_then(|raw| serde_json::from_str(&raw).ok()).unwrap_or_default();
+    if !key_endpoints.is_empty() {
+        let _ = writeln!(out, "## Key endpoints");
+        let _ = writeln!(out);
+        for endpoint in key_endpoints {
+            let _ = writeln!(out, "- `{} {}`: {}", endpoint.method, endpoint.path, endpoint.description);
+        }
+        let _ = writeln!(out);
+    }
+
+    let _ = writeln!(out, "## Endpoints");
+    let _ = writeln!(out);
+    if let Some(server) = strings("servers").first() {
+        let _ = writeln!(out, "Base URL: {}", server);
+        let _ = writeln!(out);
+    }
+    let endpoints = strings("endpoints");
+    if endpoints.is_empty() {
+        let _ = writeln!(out, "See the OpenAPI spec for the endpoint list.");
+    } else {
+        let _ = writeln!(out, "```");
+        for endpoint in endpoints {
+            let _ = writeln!(out, "{}", endpoint);
+        }
+        let _ = writeln!(out, "```");
+    }
+    Some(out)
+}
@@ -0,0 +1,510 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+/// HTTP timeout for fetching an app's API spec.
+const FETCH_TIMEOUT_SECS: u64 = 10;
+
+/// Specs larger than this are rejected without parsing.
+const MAX_SPEC_BYTES: usize = 5 * 1024 * 1024;
+
+/// At most this many operations are kept for the endpoint summary.
+const MAX_SUMMARY_ENDPOINTS: usize = 50;
+
+/// At most this many operations are converted to function-calling tools.
+const MAX_TOOLS: usize = 128;
+
+/// `$ref`s nested deeper than this are left out of tool parameter schemas.
+const MAX_SCHEMA_DEPTH: usize = 8;
+
+const HTTP_METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Background verifier for submitted `api_spec_url`s.
+///
+/// Fetches the spec, checks it parses as OpenAPI 3.x, and records the result
+/// in `app_specs` plus the app's cached `spec_status`
+/// (`pending` → `valid` / `invalid` / `unreachable`).
+/// Uses its own DB connection per run, like the scheduler and webhook delivery.
+pub struct SpecVerifier {
+    db_path: String,
+    client: reqwest::Client,
+}
+
+/// Outcome of linting a spec document.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpecReport {
+    pub valid: bool,
+    pub openapi_version: Option<String>,
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub servers: Vec<String>,
+    /// `METHOD /path` (plus ` — summary` when the spec has one), capped at 50.
+    pub endpoints: Vec<String>,
+    /// Declared security schemes, e.g. `apiKey (header X-API-Key)` or `http bearer`.
+    pub auth: Vec<String>,
+    /// Function-calling tool schemas for the operations, capped at 128.
+    pub tools: Vec<Value>,
+    pub errors: Vec<String>,
+}
+
+impl SpecReport {
+    /// A report for a spec that couldn't be checked at all.
+    fn failure(message: String) -> Self {
+        SpecReport {
+            errors: vec![message],
+            ..Default::default()
+        }
+    }
+}
+
+/// Parse and lint an OpenAPI document (JSON or YAML).
+pub fn lint_spec(raw: &str) -> SpecReport {
+    let mut report = SpecReport::default();
+
+    let doc: Value = match serde_json::from_str(raw) {
+        Ok(v) => v,
+        Err(_) => match serde_yaml::from_str(raw) {
+            Ok(v) => v,
+            Err(e) => {
+                report.errors.push(format!("Not valid JSON or YAML: {}", e));
+                return report;
+            }
+        },
+    };
+
+    if !doc.is_object() {
+        report.errors.push("Spec must be a JSON/YAML object".to_string());
+        return report;
+    }
+
+    match doc.get("openapi").and_then(|v| v.as_str()) {
+        Some(v) if v.starts_with("3.") => report.openapi_version = Some(v.to_string()),
+        Some(v) => {
+            report.openapi_version = Some(v.to_string());
+            report
+                .errors
+                .push(format!("Unsupported OpenAPI version '{}' (expected 3.x)", v));
+        }
+        None => {
+            if doc.get("swagger").is_some() {
+                report
+                    .errors
+                    .push("Swagger 2.0 specs are not supported (expected OpenAPI 3.x)".to_string());
+            } else {
+                report.errors.push("Missing 'openapi' version field".to_string());
+            }
+        }
+    }
+
+    let info = doc.get("info");
+    report.title = info
+        .and_then(|i| i.get("title"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    report.version = info
+        .and_then(|i| i.get("version"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    if info.map(|i| i.is_object()) != Some(true) {
+        report.errors.push("Missing 'info' object".to_string());
+    } else {
+        if report.title.is_none() {
+            report.errors.push("Missing 'info.title'".to_string());
+        }
+        if report.version.is_none() {
+            report.errors.push("Missing 'info.version'".to_string());
+        }
+    }
+
+    if let Some(servers) = doc.get("servers").and_then(|v| v.as_array()) {
+        report.servers = servers
+            .iter()
+            .filter_map(|s| s.get("url").and_then(|u| u.as_str()))
+            .map(|s| s.to_string())
+            .collect();
+    }
+
+    if let Some(paths) = doc.get("paths").and_then(|v| v.as_object()) {
+        'paths: for (path, item) in paths {
+            for method in HTTP_METHODS {
+                let Some(op) = item.get(*method) else { continue };
+                if report.endpoints.len() >= MAX_SUMMARY_ENDPOINTS {
+                    break 'paths;
+                }
+                let line = format!("{} {}", method.to_uppercase(), path);
+                report.endpoints.push(match op.get("summary").and_then(|s| s.as_str()) {
+                    Some(summary) if !summary.trim().is_empty() => format!("{} — {}", line, summary.trim()),
+                    _ => line,
+                });
+            }
+        }
+    }
+
+    if let Some(schemes) = doc
+        .pointer("/components/securitySchemes")
+        .and_then(|v| v.as_object())
+    {
+        report.auth = schemes.values().filter_map(describe_security_scheme).collect();
+    }
+
+    // 3.1 allows webhooks/components-only documents; 3.0 requires paths
+    let has_paths = doc.get("paths").map(|p| p.is_object()).unwrap_or(false);
+    let is_31 = report
+        .openapi_version
+        .as_deref()
+        .is_some_and(|v| v.starts_with("3.1"));
+    if !has_paths && !(is_31 && (doc.get("webhooks").is_some() || doc.get("components").is_some())) {
+        report.errors.push("Missing 'paths' object".to_string());
+    }
+
+    report.valid = report.errors.is_empty();
+    if report.valid {
+        report.tools = tool_schemas(&doc);
+    }
+    report
+}
+
+/// LLM function-calling tools for the operations of an OpenAPI document.
+///
+/// Each tool has a `name` (the operationId, or method and path when there
+/// is none), a `description` and JSON Schema `parameters` with one property
+/// per path, query and header parameter plus `body` for a request body.
+/// `http` says how to turn a call back into a request. Local `$ref`s are
+/// inlined so every schema stands alone.
+pub fn tool_schemas(doc: &Value) -> Vec<Value> {
+    let mut tools: Vec<Value> = vec![];
+    let mut names = std::collections::HashSet::new();
+    let paths = doc.get("paths").and_then(|v| v.as_object());
+    'paths: for (path, item) in paths.into_iter().flatten() {
+        for method in HTTP_METHODS {
+            let Some(op) = item.get(*method) else { continue };
+            if tools.len() >= MAX_TOOLS {
+                break 'paths;
+            }
+            let base = op
+                .get("operationId")
+                .and_then(|v| v.as_str())
+                .map(tool_name)
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| tool_name(&format!("{} {}", method, path)));
+            let mut name = base.clone();
+            let mut n = 1;
+            while !names.insert(name.clone()) {
+                n += 1;
+                name = format!("{}_{}", base, n);
+            }
+            tools.push(tool(doc, name, method, path, item, op));
+        }
+    }
+    tools
+}
+
+/// Tool names are limited to `[A-Za-z0-9_-]`, 64 characters.
+fn tool_name(raw: &str) -> String {
+    let name: String = raw
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect();
+    let name = name.trim_matches('_');
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars() {
+        if !(c == '_' && out.ends_with('_')) {
+            out.push(c);
+        }
+    }
+    out.truncate(64);
+    out
+}
+
+fn tool(doc: &Value, name: String, method: &str, path: &str, item: &Value, op: &Value) -> Value {
+    let text = |k: &str| op.get(k).and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty());
+    let description = match (text("summary"), text("description")) {
+        (Some(s), Some(d)) if s != d => format!("{}. {}", s.trim_end_matches('.'), d),
+        (Some(s), _) => s.to_string(),
+        (None, Some(d)) => d.to_string(),
+        (None, None) => format!("{} {}", method.to_uppercase(), path),
+    };
+    let description: String = description.chars().take(1024).collect();
+
+    let mut properties = serde_json::Map::new();
+    let mut required: Vec<String> = vec![];
+    let mut locations = serde_json::Map::new();
+    // Operation-level parameters override path-level ones with the same name
+    let params = item
+        .get("parameters")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .chain(op.get("parameters").and_then(|v| v.as_array()).into_iter().flatten());
+    for param in params {
+        let param = crate::client_spec::resolve(doc, param);
+        let (Some(pname), Some(location)) = (
+            param.get("name").and_then(|v| v.as_str()),
+            param.get("in").and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
+        if location == "cookie" || pname == "body" {
+            continue;
+        }
+        let mut schema = param.get("schema").map(|s| inline_schema(doc, s, 0)).unwrap_or_else(|| json!({"type": "string"}));
+        if let (Some(d), Some(obj)) = (param.get("description"), schema.as_object_mut()) {
+            obj.insert("description".to_string(), d.clone());
+        }
+        properties.insert(pname.to_string(), schema);
+        locations.insert(pname.to_string(), json!(location));
+        required.retain(|r| r != pname);
+        if location == "path" || param.get("required").and_then(|v| v.as_bool()).unwrap_or(false) {
+            required.push(pname.to_string());
+        }
+    }
+
+    let body = op.get("requestBody").map(|b| crate::client_spec::resolve(doc, b));
+    let body_schema = body.and_then(|b| {
+        let content = b.get("content")?.as_object()?;
+        let media = content.get("application/json").or_else(|| content.values().next())?;
+        media.get("schema")
+    });
+    let mut content_type = Value::Null;
+    if let (Some(body), Some(schema)) = (body, body_schema) {
+        let mut schema = inline_schema(doc, schema, 0);
+        if let (Some(d), Some(obj)) = (body.get("description"), schema.as_object_mut()) {
+            obj.entry("description").or_insert_with(|| d.clone());
+        }
+        properties.insert("body".to_string(), schema);
+        locations.insert("body".to_string(), json!("body"));
+        if body.get("required").and_then(|v| v.as_bool()).unwrap_or(false) {
+            required.push("body".to_string());
+        }
+        content_type = body
+            .get("content")
+            .and_then(|c| c.as_object())
+            .and_then(|c| if c.contains_key("application/json") { Some("application/json") } else { c.keys().next().map(|k| k.as_str()) })
+            .map(|k| json!(k))
+            .unwrap_or(Value::Null);
+    }
+
+    let mut http = json!({
+        "method": method.to_uppercase(),
+        "path": path,
+        "params": locations,
+    });
+    if !content_type.is_null() {
+        http["content_type"] = content_type;
+    }
+    json!({
+        "name": name,
+        "description": description,
+        "parameters": {
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        },
+        "http": http,
+    })
+}
+
+/// A copy of `schema` with local `$ref`s replaced by their targets.
+fn inline_schema(doc: &Value, schema: &Value, depth: usize) -> Value {
+    if depth > MAX_SCHEMA_DEPTH {
+        return json!({});
+    }
+    let schema = crate::client_spec::resolve(doc, schema);
+    match schema {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(k, _)| k.as_str() != "$ref" && k.as_str() != "xml" && k.as_str() != "externalDocs")
+                .map(|(k, v)| {
+                    let v = match k.as_str() {
+                        "properties" | "patternProperties" => match v.as_object() {
+                            Some(props) => Value::Object(
+                                props.iter().map(|(n, s)| (n.clone(), inline_schema(doc, s, depth + 1))).collect(),
+                            ),
+                            None => v.clone(),
+                        },
+                        "items" | "additionalProperties" | "not" if v.is_object() => inline_schema(doc, v, depth + 1),
+                        "allOf" | "anyOf" | "oneOf" => match v.as_array() {
+                            Some(list) => Value::Array(list.iter().map(|s| inline_schema(doc, s, depth + 1)).collect()),
+                            None => v.clone(),
+                        },
+                        _ => v.clone(),
+                    };
+                    (k.clone(), v)
+                })
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Short human-readable form of an OpenAPI security scheme object.
+fn describe_security_scheme(scheme: &Value) -> Option<String> {
+    let kind = scheme.get("type")?.as_str()?;
+    let field = |k: &str| scheme.get(k).and_then(|v| v.as_str());
+    Some(match kind {
+        "apiKey" => match (field("in"), field("name")) {
+            (Some(location), Some(name)) => format!("apiKey ({} {})", location, name),
+            _ => "apiKey".to_string(),
+        },
+        "http" => match field("scheme") {
+            Some(s) => format!("http {}", s.to_lowercase()),
+            None => "http".to_string(),
+        },
+        other => other.to_string(),
+    })
+}
+
+impl SpecVerifier {
+    pub fn new(db_path: &str) -> Self {
+        SpecVerifier {
+            db_path: db_path.to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+                .redirect(reqwest::redirect::Policy::limited(5))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Mark the app's spec as pending and verify it in the background.
+    /// Must be called from within the Tokio runtime (i.e. a route handler).
+    pub fn verify_async(&self, conn: &rusqlite::Connection, app_id: &str, spec_url: &str) {
+        let _ = conn.execute(
+            "UPDATE apps SET spec_status = 'pending' WHERE id = ?1",
+            rusqlite::params![app_id],
+        );
+
+        let db_path = self.db_path.clone();
+        let client = self.client.clone();
+        let app_id = app_id.to_string();
+        let spec_url = spec_url.to_string();
+
+        tokio::spawn(async move {
+            let (status, report) = fetch_and_lint(&client, &spec_url).await;
+
+            let conn = match rusqlite::Connection::open(&db_path) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("❌ Spec verification for {app_id}: failed to open DB: {e}");
+                    return;
+                }
+            };
+            let _ = conn.busy_timeout(Duration::from_secs(5));
+            record_result(&conn, &app_id, &spec_url, status, &report);
+        });
+    }
+}
+
+/// Fetch a spec and lint it. Returns the spec status and the lint report.
+async fn fetch_and_lint(client: &reqwest::Client, url: &str) -> (&'static str, SpecReport) {
+    let unreachable = |msg: String| ("unreachable", SpecReport::failure(msg));
+
+    let resp = match client.get(url).send().await {
+        Ok(r) => r,
+        Err(e) => {
+            let msg = if e.is_timeout() {
+                format!("Connection timed out ({}s)", FETCH_TIMEOUT_SECS)
+            } else if e.is_connect() {
+                "Connection refused or DNS failure".to_string()
+            } else {
+                format!("{}", e)
+            };
+            return unreachable(msg);
+        }
+    };
+
+    if !resp.status().is_success() {
+        return unreachable(format!("HTTP {}", resp.status()));
+    }
+
+    let body = match crate::health::read_body_limited(resp, MAX_SPEC_BYTES).await {
+        Ok(Some(b)) => b,
+        Ok(None) => {
+            return (
+                "invalid",
+                SpecReport::failure(format!("Spec exceeds {} bytes", MAX_SPEC_BYTES)),
+            )
+        }
+        Err(e) => return unreachable(format!("{}", e)),
+    };
+
+    let report = lint_spec(&String::from_utf8_lossy(&body));
+    (if report.valid { "valid" } else { "invalid" }, report)
+}
+
+fn record_result(
+    conn: &rusqlite::Connection,
+    app_id: &str,
+    spec_url: &str,
+    status: &str,
+    report: &SpecReport,
+) {
+    let servers = serde_json::to_string(&report.servers).unwrap_or_else(|_| "[]".to_string());
+    let errors = serde_json::to_string(&report.errors).unwrap_or_else(|_| "[]".to_string());
+    let endpoints = serde_json::to_string(&report.endpoints).unwrap_or_else(|_| "[]".to_string());
+    let auth = serde_json::to_string(&report.auth).unwrap_or_else(|_| "[]".to_string());
+    let tools = serde_json::to_string(&report.tools).unwrap_or_else(|_| "[]".to_string());
+
+    let _ = conn.execute(
+        "INSERT INTO app_specs (app_id, spec_url, status, openapi_version, title, version, servers, errors, endpoints, auth, tools, fetched_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, datetime('now'))
+         ON CONFLICT(app_id) DO UPDATE SET
+           spec_url = excluded.spec_url, status = excluded.status,
+           openapi_version = excluded.openapi_version, title = excluded.title,
+           version = excluded.version, servers = excluded.servers,
+           errors = excluded.errors, endpoints = excluded.endpoints,
+           auth = excluded.auth, tools = excluded.tools, fetched_at = excluded.fetched_at",
+        rusqlite::params![
+            app_id,
+            spec_url,
+            status,
+            report.openapi_version,
+            report.title,
+            report.version,
+            servers,
+            errors,
+            endpoints,
+            auth,
+            tools,
+        ],
+    );
+
+    // Only update the cached status if the spec URL hasn't changed mid-flight
+    let _ = conn.execute(
+        "UPDATE apps SET spec_status = ?1 WHERE id = ?2 AND api_spec_url = ?3",
+        rusqlite::params![status, app_id, spec_url],
+    );
+}
+
+/// Load the stored spec verification details for an app, if any.
+pub fn spec_details(conn: &rusqlite::Connection, app_id: &str) -> Option<Value> {
+    conn.query_row(
+        "SELECT spec_url, status, openapi_version, title, version, servers, errors, fetched_at, endpoints, auth
+         FROM app_specs WHERE app_id = ?1",
+        rusqlite::params![app_id],
+        |row| {
+            let servers: Vec<String> =
+                serde_json::from_str(&row.get::<_, String>(5)?).unwrap_or_default();
+            let errors: Vec<String> =
+                serde_json::from_str(&row.get::<_, String>(6)?).unwrap_or_default();
+            let endpoints: Vec<String> =
+                serde_json::from_str(&row.get::<_, String>(8)?).unwrap_or_default();
+            let auth: Vec<String> =
+                serde_json::from_str(&row.get::<_, String>(9)?).unwrap_or_default();
+            Ok(json!({
+                "spec_url": row.get::<_, String>(0)?,
+                "status": row.get::<_, String>(1)?,
+                "openapi_version": row.get::<_, Option<String>>(2)?,
+                "title": row.get::<_, Option<String>>(3)?,
+                "version": row.get::<_, Option<String>>(4)?,
+                "servers": servers,
+                "errors": errors,
+                "endpoints": endpoints,
+                "auth": auth,
+                "fetched_at": row.get::<_, String>(7)?,
+            }))
+        },
+    )
+    .ok()
+}
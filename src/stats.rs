@@ -1,54 +1,197 @@
+use std::collections::HashMap;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rocket::fairing::{Fairing, Info, Kind};
 use rocket::http::Status;
+use rocket::response::Redirect;
 use rocket::serde::json::Json;
+use rocket::{Orbit, Rocket};
 use serde_json::{json, Value};
 
+use crate::accounts::Principal;
+use crate::auth::{AuthenticatedKey, EditTokenParam, OptionalKey, ReadAccess};
 // Auth not required for stats endpoints
+use crate::errors::{ApiError, ErrorCode};
+use crate::models::{listed_visibility, VALID_STATUSES};
 use crate::DbState;
 
-/// Record a view event for an app.
-/// Called internally from get_app route.
-pub fn record_view(conn: &rusqlite::Connection, app_id: &str, viewer_key_id: &str) {
-    let id = uuid::Uuid::new_v4().to_string();
-    let _ = conn.execute(
-        "INSERT INTO app_views (id, app_id, viewer_key_id) VALUES (?1, ?2, ?3)",
-        rusqlite::params![id, app_id, viewer_key_id],
-    );
+/// A view waiting to be written to `app_views`.
+#[derive(Debug)]
+struct PendingView {
+    app_id: String,
+    viewer_key_id: String,
+    viewed_at: String,
+}
+
+/// Queues app views in memory so `get_app` never writes to the DB.
+/// `ViewFlusher` writes the queue in batches; readers of `app_views`
+/// call `flush` first so counts include views still in the queue.
+#[derive(Clone)]
+pub struct ViewRecorder {
+    sender: mpsc::Sender<PendingView>,
+    receiver: Arc<Mutex<mpsc::Receiver<PendingView>>>,
+    /// Privacy mode: every view is anonymous and dated to the day. Views
+    /// are still written one row each, so their order within a day is kept.
+    private: bool,
+}
+
+impl Default for ViewRecorder {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        ViewRecorder {
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            private: false,
+        }
+    }
+}
+
+impl ViewRecorder {
+    /// A recorder that, with `private`, drops the viewer and the time of day
+    /// from every view, leaving per-app daily counts.
+    pub fn with_privacy(private: bool) -> Self {
+        ViewRecorder {
+            private,
+            ..ViewRecorder::default()
+        }
+    }
+
+    /// Whether views are recorded without viewers, leaving nothing to tell them apart.
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
+    /// Queue a view event for an app. The timestamp is taken now, not at flush.
+    pub fn record(&self, app_id: &str, viewer_key_id: &str) {
+        let (viewer_key_id, format) = if self.private {
+            ("anonymous", "%Y-%m-%d 00:00:00")
+        } else {
+            (viewer_key_id, "%Y-%m-%d %H:%M:%S")
+        };
+        let _ = self.sender.send(PendingView {
+            app_id: app_id.to_string(),
+            viewer_key_id: viewer_key_id.to_string(),
+            viewed_at: chrono::Utc::now().format(format).to_string(),
+        });
+    }
+
+    /// Write every queued view in one transaction. Views of apps deleted
+    /// since they were queued are dropped. Returns the number written.
+    pub fn flush(&self, conn: &rusqlite::Connection) -> usize {
+        let pending: Vec<PendingView> = match self.receiver.lock() {
+            Ok(rx) => rx.try_iter().collect(),
+            Err(_) => return 0,
+        };
+        if pending.is_empty() {
+            return 0;
+        }
+        let write = || -> rusqlite::Result<usize> {
+            let tx = conn.unchecked_transaction()?;
+            let mut written = 0;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT INTO app_views (id, app_id, viewer_key_id, viewed_at)
+                     SELECT ?1, ?2, ?3, ?4 WHERE EXISTS (SELECT 1 FROM apps WHERE id = ?2)",
+                )?;
+                for view in &pending {
+                    let id = uuid::Uuid::new_v4().to_string();
+                    written += stmt.execute(rusqlite::params![id, view.app_id, view.viewer_key_id, view.viewed_at])?;
+                }
+            }
+            tx.commit()?;
+            Ok(written)
+        };
+        match write() {
+            Ok(written) => written,
+            Err(e) => {
+                rocket::error!("View flush: dropped {} views: {}", pending.len(), e);
+                0
+            }
+        }
+    }
+}
+
+/// Flushes queued views every `flush_secs` on its own connection, and once
+/// more on shutdown.
+pub struct ViewFlusher {
+    pub db_path: String,
+    pub flush_secs: u64,
+}
+
+impl ViewFlusher {
+    fn open(db_path: &str) -> Option<rusqlite::Connection> {
+        match rusqlite::Connection::open(db_path) {
+            Ok(conn) => {
+                let _ = conn.busy_timeout(Duration::from_secs(2));
+                Some(conn)
+            }
+            Err(e) => {
+                rocket::error!("View flush: failed to open DB: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for ViewFlusher {
+    fn info(&self) -> Info {
+        Info {
+            name: "Batched View Recording",
+            kind: Kind::Liftoff | Kind::Shutdown,
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let views = match rocket.state::<ViewRecorder>() {
+            Some(views) => views.clone(),
+            None => return,
+        };
+        let conn = match Self::open(&self.db_path) {
+            Some(conn) => conn,
+            None => return,
+        };
+        let interval = Duration::from_secs(self.flush_secs.max(1));
+        let shutdown = rocket.shutdown();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = shutdown.clone() => break,
+                }
+                views.flush(&conn);
+            }
+        });
+    }
+
+    async fn on_shutdown(&self, rocket: &Rocket<Orbit>) {
+        if let (Some(views), Some(conn)) = (rocket.state::<ViewRecorder>(), Self::open(&self.db_path)) {
+            views.flush(&conn);
+        }
+    }
 }
 
 /// Get statistics for a single app.
-/// Returns total views, views in last 24h, 7d, 30d, and unique viewers.
+/// Returns total views, views in last 24h, 7d, 30d, and unique viewers
+/// (`null` in privacy mode, where viewers aren't stored).
 #[get("/apps/<id>/stats")]
 pub fn get_app_stats(
+    reader: ReadAccess,
+    token: EditTokenParam,
     id: &str,
     db: &rocket::State<DbState>,
+    views: &rocket::State<ViewRecorder>,
 ) -> (Status, Json<Value>) {
     let conn = db.conn();
+    views.flush(&conn);
 
-    // Check app exists
-    let app_exists: bool = conn
-        .query_row(
-            "SELECT COUNT(*) FROM apps WHERE id = ?1 OR slug = ?1",
-            rusqlite::params![id],
-            |r| r.get::<_, i64>(0),
-        )
-        .unwrap_or(0)
-        > 0;
-
-    if !app_exists {
-        return (
-            Status::NotFound,
-            Json(json!({ "error": "NOT_FOUND", "message": "App not found" })),
-        );
-    }
-
-    // Resolve to canonical ID if slug was provided
-    let app_id: String = conn
-        .query_row(
-            "SELECT id FROM apps WHERE id = ?1 OR slug = ?1",
-            rusqlite::params![id],
-            |r| r.get(0),
-        )
-        .unwrap();
+    // Resolve to canonical ID if slug was provided; hidden listings read as missing
+    let app_id = match crate::routes::visible_app_id(&conn, id, &reader, token.0.as_deref()) {
+        Some(id) => id,
+        None => return ApiError::new(ErrorCode::NotFound, "App not found").into(),
+    };
 
     let total_views: i64 = conn
         .query_row(
@@ -82,13 +225,14 @@ pub fn get_app_stats(
         )
         .unwrap_or(0);
 
-    let unique_viewers: i64 = conn
-        .query_row(
+    let unique_viewers: Option<i64> = (!views.is_private()).then(|| {
+        conn.query_row(
             "SELECT COUNT(DISTINCT viewer_key_id) FROM app_views WHERE app_id = ?1",
             rusqlite::params![app_id],
             |r| r.get(0),
         )
-        .unwrap_or(0);
+        .unwrap_or(0)
+    });
 
     (
         Status::Ok,
@@ -103,34 +247,97 @@ pub fn get_app_stats(
     )
 }
 
+/// Links a click-through can follow, and the app column holding each URL.
+pub const CLICK_TARGETS: &[(&str, &str)] = &[("homepage", "homepage_url"), ("api", "api_url"), ("spec", "api_spec_url")];
+
+/// How recent a reviewer's click-through must be for their review to count
+/// as verified usage.
+pub const VERIFIED_USAGE_WINDOW_DAYS: i64 = 90;
+
+/// Whether `key_id` followed one of the app's links within the
+/// verified-usage window.
+pub fn has_verified_usage(conn: &rusqlite::Connection, app_id: &str, key_id: &str) -> bool {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM app_clicks WHERE app_id = ?1 AND clicker_key_id = ?2
+                         AND clicked_at >= datetime('now', ?3))",
+        rusqlite::params![app_id, key_id, format!("-{VERIFIED_USAGE_WINDOW_DAYS} days")],
+        |r| r.get(0),
+    )
+    .unwrap_or(false)
+}
+
+/// Record a click-through and redirect to the app's homepage (default),
+/// API or spec. Clicks made with an API key let that key's reviews of the
+/// app count as verified usage. Listings the caller can't see are 404.
+#[get("/apps/<id_or_slug>/visit?<target>")]
+pub fn visit_app(
+    reader: ReadAccess,
+    token: EditTokenParam,
+    id_or_slug: &str,
+    target: Option<&str>,
+    db: &rocket::State<DbState>,
+) -> Result<Redirect, ApiError> {
+    let target = target.unwrap_or(CLICK_TARGETS[0].0);
+    let Some((target, column)) = CLICK_TARGETS.iter().find(|(name, _)| *name == target) else {
+        let names: Vec<&str> = CLICK_TARGETS.iter().map(|(name, _)| *name).collect();
+        return Err(ApiError::new(ErrorCode::BadRequest, format!("target must be one of: {}", names.join(", "))));
+    };
+
+    let conn = db.conn();
+    let app_id = crate::routes::visible_app_id(&conn, id_or_slug, &reader, token.0.as_deref())
+        .ok_or_else(|| ApiError::new(ErrorCode::NotFound, "App not found"))?;
+    let url: Option<String> = conn
+        .query_row(
+            &format!("SELECT {column} FROM apps WHERE id = ?1"),
+            rusqlite::params![app_id],
+            |r| r.get(0),
+        )
+        .map_err(|_| ApiError::new(ErrorCode::NotFound, "App not found"))?;
+    let url = url
+        .filter(|u| !u.trim().is_empty())
+        .ok_or_else(|| ApiError::new(ErrorCode::NotFound, format!("App has no {target} link")))?;
+
+    let _ = conn.execute(
+        "INSERT INTO app_clicks (id, app_id, clicker_key_id, target) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![uuid::Uuid::new_v4().to_string(), app_id, reader.0.as_ref().map(|k| &k.id), target],
+    );
+    Ok(Redirect::to(url))
+}
+
 /// Trending apps — ranked by views in the last 7 days.
 /// Returns apps with their view counts and velocity (views per day).
+/// Unlisted apps never trend; private ones only for signed-in callers.
+/// `unique_viewers` is `null` in privacy mode.
 #[get("/apps/trending?<days>&<limit>")]
 pub fn trending_apps(
+    viewer: Option<Principal>,
     days: Option<i64>,
     limit: Option<i64>,
     db: &rocket::State<DbState>,
+    views: &rocket::State<ViewRecorder>,
 ) -> Json<Value> {
     let conn = db.conn();
+    views.flush(&conn);
 
     let days = days.unwrap_or(7).clamp(1, 90);
     let limit = limit.unwrap_or(10).clamp(1, 50);
     let interval = format!("-{} days", days);
 
     let mut stmt = conn
-        .prepare(
+        .prepare(&format!(
             "SELECT a.id, a.name, a.slug, a.short_description, a.protocol, a.category,
                     a.tags, a.is_featured, a.is_verified, a.avg_rating, a.review_count,
                     COUNT(v.id) as view_count,
                     COUNT(DISTINCT v.viewer_key_id) as unique_viewers
              FROM apps a
              LEFT JOIN app_views v ON v.app_id = a.id AND v.viewed_at >= datetime('now', ?1)
-             WHERE a.status = 'approved'
+             WHERE a.status = 'approved' AND {}
              GROUP BY a.id
              HAVING view_count > 0
              ORDER BY view_count DESC, unique_viewers DESC
              LIMIT ?2",
-        )
+            listed_visibility("a.visibility", viewer.is_some())
+        ))
         .unwrap();
 
     let apps: Vec<Value> = stmt
@@ -138,7 +345,7 @@ pub fn trending_apps(
             let tags_str: String = row.get(6)?;
             let tags: Vec<String> = serde_json::from_str(&tags_str).unwrap_or_default();
             let view_count: i64 = row.get(11)?;
-            let unique_viewers: i64 = row.get(12)?;
+            let unique_viewers: Option<i64> = Some(row.get(12)?).filter(|_| !views.is_private());
             Ok(json!({
                 "id": row.get::<_, String>(0)?,
                 "name": row.get::<_, String>(1)?,
@@ -165,3 +372,637 @@ pub fn trending_apps(
         "period_days": days,
     }))
 }
+
+/// Score weights for recommendations: each other agent that viewed one of
+/// the caller's apps and the candidate, each endorsement between the
+/// candidate and one of the caller's apps, each of the caller's apps in the
+/// same category, and each tag shared with them.
+const CO_VIEWER_WEIGHT: f64 = 3.0;
+const ENDORSEMENT_WEIGHT: f64 = 3.0;
+const CATEGORY_WEIGHT: f64 = 2.0;
+const TAG_WEIGHT: f64 = 1.0;
+
+/// "For you" feed: approved apps similar to what the calling key viewed or
+/// clicked in the last `days` (default 90), leaving out the ones it has
+/// already seen. Similarity is collaborative (other keys that viewed the
+/// same apps also viewed this one), endorsements to or from the apps it
+/// saw, and category and tag overlap.
+#[get("/apps/recommended?<days>&<limit>")]
+pub fn recommended_apps(
+    key: AuthenticatedKey,
+    days: Option<i64>,
+    limit: Option<usize>,
+    db: &rocket::State<DbState>,
+    views: &rocket::State<ViewRecorder>,
+) -> Json<Value> {
+    let conn = db.conn();
+    views.flush(&conn);
+
+    let days = days.unwrap_or(90).clamp(1, 365);
+    let limit = limit.unwrap_or(10).clamp(1, 50);
+    let since = format!("-{} days", days);
+    let seen_sql = "SELECT app_id FROM app_views WHERE viewer_key_id = ?1 AND viewed_at >= datetime('now', ?2)
+                    UNION SELECT app_id FROM app_clicks WHERE clicker_key_id = ?1 AND clicked_at >= datetime('now', ?2)";
+
+    // Categories and tags of what the key has seen
+    let seen: Vec<(String, Vec<String>)> = conn
+        .prepare(&format!("SELECT category, tags FROM apps WHERE id IN ({seen_sql})"))
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![key.id, since], |r| {
+                let tags: Vec<String> = serde_json::from_str(&r.get::<_, String>(1)?).unwrap_or_default();
+                Ok((r.get::<_, String>(0)?, tags))
+            })?
+            .collect()
+        })
+        .unwrap_or_default();
+    if seen.is_empty() {
+        return Json(json!({ "recommended": [], "based_on": 0, "period_days": days }));
+    }
+    let mut seen_categories: HashMap<&str, usize> = HashMap::new();
+    let mut seen_tags: HashMap<String, usize> = HashMap::new();
+    for (category, tags) in &seen {
+        *seen_categories.entry(category.as_str()).or_default() += 1;
+        for tag in tags {
+            *seen_tags.entry(tag.to_lowercase()).or_default() += 1;
+        }
+    }
+
+    // Other keys that viewed something the caller saw, and what else they viewed
+    let co_viewers: HashMap<String, i64> = conn
+        .prepare(&format!(
+            "SELECT other.app_id, COUNT(DISTINCT other.viewer_key_id)
+             FROM app_views shared
+             JOIN app_views other ON other.viewer_key_id = shared.viewer_key_id
+             WHERE shared.app_id IN ({seen_sql}) AND shared.viewer_key_id NOT IN (?1, 'anonymous')
+               AND shared.viewed_at >= datetime('now', ?2) AND other.viewed_at >= datetime('now', ?2)
+             GROUP BY other.app_id"
+        ))
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![key.id, since], |r| Ok((r.get(0)?, r.get(1)?)))?.collect()
+        })
+        .unwrap_or_default();
+
+    // Endorsements given to or received from an app the caller saw
+    let endorsements: HashMap<String, i64> = conn
+        .prepare(&format!(
+            "SELECT app_id, COUNT(*) FROM (
+                SELECT endorsed_app_id AS app_id FROM app_endorsements WHERE endorser_app_id IN ({seen_sql})
+                UNION ALL
+                SELECT endorser_app_id FROM app_endorsements WHERE endorsed_app_id IN ({seen_sql})
+             ) GROUP BY app_id"
+        ))
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![key.id, since], |r| Ok((r.get(0)?, r.get(1)?)))?.collect()
+        })
+        .unwrap_or_default();
+
+    let mut candidates: Vec<(f64, Value)> = conn
+        .prepare(&format!(
+            "SELECT id, name, slug, short_description, protocol, category, tags, is_featured, is_verified,
+                    avg_rating, review_count
+             FROM apps
+             WHERE status = 'approved' AND {} AND id NOT IN ({seen_sql})",
+            listed_visibility("visibility", true)
+        ))
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![key.id, since], |row| {
+                let id: String = row.get(0)?;
+                let category: String = row.get(5)?;
+                let tags: Vec<String> = serde_json::from_str(&row.get::<_, String>(6)?).unwrap_or_default();
+                let co_viewers = co_viewers.get(&id).copied().unwrap_or(0);
+                let endorsements = endorsements.get(&id).copied().unwrap_or(0);
+                let same_category = seen_categories.get(category.as_str()).copied().unwrap_or(0);
+                let shared_tags: Vec<&String> = tags.iter().filter(|t| seen_tags.contains_key(&t.to_lowercase())).collect();
+                let score = CO_VIEWER_WEIGHT * co_viewers as f64
+                    + ENDORSEMENT_WEIGHT * endorsements as f64
+                    + CATEGORY_WEIGHT * same_category as f64
+                    + TAG_WEIGHT * shared_tags.iter().map(|t| seen_tags[&t.to_lowercase()]).sum::<usize>() as f64;
+                let reasons = json!({
+                    "co_viewers": co_viewers,
+                    "endorsements": endorsements,
+                    "same_category": same_category > 0,
+                    "shared_tags": shared_tags,
+                });
+                Ok((
+                    score,
+                    json!({
+                        "id": id,
+                        "name": row.get::<_, String>(1)?,
+                        "slug": row.get::<_, String>(2)?,
+                        "short_description": row.get::<_, String>(3)?,
+                        "protocol": row.get::<_, String>(4)?,
+                        "category": category,
+                        "tags": tags,
+                        "is_featured": row.get::<_, i32>(7)? != 0,
+                        "is_verified": row.get::<_, i32>(8)? != 0,
+                        "avg_rating": row.get::<_, f64>(9)?,
+                        "review_count": row.get::<_, i64>(10)?,
+                        "score": score,
+                        "reasons": reasons,
+                    }),
+                ))
+            })?
+            .collect()
+        })
+        .unwrap_or_default();
+
+    // Best score first; rating and review count break ties
+    candidates.retain(|(score, _)| *score > 0.0);
+    candidates.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .total_cmp(a_score)
+            .then(b["avg_rating"].as_f64().unwrap_or(0.0).total_cmp(&a["avg_rating"].as_f64().unwrap_or(0.0)))
+            .then(b["review_count"].as_i64().cmp(&a["review_count"].as_i64()))
+    });
+    let apps: Vec<Value> = candidates.into_iter().take(limit).map(|(_, app)| app).collect();
+
+    Json(json!({
+        "recommended": apps,
+        "based_on": seen.len(),
+        "period_days": days,
+    }))
+}
+
+/// Periods accepted by the overview endpoint, with their SQLite date modifiers.
+const OVERVIEW_PERIODS: &[(&str, Option<&str>)] = &[
+    ("24h", Some("-1 day")),
+    ("7d", Some("-7 days")),
+    ("30d", Some("-30 days")),
+    ("90d", Some("-90 days")),
+    ("all", None),
+];
+
+/// Aggregate approved apps grouped by `group_expr` (an expression over `apps a`).
+/// Activity counts (new apps, reviews, views, health checks) are limited to `since`.
+fn aggregate_by(conn: &rusqlite::Connection, group_expr: &str, since: Option<&str>) -> Vec<Value> {
+    let sql = format!(
+        "SELECT {g} AS grp,
+                COUNT(*),
+                SUM(CASE WHEN ?1 IS NULL OR a.created_at >= datetime('now', ?1) THEN 1 ELSE 0 END),
+                AVG(CASE WHEN a.review_count > 0 THEN a.avg_rating END),
+                COALESCE(SUM(r.reviews), 0),
+                COALESCE(SUM(v.views), 0),
+                COALESCE(SUM(h.checks), 0),
+                COALESCE(SUM(h.healthy), 0)
+         FROM apps a
+         LEFT JOIN (SELECT app_id, COUNT(*) AS reviews FROM reviews
+                    WHERE ?1 IS NULL OR created_at >= datetime('now', ?1) GROUP BY app_id) r ON r.app_id = a.id
+         LEFT JOIN (SELECT app_id, COUNT(*) AS views FROM app_views
+                    WHERE ?1 IS NULL OR viewed_at >= datetime('now', ?1) GROUP BY app_id) v ON v.app_id = a.id
+         LEFT JOIN (SELECT app_id, COUNT(*) AS checks,
+                           SUM(CASE WHEN status = 'healthy' THEN 1 ELSE 0 END) AS healthy
+                    FROM health_checks
+                    WHERE ?1 IS NULL OR checked_at >= datetime('now', ?1) GROUP BY app_id) h ON h.app_id = a.id
+         WHERE a.status = 'approved'
+         GROUP BY grp
+         ORDER BY COUNT(*) DESC, grp ASC",
+        g = group_expr
+    );
+
+    let mut stmt = conn.prepare(&sql).unwrap();
+    stmt.query_map(rusqlite::params![since], |row| {
+        let checks: i64 = row.get(6)?;
+        let healthy: i64 = row.get(7)?;
+        Ok(json!({
+            "name": row.get::<_, String>(0)?,
+            "apps": row.get::<_, i64>(1)?,
+            "new_apps": row.get::<_, i64>(2)?,
+            "avg_rating": row.get::<_, Option<f64>>(3)?,
+            "reviews": row.get::<_, i64>(4)?,
+            "views": row.get::<_, i64>(5)?,
+            "health_checks": checks,
+            "healthy_checks": healthy,
+            "healthy_ratio": (checks > 0).then(|| healthy as f64 / checks as f64),
+        }))
+    })
+    .unwrap()
+    .filter_map(|r| r.ok())
+    .collect()
+}
+
+/// Directory-wide aggregates grouped by top-level category and by protocol.
+/// `period` is one of 24h, 7d, 30d (default), 90d, all.
+#[get("/stats/overview?<period>")]
+pub fn stats_overview(
+    period: Option<&str>,
+    db: &rocket::State<DbState>,
+    views: &rocket::State<ViewRecorder>,
+) -> (Status, Json<Value>) {
+    let period = period.unwrap_or("30d");
+    let since = match OVERVIEW_PERIODS.iter().find(|(name, _)| *name == period) {
+        Some((_, since)) => *since,
+        None => {
+            let valid: Vec<&str> = OVERVIEW_PERIODS.iter().map(|(name, _)| *name).collect();
+            return ApiError::new(
+                ErrorCode::InvalidPeriod,
+                format!("Valid periods: {}", valid.join(", ")),
+            )
+            .into();
+        }
+    };
+
+    let conn = db.conn();
+    views.flush(&conn);
+
+    let top_level = "CASE WHEN instr(a.category, '/') > 0
+                          THEN substr(a.category, 1, instr(a.category, '/') - 1)
+                          ELSE a.category END";
+    let by_category = aggregate_by(&conn, top_level, since);
+    let by_protocol = aggregate_by(&conn, "a.protocol", since);
+
+    let sum = |field: &str| -> i64 { by_protocol.iter().filter_map(|g| g[field].as_i64()).sum() };
+    let checks = sum("health_checks");
+    let healthy = sum("healthy_checks");
+    let avg_rating: Option<f64> = conn
+        .query_row(
+            "SELECT AVG(avg_rating) FROM apps WHERE status = 'approved' AND review_count > 0",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap_or(None);
+
+    (
+        Status::Ok,
+        Json(json!({
+            "period": period,
+            "totals": {
+                "apps": sum("apps"),
+                "new_apps": sum("new_apps"),
+                "avg_rating": avg_rating,
+                "reviews": sum("reviews"),
+                "views": sum("views"),
+                "health_checks": checks,
+                "healthy_checks": healthy,
+                "healthy_ratio": (checks > 0).then(|| healthy as f64 / checks as f64),
+            },
+            "by_category": by_category,
+            "by_protocol": by_protocol,
+        })),
+    )
+}
+
+/// How long `GET /stats/site` reuses a computed response.
+const SITE_STATS_TTL: Duration = Duration::from_secs(60);
+
+/// Last `GET /stats/site` response and when it was computed.
+#[derive(Default)]
+pub struct SiteStatsCache(Mutex<Option<(Instant, Value)>>);
+
+fn compute_site_stats(conn: &rusqlite::Connection) -> Value {
+    let count = |sql: &str| -> i64 { conn.query_row(sql, [], |r| r.get(0)).unwrap_or(0) };
+    let avg_rating: Option<f64> = conn
+        .query_row(
+            "SELECT AVG(r.rating) FROM reviews r JOIN apps a ON a.id = r.app_id WHERE a.status = 'approved'",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap_or(None);
+    // The response is cached for everyone, so only public listings can headline it
+    let newest_app: Option<Value> = conn
+        .query_row(
+            &format!(
+                "SELECT id, name, slug, short_description, created_at FROM apps
+                 WHERE status = 'approved' AND {} ORDER BY created_at DESC, rowid DESC LIMIT 1",
+                listed_visibility("visibility", false)
+            ),
+            [],
+            |r| {
+                Ok(json!({
+                    "id": r.get::<_, String>(0)?,
+                    "name": r.get::<_, String>(1)?,
+                    "slug": r.get::<_, String>(2)?,
+                    "short_description": r.get::<_, String>(3)?,
+                    "created_at": r.get::<_, String>(4)?,
+                }))
+            },
+        )
+        .ok();
+
+    json!({
+        "apps": count("SELECT COUNT(*) FROM apps WHERE status = 'approved'"),
+        "reviews": count(
+            "SELECT COUNT(*) FROM reviews r JOIN apps a ON a.id = r.app_id WHERE a.status = 'approved'",
+        ),
+        "avg_rating": avg_rating,
+        "protocols": count("SELECT COUNT(DISTINCT protocol) FROM apps WHERE status = 'approved'"),
+        "newest_app": newest_app,
+        "last_7d": {
+            "submissions": count("SELECT COUNT(*) FROM apps WHERE created_at >= datetime('now', '-7 days')"),
+            "views": count("SELECT COUNT(*) FROM app_views WHERE viewed_at >= datetime('now', '-7 days')"),
+        },
+        "generated_at": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    })
+}
+
+/// Headline numbers for the home page in one request, cached for 60 seconds.
+#[get("/stats/site")]
+pub fn site_stats(
+    db: &rocket::State<DbState>,
+    views: &rocket::State<ViewRecorder>,
+    cache: &rocket::State<SiteStatsCache>,
+) -> Json<Value> {
+    let mut cached = cache.0.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((at, body)) = cached.as_ref() {
+        if at.elapsed() < SITE_STATS_TTL {
+            return Json(body.clone());
+        }
+    }
+    let conn = db.conn();
+    views.flush(&conn);
+    let body = compute_site_stats(&conn);
+    *cached = Some((Instant::now(), body.clone()));
+    Json(body)
+}
+
+/// Metrics kept in `directory_snapshots`, besides `apps.<status>` for each
+/// app status. `apps` and `reviews` count approved listings like
+/// `/stats/site`, `keys` active API keys, and `views` the day's views.
+pub const SNAPSHOT_METRICS: &[&str] = &["apps", "reviews", "keys", "views"];
+
+/// Longest history `GET /stats/history` returns, in days.
+const MAX_HISTORY_DAYS: i64 = 365;
+
+/// Write today's directory totals to `directory_snapshots`, replacing any
+/// earlier snapshot of the day, so the last one taken stands for the day.
+/// Returns the number of metrics written.
+pub fn record_snapshot(conn: &rusqlite::Connection) -> rusqlite::Result<usize> {
+    let count = |sql: &str| -> rusqlite::Result<i64> { conn.query_row(sql, [], |r| r.get(0)) };
+    let mut values = vec![
+        ("apps".to_string(), count("SELECT COUNT(*) FROM apps WHERE status = 'approved'")?),
+        (
+            "reviews".to_string(),
+            count("SELECT COUNT(*) FROM reviews r JOIN apps a ON a.id = r.app_id WHERE a.status = 'approved'")?,
+        ),
+        ("keys".to_string(), count("SELECT COUNT(*) FROM api_keys WHERE revoked = 0")?),
+        ("views".to_string(), count("SELECT COUNT(*) FROM app_views WHERE viewed_at >= date('now')")?),
+    ];
+    for status in VALID_STATUSES {
+        let n = conn.query_row("SELECT COUNT(*) FROM apps WHERE status = ?1", [status], |r| r.get(0))?;
+        values.push((format!("apps.{status}"), n));
+    }
+    for (metric, value) in &values {
+        conn.execute(
+            "INSERT INTO directory_snapshots (day, metric, value) VALUES (date('now'), ?1, ?2)
+             ON CONFLICT (day, metric) DO UPDATE SET value = excluded.value",
+            rusqlite::params![metric, value],
+        )?;
+    }
+    Ok(values.len())
+}
+
+/// Daily values of one snapshot metric over the last `days` (default 90,
+/// max 365), oldest first, for growth charts. Days without a snapshot are
+/// left out. `keys` needs an admin key.
+#[get("/stats/history?<metric>&<days>")]
+pub fn stats_history(
+    key: OptionalKey,
+    metric: Option<&str>,
+    days: Option<i64>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    let metric = metric.unwrap_or("apps");
+    let known = SNAPSHOT_METRICS.contains(&metric)
+        || metric.strip_prefix("apps.").is_some_and(|status| VALID_STATUSES.contains(&status));
+    if !known {
+        let mut valid: Vec<String> = SNAPSHOT_METRICS.iter().map(|m| m.to_string()).collect();
+        valid.extend(VALID_STATUSES.iter().map(|s| format!("apps.{s}")));
+        return ApiError::new(ErrorCode::InvalidMetric, format!("Valid metrics: {}", valid.join(", "))).into();
+    }
+    if metric == "keys" && !key.0.as_ref().is_some_and(|k| k.is_admin) {
+        return ApiError::new(ErrorCode::AdminRequired, "Key counts are only shown to admins").into();
+    }
+    let days = days.unwrap_or(90).clamp(1, MAX_HISTORY_DAYS);
+
+    let conn = db.conn();
+    let points: Vec<Value> = conn
+        .prepare(
+            "SELECT day, value FROM directory_snapshots
+             WHERE metric = ?1 AND day > date('now', ?2)
+             ORDER BY day",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![metric, format!("-{days} days")], |r| {
+                Ok(json!({ "date": r.get::<_, String>(0)?, "value": r.get::<_, i64>(1)? }))
+            })?
+            .collect()
+        })
+        .unwrap_or_default();
+
+    (
+        Status::Ok,
+        Json(json!({
+            "metric": metric,
+            "days": days,
+            "points": points,
+        })),
+    )
+}
+
+/// Reviews' worth of the category mean blended into every leaderboard
+/// score, so an app needs several good reviews to beat a well-reviewed one.
+pub const LEADERBOARD_PRIOR_WEIGHT: f64 = 5.0;
+
+/// Periods rank deltas can be measured over, with their SQLite date modifiers.
+const LEADERBOARD_PERIODS: &[(&str, &str)] = &[("7d", "-7 days"), ("30d", "-30 days"), ("90d", "-90 days")];
+
+/// An app's place on a category leaderboard.
+#[derive(Debug, Clone, PartialEq)]
+struct Standing {
+    id: String,
+    name: String,
+    slug: String,
+    reviews: i64,
+    avg_rating: f64,
+    score: f64,
+}
+
+/// Bayesian average: `weight` reviews at `prior` plus the app's own.
+fn bayesian_score(prior: f64, weight: f64, avg_rating: f64, reviews: i64) -> f64 {
+    (prior * weight + avg_rating * reviews as f64) / (weight + reviews as f64)
+}
+
+/// Reviewed apps in `category` and its subcategories, best first, counting
+/// reviews written before `before` (a date modifier; `None` for all). The
+/// prior is the mean of all those reviews. Also returns that mean.
+fn category_standings(
+    conn: &rusqlite::Connection,
+    category: &str,
+    signed_in: bool,
+    before: Option<&str>,
+) -> (Vec<Standing>, Option<f64>) {
+    let sql = format!(
+        "SELECT a.id, a.name, a.slug, COUNT(*), AVG(CAST(r.rating AS REAL))
+         FROM apps a JOIN reviews r ON r.app_id = a.id
+         WHERE a.status = 'approved' AND {} AND {}
+           AND (?2 IS NULL OR r.created_at < datetime('now', ?2))
+         GROUP BY a.id",
+        listed_visibility("a.visibility", signed_in),
+        crate::categories::subtree_condition("a.category", 1),
+    );
+    let mut standings: Vec<Standing> = match conn.prepare(&sql) {
+        Ok(mut stmt) => stmt
+            .query_map(rusqlite::params![category, before], |r| {
+                Ok(Standing {
+                    id: r.get(0)?,
+                    name: r.get(1)?,
+                    slug: r.get(2)?,
+                    reviews: r.get(3)?,
+                    avg_rating: r.get(4)?,
+                    score: 0.0,
+                })
+            })
+            .map(|rows| rows.filter_map(|r| r.ok()).collect())
+            .unwrap_or_default(),
+        Err(_) => vec![],
+    };
+    let total: i64 = standings.iter().map(|s| s.reviews).sum();
+    if total == 0 {
+        return (standings, None);
+    }
+    let prior = standings.iter().map(|s| s.avg_rating * s.reviews as f64).sum::<f64>() / total as f64;
+    for s in standings.iter_mut() {
+        s.score = bayesian_score(prior, LEADERBOARD_PRIOR_WEIGHT, s.avg_rating, s.reviews);
+    }
+    standings.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(b.reviews.cmp(&a.reviews))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    (standings, Some(prior))
+}
+
+fn round3(v: f64) -> f64 {
+    (v * 1000.0).round() / 1000.0
+}
+
+/// Top-rated apps in a category (subcategories included), ranked by a
+/// Bayesian-adjusted rating. `rank_delta` compares with the standings at
+/// the start of `period` (7d, 30d or 90d; default 30d): positive moved up,
+/// `null` wasn't ranked then. `limit` defaults to 10 (max 50).
+#[get("/categories/<path..>?<period>&<limit>", rank = 2)]
+pub fn category_leaderboard(
+    path: std::path::PathBuf,
+    period: Option<&str>,
+    limit: Option<i64>,
+    viewer: Option<Principal>,
+    db: &rocket::State<DbState>,
+) -> (Status, Json<Value>) {
+    // `<path..>` can't be followed by a literal, so the suffix is checked here
+    let raw = path.to_string_lossy().replace('\\', "/");
+    let category = match raw.trim_matches('/').strip_suffix("/leaderboard") {
+        Some(category) => category.to_string(),
+        None => {
+            return ApiError::new(ErrorCode::NotFound, format!("No route for GET /api/v1/categories/{}", raw)).into()
+        }
+    };
+    let category = match crate::categories::parse_category_path(&category) {
+        Ok(c) => c,
+        Err(msg) => return ApiError::new(ErrorCode::InvalidCategory, msg).into(),
+    };
+    let period = period.unwrap_or("30d");
+    let since = match LEADERBOARD_PERIODS.iter().find(|(name, _)| *name == period) {
+        Some((_, since)) => *since,
+        None => {
+            let valid: Vec<&str> = LEADERBOARD_PERIODS.iter().map(|(name, _)| *name).collect();
+            return ApiError::new(ErrorCode::InvalidPeriod, format!("Valid periods: {}", valid.join(", "))).into();
+        }
+    };
+    let limit = limit.unwrap_or(10).clamp(1, 50) as usize;
+
+    let conn = db.conn();
+    let exists = conn
+        .query_row("SELECT 1 FROM categories WHERE id = ?1", rusqlite::params![category], |_| Ok(()))
+        .is_ok();
+    if !exists {
+        return ApiError::new(ErrorCode::NotFound, "Category not found").into();
+    }
+
+    let signed_in = viewer.is_some();
+    let (current, prior) = category_standings(&conn, &category, signed_in, None);
+    let (previous, _) = category_standings(&conn, &category, signed_in, Some(since));
+    let previous_rank: HashMap<&str, usize> =
+        previous.iter().enumerate().map(|(i, s)| (s.id.as_str(), i + 1)).collect();
+
+    let leaderboard: Vec<Value> = current
+        .iter()
+        .take(limit)
+        .enumerate()
+        .map(|(i, s)| {
+            let rank = i + 1;
+            let before = previous_rank.get(s.id.as_str()).copied();
+            json!({
+                "rank": rank,
+                "previous_rank": before,
+                "rank_delta": before.map(|b| b as i64 - rank as i64),
+                "id": s.id,
+                "name": s.name,
+                "slug": s.slug,
+                "score": round3(s.score),
+                "avg_rating": round3(s.avg_rating),
+                "review_count": s.reviews,
+            })
+        })
+        .collect();
+
+    (
+        Status::Ok,
+        Json(json!({
+            "category": category,
+            "period": period,
+            "prior": {
+                "mean_rating": prior.map(round3),
+                "weight": LEADERBOARD_PRIOR_WEIGHT,
+            },
+            "ranked_apps": current.len(),
+            "leaderboard": leaderboard,
+        })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bayesian_score_discounts_few_reviews() {
+        let single = bayesian_score(3.5, LEADERBOARD_PRIOR_WEIGHT, 5.0, 1);
+        let many = bayesian_score(3.5, LEADERBOARD_PRIOR_WEIGHT, 4.6, 20);
+        assert!(many > single, "{many} <= {single}");
+        assert_eq!(bayesian_score(3.5, LEADERBOARD_PRIOR_WEIGHT, 1.0, 0), 3.5);
+    }
+
+    #[test]
+    fn flushes_queued_views_in_batches() {
+        let conn = crate::db::init_db(":memory:");
+        conn.execute(
+            "INSERT INTO apps (id, name, slug, short_description, description, protocol, category, tags, author_name)
+             VALUES ('a1', 'Weather', 'weather', 'Forecasts', 'Forecasts', 'rest', 'data', '[]', 'Tester')",
+            [],
+        )
+        .unwrap();
+        let views = ViewRecorder::default();
+        views.record("a1", "anonymous");
+        views.clone().record("a1", "k1");
+        views.record("gone", "k1");
+
+        let count = || -> i64 { conn.query_row("SELECT COUNT(*) FROM app_views", [], |r| r.get(0)).unwrap() };
+        assert_eq!(count(), 0);
+        // Views of unknown apps are dropped
+        assert_eq!(views.flush(&conn), 2);
+        assert_eq!(count(), 2);
+        assert_eq!(views.flush(&conn), 0);
+
+        // Privacy mode keeps neither the viewer nor the time of day
+        let private = ViewRecorder::with_privacy(true);
+        private.record("a1", "k1");
+        assert_eq!(private.flush(&conn), 1);
+        let (viewer, at): (String, String) = conn
+            .query_row("SELECT viewer_key_id, viewed_at FROM app_views ORDER BY rowid DESC LIMIT 1", [], |r| {
+                Ok((r.get(0)?, r.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(viewer, "anonymous");
+        assert!(at.ends_with(" 00:00:00"), "{at}");
+    }
+}
@@ -0,0 +1,200 @@
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// Suggestions returned when no `limit` is given.
+pub const DEFAULT_SUGGESTION_LIMIT: usize = 5;
+pub const MAX_SUGGESTION_LIMIT: usize = 20;
+
+/// A vocabulary tag found in an app's text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TagSuggestion {
+    pub tag: String,
+    /// Weighted mentions: 3 per hit in the name, 2 in the short
+    /// description, 1 in the description.
+    pub score: u32,
+    /// Approved apps already using the tag.
+    pub app_count: i64,
+}
+
+/// Tags on approved public apps with how many apps use each, most used
+/// first. Suggestions only draw from this, so they never invent new tags.
+pub fn vocabulary(conn: &Connection) -> rusqlite::Result<Vec<(String, i64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT LOWER(TRIM(t.value)), COUNT(DISTINCT a.id) FROM apps a, json_each(a.tags) t
+         WHERE a.status = 'approved' AND a.visibility = 'public' AND TRIM(t.value) != ''
+         GROUP BY 1 ORDER BY 2 DESC, 1 ASC",
+    )?;
+    let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+    rows.collect()
+}
+
+/// An app whose tags a rename or merge rewrites.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TagRewrite {
+    pub app_id: String,
+    pub name: String,
+    pub slug: String,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// A tag as renames and merges compare it: trimmed and lowercased.
+pub fn normalize(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Replace tags matching any of `sources` with `target`, dropping tags that
+/// end up duplicated (compared normalized) and keeping the order otherwise.
+pub fn rewrite(tags: &[String], sources: &[String], target: &str) -> Vec<String> {
+    let mut seen = Vec::new();
+    let mut out = Vec::new();
+    for tag in tags {
+        let tag = if sources.contains(&normalize(tag)) { target } else { tag.as_str() };
+        if !seen.contains(&normalize(tag)) {
+            seen.push(normalize(tag));
+            out.push(tag.to_string());
+        }
+    }
+    out
+}
+
+/// Apps of any status carrying one of `sources` (normalized), with their tags
+/// before and after replacing those with `target`. Apps the rewrite wouldn't
+/// change, such as a case-only rename already applied, are left out.
+pub fn plan_rewrite(conn: &Connection, sources: &[String], target: &str) -> rusqlite::Result<Vec<TagRewrite>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, slug, tags FROM apps a
+         WHERE EXISTS (SELECT 1 FROM json_each(a.tags) t WHERE LOWER(TRIM(t.value)) IN (SELECT value FROM json_each(?1)))
+         ORDER BY created_at, rowid",
+    )?;
+    let sources_json = serde_json::to_string(sources).unwrap_or_default();
+    let rows = stmt.query_map(rusqlite::params![sources_json], |r| {
+        Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?, r.get::<_, String>(3)?))
+    })?;
+    let mut plan = Vec::new();
+    for row in rows {
+        let (app_id, name, slug, tags) = row?;
+        let before: Vec<String> = serde_json::from_str(&tags).unwrap_or_default();
+        let after = rewrite(&before, sources, target);
+        if after != before {
+            plan.push(TagRewrite { app_id, name, slug, before, after });
+        }
+    }
+    Ok(plan)
+}
+
+/// Apps with `tag` (normalized) among their tags.
+pub fn usage(conn: &Connection, tag: &str) -> rusqlite::Result<i64> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM apps a WHERE EXISTS (SELECT 1 FROM json_each(a.tags) t WHERE LOWER(TRIM(t.value)) = ?1)",
+        rusqlite::params![normalize(tag)],
+        |r| r.get(0),
+    )
+}
+
+/// Lowercase words, with a trailing plural `s` dropped so "payments" and
+/// "payment" match.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let w = w.to_lowercase();
+            match w.strip_suffix('s') {
+                Some(stem) if stem.chars().count() >= 3 && !stem.ends_with('s') => stem.to_string(),
+                _ => w,
+            }
+        })
+        .collect()
+}
+
+/// Times `needle` appears as a run of consecutive words in `haystack`.
+fn occurrences(haystack: &[String], needle: &[String]) -> u32 {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return 0;
+    }
+    haystack.windows(needle.len()).filter(|w| *w == needle).count() as u32
+}
+
+/// Rank `vocabulary` tags mentioned in an app's name and descriptions.
+/// Multi-word tags ("machine-learning") match the words in sequence
+/// ("machine learning"). Tags in `existing` are skipped. Ties go to the
+/// more widely used tag.
+pub fn suggest(
+    name: &str,
+    short_description: &str,
+    description: &str,
+    existing: &[String],
+    vocabulary: &[(String, i64)],
+    limit: usize,
+) -> Vec<TagSuggestion> {
+    let fields = [(words(name), 3), (words(short_description), 2), (words(description), 1)];
+    let existing: Vec<Vec<String>> = existing.iter().map(|t| words(t)).collect();
+
+    let mut suggestions: Vec<TagSuggestion> = vocabulary
+        .iter()
+        .filter_map(|(tag, app_count)| {
+            let needle = words(tag);
+            if existing.contains(&needle) {
+                return None;
+            }
+            let score: u32 = fields.iter().map(|(text, weight)| weight * occurrences(text, &needle)).sum();
+            (score > 0).then(|| TagSuggestion {
+                tag: tag.clone(),
+                score,
+                app_count: *app_count,
+            })
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(b.app_count.cmp(&a.app_count))
+            .then(a.tag.cmp(&b.tag))
+    });
+    suggestions.truncate(limit);
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_vocabulary_tags_from_text() {
+        let vocabulary = vec![
+            ("payments".to_string(), 4),
+            ("machine-learning".to_string(), 2),
+            ("weather".to_string(), 6),
+            ("ml".to_string(), 1),
+        ];
+        let found = suggest(
+            "Payment Gateway",
+            "Accept payments with machine learning fraud checks",
+            "Uses Machine-Learning models.",
+            &[],
+            &vocabulary,
+            5,
+        );
+        let tags: Vec<&str> = found.iter().map(|s| s.tag.as_str()).collect();
+        assert_eq!(tags, ["payments", "machine-learning"]);
+        assert_eq!(found[0].score, 5);
+        assert_eq!(found[1].score, 3);
+
+        // Tags the app already has aren't suggested again
+        let found = suggest("Payment Gateway", "", "", &["Payment".to_string()], &vocabulary, 5);
+        assert!(found.is_empty());
+
+        // Word boundaries: "ml" doesn't match inside "html"
+        assert!(suggest("HTML tools", "", "", &[], &vocabulary, 5).is_empty());
+    }
+
+    #[test]
+    fn rewrites_sources_and_drops_duplicates() {
+        let tags: Vec<String> = ["AI", "qrcode", "Tools", "qr-code", "QR_Code"].iter().map(|t| t.to_string()).collect();
+        let sources = vec!["qrcode".to_string(), "qr_code".to_string()];
+        assert_eq!(rewrite(&tags, &sources, "qr-code"), ["AI", "qr-code", "Tools"]);
+
+        // Case-only rename
+        assert_eq!(rewrite(&["Ai".to_string()], &["ai".to_string()], "AI"), ["AI"]);
+    }
+}
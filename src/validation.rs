@@ -0,0 +1,207 @@
+use crate::errors::{ApiError, ErrorCode};
+use crate::models::{KeyEndpoint, SubmitAppRequest, SubmitReviewRequest, TranslationRequest, UpdateAppRequest};
+
+/// Maximum field sizes for submitted content, in characters (tags: count and per-tag length).
+/// Each limit can be overridden with the env var named in `from_env`.
+#[derive(Debug, Clone)]
+pub struct FieldLimits {
+    pub name: usize,
+    pub short_description: usize,
+    pub description: usize,
+    pub tag_count: usize,
+    pub tag_length: usize,
+    pub url: usize,
+    pub author_name: usize,
+    pub review_title: usize,
+    pub review_body: usize,
+    pub reviewer_name: usize,
+    pub review_point_count: usize,
+    pub review_point_length: usize,
+    pub review_use_case: usize,
+    pub docs_markdown: usize,
+    pub key_endpoint_count: usize,
+    pub key_endpoint_path: usize,
+    pub key_endpoint_description: usize,
+}
+
+impl Default for FieldLimits {
+    fn default() -> Self {
+        FieldLimits {
+            name: 100,
+            short_description: 300,
+            description: 10_000,
+            tag_count: 20,
+            tag_length: 50,
+            url: 2048,
+            author_name: 100,
+            review_title: 200,
+            review_body: 5_000,
+            reviewer_name: 100,
+            review_point_count: 10,
+            review_point_length: 200,
+            review_use_case: 500,
+            docs_markdown: 50_000,
+            key_endpoint_count: 10,
+            key_endpoint_path: 200,
+            key_endpoint_description: 200,
+        }
+    }
+}
+
+impl FieldLimits {
+    /// Defaults, overridden by `MAX_NAME_LENGTH`, `MAX_SHORT_DESCRIPTION_LENGTH`,
+    /// `MAX_DESCRIPTION_LENGTH`, `MAX_TAGS`, `MAX_TAG_LENGTH`, `MAX_URL_LENGTH`,
+    /// `MAX_REVIEW_BODY_LENGTH`, `MAX_DOCS_LENGTH` and `MAX_KEY_ENDPOINTS` when set.
+    pub fn from_env() -> Self {
+        let env = |name: &str, default: usize| -> usize {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+        let d = FieldLimits::default();
+        FieldLimits {
+            name: env("MAX_NAME_LENGTH", d.name),
+            short_description: env("MAX_SHORT_DESCRIPTION_LENGTH", d.short_description),
+            description: env("MAX_DESCRIPTION_LENGTH", d.description),
+            tag_count: env("MAX_TAGS", d.tag_count),
+            tag_length: env("MAX_TAG_LENGTH", d.tag_length),
+            url: env("MAX_URL_LENGTH", d.url),
+            review_body: env("MAX_REVIEW_BODY_LENGTH", d.review_body),
+            docs_markdown: env("MAX_DOCS_LENGTH", d.docs_markdown),
+            key_endpoint_count: env("MAX_KEY_ENDPOINTS", d.key_endpoint_count),
+            ..d
+        }
+    }
+
+    pub fn check_submission(&self, body: &SubmitAppRequest) -> Result<(), ApiError> {
+        check("name", Some(&body.name), self.name)?;
+        check("short_description", Some(&body.short_description), self.short_description)?;
+        check("description", Some(&body.description), self.description)?;
+        check("author_name", Some(&body.author_name), self.author_name)?;
+        self.check_urls(&[
+            ("homepage_url", &body.homepage_url),
+            ("api_url", &body.api_url),
+            ("api_spec_url", &body.api_spec_url),
+            ("logo_url", &body.logo_url),
+            ("author_url", &body.author_url),
+        ])?;
+        self.check_tags(&body.tags)
+    }
+
+    pub fn check_update(&self, body: &UpdateAppRequest) -> Result<(), ApiError> {
+        check("name", body.name.as_ref(), self.name)?;
+        check("short_description", body.short_description.as_ref(), self.short_description)?;
+        check("description", body.description.as_ref(), self.description)?;
+        check("author_name", body.author_name.as_ref(), self.author_name)?;
+        check("docs_markdown", body.docs_markdown.as_ref(), self.docs_markdown)?;
+        self.check_key_endpoints(&body.key_endpoints)?;
+        self.check_urls(&[
+            ("homepage_url", &body.homepage_url),
+            ("api_url", &body.api_url),
+            ("api_spec_url", &body.api_spec_url),
+            ("logo_url", &body.logo_url),
+            ("author_url", &body.author_url),
+        ])?;
+        self.check_tags(&body.tags)
+    }
+
+    pub fn check_translation(&self, body: &TranslationRequest) -> Result<(), ApiError> {
+        check("name", body.name.as_ref(), self.name)?;
+        check("short_description", body.short_description.as_ref(), self.short_description)?;
+        check("description", body.description.as_ref(), self.description)
+    }
+
+    pub fn check_review(&self, body: &SubmitReviewRequest) -> Result<(), ApiError> {
+        check("title", body.title.as_ref(), self.review_title)?;
+        check("body", body.body.as_ref(), self.review_body)?;
+        check("reviewer_name", body.reviewer_name.as_ref(), self.reviewer_name)?;
+        check("use_case", body.use_case.as_ref(), self.review_use_case)?;
+        check_list("pros", &body.pros, self.review_point_count, self.review_point_length)?;
+        check_list("cons", &body.cons, self.review_point_count, self.review_point_length)
+    }
+
+    fn check_urls(&self, urls: &[(&str, &Option<String>)]) -> Result<(), ApiError> {
+        for (field, value) in urls {
+            check(field, value.as_ref(), self.url)?;
+        }
+        Ok(())
+    }
+
+    fn check_tags(&self, tags: &Option<Vec<String>>) -> Result<(), ApiError> {
+        check_list("tags", tags, self.tag_count, self.tag_length)
+    }
+
+    fn check_key_endpoints(&self, endpoints: &Option<Vec<KeyEndpoint>>) -> Result<(), ApiError> {
+        let Some(endpoints) = endpoints else { return Ok(()) };
+        let paths = Some(endpoints.iter().map(|e| e.path.clone()).collect());
+        check_list("key_endpoints", &paths, self.key_endpoint_count, self.key_endpoint_path)?;
+        for endpoint in endpoints {
+            check("key_endpoints", Some(&endpoint.description), self.key_endpoint_description)?;
+        }
+        Ok(())
+    }
+}
+
+/// Limit a list field to `max_items` entries of at most `max_length` characters each.
+fn check_list(field: &str, items: &Option<Vec<String>>, max_items: usize, max_length: usize) -> Result<(), ApiError> {
+    let items = match items {
+        Some(i) => i,
+        None => return Ok(()),
+    };
+    if items.len() > max_items {
+        return Err(ApiError::new(
+            ErrorCode::FieldTooLong,
+            format!("At most {} {} are allowed", max_items, field),
+        )
+        .with("field", field)
+        .with("max", max_items)
+        .with("actual", items.len()));
+    }
+    for item in items {
+        check(field, Some(item), max_length)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn check(field: &str, value: Option<&String>, max: usize) -> Result<(), ApiError> {
+    let len = value.map(|v| v.chars().count()).unwrap_or(0);
+    if len > max {
+        return Err(ApiError::new(
+            ErrorCode::FieldTooLong,
+            format!("'{}' must be at most {} characters", field, max),
+        )
+        .with("field", field)
+        .with("max", max)
+        .with("actual", len));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_characters_not_bytes() {
+        let s = "é".repeat(10);
+        assert!(check("name", Some(&s), 10).is_ok());
+        let err = check("name", Some(&s), 9).unwrap_err().body();
+        assert_eq!(err["error"], "FIELD_TOO_LONG");
+        assert_eq!(err["field"], "name");
+        assert_eq!(err["actual"], 10);
+    }
+
+    #[test]
+    fn limits_tag_count_and_length() {
+        let limits = FieldLimits {
+            tag_count: 2,
+            tag_length: 3,
+            ..Default::default()
+        };
+        assert!(limits.check_tags(&Some(vec!["a".into(), "b".into()])).is_ok());
+        assert!(limits.check_tags(&Some(vec!["a".into(), "b".into(), "c".into()])).is_err());
+        assert!(limits.check_tags(&Some(vec!["long".into()])).is_err());
+    }
+}
@@ -1,21 +1,41 @@
 use hmac::{Hmac, Mac};
+use rocket::futures::stream::{self, StreamExt};
 use sha2::Sha256;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
 
 type HmacSha256 = Hmac<Sha256>;
 
 /// Shared database connection for async webhook delivery (separate from main).
 pub type WebhookDb = Arc<Mutex<rusqlite::Connection>>;
 
-/// Open a separate database connection for async webhook delivery.
-pub fn init_webhook_db() -> WebhookDb {
-    let db_path = std::env::var("DATABASE_PATH").unwrap_or_else(|_| "app_directory.db".to_string());
-    let conn = rusqlite::Connection::open(&db_path).expect("Failed to open webhook DB");
+/// Open a separate database connection for async webhook delivery
+/// and event history.
+pub fn init_webhook_db(db_path: &str) -> WebhookDb {
+    let conn = rusqlite::Connection::open(db_path).expect("Failed to open webhook DB");
     conn.execute_batch("PRAGMA journal_mode=WAL;")
         .expect("Failed to set WAL mode for webhook DB");
+    let _ = conn.busy_timeout(Duration::from_secs(2));
     Arc::new(Mutex::new(conn))
 }
 
+/// Grace period used when `WEBHOOK_SECRET_GRACE_SECS` is unset (24h).
+pub const DEFAULT_SECRET_GRACE_SECS: u64 = 86_400;
+
+/// Longest grace period a rotation may ask for (30 days).
+pub const MAX_SECRET_GRACE_SECS: u64 = 30 * 86_400;
+
+/// How long a replaced secret keeps signing deliveries after a rotation
+/// that doesn't give its own `grace_period_secs`.
+pub struct SecretGrace(pub u64);
+
+/// New random signing secret.
+pub fn generate_secret() -> String {
+    format!("whsec_{}", uuid::Uuid::new_v4().to_string().replace('-', ""))
+}
+
 /// Compute HMAC-SHA256 signature for a payload.
 fn sign_payload(secret: &str, payload: &[u8]) -> String {
     let mut mac =
@@ -25,6 +45,81 @@ fn sign_payload(secret: &str, payload: &[u8]) -> String {
     hex::encode(result.into_bytes())
 }
 
+/// `X-AppDirectory-Signature` value: one `sha256=<hex>` per secret,
+/// comma-separated, current secret first. Receivers accept the request if
+/// any signature matches a secret they hold.
+pub fn signature_header(secrets: &[&str], payload: &[u8]) -> String {
+    secrets
+        .iter()
+        .map(|secret| format!("sha256={}", sign_payload(secret, payload)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Delivery timeout for webhooks without their own `timeout_ms` when
+/// `WEBHOOK_TIMEOUT_MS` is unset.
+pub const DEFAULT_TIMEOUT_MS: u64 = 10_000;
+
+/// Bounds for a webhook's `timeout_ms`.
+pub const MIN_TIMEOUT_MS: u64 = 100;
+pub const MAX_TIMEOUT_MS: u64 = 30_000;
+
+/// Deliveries in flight at once when `WEBHOOK_DELIVERY_CONCURRENCY` is unset.
+pub const DEFAULT_DELIVERY_CONCURRENCY: usize = 8;
+
+/// Delivery attempts kept per webhook; older ones are dropped.
+pub const DELIVERY_LOG_LIMIT: i64 = 100;
+
+/// Timeout and concurrency settings shared by every delivery. Cheaply
+/// cloneable; clones share the concurrency limit.
+#[derive(Clone)]
+pub struct DeliverySettings {
+    pub default_timeout_ms: u64,
+    pub concurrency: usize,
+    permits: Arc<Semaphore>,
+}
+
+impl DeliverySettings {
+    pub fn new(default_timeout_ms: u64, concurrency: usize) -> Self {
+        let concurrency = concurrency.max(1);
+        DeliverySettings {
+            default_timeout_ms: default_timeout_ms.clamp(MIN_TIMEOUT_MS, MAX_TIMEOUT_MS),
+            concurrency,
+            permits: Arc::new(Semaphore::new(concurrency)),
+        }
+    }
+
+    /// `WEBHOOK_TIMEOUT_MS` (default 10000) and `WEBHOOK_DELIVERY_CONCURRENCY`
+    /// (default 8).
+    pub fn from_env() -> Self {
+        fn env<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+        DeliverySettings::new(
+            env("WEBHOOK_TIMEOUT_MS").unwrap_or(DEFAULT_TIMEOUT_MS),
+            env("WEBHOOK_DELIVERY_CONCURRENCY").unwrap_or(DEFAULT_DELIVERY_CONCURRENCY),
+        )
+    }
+}
+
+impl Default for DeliverySettings {
+    fn default() -> Self {
+        DeliverySettings::new(DEFAULT_TIMEOUT_MS, DEFAULT_DELIVERY_CONCURRENCY)
+    }
+}
+
+/// Check a requested `timeout_ms` against `MIN_TIMEOUT_MS..=MAX_TIMEOUT_MS`.
+pub fn validate_timeout(timeout_ms: u64) -> Result<(), String> {
+    if (MIN_TIMEOUT_MS..=MAX_TIMEOUT_MS).contains(&timeout_ms) {
+        Ok(())
+    } else {
+        Err(format!(
+            "timeout_ms must be between {} and {}",
+            MIN_TIMEOUT_MS, MAX_TIMEOUT_MS
+        ))
+    }
+}
+
 /// A webhook event to deliver.
 #[derive(Debug, Clone)]
 pub struct WebhookEvent {
@@ -38,16 +133,48 @@ struct WebhookTarget {
     id: String,
     url: String,
     secret: String,
+    /// Replaced secret still inside its rotation grace period.
+    previous_secret: Option<String>,
     events: Vec<String>,
+    timeout_ms: Option<u64>,
+}
+
+/// How one delivery attempt ended, as recorded in `webhook_deliveries`:
+/// `success` (2xx), `http_error` (any other status), `timeout` (no
+/// complete response within the webhook's timeout), or `network_error`
+/// (connection refused, DNS failure, ...).
+fn classify(result: &Result<reqwest::Response, reqwest::Error>) -> (&'static str, Option<i64>, Option<String>) {
+    match result {
+        Ok(resp) if resp.status().is_success() => ("success", Some(resp.status().as_u16() as i64), None),
+        Ok(resp) => (
+            "http_error",
+            Some(resp.status().as_u16() as i64),
+            Some(format!("HTTP {}", resp.status())),
+        ),
+        Err(e) if e.is_timeout() => ("timeout", None, Some("Timed out waiting for a response".to_string())),
+        Err(e) => ("network_error", None, Some(e.to_string())),
+    }
 }
 
-/// Fire-and-forget delivery of a webhook event to all matching registered webhooks.
-pub fn deliver_webhooks(db: WebhookDb, event: WebhookEvent, client: reqwest::Client) {
+/// Fire-and-forget delivery of a webhook event to all matching registered
+/// webhooks. Each request uses the webhook's `timeout_ms` (or the default)
+/// and waits for one of `settings.concurrency` slots shared by all
+/// deliveries. Every attempt is logged in `webhook_deliveries`. The
+/// returned handle finishes once every delivery has been attempted.
+pub fn deliver_webhooks(
+    db: WebhookDb,
+    event: WebhookEvent,
+    client: reqwest::Client,
+    settings: DeliverySettings,
+) -> JoinHandle<()> {
     tokio::spawn(async move {
         let targets = {
             let conn = db.lock().unwrap();
             let mut stmt = match conn.prepare(
-                "SELECT id, url, secret, events FROM webhooks WHERE active = 1 AND failure_count < 10",
+                "SELECT id, url, secret, events,
+                        CASE WHEN previous_secret_expires_at > datetime('now') THEN previous_secret END,
+                        timeout_ms
+                 FROM webhooks WHERE active = 1 AND failure_count < 10",
             ) {
                 Ok(s) => s,
                 Err(_) => return,
@@ -60,7 +187,9 @@ pub fn deliver_webhooks(db: WebhookDb, event: WebhookEvent, client: reqwest::Cli
                     id: row.get(0)?,
                     url: row.get(1)?,
                     secret: row.get(2)?,
+                    previous_secret: row.get(4)?,
                     events,
+                    timeout_ms: row.get(5)?,
                 })
             })
             .ok()
@@ -68,6 +197,11 @@ pub fn deliver_webhooks(db: WebhookDb, event: WebhookEvent, client: reqwest::Cli
             .unwrap_or_default()
         };
 
+        // Filter: if webhook has specific events configured, check match
+        let targets: Vec<WebhookTarget> = targets
+            .into_iter()
+            .filter(|t| t.events.is_empty() || t.events.contains(&event.event))
+            .collect();
         if targets.is_empty() {
             return;
         }
@@ -79,44 +213,148 @@ pub fn deliver_webhooks(db: WebhookDb, event: WebhookEvent, client: reqwest::Cli
         });
         let payload_bytes = serde_json::to_vec(&payload).unwrap_or_default();
 
-        for target in targets {
-            // Filter: if webhook has specific events configured, check match
-            if !target.events.is_empty() && !target.events.contains(&event.event) {
-                continue;
-            }
-
-            let signature = sign_payload(&target.secret, &payload_bytes);
-
-            let result = client
-                .post(&target.url)
-                .header("Content-Type", "application/json")
-                .header("X-AppDirectory-Signature", format!("sha256={}", signature))
-                .header("X-AppDirectory-Event", &event.event)
-                .body(payload_bytes.clone())
-                .timeout(std::time::Duration::from_secs(10))
-                .send()
-                .await;
-
-            let success = match result {
-                Ok(resp) => resp.status().is_success(),
-                Err(_) => false,
-            };
+        stream::iter(targets)
+            .for_each_concurrent(None, |target| {
+                let (db, client, settings) = (&db, &client, &settings);
+                let (event, payload_bytes) = (&event.event, &payload_bytes);
+                async move {
+                    let Ok(_permit) = settings.permits.acquire().await else {
+                        return;
+                    };
 
-            // Update stats
-            let db_ref = db.clone();
-            let webhook_id = target.id.clone();
-            let conn = db_ref.lock().unwrap();
-            if success {
-                let _ = conn.execute(
-                    "UPDATE webhooks SET failure_count = 0, last_triggered_at = datetime('now') WHERE id = ?1",
-                    rusqlite::params![webhook_id],
-                );
-            } else {
-                let _ = conn.execute(
-                    "UPDATE webhooks SET failure_count = failure_count + 1, last_triggered_at = datetime('now') WHERE id = ?1",
-                    rusqlite::params![webhook_id],
-                );
-            }
-        }
-    });
+                    let mut secrets = vec![target.secret.as_str()];
+                    secrets.extend(target.previous_secret.as_deref());
+                    let signature = signature_header(&secrets, payload_bytes);
+                    let timeout_ms = target.timeout_ms.unwrap_or(settings.default_timeout_ms);
+
+                    let start = std::time::Instant::now();
+                    let result = client
+                        .post(&target.url)
+                        .header("Content-Type", "application/json")
+                        .header("X-AppDirectory-Signature", signature)
+                        .header("X-AppDirectory-Event", event)
+                        .body(payload_bytes.clone())
+                        .timeout(Duration::from_millis(timeout_ms))
+                        .send()
+                        .await;
+                    let duration_ms = start.elapsed().as_millis() as i64;
+                    let (outcome, status_code, error_message) = classify(&result);
+
+                    // Update stats and the delivery log
+                    let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+                    if outcome == "success" {
+                        let _ = conn.execute(
+                            "UPDATE webhooks SET failure_count = 0, last_triggered_at = datetime('now') WHERE id = ?1",
+                            rusqlite::params![target.id],
+                        );
+                    } else {
+                        let _ = conn.execute(
+                            "UPDATE webhooks SET failure_count = failure_count + 1, last_triggered_at = datetime('now') WHERE id = ?1",
+                            rusqlite::params![target.id],
+                        );
+                    }
+                    let _ = conn.execute(
+                        "INSERT INTO webhook_deliveries (webhook_id, event, outcome, status_code, error_message, duration_ms, timeout_ms)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                        rusqlite::params![target.id, event, outcome, status_code, error_message, duration_ms, timeout_ms as i64],
+                    );
+                    let _ = conn.execute(
+                        "DELETE FROM webhook_deliveries WHERE webhook_id = ?1 AND id NOT IN
+                           (SELECT id FROM webhook_deliveries WHERE webhook_id = ?1 ORDER BY id DESC LIMIT ?2)",
+                        rusqlite::params![target.id, DELIVERY_LOG_LIMIT],
+                    );
+                }
+            })
+            .await;
+    })
+}
+
+/// Events sent to an app's owner notification URL.
+pub const OWNER_EVENTS: &[&str] = &["review.submitted", "app.health_changed", "app.deprecated"];
+
+/// Consecutive failures after which an owner notification URL stops
+/// receiving deliveries until it is registered again.
+pub const OWNER_MAX_FAILURES: i64 = 10;
+
+/// Fire-and-forget delivery of an `OWNER_EVENTS` event to the notification
+/// URL registered on the app it concerns, signed with that app's secret.
+/// Shares the delivery timeout and concurrency limit with admin webhooks;
+/// the outcome is kept on the app.
+pub fn deliver_owner_notification(
+    db: WebhookDb,
+    app_id: String,
+    event: WebhookEvent,
+    client: reqwest::Client,
+    settings: DeliverySettings,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let target: Option<(String, String)> = {
+            let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+            conn.query_row(
+                "SELECT notify_url, notify_secret FROM apps
+                 WHERE id = ?1 AND notify_url IS NOT NULL AND notify_failure_count < ?2",
+                rusqlite::params![app_id, OWNER_MAX_FAILURES],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .ok()
+        };
+        let Some((url, secret)) = target else { return };
+
+        let payload = serde_json::json!({
+            "event": event.event,
+            "data": event.data,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        });
+        let payload_bytes = serde_json::to_vec(&payload).unwrap_or_default();
+        let Ok(_permit) = settings.permits.acquire().await else {
+            return;
+        };
+        let result = client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .header("X-AppDirectory-Signature", signature_header(&[&secret], &payload_bytes))
+            .header("X-AppDirectory-Event", &event.event)
+            .body(payload_bytes)
+            .timeout(Duration::from_millis(settings.default_timeout_ms))
+            .send()
+            .await;
+        let (outcome, _, _) = classify(&result);
+
+        let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = conn.execute(
+            "UPDATE apps SET notify_last_outcome = ?1, notify_last_delivered_at = datetime('now'),
+                notify_failure_count = CASE WHEN ?1 = 'success' THEN 0 ELSE notify_failure_count + 1 END
+             WHERE id = ?2",
+            rusqlite::params![outcome, app_id],
+        );
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_with_every_active_secret() {
+        let payload = br#"{"event":"app.approved"}"#;
+        let single = signature_header(&["whsec_new"], payload);
+        assert_eq!(single, format!("sha256={}", sign_payload("whsec_new", payload)));
+
+        let dual = signature_header(&["whsec_new", "whsec_old"], payload);
+        let parts: Vec<&str> = dual.split(',').collect();
+        assert_eq!(parts[0], single);
+        assert_eq!(parts[1], format!("sha256={}", sign_payload("whsec_old", payload)));
+    }
+
+    #[test]
+    fn bounds_timeouts() {
+        assert!(validate_timeout(MIN_TIMEOUT_MS).is_ok());
+        assert!(validate_timeout(MAX_TIMEOUT_MS).is_ok());
+        assert!(validate_timeout(MIN_TIMEOUT_MS - 1).is_err());
+        assert!(validate_timeout(MAX_TIMEOUT_MS + 1).is_err());
+
+        let settings = DeliverySettings::new(0, 0);
+        assert_eq!(settings.default_timeout_ms, MIN_TIMEOUT_MS);
+        assert_eq!(settings.concurrency, 1);
+    }
 }
@@ -24,6 +24,12 @@ fn setup_client_with_path() -> (Client, String, String) {
     (client, test_key, db_path)
 }
 
+/// Socket address for a simulated client. Client IPs come from the peer
+/// address, not from headers, unless `TRUSTED_IP_HEADER` is set.
+fn client_addr(ip: &str) -> std::net::SocketAddr {
+    std::net::SocketAddr::new(ip.parse().unwrap(), 8000)
+}
+
 #[test]
 fn test_health() {
     let (client, _) = setup_client();
@@ -32,6 +38,10 @@ fn test_health() {
     let body: Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
     assert_eq!(body["status"], "ok");
     assert_eq!(body["service"], "app-directory");
+    assert_eq!(body["privacy"]["privacy_mode"], false);
+    assert_eq!(body["privacy"]["viewer_ids_stored"], true);
+    assert_eq!(body["privacy"]["unique_viewers_reported"], true);
+    assert_eq!(body["privacy"]["stats_retention_days"], 180);
 }
 
 #[test]
@@ -182,6 +192,177 @@ fn test_search_apps() {
     assert_eq!(body["total"], 0);
 }
 
+#[test]
+fn test_search_terms_and_phrases() {
+    let (client, key) = setup_client();
+
+    for (name, description) in [
+        ("Weather Forecast API", "Forecasts for any city"),
+        ("Weather Radar", "Live radar imagery, no forecast"),
+        ("Stock API", "Market quotes"),
+    ] {
+        client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name,
+                "short_description": name,
+                "description": description,
+                "author_name": "Tester",
+            }).to_string())
+            .dispatch();
+    }
+    let search = |q: &str| -> Value {
+        client
+            .get(format!("/api/v1/apps/search?q={}", q))
+            .dispatch()
+            .into_json()
+            .unwrap()
+    };
+
+    // Terms are ANDed across fields, not matched as one substring
+    let body = search("weather%20forecast%20api");
+    assert_eq!(body["match"], "all");
+    assert_eq!(body["terms"], serde_json::json!(["weather", "forecast", "api"]));
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["apps"][0]["name"], "Weather Forecast API");
+    assert_eq!(body["apps"][0]["matched_terms"], serde_json::json!(["weather", "forecast", "api"]));
+
+    let body = search("radar%20forecast");
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["apps"][0]["name"], "Weather Radar");
+
+    // Quoted phrases must appear verbatim
+    let body = search("%22forecast%20api%22");
+    assert_eq!(body["terms"], serde_json::json!(["forecast api"]));
+    assert_eq!(body["total"], 1);
+    let body = search("%22radar%20forecast%22");
+    assert_eq!(body["total"], 0);
+    assert_eq!(body["match"], "all");
+
+    // No app has every term: fall back to any term
+    let body = search("stock%20radar");
+    assert_eq!(body["match"], "any");
+    assert_eq!(body["total"], 2);
+    for app in body["apps"].as_array().unwrap() {
+        assert_eq!(app["matched_terms"].as_array().unwrap().len(), 1);
+    }
+}
+
+#[test]
+fn test_search_facets() {
+    let (client, key, db_path) = setup_client_with_path();
+
+    for (name, protocol, category) in [
+        ("Facet Alpha", "rest", "data"),
+        ("Facet Beta", "rest", "developer-tools"),
+        ("Facet Gamma", "mcp", "data"),
+        ("Unrelated", "mcp", "data"),
+    ] {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name,
+                "short_description": name,
+                "description": name,
+                "author_name": "Tester",
+                "protocol": protocol,
+                "category": category,
+            }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+    }
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute_batch(
+        "UPDATE apps SET is_verified = 1, last_health_status = 'healthy' WHERE slug = 'facet-alpha';
+         UPDATE apps SET last_health_status = 'unreachable' WHERE slug = 'facet-gamma';",
+    )
+    .unwrap();
+
+    let body: Value = client
+        .get("/api/v1/apps/search?q=facet&per_page=1")
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(body["total"], 3);
+    assert_eq!(body["apps"].as_array().unwrap().len(), 1);
+    let facets = &body["facets"];
+    assert_eq!(facets["category"]["data"], 2);
+    assert_eq!(facets["category"]["developer-tools"], 1);
+    assert_eq!(facets["protocol"]["rest"], 2);
+    assert_eq!(facets["protocol"]["mcp"], 1);
+    assert_eq!(facets["health_status"]["healthy"], 1);
+    assert_eq!(facets["health_status"]["unreachable"], 1);
+    assert_eq!(facets["health_status"]["unknown"], 1);
+    assert_eq!(facets["verified"]["verified"], 1);
+    assert_eq!(facets["verified"]["unverified"], 2);
+
+    // Facets follow the active filters
+    let body: Value = client
+        .get("/api/v1/apps/search?q=facet&protocol=rest")
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(body["total"], 2);
+    assert!(body["facets"]["protocol"]["mcp"].is_null());
+    assert_eq!(body["facets"]["category"]["data"], 1);
+}
+
+#[test]
+fn test_sparse_fieldsets() {
+    let (client, key) = setup_client();
+    submit_simple_app(&client, &key, "Fieldset Picker");
+
+    let body: Value = client
+        .get("/api/v1/apps?fields=name,slug,avg_rating")
+        .dispatch()
+        .into_json()
+        .unwrap();
+    let app = body["apps"][0].as_object().unwrap();
+    assert_eq!(app.keys().collect::<Vec<_>>(), vec!["avg_rating", "name", "slug"]);
+    assert_eq!(app["name"], "Fieldset Picker");
+    assert_eq!(body["total"], 1);
+
+    let body: Value = client
+        .get("/api/v1/apps?fields=id,tags,is_featured,badges")
+        .dispatch()
+        .into_json()
+        .unwrap();
+    let app = &body["apps"][0];
+    assert!(app["id"].is_string());
+    assert!(app["tags"].is_array());
+    assert_eq!(app["is_featured"], false);
+    assert!(app["badges"].is_array());
+    assert!(app.get("description").is_none());
+
+    let body: Value = client
+        .get("/api/v1/apps/search?q=picker&fields=slug,matched_terms")
+        .dispatch()
+        .into_json()
+        .unwrap();
+    let app = body["apps"][0].as_object().unwrap();
+    assert_eq!(app.len(), 2);
+    assert_eq!(app["slug"], "fieldset-picker");
+    assert_eq!(app["matched_terms"], serde_json::json!(["picker"]));
+    assert_eq!(body["facets"]["protocol"]["rest"], 1);
+
+    let resp = client.get("/api/v1/apps?fields=name,edit_token_hash").dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_FIELD");
+    assert_eq!(body["field"], "edit_token_hash");
+    assert!(body["allowed"].as_array().unwrap().contains(&serde_json::json!("category")));
+
+    // matched_terms only exists on search
+    let resp = client.get("/api/v1/apps?fields=matched_terms").dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+}
+
 #[test]
 fn test_submit_and_get_review() {
     let (client, key) = setup_client();
@@ -434,6 +615,43 @@ fn test_rate_limiting() {
     assert_eq!(response.status(), Status::TooManyRequests);
 }
 
+#[test]
+fn test_rate_limit_warning_event() {
+    let (client, _, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let key = app_directory::auth::create_api_key(&conn, "warn-test", false, Some(5));
+    drop(conn);
+
+    let bus = client.rocket().state::<app_directory::events::EventBus>().unwrap();
+    let mut rx = bus.subscribe();
+
+    let get_mine = || {
+        client
+            .get("/api/v1/apps/mine")
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch()
+    };
+    for _ in 0..3 {
+        get_mine();
+    }
+    assert!(rx.try_recv().is_err(), "no warning below 80%");
+
+    // 4th of 5 requests crosses 80%
+    get_mine();
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.event, "rate_limit.warning");
+    assert_eq!(event.data["name"], "warn-test");
+    assert_eq!(event.data["limit"], 5);
+    assert_eq!(event.data["remaining"], 1);
+    assert!(event.data["reset_secs"].as_u64().unwrap() <= 60);
+    assert!(event.data["reset_at"].is_string());
+
+    // Only once per window, including once the key is throttled
+    get_mine();
+    assert_eq!(get_mine().status(), Status::TooManyRequests);
+    assert!(rx.try_recv().is_err());
+}
+
 #[test]
 fn test_rate_limit_headers_present() {
     let (client, key) = setup_client();
@@ -454,6 +672,280 @@ fn test_rate_limit_headers_present() {
     assert!(response.headers().get_one("X-RateLimit-Reset").is_some());
 }
 
+#[test]
+fn test_rate_limit_info() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let key = app_directory::auth::create_api_key(&conn, "pacer", false, Some(3));
+
+    let info = |key: Option<&str>| -> Value {
+        let mut req = client.get("/api/v1/rate-limit");
+        if let Some(key) = key {
+            req = req.header(Header::new("X-API-Key", key.to_string()));
+        }
+        let response = req.dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        response.into_json().unwrap()
+    };
+    let spend = || {
+        client
+            .get("/api/v1/apps/mine")
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch()
+            .status()
+    };
+
+    let body = info(None);
+    assert_eq!(body["class"], "anonymous");
+    assert!(body["limit"].is_null());
+
+    // Asking doesn't use up the window
+    for _ in 0..5 {
+        let body = info(Some(&key));
+        assert_eq!(body["class"], "standard");
+        assert_eq!(body["limit"], 3);
+        assert_eq!(body["remaining"], 3);
+        assert_eq!(body["policy"], "3;w=60");
+        assert!(body["reset_at"].is_string());
+    }
+    spend();
+    assert_eq!(info(Some(&key))["remaining"], 2);
+
+    // Still answers once the key is out of requests
+    spend();
+    spend();
+    assert_eq!(spend(), Status::TooManyRequests);
+    let response = client.get("/api/v1/rate-limit").header(Header::new("X-API-Key", key.clone())).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("X-RateLimit-Remaining"), Some("0"));
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["remaining"], 0);
+    assert!(body["reset_secs"].as_u64().unwrap() <= 60);
+
+    assert_eq!(info(Some(&admin))["class"], "admin");
+    conn.execute("UPDATE api_keys SET throttled_until = datetime('now', '+10 minutes') WHERE name = 'pacer'", [])
+        .unwrap();
+    let body = info(Some(&key));
+    assert_eq!(body["class"], "throttled");
+    assert!(body["throttled_until"].is_string());
+    assert_eq!(body["limit"], 1);
+}
+
+#[test]
+fn test_rate_limit_burst_and_exempt_routes() {
+    let (client, admin_key) = setup_client();
+
+    // Only admins hand out bursts and exemptions
+    let response = client
+        .post("/api/v1/keys")
+        .header(ContentType::JSON)
+        .body(r#"{"name": "sneaky", "burst": 50}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let response = client
+        .post("/api/v1/keys")
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", admin_key.clone()))
+        .body(r#"{"name": "bad", "exempt_routes": ["apps/mine"]}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_RATE_LIMIT");
+    assert_eq!(body["field"], "exempt_routes");
+
+    let response = client
+        .post("/api/v1/keys")
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", admin_key.clone()))
+        .body(r#"{"name": "bursty", "rate_limit": 2, "exempt_routes": ["get /api/v1/apps/mine"]}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Created);
+    let body: Value = response.into_json().unwrap();
+    let key_id = body["id"].as_str().unwrap().to_string();
+    let key = body["api_key"].as_str().unwrap().to_string();
+
+    // Exempt requests aren't counted and carry no rate limit headers
+    for _ in 0..4 {
+        let response = client
+            .get("/api/v1/apps/mine")
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.headers().get_one("X-RateLimit-Limit").is_none());
+    }
+
+    // Give the key a burst of 1 on top of its limit of 2
+    let response = client
+        .patch(format!("/api/v1/keys/{key_id}"))
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", admin_key.clone()))
+        .body(r#"{"burst": 1}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["burst"], 1);
+    assert_eq!(body["rate_limit"], 2);
+    assert_eq!(body["exempt_routes"], serde_json::json!(["GET /api/v1/apps/mine"]));
+
+    let hit = || {
+        client
+            .get("/api/v1/webhooks")
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch()
+    };
+    for _ in 0..2 {
+        let response = hit();
+        assert_ne!(response.status(), Status::TooManyRequests);
+        assert_eq!(response.headers().get_one("X-RateLimit-Burst-Remaining"), Some("1"));
+    }
+    // Third request spends the burst token
+    let response = hit();
+    assert_ne!(response.status(), Status::TooManyRequests);
+    assert_eq!(response.headers().get_one("X-RateLimit-Remaining"), Some("0"));
+    assert_eq!(response.headers().get_one("X-RateLimit-Burst-Remaining"), Some("0"));
+    let policy = response.headers().get_one("X-RateLimit-Policy").unwrap();
+    assert!(policy.starts_with("2;w=") && policy.ends_with(";burst=1"), "{policy}");
+    assert_eq!(hit().status(), Status::TooManyRequests);
+
+    // Settings show up in the key list; other keys get a plain policy
+    let response = client
+        .get("/api/v1/keys")
+        .header(Header::new("X-API-Key", admin_key.clone()))
+        .dispatch();
+    let policy = response.headers().get_one("X-RateLimit-Policy").unwrap().to_string();
+    assert!(!policy.contains("burst"));
+    let body: Value = response.into_json().unwrap();
+    let listed = body["keys"].as_array().unwrap().iter().find(|k| k["id"] == key_id.as_str()).unwrap();
+    assert_eq!(listed["burst"], 1);
+
+    let response = client
+        .patch(format!("/api/v1/keys/{key_id}"))
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", admin_key))
+        .body(r#"{"burst": -1}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn test_per_key_page_sizes() {
+    let (client, admin_key) = setup_client();
+    for i in 0..5 {
+        submit_simple_app(&client, &admin_key, &format!("Paged {i}"));
+    }
+
+    // Anonymous callers get the instance limits, and larger requests are capped
+    let body: Value = client.get("/api/v1/apps?per_page=500").dispatch().into_json().unwrap();
+    assert_eq!(body["per_page"], 100);
+    assert_eq!(body["page_limits"], serde_json::json!({"default_per_page": 20, "max_per_page": 100}));
+
+    let response = client
+        .post("/api/v1/keys")
+        .header(ContentType::JSON)
+        .body(r#"{"name": "greedy", "max_page_size": 500}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let response = client
+        .post("/api/v1/keys")
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", admin_key.clone()))
+        .body(r#"{"name": "too big", "max_page_size": 5000}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_PAGE_SIZE");
+    assert_eq!(body["field"], "max_page_size");
+
+    let response = client
+        .post("/api/v1/keys")
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", admin_key.clone()))
+        .body(r#"{"name": "scraper", "default_page_size": 2, "max_page_size": 500}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Created);
+    let body: Value = response.into_json().unwrap();
+    let key_id = body["id"].as_str().unwrap().to_string();
+    let scraper = body["api_key"].as_str().unwrap().to_string();
+
+    let list = |url: &str| -> Value {
+        client.get(url.to_string()).header(Header::new("X-API-Key", scraper.clone())).dispatch().into_json().unwrap()
+    };
+    let body = list("/api/v1/apps");
+    assert_eq!(body["per_page"], 2);
+    assert_eq!(body["apps"].as_array().unwrap().len(), 2);
+    assert_eq!(body["page_limits"]["max_per_page"], 500);
+    assert_eq!(list("/api/v1/apps?per_page=300")["per_page"], 300);
+    assert_eq!(list("/api/v1/apps?per_page=900")["per_page"], 500);
+    let body = list("/api/v1/apps/search?q=paged&per_page=250");
+    assert_eq!(body["per_page"], 250);
+    assert_eq!(body["page_limits"]["default_per_page"], 2);
+
+    // 0 falls back to the instance limit
+    let response = client
+        .patch(format!("/api/v1/keys/{key_id}"))
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", admin_key.clone()))
+        .body(r#"{"default_page_size": 0}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["default_page_size"], Value::Null);
+    assert_eq!(body["max_page_size"], 500);
+    assert_eq!(list("/api/v1/apps")["per_page"], 20);
+}
+
+#[test]
+fn test_admin_edits_key() {
+    let (client, admin_key, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let member = app_directory::auth::create_api_key(&conn, "member", false, Some(1));
+    let member_id: String = conn.query_row("SELECT id FROM api_keys WHERE name = 'member'", [], |r| r.get(0)).unwrap();
+    let admin_id: String = conn
+        .query_row("SELECT id FROM api_keys WHERE name = 'test-admin'", [], |r| r.get(0))
+        .unwrap();
+    // Leave the test admin as the only other admin
+    conn.execute("UPDATE api_keys SET revoked = 1 WHERE is_admin = 1 AND id != ?1", rusqlite::params![admin_id]).unwrap();
+    let patch = |key: &str, id: &str, body: &str| {
+        client
+            .patch(format!("/api/v1/keys/{id}"))
+            .header(ContentType::JSON)
+            .header(Header::new("X-API-Key", key.to_string()))
+            .body(body)
+            .dispatch()
+    };
+    let list_keys = || client.get("/api/v1/keys").header(Header::new("X-API-Key", member.clone())).dispatch();
+
+    assert_eq!(patch(&member, &member_id, r#"{"is_admin": true}"#).status(), Status::Forbidden);
+    assert_eq!(list_keys().status(), Status::TooManyRequests);
+
+    // A new limit starts a fresh window; promotion applies on the next request
+    let resp = patch(&admin_key, &member_id, r#"{"name": "ops bot", "is_admin": true, "rate_limit": 5}"#);
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!((body["name"].as_str(), body["is_admin"].as_bool(), body["rate_limit"].as_i64()), (Some("ops bot"), Some(true), Some(5)));
+    assert_eq!(list_keys().status(), Status::Ok);
+
+    let details: String = conn
+        .query_row("SELECT details FROM audit_log WHERE action = 'key.update'", [], |r| r.get(0))
+        .unwrap();
+    let details: Value = serde_json::from_str(&details).unwrap();
+    assert_eq!(details["key_id"], member_id.as_str());
+    assert_eq!(details["changes"]["is_admin"], serde_json::json!({"from": false, "to": true}));
+    assert_eq!(details["changes"]["name"]["from"], "member");
+    assert!(details["changes"].get("burst").is_none());
+
+    // Someone has to stay an admin
+    assert_eq!(patch(&member, &admin_id, r#"{"is_admin": false}"#).status(), Status::Ok);
+    let resp = patch(&member, &member_id, r#"{"is_admin": false}"#);
+    assert_eq!(resp.status(), Status::Conflict);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "LAST_ADMIN");
+
+    assert_eq!(patch(&member, &member_id, r#"{"name": "  "}"#).status(), Status::BadRequest);
+    assert_eq!(patch(&member, "missing", r#"{"name": "x"}"#).status(), Status::NotFound);
+}
+
 #[test]
 fn test_badges_default_false() {
     let (client, key) = setup_client();
@@ -545,6 +1037,99 @@ fn test_admin_set_badges() {
     assert_eq!(body["is_verified"], true);
 }
 
+#[test]
+fn test_custom_badges() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let user = app_directory::auth::create_api_key(&conn, "user", false, Some(100));
+    drop(conn);
+
+    let create = |key: &str, body: Value| {
+        client.post("/api/v1/badges")
+            .header(Header::new("X-API-Key", key.to_string()))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch()
+    };
+    let resp = create(&admin, serde_json::json!({ "slug": "staff-pick", "label": "Staff pick", "color": "#1A7F37" }));
+    assert_eq!(resp.status(), Status::Created);
+    let badge: Value = resp.into_json().unwrap();
+    assert_eq!(badge["color"], "#1a7f37");
+    assert_eq!(create(&admin, serde_json::json!({ "slug": "open-source", "label": "Open source" })).status(), Status::Created);
+
+    assert_eq!(create(&user, serde_json::json!({ "slug": "new", "label": "New" })).status(), Status::Forbidden);
+    let resp = create(&admin, serde_json::json!({ "slug": "staff-pick", "label": "Again" }));
+    assert_eq!(resp.status(), Status::Conflict);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "BADGE_EXISTS");
+    let resp = create(&admin, serde_json::json!({ "slug": "Bad Slug", "label": "Bad" }));
+    assert_eq!(resp.status(), Status::BadRequest);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_BADGE");
+    assert_eq!(body["field"], "slug");
+    let resp = create(&admin, serde_json::json!({ "slug": "red", "label": "Red", "color": "red" }));
+    assert_eq!(resp.into_json::<Value>().unwrap()["field"], "color");
+
+    let created: Value = client.post("/api/v1/apps")
+        .header(Header::new("X-API-Key", admin.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Badged App","short_description":"s","description":"d","author_name":"a"}"#)
+        .dispatch()
+        .into_json()
+        .unwrap();
+    let app_id = created["app_id"].as_str().unwrap().to_string();
+
+    let resp = client.post(format!("/api/v1/apps/{}/badges/staff-pick", app_id))
+        .header(Header::new("X-API-Key", user.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Forbidden);
+    for slug in ["staff-pick", "open-source", "staff-pick"] {
+        let resp = client.post(format!("/api/v1/apps/{}/badges/{}", app_id, slug))
+            .header(Header::new("X-API-Key", admin.clone()))
+            .dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+    }
+    let resp = client.post(format!("/api/v1/apps/{}/badges/missing", app_id))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+
+    let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+    let badges = app["badges"].as_array().unwrap();
+    assert_eq!(badges.len(), 2);
+    assert_eq!(badges[0]["slug"], "staff-pick");
+    assert_eq!(badges[0]["label"], "Staff pick");
+    let list: Value = client.get("/api/v1/apps").dispatch().into_json().unwrap();
+    assert_eq!(list["apps"][0]["badges"].as_array().unwrap().len(), 2);
+
+    // Edits show up on every app carrying the badge
+    let resp = client.patch("/api/v1/badges/staff-pick")
+        .header(Header::new("X-API-Key", admin.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"label":"Editor's pick"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(app["badges"][0]["label"], "Editor's pick");
+
+    let resp = client.delete(format!("/api/v1/apps/{}/badges/staff-pick", app_id))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["badges"].as_array().unwrap().len(), 1);
+
+    let listed: Value = client.get("/api/v1/badges").dispatch().into_json().unwrap();
+    assert_eq!(listed["badges"][0]["slug"], "open-source");
+    assert_eq!(listed["badges"][0]["app_count"], 1);
+
+    let resp = client.delete("/api/v1/badges/open-source")
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch();
+    assert_eq!(resp.into_json::<Value>().unwrap()["removed_from_apps"], 1);
+    let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(app["badges"], serde_json::json!([]));
+}
+
 #[test]
 fn test_non_admin_cannot_set_badges() {
     let (client, admin_key) = setup_client();
@@ -665,6 +1250,75 @@ fn test_filter_featured_apps() {
     assert_eq!(body["total"], 3);
 }
 
+#[test]
+fn test_scheduled_featured_window() {
+    let (client, key, db_path) = setup_client_with_path();
+    let app_id = submit_simple_app(&client, &key, "Spotlight");
+    let bus = client.rocket().state::<app_directory::events::EventBus>().unwrap();
+    let mut rx = bus.subscribe();
+
+    let patch = |body: serde_json::Value| {
+        client
+            .patch(format!("/api/v1/apps/{app_id}"))
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch()
+    };
+    let get_app = || -> Value {
+        client
+            .get(format!("/api/v1/apps/{app_id}"))
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch()
+            .into_json()
+            .unwrap()
+    };
+    let in_secs = |secs: i64| (chrono::Utc::now() + chrono::Duration::seconds(secs)).to_rfc3339();
+
+    // Windows must end in the future and after they start
+    let response = patch(serde_json::json!({ "featured_until": in_secs(-60) }));
+    assert_eq!(response.status(), Status::BadRequest);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_FEATURE_WINDOW");
+    let response = patch(serde_json::json!({ "featured_from": in_secs(600), "featured_until": in_secs(300) }));
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = patch(serde_json::json!({ "featured_from": "tomorrow" }));
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // A future window is stored but doesn't feature the app yet
+    assert_eq!(patch(serde_json::json!({ "featured_from": in_secs(3600) })).status(), Status::Ok);
+    let app = get_app();
+    assert_eq!(app["is_featured"], false);
+    assert!(app["featured_from"].is_string());
+
+    // A window that has already started applies immediately
+    let response = patch(serde_json::json!({ "featured_from": in_secs(-60), "featured_until": in_secs(2) }));
+    assert_eq!(response.status(), Status::Ok);
+    let app = get_app();
+    assert_eq!(app["is_featured"], true);
+    assert!(app["featured_from"].is_null());
+    assert!(app["featured_until"].is_string());
+    let featured = std::iter::from_fn(|| rx.try_recv().ok()).find(|e| e.event == "app.featured").unwrap();
+    assert_eq!(featured.data["app_id"], app_id.as_str());
+    assert_eq!(featured.data["scheduled"], true);
+
+    // Once the window ends the scheduler unfeatures it
+    std::thread::sleep(std::time::Duration::from_millis(2100));
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let bus = app_directory::events::EventBus::new();
+    let mut rx = bus.subscribe();
+    assert_eq!(app_directory::scheduler::apply_featured_schedule(&conn, &bus), (0, 1));
+    let unfeatured = rx.try_recv().unwrap();
+    assert_eq!(unfeatured.event, "app.unfeatured");
+    assert_eq!(unfeatured.data["app_id"], app_id.as_str());
+    let app = get_app();
+    assert_eq!(app["is_featured"], false);
+    assert!(app["featured_until"].is_null());
+
+    // Nothing left to do on the next run
+    assert_eq!(app_directory::scheduler::apply_featured_schedule(&conn, &bus), (0, 0));
+}
+
 // === Health Check Tests ===
 
 #[test]
@@ -739,22 +1393,281 @@ fn test_health_check_no_url() {
 }
 
 #[test]
-fn test_health_check_not_found() {
-    let (client, key) = setup_client();
-
-    let response = client
-        .post("/api/v1/apps/nonexistent-id/health-check")
-        .header(Header::new("X-API-Key", key))
+fn test_health_changed_event_on_transition() {
+    let (client, key, db_path) = setup_client_with_path();
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Flaky","short_description":"Down","description":"Nothing listens here","author_name":"Tester","api_url":"http://127.0.0.1:9/api"}"#)
         .dispatch();
-    assert_eq!(response.status(), Status::NotFound);
+    let body: Value = resp.into_json().unwrap();
+    let app_id = body["app_id"].as_str().unwrap().to_string();
+
+    let bus = client.rocket().state::<app_directory::events::EventBus>().unwrap();
+    let mut rx = bus.subscribe();
+    let check = || {
+        let resp = client
+            .post(format!("/api/v1/apps/{}/health-check", app_id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+    };
+    let changes = |rx: &mut tokio::sync::broadcast::Receiver<app_directory::events::AppEvent>| {
+        let mut found = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if event.event == "app.health_changed" {
+                found.push(event.data);
+            }
+        }
+        found
+    };
+
+    // First check finds it down
+    check();
+    let found = changes(&mut rx);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0]["app_id"], app_id.as_str());
+    assert!(found[0]["previous_status"].is_null());
+    assert_eq!(found[0]["status"], "unreachable");
+    assert_eq!(found[0]["transition"], "unreachable");
+    assert!(found[0]["response_time_ms"].is_i64());
+
+    // Same status again: no transition
+    check();
+    assert!(changes(&mut rx).is_empty());
+
+    // Healthy -> unreachable
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute("UPDATE apps SET last_health_status = 'healthy' WHERE id = ?1", rusqlite::params![app_id]).unwrap();
+    check();
+    let found = changes(&mut rx);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0]["previous_status"], "healthy");
+    assert_eq!(found[0]["transition"], "unreachable");
 }
 
 #[test]
-fn test_health_history_empty() {
+fn test_health_expectation_degraded() {
     let (client, key) = setup_client();
-
-    // Submit an app
-    let response = client
+    let url = serve_static(r#"{"status":"ok","db":"down"}"#);
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": "Body Checked",
+            "short_description": "Reports its own db",
+            "description": "Health endpoint answers 200 even when its db is down",
+            "author_name": "Tester",
+            "api_url": url,
+        }).to_string())
+        .dispatch();
+    let app_id = resp.into_json::<Value>().unwrap()["app_id"].as_str().unwrap().to_string();
+
+    let expect = |expectation: &str| {
+        client
+            .patch(format!("/api/v1/apps/{}", app_id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"health_expectation":{}}}"#, expectation))
+            .dispatch()
+    };
+    let check = || -> Value {
+        client
+            .post(format!("/api/v1/apps/{}/health-check", app_id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch()
+            .into_json()
+            .unwrap()
+    };
+
+    assert_eq!(check()["status"], "healthy");
+
+    // A 200 whose body fails the expectation is degraded
+    assert_eq!(expect(r#"{"json_pointer":"/db","json_value":"up"}"#).status(), Status::Ok);
+    let result = check();
+    assert_eq!(result["status"], "degraded");
+    assert_eq!(result["status_code"], 200);
+    assert_eq!(result["error_message"], r#"/db is "down", expected "up""#);
+
+    let history: Value = client.get(format!("/api/v1/apps/{}/health", app_id)).dispatch().into_json().unwrap();
+    let mut statuses: Vec<&str> = history["checks"].as_array().unwrap().iter().map(|c| c["status"].as_str().unwrap()).collect();
+    statuses.sort();
+    assert_eq!(statuses, ["degraded", "healthy"]);
+    assert_eq!(history["expectation"]["json_pointer"], "/db");
+    let summary: Value = client.get("/api/v1/apps/health/summary").dispatch().into_json().unwrap();
+    assert_eq!(summary["degraded"], 1);
+    assert!(summary["issues"].as_array().unwrap().iter().any(|i| i["id"] == app_id.as_str()));
+    let listed: Value = client.get("/api/v1/apps?health=degraded").dispatch().into_json().unwrap();
+    assert_eq!(listed["total"], 1);
+    let uptime: Value = client.get(format!("/api/v1/apps/{}/uptime?days=1", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(uptime["buckets"][0]["degraded"], 1);
+    assert_eq!(uptime["uptime_pct"], 50.0);
+
+    // Substring match; a pointer alone only needs the value to exist
+    assert_eq!(expect(r#"{"contains":"\"status\":\"ok\""}"#).status(), Status::Ok);
+    assert_eq!(check()["status"], "healthy");
+    expect(r#"{"json_pointer":"/version"}"#);
+    assert_eq!(check()["error_message"], "No value at /version");
+
+    for invalid in [r#"{"json_value":"up"}"#, r#"{"json_pointer":"db"}"#, r#"{"contains":""}"#] {
+        let resp = expect(invalid);
+        assert_eq!(resp.status(), Status::BadRequest);
+        assert_eq!(resp.into_json::<Value>().unwrap()["error"], "INVALID_HEALTH_EXPECTATION");
+    }
+
+    // An empty object clears it
+    expect("{}");
+    let history: Value = client.get(format!("/api/v1/apps/{}/health", app_id)).dispatch().into_json().unwrap();
+    assert!(history["expectation"].is_null());
+    assert_eq!(check()["status"], "healthy");
+}
+
+#[test]
+fn test_health_check_not_found() {
+    let (client, key) = setup_client();
+
+    let response = client
+        .post("/api/v1/apps/nonexistent-id/health-check")
+        .header(Header::new("X-API-Key", key))
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn test_uptime_buckets() {
+    let (client, key, db_path) = setup_client_with_path();
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Uptime App","short_description":"Bars","description":"Status page","author_name":"Tester","api_url":"https://example.com"}"#)
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    let app_id = body["app_id"].as_str().unwrap().to_string();
+    let slug = body["slug"].as_str().unwrap().to_string();
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let rows = [
+        ("healthy", 100, "-0 days"),
+        ("healthy", 200, "-0 days"),
+        ("unhealthy", 300, "-0 days"),
+        ("unreachable", 400, "-0 days"),
+        ("healthy", 50, "-2 days"),
+        ("healthy", 999, "-45 days"),
+    ];
+    for (status, ms, ago) in rows {
+        conn.execute(
+            "INSERT INTO health_checks (id, app_id, status, response_time_ms, checked_url, checked_at)
+             VALUES (?1, ?2, ?3, ?4, 'https://example.com', datetime('now', 'start of day', ?5, '+1 minute'))",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), app_id, status, ms, ago],
+        )
+        .unwrap();
+    }
+    drop(conn);
+
+    let resp = client.get(format!("/api/v1/apps/{}/uptime", slug)).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["days"], 30);
+    assert_eq!(body["checks"], 5);
+    assert_eq!(body["uptime_pct"], 60.0);
+    let buckets = body["buckets"].as_array().unwrap();
+    assert_eq!(buckets.len(), 30);
+    let today = &buckets[29];
+    assert_eq!(today["checks"], 4);
+    assert_eq!(today["healthy"], 2);
+    assert_eq!(today["uptime_pct"], 50.0);
+    assert_eq!(today["avg_response_time_ms"], 250);
+    assert_eq!(buckets[27]["uptime_pct"], 100.0);
+    assert!(buckets[28]["uptime_pct"].is_null());
+    assert_eq!(body["latency_ms"]["p50"], 200);
+    assert_eq!(body["latency_ms"]["p99"], 400);
+    assert_eq!(body["latency_ms"]["avg"], 210);
+
+    let resp = client.get(format!("/api/v1/apps/{}/uptime?days=90", app_id)).dispatch();
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["buckets"].as_array().unwrap().len(), 90);
+    assert_eq!(body["checks"], 6);
+
+    let resp = client.get("/api/v1/apps/nope/uptime").dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+#[test]
+fn test_health_metrics() {
+    let (client, key, db_path) = setup_client_with_path();
+    let app_id = submit_simple_app(&client, &key, "Metrics App");
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let rows: [(&str, Option<i64>, &str); 8] = [
+        ("healthy", Some(100), "-10 minutes"),
+        ("healthy", Some(200), "-10 minutes"),
+        ("unhealthy", Some(300), "-10 minutes"),
+        ("healthy", Some(400), "-10 minutes"),
+        ("unreachable", None, "-5 minutes"),
+        ("healthy", Some(100), "-90 minutes"),
+        ("unhealthy", Some(500), "-30 hours"),
+        ("healthy", Some(80), "-40 days"),
+    ];
+    for (status, ms, ago) in rows {
+        conn.execute(
+            "INSERT INTO health_checks (id, app_id, status, response_time_ms, checked_url, checked_at)
+             VALUES (?1, ?2, ?3, ?4, 'https://example.com', datetime('now', ?5))",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), app_id, status, ms, ago],
+        )
+        .unwrap();
+    }
+    drop(conn);
+
+    let resp = client.get(format!("/api/v1/apps/{}/health/metrics", app_id)).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    let windows = body["windows"].as_array().unwrap();
+    assert_eq!(windows.len(), 4);
+
+    let hour = &windows[0];
+    assert_eq!(hour["window"], "1h");
+    assert_eq!(hour["checks"], 5);
+    assert_eq!(hour["failures"], 2);
+    assert_eq!(hour["failure_rate"], 0.4);
+    assert_eq!(hour["latency_ms"]["p50"], 200);
+    assert_eq!(hour["latency_ms"]["p95"], 400);
+    assert_eq!(hour["latency_ms"]["p99"], 400);
+    assert_eq!(hour["latency_ms"]["avg"], 250);
+    assert_eq!(hour["previous"]["checks"], 1);
+    assert_eq!(hour["previous"]["p95"], 100);
+    assert_eq!(hour["latency_trend"], "degrading");
+
+    let day = &windows[1];
+    assert_eq!(day["checks"], 6);
+    assert_eq!(day["previous"]["failure_rate"], 1.0);
+    assert_eq!(day["latency_trend"], "improving");
+    assert_eq!(windows[3]["checks"], 7);
+    assert_eq!(windows[3]["previous"]["checks"], 1);
+
+    let resp = client.get(format!("/api/v1/apps/{}/health/metrics?window=7d,1h", app_id)).dispatch();
+    let body: Value = resp.into_json().unwrap();
+    let names: Vec<&str> = body["windows"].as_array().unwrap().iter().map(|w| w["window"].as_str().unwrap()).collect();
+    assert_eq!(names, ["7d", "1h"]);
+    assert_eq!(body["windows"][1]["latency_trend"], "degrading");
+
+    let resp = client.get(format!("/api/v1/apps/{}/health/metrics?window=2w", app_id)).dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_PERIOD");
+
+    let resp = client.get("/api/v1/apps/nope/health/metrics").dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+#[test]
+fn test_health_history_empty() {
+    let (client, key) = setup_client();
+
+    // Submit an app
+    let response = client
         .post("/api/v1/apps")
         .header(Header::new("X-API-Key", key.clone()))
         .header(ContentType::JSON)
@@ -1047,6 +1960,230 @@ fn test_webhook_not_found() {
     assert_eq!(response.status(), Status::NotFound);
 }
 
+#[test]
+fn test_webhook_timeouts_and_delivery_log() {
+    let (client, key) = setup_client();
+    let create = |url: &str, timeout_ms: Option<u64>| -> (Status, Value) {
+        let resp = client
+            .post("/api/v1/webhooks")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({"url": url, "events": ["app.approved"], "timeout_ms": timeout_ms}).to_string())
+            .dispatch();
+        let status = resp.status();
+        (status, resp.into_json().unwrap())
+    };
+
+    // Out-of-range timeouts are rejected
+    let (status, body) = create("https://example.com/hook", Some(50));
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["error"], "INVALID_TIMEOUT");
+
+    // Accepts connections but never answers
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let silent = format!("http://{}", listener.local_addr().unwrap());
+    let (status, body) = create(&silent, Some(200));
+    assert_eq!(status, Status::Created);
+    assert_eq!(body["timeout_ms"], 200);
+    let silent_id = body["id"].as_str().unwrap().to_string();
+    let (_, body) = create(&serve_static("{}"), None);
+    assert!(body["timeout_ms"].is_null());
+    let ok_id = body["id"].as_str().unwrap().to_string();
+
+    let resp = client
+        .patch(format!("/api/v1/webhooks/{}", ok_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"timeout_ms": 40000}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+    let resp = client
+        .patch(format!("/api/v1/webhooks/{}", ok_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"timeout_ms": 5000}"#)
+        .dispatch();
+    assert_eq!(resp.into_json::<Value>().unwrap()["timeout_ms"], 5000);
+
+    submit_simple_app(&client, &key, "Webhook Timeout App");
+
+    let deliveries = |id: &str| -> Value {
+        for _ in 0..50 {
+            let body: Value = client
+                .get(format!("/api/v1/webhooks/{}/deliveries", id))
+                .header(Header::new("X-API-Key", key.clone()))
+                .dispatch()
+                .into_json()
+                .unwrap();
+            if !body["deliveries"].as_array().unwrap().is_empty() {
+                return body;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        panic!("no delivery logged for {id}");
+    };
+
+    // Timeouts are logged apart from HTTP and network errors
+    let body = deliveries(&silent_id);
+    let attempt = &body["deliveries"][0];
+    assert_eq!(attempt["event"], "app.approved");
+    assert_eq!(attempt["outcome"], "timeout");
+    assert_eq!(attempt["timeout_ms"], 200);
+    assert!(attempt["status_code"].is_null());
+    assert!(attempt["duration_ms"].as_i64().unwrap() < 2000);
+    assert_eq!(body["counts"]["timeout"], 1);
+    assert_eq!(body["counts"]["http_error"], 0);
+
+    let body = deliveries(&ok_id);
+    assert_eq!(body["deliveries"][0]["outcome"], "success");
+    assert_eq!(body["deliveries"][0]["status_code"], 200);
+    assert_eq!(body["deliveries"][0]["timeout_ms"], 5000);
+
+    let resp = client
+        .get(format!("/api/v1/webhooks/{}/deliveries?outcome=success", silent_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert!(resp.into_json::<Value>().unwrap()["deliveries"].as_array().unwrap().is_empty());
+    let resp = client
+        .get(format!("/api/v1/webhooks/{}/deliveries?outcome=slow", silent_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+    drop(listener);
+}
+
+#[test]
+fn test_app_owner_notify_webhook() {
+    use std::io::{Read, Write};
+    let (client, admin_key) = setup_client();
+
+    // Receiver that records each delivery's event header and signature
+    let received: std::sync::Arc<std::sync::Mutex<Vec<(String, String)>>> = Default::default();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let log = received.clone();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut buf = [0u8; 8192];
+            let n = stream.read(&mut buf).unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+            let header = |name: &str| {
+                request
+                    .lines()
+                    .find_map(|l| l.strip_prefix(name))
+                    .map(|v| v.trim().to_string())
+                    .unwrap_or_default()
+            };
+            log.lock()
+                .unwrap()
+                .push((header("x-appdirectory-event:"), header("x-appdirectory-signature:")));
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        }
+    });
+
+    let response = client
+        .post("/api/v1/apps")
+        .header(ContentType::JSON)
+        .body(r#"{"name": "Owned App", "short_description": "Mine", "description": "Owner notifications", "author_name": "Owner"}"#)
+        .dispatch();
+    let body: Value = response.into_json().unwrap();
+    let app_id = body["app_id"].as_str().unwrap().to_string();
+    let edit_token = body["edit_token"].as_str().unwrap().to_string();
+    let hook_path = format!("/api/v1/apps/{app_id}/notify-webhook");
+
+    // Other callers can't register a URL on someone else's listing
+    let stranger_key = {
+        let response = client
+            .post("/api/v1/keys")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "stranger"}"#)
+            .dispatch();
+        let body: Value = response.into_json().unwrap();
+        body["api_key"].as_str().unwrap().to_string()
+    };
+    let response = client
+        .put(&hook_path)
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", stranger_key))
+        .body(serde_json::json!({ "url": url }).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let response = client
+        .put(&hook_path)
+        .header(ContentType::JSON)
+        .header(Header::new("X-Edit-Token", edit_token.clone()))
+        .body(r#"{"url": "ftp://example.com"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // The edit token holder registers the URL and gets a secret once
+    let response = client
+        .put(&hook_path)
+        .header(ContentType::JSON)
+        .header(Header::new("X-Edit-Token", edit_token.clone()))
+        .body(serde_json::json!({ "url": url }).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = response.into_json().unwrap();
+    assert!(body["secret"].as_str().unwrap().starts_with("whsec_"));
+    assert_eq!(body["events"], serde_json::json!(["review.submitted", "app.health_changed", "app.deprecated"]));
+    let response = client
+        .put(&hook_path)
+        .header(ContentType::JSON)
+        .header(Header::new("X-Edit-Token", edit_token.clone()))
+        .body(serde_json::json!({ "url": url }).to_string())
+        .dispatch();
+    let body: Value = response.into_json().unwrap();
+    assert!(body.get("secret").is_none());
+
+    // A review on the app is delivered; events it doesn't cover are not
+    client
+        .patch(format!("/api/v1/apps/{app_id}"))
+        .header(ContentType::JSON)
+        .header(Header::new("X-Edit-Token", edit_token.clone()))
+        .body(r#"{"description": "Changed"}"#)
+        .dispatch();
+    let response = client
+        .post(format!("/api/v1/apps/{app_id}/reviews"))
+        .header(Header::new("X-API-Key", admin_key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"rating": 5, "title": "Great"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Created);
+
+    let mut status = Value::Null;
+    for _ in 0..50 {
+        status = client
+            .get(&hook_path)
+            .header(Header::new("X-Edit-Token", edit_token.clone()))
+            .dispatch()
+            .into_json()
+            .unwrap();
+        if !status["last_outcome"].is_null() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert_eq!(status["last_outcome"], "success");
+    assert_eq!(status["failure_count"], 0);
+    let received = received.lock().unwrap().clone();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].0, "review.submitted");
+    assert!(received[0].1.starts_with("sha256="));
+
+    let response = client
+        .delete(&hook_path)
+        .header(Header::new("X-Edit-Token", edit_token.clone()))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let response = client
+        .get(&hook_path)
+        .header(Header::new("X-Edit-Token", edit_token))
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
 #[test]
 fn test_schedule_endpoint() {
     let (client, key, db_path) = setup_client_with_path();
@@ -1062,6 +2199,9 @@ fn test_schedule_endpoint() {
     assert!(body["interval_seconds"].is_number());
     assert_eq!(body["config_var"], "HEALTH_CHECK_INTERVAL_SECS");
     assert_eq!(body["default_interval"], 300);
+    assert!(body["concurrency"].as_u64().unwrap() > 0);
+    assert_eq!(body["stats"]["runs"], 0);
+    assert!(body["stats"]["last_run"].is_null());
 
     // Non-admin cannot view schedule
     let conn = rusqlite::Connection::open(&db_path).unwrap();
@@ -1075,6 +2215,122 @@ fn test_schedule_endpoint() {
     assert_eq!(response.status(), Status::Forbidden);
 }
 
+#[test]
+fn test_scheduled_checks_concurrency_and_deadline() {
+    use app_directory::scheduler::{run_scheduled_checks, ScheduleConfig, ScheduleStatus};
+    let (client, key, db_path) = setup_client_with_path();
+
+    // Accepts connections but never answers, so every probe hangs
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let silent = format!("http://{}", listener.local_addr().unwrap());
+    for i in 0..3 {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": format!("Silent {i}"),
+                "short_description": "Never answers",
+                "description": "Never answers",
+                "author_name": "Tester",
+                "homepage_url": silent,
+            }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+    }
+
+    let db = app_directory::scheduler::init_scheduler_db(&db_path);
+    let bus = app_directory::events::EventBus::new();
+    let rt = rocket::tokio::runtime::Runtime::new().unwrap();
+
+    // Probes time out concurrently: three 1s timeouts take about 1s, not 3s
+    let config = ScheduleConfig {
+        concurrency: 3,
+        probe_timeout_secs: 1,
+        batch_deadline_secs: 0,
+        ..Default::default()
+    };
+    let stats = rt.block_on(run_scheduled_checks(&db, &bus, &config));
+    assert_eq!((stats.total, stats.checked, stats.unreachable, stats.skipped), (3, 3, 3, 0));
+    assert!(stats.duration_ms < 2500, "took {}ms", stats.duration_ms);
+
+    // The batch deadline cuts off probes still in flight
+    let config = ScheduleConfig {
+        concurrency: 1,
+        probe_timeout_secs: 30,
+        batch_deadline_secs: 1,
+        ..Default::default()
+    };
+    let stats = rt.block_on(run_scheduled_checks(&db, &bus, &config));
+    assert!(stats.deadline_hit);
+    assert_eq!((stats.checked, stats.skipped), (0, 3));
+
+    let status = ScheduleStatus::new(config);
+    status.record(stats);
+    let summary = status.stats();
+    assert_eq!(summary["runs"], 1);
+    assert_eq!(summary["last_run"]["skipped"], 3);
+    drop(listener);
+}
+
+#[test]
+fn test_scheduled_batch_digest() {
+    use app_directory::scheduler::{run_scheduled_checks, ScheduleConfig};
+    let (client, key, db_path) = setup_client_with_path();
+
+    let up = serve_static("ok");
+    let down = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    };
+    let mut ids = Vec::new();
+    for (name, url) in [("Steady", &up), ("Comeback", &up), ("Fallen", &down)] {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name,
+                "short_description": "Probed",
+                "description": "Probed",
+                "author_name": "Tester",
+                "homepage_url": url,
+            }).to_string())
+            .dispatch();
+        ids.push(resp.into_json::<Value>().unwrap()["app_id"].as_str().unwrap().to_string());
+    }
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute("UPDATE apps SET last_health_status = 'healthy' WHERE id IN (?1, ?2)", rusqlite::params![ids[0], ids[2]])
+        .unwrap();
+    conn.execute("UPDATE apps SET last_health_status = 'unreachable' WHERE id = ?1", rusqlite::params![ids[1]])
+        .unwrap();
+
+    let db = app_directory::scheduler::init_scheduler_db(&db_path);
+    let bus = app_directory::events::EventBus::new();
+    let mut rx = bus.subscribe();
+    let rt = rocket::tokio::runtime::Runtime::new().unwrap();
+    let config = ScheduleConfig { probe_timeout_secs: 5, ..Default::default() };
+    rt.block_on(run_scheduled_checks(&db, &bus, &config));
+
+    let digests: Vec<_> = std::iter::from_fn(|| rx.try_recv().ok())
+        .filter(|e| e.event == "health.batch_completed")
+        .collect();
+    assert_eq!(digests.len(), 1);
+    let data = &digests[0].data;
+    assert_eq!((data["total"].as_u64(), data["healthy"].as_u64(), data["unreachable"].as_u64()), (Some(3), Some(2), Some(1)));
+    assert_eq!(data["newly_broken"].as_array().unwrap().len(), 1);
+    assert_eq!(data["newly_broken"][0]["app_name"], "Fallen");
+    assert_eq!(data["newly_broken"][0]["status"], "unreachable");
+    assert_eq!(data["recovered"].as_array().unwrap().len(), 1);
+    assert_eq!(data["recovered"][0]["app_id"], ids[1].as_str());
+
+    // Nothing changed the second time round
+    rt.block_on(run_scheduled_checks(&db, &bus, &config));
+    let digest = std::iter::from_fn(|| rx.try_recv().ok()).find(|e| e.event == "health.batch_completed").unwrap();
+    assert_eq!(digest.data["newly_broken"], serde_json::json!([]));
+    assert_eq!(digest.data["recovered"], serde_json::json!([]));
+}
+
 // === Approval Workflow Tests ===
 
 #[test]
@@ -1357,6 +2613,240 @@ fn test_app_stats() {
     assert_eq!(response.status(), Status::NotFound);
 }
 
+#[test]
+fn test_category_leaderboard() {
+    let (client, key, db_path) = setup_client_with_path();
+    let submit = |name: &str, category: &str| -> String {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name,
+                "short_description": "Leaderboard",
+                "description": "Leaderboard",
+                "author_name": "Tester",
+                "category": category,
+            }).to_string())
+            .dispatch();
+        resp.into_json::<Value>().unwrap()["app_id"].as_str().unwrap().to_string()
+    };
+    let steady = submit("Steady", "data/weather");
+    let lucky = submit("Lucky", "data");
+    let rising = submit("Rising", "data");
+    let meh = submit("Meh", "data");
+    submit("Unreviewed", "data");
+    let elsewhere = submit("Elsewhere", "finance");
+
+    // Steady: many good reviews, all old. Lucky: a single 5. Rising: new 5s. Meh: many 2s.
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let review = |app: &str, rating: i64, age: &str| {
+        conn.execute(
+            "INSERT INTO reviews (id, app_id, rating, created_at) VALUES (?1, ?2, ?3, datetime('now', ?4))",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), app, rating, age],
+        )
+        .unwrap();
+    };
+    for _ in 0..8 {
+        review(&steady, 5, "-60 days");
+    }
+    review(&steady, 3, "-60 days");
+    review(&lucky, 5, "-60 days");
+    review(&rising, 2, "-60 days");
+    for _ in 0..10 {
+        review(&meh, 2, "-60 days");
+    }
+    for _ in 0..8 {
+        review(&rising, 5, "-1 day");
+    }
+    review(&elsewhere, 5, "-1 day");
+
+    let resp = client.get("/api/v1/categories/data/leaderboard").dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["category"], "data");
+    assert_eq!(body["period"], "30d");
+    assert_eq!(body["ranked_apps"], 4);
+    let board = body["leaderboard"].as_array().unwrap();
+    let names: Vec<&str> = board.iter().map(|e| e["name"].as_str().unwrap()).collect();
+    // A single 5-star review doesn't beat many good ones
+    assert_eq!(names, ["Steady", "Rising", "Lucky", "Meh"]);
+    assert_eq!(board[0]["review_count"], 9);
+    assert!(board[0]["score"].as_f64().unwrap() < board[0]["avg_rating"].as_f64().unwrap());
+    // Rising was last before its recent reviews
+    assert_eq!(board[1]["previous_rank"], 3);
+    assert_eq!(board[1]["rank_delta"], 1);
+    assert_eq!(board[2]["rank_delta"], -1);
+    assert_eq!(body["prior"]["weight"], 5.0);
+
+    // Subcategories have their own boards; the limit trims the list
+    let body: Value = client.get("/api/v1/categories/data/weather/leaderboard").dispatch().into_json().unwrap();
+    assert_eq!(body["leaderboard"].as_array().unwrap().len(), 1);
+    let body: Value = client.get("/api/v1/categories/data/leaderboard?limit=1&period=90d").dispatch().into_json().unwrap();
+    assert_eq!(body["leaderboard"].as_array().unwrap().len(), 1);
+    assert_eq!(body["leaderboard"][0]["previous_rank"], Value::Null);
+
+    let resp = client.get("/api/v1/categories/data/leaderboard?period=1y").dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+    let resp = client.get("/api/v1/categories/nope/leaderboard").dispatch();
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "INVALID_CATEGORY");
+    let resp = client.get("/api/v1/categories/media/nothing-here/leaderboard").dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+    // The category list is unaffected
+    assert_eq!(client.get("/api/v1/categories").dispatch().status(), Status::Ok);
+}
+
+#[test]
+fn test_stats_overview() {
+    let (client, key, db_path) = setup_client_with_path();
+    let mut ids = Vec::new();
+    for (name, category, protocol) in [
+        ("Weather One", "data/weather", "rest"),
+        ("Weather Two", "data", "graphql"),
+        ("Chat Bot", "communication", "rest"),
+    ] {
+        let body = serde_json::json!({
+            "name": name, "short_description": "s", "description": "d", "author_name": "a",
+            "category": category, "protocol": protocol,
+        });
+        let resp = client.post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON).body(body.to_string()).dispatch();
+        let created: Value = resp.into_json().unwrap();
+        ids.push(created["app_id"].as_str().unwrap().to_string());
+    }
+    client.get(format!("/api/v1/apps/{}", ids[0])).dispatch();
+    client.get(format!("/api/v1/apps/{}", ids[1])).dispatch();
+    client.post(format!("/api/v1/apps/{}/reviews", ids[2]))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON).body(r#"{"rating":4}"#).dispatch();
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    for (status, ago) in [("healthy", "-1 hour"), ("unhealthy", "-2 hours"), ("healthy", "-40 days")] {
+        conn.execute(
+            "INSERT INTO health_checks (id, app_id, status, checked_url, checked_at)
+             VALUES (?1, ?2, ?3, 'https://example.com', datetime('now', ?4))",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), ids[0], status, ago],
+        )
+        .unwrap();
+    }
+    drop(conn);
+
+    let resp = client.get("/api/v1/stats/overview").dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["period"], "30d");
+    assert_eq!(body["totals"]["apps"], 3);
+    assert_eq!(body["totals"]["views"], 2);
+    assert_eq!(body["totals"]["reviews"], 1);
+    assert_eq!(body["totals"]["health_checks"], 2);
+    assert_eq!(body["totals"]["healthy_ratio"], 0.5);
+
+    let categories = body["by_category"].as_array().unwrap();
+    assert_eq!(categories[0]["name"], "data");
+    assert_eq!(categories[0]["apps"], 2);
+    assert_eq!(categories[0]["views"], 2);
+    assert!(categories[0]["avg_rating"].is_null());
+    assert_eq!(categories[1]["name"], "communication");
+    assert_eq!(categories[1]["avg_rating"], 4.0);
+
+    let protocols = body["by_protocol"].as_array().unwrap();
+    assert_eq!(protocols[0]["name"], "rest");
+    assert_eq!(protocols[0]["apps"], 2);
+
+    let body: Value = client.get("/api/v1/stats/overview?period=all").dispatch().into_json().unwrap();
+    assert_eq!(body["totals"]["health_checks"], 3);
+
+    let resp = client.get("/api/v1/stats/overview?period=1y").dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_PERIOD");
+}
+
+#[test]
+fn test_site_stats() {
+    let (client, key) = setup_client();
+    let mut ids = Vec::new();
+    for (name, protocol) in [("Site One", "rest"), ("Site Two", "mcp"), ("Site Three", "rest")] {
+        let body = serde_json::json!({
+            "name": name, "short_description": "s", "description": "d", "author_name": "a",
+            "protocol": protocol,
+        });
+        let created: Value = client.post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON).body(body.to_string()).dispatch()
+            .into_json().unwrap();
+        ids.push(created["app_id"].as_str().unwrap().to_string());
+    }
+    for (id, rating) in [(&ids[0], 5), (&ids[1], 2)] {
+        client.post(format!("/api/v1/apps/{}/reviews", id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON).body(format!(r#"{{"rating":{}}}"#, rating)).dispatch();
+    }
+    client.get(format!("/api/v1/apps/{}", ids[1])).dispatch();
+
+    let body: Value = client.get("/api/v1/stats/site").dispatch().into_json().unwrap();
+    assert_eq!(body["apps"], 3);
+    assert_eq!(body["reviews"], 2);
+    assert_eq!(body["avg_rating"], 3.5);
+    assert_eq!(body["protocols"], 2);
+    assert_eq!(body["newest_app"]["name"], "Site Three");
+    assert_eq!(body["last_7d"]["submissions"], 3);
+    assert_eq!(body["last_7d"]["views"], 1);
+
+    // Cached: new activity doesn't show up until the TTL expires
+    client.get(format!("/api/v1/apps/{}", ids[2])).dispatch();
+    let cached: Value = client.get("/api/v1/stats/site").dispatch().into_json().unwrap();
+    assert_eq!(cached, body);
+}
+
+#[test]
+fn test_stats_history() {
+    let (client, admin, db_path) = setup_client_with_path();
+    submit_simple_app(&client, &admin, "History One");
+    submit_simple_app(&client, &admin, "History Two");
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    for (ago, value) in [(100, 0), (30, 1)] {
+        conn.execute(
+            "INSERT INTO directory_snapshots (day, metric, value) VALUES (date('now', ?1), 'apps', ?2)",
+            rusqlite::params![format!("-{ago} days"), value],
+        )
+        .unwrap();
+    }
+    assert!(app_directory::stats::record_snapshot(&conn).unwrap() > 4);
+    // A later snapshot the same day replaces the earlier one
+    submit_simple_app(&client, &admin, "History Three");
+    app_directory::stats::record_snapshot(&conn).unwrap();
+
+    let get = |query: &str| client.get(format!("/api/v1/stats/history{query}")).dispatch();
+    let body: Value = get("?metric=apps").into_json().unwrap();
+    assert_eq!(body["days"], 90);
+    let points = body["points"].as_array().unwrap();
+    assert_eq!(points.len(), 2);
+    assert_eq!(points[0]["value"], 1);
+    assert_eq!(points[1]["value"], 3);
+
+    let body: Value = get("?metric=apps&days=365").into_json().unwrap();
+    assert_eq!(body["points"].as_array().unwrap().len(), 3);
+    let body: Value = get("?metric=apps.pending&days=7").into_json().unwrap();
+    assert_eq!(body["points"][0]["value"], 0);
+
+    let resp = get("?metric=downloads");
+    assert_eq!(resp.status(), Status::BadRequest);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_METRIC");
+
+    assert_eq!(get("?metric=keys").status(), Status::Forbidden);
+    let body: Value = client
+        .get("/api/v1/stats/history?metric=keys")
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    let keys: i64 = conn.query_row("SELECT COUNT(*) FROM api_keys WHERE revoked = 0", [], |r| r.get(0)).unwrap();
+    assert_eq!(body["points"][0]["value"], keys);
+}
+
 #[test]
 fn test_trending_apps() {
     let (client, admin_key) = setup_client();
@@ -2093,34 +3583,218 @@ fn test_list_apps_filter_by_category() {
 }
 
 #[test]
-fn test_list_apps_filter_by_protocol() {
+fn test_nested_categories() {
     let (client, key) = setup_client();
 
-    let body1 = serde_json::json!({
-        "name": "REST API",
-        "short_description": "REST",
-        "description": "REST service",
-        "protocol": "rest",
-        "author_name": "Tester"
-    });
-    let body2 = serde_json::json!({
-        "name": "GraphQL API",
-        "short_description": "GraphQL",
-        "description": "GraphQL service",
-        "protocol": "graphql",
-        "author_name": "Tester"
-    });
-    client.post("/api/v1/apps").header(Header::new("X-API-Key", key.clone()))
-        .header(ContentType::JSON).body(body1.to_string()).dispatch();
-    client.post("/api/v1/apps").header(Header::new("X-API-Key", key.clone()))
-        .header(ContentType::JSON).body(body2.to_string()).dispatch();
-
-    let response = client.get("/api/v1/apps?protocol=graphql").dispatch();
+    for (name, category) in [
+        ("Weather Feed", "data/weather"),
+        ("Tide Tables", "Data/Weather/Marine/"),
+        ("Stock Ticker", "data/markets"),
+        ("Dataless", "developer-tools"),
+    ] {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name,
+                "short_description": "Test",
+                "description": "Test app",
+                "category": category,
+                "author_name": "Tester"
+            }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+    }
+
+    // Parent matches itself and all descendants
+    let body: Value = client.get("/api/v1/apps?category=data").dispatch().into_json().unwrap();
+    assert_eq!(body["total"], 3);
+    let body: Value = client.get("/api/v1/apps?category=data/weather").dispatch().into_json().unwrap();
+    assert_eq!(body["total"], 2);
+    let body: Value = client.get("/api/v1/apps?category=data/weather/marine").dispatch().into_json().unwrap();
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["apps"][0]["category"], "data/weather/marine");
+    // Prefix of a sibling name is not a match
+    let body: Value = client.get("/api/v1/apps?category=data/weat").dispatch().into_json().unwrap();
+    assert_eq!(body["total"], 0);
+
+    let body: Value = client.get("/api/v1/apps/search?q=e&category=data/weather").dispatch().into_json().unwrap();
+    assert_eq!(body["total"], 2);
+
+    let body: Value = client.get("/api/v1/categories").dispatch().into_json().unwrap();
+    let cats = body["categories"].as_array().unwrap();
+    let find = |name: &str| cats.iter().find(|c| c["name"] == name).cloned().unwrap();
+    assert_eq!(find("data")["count"], 3);
+    assert!(find("data")["parent_id"].is_null());
+    assert_eq!(find("data/weather")["count"], 2);
+    assert_eq!(find("data/weather")["parent_id"], "data");
+    assert_eq!(find("data/weather/marine")["parent_id"], "data/weather");
+
+    // Top-level segment must be a known category; subcategories must be slugs
+    for bad in ["weather/data", "data/Not A Slug", "data/a/b/c", "data//x"] {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": "Bad",
+                "short_description": "Test",
+                "description": "Test app",
+                "category": bad,
+                "author_name": "Tester"
+            }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::BadRequest, "{bad}");
+        let body: Value = resp.into_json().unwrap();
+        assert_eq!(body["error"], "INVALID_CATEGORY");
+    }
+
+    // Updates accept paths too
+    let id = submit_simple_app(&client, &key, "Movable");
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"category": "media/podcasts"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = client.get(format!("/api/v1/apps/{}", id)).dispatch().into_json().unwrap();
+    assert_eq!(body["category"], "media/podcasts");
+}
+
+#[test]
+fn test_category_presentation() {
+    let (client, key, db_path) = setup_client_with_path();
+    for (name, category) in [("Weather Feed", "data/weather"), ("Wallet", "finance")] {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name, "short_description": "s", "description": "d", "category": category, "author_name": "T",
+            }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+    }
+    let categories = || -> Vec<Value> {
+        let body: Value = client.get("/api/v1/categories").dispatch().into_json().unwrap();
+        body["categories"].as_array().unwrap().clone()
+    };
+    let find = |cats: &[Value], name: &str| cats.iter().find(|c| c["name"] == name).cloned().unwrap();
+
+    // Top-level categories come with seeded defaults; subcategories start bare
+    let cats = categories();
+    assert_eq!(find(&cats, "finance")["icon"], "💰");
+    assert!(find(&cats, "data")["description"].as_str().unwrap().contains("Datasets"));
+    assert!(find(&cats, "data/weather")["icon"].is_null());
+
+    let patch = |path: &str, api_key: &str, body: Value| {
+        client
+            .patch(format!("/api/v1/categories/{path}"))
+            .header(Header::new("X-API-Key", api_key.to_string()))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch()
+    };
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let viewer = app_directory::auth::create_api_key(&conn, "viewer", false, Some(100));
+    drop(conn);
+    assert_eq!(patch("finance", &viewer, serde_json::json!({"icon": "x"})).status(), Status::Forbidden);
+    assert_eq!(patch("nope", &key, serde_json::json!({"icon": "x"})).status(), Status::NotFound);
+    let resp = patch("finance", &key, serde_json::json!({"icon": "x".repeat(65)}));
+    assert_eq!(resp.status(), Status::BadRequest);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "FIELD_TOO_LONG");
+
+    let resp = patch("data/weather", &key, serde_json::json!({"icon": "🌦️", "description": "Forecasts and observations"}));
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.into_json::<Value>().unwrap()["parent_id"], "data");
+    // Empty string clears; omitted fields are kept
+    let resp = patch("finance", &key, serde_json::json!({"description": ""}));
+    let body: Value = resp.into_json().unwrap();
+    assert!(body["description"].is_null());
+    assert_eq!(body["icon"], "💰");
+
+    let cats = categories();
+    assert_eq!(find(&cats, "data/weather")["icon"], "🌦️");
+    assert!(find(&cats, "finance")["description"].is_null());
+
+    // Edited categories survive reseeding at startup
+    app_directory::db::init_db(&db_path);
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let description: Option<String> = conn
+        .query_row("SELECT description FROM categories WHERE id = 'finance'", [], |r| r.get(0))
+        .unwrap();
+    assert!(description.is_none());
+}
+
+#[test]
+fn test_list_apps_filter_by_protocol() {
+    let (client, key) = setup_client();
+
+    let body1 = serde_json::json!({
+        "name": "REST API",
+        "short_description": "REST",
+        "description": "REST service",
+        "protocol": "rest",
+        "author_name": "Tester"
+    });
+    let body2 = serde_json::json!({
+        "name": "GraphQL API",
+        "short_description": "GraphQL",
+        "description": "GraphQL service",
+        "protocol": "graphql",
+        "author_name": "Tester"
+    });
+    client.post("/api/v1/apps").header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON).body(body1.to_string()).dispatch();
+    client.post("/api/v1/apps").header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON).body(body2.to_string()).dispatch();
+
+    let response = client.get("/api/v1/apps?protocol=graphql").dispatch();
     let body: Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
     assert_eq!(body["total"], 1);
     assert_eq!(body["apps"][0]["name"], "GraphQL API");
 }
 
+#[test]
+fn test_list_apps_time_range_filters() {
+    let (client, key, db_path) = setup_client_with_path();
+    let old_id = submit_simple_app(&client, &key, "Old Sync App");
+    let new_id = submit_simple_app(&client, &key, "New Sync App");
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute(
+        "UPDATE apps SET created_at = '2024-01-01 00:00:00', updated_at = '2024-06-01 00:00:00' WHERE id = ?1",
+        rusqlite::params![old_id],
+    )
+    .unwrap();
+    drop(conn);
+
+    let ids = |url: &str| -> Vec<String> {
+        let response = client.get(url).dispatch();
+        assert_eq!(response.status(), Status::Ok, "{url}");
+        let body: Value = response.into_json().unwrap();
+        body["apps"].as_array().unwrap().iter().map(|a| a["id"].as_str().unwrap().to_string()).collect()
+    };
+
+    assert_eq!(ids("/api/v1/apps?created_after=2025-01-01T00:00:00Z"), vec![new_id.clone()]);
+    assert_eq!(ids("/api/v1/apps?created_before=2025-01-01T00:00:00Z"), vec![old_id.clone()]);
+    // Bounds are inclusive, and offsets are normalized to UTC
+    assert_eq!(ids("/api/v1/apps?created_before=2025-01-01T00:00:00Z&updated_after=2024-06-01T02:00:00%2B02:00"), vec![old_id.clone()]);
+    assert!(ids("/api/v1/apps?updated_after=2024-06-01T00:00:01Z&created_before=2025-01-01T00:00:00Z").is_empty());
+    assert_eq!(ids("/api/v1/apps/search?q=sync&updated_after=2025-01-01T00:00:00Z"), vec![new_id]);
+    assert_eq!(ids("/api/v1/apps/search?q=sync&created_before=2025-01-01T00:00:00Z"), vec![old_id]);
+
+    let response = client.get("/api/v1/apps?updated_after=yesterday").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_TIMESTAMP");
+    assert_eq!(body["field"], "updated_after");
+    let response = client.get("/api/v1/apps/search?q=sync&created_after=2025-13-01").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
 #[test]
 fn test_list_apps_filter_status_all() {
     let (client, key) = setup_client();
@@ -2303,6 +3977,178 @@ fn test_review_upsert() {
     assert_eq!(body["reviews"][0]["body"], "Actually great!");
 }
 
+#[test]
+fn test_review_author_edit_and_delete() {
+    let (client, key) = setup_client();
+    let app_id = submit_simple_app(&client, &key, "Editable Reviews");
+    let rating_of = |client: &Client| -> Value {
+        client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap()
+    };
+
+    // Anonymous reviews get a secret at creation
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .remote(client_addr("10.9.0.1"))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":2,"body":"Meh"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+    let created: Value = resp.into_json().unwrap();
+    let review_id = created["id"].as_str().unwrap().to_string();
+    let secret = created["review_secret"].as_str().unwrap().to_string();
+    assert!(secret.starts_with("rvs_"));
+
+    // Keyed reviewers don't need one
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":4}"#)
+        .dispatch();
+    let keyed: Value = resp.into_json().unwrap();
+    assert!(keyed.get("review_secret").is_none());
+    assert_eq!(rating_of(&client)["avg_rating"], 3.0);
+
+    // No credential, wrong secret, nothing to change
+    let resp = client
+        .patch(format!("/api/v1/apps/{}/reviews/mine", app_id))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":5}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Unauthorized);
+    let resp = client
+        .patch(format!("/api/v1/apps/{}/reviews/mine", app_id))
+        .header(Header::new("X-Review-Secret", "rvs_wrong"))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":5}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+    let resp = client
+        .patch(format!("/api/v1/apps/{}/reviews/mine", app_id))
+        .header(Header::new("X-Review-Secret", secret.clone()))
+        .header(ContentType::JSON)
+        .body("{}")
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "NO_CHANGES");
+    let resp = client
+        .patch(format!("/api/v1/apps/{}/reviews/mine", app_id))
+        .header(Header::new("X-Review-Secret", secret.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":9}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+
+    // Editing with the secret keeps the old version and recalculates the average
+    let resp = client
+        .patch(format!("/api/v1/apps/{}/reviews/mine", app_id))
+        .header(Header::new("X-Review-Secret", secret.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":5,"body":"Grew on me","pros":["fast"]}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["rating"], 5);
+    assert!(body["edited_at"].is_string());
+    assert_eq!(rating_of(&client)["avg_rating"], 4.5);
+
+    let list: Value = client
+        .get(format!("/api/v1/apps/{}/reviews", app_id))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    let edited = list["reviews"].as_array().unwrap().iter().find(|r| r["id"] == review_id.as_str()).unwrap();
+    assert_eq!(edited["body"], "Grew on me");
+    assert_eq!(edited["pros"], serde_json::json!(["fast"]));
+    assert!(edited["edited_at"].is_string());
+
+    let history: Value = client
+        .get(format!("/api/v1/apps/{}/reviews/{}/history", app_id, review_id))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    let versions = history["versions"].as_array().unwrap();
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0]["rating"], 2);
+    assert_eq!(versions[0]["body"], "Meh");
+
+    // Keyed reviewers delete theirs with the API key
+    let resp = client
+        .delete(format!("/api/v1/apps/{}/reviews/mine", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let app = rating_of(&client);
+    assert_eq!(app["review_count"], 1);
+    assert_eq!(app["avg_rating"], 5.0);
+
+    let resp = client
+        .delete(format!("/api/v1/apps/{}/reviews/mine?secret={}", app_id, secret))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let app = rating_of(&client);
+    assert_eq!(app["review_count"], 0);
+    let resp = client
+        .get(format!("/api/v1/apps/{}/reviews/{}/history", app_id, review_id))
+        .dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+#[test]
+fn test_list_my_reviews() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let member = app_directory::auth::create_api_key(&conn, "critic", false, Some(1000));
+    let review = |app_id: &str, key: Option<&str>, rating: i64| {
+        let mut req = client
+            .post(format!("/api/v1/apps/{}/reviews", app_id))
+            .remote(client_addr("10.9.1.1"))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "rating": rating, "body": "Tried it" }).to_string());
+        if let Some(key) = key {
+            req = req.header(Header::new("X-API-Key", key.to_string()));
+        }
+        assert_eq!(req.dispatch().status(), Status::Created);
+    };
+    let mine = |key: Option<&str>, query: &str| {
+        let mut req = client.get(format!("/api/v1/reviews/mine{}", query));
+        if let Some(key) = key {
+            req = req.header(Header::new("X-API-Key", key.to_string()));
+        }
+        let response = req.dispatch();
+        let status = response.status();
+        (status, response.into_json::<Value>().unwrap_or(Value::Null))
+    };
+
+    let apps: Vec<String> = ["First Pick", "Second Pick", "Third Pick"]
+        .iter()
+        .map(|name| submit_simple_app(&client, &admin, name))
+        .collect();
+    for (i, app_id) in apps.iter().enumerate() {
+        review(app_id, Some(&member), i as i64 + 3);
+    }
+    review(&apps[0], None, 1);
+    review(&apps[1], Some(&admin), 2);
+
+    let (status, body) = mine(Some(&member), "");
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["total"], 3);
+    let reviews = body["reviews"].as_array().unwrap();
+    assert!(reviews.iter().all(|r| r["body"] == "Tried it" && r["rating"].as_i64().unwrap() >= 3));
+    let first = reviews.iter().find(|r| r["app_id"] == apps[0].as_str()).unwrap();
+    assert_eq!(first["rating"], 3);
+    assert_eq!(first["app"]["name"], "First Pick");
+    assert_eq!(first["app"]["slug"], "first-pick");
+    assert_eq!(first["app"]["status"], "approved");
+    assert_eq!(first["app"]["avg_rating"], 2.0);
+
+    let (_, page) = mine(Some(&member), "?per_page=2&page=2");
+    assert_eq!(page["reviews"].as_array().unwrap().len(), 1);
+    assert_eq!(page["page"], 2);
+    assert_eq!(mine(Some(&admin), "").1["total"], 1);
+    assert_eq!(mine(None, "").0, Status::Unauthorized);
+}
+
 #[test]
 fn test_review_for_nonexistent_app() {
     let (client, key) = setup_client();
@@ -2349,6 +4195,123 @@ fn test_review_pagination() {
     assert!(body["total"].as_i64().unwrap() >= 1);
 }
 
+#[test]
+fn test_stats_retention() {
+    use app_directory::retention::{apply, RetentionPolicy};
+
+    let (client, key, db_path) = setup_client_with_path();
+    let app_id = submit_simple_app(&client, &key, "Retention App");
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let view = |viewer: &str, ago: &str| {
+        conn.execute(
+            "INSERT INTO app_views (id, app_id, viewer_key_id, viewed_at) VALUES (?1, ?2, ?3, datetime('now', ?4))",
+            rusqlite::params![uuid::Uuid::new_v4().to_string(), app_id, viewer, ago],
+        )
+        .unwrap();
+    };
+    view("key-a", "-1 day");
+    view("key-a", "-100 days");
+    view("key-a", "-120 days");
+    view("key-b", "-100 days");
+    view("anonymous", "-100 days");
+    view("key-a", "-200 days");
+    conn.execute(
+        "INSERT INTO app_clicks (id, app_id, clicker_key_id, target, clicked_at) VALUES ('c1', ?1, 'key-a', 'homepage', datetime('now', '-60 days'))",
+        rusqlite::params![app_id],
+    )
+    .unwrap();
+
+    let policy = RetentionPolicy { retention_days: 180, anonymize_after_days: 30, interval_secs: 3600 };
+    let report = apply(&conn, &policy).unwrap();
+    assert_eq!(report.views_deleted, 1);
+    assert_eq!(report.views_anonymized, 3);
+    // Click-throughs keep their key through the verified-usage window
+    assert_eq!(report.clicks_anonymized, 0);
+
+    let viewers: Vec<String> = conn
+        .prepare("SELECT viewer_key_id FROM app_views WHERE app_id = ?1 ORDER BY viewed_at DESC")
+        .unwrap()
+        .query_map(rusqlite::params![app_id], |r| r.get(0))
+        .unwrap()
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(viewers.len(), 5);
+    assert_eq!(viewers[0], "key-a");
+    let old: Vec<&String> = viewers[1..].iter().filter(|v| *v != "anonymous").collect();
+    assert!(old.iter().all(|v| v.starts_with("anon:")));
+    // The same key gets the same pseudonym, so distinct counts hold
+    let distinct: i64 = conn
+        .query_row("SELECT COUNT(DISTINCT viewer_key_id) FROM app_views WHERE app_id = ?1", rusqlite::params![app_id], |r| r.get(0))
+        .unwrap();
+    assert_eq!(distinct, 4);
+
+    // A second run has nothing left to do
+    assert_eq!(apply(&conn, &policy).unwrap(), Default::default());
+}
+
+#[test]
+fn test_review_reactions() {
+    let (client, key) = setup_client();
+    let app_id = submit_simple_app(&client, &key, "Reaction App");
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":5,"body":"Solid"}"#)
+        .dispatch();
+    let review_id = resp.into_json::<Value>().unwrap()["id"].as_str().unwrap().to_string();
+    let react = |emoji: &str, auth: Option<&str>, ip: &str| {
+        let mut req = client
+            .post(format!("/api/v1/reviews/{}/reactions", review_id))
+            .header(ContentType::JSON)
+            .remote(client_addr(ip))
+            .body(serde_json::json!({ "emoji": emoji }).to_string());
+        if let Some(k) = auth {
+            req = req.header(Header::new("X-API-Key", k.to_string()));
+        }
+        let resp = req.dispatch();
+        (resp.status(), resp.into_json::<Value>().unwrap())
+    };
+
+    let (status, body) = react("👍", Some(&key), "10.5.0.1");
+    assert_eq!(status, Status::Created);
+    assert_eq!(body["added"], true);
+    assert_eq!(body["reactions"]["👍"], 1);
+    // Same key again, from another IP: still one
+    let (status, body) = react("thumbs_up", Some(&key), "10.5.0.2");
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["added"], false);
+    assert_eq!(body["reactions"]["👍"], 1);
+    // Anonymous callers are deduplicated by IP
+    assert_eq!(react("👍", None, "10.5.0.3").0, Status::Created);
+    assert_eq!(react("👍", None, "10.5.0.3").0, Status::Ok);
+    assert_eq!(react("🎯", None, "10.5.0.3").0, Status::Created);
+
+    let (status, body) = react("🔥", None, "10.5.0.3");
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["error"], "INVALID_REACTION");
+    assert_eq!(react("👍", None, "10.5.0.3").1["reactions"]["❗"], 0);
+
+    let resp = client.get(format!("/api/v1/apps/{}/reviews", app_id)).dispatch();
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["reviews"][0]["reactions"], serde_json::json!({ "👍": 2, "🎯": 1, "❗": 0 }));
+
+    let resp = client
+        .delete(format!("/api/v1/reviews/{}/reactions/%F0%9F%8E%AF", review_id))
+        .remote(client_addr("10.5.0.3"))
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["removed"], true);
+    assert_eq!(body["reactions"]["🎯"], 0);
+
+    let resp = client
+        .post("/api/v1/reviews/nope/reactions")
+        .header(ContentType::JSON)
+        .body(r#"{"emoji":"👍"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
 // ── Search Edge Cases ──
 
 #[test]
@@ -2632,6 +4595,103 @@ fn test_list_pending_no_auth() {
     assert_eq!(response.status(), Status::Unauthorized);
 }
 
+#[test]
+fn test_discovery_crawler_proposes_pending_listings() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let member = app_directory::auth::create_api_key(&conn, "member", false, Some(1000));
+    let seed_url = serve_static(
+        "# Example Co\n\n- [Geo Finder](https://geo.example.com): Geocoding for agents\n- [Listed](https://listed.example.com/)\n- [Tide Tables](https://tides.example.com)\n",
+    );
+    client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", admin.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Already Listed","short_description":"Here already","description":"Listed by hand","author_name":"Tester","homepage_url":"https://listed.example.com"}"#)
+        .dispatch();
+
+    let add = |key: &str, url: &str| {
+        client
+            .post("/api/v1/admin/discovery/seeds")
+            .header(Header::new("X-API-Key", key.to_string()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "url": url }).to_string())
+            .dispatch()
+    };
+    assert_eq!(add(&member, &seed_url).status(), Status::Forbidden);
+    assert_eq!(add(&admin, "ftp://example.com/llms.txt").status(), Status::BadRequest);
+    let resp = add(&admin, &seed_url);
+    assert_eq!(resp.status(), Status::Created);
+    let seed_id = resp.into_json::<Value>().unwrap()["id"].as_str().unwrap().to_string();
+    let resp = add(&admin, &seed_url);
+    assert_eq!(resp.status(), Status::Conflict);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "SEED_EXISTS");
+
+    let crawl = || -> Value {
+        client
+            .post(format!("/api/v1/admin/discovery/seeds/{}/crawl", seed_id))
+            .header(Header::new("X-API-Key", admin.clone()))
+            .dispatch()
+            .into_json()
+            .unwrap()
+    };
+    let report = crawl();
+    assert_eq!(report["format"], "llms");
+    assert_eq!(report["found"], 3);
+    assert_eq!(report["known"], 1);
+    let proposed = report["proposed"].as_array().unwrap();
+    assert_eq!(proposed.len(), 2);
+    assert_eq!(proposed[0]["name"], "Geo Finder");
+
+    // Proposals wait in the pending queue with their provenance
+    let pending: Value = client
+        .get("/api/v1/apps/pending")
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    let geo = pending["apps"].as_array().unwrap().iter().find(|a| a["name"] == "Geo Finder").unwrap().clone();
+    assert_eq!(geo["short_description"], "Geocoding for agents");
+    assert_eq!(geo["author_name"], "127.0.0.1");
+    assert_eq!(geo["discovery"]["seed_id"], seed_id.as_str());
+    assert_eq!(geo["discovery"]["seed_url"], seed_url.as_str());
+    assert_eq!(geo["discovery"]["source_url"], "https://geo.example.com/");
+
+    // A rejected proposal is not proposed again
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reject", geo["id"].as_str().unwrap()))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"reason":"Not an agent service"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let report = crawl();
+    assert!(report["proposed"].as_array().unwrap().is_empty());
+    assert_eq!(report["known"], 3);
+
+    let seeds: Value = client
+        .get("/api/v1/admin/discovery/seeds")
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(seeds["total"], 1);
+    assert_eq!(seeds["seeds"][0]["proposed_count"], 2);
+    assert_eq!(seeds["seeds"][0]["last_format"], "llms");
+    assert!(seeds["seeds"][0]["last_error"].is_null());
+
+    let resp = client
+        .delete(format!("/api/v1/admin/discovery/seeds/{}", seed_id))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let resp = client
+        .post(format!("/api/v1/admin/discovery/seeds/{}/crawl", seed_id))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
 // ── Partial Update ──
 
 #[test]
@@ -2693,6 +4753,55 @@ fn test_openapi_json_endpoint() {
     assert!(body["info"]["title"].is_string());
 }
 
+#[test]
+fn test_client_spec() {
+    let (client, _) = setup_client();
+    let response = client.get("/api/v1/client-spec").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let spec: Value = response.into_json().unwrap();
+    assert_eq!(spec["base_path"], "/api/v1");
+
+    // One entry per operation in the OpenAPI document
+    let openapi: Value = client.get("/api/v1/openapi.json").dispatch().into_json().unwrap();
+    let operations: usize = openapi["paths"].as_object().unwrap().values().map(|p| p.as_object().unwrap().len()).sum();
+    let endpoints = spec["endpoints"].as_array().unwrap();
+    assert_eq!(endpoints.len(), operations);
+    let find = |name: &str| endpoints.iter().find(|e| e["name"] == name).unwrap().clone();
+
+    let submit = find("submitApp");
+    assert_eq!(submit["method"], "POST");
+    assert_eq!(submit["path"], "/api/v1/apps");
+    assert_eq!(submit["auth"], "api_key");
+    let name = submit["params"].as_array().unwrap().iter().find(|p| p["name"] == "name").unwrap();
+    assert_eq!(name["in"], "body");
+    assert_eq!(name["required"], true);
+    let codes: Vec<&str> = submit["errors"].as_array().unwrap().iter()
+        .flat_map(|e| e["codes"].as_array().unwrap().iter().filter_map(|c| c.as_str()))
+        .collect();
+    assert!(codes.contains(&"FIELD_TOO_LONG"));
+    assert!(codes.contains(&"CAPTCHA_FAILED"));
+
+    let get = find("getApp");
+    assert_eq!(get["path"], "/api/v1/apps/{id}");
+    assert!(get["params"].as_array().unwrap().iter().any(|p| p["name"] == "id" && p["in"] == "path" && p["required"] == true));
+    assert!(get["errors"].as_array().unwrap().iter().any(|e| e["status"] == 404 && e["codes"][0] == "NOT_FOUND"));
+
+    assert_eq!(find("updateApp")["auth"], "optional");
+    assert_eq!(find("health")["auth"], "none");
+    assert_eq!(find("getAgentCard")["path"], "/.well-known/agent.json");
+
+    // Every listed code is a registered one
+    let registry: Value = client.get("/api/v1/errors").dispatch().into_json().unwrap();
+    let known: Vec<&str> = registry["errors"].as_array().unwrap().iter().map(|e| e["code"].as_str().unwrap()).collect();
+    for e in endpoints {
+        for group in e["errors"].as_array().unwrap() {
+            for code in group["codes"].as_array().unwrap() {
+                assert!(known.contains(&code.as_str().unwrap()), "unknown code {code}");
+            }
+        }
+    }
+}
+
 #[test]
 fn test_root_llms_txt() {
     let (client, _) = setup_client();
@@ -2702,19 +4811,187 @@ fn test_root_llms_txt() {
     assert!(text.contains("App Directory"));
 }
 
-// ── Delete Cascade ──
+#[test]
+fn test_opensearch_and_search_meta() {
+    let (client, _) = setup_client();
+
+    let response = client
+        .get("/opensearch.xml")
+        .header(Header::new("Host", "dir.example.com"))
+        .header(Header::new("X-Forwarded-Proto", "https"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.content_type().unwrap().to_string(),
+        "application/opensearchdescription+xml"
+    );
+    let xml = response.into_string().unwrap();
+    assert!(xml.contains(
+        r#"template="https://dir.example.com/api/v1/apps/search?q={searchTerms}&amp;page={startPage?}&amp;per_page={count?}""#
+    ));
+
+    let response = client.get("/api/v1/search/meta").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let meta: Value = response.into_json().unwrap();
+    assert_eq!(meta["search"]["path"], "/api/v1/apps/search");
+    let names = |v: &Value| -> Vec<String> {
+        v.as_array().unwrap().iter().map(|f| f["name"].as_str().unwrap().to_string()).collect()
+    };
+    assert_eq!(names(&meta["search"]["facets"]), ["category", "protocol", "health_status", "verified"]);
+    assert_eq!(
+        names(&meta["search"]["filters"]),
+        ["category", "protocol", "featured", "verified", "health", "created_after", "created_before", "updated_after"]
+    );
+    // The list endpoint takes every search filter, plus status
+    let list_filters = names(&meta["list"]["filters"]);
+    assert!(names(&meta["search"]["filters"]).iter().all(|f| list_filters.contains(f)));
+    assert!(list_filters.contains(&"status".to_string()));
+    assert_eq!(meta["list"]["query"]["param"], "q");
+    assert!(meta["search"]["fields"].as_array().unwrap().contains(&serde_json::json!("matched_terms")));
+    assert_eq!(meta["list"]["sort"]["default"], "newest");
+    assert_eq!(meta["list"]["sort"]["options"], serde_json::json!(["newest", "oldest", "rating", "name"]));
+
+    // Every advertised sort is accepted by the list endpoint
+    for sort in meta["list"]["sort"]["options"].as_array().unwrap() {
+        let response = client.get(format!("/api/v1/apps?sort={}", sort.as_str().unwrap())).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+}
 
 #[test]
-fn test_delete_app_cascades_reviews() {
+fn test_llms_txt_reflects_catalog() {
     let (client, key) = setup_client();
 
-    let body = serde_json::json!({
-        "name": "Cascade Delete Test",
-        "short_description": "Will be deleted",
-        "description": "App to test cascade delete",
-        "author_name": "Author"
-    });
-    let response = client.post("/api/v1/apps")
+    let response = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(
+            r#"{
+                "name": "Llms Featured",
+                "short_description": "Shows up in llms.txt",
+                "description": "Featured app listed in the generated llms.txt",
+                "author_name": "Builder",
+                "category": "developer-tools"
+            }"#,
+        )
+        .dispatch();
+    let body: Value = serde_json::from_str(&response.into_string().unwrap()).unwrap();
+    let app_id = body["app_id"].as_str().unwrap().to_string();
+    client
+        .post(format!("/api/v1/apps/{}/approve", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    let response = client
+        .patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{ "is_featured": true }"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let text = client.get("/llms.txt").dispatch().into_string().unwrap();
+    assert!(text.contains("- Approved apps: 1"));
+    assert!(text.contains("- Featured apps: 1"));
+    assert!(text.contains("- developer-tools (1 apps)"));
+    assert!(text.contains("[Llms Featured](/api/v1/apps/llms-featured)"));
+    // Endpoint list comes from the mounted routes
+    assert!(text.contains("- GET /api/v1/apps/<id>"));
+    assert!(text.contains("- POST /api/v1/apps"));
+    assert!(!text.contains("OPTIONS "));
+
+    // Served from cache until the TTL expires
+    client
+        .patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{ "is_featured": false }"#)
+        .dispatch();
+    let cached = client.get("/api/v1/llms.txt").dispatch().into_string().unwrap();
+    assert_eq!(cached, text);
+}
+
+// ── Delete Cascade ──
+
+#[test]
+fn test_export_app_bundle() {
+    let (client, key, db_path) = setup_client_with_path();
+    let resp = client
+        .post("/api/v1/apps")
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Export App","short_description":"Bundle","description":"Owner export","author_name":"Owner","author_email":"owner@example.com","api_url":"https://example.com"}"#)
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    let app_id = body["app_id"].as_str().unwrap().to_string();
+    let slug = body["slug"].as_str().unwrap().to_string();
+    let edit_token = body["edit_token"].as_str().unwrap().to_string();
+
+    client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":4,"body":"Works","pros":["fast"]}"#)
+        .dispatch();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute(
+        "INSERT INTO health_checks (id, app_id, status, response_time_ms, checked_url) VALUES ('hc1', ?1, 'healthy', 120, 'https://example.com')",
+        rusqlite::params![app_id],
+    )
+    .unwrap();
+    drop(conn);
+
+    // Anonymous callers and other keys can't export
+    let resp = client.get(format!("/api/v1/apps/{}/export", app_id)).dispatch();
+    assert_eq!(resp.status(), Status::Unauthorized);
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let stranger = app_directory::auth::create_api_key(&conn, "stranger", false, Some(100));
+    drop(conn);
+    let resp = client
+        .get(format!("/api/v1/apps/{}/export", app_id))
+        .header(Header::new("X-API-Key", stranger))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Forbidden);
+
+    let resp = client
+        .get(format!("/api/v1/apps/{}/export", slug))
+        .header(Header::new("X-Edit-Token", edit_token))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let bundle: Value = resp.into_json().unwrap();
+    assert_eq!(bundle["app"]["id"], app_id.as_str());
+    assert_eq!(bundle["app"]["author_email"], "owner@example.com");
+    assert_eq!(bundle["reviews"].as_array().unwrap().len(), 1);
+    assert_eq!(bundle["reviews"][0]["pros"], serde_json::json!(["fast"]));
+    assert!(bundle["reviews"][0].get("reviewer_key_id").is_none());
+    assert_eq!(bundle["stats"]["reviews"]["distribution"]["4"], 1);
+    assert_eq!(bundle["stats"]["clicks"]["homepage"], 0);
+    assert_eq!(bundle["health_checks"][0]["response_time_ms"], 120);
+    assert!(bundle["exported_at"].is_string());
+
+    // Admins can export any listing, and their exports are audited
+    let resp = client
+        .get(format!("/api/v1/apps/{}/export", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let exports: i64 = conn
+        .query_row("SELECT COUNT(*) FROM audit_log WHERE action = 'app.export'", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(exports, 1);
+}
+
+#[test]
+fn test_delete_app_cascades_reviews() {
+    let (client, key) = setup_client();
+
+    let body = serde_json::json!({
+        "name": "Cascade Delete Test",
+        "short_description": "Will be deleted",
+        "description": "App to test cascade delete",
+        "author_name": "Author"
+    });
+    let response = client.post("/api/v1/apps")
         .header(Header::new("X-API-Key", key.clone()))
         .header(ContentType::JSON)
         .body(body.to_string())
@@ -2862,6 +5139,66 @@ fn test_deprecate_self_reference() {
     assert_eq!(response.status(), Status::BadRequest);
 }
 
+#[test]
+fn test_replacement_chain_resolution() {
+    let (client, key) = setup_client();
+
+    let mut ids = Vec::new();
+    for name in ["Chain A", "Chain B", "Chain C", "Chain D"] {
+        let body = serde_json::json!({
+            "name": name, "short_description": "Test", "description": "Test", "author_name": "Author"
+        });
+        let created: Value = client.post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON).body(body.to_string()).dispatch()
+            .into_json().unwrap();
+        ids.push(created["app_id"].as_str().unwrap().to_string());
+    }
+    let deprecate = |id: &str, replacement: Option<&str>| {
+        let dep = serde_json::json!({ "reason": "Superseded", "replacement_app_id": replacement });
+        let resp = client.post(format!("/api/v1/apps/{}/deprecate", id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON).body(dep.to_string()).dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+    };
+
+    // A → B → C, C is active
+    deprecate(&ids[0], Some(&ids[1]));
+    deprecate(&ids[1], Some(&ids[2]));
+
+    let app: Value = client.get(format!("/api/v1/apps/{}", ids[0])).dispatch().into_json().unwrap();
+    assert_eq!(app["replacement_app_id"], ids[1].as_str());
+    assert_eq!(app["resolved_replacement"]["id"], ids[2].as_str());
+    assert_eq!(app["resolved_replacement"]["slug"], "chain-c");
+    assert_eq!(app["resolved_replacement"]["hops"], 2);
+
+    let resp = client.get(format!("/api/v1/apps/{}/replacement", ids[0])).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["chain"], serde_json::json!([ids[1], ids[2]]));
+    assert_eq!(body["replacement"]["id"], ids[2].as_str());
+    assert_eq!(body["replacement"]["status"], "approved");
+
+    let resp = client.get(format!("/api/v1/apps/{}/replacement", ids[2])).dispatch();
+    assert_eq!(resp.status(), Status::Conflict);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "NOT_DEPRECATED");
+
+    // Deprecated without a replacement: nothing to resolve to
+    deprecate(&ids[3], None);
+    let resp = client.get(format!("/api/v1/apps/{}/replacement", ids[3])).dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+
+    // C → A closes a loop
+    deprecate(&ids[2], Some(&ids[0]));
+    let resp = client.get(format!("/api/v1/apps/{}/replacement", ids[0])).dispatch();
+    assert_eq!(resp.status(), Status::Conflict);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "REPLACEMENT_CYCLE");
+    assert_eq!(body["chain"], serde_json::json!([ids[1], ids[2]]));
+    let app: Value = client.get(format!("/api/v1/apps/{}", ids[0])).dispatch().into_json().unwrap();
+    assert!(app["resolved_replacement"].is_null());
+}
+
 #[test]
 fn test_undeprecate_non_deprecated() {
     let (client, key) = setup_client();
@@ -2884,6 +5221,238 @@ fn test_undeprecate_non_deprecated() {
     assert_eq!(response.status(), Status::Conflict);
 }
 
+#[test]
+fn test_archive_freezes_and_hides_app() {
+    let (client, key, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let user_key = app_directory::auth::create_api_key(&conn, "user", false, Some(100));
+    drop(conn);
+
+    let body = serde_json::json!({
+        "name": "Historic Quasar Tool",
+        "short_description": "Old but linked",
+        "description": "Kept for historical links",
+        "author_name": "Author"
+    });
+    let response = client.post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON).body(body.to_string()).dispatch();
+    let created: Value = response.into_json().unwrap();
+    let app_id = created["app_id"].as_str().unwrap().to_string();
+    let slug = created["slug"].as_str().unwrap().to_string();
+
+    // Non-admins can't archive
+    let response = client.post(format!("/api/v1/apps/{}/archive", app_id))
+        .header(Header::new("X-API-Key", user_key))
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let bus = client.rocket().state::<app_directory::events::EventBus>().unwrap();
+    let mut rx = bus.subscribe();
+    let response = client.post(format!("/api/v1/apps/{}/archive", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["previous_status"], "approved");
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.event, "app.archived");
+
+    let response = client.post(format!("/api/v1/apps/{}/archive", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(response.status(), Status::Conflict);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["error"], "ALREADY_ARCHIVED");
+
+    // Frozen: edits, translations, and reviews are refused
+    let response = client.patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"short_description":"New text"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Conflict);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["error"], "ARCHIVED");
+    let response = client.put(format!("/api/v1/apps/{}/translations/de", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Historisch"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Conflict);
+    let response = client.post(format!("/api/v1/apps/{}/reviews", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":5}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Conflict);
+    let response = client.post(format!("/api/v1/apps/{}/approve", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body("{}")
+        .dispatch();
+    assert_eq!(response.status(), Status::Conflict);
+
+    // Hidden from default listings and search, still addressable by slug
+    let list: Value = client.get("/api/v1/apps").dispatch().into_json().unwrap();
+    assert_eq!(list["total"], 0);
+    let list: Value = client.get("/api/v1/apps?status=archived").dispatch().into_json().unwrap();
+    assert_eq!(list["total"], 1);
+    let found: Value = client.get("/api/v1/apps/search?q=Quasar").dispatch().into_json().unwrap();
+    assert_eq!(found["total"], 0);
+    let response = client.get(format!("/api/v1/apps/{}", slug)).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let app: Value = response.into_json().unwrap();
+    assert_eq!(app["status"], "archived");
+    assert!(app["archived_at"].is_string());
+
+    // Unarchive restores the previous status
+    let response = client.post(format!("/api/v1/apps/{}/unarchive", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["restored_to"], "approved");
+    let response = client.post(format!("/api/v1/apps/{}/unarchive", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["error"], "NOT_ARCHIVED");
+    let response = client.patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", key))
+        .header(ContentType::JSON)
+        .body(r#"{"short_description":"New text"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[test]
+fn test_if_match_optimistic_concurrency() {
+    let (client, key) = setup_client();
+    let body = serde_json::json!({
+        "name": "Contended", "short_description": "s", "description": "d", "author_name": "a"
+    });
+    let created: Value = client.post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON).body(body.to_string()).dispatch()
+        .into_json().unwrap();
+    let app_id = created["app_id"].as_str().unwrap().to_string();
+
+    let response = client.get(format!("/api/v1/apps/{}", app_id)).dispatch();
+    assert_eq!(response.headers().get_one("ETag"), Some("\"1\""));
+    let app: Value = response.into_json().unwrap();
+    assert_eq!(app["revision"], 1);
+
+    let patch = |if_match: &str, text: &str| {
+        client.patch(format!("/api/v1/apps/{}", app_id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(Header::new("If-Match", if_match.to_string()))
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"short_description":"{}"}}"#, text))
+            .dispatch()
+    };
+
+    // First writer wins and bumps the revision
+    let response = patch("\"1\"", "first");
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["revision"], 2);
+
+    // Second writer with the stale ETag is refused
+    let response = patch("\"1\"", "second");
+    assert_eq!(response.status(), Status::PreconditionFailed);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["error"], "PRECONDITION_FAILED");
+    assert_eq!(body["current_revision"], 2);
+
+    let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(app["short_description"], "first");
+
+    // Writes without If-Match still work and still bump the revision
+    let response = client.patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"short_description":"third"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = client.delete(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(Header::new("If-Match", "\"2\""))
+        .dispatch();
+    assert_eq!(response.status(), Status::PreconditionFailed);
+    let response = client.delete(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(Header::new("If-Match", "\"3\""))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[test]
+fn test_revision_history_diff_and_rollback() {
+    let (client, key, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let user_key = app_directory::auth::create_api_key(&conn, "user", false, Some(100));
+    drop(conn);
+
+    let body = serde_json::json!({
+        "name": "Versioned", "short_description": "v1", "description": "d", "author_name": "a",
+        "tags": ["one"]
+    });
+    let created: Value = client.post("/api/v1/apps")
+        .header(Header::new("X-API-Key", user_key.clone()))
+        .header(ContentType::JSON).body(body.to_string()).dispatch()
+        .into_json().unwrap();
+    let app_id = created["app_id"].as_str().unwrap().to_string();
+    let slug = created["slug"].as_str().unwrap().to_string();
+
+    for patch in [r#"{"short_description":"v2"}"#, r#"{"short_description":"v3","tags":["one","two"]}"#] {
+        let response = client.patch(format!("/api/v1/apps/{}", app_id))
+            .header(Header::new("X-API-Key", user_key.clone()))
+            .header(ContentType::JSON).body(patch).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    let history: Value = client.get(format!("/api/v1/apps/{}/revisions", slug)).dispatch().into_json().unwrap();
+    assert_eq!(history["total"], 3);
+    let revisions = history["revisions"].as_array().unwrap();
+    assert_eq!(revisions[0]["revision"], 3);
+    assert_eq!(revisions[0]["editor"]["kind"], "api_key");
+    assert_eq!(revisions[0]["changed_fields"], serde_json::json!(["short_description", "tags"]));
+    assert_eq!(revisions[2]["revision"], 1);
+
+    let diff: Value = client.get(format!("/api/v1/apps/{}/revisions/2/diff", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(diff["previous_revision"], 1);
+    assert_eq!(diff["changes"]["short_description"]["from"], "v1");
+    assert_eq!(diff["changes"]["short_description"]["to"], "v2");
+    assert_eq!(diff["changes"].as_object().unwrap().len(), 1);
+
+    let response = client.get(format!("/api/v1/apps/{}/revisions/9/diff", app_id)).dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+
+    // Rollback is admin only and becomes a new revision
+    let response = client.post(format!("/api/v1/apps/{}/revisions/1/rollback", app_id))
+        .header(Header::new("X-API-Key", user_key))
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    let response = client.post(format!("/api/v1/apps/{}/revisions/1/rollback", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["revision"], 4);
+
+    let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(app["short_description"], "v1");
+    assert_eq!(app["tags"], serde_json::json!(["one"]));
+    assert_eq!(app["revision"], 4);
+
+    let diff: Value = client.get(format!("/api/v1/apps/{}/revisions/4/diff", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(diff["changes"]["short_description"]["from"], "v3");
+    let history: Value = client.get(format!("/api/v1/apps/{}/revisions", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(history["revisions"][0]["editor"]["kind"], "admin");
+}
+
 // ── Key Management Edge Cases ──
 
 #[test]
@@ -3064,6 +5633,69 @@ fn test_skills_index_json() {
     assert!(files.contains(&serde_json::json!("SKILL.md")));
 }
 
+#[test]
+fn test_per_app_skill_manifests() {
+    let (client, key) = setup_client();
+    let spec_url = serve_static(
+        r#"{"openapi":"3.0.3","info":{"title":"Weather","version":"1"},"servers":[{"url":"https://weather.example.com"}],"paths":{"/forecast":{"get":{"summary":"Get a forecast"}}},"components":{"securitySchemes":{"bearer":{"type":"http","scheme":"bearer"}}}}"#,
+    ) + "/openapi.json";
+
+    let submit = |name: &str, spec: Option<&str>| -> String {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name,
+                "short_description": format!("{} for agents", name),
+                "description": "Forecasts by coordinates",
+                "author_name": "Tester",
+                "api_spec_url": spec,
+            }).to_string())
+            .dispatch();
+        let body: Value = resp.into_json().unwrap();
+        body["app_id"].as_str().unwrap().to_string()
+    };
+    let weather = submit("Weather API", Some(&spec_url));
+    let no_spec = submit("No Spec API", None);
+    let rejected = submit("Rejected API", Some(&spec_url));
+    for id in [&weather, &no_spec] {
+        client
+            .post(format!("/api/v1/apps/{}/approve", id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch();
+    }
+    client
+        .post(format!("/api/v1/apps/{}/reject", rejected))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{ "reason": "Not an agent tool" }"#)
+        .dispatch();
+    assert_eq!(wait_for_spec_status(&client, &weather)["spec_status"], "valid");
+
+    let index: Value = client.get("/.well-known/skills/index.json").dispatch().into_json().unwrap();
+    let skills = index["skills"].as_array().unwrap();
+    assert_eq!(skills.len(), 2);
+    assert_eq!(skills[1]["name"], "apps/weather-api");
+    assert_eq!(skills[1]["description"], "Weather API for agents");
+    assert_eq!(skills[1]["url"], "/.well-known/skills/apps/weather-api/SKILL.md");
+
+    let resp = client.get("/.well-known/skills/apps/weather-api/SKILL.md").dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let md = resp.into_string().unwrap();
+    assert!(md.contains("# Weather API"));
+    assert!(md.contains("Forecasts by coordinates"));
+    assert!(md.contains("## Authentication\n\n- http bearer"));
+    assert!(md.contains("Base URL: https://weather.example.com"));
+    assert!(md.contains("GET /forecast — Get a forecast"));
+
+    // Apps without a spec, or not approved, have no manifest
+    for slug in ["no-spec-api", "rejected-api", "missing"] {
+        let resp = client.get(format!("/.well-known/skills/apps/{}/SKILL.md", slug)).dispatch();
+        assert_eq!(resp.status(), Status::NotFound);
+    }
+}
+
 #[test]
 fn test_skills_skill_md() {
     let (client, _) = setup_client();
@@ -3084,11 +5716,11 @@ fn test_skill_md_root() {
     assert_eq!(resp.status(), Status::Ok);
     let body = resp.into_string().unwrap();
     assert!(body.contains("# App Directory"));
-    // Verify llms.txt serves same content
+    // llms.txt is generated, but points agents back at SKILL.md
     let llms_resp = client.get("/llms.txt").dispatch();
     assert_eq!(llms_resp.status(), Status::Ok);
     let llms_body = llms_resp.into_string().unwrap();
-    assert_eq!(body, llms_body, "llms.txt should alias SKILL.md");
+    assert!(llms_body.contains("/SKILL.md"));
 }
 
 // ── Anonymous Review Bug Fix ──
@@ -3140,10 +5772,11 @@ fn test_multiple_anonymous_reviews_allowed() {
     let body: Value = resp.into_json().unwrap();
     let app_id = body["app_id"].as_str().unwrap();
 
-    // Submit 3 anonymous reviews
-    for rating in [5, 3, 4] {
+    // Submit 3 anonymous reviews from distinct clients
+    for (i, rating) in [5, 3, 4].into_iter().enumerate() {
         let resp = client
             .post(format!("/api/v1/apps/{}/reviews", app_id))
+            .remote(client_addr(&format!("10.0.0.{}", i + 1)))
             .header(ContentType::JSON)
             .body(format!(r#"{{"rating":{},"reviewer_name":"anon"}}"#, rating))
             .dispatch();
@@ -3168,17 +5801,72 @@ fn test_multiple_anonymous_reviews_allowed() {
 }
 
 #[test]
-fn test_anonymous_review_default_reviewer_name() {
+fn test_review_pros_cons_summary() {
     let (client, key) = setup_client();
+    let app_id = submit_simple_app(&client, &key, "Pros Cons App");
+
+    let reviews = [
+        serde_json::json!({ "rating": 5, "pros": ["Fast", " Good docs ", "fast"], "cons": ["Pricey"], "use_case": "Nightly ETL" }),
+        serde_json::json!({ "rating": 4, "pros": ["fast", ""], "cons": ["pricey", "No SDK"] }),
+        serde_json::json!({ "rating": 2, "cons": ["No SDK", "Pricey"] }),
+    ];
+    for (i, review) in reviews.iter().enumerate() {
+        let resp = client
+            .post(format!("/api/v1/apps/{}/reviews", app_id))
+            .remote(client_addr(&format!("10.1.0.{}", i + 1)))
+            .header(ContentType::JSON)
+            .body(review.to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+    }
+
+    let body: Value = client.get(format!("/api/v1/apps/{}/reviews", app_id)).dispatch().into_json().unwrap();
+    let first = body["reviews"].as_array().unwrap().iter().find(|r| r["rating"] == 5).unwrap();
+    assert_eq!(first["pros"], serde_json::json!(["Fast", "Good docs"]));
+    assert_eq!(first["use_case"], "Nightly ETL");
+    let last = body["reviews"].as_array().unwrap().iter().find(|r| r["rating"] == 2).unwrap();
+    assert_eq!(last["pros"], serde_json::json!([]));
+    assert!(last["use_case"].is_null());
 
+    let resp = client.get(format!("/api/v1/apps/{}/reviews/summary?limit=2", app_id)).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let summary: Value = resp.into_json().unwrap();
+    assert_eq!(summary["review_count"], 3);
+    assert_eq!(summary["distribution"]["5"], 1);
+    assert_eq!(summary["distribution"]["3"], 0);
+    assert_eq!(summary["top_pros"][0], serde_json::json!({ "text": "Fast", "count": 2 }));
+    assert_eq!(summary["top_pros"][1]["text"], "Good docs");
+    assert_eq!(summary["top_cons"][0], serde_json::json!({ "text": "Pricey", "count": 3 }));
+    assert_eq!(summary["top_cons"].as_array().unwrap().len(), 2);
+
+    // Too many pros is rejected
+    let many: Vec<String> = (0..11).map(|i| format!("pro {i}")).collect();
     let resp = client
-        .post("/api/v1/apps")
-        .header(Header::new("X-API-Key", key.clone()))
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .header(Header::new("X-API-Key", key))
         .header(ContentType::JSON)
-        .body(r#"{"name":"Default Name App","short_description":"Test","description":"Test default reviewer name","author_name":"Tester"}"#)
+        .body(serde_json::json!({ "rating": 5, "pros": many }).to_string())
         .dispatch();
-    let body: Value = resp.into_json().unwrap();
-    let app_id = body["app_id"].as_str().unwrap();
+    assert_eq!(resp.status(), Status::BadRequest);
+    let err: Value = resp.into_json().unwrap();
+    assert_eq!((err["error"].as_str(), err["field"].as_str()), (Some("FIELD_TOO_LONG"), Some("pros")));
+
+    let resp = client.get("/api/v1/apps/missing/reviews/summary").dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+#[test]
+fn test_anonymous_review_default_reviewer_name() {
+    let (client, key) = setup_client();
+
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Default Name App","short_description":"Test","description":"Test default reviewer name","author_name":"Tester"}"#)
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    let app_id = body["app_id"].as_str().unwrap();
 
     // Submit without reviewer_name
     let resp = client
@@ -3584,10 +6272,11 @@ fn test_review_average_rating() {
     let body: Value = resp.into_json().unwrap();
     let id = body["app_id"].as_str().unwrap();
 
-    // Submit 3 anonymous reviews with different ratings
+    // Submit 3 anonymous reviews with different ratings from distinct clients
     for rating in [3, 4, 5] {
         client
             .post(format!("/api/v1/apps/{}/reviews", id))
+            .remote(client_addr(&format!("10.0.0.{}", rating)))
             .header(ContentType::JSON)
             .body(format!(r#"{{"rating":{}}}"#, rating))
             .dispatch();
@@ -3671,6 +6360,74 @@ fn test_update_app_name_preserves_slug() {
     assert_eq!(app["slug"], original_slug, "Slug should remain stable after name change");
 }
 
+#[test]
+fn test_custom_slug_and_rename() {
+    let (client, key) = setup_client();
+    let submit = |slug: &str| {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": "Chosen Slug Agent",
+                "short_description": "Test",
+                "description": "Custom slug test",
+                "author_name": "Test",
+                "slug": slug,
+            }).to_string())
+            .dispatch();
+        let status = resp.status();
+        (status, resp.into_json::<Value>().unwrap())
+    };
+
+    let (status, body) = submit("chosen-agent");
+    assert_eq!(status, Status::Created);
+    assert_eq!(body["slug"], "chosen-agent");
+    let id = body["app_id"].as_str().unwrap().to_string();
+
+    for bad in ["Chosen", "a", "-lead", "double--dash", "trending"] {
+        let (status, body) = submit(bad);
+        assert_eq!(status, Status::BadRequest, "{bad}");
+        assert_eq!(body["error"], "INVALID_SLUG");
+        assert_eq!(body["field"], "slug");
+    }
+    let (status, body) = submit("chosen-agent");
+    assert_eq!(status, Status::Conflict);
+    assert_eq!(body["error"], "SLUG_TAKEN");
+
+    let rename = |slug: &str| {
+        let resp = client
+            .post(format!("/api/v1/apps/{}/slug", id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "slug": slug }).to_string())
+            .dispatch();
+        let status = resp.status();
+        (status, resp.into_json::<Value>().unwrap())
+    };
+    let (status, body) = rename("renamed-agent");
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["slug"], "renamed-agent");
+    assert_eq!(body["previous_slug"], "chosen-agent");
+    assert_eq!(body["redirects"], serde_json::json!(["chosen-agent"]));
+
+    // The old slug redirects to the new one and can't be taken by others
+    let resp = client.get("/api/v1/apps/chosen-agent").dispatch();
+    assert_eq!(resp.status(), Status::MovedPermanently);
+    assert_eq!(resp.headers().get_one("Location"), Some("/api/v1/apps/renamed-agent"));
+    let resp = client.get("/api/v1/apps/renamed-agent").dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(submit("chosen-agent").0, Status::Conflict);
+    assert_eq!(rename("renamed-agent").0, Status::BadRequest);
+
+    // The app itself can take an old slug back
+    let (status, body) = rename("chosen-agent");
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["redirects"], serde_json::json!(["renamed-agent"]));
+    let resp = client.get("/api/v1/apps/chosen-agent").dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+}
+
 // ── Health check response structure ──
 
 #[test]
@@ -4249,9 +7006,10 @@ fn test_review_mixed_anonymous_and_authenticated() {
         .dispatch();
     assert_eq!(resp.status(), Status::Created);
 
-    // Second anonymous review (should create new entry)
+    // Second anonymous review from a different client (should create new entry)
     let resp = client
         .post(format!("/api/v1/apps/{}/reviews", id))
+        .header(Header::new("User-Agent", "another-agent/1.0"))
         .header(ContentType::JSON)
         .body(r#"{"rating":2,"title":"Another anonymous"}"#)
         .dispatch();
@@ -4533,6 +7291,50 @@ fn test_edit_token_full_lifecycle() {
     assert_eq!(resp.status(), Status::NotFound);
 }
 
+#[test]
+fn test_actor_credentials() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let user = app_directory::auth::create_api_key(&conn, "actor-user", false, Some(100));
+    drop(conn);
+    let app_id = submit_simple_app(&client, &admin, "Actor App");
+
+    let patch = |headers: Vec<Header<'static>>| {
+        let mut req = client
+            .patch(format!("/api/v1/apps/{}", app_id))
+            .header(ContentType::JSON)
+            .body(r#"{"description":"Edited"}"#);
+        for h in headers {
+            req = req.header(h);
+        }
+        req.dispatch().status()
+    };
+    assert_eq!(patch(vec![]), Status::Unauthorized);
+    assert_eq!(patch(vec![Header::new("X-API-Key", user.clone())]), Status::Forbidden);
+    assert_eq!(patch(vec![Header::new("X-Edit-Token", "ad_wrong")]), Status::Forbidden);
+    assert_eq!(patch(vec![Header::new("X-API-Key", admin.clone())]), Status::Ok);
+
+    // A presented but invalid key is rejected rather than treated as anonymous
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", "ad_not_a_key"))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Bad Key","short_description":"x","description":"x","author_name":"x"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Unauthorized);
+
+    // Admin routes: no credentials is 401, a regular key is 403
+    let resp = client.get("/api/v1/apps/pending").dispatch();
+    assert_eq!(resp.status(), Status::Unauthorized);
+    let resp = client
+        .get("/api/v1/apps/pending")
+        .header(Header::new("X-API-Key", user.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Forbidden);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "ADMIN_REQUIRED");
+}
+
 // ── Webhook: multiple webhooks for same events ──
 
 #[test]
@@ -4642,6 +7444,77 @@ fn test_webhook_secret_only_on_create() {
     assert!(wh.get("secret").is_none() || wh["secret"].is_null());
 }
 
+#[test]
+fn test_webhook_secret_rotation() {
+    let (client, key, db_path) = setup_client_with_path();
+    let resp = client
+        .post("/api/v1/webhooks")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"url":"https://hook.example.com/rotate"}"#)
+        .dispatch();
+    let created: Value = resp.into_json().unwrap();
+    let wh_id = created["id"].as_str().unwrap().to_string();
+    let original = created["secret"].as_str().unwrap().to_string();
+    let rotate = |body: Option<&str>| {
+        let req = client
+            .post(format!("/api/v1/webhooks/{}/rotate-secret", wh_id))
+            .header(Header::new("X-API-Key", key.clone()));
+        match body {
+            Some(b) => req.header(ContentType::JSON).body(b).dispatch(),
+            None => req.dispatch(),
+        }
+    };
+    let stored = || {
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.query_row(
+            "SELECT secret, previous_secret, previous_secret_expires_at > datetime('now', '+86390 seconds') FROM webhooks WHERE id = ?1",
+            [&wh_id],
+            |r| Ok((r.get::<_, String>(0)?, r.get::<_, Option<String>>(1)?, r.get::<_, Option<bool>>(2)?)),
+        )
+        .unwrap()
+    };
+
+    // Default grace period keeps the old secret signing for a day
+    let resp = rotate(None);
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    let rotated = body["secret"].as_str().unwrap().to_string();
+    assert!(rotated.starts_with("whsec_") && rotated != original);
+    assert_eq!(body["grace_period_secs"], 86400);
+    assert!(body["previous_secret_expires_at"].is_string());
+    assert_eq!(stored(), (rotated.clone(), Some(original.clone()), Some(true)));
+
+    let list: Value = client.get("/api/v1/webhooks").header(Header::new("X-API-Key", key.clone())).dispatch().into_json().unwrap();
+    assert_eq!(list["webhooks"][0]["previous_secret_expires_at"], body["previous_secret_expires_at"]);
+    assert!(list["webhooks"][0].get("secret").is_none());
+
+    // Rotating again replaces the oldest secret
+    let body: Value = rotate(Some(r#"{"grace_period_secs":60}"#)).into_json().unwrap();
+    let newest = body["secret"].as_str().unwrap().to_string();
+    assert_eq!(stored(), (newest.clone(), Some(rotated), Some(false)));
+
+    // No grace period retires the old secret at once
+    let body: Value = rotate(Some(r#"{"grace_period_secs":0}"#)).into_json().unwrap();
+    assert!(body["previous_secret_expires_at"].is_null());
+    assert_eq!(stored(), (body["secret"].as_str().unwrap().to_string(), None, None));
+
+    let resp = rotate(Some(r#"{"grace_period_secs":99999999}"#));
+    assert_eq!(resp.status(), Status::BadRequest);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "INVALID_GRACE_PERIOD");
+
+    let resp = client.post("/api/v1/webhooks/nope/rotate-secret").header(Header::new("X-API-Key", key.clone())).dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let viewer = app_directory::auth::create_api_key(&conn, "viewer", false, Some(100));
+    let resp = client
+        .post(format!("/api/v1/webhooks/{}/rotate-secret", wh_id))
+        .header(Header::new("X-API-Key", viewer))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Forbidden);
+}
+
 // ── Key management: create + list + revoke lifecycle ──
 
 #[test]
@@ -5173,6 +8046,106 @@ fn test_batch_health_check_admin() {
     assert!(body["results"].is_array());
 }
 
+// ── Batch health check: selected apps and queued mode ──
+
+#[test]
+fn test_batch_health_check_selection() {
+    let (client, key) = setup_client();
+    let submit = |name: &str, protocol: &str, category: &str| -> String {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(
+                serde_json::json!({
+                    "name": name,
+                    "short_description": "Test",
+                    "description": "Batch selection test",
+                    "author_name": "Test",
+                    "protocol": protocol,
+                    "category": category,
+                    "api_url": "http://127.0.0.1:1",
+                })
+                .to_string(),
+            )
+            .dispatch();
+        let body: Value = resp.into_json().unwrap();
+        body["app_id"].as_str().unwrap().to_string()
+    };
+    let weather = submit("Weather Feed", "rest", "data/weather");
+    let chat = submit("Chat Relay", "mcp", "communication");
+    let batch = |body: Value| {
+        let resp = client
+            .post("/api/v1/apps/health-check/batch")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch();
+        let status = resp.status();
+        (status, resp.into_json::<Value>().unwrap())
+    };
+
+    // Only the listed ids are checked; unknown ones are reported
+    let (status, body) = batch(serde_json::json!({"app_ids": [weather, "missing"]}));
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["checked"], 1);
+    assert_eq!(body["unreachable"], 1);
+    assert_eq!(body["unmatched"], serde_json::json!(["missing"]));
+    assert_eq!(body["results"][0]["app_id"], weather.as_str());
+    assert_eq!(body["results"][0]["status"], "unreachable");
+
+    // Filters: category subtree and protocol
+    let (_, body) = batch(serde_json::json!({"category": "data"}));
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["results"][0]["app_id"], weather.as_str());
+    let (_, body) = batch(serde_json::json!({"protocol": "mcp"}));
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["results"][0]["app_id"], chat.as_str());
+
+    // Both were just checked, so nothing is stale yet
+    let (_, body) = batch(serde_json::json!({"stale_only": true}));
+    assert_eq!(body["total"], 0);
+    let (_, body) = batch(serde_json::json!({"stale_only": true, "stale_after_secs": 0}));
+    assert_eq!(body["total"], 2);
+
+    // Validation
+    let (status, body) = batch(serde_json::json!({"app_ids": []}));
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["error"], "NO_APPS");
+    let (status, body) = batch(serde_json::json!({"protocol": "smtp"}));
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["error"], "INVALID_PROTOCOL");
+
+    // Queued mode returns a job to poll
+    let (status, body) = batch(serde_json::json!({"app_ids": [weather, chat], "queue": true}));
+    assert_eq!(status, Status::Accepted);
+    assert_eq!(body["total"], 2);
+    let status_url = body["status_url"].as_str().unwrap().to_string();
+    let mut job = Value::Null;
+    for _ in 0..50 {
+        job = client
+            .get(status_url.as_str())
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch()
+            .into_json()
+            .unwrap();
+        if job["status"] != "running" {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert_eq!(job["status"], "completed");
+    assert_eq!(job["checked"], 2);
+    assert_eq!(job["results"].as_array().unwrap().len(), 2);
+
+    let resp = client
+        .get("/api/v1/apps/health-check/batch/nope")
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
 // ── Delete with wrong edit token ──
 
 #[test]
@@ -5411,10 +8384,11 @@ fn test_review_ordering() {
     let body: Value = resp.into_json().unwrap();
     let id = body["app_id"].as_str().unwrap();
 
-    // Submit multiple anonymous reviews
+    // Submit multiple anonymous reviews from distinct clients
     for i in 1..=4 {
         client
             .post(format!("/api/v1/apps/{}/reviews", id))
+            .remote(client_addr(&format!("10.0.0.{}", i)))
             .header(ContentType::JSON)
             .body(format!(r#"{{"rating":{},"title":"Review {}"}}"#, i, i))
             .dispatch();
@@ -5575,3 +8549,4312 @@ fn test_list_apps_search_param() {
     assert!(total_empty >= 2, "empty search= should return all approved apps");
 }
 
+#[test]
+fn test_list_and_search_share_filters() {
+    let (client, key) = setup_client();
+    let featured = submit_simple_app(&client, &key, "Shared Filter Weather");
+    submit_simple_app(&client, &key, "Shared Filter Tides");
+    client
+        .patch(format!("/api/v1/apps/{}", featured))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"is_featured":true,"is_verified":true}"#)
+        .dispatch();
+
+    let names = |url: &str| -> Vec<String> {
+        let body: Value = client.get(url).dispatch().into_json().unwrap();
+        let mut names: Vec<String> =
+            body["apps"].as_array().unwrap().iter().map(|a| a["name"].as_str().unwrap().to_string()).collect();
+        names.sort();
+        names
+    };
+
+    for base in ["/api/v1/apps/search?q=shared%20filter&", "/api/v1/apps?q=shared%20filter&"] {
+        assert_eq!(names(&format!("{base}featured=true")), ["Shared Filter Weather"]);
+        assert_eq!(names(&format!("{base}featured=false")), ["Shared Filter Tides"]);
+        assert_eq!(names(&format!("{base}verified=true")), ["Shared Filter Weather"]);
+        assert_eq!(names(&format!("{base}health=unknown")), ["Shared Filter Tides", "Shared Filter Weather"]);
+        assert!(names(&format!("{base}health=healthy")).is_empty());
+    }
+
+    // Quoted phrases and multiple terms work on the list endpoint too
+    assert_eq!(names("/api/v1/apps?q=%22filter%20tides%22"), ["Shared Filter Tides"]);
+    assert_eq!(names("/api/v1/apps?q=shared%20weather"), ["Shared Filter Weather"]);
+    assert!(names("/api/v1/apps?q=shared%20nonexistent").is_empty());
+}
+
+
+// ── Content Negotiation ──
+
+fn submit_negotiation_app(client: &Client, key: &str) -> String {
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.to_string()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Negotiated App","short_description":"Speaks many formats","description":"Long description here","api_url":"https://api.example.com","tags":["docs","formats"],"author_name":"Tester","author_url":"https://tester.example.com"}"#)
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    body["app_id"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_get_app_defaults_to_json() {
+    let (client, key) = setup_client();
+    let app_id = submit_negotiation_app(&client, &key);
+
+    let resp = client
+        .get(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("Accept", "*/*"))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.content_type(), Some(ContentType::JSON));
+    assert_eq!(resp.headers().get_one("Vary"), Some("Accept, Accept-Language"));
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["name"], "Negotiated App");
+}
+
+#[test]
+fn test_get_app_as_markdown() {
+    let (client, key) = setup_client();
+    let app_id = submit_negotiation_app(&client, &key);
+
+    let resp = client
+        .get(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("Accept", "text/markdown"))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let ct = resp.content_type().unwrap();
+    assert_eq!((ct.top().as_str(), ct.sub().as_str()), ("text", "markdown"));
+    let body = resp.into_string().unwrap();
+    assert!(body.starts_with("# Negotiated App\n"));
+    assert!(body.contains("> Speaks many formats"));
+    assert!(body.contains("- **Tags:** `docs`, `formats`"));
+    assert!(body.contains("- **Author:** [Tester](https://tester.example.com)"));
+    assert!(body.contains("- API: <https://api.example.com>"));
+}
+
+#[test]
+fn test_get_app_as_plain_text() {
+    let (client, key) = setup_client();
+    let app_id = submit_negotiation_app(&client, &key);
+
+    let resp = client
+        .get(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("Accept", "text/html;q=0.9, text/plain;q=0.8"))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.content_type(), Some(ContentType::Plain));
+    let body = resp.into_string().unwrap();
+    assert!(body.starts_with("Negotiated App\nSpeaks many formats\n"));
+    assert!(body.contains("Protocol: rest"));
+    assert!(body.contains("Tags: docs, formats"));
+}
+
+#[test]
+fn test_get_app_not_found_is_json_regardless_of_accept() {
+    let (client, _) = setup_client();
+    let resp = client
+        .get("/api/v1/apps/does-not-exist")
+        .header(Header::new("Accept", "text/markdown"))
+        .dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "NOT_FOUND");
+}
+
+// ── Realtime: WebSocket channel & event filters ──
+
+#[test]
+fn test_event_filter_matching() {
+    use app_directory::events::{AppEvent, EventFilter};
+
+    let approved = AppEvent {
+        event: "app.approved".to_string(),
+        data: serde_json::json!({ "app_id": "abc" }),
+    };
+    let review = AppEvent {
+        event: "review.submitted".to_string(),
+        data: serde_json::json!({ "app_id": "xyz" }),
+    };
+
+    let all = EventFilter::from_query(None, None);
+    assert!(all.matches(&approved) && all.matches(&review));
+
+    let by_type = EventFilter::from_query(Some("review.submitted, health.checked"), None);
+    assert!(!by_type.matches(&approved));
+    assert!(by_type.matches(&review));
+
+    let wildcard = EventFilter::from_query(Some("app.*"), None);
+    assert!(wildcard.matches(&approved));
+    assert!(!wildcard.matches(&review));
+
+    let by_app = EventFilter::from_query(None, Some("abc"));
+    assert!(by_app.matches(&approved));
+    assert!(!by_app.matches(&review));
+
+    let combined = EventFilter::from_query(Some("app.*"), Some("xyz"));
+    assert!(!combined.matches(&approved));
+    assert!(!combined.matches(&review));
+}
+
+#[test]
+fn test_websocket_requires_upgrade() {
+    let (client, _) = setup_client();
+    // A plain GET without the WebSocket handshake headers is not upgraded
+    let resp = client.get("/api/v1/events/ws").dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+}
+
+#[test]
+fn test_websocket_handshake_upgrades() {
+    let (client, _) = setup_client();
+    let resp = client
+        .get("/api/v1/events/ws?events=app.*")
+        .header(Header::new("Connection", "Upgrade"))
+        .header(Header::new("Upgrade", "websocket"))
+        .header(Header::new("Sec-WebSocket-Version", "13"))
+        .header(Header::new("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+        .dispatch();
+    // The local test client doesn't perform the protocol switch, but the handshake response is built
+    assert_ne!(resp.status(), Status::BadRequest);
+    assert_eq!(
+        resp.headers().get_one("Sec-WebSocket-Accept"),
+        Some("s3pPLMBiTxaQ9kYGzzhZRbK+xOo=")
+    );
+}
+
+#[test]
+fn test_sse_stream_accepts_filters() {
+    let (client, _) = setup_client();
+    let resp = client
+        .get("/api/v1/events/stream?events=app.approved,review.submitted&app_id=abc")
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+}
+
+#[test]
+fn test_health_event_stream() {
+    let (client, _) = setup_client();
+    for uri in ["/api/v1/events/health", "/api/v1/events/health?app_id=abc"] {
+        let resp = client.get(uri).dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+        assert_eq!(resp.content_type(), Some(ContentType::EventStream));
+    }
+}
+
+#[test]
+fn test_event_history() {
+    let (client, key, db_path) = setup_client_with_path();
+    let a = submit_simple_app(&client, &key, "History A");
+    let b = submit_simple_app(&client, &key, "History B");
+    for (id, rating) in [(&a, 5), (&b, 3)] {
+        let resp = client
+            .post(format!("/api/v1/apps/{}/reviews", id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "rating": rating }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+    }
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", a))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"short_description": "Updated"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+
+    let get = |query: &str| -> Value {
+        let resp = client.get(format!("/api/v1/events{}", query)).dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+        resp.into_json().unwrap()
+    };
+
+    let body = get("?type=review.*");
+    assert_eq!(body["total"], 2);
+    let events = body["events"].as_array().unwrap();
+    assert_eq!(events[0]["event"], "review.submitted");
+    assert_eq!(events[0]["app_id"], a.as_str());
+    assert!(events[0]["id"].as_i64().unwrap() < events[1]["id"].as_i64().unwrap());
+
+    let body = get(&format!("?app_id={}&type=review.submitted,app.updated", a));
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["events"][1]["event"], "app.updated");
+
+    let body = get("?type=review.*&per_page=1&page=2");
+    assert_eq!(body["events"].as_array().unwrap().len(), 1);
+    assert_eq!(body["events"][0]["app_id"], b.as_str());
+
+    assert_eq!(get("?since=2999-01-01T00:00:00Z")["total"], 0);
+    assert_eq!(get("?type=review.*&since=2000-01-01T00:00:00Z")["total"], 2);
+    let resp = client.get("/api/v1/events?since=yesterday").dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_SINCE");
+
+    // Pruning drops rows past the retention window
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute(
+        "INSERT INTO events (event, data, created_at) VALUES ('app.updated', '{}', datetime('now', '-30 days'))",
+        [],
+    )
+    .unwrap();
+    assert_eq!(app_directory::events::prune(&conn, 7).unwrap(), 1);
+}
+
+#[test]
+fn test_event_outbox_relay() {
+    let (client, key, db_path) = setup_client_with_path();
+    let id = submit_simple_app(&client, &key, "Outbox App");
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let pending = || -> i64 { conn.query_row("SELECT COUNT(*) FROM event_outbox", [], |r| r.get(0)).unwrap() };
+
+    // Dispatched events leave the outbox once delivered
+    for _ in 0..50 {
+        if pending() == 0 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    assert_eq!(pending(), 0);
+
+    // An event committed but never dispatched (the process stopped) is relayed
+    conn.execute(
+        "INSERT INTO event_outbox (event, data) VALUES ('app.updated', ?1)",
+        rusqlite::params![serde_json::json!({ "app_id": id }).to_string()],
+    )
+    .unwrap();
+    let bus = app_directory::events::EventBus::with_webhooks(
+        app_directory::webhooks::init_webhook_db(&db_path),
+        7,
+        app_directory::webhooks::DeliverySettings::default(),
+    );
+    let rt = rocket::tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        assert_eq!(bus.relay_outbox(10), 1);
+        // Claimed entries aren't picked up again while delivery runs
+        assert_eq!(bus.relay_outbox(10), 0);
+        for _ in 0..50 {
+            if pending() == 0 {
+                break;
+            }
+            rocket::tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    });
+    assert_eq!(pending(), 0);
+
+    let resp = client.get(format!("/api/v1/events?app_id={}&type=app.updated", id)).dispatch();
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["total"], 1);
+}
+
+// ── Anonymous review dedup & throttling ──
+
+#[test]
+fn test_anonymous_review_same_fingerprint_upserts() {
+    let (client, key) = setup_client();
+
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Fingerprint App","short_description":"Test","description":"Anonymous dedup","author_name":"Tester"}"#)
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    let app_id = body["app_id"].as_str().unwrap();
+
+    let mut ids = Vec::new();
+    for rating in [1, 5] {
+        let resp = client
+            .post(format!("/api/v1/apps/{}/reviews", app_id))
+            .remote(client_addr("203.0.113.7"))
+            .header(Header::new("User-Agent", "bomber/1.0"))
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"rating":{}}}"#, rating))
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+        let body: Value = resp.into_json().unwrap();
+        ids.push(body["id"].as_str().unwrap().to_string());
+    }
+    assert_eq!(ids[0], ids[1], "same fingerprint should update the existing review");
+
+    // Same IP with a different User-Agent counts as a different reviewer
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .remote(client_addr("203.0.113.7"))
+        .header(Header::new("User-Agent", "other/2.0"))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":3}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+
+    let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(app["review_count"], 2);
+    assert!((app["avg_rating"].as_f64().unwrap() - 4.0).abs() < 0.01);
+
+    // Fingerprints are never exposed
+    let reviews: Value = client
+        .get(format!("/api/v1/apps/{}/reviews", app_id))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert!(reviews["reviews"][0].get("reviewer_fingerprint").is_none());
+}
+
+#[test]
+fn test_anonymous_review_daily_ip_cap() {
+    let (client, key) = setup_client();
+
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Capped App","short_description":"Test","description":"Per-IP cap","author_name":"Tester"}"#)
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    let app_id = body["app_id"].as_str().unwrap();
+
+    // Default cap is 20 anonymous reviews per IP per day, regardless of User-Agent
+    for i in 0..20 {
+        let resp = client
+            .post(format!("/api/v1/apps/{}/reviews", app_id))
+            .remote(client_addr("198.51.100.1"))
+            .header(Header::new("User-Agent", format!("agent-{}", i)))
+            .header(ContentType::JSON)
+            .body(r#"{"rating":4}"#)
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+    }
+
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .remote(client_addr("198.51.100.1"))
+        .header(Header::new("User-Agent", "agent-21"))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":1}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::TooManyRequests);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "REVIEW_LIMIT_EXCEEDED");
+
+    // Other IPs and authenticated reviewers are unaffected
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .remote(client_addr("198.51.100.2"))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":5}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .remote(client_addr("198.51.100.1"))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":5}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+}
+
+// ── API spec verification ──
+
+/// Serve a fixed HTTP 200 body on a local port (for as many requests as arrive) and return its URL.
+fn serve_static(body: &'static str) -> String {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            let resp = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(resp.as_bytes());
+        }
+    });
+    format!("http://{}", addr)
+}
+
+/// Poll an app until its spec_status leaves "pending" (or give up after ~5s).
+fn wait_for_spec_status(client: &Client, app_id: &str) -> Value {
+    for _ in 0..50 {
+        let app: Value = client
+            .get(format!("/api/v1/apps/{}", app_id))
+            .dispatch()
+            .into_json()
+            .unwrap();
+        if app["spec_status"] != "pending" {
+            return app;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    panic!("spec verification did not finish");
+}
+
+#[test]
+fn test_lint_spec() {
+    use app_directory::specs::lint_spec;
+
+    let ok = lint_spec(r#"{"openapi":"3.0.3","info":{"title":"Pets","version":"1.2.0"},"servers":[{"url":"https://pets.example.com/v1"}],"paths":{}}"#);
+    assert!(ok.valid, "{:?}", ok.errors);
+    assert_eq!(ok.title.as_deref(), Some("Pets"));
+    assert_eq!(ok.version.as_deref(), Some("1.2.0"));
+    assert_eq!(ok.servers, vec!["https://pets.example.com/v1".to_string()]);
+
+    let yaml = lint_spec("openapi: 3.1.0\ninfo:\n  title: Yaml API\n  version: '2'\npaths: {}\n");
+    assert!(yaml.valid, "{:?}", yaml.errors);
+    assert_eq!(yaml.openapi_version.as_deref(), Some("3.1.0"));
+
+    let swagger = lint_spec(r#"{"swagger":"2.0","info":{"title":"Old","version":"1"},"paths":{}}"#);
+    assert!(!swagger.valid);
+    assert!(swagger.errors[0].contains("Swagger 2.0"));
+
+    let missing = lint_spec(r#"{"openapi":"3.0.0"}"#);
+    assert!(!missing.valid);
+    assert!(missing.errors.iter().any(|e| e.contains("info")));
+    assert!(missing.errors.iter().any(|e| e.contains("paths")));
+
+    assert!(!lint_spec("<html>not a spec</html>: [").valid);
+
+    let summarized = lint_spec(
+        r#"{"openapi":"3.0.3","info":{"title":"Pets","version":"1"},
+            "paths":{"/pets":{"get":{"summary":"List pets"},"post":{}}},
+            "components":{"securitySchemes":{"key":{"type":"apiKey","in":"header","name":"X-Key"},"jwt":{"type":"http","scheme":"Bearer"}}}}"#,
+    );
+    assert_eq!(summarized.endpoints, vec!["GET /pets — List pets".to_string(), "POST /pets".to_string()]);
+    assert!(summarized.auth.contains(&"apiKey (header X-Key)".to_string()));
+    assert!(summarized.auth.contains(&"http bearer".to_string()));
+
+    let tools = lint_spec(
+        r##"{"openapi":"3.0.3","info":{"title":"Pets","version":"1"},
+            "paths":{"/pets/{petId}":{
+              "parameters":[{"name":"petId","in":"path","schema":{"type":"integer"}}],
+              "get":{"operationId":"getPet","summary":"Get a pet","parameters":[{"name":"fields","in":"query","schema":{"type":"string"}}]},
+              "put":{"summary":"Replace a pet","requestBody":{"required":true,"content":{"application/json":{"schema":{"$ref":"#/components/schemas/Pet"}}}}}}},
+            "components":{"schemas":{"Pet":{"type":"object","required":["name"],"properties":{"name":{"type":"string"},"owner":{"$ref":"#/components/schemas/Owner"}}},
+                                     "Owner":{"type":"object","properties":{"email":{"type":"string"}}}}}}"##,
+    )
+    .tools;
+    assert_eq!(tools.len(), 2);
+    assert_eq!(tools[0]["name"], "getPet");
+    assert_eq!(tools[0]["description"], "Get a pet");
+    assert_eq!(tools[0]["parameters"]["properties"]["petId"]["type"], "integer");
+    assert_eq!(tools[0]["parameters"]["required"], serde_json::json!(["petId"]));
+    assert_eq!(tools[0]["http"]["params"]["fields"], "query");
+    assert_eq!(tools[1]["name"], "put_pets_petId");
+    let body = &tools[1]["parameters"]["properties"]["body"];
+    assert_eq!(body["required"], serde_json::json!(["name"]));
+    assert_eq!(body["properties"]["owner"]["properties"]["email"]["type"], "string");
+    assert_eq!(tools[1]["parameters"]["required"], serde_json::json!(["petId", "body"]));
+    assert_eq!(tools[1]["http"]["content_type"], "application/json");
+}
+
+#[test]
+fn test_spec_verified_on_submission() {
+    let (client, key) = setup_client();
+    let spec_url = serve_static(
+        r#"{"openapi":"3.0.3","info":{"title":"Spec'd API","version":"0.9.1"},"servers":[{"url":"https://api.example.com"}],"paths":{"/ping":{}}}"#,
+    ) + "/openapi.json";
+
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": "Spec App",
+            "short_description": "Has a spec",
+            "description": "Spec verification",
+            "author_name": "Tester",
+            "api_spec_url": spec_url,
+        }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+    let body: Value = resp.into_json().unwrap();
+    let app_id = body["app_id"].as_str().unwrap();
+
+    let app = wait_for_spec_status(&client, app_id);
+    assert_eq!(app["spec_status"], "valid");
+    assert_eq!(app["spec"]["title"], "Spec'd API");
+    assert_eq!(app["spec"]["version"], "0.9.1");
+    assert_eq!(app["spec"]["openapi_version"], "3.0.3");
+    assert_eq!(app["spec"]["servers"][0], "https://api.example.com");
+    assert_eq!(app["spec"]["errors"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_app_tools_json() {
+    let (client, key) = setup_client();
+    let spec_url = serve_static(
+        r#"{"openapi":"3.0.3","info":{"title":"Weather","version":"2"},"servers":[{"url":"https://weather.example.com"}],
+            "paths":{"/forecast":{"get":{"operationId":"get forecast","summary":"Forecast for a city",
+              "parameters":[{"name":"city","in":"query","required":true,"description":"City name","schema":{"type":"string"}}]}}}}"#,
+    );
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": "Weather Tools",
+            "short_description": "Forecasts",
+            "description": "Tool export",
+            "author_name": "Tester",
+            "api_spec_url": spec_url,
+        }).to_string())
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    let app_id = body["app_id"].as_str().unwrap();
+    assert_eq!(wait_for_spec_status(&client, app_id)["spec_status"], "valid");
+
+    let resp = client.get("/api/v1/apps/weather-tools/tools.json").dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let tools: Value = resp.into_json().unwrap();
+    assert_eq!(tools["app_id"], app_id);
+    assert_eq!(tools["servers"][0], "https://weather.example.com");
+    let tool = &tools["tools"][0];
+    assert_eq!(tool["name"], "get_forecast");
+    assert_eq!(tool["description"], "Forecast for a city");
+    assert_eq!(tool["parameters"]["type"], "object");
+    assert_eq!(tool["parameters"]["properties"]["city"]["description"], "City name");
+    assert_eq!(tool["parameters"]["required"], serde_json::json!(["city"]));
+    assert_eq!(tool["http"]["method"], "GET");
+    assert_eq!(tool["http"]["path"], "/forecast");
+
+    // No spec, no tools
+    let other = submit_simple_app(&client, &key, "No Spec");
+    let resp = client.get(format!("/api/v1/apps/{}/tools.json", other)).dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+#[test]
+fn test_a2a_agent_cards() {
+    let (client, key) = setup_client();
+
+    let resp = client.get("/.well-known/agent.json").header(Header::new("Host", "apps.example.com")).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let card: Value = resp.into_json().unwrap();
+    assert_eq!(card["url"], "http://apps.example.com/api/v1");
+    let skills: Vec<&str> = card["skills"].as_array().unwrap().iter().map(|s| s["id"].as_str().unwrap()).collect();
+    assert_eq!(skills, ["search", "submit", "review"]);
+
+    let agent = serve_static(
+        r#"{"name":"Echo Agent","description":"Repeats you","url":"https://echo.example.com/a2a","version":"2.1","capabilities":{"streaming":true},"skills":[{"id":"echo","name":"Echo"}]}"#,
+    );
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": "Echo Agent",
+            "short_description": "A2A echo",
+            "description": "Agent card verification",
+            "author_name": "Tester",
+            "protocol": "a2a",
+            "api_url": format!("{}/a2a", agent),
+        }).to_string())
+        .dispatch();
+    let app_id = resp.into_json::<Value>().unwrap()["app_id"].as_str().unwrap().to_string();
+
+    let mut app = Value::Null;
+    for _ in 0..50 {
+        app = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+        if app["agent_card"]["status"] != "pending" {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert_eq!(app["agent_card"]["status"], "valid", "{}", app["agent_card"]);
+    assert_eq!(app["agent_card"]["card_url"], format!("{}/.well-known/agent.json", agent));
+    assert_eq!(app["agent_card"]["skills"], serde_json::json!(["echo"]));
+
+    let resp = client.get(format!("/api/v1/apps/{}/agent.json", app_id)).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.into_json::<Value>().unwrap()["name"], "Echo Agent");
+
+    // Switching protocol drops the stored card
+    client
+        .patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"protocol":"rest"}"#)
+        .dispatch();
+    let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+    assert!(app.get("agent_card").is_none());
+    let resp = client.get(format!("/api/v1/apps/{}/agent.json", app_id)).dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+#[test]
+fn test_spec_invalid_and_unreachable() {
+    let (client, key) = setup_client();
+    let bad_url = serve_static(r#"{"hello":"world"}"#);
+
+    let submit = |spec_url: &str| -> String {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": format!("Spec {}", spec_url),
+                "short_description": "Spec",
+                "description": "Spec",
+                "author_name": "Tester",
+                "api_spec_url": spec_url,
+            }).to_string())
+            .dispatch();
+        let body: Value = resp.into_json().unwrap();
+        body["app_id"].as_str().unwrap().to_string()
+    };
+
+    let invalid_id = submit(&bad_url);
+    let app = wait_for_spec_status(&client, &invalid_id);
+    assert_eq!(app["spec_status"], "invalid");
+    assert!(!app["spec"]["errors"].as_array().unwrap().is_empty());
+
+    let unreachable_id = submit("http://127.0.0.1:1/openapi.json");
+    let app = wait_for_spec_status(&client, &unreachable_id);
+    assert_eq!(app["spec_status"], "unreachable");
+
+    // Apps without a spec URL have no spec status
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"No Spec","short_description":"x","description":"x","author_name":"x"}"#)
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    let app: Value = client
+        .get(format!("/api/v1/apps/{}", body["app_id"].as_str().unwrap()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert!(app["spec_status"].is_null());
+    assert!(app["spec"].is_null());
+}
+
+#[test]
+fn test_conformance_check() {
+    let (client, admin, db_path) = setup_client_with_path();
+    // The fixture answers every path with the spec itself, so /status returns it too
+    let conformant = serve_static(
+        r#"{"openapi":"3.0.3","info":{"title":"Echo","version":"1"},"paths":{"/status":{"get":{"responses":{"200":{"content":{"application/json":{"schema":{"type":"object","required":["openapi","info"],"properties":{"openapi":{"type":"string"}}}}}}}}}}}"#,
+    );
+    let drifted = serve_static(
+        r#"{"openapi":"3.0.3","info":{"title":"Echo","version":"1"},"paths":{"/pets":{"get":{"responses":{"200":{"content":{"application/json":{"schema":{"type":"array"}}}}}}},"/me":{"get":{"security":[{"key":[]}],"responses":{"200":{}}}}}}"#,
+    );
+
+    let submit = |name: &str, spec_url: Option<String>| -> String {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", admin.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name,
+                "short_description": "Probe",
+                "description": "Probe",
+                "author_name": "Tester",
+                "api_spec_url": spec_url,
+            }).to_string())
+            .dispatch();
+        let body: Value = resp.into_json().unwrap();
+        body["app_id"].as_str().unwrap().to_string()
+    };
+    let check = |id: &str, key: &str| {
+        let resp = client
+            .post(format!("/api/v1/apps/{}/conformance-check", id))
+            .header(Header::new("X-API-Key", key.to_string()))
+            .dispatch();
+        let status = resp.status();
+        (status, resp.into_json::<Value>().unwrap())
+    };
+
+    let passing = submit("Conformant API", Some(format!("{}/openapi.json", conformant)));
+    let (status, body) = check(&passing, &admin);
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["status"], "passed");
+    assert_eq!(body["probes"].as_array().unwrap().len(), 1);
+    assert_eq!(body["probes"][0]["url"], format!("{}/status", conformant));
+    assert_eq!(body["probes"][0]["status_code"], 200);
+
+    let app: Value = client.get(format!("/api/v1/apps/{}", passing)).dispatch().into_json().unwrap();
+    assert_eq!(app["conformance"]["status"], "passed");
+    assert!(app["conformance"]["checked_at"].is_string());
+
+    // Only the public endpoint is probed, and its body isn't an array
+    let failing = submit("Drifted API", Some(format!("{}/openapi.json", drifted)));
+    let (_, body) = check(&failing, &admin);
+    assert_eq!(body["status"], "failed");
+    assert_eq!(body["probes"].as_array().unwrap().len(), 1);
+    assert_eq!(body["probes"][0]["path"], "/pets");
+    assert_eq!(body["probes"][0]["errors"][0], "$: expected array, got object");
+
+    let unreachable = submit("Gone API", Some("http://127.0.0.1:1/openapi.json".to_string()));
+    let (_, body) = check(&unreachable, &admin);
+    assert_eq!(body["status"], "untestable");
+
+    let no_spec = submit("Specless API", None);
+    let (status, body) = check(&no_spec, &admin);
+    assert_eq!(status, Status::UnprocessableEntity);
+    assert_eq!(body["error"], "NO_URL");
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let member = app_directory::auth::create_api_key(&conn, "member", false, Some(1000));
+    assert_eq!(check(&passing, &member).0, Status::Forbidden);
+    let app: Value = client.get(format!("/api/v1/apps/{}", no_spec)).dispatch().into_json().unwrap();
+    assert!(app["conformance"].is_null());
+}
+
+// ── Bulk moderation ──
+
+fn submit_simple_app(client: &Client, key: &str, name: &str) -> String {
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.to_string()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": name,
+            "short_description": "Test app",
+            "description": "A test application",
+            "author_name": "Tester"
+        }).to_string())
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    body["app_id"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_listing_visibility() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let member = app_directory::auth::create_api_key(&conn, "member", false, Some(1000));
+
+    let mut ids = Vec::new();
+    for (name, visibility) in [("Open Agent", "public"), ("Hidden Link Agent", "unlisted"), ("Internal Agent", "private")] {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", admin.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name,
+                "short_description": "Agent listing",
+                "description": "An agent",
+                "author_name": "Team",
+                "category": "finance",
+                "visibility": visibility,
+            }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+        let body: Value = resp.into_json().unwrap();
+        assert_eq!(body["visibility"], visibility);
+        ids.push(body["app_id"].as_str().unwrap().to_string());
+    }
+    let (unlisted, private) = (ids[1].clone(), ids[2].clone());
+
+    let names = |path: &str, key: Option<&str>, list: &str| -> Vec<String> {
+        let mut req = client.get(path.to_string());
+        if let Some(key) = key {
+            req = req.header(Header::new("X-API-Key", key.to_string()));
+        }
+        let body: Value = req.dispatch().into_json().unwrap();
+        let mut names: Vec<String> =
+            body[list].as_array().unwrap().iter().map(|a| a["name"].as_str().unwrap().to_string()).collect();
+        names.sort();
+        names
+    };
+
+    // Lists and search: anonymous callers see public apps, signed-in ones private too, admins everything
+    assert_eq!(names("/api/v1/apps", None, "apps"), ["Open Agent"]);
+    assert_eq!(names("/api/v1/apps", Some(&member), "apps"), ["Internal Agent", "Open Agent"]);
+    assert_eq!(names("/api/v1/apps", Some(&admin), "apps").len(), 3);
+    assert_eq!(names("/api/v1/apps/search?q=agent", None, "apps"), ["Open Agent"]);
+    assert_eq!(names("/api/v1/apps/search?q=agent", Some(&member), "apps"), ["Internal Agent", "Open Agent"]);
+
+    // Unlisted apps open by link; private ones need a key
+    let resp = client.get(format!("/api/v1/apps/{}", unlisted)).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.into_json::<Value>().unwrap()["visibility"], "unlisted");
+    assert_eq!(client.get(format!("/api/v1/apps/{}", private)).dispatch().status(), Status::NotFound);
+    assert_eq!(client.get(format!("/apps/{}", private)).dispatch().status(), Status::NotFound);
+    let resp = client
+        .get(format!("/api/v1/apps/{}", private))
+        .header(Header::new("X-API-Key", member.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let page = client.get(format!("/apps/{}", unlisted)).dispatch().into_string().unwrap();
+    assert!(page.contains(r#"<meta name="robots" content="noindex">"#));
+
+    // Category counts
+    let finance = |key: Option<&str>| -> i64 {
+        let mut req = client.get("/api/v1/categories");
+        if let Some(key) = key {
+            req = req.header(Header::new("X-API-Key", key.to_string()));
+        }
+        let body: Value = req.dispatch().into_json().unwrap();
+        body["categories"].as_array().unwrap().iter().find(|c| c["name"] == "finance").unwrap()["count"]
+            .as_i64()
+            .unwrap()
+    };
+    assert_eq!(finance(None), 1);
+    assert_eq!(finance(Some(&member)), 2);
+
+    // Trending
+    for id in &ids {
+        client.get(format!("/api/v1/apps/{}", id)).header(Header::new("X-API-Key", admin.clone())).dispatch();
+    }
+    assert_eq!(names("/api/v1/apps/trending", None, "trending"), ["Open Agent"]);
+    assert_eq!(names("/api/v1/apps/trending", Some(&member), "trending"), ["Internal Agent", "Open Agent"]);
+
+    // Owners switch visibility with PATCH
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", unlisted))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"visibility":"secret"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "INVALID_VISIBILITY");
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", unlisted))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"visibility":"public"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(names("/api/v1/apps", None, "apps"), ["Hidden Link Agent", "Open Agent"]);
+}
+
+#[test]
+fn test_bulk_reject_with_per_item_results() {
+    let (client, key, db_path) = setup_client_with_path();
+    let a = submit_simple_app(&client, &key, "Spam One");
+    let b = submit_simple_app(&client, &key, "Spam Two");
+
+    let resp = client
+        .post("/api/v1/apps/bulk")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "action": "reject",
+            "app_ids": [a, b, "missing-app"],
+            "reason": "Spam wave"
+        }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["succeeded"], 2);
+    assert_eq!(body["failed"], 1);
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results[0]["ok"], true);
+    assert_eq!(results[2]["ok"], false);
+    assert_eq!(results[2]["error"], "NOT_FOUND");
+
+    for id in [&a, &b] {
+        let app: Value = client.get(format!("/api/v1/apps/{}", id)).dispatch().into_json().unwrap();
+        assert_eq!(app["status"], "rejected");
+        assert_eq!(app["review_note"], "Spam wave");
+    }
+
+    // Re-rejecting reports ALREADY_REJECTED per item
+    let resp = client
+        .post("/api/v1/apps/bulk")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "action": "reject", "app_ids": [a], "reason": "again" }).to_string())
+        .dispatch();
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["results"][0]["error"], "ALREADY_REJECTED");
+
+    // One audit entry per bulk request
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let audits: i64 = conn
+        .query_row("SELECT COUNT(*) FROM audit_log WHERE action = 'bulk.reject'", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(audits, 2);
+}
+
+#[test]
+fn test_bulk_delete_feature_and_deprecate() {
+    let (client, key) = setup_client();
+    let a = submit_simple_app(&client, &key, "Bulk A");
+    let b = submit_simple_app(&client, &key, "Bulk B");
+    let c = submit_simple_app(&client, &key, "Bulk C");
+
+    let bulk = |action: &str, ids: Vec<&String>, reason: Option<&str>| -> Value {
+        let resp = client
+            .post("/api/v1/apps/bulk")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "action": action, "app_ids": ids, "reason": reason }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+        resp.into_json().unwrap()
+    };
+
+    assert_eq!(bulk("feature", vec![&a], None)["succeeded"], 1);
+    let app: Value = client.get(format!("/api/v1/apps/{}", a)).dispatch().into_json().unwrap();
+    assert_eq!(app["is_featured"], true);
+
+    assert_eq!(bulk("deprecate", vec![&b], Some("Superseded"))["succeeded"], 1);
+    let app: Value = client.get(format!("/api/v1/apps/{}", b)).dispatch().into_json().unwrap();
+    assert_eq!(app["status"], "deprecated");
+    assert_eq!(app["deprecated_reason"], "Superseded");
+
+    assert_eq!(bulk("delete", vec![&c, &c], None)["succeeded"], 1);
+    let resp = client.get(format!("/api/v1/apps/{}", c)).dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+#[test]
+fn test_bulk_validation_and_auth() {
+    let (client, key) = setup_client();
+    let a = submit_simple_app(&client, &key, "Bulk Auth");
+
+    let post = |key: Option<&str>, body: Value| {
+        let mut req = client
+            .post("/api/v1/apps/bulk")
+            .header(ContentType::JSON)
+            .body(body.to_string());
+        if let Some(k) = key {
+            req = req.header(Header::new("X-API-Key", k.to_string()));
+        }
+        req.dispatch().status()
+    };
+
+    assert_eq!(post(None, serde_json::json!({ "action": "delete", "app_ids": [a] })), Status::Unauthorized);
+
+    let regular_key = {
+        let resp = client
+            .post("/api/v1/keys")
+            .header(ContentType::JSON)
+            .body(r#"{"name":"regular"}"#)
+            .dispatch();
+        let body: Value = resp.into_json().unwrap();
+        body["api_key"].as_str().unwrap().to_string()
+    };
+    assert_eq!(post(Some(&regular_key), serde_json::json!({ "action": "delete", "app_ids": [a] })), Status::Forbidden);
+
+    assert_eq!(post(Some(&key), serde_json::json!({ "action": "nuke", "app_ids": [a] })), Status::BadRequest);
+    assert_eq!(post(Some(&key), serde_json::json!({ "action": "delete", "app_ids": [] })), Status::BadRequest);
+    assert_eq!(post(Some(&key), serde_json::json!({ "action": "reject", "app_ids": [a] })), Status::BadRequest);
+
+    // Nothing was changed by the rejected requests
+    let app: Value = client.get(format!("/api/v1/apps/{}", a)).dispatch().into_json().unwrap();
+    assert_eq!(app["status"], "approved");
+}
+
+#[test]
+fn test_admin_db_optimize() {
+    let (client, key, db_path) = setup_client_with_path();
+    submit_simple_app(&client, &key, "Optimized");
+    // Leave free pages behind for VACUUM to reclaim
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE junk AS WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 2000)
+         SELECT i, hex(randomblob(200)) AS filler FROM n;
+         DROP TABLE junk;",
+    )
+    .unwrap();
+    assert_eq!(app_directory::db::integrity_check(&conn, true).unwrap(), Ok(()));
+    let viewer = app_directory::auth::create_api_key(&conn, "viewer", false, Some(100));
+    drop(conn);
+
+    let resp = client
+        .post("/api/v1/admin/db/optimize")
+        .header(Header::new("X-API-Key", viewer))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Forbidden);
+
+    let resp = client
+        .post("/api/v1/admin/db/optimize?vacuum=false&analyze=false")
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+
+    let resp = client
+        .post("/api/v1/admin/db/optimize")
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!((body["vacuumed"].as_bool(), body["analyzed"].as_bool()), (Some(true), Some(true)));
+    assert!(body["reclaimed_bytes"].as_i64().unwrap() > 0);
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let audited: i64 = conn
+        .query_row("SELECT COUNT(*) FROM audit_log WHERE action = 'admin.db_optimize'", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(audited, 1);
+    let stats: i64 = conn.query_row("SELECT COUNT(*) FROM sqlite_stat1", [], |r| r.get(0)).unwrap();
+    assert!(stats > 0);
+}
+
+#[test]
+fn test_admin_backup_and_restore() {
+    use app_directory::backup::{self, BackupConfig};
+
+    let (client, key, db_path) = setup_client_with_path();
+    let kept = submit_simple_app(&client, &key, "Backed Up");
+    let config = BackupConfig::from_env(&db_path);
+
+    let resp = client.get("/api/v1/admin/backups").header(Header::new("X-API-Key", key.clone())).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.into_json::<Value>().unwrap()["total"], 0);
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let viewer = app_directory::auth::create_api_key(&conn, "viewer", false, Some(100));
+    drop(conn);
+    for (method, path) in [("POST", "/api/v1/admin/backup"), ("GET", "/api/v1/admin/backups")] {
+        let req = if method == "POST" { client.post(path) } else { client.get(path) };
+        assert_eq!(req.header(Header::new("X-API-Key", viewer.clone())).dispatch().status(), Status::Forbidden);
+    }
+
+    let resp = client.post("/api/v1/admin/backup").header(Header::new("X-API-Key", key.clone())).dispatch();
+    assert_eq!(resp.status(), Status::Created);
+    let body: Value = resp.into_json().unwrap();
+    let name = body["backup"]["name"].as_str().unwrap().to_string();
+    assert!(backup::is_backup_name(&name));
+    assert!(body["backup"]["size_bytes"].as_u64().unwrap() > 0);
+
+    let resp = client.get("/api/v1/admin/backups").header(Header::new("X-API-Key", key.clone())).dispatch();
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["backups"][0]["name"], name);
+
+    // Rotation keeps only the newest snapshots
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let small = BackupConfig { dir: config.dir.clone(), keep: 2 };
+    let (_, removed) = backup::create(&conn, &small).unwrap();
+    assert!(removed.is_empty());
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    let (newest, removed) = backup::create(&conn, &small).unwrap();
+    assert_eq!(removed, vec![name.clone()]);
+    let audited: i64 = conn
+        .query_row("SELECT COUNT(*) FROM audit_log WHERE action = 'admin.backup'", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(audited, 1);
+
+    // Restoring brings back the snapshot and saves the current database first
+    conn.execute("UPDATE apps SET name = 'Changed' WHERE id = ?1", [&kept]).unwrap();
+    drop(conn);
+    let safety = backup::restore(&config.dir.join(&newest.name), &db_path, &config).unwrap();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let restored: String = conn.query_row("SELECT name FROM apps WHERE id = ?1", [&kept], |r| r.get(0)).unwrap();
+    assert_eq!(restored, "Backed Up");
+    let saved = rusqlite::Connection::open(config.dir.join(&safety.name)).unwrap();
+    let before: String = saved.query_row("SELECT name FROM apps WHERE id = ?1", [&kept], |r| r.get(0)).unwrap();
+    assert_eq!(before, "Changed");
+
+    assert!(backup::restore(&config.dir.join("backup-missing.db"), &db_path, &config).is_err());
+    let _ = std::fs::remove_dir_all(&config.dir);
+}
+
+#[test]
+fn test_maintenance_mode() {
+    let (client, key, db_path) = setup_client_with_path();
+    let app_id = submit_simple_app(&client, &key, "Before Maintenance");
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let viewer = app_directory::auth::create_api_key(&conn, "viewer", false, Some(100));
+    drop(conn);
+
+    let health: Value = client.get("/api/v1/health").dispatch().into_json().unwrap();
+    assert_eq!(health["maintenance"], false);
+    assert!(health["banner"].is_null());
+
+    let toggle = |api_key: &str, body: Value| {
+        client
+            .put("/api/v1/admin/maintenance")
+            .header(Header::new("X-API-Key", api_key.to_string()))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch()
+    };
+    assert_eq!(toggle(&viewer, serde_json::json!({"enabled": true})).status(), Status::Forbidden);
+
+    let resp = toggle(&key, serde_json::json!({"enabled": true, "message": "Upgrading storage", "retry_after_secs": 120}));
+    assert_eq!(resp.status(), Status::Ok);
+    let state: Value = resp.into_json().unwrap();
+    assert_eq!(state["enabled"], true);
+    assert!(state["since"].is_string());
+
+    // Writes are refused, including ones that would fail validation
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({"name": "During"}).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::ServiceUnavailable);
+    assert_eq!(resp.headers().get_one("Retry-After"), Some("120"));
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "MAINTENANCE");
+    assert_eq!(body["message"], "Upgrading storage");
+    assert_eq!(body["retry_after_secs"], 120);
+
+    let resp = client
+        .delete(format!("/api/v1/apps/{app_id}"))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::ServiceUnavailable);
+
+    // Reads keep working and health carries the banner
+    assert_eq!(client.get(format!("/api/v1/apps/{app_id}")).dispatch().status(), Status::Ok);
+    assert_eq!(client.get("/api/v1/apps").dispatch().status(), Status::Ok);
+    let health: Value = client.get("/api/v1/health").dispatch().into_json().unwrap();
+    assert_eq!(health["maintenance"], true);
+    assert_eq!(health["banner"], "Upgrading storage");
+
+    let resp = client
+        .get("/api/v1/admin/maintenance")
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(resp.into_json::<Value>().unwrap()["retry_after_secs"], 120);
+
+    // The toggle stays open so maintenance can be ended
+    let resp = toggle(&key, serde_json::json!({"enabled": false}));
+    assert_eq!(resp.status(), Status::Ok);
+    assert!(resp.into_json::<Value>().unwrap()["since"].is_null());
+    submit_simple_app(&client, &key, "After Maintenance");
+    let health: Value = client.get("/api/v1/health").dispatch().into_json().unwrap();
+    assert!(health["banner"].is_null());
+}
+
+#[test]
+fn test_rank_boost() {
+    let (client, key, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let owner = app_directory::auth::create_api_key(&conn, "owner", false, Some(100));
+    let old = submit_simple_app(&client, &owner, "Boost Old");
+    let new = submit_simple_app(&client, &key, "Boost New");
+    conn.execute("UPDATE apps SET created_at = '2020-01-01 00:00:00' WHERE id = ?1", [&old]).unwrap();
+    drop(conn);
+
+    let patch = |api_key: &str, id: &str, boost: i64| {
+        client
+            .patch(format!("/api/v1/apps/{id}"))
+            .header(Header::new("X-API-Key", api_key.to_string()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "rank_boost": boost }).to_string())
+            .dispatch()
+    };
+    let first = |url: &str| -> String {
+        let body: Value = client.get(url).dispatch().into_json().unwrap();
+        body["apps"][0]["id"].as_str().unwrap().to_string()
+    };
+    assert_eq!(first("/api/v1/apps"), new);
+
+    // Owners can edit their app but not its ranking
+    let resp = patch(&owner, &old, 5);
+    assert_eq!(resp.status(), Status::Forbidden);
+    let resp = patch(&key, &old, 11);
+    assert_eq!(resp.status(), Status::BadRequest);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "INVALID_RANK_BOOST");
+
+    assert_eq!(patch(&key, &old, 5).status(), Status::Ok);
+    assert_eq!(first("/api/v1/apps"), old);
+    // An explicit sort ignores the boost
+    assert_eq!(first("/api/v1/apps?sort=newest"), new);
+    // Search breaks rating ties with it
+    assert_eq!(first("/api/v1/apps/search?q=boost"), old);
+    assert_eq!(patch(&key, &old, -3).status(), Status::Ok);
+    assert_eq!(first("/api/v1/apps/search?q=boost"), new);
+
+    // Only admins see the value
+    let app: Value = client.get(format!("/api/v1/apps/{old}")).dispatch().into_json().unwrap();
+    assert!(app.get("rank_boost").is_none());
+    let app: Value = client
+        .get(format!("/api/v1/apps/{old}"))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(app["rank_boost"], -3);
+    let list: Value = client
+        .get("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert!(list["apps"].as_array().unwrap().iter().all(|a| a["rank_boost"].is_i64()));
+    let list: Value = client.get("/api/v1/apps").dispatch().into_json().unwrap();
+    assert!(list["apps"][0].get("rank_boost").is_none());
+}
+
+#[test]
+fn test_tag_suggestions() {
+    let (client, key) = setup_client();
+    let submit = |body: Value| -> Value {
+        client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch()
+            .into_json()
+            .unwrap()
+    };
+    for (name, tags) in [("Pay One", vec!["payments", "fintech"]), ("Learn One", vec!["machine-learning", "payments"])] {
+        let created = submit(serde_json::json!({
+            "name": name, "short_description": "x", "description": "x", "author_name": "T", "tags": tags,
+        }));
+        assert!(created.get("suggested_tags").is_none());
+    }
+
+    let created = submit(serde_json::json!({
+        "name": "Fraud Guard",
+        "short_description": "Machine learning fraud scoring for payments",
+        "description": "Scores every payment before capture.",
+        "author_name": "T",
+    }));
+    assert_eq!(created["suggested_tags"], serde_json::json!(["payments", "machine-learning"]));
+    let id = created["app_id"].as_str().unwrap();
+
+    let resp = client.get(format!("/api/v1/apps/{id}/suggest-tags")).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["suggestions"][0]["tag"], "payments");
+    assert_eq!(body["suggestions"][0]["score"], 3);
+    assert_eq!(body["suggestions"][0]["app_count"], 2);
+
+    // Accepted tags drop out of the suggestions
+    let resp = client
+        .patch(format!("/api/v1/apps/{id}"))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "tags": ["payments"] }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = client
+        .get(format!("/api/v1/apps/{id}/suggest-tags?limit=1"))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(body["tags"], serde_json::json!(["payments"]));
+    assert_eq!(body["suggestions"].as_array().unwrap().len(), 1);
+    assert_eq!(body["suggestions"][0]["tag"], "machine-learning");
+
+    let resp = client.get("/api/v1/apps/no-such-app/suggest-tags").dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+#[test]
+fn test_admin_erase_by_key() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let (user_id, user) = app_directory::auth::create_expiring_api_key(&conn, "subject", false, Some(100), None);
+    drop(conn);
+
+    let listed = submit_simple_app(&client, &admin, "Erase Target");
+    let submitted = submit_simple_app(&client, &user, "Erase Mine");
+    client
+        .post(format!("/api/v1/apps/{}/reviews", listed))
+        .header(Header::new("X-API-Key", user.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"rating": 1, "title": "Bad"}"#)
+        .dispatch();
+    client
+        .get(format!("/api/v1/apps/{}", listed))
+        .header(Header::new("X-API-Key", user.clone()))
+        .dispatch();
+    let app: Value = client.get(format!("/api/v1/apps/{}", listed)).dispatch().into_json().unwrap();
+    assert_eq!(app["review_count"], 1);
+
+    let erase = |key: &str, body: Value| {
+        client
+            .post("/api/v1/admin/erase")
+            .header(Header::new("X-API-Key", key.to_string()))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch()
+    };
+
+    let resp = erase(&user, serde_json::json!({ "key_id": user_id }));
+    assert_eq!(resp.status(), Status::Forbidden);
+    let resp = erase(&admin, serde_json::json!({ "key_id": user_id, "email": "a@example.com" }));
+    assert_eq!(resp.status(), Status::BadRequest);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_SUBJECT");
+
+    let resp = erase(&admin, serde_json::json!({ "key_id": user_id }));
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["mode"], "delete");
+    assert_eq!(body["subject"], "key_id");
+    assert_eq!(body["counts"]["reviews"], 1);
+    assert_eq!(body["counts"]["views"], 1);
+    assert_eq!(body["counts"]["submissions"], 1);
+    assert_eq!(body["app_ids"][0], submitted.as_str());
+
+    // Ratings are recomputed and the submission is gone
+    let app: Value = client.get(format!("/api/v1/apps/{}", listed)).dispatch().into_json().unwrap();
+    assert_eq!(app["review_count"], 0);
+    let resp = client.get(format!("/api/v1/apps/{}", submitted)).dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+
+    // The erasure is audited without the subject's id
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let details: String = conn
+        .query_row("SELECT details FROM audit_log WHERE action = 'admin.erase'", [], |r| r.get(0))
+        .unwrap();
+    assert!(!details.contains(&user_id));
+}
+
+#[test]
+fn test_admin_erase_anonymizes() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let app_id = submit_simple_app(&client, &admin, "Anon Target");
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"author_email": "dev@example.com"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute(
+        "INSERT INTO reviews (id, app_id, reviewer_name, reviewer_fingerprint, rating) VALUES ('r-anon', ?1, 'Visitor', 'fp-123', 4)",
+        rusqlite::params![app_id],
+    )
+    .unwrap();
+
+    let erase = |body: Value| -> Value {
+        let resp = client
+            .post("/api/v1/admin/erase")
+            .header(Header::new("X-API-Key", admin.clone()))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+        resp.into_json().unwrap()
+    };
+
+    let body = erase(serde_json::json!({ "fingerprint": "fp-123", "anonymize": true }));
+    assert_eq!(body["mode"], "anonymize");
+    assert_eq!(body["counts"]["reviews"], 1);
+    let (name, fingerprint): (Option<String>, Option<String>) = conn
+        .query_row("SELECT reviewer_name, reviewer_fingerprint FROM reviews WHERE id = 'r-anon'", [], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })
+        .unwrap();
+    assert_eq!((name, fingerprint), (None, None));
+
+    // A queued notification to the address goes, other mail stays
+    let db = client.rocket().state::<app_directory::DbState>().unwrap();
+    app_directory::notifications::enqueue_email(&db.conn(), "dev@example.com", "Approved", "Your listing is live").unwrap();
+    app_directory::notifications::enqueue_email(&db.conn(), "other@example.com", "Digest", "1 pending").unwrap();
+
+    let body = erase(serde_json::json!({ "email": "DEV@example.com", "anonymize": true }));
+    assert_eq!(body["counts"]["submissions"], 1);
+    assert_eq!(body["counts"]["jobs"], 1);
+    let recipients: Vec<String> = conn
+        .prepare("SELECT json_extract(payload, '$.to') FROM jobs")
+        .unwrap()
+        .query_map([], |r| r.get(0))
+        .unwrap()
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(recipients, vec!["other@example.com".to_string()]);
+    let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(app["author_name"], "Anonymous");
+    let reviews: i64 = conn.query_row("SELECT COUNT(*) FROM reviews", [], |r| r.get(0)).unwrap();
+    assert_eq!(reviews, 1);
+    let email: Option<String> = conn
+        .query_row("SELECT author_email FROM apps WHERE id = ?1", rusqlite::params![app_id], |r| r.get(0))
+        .unwrap();
+    assert!(email.is_none());
+}
+
+#[test]
+fn test_translations_follow_accept_language() {
+    let (client, key) = setup_client();
+    let id = submit_simple_app(&client, &key, "Weather Bot");
+
+    let resp = client
+        .put(format!("/api/v1/apps/{}/translations/de-DE", id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Wetter Bot","short_description":"Testanwendung"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["lang"], "de-de");
+
+    // Exact match, with missing fields falling back to the default language
+    let app: Value = client
+        .get(format!("/api/v1/apps/{}", id))
+        .header(Header::new("Accept-Language", "de-DE,de;q=0.9,en;q=0.5"))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(app["lang"], "de-de");
+    assert_eq!(app["name"], "Wetter Bot");
+    assert_eq!(app["short_description"], "Testanwendung");
+    assert_eq!(app["description"], "A test application");
+    assert_eq!(app["available_languages"], serde_json::json!(["de-de"]));
+
+    // Default language preferred, or no header: untranslated
+    for accept in [Some("en,de-DE;q=0.8"), None] {
+        let mut req = client.get(format!("/api/v1/apps/{}", id));
+        if let Some(a) = accept {
+            req = req.header(Header::new("Accept-Language", a));
+        }
+        let app: Value = req.dispatch().into_json().unwrap();
+        assert_eq!(app["lang"], "en");
+        assert_eq!(app["name"], "Weather Bot");
+    }
+
+    // Unknown language falls back to default; list_apps is localized too
+    let list: Value = client
+        .get("/api/v1/apps")
+        .header(Header::new("Accept-Language", "fr, de-DE;q=0.7"))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(list["apps"][0]["name"], "Wetter Bot");
+    assert_eq!(list["apps"][0]["lang"], "de-de");
+}
+
+#[test]
+fn test_translation_validation_and_auth() {
+    let (client, key) = setup_client();
+    let id = submit_simple_app(&client, &key, "Translate Me");
+
+    let put = |lang: &str, key: Option<&str>, body: &str| {
+        let mut req = client
+            .put(format!("/api/v1/apps/{}/translations/{}", id, lang))
+            .header(ContentType::JSON)
+            .body(body);
+        if let Some(k) = key {
+            req = req.header(Header::new("X-API-Key", k.to_string()));
+        }
+        req.dispatch().status()
+    };
+
+    assert_eq!(put("fr", None, r#"{"name":"Traduis-moi"}"#), Status::Unauthorized);
+    assert_eq!(put("not_a_lang", Some(&key), r#"{"name":"x"}"#), Status::BadRequest);
+    assert_eq!(put("en", Some(&key), r#"{"name":"x"}"#), Status::BadRequest);
+    assert_eq!(put("fr", Some(&key), r#"{}"#), Status::BadRequest);
+    assert_eq!(put("fr", Some(&key), r#"{"name":"Traduis-moi"}"#), Status::Ok);
+
+    let resp = client
+        .put("/api/v1/apps/nonexistent/translations/fr")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"x"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+#[test]
+fn test_field_length_limits() {
+    let (client, key) = setup_client();
+
+    let submit = |body: Value| {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch();
+        let status = resp.status();
+        (status, resp.into_json::<Value>().unwrap())
+    };
+
+    let (status, body) = submit(serde_json::json!({
+        "name": "x".repeat(101),
+        "short_description": "Short",
+        "description": "Long",
+        "author_name": "Tester"
+    }));
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["error"], "FIELD_TOO_LONG");
+    assert_eq!(body["field"], "name");
+    assert_eq!(body["max"], 100);
+
+    let tags: Vec<String> = (0..21).map(|i| format!("tag{}", i)).collect();
+    let (status, body) = submit(serde_json::json!({
+        "name": "Taggy",
+        "short_description": "Short",
+        "description": "Long",
+        "author_name": "Tester",
+        "tags": tags
+    }));
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["field"], "tags");
+
+    let (status, body) = submit(serde_json::json!({
+        "name": "Linky",
+        "short_description": "Short",
+        "description": "Long",
+        "author_name": "Tester",
+        "homepage_url": format!("https://example.com/{}", "a".repeat(2048))
+    }));
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["field"], "homepage_url");
+
+    // Updates and reviews are checked too
+    let id = submit_simple_app(&client, &key, "Limited");
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "description": "d".repeat(10_001) }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["field"], "description");
+
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", id))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "rating": 5, "body": "b".repeat(5_001) }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "FIELD_TOO_LONG");
+    assert_eq!(body["field"], "body");
+}
+
+#[test]
+fn test_oversized_body_rejected() {
+    let (client, _) = setup_client();
+    let resp = client
+        .post("/api/v1/apps")
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": "Huge",
+            "short_description": "Short",
+            "description": "x".repeat(300 * 1024),
+            "author_name": "Tester"
+        }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::PayloadTooLarge);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "PAYLOAD_TOO_LARGE");
+}
+
+fn create_key_with(client: &Client, admin_key: &str, body: Value) -> (Status, Value) {
+    let resp = client
+        .post("/api/v1/keys")
+        .header(Header::new("X-API-Key", admin_key.to_string()))
+        .header(ContentType::JSON)
+        .body(body.to_string())
+        .dispatch();
+    let status = resp.status();
+    (status, resp.into_json().unwrap())
+}
+
+#[test]
+fn test_expired_key_rejected() {
+    let (client, admin, db_path) = setup_client_with_path();
+
+    let (status, body) = create_key_with(&client, &admin, serde_json::json!({ "name": "past", "expires_at": "2001-01-01T00:00:00Z" }));
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["error"], "INVALID_EXPIRY");
+    let (status, _) = create_key_with(&client, &admin, serde_json::json!({ "name": "junk", "expires_at": "next week" }));
+    assert_eq!(status, Status::BadRequest);
+
+    let (status, body) = create_key_with(&client, &admin, serde_json::json!({ "name": "temp", "expires_at": "2099-06-01T12:00:00+02:00" }));
+    assert_eq!(status, Status::Created);
+    assert_eq!(body["expires_at"], "2099-06-01 10:00:00");
+    let key_id = body["id"].as_str().unwrap().to_string();
+    let raw = body["api_key"].as_str().unwrap().to_string();
+
+    let resp = client.get("/api/v1/apps/mine").header(Header::new("X-API-Key", raw.clone())).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+
+    // Force expiry
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute(
+        "UPDATE api_keys SET expires_at = datetime('now', '-1 minute') WHERE id = ?1",
+        rusqlite::params![key_id],
+    )
+    .unwrap();
+
+    let resp = client.get("/api/v1/apps/mine").header(Header::new("X-API-Key", raw)).dispatch();
+    assert_eq!(resp.status(), Status::Unauthorized);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "KEY_EXPIRED");
+
+    // Missing key is a plain UNAUTHORIZED
+    let resp = client.get("/api/v1/apps/mine").dispatch();
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "UNAUTHORIZED");
+}
+
+#[test]
+fn test_rotate_key() {
+    let (client, admin, db_path) = setup_client_with_path();
+
+    let (_, body) = create_key_with(&client, &admin, serde_json::json!({ "name": "rotating", "rate_limit": 42 }));
+    let key_id = body["id"].as_str().unwrap().to_string();
+    let old = body["api_key"].as_str().unwrap().to_string();
+
+    // Owner rotates their own key and sets an expiry
+    let resp = client
+        .post(format!("/api/v1/keys/{}/rotate", key_id))
+        .header(Header::new("X-API-Key", old.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"expires_at":"2099-01-01T00:00:00Z"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["id"], key_id.as_str());
+    assert_eq!(body["expires_at"], "2099-01-01 00:00:00");
+    let new = body["api_key"].as_str().unwrap().to_string();
+    assert_ne!(new, old);
+
+    let status = |k: &str| client.get("/api/v1/apps/mine").header(Header::new("X-API-Key", k.to_string())).dispatch().status();
+    assert_eq!(status(&old), Status::Unauthorized);
+    assert_eq!(status(&new), Status::Ok);
+
+    // Metadata is preserved
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let (name, rl): (String, i64) = conn
+        .query_row("SELECT name, rate_limit FROM api_keys WHERE id = ?1", rusqlite::params![key_id], |r| Ok((r.get(0)?, r.get(1)?)))
+        .unwrap();
+    assert_eq!(name, "rotating");
+    assert_eq!(rl, 42);
+
+    // Non-admins can't rotate other keys; admin rotation without a body keeps expiry
+    let (_, other) = create_key_with(&client, &admin, serde_json::json!({ "name": "other" }));
+    let resp = client
+        .post(format!("/api/v1/keys/{}/rotate", other["id"].as_str().unwrap()))
+        .header(Header::new("X-API-Key", new.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Forbidden);
+
+    let resp = client
+        .post(format!("/api/v1/keys/{}/rotate", key_id))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["expires_at"], "2099-01-01 00:00:00");
+
+    let resp = client
+        .post("/api/v1/keys/nonexistent/rotate")
+        .header(Header::new("X-API-Key", admin))
+        .dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+#[test]
+fn test_key_expiry_warning_sent_once() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let (_, body) = create_key_with(&client, &admin, serde_json::json!({ "name": "soon", "expires_at": "2099-01-01T00:00:00Z" }));
+    let key_id = body["id"].as_str().unwrap().to_string();
+
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let bus = app_directory::events::EventBus::new();
+    assert_eq!(app_directory::scheduler::warn_expiring_keys(&conn, &bus, 7), 0);
+
+    conn.execute(
+        "UPDATE api_keys SET expires_at = datetime('now', '+2 days') WHERE id = ?1",
+        rusqlite::params![key_id],
+    )
+    .unwrap();
+    let mut rx = bus.subscribe();
+    assert_eq!(app_directory::scheduler::warn_expiring_keys(&conn, &bus, 7), 1);
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.event, "key.expiring");
+    assert_eq!(event.data["key_id"], key_id.as_str());
+
+    assert_eq!(app_directory::scheduler::warn_expiring_keys(&conn, &bus, 7), 0);
+}
+
+#[test]
+fn test_key_usage_anomaly_throttles_key() {
+    use app_directory::key_usage::{detect_anomalies, hour_start, AnomalyConfig, KeyUsage};
+    let (client, admin, db_path) = setup_client_with_path();
+    let (_, body) = create_key_with(&client, &admin, serde_json::json!({ "name": "agent", "rate_limit": 20 }));
+    let key_id = body["id"].as_str().unwrap().to_string();
+    let raw = body["api_key"].as_str().unwrap().to_string();
+    let call = || client.get("/api/v1/keys").header(Header::new("X-API-Key", raw.clone())).dispatch().status();
+
+    // A steady baseline of 1 request an hour over the last day
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    for h in 1..=24 {
+        conn.execute(
+            "INSERT INTO key_usage_hourly (key_id, hour, requests) VALUES (?1, ?2, 1)",
+            rusqlite::params![key_id, hour_start(chrono::Utc::now() - chrono::Duration::hours(h))],
+        )
+        .unwrap();
+    }
+    let usage = client.rocket().state::<KeyUsage>().unwrap();
+    let bus = app_directory::events::EventBus::new();
+    let config = AnomalyConfig { factor: 5.0, min_requests: 10, throttle_mins: 30 };
+
+    for _ in 0..4 {
+        assert_eq!(call(), Status::Forbidden);
+    }
+    usage.flush(&conn);
+    assert!(detect_anomalies(&conn, &bus, &config).is_empty());
+
+    // 10 requests this hour: over the floor and 10x the baseline
+    for _ in 0..6 {
+        assert_eq!(call(), Status::Forbidden);
+    }
+    usage.flush(&conn);
+    let mut rx = bus.subscribe();
+    assert_eq!(detect_anomalies(&conn, &bus, &config), vec![key_id.clone()]);
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.event, "key.anomaly");
+    assert_eq!(event.data["requests"], 10);
+    assert_eq!(event.data["baseline"], 1.0);
+    assert!(event.data["throttled_until"].is_string());
+
+    // Flagged once per hour
+    assert!(detect_anomalies(&conn, &bus, &config).is_empty());
+
+    // Throttled to a tenth of the limit (2), already used up in this window
+    let resp = client.get("/api/v1/keys").header(Header::new("X-API-Key", raw.clone())).dispatch();
+    assert_eq!(resp.status(), Status::TooManyRequests);
+    assert_eq!(resp.headers().get_one("X-RateLimit-Limit"), Some("2"));
+
+    let resp = client
+        .delete(format!("/api/v1/keys/{}/throttle", key_id))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert!(body["throttled_until"].is_null());
+    assert_eq!(call(), Status::Forbidden);
+
+    let resp = client.delete(format!("/api/v1/keys/{}/throttle", key_id)).header(Header::new("X-API-Key", raw.clone())).dispatch();
+    assert_eq!(resp.status(), Status::Forbidden);
+}
+
+#[test]
+fn test_event_bus_relay() {
+    use app_directory::events::{AppEvent, EventBus};
+    let (a, b) = (EventBus::new(), EventBus::new());
+    let (tx, mut outbox) = tokio::sync::mpsc::unbounded_channel();
+    a.attach_relay(tx);
+
+    let mut a_all = a.subscribe();
+    let mut b_all = b.subscribe();
+    let mut b_local = b.subscribe_local();
+    a.emit(AppEvent {
+        event: "app.approved".to_string(),
+        data: serde_json::json!({ "app_id": "x" }),
+    });
+    assert_eq!(a_all.try_recv().unwrap().event, "app.approved");
+
+    // What process A published reaches B's subscribers, but not B's local-only ones
+    let message = outbox.try_recv().unwrap();
+    assert!(app_directory::relay::decode(&message, a.origin()).is_none());
+    b.deliver_relayed(app_directory::relay::decode(&message, b.origin()).unwrap());
+    let relayed = b_all.try_recv().unwrap();
+    assert_eq!((relayed.event.as_str(), relayed.app_id()), ("app.approved", Some("x")));
+    assert!(b_local.try_recv().is_err());
+}
+
+/// Request a magic link and pull the code out of the log mailer's outbox.
+fn request_login_code(client: &Client, email: &str) -> String {
+    let resp = client
+        .post("/api/v1/auth/magic-link")
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "email": email }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::Accepted);
+    let mailer = client.rocket().state::<app_directory::accounts::Mailer>().unwrap();
+    let mail = mailer.outbox().pop().expect("login email sent");
+    assert_eq!(mail.to, email.to_lowercase());
+    mail.body
+        .split_whitespace()
+        .find(|w| w.len() == 6 && w.chars().all(|c| c.is_ascii_digit()))
+        .unwrap()
+        .to_string()
+}
+
+fn sign_in(client: &Client, email: &str) -> Value {
+    let code = request_login_code(client, email);
+    let resp = client
+        .post("/api/v1/auth/verify")
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "email": email, "code": code }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    resp.into_json().unwrap()
+}
+
+#[test]
+fn test_author_email_notifications_queue() {
+    let (client, key) = setup_client();
+
+    let response = client.post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Notified","short_description":"s","description":"d","author_name":"a","author_email":"not-an-email"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let body: Value = response.into_json().unwrap();
+    assert_eq!(body["error"], "INVALID_EMAIL");
+
+    let created: Value = client.post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Notified","short_description":"s","description":"d","author_name":"a","author_email":"Dev@Example.com"}"#)
+        .dispatch()
+        .into_json()
+        .unwrap();
+    let app_id = created["app_id"].as_str().unwrap().to_string();
+
+    // Never exposed on the public listing
+    let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+    assert!(app.get("author_email").is_none());
+
+    let bus = client.rocket().state::<app_directory::events::EventBus>().unwrap();
+    let mut rx = bus.subscribe();
+    let response = client.post(format!("/api/v1/apps/{}/reject", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"reason":"Broken homepage"}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let db = client.rocket().state::<app_directory::DbState>().unwrap();
+    let mut queued = 0;
+    while let Ok(event) = rx.try_recv() {
+        if app_directory::notifications::enqueue_for_event(&db.conn(), &event) {
+            queued += 1;
+        }
+    }
+    assert_eq!(queued, 1);
+    assert_eq!(
+        app_directory::notifications::enqueue_admin_digest(&db.conn(), &["admin@example.com".to_string()], 3600),
+        0,
+        "no digest when nothing is pending"
+    );
+
+    let mailer = client.rocket().state::<app_directory::accounts::Mailer>().unwrap();
+    let runtime = rocket::tokio::runtime::Runtime::new().unwrap();
+    let sent = runtime.block_on(app_directory::notifications::process_due_jobs(&db.0, mailer));
+    assert_eq!(sent, 1);
+    let mail = mailer.outbox().into_iter().find(|m| m.to == "dev@example.com").unwrap();
+    assert_eq!(mail.subject, "\"Notified\" was rejected");
+    assert!(mail.body.contains("Broken homepage"));
+
+    // Delivered jobs are not sent again
+    let sent = runtime.block_on(app_directory::notifications::process_due_jobs(&db.0, mailer));
+    assert_eq!(sent, 0);
+
+    // Failures are rescheduled, then given up on
+    let conn = db.conn();
+    app_directory::notifications::enqueue_email(&conn, "x@example.com", "s", "b").unwrap();
+    let job_id: String = conn
+        .query_row("SELECT id FROM jobs WHERE payload LIKE '%x@example.com%'", [], |r| r.get(0))
+        .unwrap();
+    app_directory::notifications::record_failure(&conn, &job_id, 1, "timeout");
+    let (status, due_later): (String, bool) = conn
+        .query_row("SELECT status, run_after > datetime('now') FROM jobs WHERE id = ?1", [&job_id], |r| Ok((r.get(0)?, r.get(1)?)))
+        .unwrap();
+    assert_eq!((status.as_str(), due_later), ("pending", true));
+    app_directory::notifications::record_failure(&conn, &job_id, app_directory::notifications::MAX_ATTEMPTS, "timeout");
+    let status: String = conn.query_row("SELECT status FROM jobs WHERE id = ?1", [&job_id], |r| r.get(0)).unwrap();
+    assert_eq!(status, "failed");
+}
+
+#[test]
+fn test_magic_link_login_and_app_ownership() {
+    let (client, _) = setup_client();
+    let session = sign_in(&client, "Owner@Example.com");
+    assert_eq!(session["email"], "owner@example.com");
+    let token = session["session_token"].as_str().unwrap().to_string();
+    let account_id = session["account_id"].as_str().unwrap().to_string();
+
+    let me: Value = client
+        .get("/api/v1/auth/me")
+        .header(Header::new("X-Session-Token", token.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(me["account_id"], account_id.as_str());
+
+    // Submitting while signed in binds the app to the account
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Account App","short_description":"Mine","description":"Owned by an account","author_name":"Owner"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["account_id"], account_id.as_str());
+    let app_id = body["app_id"].as_str().unwrap().to_string();
+
+    let mine: Value = client
+        .get("/api/v1/apps/mine")
+        .header(Header::new("X-Session-Token", token.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(mine["total"], 1);
+    assert_eq!(mine["apps"][0]["id"], app_id.as_str());
+
+    // The account can edit without the edit token; another account can't
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-Session-Token", token.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"short_description":"Edited by owner"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+
+    let other = sign_in(&client, "someone@example.com");
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-Session-Token", other["session_token"].as_str().unwrap().to_string()))
+        .header(ContentType::JSON)
+        .body(r#"{"short_description":"Hijacked"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Forbidden);
+
+    // Signing in again reuses the account
+    let again = sign_in(&client, "owner@example.com");
+    assert_eq!(again["account_id"], account_id.as_str());
+}
+
+#[test]
+fn test_magic_link_code_validation() {
+    let (client, _) = setup_client();
+
+    let resp = client
+        .post("/api/v1/auth/magic-link")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"not-an-email"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+
+    let code = request_login_code(&client, "guess@example.com");
+    let wrong = if code == "000000" { "111111" } else { "000000" };
+    let verify = |code: &str| {
+        let resp = client
+            .post("/api/v1/auth/verify")
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "email": "guess@example.com", "code": code }).to_string())
+            .dispatch();
+        let status = resp.status();
+        let body: Value = resp.into_json().unwrap();
+        (status, body["error"].as_str().unwrap_or("").to_string())
+    };
+
+    // Five wrong guesses burn the code, even the right one fails afterwards
+    for _ in 0..5 {
+        assert_eq!(verify(wrong), (Status::Unauthorized, "INVALID_CODE".to_string()));
+    }
+    assert_eq!(verify(&code), (Status::Unauthorized, "CODE_EXPIRED".to_string()));
+
+    // A fresh code works exactly once
+    let code = request_login_code(&client, "guess@example.com");
+    assert_eq!(verify(&code).0, Status::Ok);
+    assert_eq!(verify(&code), (Status::Unauthorized, "CODE_EXPIRED".to_string()));
+
+    // Bad session tokens are rejected on /apps/mine
+    let resp = client
+        .get("/api/v1/apps/mine")
+        .header(Header::new("X-Session-Token", "sess_bogus"))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Unauthorized);
+}
+
+#[test]
+fn test_magic_link_throttles() {
+    let (client, _) = setup_client();
+    let send = |email: &str, ip: &str| {
+        let resp = client
+            .post("/api/v1/auth/magic-link")
+            .remote(client_addr(ip))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "email": email }).to_string())
+            .dispatch();
+        let status = resp.status();
+        let body: Value = resp.into_json().unwrap();
+        (status, body["error"].as_str().unwrap_or("").to_string())
+    };
+
+    // Five codes per email per hour, whichever IPs ask for them
+    for i in 0..5 {
+        assert_eq!(send("target@example.com", &format!("10.1.0.{i}")).0, Status::Accepted);
+    }
+    assert_eq!(
+        send("target@example.com", "10.1.0.9"),
+        (Status::TooManyRequests, "LOGIN_LIMIT_EXCEEDED".to_string())
+    );
+
+    // The count lives with the email's code row, not the client
+    let db = client.rocket().state::<app_directory::DbState>().unwrap();
+    let issued: i64 = db
+        .conn()
+        .query_row("SELECT issued FROM login_codes WHERE email = 'target@example.com'", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(issued, 5);
+
+    // One IP gets LOGIN_IP_HOURLY_CAP requests per endpoint, spread over any emails
+    for i in 0..20 {
+        assert_eq!(send(&format!("user{i}@example.com"), "10.2.0.1").0, Status::Accepted);
+    }
+    assert_eq!(
+        send("user99@example.com", "10.2.0.1"),
+        (Status::TooManyRequests, "LOGIN_LIMIT_EXCEEDED".to_string())
+    );
+    assert_eq!(send("user99@example.com", "10.2.0.2").0, Status::Accepted);
+
+    let verify = |ip: &str| {
+        client
+            .post("/api/v1/auth/verify")
+            .remote(client_addr(ip))
+            .header(ContentType::JSON)
+            .body(r#"{"email":"user0@example.com","code":"000000"}"#)
+            .dispatch()
+            .status()
+    };
+    for _ in 0..20 {
+        assert_ne!(verify("10.3.0.1"), Status::TooManyRequests);
+    }
+    assert_eq!(verify("10.3.0.1"), Status::TooManyRequests);
+}
+
+#[test]
+fn test_seed_demo_data_is_deterministic() {
+    let seed = || {
+        let path = format!("/tmp/test_app_dir_{}.db", uuid::Uuid::new_v4());
+        let conn = app_directory::db::init_db(&path);
+        let summary = app_directory::db::seed::seed_demo_data(&conn).unwrap().unwrap();
+        // Second run is a no-op
+        assert!(app_directory::db::seed::seed_demo_data(&conn).unwrap().is_none());
+        let ratings: Vec<(String, f64, i64)> = conn
+            .prepare("SELECT id, avg_rating, review_count FROM apps ORDER BY id")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        (summary, ratings)
+    };
+
+    let (a, ratings_a) = seed();
+    let (b, ratings_b) = seed();
+    assert_eq!(a, b);
+    assert_eq!(ratings_a, ratings_b);
+    assert!(a.apps >= 10 && a.reviews > 0 && a.views > 0 && a.health_checks > 0);
+}
+
+#[test]
+fn test_seeded_catalog_is_browsable() {
+    let (client, _, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    app_directory::db::seed::seed_demo_data(&conn).unwrap().unwrap();
+
+    let list: Value = client.get("/api/v1/apps?per_page=100").dispatch().into_json().unwrap();
+    let total = list["total"].as_i64().unwrap();
+    assert!(total >= 10);
+    let categories: std::collections::HashSet<&str> = list["apps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["category"].as_str().unwrap())
+        .collect();
+    assert!(categories.len() >= 8);
+
+    let id = app_directory::db::seed::demo_app_id("skyline-weather");
+    let app: Value = client.get(format!("/api/v1/apps/{}", id)).dispatch().into_json().unwrap();
+    assert_eq!(app["is_featured"], true);
+    assert!(app["last_health_status"].is_string());
+    assert!(app["uptime_pct"].as_f64().unwrap() > 0.0);
+
+    let stats: Value = client.get(format!("/api/v1/apps/{}/stats", id)).dispatch().into_json().unwrap();
+    assert!(stats["total_views"].as_i64().unwrap() > 0);
+}
+
+#[test]
+fn test_rate_limit_survives_restart() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let (_, body) = create_key_with(&client, &admin, serde_json::json!({ "name": "limited", "rate_limit": 2 }));
+    let limited = body["api_key"].as_str().unwrap().to_string();
+
+    let mine = |client: &Client| {
+        client
+            .get("/api/v1/apps/mine")
+            .header(Header::new("X-API-Key", limited.clone()))
+            .dispatch()
+            .status()
+    };
+    assert_eq!(mine(&client), Status::Ok);
+    assert_eq!(mine(&client), Status::Ok);
+    assert_eq!(mine(&client), Status::TooManyRequests);
+
+    // Flush as the persistence fairing would, then "restart" on the same database
+    let limiter = client.rocket().state::<app_directory::rate_limit::RateLimiter>().unwrap();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    limiter.save(&conn, app_directory::rate_limit::SCOPE_API_KEYS).unwrap();
+    drop(client);
+
+    let restarted = Client::tracked(app_directory::rocket_with_path(&db_path)).unwrap();
+    assert_eq!(mine(&restarted), Status::TooManyRequests);
+}
+
+#[test]
+fn test_error_envelope_and_registry() {
+    let (client, admin) = setup_client();
+    let (_, body) = create_key_with(&client, &admin, serde_json::json!({ "name": "plain" }));
+    let plain = body["api_key"].as_str().unwrap().to_string();
+
+    let registry: Value = client.get("/api/v1/errors").dispatch().into_json().unwrap();
+    let registry = registry["errors"].as_array().unwrap().clone();
+    let status_of = |code: &str| {
+        registry
+            .iter()
+            .find(|e| e["code"] == code)
+            .unwrap_or_else(|| panic!("{code} missing from registry"))["status"]
+            .as_u64()
+            .unwrap() as u16
+    };
+
+    let responses = vec![
+        // Handler errors
+        client.get("/api/v1/apps/does-not-exist").dispatch(),
+        client.get("/api/v1/keys").header(Header::new("X-API-Key", plain.clone())).dispatch(),
+        // Guard and catcher errors
+        client.get("/api/v1/apps/mine").dispatch(),
+        client.get("/api/v1/no-such-route").dispatch(),
+        client.post("/api/v1/apps").header(ContentType::JSON).body(r#"{"name": 5}"#).dispatch(),
+    ];
+    let mut codes = Vec::new();
+    for resp in responses {
+        let status = resp.status().code;
+        assert_eq!(resp.content_type(), Some(ContentType::JSON));
+        let body: Value = resp.into_json().unwrap();
+        let code = body["error"].as_str().unwrap().to_string();
+        assert!(body["message"].as_str().is_some_and(|m| !m.is_empty()), "{code} has no message");
+        assert_eq!(status_of(&code), status, "{code}");
+        codes.push(code);
+    }
+    assert_eq!(codes, ["NOT_FOUND", "ADMIN_REQUIRED", "UNAUTHORIZED", "NOT_FOUND", "UNPROCESSABLE_ENTITY"]);
+}
+
+/// Minimal rocket with reads locked down and CORS restricted to one origin.
+/// Built by hand because `rocket_with_path` reads these settings from the environment.
+fn setup_private_client() -> (Client, String) {
+    let db_path = format!("/tmp/test_app_dir_{}.db", uuid::Uuid::new_v4());
+    let conn = app_directory::db::init_db(&db_path);
+    let key = app_directory::auth::create_api_key(&conn, "reader", false, Some(1000));
+    let rocket = rocket::build()
+        .manage(app_directory::DbState(std::sync::Mutex::new(conn)))
+        .manage(app_directory::rate_limit::RateLimiter::new(std::time::Duration::from_secs(60)))
+        .manage(app_directory::i18n::DefaultLanguage("en".to_string()))
+        .manage(app_directory::pagination::PageLimits::default())
+        .manage(app_directory::stats::ViewRecorder::default())
+        .manage(app_directory::auth::ReadPolicy { require_auth: true })
+        .manage(app_directory::maintenance::Maintenance::new(false, None, 300))
+        .manage(app_directory::replica::Replica::disabled())
+        .manage(app_directory::retention::PrivacyMode::default())
+        .attach(app_directory::Cors::allow_origins(&["https://dash.example.com"]))
+        .mount(
+            "/api/v1",
+            rocket::routes![
+                app_directory::routes::list_apps,
+                app_directory::routes::get_app,
+                app_directory::routes::search_apps,
+                app_directory::routes::health,
+            ],
+        );
+    (Client::tracked(rocket).unwrap(), key)
+}
+
+#[test]
+fn test_privacy_mode_drops_unique_viewers() {
+    let db_path = format!("/tmp/test_app_dir_{}.db", uuid::Uuid::new_v4());
+    let conn = app_directory::db::init_db(&db_path);
+    conn.execute(
+        "INSERT INTO apps (id, name, slug, short_description, description, protocol, category, tags, author_name, status)
+         VALUES ('a1', 'Weather', 'weather', 'Forecasts', 'Forecasts', 'rest', 'data', '[]', 'Tester', 'approved')",
+        [],
+    )
+    .unwrap();
+    let rocket = rocket::build()
+        .manage(app_directory::DbState(std::sync::Mutex::new(conn)))
+        .manage(app_directory::rate_limit::RateLimiter::new(std::time::Duration::from_secs(60)))
+        .manage(app_directory::i18n::DefaultLanguage("en".to_string()))
+        .manage(app_directory::stats::ViewRecorder::with_privacy(true))
+        .manage(app_directory::maintenance::Maintenance::new(false, None, 300))
+        .manage(app_directory::replica::Replica::disabled())
+        .manage(app_directory::retention::PrivacyMode { enabled: true, ..Default::default() })
+        .mount(
+            "/api/v1",
+            rocket::routes![
+                app_directory::routes::get_app,
+                app_directory::routes::health,
+                app_directory::stats::get_app_stats,
+                app_directory::stats::trending_apps,
+            ],
+        );
+    let client = Client::tracked(rocket).unwrap();
+    for _ in 0..2 {
+        assert_eq!(client.get("/api/v1/apps/a1").dispatch().status(), Status::Ok);
+    }
+
+    // Views are counted, but every one is anonymous, so there are no viewers to count
+    let stats: Value = client.get("/api/v1/apps/a1/stats").dispatch().into_json().unwrap();
+    assert_eq!(stats["total_views"], 2);
+    assert!(stats["unique_viewers"].is_null());
+    let trending: Value = client.get("/api/v1/apps/trending").dispatch().into_json().unwrap();
+    assert_eq!(trending["trending"][0]["view_count"], 2);
+    assert!(trending["trending"][0]["unique_viewers"].is_null());
+    let health: Value = client.get("/api/v1/health").dispatch().into_json().unwrap();
+    assert_eq!(health["privacy"]["unique_viewers_reported"], false);
+}
+
+#[test]
+fn test_require_auth_for_reads() {
+    let (client, key) = setup_private_client();
+
+    for path in ["/api/v1/apps", "/api/v1/apps/some-app", "/api/v1/apps/search?q=x"] {
+        assert_eq!(client.get(path).dispatch().status(), Status::Unauthorized, "{path}");
+    }
+    let resp = client.get("/api/v1/apps").header(Header::new("X-API-Key", key.clone())).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let resp = client.get("/api/v1/apps/search?q=x").header(Header::new("X-API-Key", key.clone())).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let resp = client.get("/api/v1/apps/some-app").header(Header::new("X-API-Key", key)).dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+
+    // Health stays public
+    assert_eq!(client.get("/api/v1/health").dispatch().status(), Status::Ok);
+}
+
+#[test]
+fn test_cors_allowed_origins() {
+    let (client, _) = setup_private_client();
+
+    let resp = client.get("/api/v1/health").header(Header::new("Origin", "https://dash.example.com")).dispatch();
+    assert_eq!(resp.headers().get_one("Access-Control-Allow-Origin"), Some("https://dash.example.com"));
+    assert_eq!(resp.headers().get_one("Vary"), Some("Origin"));
+
+    let resp = client.get("/api/v1/health").header(Header::new("Origin", "https://evil.example.com")).dispatch();
+    assert_eq!(resp.headers().get_one("Access-Control-Allow-Origin"), None);
+
+    // Default instance stays open
+    let (open, _) = setup_client();
+    let resp = open.get("/api/v1/health").header(Header::new("Origin", "https://anywhere.example")).dispatch();
+    assert_eq!(resp.headers().get_one("Access-Control-Allow-Origin"), Some("*"));
+}
+
+#[test]
+fn test_cors_configuration() {
+    let cors = app_directory::Cors {
+        allowed_headers: vec!["Content-Type".to_string(), "X-API-Key".to_string(), "X-Edit-Token".to_string()],
+        max_age_secs: Some(600),
+        allow_credentials: true,
+        ..app_directory::Cors::allow_origins(&["https://*.example.com"])
+    };
+    let rocket = rocket::build()
+        .manage(app_directory::maintenance::Maintenance::new(false, None, 300))
+        .manage(app_directory::replica::Replica::disabled())
+        .manage(app_directory::retention::PrivacyMode::default())
+        .attach(cors)
+        .mount("/api/v1", rocket::routes![app_directory::routes::health, app_directory::routes::cors_preflight]);
+    let client = Client::tracked(rocket).unwrap();
+
+    // Preflight from a matching subdomain
+    let resp = client
+        .options("/api/v1/apps")
+        .header(Header::new("Origin", "https://team.example.com"))
+        .header(Header::new("Access-Control-Request-Method", "POST"))
+        .dispatch();
+    assert_eq!(resp.status(), Status::NoContent);
+    let headers = resp.headers();
+    assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some("https://team.example.com"));
+    assert_eq!(headers.get_one("Access-Control-Allow-Credentials"), Some("true"));
+    assert_eq!(headers.get_one("Access-Control-Allow-Headers"), Some("Content-Type, X-API-Key, X-Edit-Token"));
+    assert_eq!(headers.get_one("Access-Control-Max-Age"), Some("600"));
+    assert_eq!(headers.get_one("Vary"), Some("Origin"));
+
+    // Max-Age only on preflights
+    let resp = client.get("/api/v1/health").header(Header::new("Origin", "https://team.example.com")).dispatch();
+    assert_eq!(resp.headers().get_one("Access-Control-Allow-Origin"), Some("https://team.example.com"));
+    assert_eq!(resp.headers().get_one("Access-Control-Max-Age"), None);
+
+    // Other origins get nothing, but the response still varies by Origin
+    let resp = client.get("/api/v1/health").header(Header::new("Origin", "https://example.com.evil.net")).dispatch();
+    assert_eq!(resp.headers().get_one("Access-Control-Allow-Origin"), None);
+    assert_eq!(resp.headers().get_one("Access-Control-Allow-Credentials"), None);
+    assert_eq!(resp.headers().get_one("Vary"), Some("Origin"));
+}
+
+#[test]
+fn test_db_debug_headers() {
+    let db_path = format!("/tmp/test_app_dir_{}.db", uuid::Uuid::new_v4());
+    let mut conn = app_directory::db::init_db(&db_path);
+    app_directory::query_stats::install(&mut conn);
+    let rocket = rocket::build()
+        .manage(app_directory::DbState(std::sync::Mutex::new(conn)))
+        .manage(app_directory::rate_limit::RateLimiter::new(std::time::Duration::from_secs(60)))
+        .manage(app_directory::i18n::DefaultLanguage("en".to_string()))
+        .manage(app_directory::pagination::PageLimits::default())
+        .manage(app_directory::stats::ViewRecorder::default())
+        .manage(app_directory::auth::ReadPolicy { require_auth: false })
+        .manage(app_directory::maintenance::Maintenance::new(false, None, 300))
+        .attach(app_directory::query_stats::QueryInstrumentation { headers: true })
+        .mount("/api/v1", rocket::routes![app_directory::routes::list_apps]);
+    let client = Client::tracked(rocket).unwrap();
+
+    let resp = client.get("/api/v1/apps").dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let queries: u64 = resp.headers().get_one("X-DB-Queries").unwrap().parse().unwrap();
+    assert!(queries >= 2, "list runs a count and a page query, got {queries}");
+    let time: f64 = resp.headers().get_one("X-DB-Time").unwrap().parse().unwrap();
+    assert!(time >= 0.0);
+
+    // Totals are per request, not cumulative
+    let again: u64 = client.get("/api/v1/apps").dispatch().headers().get_one("X-DB-Queries").unwrap().parse().unwrap();
+    assert_eq!(again, queries);
+
+    // Off by default
+    let (default_client, _) = setup_client();
+    let resp = default_client.get("/api/v1/apps").dispatch();
+    assert!(resp.headers().get_one("X-DB-Queries").is_none());
+}
+
+/// Minimal rocket with `MANUAL_REVIEW` on and a one-hour SLA.
+/// Returns the client, an admin key, and the database path.
+fn setup_review_client() -> (Client, String, String) {
+    let db_path = format!("/tmp/test_app_dir_{}.db", uuid::Uuid::new_v4());
+    let conn = app_directory::db::init_db(&db_path);
+    let admin = app_directory::auth::create_api_key(&conn, "admin", true, Some(1000));
+    let rocket = rocket::build()
+        .manage(app_directory::DbState(std::sync::Mutex::new(conn)))
+        .manage(app_directory::rate_limit::RateLimiter::new(std::time::Duration::from_secs(60)))
+        .manage(app_directory::specs::SpecVerifier::new(&db_path))
+        .manage(app_directory::logos::LogoCache::from_env(&db_path))
+        .manage(app_directory::a2a::AgentCardVerifier::new(&db_path))
+        .manage(app_directory::validation::FieldLimits::default())
+        .manage(app_directory::moderation::ContentFilter::new(vec![], None))
+        .manage(app_directory::policy::PolicyHook::default())
+        .manage(app_directory::captcha::Captcha::default())
+        .manage(app_directory::events::EventBus::new())
+        .manage(app_directory::moderation::ReviewPolicy { manual: true, sla_hours: 1 })
+        .mount(
+            "/api/v1",
+            rocket::routes![
+                app_directory::routes::submit_app,
+                app_directory::routes::list_pending_apps,
+                app_directory::routes::assign_reviewer,
+                app_directory::routes::approve_app,
+                app_directory::routes::publish_app,
+            ],
+        );
+    (Client::tracked(rocket).unwrap(), admin, db_path)
+}
+
+#[test]
+fn test_manual_review_queue() {
+    let (client, admin, db_path) = setup_review_client();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let reviewer = app_directory::auth::create_api_key(&conn, "reviewer", true, Some(1000));
+    let reviewer_id: String = conn
+        .query_row("SELECT id FROM api_keys WHERE name = 'reviewer'", [], |r| r.get(0))
+        .unwrap();
+    let agent = app_directory::auth::create_api_key(&conn, "agent", false, Some(1000));
+    let agent_id: String = conn.query_row("SELECT id FROM api_keys WHERE name = 'agent'", [], |r| r.get(0)).unwrap();
+
+    // Non-admin and anonymous submissions wait in the queue; admins skip it
+    let submit = |key: Option<&str>, name: &str| -> Value {
+        let mut req = client.post("/api/v1/apps").header(ContentType::JSON).body(
+            serde_json::json!({ "name": name, "short_description": "s", "description": "d", "author_name": "A" })
+                .to_string(),
+        );
+        if let Some(key) = key {
+            req = req.header(Header::new("X-API-Key", key.to_string()));
+        }
+        req.dispatch().into_json().unwrap()
+    };
+    let old = submit(Some(&agent), "Queued Old");
+    assert_eq!(old["status"], "pending");
+    let old = old["app_id"].as_str().unwrap().to_string();
+    let new = submit(None, "Queued New")["app_id"].as_str().unwrap().to_string();
+    assert_eq!(submit(Some(&admin), "Admin App")["status"], "approved");
+    conn.execute("UPDATE apps SET created_at = datetime('now', '-3 hours') WHERE id = ?1", [&old]).unwrap();
+
+    let queue = |query: &str| -> Value {
+        client
+            .get(format!("/api/v1/apps/pending{query}"))
+            .header(Header::new("X-API-Key", admin.clone()))
+            .dispatch()
+            .into_json()
+            .unwrap()
+    };
+    let body = queue("");
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["overdue"], 1);
+    assert_eq!(body["sla_hours"], 1);
+    assert_eq!(body["apps"][0]["id"], old.as_str());
+    assert_eq!(body["apps"][0]["sla"]["overdue"], true);
+    assert!(body["apps"][0]["sla"]["waiting_hours"].as_f64().unwrap() >= 3.0);
+    assert_eq!(body["apps"][1]["sla"]["overdue"], false);
+    assert!(body["apps"][1]["submitted_by_key_id"].is_null());
+
+    let assign = |id: &str, reviewer: Value| {
+        client
+            .post(format!("/api/v1/apps/{id}/assign"))
+            .header(Header::new("X-API-Key", admin.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "reviewer_key_id": reviewer }).to_string())
+            .dispatch()
+    };
+    let resp = assign(&old, serde_json::json!(agent_id));
+    assert_eq!(resp.status(), Status::BadRequest);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "INVALID_REVIEWER");
+    let resp = assign(&old, serde_json::json!(reviewer_id));
+    assert_eq!(resp.status(), Status::Ok);
+    assert!(resp.into_json::<Value>().unwrap()["assigned_at"].is_string());
+
+    let mine: Value = client
+        .get("/api/v1/apps/pending?reviewer=me")
+        .header(Header::new("X-API-Key", reviewer.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(mine["total"], 1);
+    assert_eq!(mine["apps"][0]["assigned_reviewer_key_id"], reviewer_id.as_str());
+    assert_eq!(queue("?reviewer=none")["apps"][0]["id"], new.as_str());
+
+    // The reviewer's decision is audited with the assignment
+    let resp = client
+        .post(format!("/api/v1/apps/{old}/approve"))
+        .header(Header::new("X-API-Key", reviewer.clone()))
+        .header(ContentType::JSON)
+        .body("{}")
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let details: String = conn
+        .query_row(
+            "SELECT details FROM audit_log WHERE action = 'review.approve' AND actor_key_id = ?1",
+            [&reviewer_id],
+            |r| r.get(0),
+        )
+        .unwrap();
+    let details: Value = serde_json::from_str(&details).unwrap();
+    assert_eq!(details["app_id"], old.as_str());
+    assert_eq!(details["assigned_reviewer_key_id"], reviewer_id.as_str());
+    let assigned: i64 = conn
+        .query_row("SELECT COUNT(*) FROM audit_log WHERE action = 'review.assign'", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(assigned, 1);
+
+    // Only pending apps can be assigned
+    let resp = assign(&old, serde_json::json!(reviewer_id));
+    assert_eq!(resp.status(), Status::Conflict);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "NOT_PENDING");
+}
+
+#[test]
+fn test_captcha_verification() {
+    use app_directory::auth::{Actor, AuthenticatedKey};
+    use app_directory::captcha::{Captcha, CaptchaProvider, CaptchaRoute};
+
+    let rt = rocket::tokio::runtime::Runtime::new().unwrap();
+    let passing = serve_static(r#"{"success":true}"#);
+    let failing = serve_static(r#"{"success":false,"error-codes":["invalid-input-response"]}"#);
+    let captcha = |url: &str, routes: Vec<CaptchaRoute>| {
+        Captcha::new(Some(CaptchaProvider::Turnstile), "secret".to_string(), routes).with_verify_url(url)
+    };
+    let all = vec![CaptchaRoute::Apps, CaptchaRoute::Reviews];
+
+    // Disabled by default
+    let off = Captcha::default();
+    assert!(rt.block_on(off.verify(CaptchaRoute::Apps, &Actor::Anonymous, None, None)).is_ok());
+    assert_eq!(off.describe()["enabled"], false);
+
+    let on = captcha(&passing, all.clone());
+    let err = rt.block_on(on.verify(CaptchaRoute::Apps, &Actor::Anonymous, None, None)).unwrap_err();
+    assert_eq!(err.code.as_str(), "CAPTCHA_REQUIRED");
+    assert!(rt.block_on(on.verify(CaptchaRoute::Reviews, &Actor::Anonymous, Some("tok"), None)).is_ok());
+    assert_eq!(on.describe()["provider"], "turnstile");
+
+    // Authenticated callers are never asked
+    let key = Actor::Key(AuthenticatedKey { id: "k".into(), name: "agent".into(), is_admin: false });
+    let rejecting = captcha(&failing, all);
+    assert!(rt.block_on(rejecting.verify(CaptchaRoute::Apps, &key, None, None)).is_ok());
+
+    let err = rt.block_on(rejecting.verify(CaptchaRoute::Apps, &Actor::Anonymous, Some("tok"), None)).unwrap_err();
+    assert_eq!(err.code.as_str(), "CAPTCHA_FAILED");
+    assert_eq!(err.body()["provider_errors"][0], "invalid-input-response");
+
+    // Per-route policy
+    let reviews_only = captcha(&failing, vec![CaptchaRoute::Reviews]);
+    assert!(!reviews_only.required_for(CaptchaRoute::Apps));
+    assert!(rt.block_on(reviews_only.verify(CaptchaRoute::Apps, &Actor::Anonymous, None, None)).is_ok());
+
+    // An unreachable provider fails closed
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let dead = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+    let err = rt.block_on(captcha(&dead, vec![CaptchaRoute::Apps]).verify(CaptchaRoute::Apps, &Actor::Anonymous, Some("tok"), None)).unwrap_err();
+    assert_eq!(err.code.as_str(), "CAPTCHA_UNAVAILABLE");
+}
+
+#[test]
+fn test_captcha_config_endpoint_and_token_field() {
+    let (client, _) = setup_client();
+    let body: Value = client.get("/api/v1/captcha").dispatch().into_json().unwrap();
+    assert_eq!(body["enabled"], false);
+    assert!(body["provider"].is_null());
+
+    // The token field is accepted (and ignored) when CAPTCHA is off
+    let resp = client
+        .post("/api/v1/apps")
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": "Captcha Ready",
+            "short_description": "Anonymous",
+            "description": "Submitted anonymously",
+            "author_name": "Someone",
+            "captcha_token": "ignored",
+        }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+}
+
+#[test]
+fn test_app_preview_html() {
+    let (client, key) = setup_client();
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": "Unfurl Me",
+            "short_description": "Tools & <tricks>",
+            "description": "Closes </script> tags",
+            "author_name": "Preview Co",
+            "homepage_url": "https://unfurl.example.com",
+            "logo_url": "https://unfurl.example.com/logo.png",
+            "tags": ["preview"],
+        }).to_string())
+        .dispatch();
+    let app_id = resp.into_json::<Value>().unwrap()["app_id"].as_str().unwrap().to_string();
+
+    let resp = client.get("/apps/unfurl-me").header(Header::new("Host", "dir.example.com")).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.content_type(), Some(ContentType::HTML));
+    let html = resp.into_string().unwrap();
+    assert!(html.contains("<title>Unfurl Me — App Directory</title>"));
+    assert!(html.contains(r#"<meta property="og:description" content="Tools &amp; &lt;tricks&gt;">"#));
+    assert!(html.contains(r#"<meta property="og:url" content="http://dir.example.com/apps/unfurl-me">"#));
+    assert!(html.contains(r#"<meta name="twitter:image" content="https://unfurl.example.com/logo.png">"#));
+    assert!(!html.contains("</script> tags"));
+
+    let start = html.find(r#"<script type="application/ld+json">"#).unwrap() + 35;
+    let end = start + html[start..].find("</script>").unwrap();
+    let ld: Value = serde_json::from_str(&html[start..end]).unwrap();
+    assert_eq!(ld["@type"], "SoftwareApplication");
+    assert_eq!(ld["url"], "https://unfurl.example.com");
+    assert_eq!(ld["keywords"], "preview");
+    assert!(ld.get("aggregateRating").is_none());
+
+    // The standalone endpoint serves the same block
+    let resp = client.get(format!("/api/v1/apps/{}/jsonld", app_id)).header(Header::new("Host", "dir.example.com")).dispatch();
+    assert_eq!(resp.content_type().map(|c| c.to_string()), Some("application/ld+json".to_string()));
+    assert_eq!(resp.into_json::<Value>().unwrap(), ld);
+
+    // Unapproved listings are kept out of search engines
+    let rejected = submit_simple_app(&client, &key, "Turned Down");
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reject", rejected))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"reason":"Spam"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let noindex = r#"<meta name="robots" content="noindex">"#;
+    assert!(client.get(format!("/apps/{}", rejected)).dispatch().into_string().unwrap().contains(noindex));
+    assert!(!client.get(format!("/apps/{}", app_id)).dispatch().into_string().unwrap().contains(noindex));
+
+    let resp = client.get("/apps/no-such-app").dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+    assert!(resp.into_string().unwrap().contains("App not found"));
+
+    // The JSON API is unaffected
+    let resp = client.get("/api/v1/apps/unfurl-me").dispatch();
+    assert_eq!(resp.content_type(), Some(ContentType::JSON));
+}
+
+#[test]
+fn test_app_og_image() {
+    let (client, key, db_path) = setup_client_with_path();
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": "Card Weather Service",
+            "short_description": "Hourly forecasts for any coordinate, with alerts and historical data",
+            "description": "Forecasts",
+            "author_name": "Forecast Inc",
+            "category": "data/weather",
+        }).to_string())
+        .dispatch();
+    let app_id = resp.into_json::<Value>().unwrap()["app_id"].as_str().unwrap().to_string();
+
+    let resp = client.get("/api/v1/apps/card-weather-service/og.png").dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.content_type(), Some(ContentType::PNG));
+    let png = resp.into_bytes().unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+    // Cached on disk next to the database and served from there
+    let cache_dir = db_path.replace(".db", "-og");
+    let cached = || -> Vec<String> {
+        std::fs::read_dir(&cache_dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect()
+    };
+    assert_eq!(cached().len(), 1);
+    assert!(cached()[0].starts_with(&app_id));
+    let again = client.get(format!("/api/v1/apps/{}/og.png", app_id)).dispatch().into_bytes().unwrap();
+    assert_eq!(again, png);
+
+    // A new rating redraws the card and replaces the old file
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"rating": 4, "title": "Good", "body": "Accurate forecasts"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+    let redrawn = client.get(format!("/api/v1/apps/{}/og.png", app_id)).dispatch().into_bytes().unwrap();
+    assert_ne!(redrawn, png);
+    assert_eq!(cached().len(), 1);
+
+    // The listing page points crawlers at the card
+    let html = client.get("/apps/card-weather-service").header(Header::new("Host", "dir.example.com")).dispatch().into_string().unwrap();
+    let card_url = format!("http://dir.example.com/api/v1/apps/{}/og.png", app_id);
+    assert!(html.contains(&format!(r#"<meta property="og:image" content="{}">"#, card_url)));
+    assert!(html.contains(r#"<meta name="twitter:card" content="summary_large_image">"#));
+
+    let resp = client.get("/api/v1/apps/no-such-app/og.png").dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+    let _ = std::fs::remove_dir_all(&cache_dir);
+}
+
+#[test]
+fn test_app_json_ld() {
+    let (client, key) = setup_client();
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": "Weather API",
+            "short_description": "Forecasts",
+            "description": "Forecasts as JSON",
+            "author_name": "Forecast Inc",
+            "author_url": "https://forecast.example.com",
+            "api_url": "https://api.forecast.example.com",
+            "api_spec_url": "https://api.forecast.example.com/openapi.json",
+            "category": "data",
+        }).to_string())
+        .dispatch();
+    let app_id = resp.into_json::<Value>().unwrap()["app_id"].as_str().unwrap().to_string();
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":4}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+
+    let ld: Value = client.get("/api/v1/apps/weather-api/jsonld").dispatch().into_json().unwrap();
+    assert_eq!(ld["@context"], "https://schema.org");
+    assert_eq!(ld["@type"], "WebAPI");
+    assert_eq!(ld["identifier"], app_id.as_str());
+    assert_eq!(ld["category"], "data");
+    assert_eq!(ld["documentation"], "https://api.forecast.example.com/openapi.json");
+    assert_eq!(ld["potentialAction"]["target"], "https://api.forecast.example.com");
+    assert_eq!(ld["provider"], serde_json::json!({ "@type": "Organization", "name": "Forecast Inc", "url": "https://forecast.example.com" }));
+    assert_eq!(ld["aggregateRating"]["ratingValue"], 4.0);
+    assert_eq!(ld["aggregateRating"]["ratingCount"], 1);
+    // Without a homepage the listing page is the canonical URL
+    assert_eq!(ld["url"], "/apps/weather-api");
+
+    let resp = client.get("/api/v1/apps/missing/jsonld").dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "NOT_FOUND");
+}
+
+#[test]
+fn test_content_filter_rejects_and_logs() {
+    let (client, admin) = setup_client();
+    let app_id = submit_simple_app(&client, &admin, "Filtered");
+
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "rating": 5, "title": "Great", "body": format!("BUY NOW{}", "!".repeat(30)) }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::UnprocessableEntity);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "CONTENT_REJECTED");
+    assert_eq!(body["field"], "body");
+    assert_eq!(body["rule"], "repeated_chars");
+
+    let links = (0..6).map(|i| format!("https://site{}.example.com", i)).collect::<Vec<_>>().join(" ");
+    let resp = client
+        .post("/api/v1/apps")
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": "Linkfarm",
+            "short_description": "Links",
+            "description": links,
+            "author_name": "Spammer"
+        }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::UnprocessableEntity);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["rule"], "max_links");
+    assert_eq!(body["field"], "description");
+
+    // Edits are filtered too
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "short_description": "zzzzzzzzzzzzzzzzzzzzzzzzzzzzzz" }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::UnprocessableEntity);
+
+    // And so are translations, which readers see in place of the listing's text
+    let resp = client
+        .put(format!("/api/v1/apps/{}/translations/de", app_id))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "name": "Gefiltert", "description": links }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::UnprocessableEntity);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["rule"], "max_links");
+    let resp = client
+        .get(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("Accept-Language", "de"))
+        .dispatch();
+    assert!(resp.into_json::<Value>().unwrap()["available_languages"].as_array().unwrap().is_empty());
+
+    let log: Value = client
+        .get("/api/v1/admin/content-rejections")
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(log["total"], 4);
+    let entries = log["rejections"].as_array().unwrap();
+    assert_eq!(entries[0]["kind"], "app");
+    assert_eq!(entries[0]["app_id"], app_id.as_str());
+    assert_eq!(entries[3]["kind"], "review");
+    assert_eq!(entries[3]["content"]["title"], "Great");
+
+    let reviews: Value = client
+        .get("/api/v1/admin/content-rejections?kind=review")
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(reviews["total"], 1);
+
+    let (_, body) = create_key_with(&client, &admin, serde_json::json!({ "name": "plain" }));
+    let resp = client
+        .get("/api/v1/admin/content-rejections")
+        .header(Header::new("X-API-Key", body["api_key"].as_str().unwrap().to_string()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Forbidden);
+}
+
+#[test]
+fn test_moderation_webhook_veto() {
+    use app_directory::moderation::{ContentFilter, MaxLinks};
+
+    let veto = serve_static(r#"{"allow": false, "field": "body", "reason": "Looks like spam"}"#);
+    let filter = ContentFilter::new(vec![Box::new(MaxLinks(5))], Some(veto));
+    let rt = rocket::tokio::runtime::Runtime::new().unwrap();
+    let err = rt
+        .block_on(filter.check("review", &[("title", Some("hi")), ("body", Some("cheap pills"))]))
+        .unwrap_err();
+    assert_eq!(err.rule, "webhook");
+    assert_eq!(err.field, "body");
+    assert_eq!(err.reason, "Looks like spam");
+
+    let allow = serve_static(r#"{"allow": true}"#);
+    let filter = ContentFilter::new(vec![], Some(allow));
+    assert!(rt.block_on(filter.check("review", &[("body", Some("fine"))])).is_ok());
+
+    // Unreachable webhook fails open
+    let filter = ContentFilter::new(vec![], Some("http://127.0.0.1:9/moderate".to_string()));
+    assert!(rt.block_on(filter.check("review", &[("body", Some("fine"))])).is_ok());
+}
+
+/// Minimal rocket for app submission and edits behind `policy`, sharing `db_path`.
+fn policy_client(db_path: &str, policy: app_directory::policy::PolicyHook) -> Client {
+    let rocket = rocket::build()
+        .manage(app_directory::DbState(std::sync::Mutex::new(app_directory::db::init_db(db_path))))
+        .manage(app_directory::rate_limit::RateLimiter::new(std::time::Duration::from_secs(60)))
+        .manage(app_directory::specs::SpecVerifier::new(db_path))
+        .manage(app_directory::logos::LogoCache::from_env(db_path))
+        .manage(app_directory::a2a::AgentCardVerifier::new(db_path))
+        .manage(app_directory::validation::FieldLimits::default())
+        .manage(app_directory::moderation::ContentFilter::new(vec![], None))
+        .manage(policy)
+        .manage(app_directory::captcha::Captcha::default())
+        .manage(app_directory::events::EventBus::new())
+        .manage(app_directory::moderation::ReviewPolicy::default())
+        .manage(app_directory::i18n::DefaultLanguage("en".to_string()))
+        .mount(
+            "/api/v1",
+            rocket::routes![
+                app_directory::routes::submit_app,
+                app_directory::routes::update_app,
+                app_directory::routes::put_translation,
+            ],
+        );
+    Client::tracked(rocket).unwrap()
+}
+
+#[test]
+fn test_policy_webhook_blocks_writes() {
+    use app_directory::policy::PolicyHook;
+    use std::io::{Read, Write};
+
+    let db_path = format!("/tmp/test_app_dir_{}.db", uuid::Uuid::new_v4());
+    let admin = app_directory::auth::create_api_key(&app_directory::db::init_db(&db_path), "admin", true, Some(1000));
+    let submit = |client: &Client| -> (Status, Value) {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Policy App", "short_description": "Short", "description": "Long", "author_name": "Tester"}"#)
+            .dispatch();
+        (resp.status(), resp.into_json().unwrap())
+    };
+    let apps = || -> i64 {
+        rusqlite::Connection::open(&db_path).unwrap().query_row("SELECT COUNT(*) FROM apps", [], |r| r.get(0)).unwrap()
+    };
+
+    let veto = serve_static(r#"{"allow": false, "field": "category", "reason": "Only internal tools may be listed"}"#);
+    let vetoed = policy_client(&db_path, PolicyHook::new(Some(veto), false));
+    let (status, body) = submit(&vetoed);
+    assert_eq!(status, Status::UnprocessableEntity);
+    assert_eq!(body["error"], "POLICY_REJECTED");
+    assert_eq!(body["field"], "category");
+    assert_eq!(body["message"], "Only internal tools may be listed");
+    assert_eq!(apps(), 0);
+
+    // Any non-2xx answer blocks the write too
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let failing = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let _ = stream.read(&mut [0u8; 4096]);
+            let _ = stream.write_all(b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        }
+    });
+    let (status, body) = submit(&policy_client(&db_path, PolicyHook::new(Some(failing), false)));
+    assert_eq!(status, Status::UnprocessableEntity);
+    assert_eq!(body["field"], "*");
+
+    // An unreachable service fails closed unless configured to fail open
+    let down = || Some("http://127.0.0.1:9/policy".to_string());
+    assert_eq!(submit(&policy_client(&db_path, PolicyHook::new(down(), false))).0, Status::UnprocessableEntity);
+    assert_eq!(apps(), 0);
+    assert_eq!(submit(&policy_client(&db_path, PolicyHook::new(down(), true))).0, Status::Created);
+
+    let allowed = policy_client(&db_path, PolicyHook::new(Some(serve_static(r#"{"allow": true}"#)), false));
+    let (status, body) = submit(&allowed);
+    assert_eq!(status, Status::Created);
+    let (id, token) = (body["app_id"].as_str().unwrap().to_string(), body["edit_token"].as_str().unwrap().to_string());
+    assert_eq!(apps(), 2);
+
+    let resp = vetoed
+        .patch(format!("/api/v1/apps/{}", id))
+        .header(Header::new("X-Edit-Token", token.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"category": "finance"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::UnprocessableEntity);
+
+    // Translations go through the policy service as updates
+    let translate = |client: &Client| {
+        client
+            .put(format!("/api/v1/apps/{}/translations/de", id))
+            .header(Header::new("X-Edit-Token", token.clone()))
+            .header(ContentType::JSON)
+            .body(r#"{"name": "Richtlinien-App"}"#)
+            .dispatch()
+            .status()
+    };
+    assert_eq!(translate(&vetoed), Status::UnprocessableEntity);
+    assert_eq!(translate(&allowed), Status::Ok);
+
+    // Edits that don't touch the listing fields skip the policy service
+    let resp = vetoed
+        .patch(format!("/api/v1/apps/{}", id))
+        .header(Header::new("X-API-Key", admin))
+        .header(ContentType::JSON)
+        .body(r#"{"is_featured": true}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+}
+
+#[test]
+fn test_draft_listings() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let owner = app_directory::auth::create_api_key(&conn, "owner", false, Some(1000));
+    let other = app_directory::auth::create_api_key(&conn, "other", false, Some(1000));
+
+    let draft_body = serde_json::json!({
+        "name": "Draft Tool",
+        "short_description": "Work in progress",
+        "description": "Not ready yet",
+        "author_name": "A",
+        "status": "draft",
+    })
+    .to_string();
+
+    // Drafts need an owner to come back to them
+    let resp = client.post("/api/v1/apps").header(ContentType::JSON).body(draft_body.clone()).dispatch();
+    assert_eq!(resp.status(), Status::Unauthorized);
+    let resp = client
+        .post("/api/v1/apps")
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", owner.clone()))
+        .body(draft_body.replace("\"draft\"", "\"approved\""))
+        .dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "INVALID_STATUS");
+
+    let resp = client
+        .post("/api/v1/apps")
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", owner.clone()))
+        .body(draft_body)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["status"], "draft");
+    let id = body["app_id"].as_str().unwrap().to_string();
+    let token = body["edit_token"].as_str().unwrap().to_string();
+    assert_eq!(body["publish_url"], format!("/apps/{id}/publish"));
+
+    // Invisible publicly, even with status=all
+    let get = |key: Option<&str>, query: &str| {
+        let mut req = client.get(format!("/api/v1/apps/{id}{query}"));
+        if let Some(key) = key {
+            req = req.header(Header::new("X-API-Key", key.to_string()));
+        }
+        req.dispatch().status()
+    };
+    assert_eq!(get(None, ""), Status::NotFound);
+    assert_eq!(get(Some(&other), ""), Status::NotFound);
+    assert_eq!(get(Some(&owner), ""), Status::Ok);
+    assert_eq!(get(Some(&admin), ""), Status::Ok);
+    assert_eq!(get(None, &format!("?token={token}")), Status::Ok);
+    for query in ["", "?status=all", "?status=draft"] {
+        let list: Value = client.get(format!("/api/v1/apps{query}")).dispatch().into_json().unwrap();
+        assert!(list["apps"].as_array().unwrap().iter().all(|a| a["id"] != id.as_str()), "{query}");
+    }
+    let list: Value = client
+        .get("/api/v1/apps?status=draft")
+        .header(Header::new("X-API-Key", admin.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(list["total"], 1);
+    assert_eq!(client.get(format!("/apps/{id}")).dispatch().status(), Status::NotFound);
+
+    // Fully editable while in draft
+    let resp = client
+        .patch(format!("/api/v1/apps/{id}"))
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", owner.clone()))
+        .body(r#"{"description": "Ready now"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+
+    // Admins can't approve a draft out from under its owner
+    let resp = client
+        .post(format!("/api/v1/apps/{id}/approve"))
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", admin.clone()))
+        .body("{}")
+        .dispatch();
+    assert_eq!(resp.status(), Status::Conflict);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "INVALID_TRANSITION");
+
+    let publish = |key: &str| {
+        client
+            .post(format!("/api/v1/apps/{id}/publish"))
+            .header(Header::new("X-API-Key", key.to_string()))
+            .dispatch()
+    };
+    assert_eq!(publish(&other).status(), Status::Forbidden);
+    let resp = publish(&owner);
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["status"], "approved");
+    assert_eq!(body["previous_status"], "draft");
+    assert_eq!(get(None, ""), Status::Ok);
+    let resp = publish(&owner);
+    assert_eq!(resp.status(), Status::Conflict);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "NOT_DRAFT");
+
+    // Under manual review, publishing sends the draft to the queue
+    let (client, _admin, db_path) = setup_review_client();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let owner = app_directory::auth::create_api_key(&conn, "owner", false, Some(1000));
+    let body: Value = client
+        .post("/api/v1/apps")
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", owner.clone()))
+        .body(r#"{"name": "Queued Draft", "short_description": "s", "description": "d", "author_name": "A", "status": "draft"}"#)
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(body["status"], "draft");
+    let resp = client
+        .post(format!("/api/v1/apps/{}/publish", body["app_id"].as_str().unwrap()))
+        .header(Header::new("X-API-Key", owner))
+        .dispatch();
+    assert_eq!(resp.into_json::<Value>().unwrap()["status"], "pending");
+}
+
+#[test]
+fn test_duplicate_app() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let owner = app_directory::auth::create_api_key(&conn, "owner", false, Some(1000));
+    let other = app_directory::auth::create_api_key(&conn, "other", false, Some(1000));
+
+    let original = submit_simple_app(&client, &owner, "Weather Bot");
+    client
+        .put(format!("/api/v1/apps/{original}/translations/de"))
+        .header(ContentType::JSON)
+        .header(Header::new("X-API-Key", owner.clone()))
+        .body(r#"{"short_description": "Wetter"}"#)
+        .dispatch();
+
+    let duplicate = |key: &str, body: Option<&str>| {
+        let mut req = client
+            .post(format!("/api/v1/apps/{original}/duplicate"))
+            .header(Header::new("X-API-Key", key.to_string()));
+        if let Some(body) = body {
+            req = req.header(ContentType::JSON).body(body);
+        }
+        req.dispatch()
+    };
+    assert_eq!(duplicate(&other, None).status(), Status::Forbidden);
+
+    // Plain copy: same name, fresh slug, private draft
+    let resp = duplicate(&owner, None);
+    assert_eq!(resp.status(), Status::Created);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["status"], "draft");
+    assert_eq!(body["duplicated_from"], original.as_str());
+    assert_ne!(body["slug"], "weather-bot");
+    let copy = body["app_id"].as_str().unwrap().to_string();
+    let app: Value = client
+        .get(format!("/api/v1/apps/{copy}"))
+        .header(Header::new("X-API-Key", owner.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(app["name"], "Weather Bot");
+    assert_eq!(app["short_description"], "Test app");
+    assert_eq!(app["review_count"], 0);
+    let translated: i64 = conn
+        .query_row("SELECT COUNT(*) FROM app_translations WHERE app_id = ?1", rusqlite::params![copy], |r| r.get(0))
+        .unwrap();
+    assert_eq!(translated, 1);
+    assert_eq!(client.get(format!("/api/v1/apps/{copy}")).dispatch().status(), Status::NotFound);
+
+    // v2 linked to the original once it's published
+    let resp = duplicate(&owner, Some(r#"{"name": "Weather Bot 2", "link_original": true}"#));
+    assert_eq!(resp.status(), Status::Created);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["slug"], "weather-bot-2");
+    let v2 = body["app_id"].as_str().unwrap().to_string();
+    let replacement = || -> Option<String> {
+        conn.query_row("SELECT replacement_app_id FROM apps WHERE id = ?1", rusqlite::params![original], |r| r.get(0))
+            .unwrap()
+    };
+    assert_eq!(replacement(), None);
+    let resp = client
+        .post(format!("/api/v1/apps/{v2}/publish"))
+        .header(Header::new("X-API-Key", owner.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(replacement(), Some(v2.clone()));
+
+    assert_eq!(duplicate(&admin, Some(r#"{"name": ""}"#)).status(), Status::BadRequest);
+    let resp = client.post("/api/v1/apps/nope/duplicate").header(Header::new("X-API-Key", admin)).dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+#[test]
+fn test_verified_usage_reviews() {
+    let (client, key, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let user = app_directory::auth::create_api_key(&conn, "user", false, Some(1000));
+    let lurker = app_directory::auth::create_api_key(&conn, "lurker", false, Some(1000));
+    let app_id = submit_simple_app(&client, &key, "Clickable");
+
+    // No link to follow yet
+    let resp = client.get(format!("/api/v1/apps/{app_id}/visit")).dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+    client
+        .patch(format!("/api/v1/apps/{app_id}"))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"homepage_url": "https://clickable.example.com"}"#)
+        .dispatch();
+
+    let resp = client
+        .get(format!("/api/v1/apps/{app_id}/visit"))
+        .header(Header::new("X-API-Key", user.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::SeeOther);
+    assert_eq!(resp.headers().get_one("Location"), Some("https://clickable.example.com"));
+    let resp = client.get(format!("/api/v1/apps/{app_id}/visit?target=docs")).dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+
+    let review = |key: &str, rating: i64| -> Value {
+        let resp = client
+            .post(format!("/api/v1/apps/{app_id}/reviews"))
+            .header(Header::new("X-API-Key", key.to_string()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "rating": rating }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+        resp.into_json().unwrap()
+    };
+    assert_eq!(review(&user, 5)["verified_usage"], true);
+    assert_eq!(review(&lurker, 1)["verified_usage"], false);
+
+    let reviews = |query: &str| -> Value {
+        client.get(format!("/api/v1/apps/{app_id}/reviews{query}")).dispatch().into_json().unwrap()
+    };
+    let body = reviews("?verified_usage=true");
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["reviews"][0]["rating"], 5);
+    conn.execute("UPDATE reviews SET created_at = datetime('now', '-1 hour') WHERE rating = 5", []).unwrap();
+    assert_eq!(reviews("")["reviews"][0]["rating"], 1);
+    assert_eq!(reviews("?sort=verified")["reviews"][0]["verified_usage"], true);
+
+    let summary = reviews("/summary");
+    assert_eq!(summary["review_count"], 2);
+    assert_eq!(summary["verified_usage_count"], 1);
+    assert_eq!(summary["verified_avg_rating"], 5.0);
+
+    // Clicks older than the window don't count
+    conn.execute("UPDATE app_clicks SET clicked_at = datetime('now', '-91 days')", []).unwrap();
+    assert_eq!(review(&user, 4)["verified_usage"], false);
+}
+
+#[test]
+fn test_localized_error_messages() {
+    let (client, _key) = setup_client();
+
+    // English by default, with a key for client-side translations
+    let resp = client.get("/api/v1/apps/does-not-exist").dispatch();
+    assert!(resp.headers().get_one("Content-Language").is_none());
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["message"], "App not found");
+    assert_eq!(body["message_key"], "errors.not_found");
+    assert!(body.get("message_en").is_none());
+
+    let resp = client
+        .get("/api/v1/apps/does-not-exist")
+        .header(Header::new("Accept-Language", "es-MX, en;q=0.5"))
+        .dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+    assert_eq!(resp.headers().get_one("Content-Language"), Some("es"));
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "NOT_FOUND");
+    assert_eq!(body["message"], "Recurso no encontrado");
+    assert_eq!(body["message_en"], "App not found");
+    assert_eq!(body["message_key"], "errors.not_found");
+
+    // Guard and catcher errors too; English preferred or unsupported falls back
+    let body: Value = client
+        .get("/api/v1/apps/mine")
+        .header(Header::new("Accept-Language", "de"))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(body["message"], "API-Schlüssel fehlt oder ist ungültig");
+    for lang in ["en-GB, de;q=0.9", "ja"] {
+        let body: Value = client
+            .get("/api/v1/apps/mine")
+            .header(Header::new("Accept-Language", lang))
+            .dispatch()
+            .into_json()
+            .unwrap();
+        assert_eq!(body["message"], "Missing or invalid API key", "{lang}");
+    }
+
+    // Successful responses are untouched
+    let resp = client.get("/api/v1/apps").header(Header::new("Accept-Language", "fr")).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert!(resp.headers().get_one("Content-Language").is_none());
+
+    let registry: Value = client.get("/api/v1/errors").dispatch().into_json().unwrap();
+    let not_found = registry["errors"].as_array().unwrap().iter().find(|e| e["code"] == "NOT_FOUND").unwrap();
+    assert_eq!(not_found["message_key"], "errors.not_found");
+    assert_eq!(not_found["translations"]["fr"], "Ressource introuvable");
+    assert_eq!(registry["languages"], serde_json::json!(["en", "de", "es", "fr"]));
+}
+
+#[test]
+fn test_app_docs_markdown() {
+    let (client, key) = setup_client();
+    let app_id = submit_simple_app(&client, &key, "Documented Agent");
+
+    // No docs yet
+    assert_eq!(client.get(format!("/api/v1/apps/{}/docs.md", app_id)).dispatch().status(), Status::NotFound);
+    let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+    assert!(app["docs_url"].is_null());
+
+    let markdown = "# Quick start\n\nCall `GET /v1/run` with **your** key.\n<script>alert('x')</script>\n\
+                    [Guide](javascript:alert(1)) and [site](https://example.com)\n\n```\n<b>example</b>\n```";
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "docs_markdown": markdown }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+
+    // Raw (sanitized) Markdown
+    let resp = client.get(format!("/api/v1/apps/{}/docs.md", app_id)).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.content_type().unwrap().to_string(), "text/markdown; charset=utf-8");
+    let stored = resp.into_string().unwrap();
+    assert!(stored.starts_with("# Quick start"));
+    assert!(!stored.contains("alert('x')"));
+    assert!(stored.contains("[Guide](#) and [site](https://example.com)"));
+    assert!(stored.contains("<b>example</b>"));
+    let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(app["docs_url"], format!("/api/v1/apps/{}/docs.md", app_id));
+
+    // Rendered on the listing page
+    let page = client.get(format!("/apps/{}", app_id)).dispatch().into_string().unwrap();
+    assert!(page.contains("<h2>Quick start</h2>"));
+    assert!(page.contains("<code>GET /v1/run</code> with <strong>your</strong> key."));
+    assert!(page.contains("<pre><code>&lt;b&gt;example&lt;/b&gt;</code></pre>"));
+    assert!(!page.contains("alert("));
+
+    // Length limit
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "docs_markdown": "x".repeat(50_001) }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::BadRequest);
+
+    // Empty string clears them
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", app_id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "docs_markdown": "" }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(client.get(format!("/api/v1/apps/{}/docs.md", app_id)).dispatch().status(), Status::NotFound);
+}
+
+#[test]
+fn test_admin_config_reload() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let member = app_directory::auth::create_api_key(&conn, "member", false, Some(1000));
+
+    let resp = client
+        .post("/api/v1/admin/reload")
+        .header(Header::new("X-API-Key", member))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Forbidden);
+
+    let resp = client
+        .post("/api/v1/admin/reload")
+        .header(Header::new("X-API-Key", admin))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let body: Value = resp.into_json().unwrap();
+    assert!(body["changed"].is_array());
+    for name in ["RATE_LIMIT_WINDOW_SECS", "ANON_REVIEW_DAILY_CAP", "HEALTH_CHECK_INTERVAL_SECS", "STATIC_DIR"] {
+        assert!(!body["config"][name].is_null(), "{name} missing");
+    }
+
+    let logged: i64 = conn
+        .query_row("SELECT COUNT(*) FROM audit_log WHERE action = 'admin.config_reload'", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(logged, 1);
+}
+
+#[test]
+fn test_app_endorsements() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let agent = app_directory::auth::create_api_key(&conn, "agent", false, Some(1000));
+
+    let mut apps = std::collections::HashMap::new();
+    for name in ["Ledger", "Invoicer", "Forecast"] {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", admin.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name,
+                "short_description": "Agent tool",
+                "description": "A tool",
+                "author_name": "Team",
+            }).to_string())
+            .dispatch();
+        let body: Value = resp.into_json().unwrap();
+        apps.insert(name, (body["app_id"].as_str().unwrap().to_string(), body["edit_token"].as_str().unwrap().to_string()));
+    }
+    let (ledger, ledger_token) = apps["Ledger"].clone();
+    let (invoicer, _) = apps["Invoicer"].clone();
+    let endorse = |target: &str, from: &str, credential: Option<Header<'static>>, note: &str| {
+        let mut req = client
+            .post(format!("/api/v1/apps/{target}/endorsements"))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "from_app_id": from, "note": note }).to_string());
+        if let Some(h) = credential {
+            req = req.header(h);
+        }
+        req.dispatch()
+    };
+
+    // Only whoever can edit the endorsing app may endorse from it
+    assert_eq!(endorse(&invoicer, &ledger, None, "").status(), Status::Unauthorized);
+    assert_eq!(
+        endorse(&invoicer, &ledger, Some(Header::new("X-API-Key", agent.clone())), "").status(),
+        Status::Forbidden
+    );
+    let token = || Some(Header::new("X-Edit-Token", ledger_token.clone()));
+    let resp = endorse(&ledger, &ledger, token(), "");
+    assert_eq!(resp.status(), Status::BadRequest);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "INVALID_ENDORSEMENT");
+
+    let resp = endorse(&invoicer, &ledger, token(), "Pairs well");
+    assert_eq!(resp.status(), Status::Created);
+    assert_eq!(resp.into_json::<Value>().unwrap()["endorsements"]["received"], 1);
+    let resp = endorse(&invoicer, "ledger", token(), "Works great with Ledger");
+    assert_eq!(resp.status(), Status::Ok);
+
+    // Listed on both apps
+    let body: Value = client.get("/api/v1/apps/invoicer/endorsements").dispatch().into_json().unwrap();
+    assert_eq!(body["received"][0]["app"]["id"], ledger.as_str());
+    assert_eq!(body["received"][0]["note"], "Works great with Ledger");
+    assert_eq!(body["given"], serde_json::json!([]));
+    let body: Value = client.get(format!("/api/v1/apps/{ledger}/endorsements")).dispatch().into_json().unwrap();
+    assert_eq!(body["given"][0]["app"]["slug"], "invoicer");
+    let body: Value = client.get(format!("/api/v1/apps/{invoicer}")).dispatch().into_json().unwrap();
+    assert_eq!(body["endorsements"], serde_json::json!({ "received": 1, "given": 0 }));
+
+    // Endorsements lift the endorsed app in recommendations for viewers of the endorser
+    client.get(format!("/api/v1/apps/{ledger}")).header(Header::new("X-API-Key", agent.clone())).dispatch();
+    let body: Value = client
+        .get("/api/v1/apps/recommended")
+        .header(Header::new("X-API-Key", agent.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(body["recommended"][0]["name"], "Invoicer");
+    assert_eq!(body["recommended"][0]["reasons"]["endorsements"], 1);
+    assert_eq!(body["recommended"][1]["reasons"]["endorsements"], 0);
+
+    let withdraw = || {
+        client
+            .delete(format!("/api/v1/apps/{invoicer}/endorsements/{ledger}"))
+            .header(Header::new("X-Edit-Token", ledger_token.clone()))
+            .dispatch()
+    };
+    let resp = withdraw();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.into_json::<Value>().unwrap()["endorsements"]["received"], 0);
+    assert_eq!(withdraw().status(), Status::NotFound);
+}
+
+#[test]
+fn test_recommended_apps() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let agent = app_directory::auth::create_api_key(&conn, "agent", false, Some(1000));
+    let peer = app_directory::auth::create_api_key(&conn, "peer", false, Some(1000));
+
+    let mut ids = std::collections::HashMap::new();
+    for (name, category, tag) in [
+        ("Ledger", "finance", "accounting"),
+        ("Invoicer", "finance", "billing"),
+        ("Forecast", "data", "climate"),
+        ("Audit Trail", "data", "accounting"),
+        ("Unrelated", "media", "fun"),
+    ] {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", admin.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name,
+                "short_description": "Agent tool",
+                "description": "A tool",
+                "author_name": "Team",
+                "category": category,
+                "tags": [tag],
+            }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+        let body: Value = resp.into_json().unwrap();
+        ids.insert(name, body["app_id"].as_str().unwrap().to_string());
+    }
+
+    let view = |key: &str, name: &str| {
+        let resp = client
+            .get(format!("/api/v1/apps/{}", ids[name]))
+            .header(Header::new("X-API-Key", key.to_string()))
+            .dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+    };
+    let recommended = |key: &str| -> Value {
+        let resp = client
+            .get("/api/v1/apps/recommended")
+            .header(Header::new("X-API-Key", key.to_string()))
+            .dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+        resp.into_json().unwrap()
+    };
+
+    // Needs a key; no history means nothing to go on
+    assert_eq!(client.get("/api/v1/apps/recommended").dispatch().status(), Status::Unauthorized);
+    let body = recommended(&agent);
+    assert_eq!(body["based_on"], 0);
+    assert_eq!(body["recommended"], serde_json::json!([]));
+
+    view(&agent, "Ledger");
+    view(&peer, "Ledger");
+    view(&peer, "Forecast");
+
+    let body = recommended(&agent);
+    assert_eq!(body["based_on"], 1);
+    let names: Vec<&str> = body["recommended"].as_array().unwrap().iter().map(|a| a["name"].as_str().unwrap()).collect();
+    // Viewed by a peer with the same interests, then same category, then a shared tag
+    assert_eq!(names, ["Forecast", "Invoicer", "Audit Trail"]);
+    assert_eq!(body["recommended"][0]["reasons"]["co_viewers"], 1);
+    assert_eq!(body["recommended"][1]["reasons"]["same_category"], true);
+    assert_eq!(body["recommended"][2]["reasons"]["shared_tags"], serde_json::json!(["accounting"]));
+}
+
+#[test]
+fn test_admin_tag_rename_and_merge() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let member = app_directory::auth::create_api_key(&conn, "member", false, Some(1000));
+
+    let mut ids = Vec::new();
+    for (name, tags) in [
+        ("Scanner Agent", serde_json::json!(["QRCode", "tools"])),
+        ("Barcode Agent", serde_json::json!(["qr_code", "qr-code", "barcodes"])),
+        ("Weather Agent", serde_json::json!(["weather"])),
+    ] {
+        let resp = client
+            .post("/api/v1/apps")
+            .header(Header::new("X-API-Key", admin.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({
+                "name": name,
+                "short_description": "Test",
+                "description": "Tag maintenance test",
+                "author_name": "Test",
+                "tags": tags,
+            }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+        ids.push(resp.into_json::<Value>().unwrap()["app_id"].as_str().unwrap().to_string());
+    }
+    let post = |path: &str, key: &str, body: Value| {
+        let resp = client
+            .post(format!("/api/v1/admin/tags/{}", path))
+            .header(Header::new("X-API-Key", key.to_string()))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch();
+        let status = resp.status();
+        (status, resp.into_json::<Value>().unwrap())
+    };
+    let tags_of = |id: &str| {
+        let body: Value = client
+            .get(format!("/api/v1/apps/{}", id))
+            .header(Header::new("X-API-Key", admin.clone()))
+            .dispatch()
+            .into_json()
+            .unwrap();
+        body["tags"].clone()
+    };
+
+    let (status, _) = post("rename", &member, serde_json::json!({ "from": "weather", "to": "forecast" }));
+    assert_eq!(status, Status::Forbidden);
+    let (status, body) = post("rename", &admin, serde_json::json!({ "from": " ", "to": "forecast" }));
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["error"], "INVALID_TAG");
+    assert_eq!(body["field"], "from");
+
+    // Renaming onto a tag in use is refused; merge handles that
+    let (status, body) = post("rename", &admin, serde_json::json!({ "from": "tools", "to": "Barcodes" }));
+    assert_eq!(status, Status::Conflict);
+    assert_eq!(body["error"], "TAG_EXISTS");
+    assert_eq!(body["app_count"], 1);
+
+    // Dry run reports without writing
+    let (status, body) = post(
+        "merge",
+        &admin,
+        serde_json::json!({ "from": ["qrcode", "QR_CODE"], "into": "qr-code", "dry_run": true }),
+    );
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["dry_run"], true);
+    assert_eq!(body["affected"], 2);
+    assert_eq!(body["apps"][1]["after"], serde_json::json!(["qr-code", "barcodes"]));
+    assert_eq!(tags_of(&ids[0]), serde_json::json!(["QRCode", "tools"]));
+
+    let (status, body) = post("merge", &admin, serde_json::json!({ "from": ["qrcode", "QR_CODE"], "into": "qr-code" }));
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["affected"], 2);
+    assert_eq!(tags_of(&ids[0]), serde_json::json!(["qr-code", "tools"]));
+    assert_eq!(tags_of(&ids[1]), serde_json::json!(["qr-code", "barcodes"]));
+    assert_eq!(tags_of(&ids[2]), serde_json::json!(["weather"]));
+
+    // Case-only rename onto the tag's own spelling
+    let (status, body) = post("rename", &admin, serde_json::json!({ "from": "weather", "to": "Weather" }));
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["affected"], 1);
+    assert_eq!(tags_of(&ids[2]), serde_json::json!(["Weather"]));
+
+    let revisions: i64 = conn
+        .query_row("SELECT COUNT(*) FROM app_revisions WHERE app_id = ?1", [&ids[0]], |r| r.get(0))
+        .unwrap();
+    assert_eq!(revisions, 2);
+    let audited: i64 = conn
+        .query_row("SELECT COUNT(*) FROM audit_log WHERE action IN ('admin.tag_merge', 'admin.tag_rename')", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(audited, 2);
+}
+
+#[test]
+fn test_composite_health_from_dependencies() {
+    let (client, key, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let composite = submit_simple_app(&client, &key, "Composite Agent");
+    let llm = submit_simple_app(&client, &key, "Upstream LLM");
+    let store = submit_simple_app(&client, &key, "Upstream Store");
+    let hidden = submit_simple_app(&client, &key, "Hidden Upstream");
+    conn.execute("UPDATE apps SET status = 'approved', last_health_status = 'healthy'", []).unwrap();
+    conn.execute("UPDATE apps SET visibility = 'private' WHERE id = ?1", [&hidden]).unwrap();
+    let llm_slug: String = conn.query_row("SELECT slug FROM apps WHERE id = ?1", [&llm], |r| r.get(0)).unwrap();
+
+    let patch = |depends_on: Value| {
+        let resp = client
+            .patch(format!("/api/v1/apps/{}", composite))
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "depends_on": depends_on }).to_string())
+            .dispatch();
+        let status = resp.status();
+        (status, resp.into_json::<Value>().unwrap())
+    };
+    let get = || -> Value {
+        client
+            .get(format!("/api/v1/apps/{}", composite))
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch()
+            .into_json()
+            .unwrap()
+    };
+
+    let app = get();
+    assert!(app["composite_health"].is_null());
+    assert_eq!(app["depends_on"], serde_json::json!([]));
+
+    for bad in [serde_json::json!([composite.clone()]), serde_json::json!([hidden.clone()]), serde_json::json!(["no-such-app"])] {
+        let (status, body) = patch(bad);
+        assert_eq!(status, Status::BadRequest);
+        assert_eq!(body["error"], "INVALID_DEPENDENCY");
+    }
+
+    let (status, _) = patch(serde_json::json!([llm_slug, store.clone(), llm.clone()]));
+    assert_eq!(status, Status::Ok);
+    let app = get();
+    assert_eq!(app["composite_health"], "healthy");
+    assert_eq!(app["depends_on"].as_array().unwrap().len(), 2);
+    assert_eq!(app["depends_on"][0]["id"], llm.as_str());
+
+    // One unhealthy dependency is enough, even with the app itself healthy
+    conn.execute("UPDATE apps SET last_health_status = 'degraded' WHERE id = ?1", [&store]).unwrap();
+    let app = get();
+    assert_eq!(app["last_health_status"], "healthy");
+    assert_eq!(app["composite_health"], "degraded");
+
+    let summary: Value = client.get("/api/v1/apps/health/summary").dispatch().into_json().unwrap();
+    assert_eq!(summary["composite"]["apps_with_dependencies"], 1);
+    assert_eq!(summary["composite"]["healthy"], 0);
+    let issue = &summary["composite"]["issues"][0];
+    assert_eq!(issue["id"], composite.as_str());
+    assert_eq!(issue["composite_health"], "degraded");
+    assert_eq!(issue["failing_dependencies"][0]["id"], store.as_str());
+
+    // Deleting a dependency drops it; clearing the list drops the roll-up
+    client.delete(format!("/api/v1/apps/{}", store)).header(Header::new("X-API-Key", key.clone())).dispatch();
+    assert_eq!(get()["composite_health"], "healthy");
+    let (status, _) = patch(serde_json::json!([]));
+    assert_eq!(status, Status::Ok);
+    assert!(get()["composite_health"].is_null());
+}
+
+#[test]
+fn test_oauth_client_credentials() {
+    use base64::Engine;
+
+    let (client, _, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let (client_id, client_secret) =
+        app_directory::auth::create_expiring_api_key(&conn, "oauth-admin", true, Some(1000), None);
+
+    let token = |form: String, basic: Option<&str>| {
+        let mut req = client.post("/api/v1/oauth/token").header(ContentType::Form).body(form);
+        if let Some(basic) = basic {
+            req = req.header(Header::new("Authorization", format!("Basic {}", basic)));
+        }
+        let resp = req.dispatch();
+        let status = resp.status();
+        assert_eq!(resp.headers().get_one("Cache-Control"), Some("no-store"));
+        (status, resp.into_json::<Value>().unwrap())
+    };
+
+    let (status, body) = token(format!("grant_type=password&client_id={}", client_id), None);
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["error"], "unsupported_grant_type");
+    let (status, body) = token("grant_type=client_credentials".to_string(), None);
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["error"], "invalid_request");
+    let (status, body) = token(
+        format!("grant_type=client_credentials&client_id={}&client_secret=ad_wrong", client_id),
+        None,
+    );
+    assert_eq!(status, Status::Unauthorized);
+    assert_eq!(body["error"], "invalid_client");
+
+    let (status, body) = token(
+        format!("grant_type=client_credentials&client_id={}&client_secret={}&scope=read", client_id, client_secret),
+        None,
+    );
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["token_type"], "Bearer");
+    assert_eq!(body["expires_in"], 3600);
+    let access_token = body["access_token"].as_str().unwrap().to_string();
+    assert!(access_token.starts_with("at_"));
+
+    // The token acts as the key, admin rights included
+    let bearer = Header::new("Authorization", format!("Bearer {}", access_token));
+    assert_eq!(client.get("/api/v1/keys").header(bearer.clone()).dispatch().status(), Status::Ok);
+
+    // HTTP Basic works too ("id:secret", base64)
+    let basic = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", client_id, client_secret));
+    let (status, body) = token("grant_type=client_credentials".to_string(), Some(&basic));
+    assert_eq!(status, Status::Ok);
+    assert_ne!(body["access_token"], access_token.as_str());
+
+    // Expired tokens get a specific error; unknown ones the generic one
+    conn.execute("UPDATE oauth_tokens SET expires_at = datetime('now', '-1 minute')", []).unwrap();
+    let resp = client.get("/api/v1/keys").header(bearer).dispatch();
+    assert_eq!(resp.status(), Status::Unauthorized);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "TOKEN_EXPIRED");
+    let resp = client.get("/api/v1/keys").header(Header::new("Authorization", "Bearer at_unknown")).dispatch();
+    assert_eq!(resp.status(), Status::Unauthorized);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "UNAUTHORIZED");
+
+    // Revoking the key ends its tokens
+    let (_, body) = token(
+        format!("grant_type=client_credentials&client_id={}&client_secret={}", client_id, client_secret),
+        None,
+    );
+    let bearer = Header::new("Authorization", format!("Bearer {}", body["access_token"].as_str().unwrap()));
+    conn.execute("UPDATE api_keys SET revoked = 1 WHERE id = ?1", [&client_id]).unwrap();
+    assert_eq!(client.get("/api/v1/keys").header(bearer).dispatch().status(), Status::Unauthorized);
+}
+
+#[test]
+fn test_key_endpoints() {
+    let (client, key) = setup_client();
+    let id = submit_simple_app(&client, &key, "Pinned Endpoints");
+
+    let patch = |key_endpoints: Value| {
+        let resp = client
+            .patch(format!("/api/v1/apps/{}", id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "key_endpoints": key_endpoints }).to_string())
+            .dispatch();
+        let status = resp.status();
+        (status, resp.into_json::<Value>().unwrap())
+    };
+    let get = || -> Value {
+        client
+            .get(format!("/api/v1/apps/{}", id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch()
+            .into_json()
+            .unwrap()
+    };
+
+    assert_eq!(get()["key_endpoints"], serde_json::json!([]));
+
+    let bad = [
+        serde_json::json!([{ "method": "FETCH", "path": "/x", "description": "Nope" }]),
+        serde_json::json!([{ "method": "GET", "path": "forecast", "description": "No slash" }]),
+        serde_json::json!([{ "method": "GET", "path": "/forecast", "description": " " }]),
+        serde_json::json!([
+            { "method": "GET", "path": "/forecast", "description": "One" },
+            { "method": "get", "path": "/forecast", "description": "Two" }
+        ]),
+    ];
+    for endpoints in bad {
+        let (status, body) = patch(endpoints);
+        assert_eq!(status, Status::BadRequest);
+        assert_eq!(body["error"], "INVALID_KEY_ENDPOINT");
+    }
+    let too_many: Vec<Value> = (0..11)
+        .map(|i| serde_json::json!({ "method": "GET", "path": format!("/e{}", i), "description": "Endpoint" }))
+        .collect();
+    let (status, body) = patch(Value::Array(too_many));
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["error"], "FIELD_TOO_LONG");
+
+    let (status, _) = patch(serde_json::json!([
+        { "method": "get", "path": "/forecast", "description": "Get a forecast" },
+        { "method": "POST", "path": "/alerts", "description": "Subscribe to alerts" }
+    ]));
+    assert_eq!(status, Status::Ok);
+    let endpoints = get()["key_endpoints"].clone();
+    assert_eq!(endpoints.as_array().unwrap().len(), 2);
+    assert_eq!(endpoints[0]["method"], "GET");
+    assert_eq!(endpoints[1]["path"], "/alerts");
+
+    let md = client
+        .get(format!("/api/v1/apps/{}", id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(Header::new("Accept", "text/markdown"))
+        .dispatch()
+        .into_string()
+        .unwrap();
+    assert!(md.contains("## Key endpoints"));
+    assert!(md.contains("`GET /forecast` — Get a forecast"));
+
+    let (status, _) = patch(serde_json::json!([]));
+    assert_eq!(status, Status::Ok);
+    assert_eq!(get()["key_endpoints"], serde_json::json!([]));
+}
+
+/// Poll an app until its logo_status leaves "pending" (or give up after ~5s).
+fn wait_for_logo_status(client: &Client, app_id: &str) -> Value {
+    for _ in 0..50 {
+        let app: Value = client.get(format!("/api/v1/apps/{}", app_id)).dispatch().into_json().unwrap();
+        if app["logo_status"] != "pending" {
+            return app;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    panic!("logo fetch did not finish");
+}
+
+#[test]
+fn test_logo_caching() {
+    use std::io::{Read, Write};
+
+    let (client, key) = setup_client();
+    let id = submit_simple_app(&client, &key, "Logo App");
+    let plain = submit_simple_app(&client, &key, "Plain App");
+    for app in [&id, &plain] {
+        client
+            .post(format!("/api/v1/apps/{}/approve", app))
+            .header(Header::new("X-API-Key", key.clone()))
+            .dispatch();
+    }
+
+    // A 2×1 blue PNG served from a local port
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, 2, 1);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header().unwrap().write_image_data(&[0, 0, 255, 0, 0, 255]).unwrap();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let logo_url = format!("http://{}/logo.png", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                png.len()
+            );
+            let _ = stream.write_all(head.as_bytes());
+            let _ = stream.write_all(&png);
+        }
+    });
+
+    let patch = |logo_url: &str| {
+        let resp = client
+            .patch(format!("/api/v1/apps/{}", id))
+            .header(Header::new("X-API-Key", key.clone()))
+            .header(ContentType::JSON)
+            .body(serde_json::json!({ "logo_url": logo_url }).to_string())
+            .dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+    };
+    let logo = |app_id: &str| -> Vec<u8> {
+        let resp = client.get(format!("/media/logos/{}.png", app_id)).dispatch();
+        assert_eq!(resp.status(), Status::Ok);
+        assert_eq!(resp.content_type(), Some(ContentType::PNG));
+        resp.into_bytes().unwrap()
+    };
+
+    // Without a logo_url the identicon is served
+    let app: Value = client.get(format!("/api/v1/apps/{}", id)).dispatch().into_json().unwrap();
+    assert_eq!(app["logo"], format!("/media/logos/{}.png", id));
+    assert!(app["logo_status"].is_null());
+    let identicon = logo(&id);
+    assert_eq!(identicon, logo(&id));
+    assert_ne!(identicon, logo(&plain));
+
+    patch(&logo_url);
+    let app = wait_for_logo_status(&client, &id);
+    assert_eq!(app["logo_status"], "cached");
+    assert_eq!(app["logo_url"], logo_url.as_str());
+    let cached = logo(&id);
+    let reader = png::Decoder::new(cached.as_slice());
+    let mut reader = reader.read_info().unwrap();
+    assert_eq!((reader.info().width, reader.info().height), (256, 256));
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    reader.next_frame(&mut pixels).unwrap();
+    let center = ((128 * 256 + 128) * 4) as usize;
+    assert_eq!(&pixels[center..center + 4], [0, 0, 255, 255]);
+
+    // Not an image: rejected, back to the identicon
+    patch(&serve_static("{}"));
+    assert_eq!(wait_for_logo_status(&client, &id)["logo_status"], "invalid");
+    assert_eq!(logo(&id), identicon);
+
+    patch("");
+    let app: Value = client.get(format!("/api/v1/apps/{}", id)).dispatch().into_json().unwrap();
+    assert!(app["logo_status"].is_null());
+
+    // Ids only; unknown apps have no logo
+    assert_eq!(client.get("/media/logos/logo-app.png").dispatch().status(), Status::NotFound);
+    assert_eq!(client.get("/media/logos/missing.png").dispatch().status(), Status::NotFound);
+    assert_eq!(client.get(format!("/media/logos/{}", id)).dispatch().status(), Status::NotFound);
+}
+
+#[test]
+fn test_route_costs() {
+    let (client, admin, db_path) = setup_client_with_path();
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    let key = app_directory::auth::create_api_key(&conn, "weighted", false, Some(20));
+    let id = submit_simple_app(&client, &key, "Weighted App");
+    let header = |resp: &rocket::local::blocking::LocalResponse, name: &str| {
+        resp.headers().get_one(name).map(str::to_string)
+    };
+
+    let resp = client.get("/api/v1/apps").header(Header::new("X-API-Key", key.clone())).dispatch();
+    assert_eq!(header(&resp, "X-RateLimit-Cost").as_deref(), Some("1"));
+    assert_eq!(header(&resp, "X-RateLimit-Remaining").as_deref(), Some("18"));
+
+    let resp = client.get("/api/v1/apps/search?q=weighted").header(Header::new("X-API-Key", key.clone())).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(header(&resp, "X-RateLimit-Cost").as_deref(), Some("5"));
+    assert_eq!(header(&resp, "X-RateLimit-Remaining").as_deref(), Some("13"));
+
+    let export = || client.get(format!("/api/v1/apps/{}/export", id)).header(Header::new("X-API-Key", key.clone())).dispatch();
+    let resp = export();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(header(&resp, "X-RateLimit-Cost").as_deref(), Some("10"));
+    assert_eq!(header(&resp, "X-RateLimit-Remaining").as_deref(), Some("3"));
+
+    // Three requests left aren't enough for another export
+    let resp = export();
+    assert_eq!(resp.status(), Status::TooManyRequests);
+    let body: Value = resp.into_json().unwrap();
+    assert_eq!(body["error"], "RATE_LIMITED");
+    assert_eq!(body["cost"], 10);
+    assert_eq!(body["remaining"], 3);
+
+    let info: Value = client
+        .get("/api/v1/rate-limit")
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(info["remaining"], 3);
+    let costs = info["route_costs"].as_array().unwrap();
+    assert!(costs.contains(&serde_json::json!({ "route": "GET /api/v1/apps/search", "cost": 5 })));
+
+    // Exempt routes stay free
+    let key_id: String = conn.query_row("SELECT id FROM api_keys WHERE name = 'weighted'", [], |r| r.get(0)).unwrap();
+    let resp = client
+        .patch(format!("/api/v1/keys/{}", key_id))
+        .header(Header::new("X-API-Key", admin.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"exempt_routes": ["GET /api/v1/apps/*/export"]}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let resp = export();
+    assert_eq!(resp.status(), Status::Ok);
+    assert!(header(&resp, "X-RateLimit-Cost").is_none());
+}
+
+/// Minimal read-only replica. Built by hand because `rocket_with_path`
+/// reads `READ_ONLY` from the environment.
+fn setup_replica_client() -> (Client, String) {
+    let db_path = format!("/tmp/test_app_dir_{}.db", uuid::Uuid::new_v4());
+    let conn = app_directory::db::init_db(&db_path);
+    let replica = app_directory::replica::Replica::new(Some(app_directory::replica::ReplicaConfig {
+        primary_url: Some("https://primary.example.com".to_string()),
+        api_key: None,
+        sync_secs: 300,
+    }));
+    let rocket = rocket::build()
+        .manage(app_directory::DbState(std::sync::Mutex::new(conn)))
+        .manage(app_directory::rate_limit::RateLimiter::new(std::time::Duration::from_secs(60)))
+        .manage(app_directory::i18n::DefaultLanguage("en".to_string()))
+        .manage(app_directory::pagination::PageLimits::default())
+        .manage(app_directory::stats::ViewRecorder::default())
+        .manage(app_directory::auth::ReadPolicy { require_auth: false })
+        .manage(app_directory::maintenance::Maintenance::new(true, None, 300))
+        .manage(app_directory::retention::PrivacyMode::default())
+        .manage(replica)
+        .mount(
+            "/api/v1",
+            rocket::routes![
+                app_directory::routes::list_apps,
+                app_directory::routes::get_app,
+                app_directory::routes::health,
+                app_directory::replica::feed,
+            ],
+        )
+        .mount("/api/v1", app_directory::maintenance::routes())
+        .mount("/api/v1", app_directory::replica::routes());
+    (Client::tracked(rocket).unwrap(), db_path)
+}
+
+#[test]
+fn test_read_replica() {
+    let (primary, key, primary_db) = setup_client_with_path();
+    let kept = submit_simple_app(&primary, &key, "Replicated Tool");
+    let draft = submit_simple_app(&primary, &key, "Draft Tool");
+    let private = submit_simple_app(&primary, &key, "Private Tool");
+    let conn = rusqlite::Connection::open(&primary_db).unwrap();
+    conn.execute("UPDATE apps SET status = 'draft' WHERE id = ?1", [&draft]).unwrap();
+    conn.execute("UPDATE apps SET visibility = 'private' WHERE id = ?1", [&private]).unwrap();
+    let resp = primary
+        .post(format!("/api/v1/apps/{kept}/reviews"))
+        .header(ContentType::JSON)
+        .body(r#"{"rating": 4, "title": "Solid", "reviewer_name": "Ann"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+    let resp = primary
+        .put(format!("/api/v1/apps/{kept}/translations/de"))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"name":"Replizierter Dienst"}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+
+    // The feed carries public listings only, with reviews and translations
+    let page: Value = primary.get("/api/v1/replication/feed").dispatch().into_json().unwrap();
+    let apps = page["apps"].as_array().unwrap().clone();
+    assert_eq!(apps.len(), 1);
+    assert_eq!(apps[0]["id"], kept.as_str());
+    assert!(apps[0].get("submitted_by_key_id").is_none());
+    assert_eq!(apps[0]["reviews"][0]["title"], "Solid");
+    assert!(apps[0]["reviews"][0].get("reviewer_key_id").is_none());
+    assert_eq!(apps[0]["translations"][0]["lang"], "de");
+    assert!(page["next_cursor"].is_null());
+
+    // Pages follow the cursor
+    let other = submit_simple_app(&primary, &key, "Second Tool");
+    let first: Value = primary.get("/api/v1/replication/feed?limit=1").dispatch().into_json().unwrap();
+    let cursor = first["next_cursor"].as_str().unwrap();
+    let second: Value = primary
+        .get(format!("/api/v1/replication/feed?limit=1&after={cursor}"))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    let mut paged = vec![first["apps"][0]["id"].clone(), second["apps"][0]["id"].clone()];
+    paged.sort_by_key(|id| id.as_str().unwrap().to_string());
+    let mut expected = vec![Value::from(kept.as_str()), Value::from(other.as_str())];
+    expected.sort_by_key(|id| id.as_str().unwrap().to_string());
+    assert_eq!(paged, expected);
+
+    // Applying the snapshot makes the replica serve it
+    let (replica, replica_db) = setup_replica_client();
+    let snapshot: Value = primary.get("/api/v1/replication/feed").dispatch().into_json().unwrap();
+    let mut conn = rusqlite::Connection::open(&replica_db).unwrap();
+    let applied = app_directory::replica::apply_snapshot(&mut conn, snapshot["apps"].as_array().unwrap()).unwrap();
+    assert_eq!(applied, 2);
+    let app: Value = replica.get(format!("/api/v1/apps/{kept}")).dispatch().into_json().unwrap();
+    assert_eq!(app["name"], "Replicated Tool");
+    assert_eq!(app["review_count"], 1);
+    let list: Value = replica.get("/api/v1/apps").dispatch().into_json().unwrap();
+    assert_eq!(list["total"], 2);
+
+    // Later snapshots update, and drop listings gone from the primary
+    let mut apps = snapshot["apps"].as_array().unwrap().clone();
+    apps.retain(|a| a["id"] == kept.as_str());
+    apps[0]["name"] = Value::from("Renamed Tool");
+    assert_eq!(app_directory::replica::apply_snapshot(&mut conn, &apps).unwrap(), 1);
+    let app: Value = replica.get(format!("/api/v1/apps/{kept}")).dispatch().into_json().unwrap();
+    assert_eq!(app["name"], "Renamed Tool");
+    assert_eq!(replica.get(format!("/api/v1/apps/{other}")).dispatch().status(), Status::NotFound);
+    let reviews: i64 = conn.query_row("SELECT COUNT(*) FROM reviews WHERE app_id = ?1", [&kept], |r| r.get(0)).unwrap();
+    assert_eq!(reviews, 1);
+
+    // Writes are refused ahead of maintenance, with a pointer to the primary
+    for resp in [
+        replica.post("/api/v1/apps").header(ContentType::JSON).body("{}").dispatch(),
+        replica.patch(format!("/api/v1/apps/{kept}")).header(ContentType::JSON).body("{}").dispatch(),
+        replica.delete(format!("/api/v1/apps/{kept}")).dispatch(),
+    ] {
+        assert_eq!(resp.status(), Status::Forbidden);
+        let body: Value = resp.into_json().unwrap();
+        assert_eq!(body["error"], "READ_ONLY");
+        assert_eq!(body["primary"], "https://primary.example.com");
+    }
+
+    let health: Value = replica.get("/api/v1/health").dispatch().into_json().unwrap();
+    assert_eq!(health["read_only"], true);
+    assert_eq!(health["replica"]["primary"], "https://primary.example.com");
+    let health: Value = primary.get("/api/v1/health").dispatch().into_json().unwrap();
+    assert_eq!(health["read_only"], false);
+    assert!(health["replica"].is_null());
+}
+
+#[test]
+fn test_spoofed_ip_header_does_not_reset_review_cap() {
+    let (client, key) = setup_client();
+    let app_id = submit_simple_app(&client, &key, "Spoof Target");
+
+    // A new X-Real-IP on every request is ignored: same reviewer, same cap
+    for i in 0..20 {
+        let resp = client
+            .post(format!("/api/v1/apps/{}/reviews", app_id))
+            .remote(client_addr("10.7.0.1"))
+            .header(Header::new("X-Real-IP", format!("192.0.2.{}", i + 1)))
+            .header(ContentType::JSON)
+            .body(r#"{"rating":5}"#)
+            .dispatch();
+        assert_eq!(resp.status(), Status::Created);
+    }
+    let body: Value = client.get(format!("/api/v1/apps/{}/reviews", app_id)).dispatch().into_json().unwrap();
+    assert_eq!(body["total"], 1);
+
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .remote(client_addr("10.7.0.1"))
+        .header(Header::new("X-Real-IP", "192.0.2.99"))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":5}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::TooManyRequests);
+    assert_eq!(resp.into_json::<Value>().unwrap()["error"], "REVIEW_LIMIT_EXCEEDED");
+
+    // A different client still gets through
+    let resp = client
+        .post(format!("/api/v1/apps/{}/reviews", app_id))
+        .remote(client_addr("10.7.0.2"))
+        .header(ContentType::JSON)
+        .body(r#"{"rating":4}"#)
+        .dispatch();
+    assert_eq!(resp.status(), Status::Created);
+}
+
+#[test]
+fn test_hidden_listings_stay_hidden_from_related_endpoints() {
+    let (client, key, db_path) = setup_client_with_path();
+    let old = submit_simple_app(&client, &key, "Old Service");
+    let private = submit_simple_app(&client, &key, "Private Successor");
+    let draft = submit_simple_app(&client, &key, "Draft Service");
+    let resp = client
+        .post(format!("/api/v1/apps/{}/deprecate", old))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "reason": "Superseded", "replacement_app_id": private }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    let conn = rusqlite::Connection::open(&db_path).unwrap();
+    conn.execute("UPDATE apps SET visibility = 'private' WHERE id = ?1", [&private]).unwrap();
+    conn.execute("UPDATE apps SET status = 'draft' WHERE id = ?1", [&draft]).unwrap();
+
+    // A public listing doesn't leak the private listing that replaces it
+    let app: Value = client.get(format!("/api/v1/apps/{}", old)).dispatch().into_json().unwrap();
+    assert!(app["resolved_replacement"].is_null());
+    let resp = client.get(format!("/api/v1/apps/{}/replacement", old)).dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+
+    // Signed-in readers see it
+    let app: Value = client
+        .get(format!("/api/v1/apps/{}", old))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch()
+        .into_json()
+        .unwrap();
+    assert_eq!(app["resolved_replacement"]["id"], private.as_str());
+    let resp = client
+        .get(format!("/api/v1/apps/{}/replacement", old))
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.into_json::<Value>().unwrap()["replacement"]["id"], private.as_str());
+
+    // Revision history, diffs and tag suggestions follow the listing's own visibility
+    for id in [&private, &draft] {
+        for path in [
+            format!("/api/v1/apps/{}/revisions", id),
+            format!("/api/v1/apps/{}/revisions/1/diff", id),
+            format!("/api/v1/apps/{}/suggest-tags", id),
+        ] {
+            assert_eq!(client.get(&path).dispatch().status(), Status::NotFound, "{path}");
+        }
+        for path in [format!("/api/v1/apps/{}/revisions", id), format!("/api/v1/apps/{}/suggest-tags", id)] {
+            let resp = client.get(&path).header(Header::new("X-API-Key", key.clone())).dispatch();
+            assert_eq!(resp.status(), Status::Ok, "{path}");
+        }
+    }
+    assert_eq!(client.get(format!("/api/v1/apps/{}/revisions", old)).dispatch().status(), Status::Ok);
+
+    // So do per-app reviews, stats, health and click-throughs
+    for id in [&private, &draft] {
+        let paths = [
+            format!("/api/v1/apps/{}/reviews", id),
+            format!("/api/v1/apps/{}/reviews/summary", id),
+            format!("/api/v1/apps/{}/stats", id),
+            format!("/api/v1/apps/{}/health", id),
+            format!("/api/v1/apps/{}/uptime", id),
+            format!("/api/v1/apps/{}/health/metrics", id),
+        ];
+        for path in &paths {
+            assert_eq!(client.get(path).dispatch().status(), Status::NotFound, "{path}");
+            let resp = client.get(path).header(Header::new("X-API-Key", key.clone())).dispatch();
+            assert_eq!(resp.status(), Status::Ok, "{path}");
+        }
+        let resp = client.get(format!("/api/v1/apps/{}/visit", id)).dispatch();
+        assert_eq!(resp.status(), Status::NotFound);
+    }
+    let resp = client.get(format!("/api/v1/apps/{}/reviews/unknown/history", private)).dispatch();
+    assert_eq!(resp.into_json::<Value>().unwrap()["message"], "App not found");
+
+    // Public summaries leave the private listing out for signed-out callers
+    conn.execute(
+        "UPDATE apps SET last_health_status = 'unhealthy', created_at = datetime('now', '+1 day'),
+         api_spec_url = 'https://example.com/openapi.json' WHERE id = ?1",
+        [&private],
+    )
+    .unwrap();
+    let issue_ids = |key: Option<&str>| -> Vec<String> {
+        let mut req = client.get("/api/v1/apps/health/summary");
+        if let Some(key) = key {
+            req = req.header(Header::new("X-API-Key", key.to_string()));
+        }
+        let summary: Value = req.dispatch().into_json().unwrap();
+        summary["issues"].as_array().unwrap().iter().map(|a| a["id"].as_str().unwrap().to_string()).collect()
+    };
+    assert!(!issue_ids(None).contains(&private));
+    assert!(issue_ids(Some(&key)).contains(&private));
+    let stats: Value = client.get("/api/v1/stats/site").dispatch().into_json().unwrap();
+    assert_ne!(stats["newest_app"]["id"], private.as_str());
+    let index: Value = client.get("/.well-known/skills/index.json").dispatch().into_json().unwrap();
+    assert!(!index.to_string().contains("private-successor"));
+    let resp = client.get("/.well-known/skills/apps/private-successor/SKILL.md").dispatch();
+    assert_eq!(resp.status(), Status::NotFound);
+}
+
+/// Serve `chunk` over chunked transfer encoding, with no `Content-Length`,
+/// `count` times per request; the body stops early if the client hangs up.
+fn serve_chunked(chunk: &'static str, count: usize) -> String {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            let head = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n";
+            if stream.write_all(head.as_bytes()).is_err() {
+                continue;
+            }
+            let frame = format!("{:x}\r\n{}\r\n", chunk.len(), chunk);
+            for _ in 0..count {
+                if stream.write_all(frame.as_bytes()).is_err() {
+                    break;
+                }
+            }
+            let _ = stream.write_all(b"0\r\n\r\n");
+        }
+    });
+    format!("http://{}", addr)
+}
+
+#[test]
+fn test_oversized_chunked_spec_is_rejected() {
+    let (client, key) = setup_client();
+    // 6 MiB of padding without a Content-Length, over the 5 MiB cap
+    let chunk: &'static str = Box::leak(" ".repeat(64 * 1024).into_boxed_str());
+    let spec_url = serve_chunked(chunk, 96) + "/openapi.json";
+    let resp = client
+        .post("/api/v1/apps")
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({
+            "name": "Huge Spec API",
+            "short_description": "Streams forever",
+            "description": "Spec without a length",
+            "author_name": "Tester",
+            "api_spec_url": spec_url,
+        }).to_string())
+        .dispatch();
+    let id = resp.into_json::<Value>().unwrap()["app_id"].as_str().unwrap().to_string();
+
+    let app = wait_for_spec_status(&client, &id);
+    assert_eq!(app["spec_status"], "invalid");
+    assert!(app["spec"]["errors"][0].as_str().unwrap().contains("exceeds"), "{}", app["spec"]);
+}
+
+#[test]
+fn test_oversized_chunked_logo_is_rejected() {
+    let (client, key) = setup_client();
+    let id = submit_simple_app(&client, &key, "Huge Logo App");
+    // 2 MiB without a Content-Length, over the 1 MiB cap
+    let chunk: &'static str = Box::leak("x".repeat(64 * 1024).into_boxed_str());
+    let logo_url = serve_chunked(chunk, 32) + "/logo.png";
+    let resp = client
+        .patch(format!("/api/v1/apps/{}", id))
+        .header(Header::new("X-API-Key", key.clone()))
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "logo_url": logo_url }).to_string())
+        .dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+
+    assert_eq!(wait_for_logo_status(&client, &id)["logo_status"], "invalid");
+    // The identicon is served instead
+    let resp = client.get(format!("/media/logos/{}.png", id)).dispatch();
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.content_type(), Some(ContentType::PNG));
+}